@@ -0,0 +1,232 @@
+//! Application context - What commands run against
+//!
+//! `GptEngage` bundles the effective config, the session store, the invoker
+//! registry, and the template and plugin managers. The CLI builds one per
+//! process; tests and embedding applications can build their own with an
+//! in-memory session store or stand-in invokers. The store and managers are
+//! loaded on first use, so commands that don't need them don't pay for them.
+
+use crate::config::{shared_config, ConfigHandle, ResolvedConfig};
+use crate::invokers::InvokerRegistry;
+use crate::orchestrator::DebateOrchestrator;
+use crate::plugins::PluginManager;
+use crate::session::store::open_store;
+use crate::session::trash::Trash;
+use crate::session::SessionManager;
+use crate::templates::TemplateManager;
+use anyhow::Result;
+use std::sync::{Arc, OnceLock};
+
+/// Application context shared by every command in a run
+#[derive(Default)]
+pub struct GptEngage {
+    /// Effective configuration, resolved on first use
+    pub config: ConfigHandle,
+    /// Invokers participants and commands run CLIs through
+    pub invokers: InvokerRegistry,
+    sessions: OnceLock<SessionManager>,
+    templates: OnceLock<TemplateManager>,
+}
+
+impl std::fmt::Debug for GptEngage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GptEngage")
+            .field("config", &self.config)
+            .field("invokers", &self.invokers)
+            .field("sessions", &self.sessions.get())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The value in `cell`, loading it on first use. A failed load isn't
+/// kept, so a later call tries again.
+pub(crate) fn load_once<T>(cell: &OnceLock<T>, load: impl FnOnce() -> Result<T>) -> Result<&T> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+    let value = load()?;
+    Ok(cell.get_or_init(|| value))
+}
+
+impl GptEngage {
+    /// Use the process-wide config handle, which invokers and plugins read
+    /// as well, with the built-in invokers and installed plugins
+    pub fn new() -> Self {
+        Self {
+            config: shared_config().clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Read settings from `config` instead of the process-wide handle
+    pub fn with_config(mut self, config: ConfigHandle) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Look CLIs up in `invokers`, which also holds the plugins
+    pub fn with_invokers(mut self, invokers: InvokerRegistry) -> Self {
+        self.invokers = invokers;
+        self
+    }
+
+    /// Keep sessions in `sessions` instead of the configured store
+    pub fn with_sessions(mut self, sessions: SessionManager) -> Self {
+        self.sessions = OnceLock::from(sessions);
+        self
+    }
+
+    /// Use already-loaded templates
+    pub fn with_templates(mut self, templates: TemplateManager) -> Self {
+        self.templates = OnceLock::from(templates);
+        self
+    }
+
+    /// Use already-loaded plugins
+    pub fn with_plugins(mut self, plugins: PluginManager) -> Self {
+        self.invokers = self.invokers.with_plugins(plugins);
+        self
+    }
+
+    /// The effective config, resolved once per process
+    pub fn config(&self) -> Result<Arc<ResolvedConfig>> {
+        self.config.get()
+    }
+
+    /// Re-read the config files and environment, for long-running callers
+    pub fn reload_config(&self) -> Result<Arc<ResolvedConfig>> {
+        self.config.reload()
+    }
+
+    /// Reload templates and plugins whose files changed since they were
+    /// loaded, for long-running callers. Returns true if anything reloaded.
+    pub fn reload_if_changed(&mut self) -> Result<bool> {
+        let plugins_reloaded = self.invokers.reload_plugins_if_changed()?;
+        let mut reloaded = plugins_reloaded;
+        if let Some(templates) = self.templates.get_mut() {
+            reloaded |= templates.reload_if_changed()?;
+            if plugins_reloaded {
+                templates.load_plugin_templates(self.invokers.plugins().ok());
+            }
+        }
+        Ok(reloaded)
+    }
+
+    /// The session store selected by the `session_store` config key
+    pub fn sessions(&self) -> Result<&SessionManager> {
+        load_once(&self.sessions, || {
+            let config = self
+                .config()
+                .map(|resolved| resolved.config.clone())
+                .unwrap_or_default();
+            Ok(
+                SessionManager::with_store(open_store(&config.session_store)?)
+                    .with_trash(Trash::open(config.trash_retention_days)?),
+            )
+        })
+    }
+
+    /// Built-in, user, and plugin templates
+    pub fn templates(&self) -> Result<&TemplateManager> {
+        load_once(&self.templates, || {
+            TemplateManager::new(self.plugins().ok())
+        })
+    }
+
+    /// Installed plugins
+    pub fn plugins(&self) -> Result<&PluginManager> {
+        self.invokers.plugins()
+    }
+
+    /// A debate orchestrator invoking CLIs through this context's registry
+    pub fn orchestrator(&self) -> DebateOrchestrator {
+        DebateOrchestrator::new(self.invokers.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session::{ask_session, AskOptions};
+    use crate::config::ConfigManager;
+    use crate::invokers::{AccessMode, Invoker};
+    use crate::orchestrator::{Participant, PromptContext, RoundOptions};
+    use async_trait::async_trait;
+
+    /// Stands in for claude, answering every prompt the same way
+    struct Canned;
+
+    #[async_trait]
+    impl Invoker for Canned {
+        async fn invoke(
+            &self,
+            _prompt: &str,
+            _timeout: u64,
+            _access_mode: AccessMode,
+            _model: Option<&str>,
+        ) -> anyhow::Result<String> {
+            Ok("Cache the lookups.".to_string())
+        }
+
+        fn name(&self) -> &str {
+            "claude"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn test_engage() -> GptEngage {
+        let resolved =
+            ConfigManager::resolve_layers(ConfigManager::default(), None, None, |_| None).unwrap();
+        GptEngage::new()
+            .with_config(ConfigHandle::with_config(resolved))
+            .with_invokers(InvokerRegistry::new().with(Arc::new(Canned)))
+            .with_sessions(SessionManager::in_memory())
+    }
+
+    #[tokio::test]
+    async fn test_commands_run_against_injected_context() {
+        let engage = test_engage();
+        let sessions = engage.sessions().unwrap();
+        let mut session = sessions
+            .create_session(
+                "review".to_string(),
+                "claude".to_string(),
+                "Caching".to_string(),
+            )
+            .unwrap();
+        sessions.add_turn(&mut session, "user".to_string(), "Too slow?".to_string());
+        sessions.save_session(&session).await.unwrap();
+
+        let options = AskOptions {
+            cli: None,
+            model: None,
+            history_last: None,
+            record: true,
+            timeout: 5,
+        };
+        ask_session(&engage, "review".to_string(), "Fix?".to_string(), options)
+            .await
+            .unwrap();
+        let session = sessions.load_session("review").await.unwrap();
+        assert_eq!(session.turns.len(), 3);
+        assert_eq!(session.turns[2].content, "Cache the lookups.");
+
+        let result = engage
+            .orchestrator()
+            .run_debate_with_participants(
+                "Caching",
+                vec![Participant::new("claude".to_string(), None)],
+                1,
+                5,
+                AccessMode::ReadOnly,
+                PromptContext::default(),
+                RoundOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.rounds[0][0].response, "Cache the lookups.");
+    }
+}
@@ -0,0 +1,394 @@
+//! Audit log - Append-only record of every external process execution
+//!
+//! Entries are stored as JSON lines in `~/.gptengage/audit.jsonl`.
+//! Prompts are never written; only their SHA-256 hashes are recorded,
+//! salted when `history.privacy` is hash-only.
+//!
+//! CLI invocations are recorded by the invokers. Every other process
+//! gptengage spawns (git, gh, curl, tmux, the editor, detection probes)
+//! goes through [`ProcessAudit`].
+
+use crate::invokers::AccessMode;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A single recorded process execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub user: String,
+    pub cwd: String,
+    pub command: String,
+    /// Arguments with the prompt replaced by its hash
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_sha256: Option<String>,
+    pub access_mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// "ok", "failed", or "error" (spawn failure, timeout)
+    pub outcome: String,
+    pub duration_ms: u64,
+}
+
+/// Access mode recorded for processes spawned outside the invokers
+pub const NO_ACCESS_MODE: &str = "none";
+
+/// Hex-encoded SHA-256 of a string
+pub fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Name of the user running gptengage, falling back to the numeric uid
+fn current_user() -> String {
+    if let Ok(user) = std::env::var("USER").or_else(|_| std::env::var("USERNAME")) {
+        if !user.is_empty() {
+            return user;
+        }
+    }
+    #[cfg(unix)]
+    {
+        format!("uid:{}", unsafe { libc::getuid() })
+    }
+    #[cfg(not(unix))]
+    {
+        String::new()
+    }
+}
+
+impl AuditEntry {
+    /// Build an entry for a finished execution.
+    ///
+    /// Built-in CLIs and stdin-mode plugins receive the prompt on stdin.
+    /// When stdin is empty the prompt was passed as the final argument
+    /// (plugin `arg`/`arg_last` modes), so that argument is hashed instead.
+    /// With a `salt`, the hash is salted (`history.privacy = "hash-only"`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        timestamp: DateTime<Utc>,
+        duration: std::time::Duration,
+        cmd: &str,
+        args: &[&str],
+        input: &str,
+        salt: Option<&str>,
+        access_mode: AccessMode,
+        exit_code: Option<i32>,
+        result: &Result<String>,
+    ) -> Self {
+        let hash = |text: &str| prompt_hash(text, salt);
+        let mut recorded: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let prompt_sha256 = if !input.is_empty() {
+            Some(hash(input))
+        } else if let Some(last) = recorded.last_mut() {
+            let hash = hash(last);
+            *last = format!("<prompt sha256:{}>", hash);
+            Some(hash)
+        } else {
+            None
+        };
+
+        let outcome = match (result, exit_code) {
+            (Ok(_), _) => "ok",
+            (Err(_), Some(_)) => "failed",
+            (Err(_), None) => "error",
+        };
+
+        Self {
+            timestamp,
+            user: current_user(),
+            cwd: std::env::current_dir()
+                .map(|d| d.display().to_string())
+                .unwrap_or_default(),
+            command: cmd.to_string(),
+            args: recorded,
+            prompt_sha256,
+            access_mode: access_mode.as_str().to_string(),
+            exit_code,
+            outcome: outcome.to_string(),
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+
+    /// Whether this entry is a CLI invocation rather than a helper process
+    /// such as git or curl
+    pub fn is_cli_invocation(&self) -> bool {
+        self.access_mode != NO_ACCESS_MODE
+    }
+
+    /// Record `args` with every occurrence of `prompt` replaced by its
+    /// hash, for plugins that place the prompt anywhere in their
+    /// arguments (plugin `template` mode)
+    pub fn redact_prompt_args(&mut self, args: &[&str], prompt: &str, salt: Option<&str>) {
+        let hash = prompt_hash(prompt, salt);
+        let placeholder = format!("<prompt sha256:{}>", hash);
+        self.args = args
+            .iter()
+            .map(|arg| {
+                if prompt.is_empty() {
+                    arg.to_string()
+                } else {
+                    arg.replace(prompt, &placeholder)
+                }
+            })
+            .collect();
+        self.prompt_sha256 = Some(hash);
+    }
+}
+
+/// Audit trail for a process spawned outside the invokers.
+///
+/// Start it from the command just before spawning and `finish` it with the
+/// exit status. Dropped unfinished (the spawn failed, or the process was
+/// killed at a timeout) it records an "error" outcome. Nothing is written
+/// unless `audit_log` is on.
+pub struct ProcessAudit {
+    entry: Option<AuditEntry>,
+    started: std::time::Instant,
+}
+
+impl ProcessAudit {
+    /// Begin auditing `command`
+    pub fn start(command: &std::process::Command) -> Self {
+        let entry = crate::invokers::policy::policy().audit.then(|| AuditEntry {
+            timestamp: Utc::now(),
+            user: current_user(),
+            cwd: std::env::current_dir()
+                .map(|d| d.display().to_string())
+                .unwrap_or_default(),
+            command: command.get_program().to_string_lossy().into_owned(),
+            args: command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            prompt_sha256: None,
+            access_mode: NO_ACCESS_MODE.to_string(),
+            exit_code: None,
+            outcome: "error".to_string(),
+            duration_ms: 0,
+        });
+        Self {
+            entry,
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Record `text` (debate content passed as an argument, such as an
+    /// issue or comment body) by its hash wherever it appears in the
+    /// arguments
+    pub fn redact(mut self, text: &str) -> Self {
+        if let Some(entry) = &mut self.entry {
+            let salt = crate::invokers::policy::policy()
+                .hash_only
+                .then(crate::history::redact::salt)
+                .and_then(Result::ok);
+            let args: Vec<String> = std::mem::take(&mut entry.args);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            entry.redact_prompt_args(&args, text, salt);
+        }
+        self
+    }
+
+    /// Record the exit status of the finished process
+    pub fn finish(mut self, status: &std::process::ExitStatus) {
+        if let Some(entry) = &mut self.entry {
+            entry.exit_code = status.code();
+            entry.outcome = if status.success() { "ok" } else { "failed" }.to_string();
+        }
+    }
+}
+
+impl Drop for ProcessAudit {
+    fn drop(&mut self) {
+        if let Some(mut entry) = self.entry.take() {
+            entry.duration_ms = self.started.elapsed().as_millis() as u64;
+            if let Err(e) = AuditLog::open().and_then(|log| log.append(&entry)) {
+                eprintln!("Warning: failed to write audit log: {}", e);
+            }
+        }
+    }
+}
+
+/// Hash of a prompt, salted with `salt` when given
+fn prompt_hash(text: &str, salt: Option<&str>) -> String {
+    match salt {
+        Some(salt) => crate::history::redact::salted_sha256(salt, text),
+        None => sha256_hex(text),
+    }
+}
+
+/// Append-only audit log file
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Open the audit log in the config directory
+    pub fn open() -> Result<Self> {
+        let config_dir = crate::config::ConfigManager::get_config_dir()?;
+        Ok(Self::at(config_dir.join("audit.jsonl")))
+    }
+
+    /// Use an audit log at a specific path
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Append an entry as a single JSON line
+    pub fn append(&self, entry: &AuditEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log: {}", self.path.display()))?;
+        let line = serde_json::to_string(entry)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    /// Read all entries, oldest first. Malformed lines are skipped.
+    pub fn read(&self) -> Result<Vec<AuditEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read audit log: {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_entry_hashes_stdin_prompt() {
+        let result: Result<String> = Ok("out".to_string());
+        let entry = AuditEntry::new(
+            Utc::now(),
+            Duration::from_millis(5),
+            "claude",
+            &["-p"],
+            "secret prompt",
+            None,
+            AccessMode::ReadOnly,
+            Some(0),
+            &result,
+        );
+        assert_eq!(entry.args, vec!["-p"]);
+        assert_eq!(entry.prompt_sha256, Some(sha256_hex("secret prompt")));
+        assert_eq!(entry.outcome, "ok");
+        assert_eq!(entry.access_mode, "read-only");
+    }
+
+    #[test]
+    fn test_entry_hashes_prompt_argument() {
+        let result: Result<String> = Err(anyhow::anyhow!("boom"));
+        let entry = AuditEntry::new(
+            Utc::now(),
+            Duration::from_millis(5),
+            "ollama",
+            &["run", "llama3", "secret prompt"],
+            "",
+            Some("salt"),
+            AccessMode::WorkspaceWrite,
+            Some(1),
+            &result,
+        );
+        assert_eq!(entry.args[..2], ["run", "llama3"]);
+        assert!(!entry.args[2].contains("secret"));
+        assert_eq!(
+            entry.args[2],
+            format!(
+                "<prompt sha256:{}>",
+                crate::history::redact::salted_sha256("salt", "secret prompt")
+            )
+        );
+        assert_eq!(entry.outcome, "failed");
+    }
+
+    #[test]
+    fn test_entry_hashes_prompt_within_arguments() {
+        let result: Result<String> = Ok("out".to_string());
+        let args = ["--chat", "secret prompt", "--title=secret prompt", "-q"];
+        let mut entry = AuditEntry::new(
+            Utc::now(),
+            Duration::from_millis(5),
+            "chat",
+            &args,
+            "",
+            None,
+            AccessMode::ReadOnly,
+            Some(0),
+            &result,
+        );
+        entry.redact_prompt_args(&args, "secret prompt", None);
+        let placeholder = format!("<prompt sha256:{}>", sha256_hex("secret prompt"));
+        assert_eq!(
+            entry.args,
+            vec![
+                "--chat".to_string(),
+                placeholder.clone(),
+                format!("--title={}", placeholder),
+                "-q".to_string(),
+            ]
+        );
+        assert_eq!(entry.prompt_sha256, Some(sha256_hex("secret prompt")));
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::at(dir.path().join("audit.jsonl"));
+        let result: Result<String> = Ok(String::new());
+        for _ in 0..2 {
+            let entry = AuditEntry::new(
+                Utc::now(),
+                Duration::from_millis(1),
+                "codex",
+                &["exec"],
+                "p",
+                None,
+                AccessMode::ReadOnly,
+                Some(0),
+                &result,
+            );
+            log.append(&entry).unwrap();
+        }
+        let entries = log.read().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "codex");
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! CLI argument parsing and command dispatching
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "gptengage")]
@@ -12,7 +12,7 @@ gptengage enables orchestration of multiple AI CLI tools (Claude Code, Codex, Ge
 for debates, ideation, code reviews, and interactive sessions.
 
 DEFAULT TIMEOUT:
-    120 seconds per CLI invocation. Override with --timeout or config defaultTimeout.
+    120 seconds per CLI invocation. Override with --timeout or the default_timeout config key.
 
 QUICK START:
     gptengage status                    Check available CLIs
@@ -44,11 +44,244 @@ For detailed help on any command, use: gptengage <command> --help"
 )]
 #[command(version)]
 #[command(author)]
+// Options typed after those from --options-file replace them
+#[command(args_override_self = true)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Maximum number of CLI processes running at once (all CLIs)
+    #[arg(long, global = true, value_name = "N")]
+    pub max_concurrency: Option<usize>,
+
+    /// Maximum number of concurrent processes per CLI binary
+    ///
+    /// Useful when several participants share one CLI (multi-instance mode)
+    /// and the CLI misbehaves with many copies using the same local state.
+    #[arg(long, global = true, value_name = "N", verbatim_doc_comment)]
+    pub max_per_cli: Option<usize>,
+
+    /// Run each CLI with a scratch HOME/XDG config sandbox
+    ///
+    /// Only credential files are copied in (configurable via
+    /// isolate_credentials in ~/.gptengage/config.json), so CLIs cannot
+    /// write session state or settings into your real config directories.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub isolate: bool,
+
+    /// Run each CLI without network access
+    ///
+    /// On Linux, children run inside an unprivileged network namespace.
+    /// Set network_sandbox in ~/.gptengage/config.json to use a different
+    /// wrapper (e.g. firejail). Plugins listed in plugins.network_exempt
+    /// (such as a local ollama) are exempt.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub no_network: bool,
+
+    /// Trust plugin commands that are not yet allowlisted
+    ///
+    /// Plugins may only run commands listed in plugins.allowed_commands.
+    /// Unknown commands are confirmed interactively on first use; this flag
+    /// adds them to the allowlist without asking.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub trust: bool,
+
+    /// Run CLIs even when the daily budget is used up
+    ///
+    /// Invocations still count toward the budget.daily_invocations
+    /// and budget.daily_cost limits; they just aren't refused.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub force_budget: bool,
+
+    /// Keep plugin CLIs running between prompts (experimental)
+    ///
+    /// Plugins with a [worker] section are started once and fed each
+    /// prompt over stdin, skipping their startup time on every later
+    /// prompt. Has no effect with --isolate.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub workers: bool,
+
+    /// Show absolute timestamps instead of relative times ("5m ago")
+    ///
+    /// Timestamps use the time_format config key (utc, local, or a strftime
+    /// pattern); UTC when time_format is relative.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub absolute_times: bool,
+
+    /// Snapshot the working directory before each --write run
+    ///
+    /// Inside a git repository the snapshot is stored in the repository
+    /// (your index, stash, and branches are untouched); elsewhere files are
+    /// copied to ~/.gptengage/snapshots. Undo a run with
+    /// `gptengage rollback <id>`.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub snapshot: bool,
+
+    /// Read the command's options from a TOML file
+    ///
+    /// Keys are long option names, e.g. `rounds = 4` or
+    /// `participants = ["claude:CTO", "codex"]`. Options typed on the
+    /// command line win over the file's.
+    #[arg(long, global = true, value_name = "FILE", verbatim_doc_comment)]
+    pub options_file: Option<String>,
+
+    /// Answer yes to every confirmation
+    ///
+    /// Covers deleting all sessions and running over the daily budget.
+    /// Plugin commands still need --trust.
+    #[arg(short = 'y', long, global = true, verbatim_doc_comment)]
+    pub yes: bool,
+
+    /// Never wait for input
+    ///
+    /// Confirmations are declined and questions take their default, so
+    /// scripts fail fast instead of hanging. Implied when the CI
+    /// environment variable is set or stdin is not a terminal.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub non_interactive: bool,
+}
+
+/// Who debates and how, shared by `debate` and `debate batch`
+#[derive(Args)]
+pub struct DebateTeamArgs {
+    /// Select a single CLI to use for all participants
+    ///
+    /// Values: claude, codex, gemini
+    ///
+    /// Use with --instances to create multi-instance debates where the same
+    /// LLM debates itself (leveraging nondeterminism and debate dynamics).
+    ///
+    /// Examples:
+    ///   --agent claude --instances 3 (3 Claude instances)
+    ///   --agent gemini --instances 2 (2 Gemini instances)
+    ///
+    /// Cannot be used with --participants or --agent-file
+    #[arg(long, conflicts_with_all = ["participants", "agent_file"], verbatim_doc_comment)]
+    pub agent: Option<String>,
+
+    /// Number of instances to spawn when using --agent
+    ///
+    /// Creates multiple independent instances of the same CLI. Each instance
+    /// will produce different outputs due to LLM nondeterminism and respond
+    /// to other participants' inputs during the debate.
+    ///
+    /// Default: 3 when --agent is specified
+    ///
+    /// Example: --agent claude --instances 5
+    ///
+    /// Requires --agent to be specified
+    #[arg(long, requires = "agent", verbatim_doc_comment)]
+    pub instances: Option<usize>,
+
+    /// Model to use when --agent is specified
+    ///
+    /// Specifies which model to use for all instances. If not provided,
+    /// the CLI uses its default model.
+    ///
+    /// Examples:
+    ///   --agent claude --model claude-sonnet-4-20250514
+    ///   --agent codex --model gpt-4o
+    ///   --agent gemini --model gemini-2.5-pro
+    ///
+    /// Requires --agent to be specified
+    #[arg(long, short = 'm', requires = "agent", verbatim_doc_comment)]
+    pub model: Option<String>,
+
+    /// Participants with optional persona and model
+    ///
+    /// Format: "cli:persona,cli:persona,..." or "cli:persona:model,..."
+    ///
+    /// Examples:
+    ///   -p "claude:CEO,claude:Architect,codex:PM"
+    ///   -p "claude:Security Expert,gemini:UX Designer"
+    ///   -p "claude:CEO:claude-sonnet-4-20250514,codex:CTO:gpt-4o"
+    ///   -p "claude:@security-reviewer"   (from ~/.gptengage/personas/)
+    ///
+    /// Cannot be used with --agent-file or --agent
+    #[arg(long, short = 'p', conflicts_with_all = ["agent_file", "agent"], verbatim_doc_comment)]
+    pub participants: Option<String>,
+
+    /// Path to agent definition file with full agent specifications
+    ///
+    /// Agent files require structured definitions with persona, instructions,
+    /// expertise, and communication_style. Use 'generate-agents' to create.
+    /// JSON by default; .toml and .yaml/.yml files are read as TOML and YAML.
+    ///
+    /// Repeat to compose a roster from several files; personas must be
+    /// unique across them.
+    ///
+    /// Examples:
+    ///   --agent-file agents.json
+    ///   --agent-file leads.json --agent-file security.json
+    ///
+    /// Cannot be used with --participants or --agent
+    #[arg(long, conflicts_with_all = ["participants", "agent", "template"], verbatim_doc_comment)]
+    pub agent_file: Vec<String>,
+
+    /// Use a predefined debate template
+    ///
+    /// Templates provide pre-configured participants with personas and instructions.
+    /// Use 'gptengage template list' to see available templates.
+    ///
+    /// Examples:
+    ///   --template code-review
+    ///   --template security-audit
+    ///   --template architecture-decision
+    ///
+    /// Cannot be used with --participants, --agent, or --agent-file
+    #[arg(long, conflicts_with_all = ["participants", "agent", "agent_file"], verbatim_doc_comment)]
+    pub template: Option<String>,
+
+    /// Number of debate rounds (default: the template's rounds with --template,
+    /// otherwise default_debate_rounds from config, 3)
+    #[arg(long, short = 'r')]
+    pub rounds: Option<usize>,
+
+    /// Timeout per CLI invocation in seconds
+    ///
+    /// The CLI process is terminated if it exceeds this duration.
+    /// Default: default_timeout from config (120 seconds)
+    #[arg(long, short = 't', verbatim_doc_comment)]
+    pub timeout: Option<u64>,
+
+    /// Allow write access within the current directory (default: read-only)
+    #[arg(long, verbatim_doc_comment)]
+    pub write: bool,
+
+    /// Generate a synthesis after the debate completes
+    ///
+    /// Produces a structured summary including:
+    /// - Key points of consensus
+    /// - Points of disagreement
+    /// - Key insights
+    /// - Recommendation (if applicable)
+    ///
+    /// Example: gptengage debate "topic" --synthesize
+    #[arg(long, verbatim_doc_comment)]
+    pub synthesize: bool,
+
+    /// CLI to use for synthesis generation (default: claude)
+    ///
+    /// Only used when --synthesize is specified.
+    /// Example: gptengage debate "topic" --synthesize --synthesizer codex
+    #[arg(
+        long,
+        default_value = "claude",
+        requires = "synthesize",
+        verbatim_doc_comment
+    )]
+    pub synthesizer: String,
+
+    /// Collapse each response into a <details> block (markdown output)
+    ///
+    /// Keeps long transcripts skimmable on GitHub/GitLab; the table of
+    /// contents still links to every round and response.
+    #[arg(long, verbatim_doc_comment)]
+    pub collapse: bool,
 }
 
+// Parsed once per process, so variant sizes don't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Run a multi-AI debate (Claude + Codex + Gemini)
@@ -74,152 +307,369 @@ pub enum Commands {
     ///
     ///   # 5 rounds with JSON output
     ///   gptengage debate "REST vs GraphQL" --rounds 5 --output json
-    #[command(verbatim_doc_comment)]
+    ///
+    ///   # One debate per line of topics.txt
+    ///   gptengage debate batch --topics-file topics.txt --template architecture-decision
+    #[command(verbatim_doc_comment, args_conflicts_with_subcommands = true)]
     Debate {
+        #[command(subcommand)]
+        command: Option<DebateCommands>,
+
         /// The topic to debate (optional if piping via stdin)
         ///
         /// Can be omitted when piping input via stdin.
         /// Example: echo "topic" | gptengage debate
+        ///
+        /// batch, export, and import are subcommands; to debate a topic
+        /// spelled like one, put it after --: gptengage debate -- batch
         #[arg(default_value = "", verbatim_doc_comment)]
         topic: String,
 
-        /// Select a single CLI to use for all participants
-        ///
-        /// Values: claude, codex, gemini
+        #[command(flatten)]
+        team: DebateTeamArgs,
+
+        /// Generate agent definitions for these roles, then debate with them
         ///
-        /// Use with --instances to create multi-instance debates where the same
-        /// LLM debates itself (leveraging nondeterminism and debate dynamics).
+        /// Runs the generate-agents flow in memory (no intermediate file)
+        /// and starts the debate with the generated definitions. Roles
+        /// written as @name come from the persona library.
         ///
         /// Examples:
-        ///   --agent claude --instances 3 (3 Claude instances)
-        ///   --agent gemini --instances 2 (2 Gemini instances)
+        ///   --auto-agents "CEO,CTO,SRE"
+        ///   --auto-agents "CTO,@security-reviewer" --save-agents agents.json
         ///
-        /// Cannot be used with --participants or --agent-file
-        #[arg(long, conflicts_with_all = ["participants", "agent_file"], verbatim_doc_comment)]
-        agent: Option<String>,
+        /// Cannot be used with --participants, --agent, --agent-file, or --template
+        #[arg(
+            long,
+            value_name = "ROLES",
+            conflicts_with_all = ["participants", "agent", "agent_file", "template"],
+            verbatim_doc_comment
+        )]
+        auto_agents: Option<String>,
+
+        /// CLI that generates the --auto-agents definitions (default: claude)
+        #[arg(
+            long,
+            value_name = "CLI",
+            default_value = "claude",
+            requires = "auto_agents",
+            verbatim_doc_comment
+        )]
+        auto_agents_cli: String,
 
-        /// Number of instances to spawn when using --agent
+        /// Also write the generated agent file, for reuse with --agent-file
+        #[arg(
+            long,
+            value_name = "FILE",
+            requires = "auto_agents",
+            verbatim_doc_comment
+        )]
+        save_agents: Option<String>,
+
+        /// Output format: text, json, markdown, dot, mermaid, csv
         ///
-        /// Creates multiple independent instances of the same CLI. Each instance
-        /// will produce different outputs due to LLM nondeterminism and respond
-        /// to other participants' inputs during the debate.
+        /// dot (Graphviz) and mermaid draw an argument map: participants,
+        /// the synthesis's consensus and disagreement points, and who
+        /// agrees or disagrees with whom. Use with --synthesize and
+        /// --track-stances for a complete map. csv writes the risk
+        /// register (requires --risk-register).
+        #[arg(long, short = 'o', default_value = "text", verbatim_doc_comment)]
+        output: String,
+
+        /// Close each round this many seconds after it starts
         ///
-        /// Default: 3 when --agent is specified
+        /// The round continues with whichever responses arrived; CLIs
+        /// still running are cancelled and left out of that round.
+        /// Without it, every round waits for its slowest participant
+        /// (up to --timeout).
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            value_parser = clap::value_parser!(u64).range(1..),
+            verbatim_doc_comment
+        )]
+        round_timeout: Option<u64>,
+
+        /// Start a duplicate request to a participant that hasn't answered
+        /// after this many seconds, keeping whichever answers first
         ///
-        /// Example: --agent claude --instances 5
+        /// The other request is cancelled. A hedge against CLIs that
+        /// occasionally hang; each duplicate counts against budgets.
+        /// Not available with --write: two CLIs would edit the workspace
+        /// at once, and the cancelled one would stop mid-edit.
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            value_parser = clap::value_parser!(u64).range(1..),
+            conflicts_with = "write",
+            verbatim_doc_comment
+        )]
+        hedge_after: Option<u64>,
+
+        /// Bench a participant after N failed rounds in a row
         ///
-        /// Requires --agent to be specified
-        #[arg(long, requires = "agent", verbatim_doc_comment)]
-        instances: Option<usize>,
+        /// A benched participant sits out the remaining rounds instead of
+        /// timing out again; benchings are noted in the transcript.
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = clap::value_parser!(u64).range(1..),
+            verbatim_doc_comment
+        )]
+        bench_after: Option<u64>,
 
-        /// Model to use when --agent is specified
+        /// Re-probe benched participants with a tiny prompt before the
+        /// final round; those that answer rejoin for it
+        #[arg(long, requires = "bench_after", verbatim_doc_comment)]
+        reprobe: bool,
+
+        /// How to interpret stdin when input is piped
         ///
-        /// Specifies which model to use for all instances. If not provided,
-        /// the CLI uses its default model.
+        /// Controls behavior when data is piped to gptengage:
+        ///   auto    - Use stdin as topic if no topic provided, otherwise as context
+        ///   context - Always use stdin as additional context
+        ///   file    - Save stdin to a file the CLIs are told to read
+        ///   ignore  - Ignore stdin input
         ///
         /// Examples:
-        ///   --agent claude --model claude-sonnet-4-20250514
-        ///   --agent codex --model gpt-4o
-        ///   --agent gemini --model gemini-2.5-pro
+        ///   echo "topic" | gptengage debate                    # stdin becomes topic
+        ///   cat code.rs | gptengage debate "Review" --stdin-as context  # stdin is context
+        ///   cat huge.log | gptengage debate "Root cause?" --stdin-as file  # stdin is a file
+        ///   gptengage debate "topic" --stdin-as ignore         # ignore any piped input
+        #[arg(long, value_enum, default_value = "auto", verbatim_doc_comment)]
+        stdin_as: StdinMode,
+
+        /// Add the output of a context provider plugin to the topic
         ///
-        /// Requires --agent to be specified
-        #[arg(long, short = 'm', requires = "agent", verbatim_doc_comment)]
-        model: Option<String>,
+        /// The plugin is run once with the topic before the debate starts.
+        /// Repeat for several providers.
+        /// Example: --context-provider k8s-state
+        #[arg(long, value_name = "PLUGIN", verbatim_doc_comment)]
+        context_provider: Vec<String>,
 
-        /// Participants with optional persona and model
+        /// Update the synthesis after every round instead of once at the end
         ///
-        /// Format: "cli:persona,cli:persona,..." or "cli:persona:model,..."
+        /// Each closed round goes to the synthesizer with the synthesis so
+        /// far, while the next round runs, and the evolving summary is
+        /// printed. The call after the debate only covers the last round.
+        #[arg(long, requires = "synthesize", verbatim_doc_comment)]
+        incremental_synthesis: bool,
+
+        /// CLI that checks the synthesis against the transcript
         ///
-        /// Examples:
-        ///   -p "claude:CEO,claude:Architect,codex:PM"
-        ///   -p "claude:Security Expert,gemini:UX Designer"
-        ///   -p "claude:CEO:claude-sonnet-4-20250514,codex:CTO:gpt-4o"
+        /// After synthesis, flags each claim the debate doesn't support
+        /// (things nobody said, consensus that wasn't reached), quoting
+        /// the transcript passages it was checked against.
+        ///
+        /// Example: gptengage debate "topic" --synthesize --fact-check codex
+        #[arg(
+            long,
+            value_name = "CLI",
+            requires = "synthesize",
+            verbatim_doc_comment
+        )]
+        fact_check: Option<String>,
+
+        /// Track each participant's position across rounds
+        ///
+        /// After the debate, a classifier prompt labels every participant's
+        /// position per round (one extra invocation per round). Position
+        /// changes are reported in the output and fed into --synthesize.
         ///
-        /// Cannot be used with --agent-file or --agent
-        #[arg(long, short = 'p', conflicts_with_all = ["agent_file", "agent"], verbatim_doc_comment)]
-        participants: Option<String>,
+        /// Example: gptengage debate "topic" --track-stances --synthesize
+        #[arg(long, verbatim_doc_comment)]
+        track_stances: bool,
 
-        /// Path to agent definition file (JSON) with full agent specifications
+        /// CLI to use for stance classification (default: claude)
         ///
-        /// Agent files require structured definitions with persona, instructions,
-        /// expertise, and communication_style. Use 'generate-agents' to create.
+        /// Only used when --track-stances is specified.
+        #[arg(
+            long,
+            default_value = "claude",
+            requires = "track_stances",
+            verbatim_doc_comment
+        )]
+        stance_cli: String,
+
+        /// Extract a risk register from the debate
         ///
-        /// Example: --agent-file agents.json
+        /// After the debate, one more invocation lists the risks raised,
+        /// each with likelihood, impact, mitigation, and a suggested
+        /// owner. Shown as a table in markdown output; --output csv writes
+        /// only the register.
         ///
-        /// Cannot be used with --participants or --agent
-        #[arg(long, conflicts_with_all = ["participants", "agent", "template"], verbatim_doc_comment)]
-        agent_file: Option<String>,
+        /// Example: gptengage debate "topic" --synthesize --risk-register -o markdown
+        #[arg(long, verbatim_doc_comment)]
+        risk_register: bool,
 
-        /// Use a predefined debate template
+        /// CLI to use for risk extraction (default: claude)
         ///
-        /// Templates provide pre-configured participants with personas and instructions.
-        /// Use 'gptengage template list' to see available templates.
+        /// Only used when --risk-register is specified.
+        #[arg(
+            long,
+            default_value = "claude",
+            requires = "risk_register",
+            verbatim_doc_comment
+        )]
+        risk_cli: String,
+
+        /// Extract follow-up tasks from the debate
         ///
-        /// Examples:
-        ///   --template code-review
-        ///   --template security-audit
-        ///   --template architecture-decision
+        /// After the debate, one more invocation lists the tasks it calls
+        /// for, each with a suggested owner and a priority. Shown in text
+        /// and markdown output and kept in JSON.
         ///
-        /// Cannot be used with --participants, --agent, or --agent-file
-        #[arg(long, conflicts_with_all = ["participants", "agent", "agent_file"], verbatim_doc_comment)]
-        template: Option<String>,
+        /// Example: gptengage debate "Review this PR" --template code-review --action-items
+        #[arg(long, verbatim_doc_comment)]
+        action_items: bool,
 
-        /// Number of debate rounds (default: 3, or template default if using --template)
-        #[arg(long, short = 'r')]
-        rounds: Option<usize>,
+        /// CLI to use for action item extraction (default: claude)
+        #[arg(
+            long,
+            default_value = "claude",
+            requires = "action_items",
+            verbatim_doc_comment
+        )]
+        action_items_cli: String,
 
-        /// Output format: text, json, markdown
-        #[arg(long, short = 'o', default_value = "text")]
-        output: String,
+        /// File an issue for each action item
+        ///
+        /// Without a value, creates GitHub issues with `gh` in the current
+        /// directory's repository. With a name, posts to that tracker from
+        /// issue_trackers in config (e.g. jira, linear). Issue URLs or keys
+        /// are recorded on the items.
+        #[arg(
+            long,
+            value_name = "TRACKER",
+            num_args = 0..=1,
+            default_missing_value = "github",
+            requires = "action_items",
+            verbatim_doc_comment
+        )]
+        create_issues: Option<String>,
 
-        /// Timeout per CLI invocation in seconds
+        /// Post the debate as a comment on a pull/merge request
         ///
-        /// The CLI process is terminated if it exceeds this duration.
-        /// Default: 120 seconds (2 minutes)
-        #[arg(long, short = 't', default_value = "120", verbatim_doc_comment)]
-        timeout: u64,
+        /// Comments the markdown output (responses collapsed) with the CLI
+        /// of the forge set in config: gh (github, the default), glab
+        /// (gitlab), or tea (gitea), e.g. `gptengage config set forge
+        /// gitlab`. Without a number, comments on the current branch's
+        /// request; Gitea needs the number.
+        #[arg(long, value_name = "NUMBER", num_args = 0..=1, verbatim_doc_comment)]
+        post_to_mr: Option<Option<u64>>,
+
+        /// Add an observer who sits out the rounds and critiques the
+        /// whole debate afterwards (repeatable)
+        ///
+        /// Same format as a --participants entry: cli, cli:persona, or
+        /// cli:persona:model. Observers don't see the synthesis or each
+        /// other's critiques.
+        ///
+        /// Example: --observer "claude:Red Team" --observer "gemini:Skeptical CFO"
+        #[arg(long, value_name = "CLI:PERSONA", verbatim_doc_comment)]
+        observer: Vec<String>,
 
-        /// Allow write access within the current directory (default: read-only)
+        /// Print each participant's round-1 prompt with a size breakdown
+        /// (to stderr) before running the debate
         #[arg(long, verbatim_doc_comment)]
-        write: bool,
+        show_prompt: bool,
 
-        /// How to interpret stdin when input is piped
-        ///
-        /// Controls behavior when data is piped to gptengage:
-        ///   auto    - Use stdin as topic if no topic provided, otherwise as context
-        ///   context - Always use stdin as additional context
-        ///   ignore  - Ignore stdin input
+        /// Print each participant's round-1 prompt with a size breakdown
+        /// instead of running the debate
+        #[arg(long, verbatim_doc_comment)]
+        show_prompt_only: bool,
+
+        /// Write each participant's round-1 prompt to a file in DIR and
+        /// exit without invoking any participant
         ///
-        /// Examples:
-        ///   echo "topic" | gptengage debate                    # stdin becomes topic
-        ///   cat code.rs | gptengage debate "Review" --stdin-as context  # stdin is context
-        ///   gptengage debate "topic" --stdin-as ignore         # ignore any piped input
-        #[arg(long, value_enum, default_value = "auto", verbatim_doc_comment)]
-        stdin_as: StdinMode,
+        /// Files are named <NN>-<participant>.txt and hold exactly the
+        /// text that would be sent.
+        #[arg(long, value_name = "DIR", verbatim_doc_comment)]
+        dump_prompts: Option<String>,
 
-        /// Generate a synthesis after the debate completes
+        /// Leave previous rounds' responses out of participant prompts
         ///
-        /// Produces a structured summary including:
-        /// - Key points of consensus
-        /// - Points of disagreement
-        /// - Key insights
-        /// - Recommendation (if applicable)
+        /// Every round then answers the topic independently.
+        #[arg(long, conflicts_with = "history_last", verbatim_doc_comment)]
+        no_history: bool,
+
+        /// Include the responses of the last N rounds in each prompt
+        /// (default: 1, the previous round only)
+        #[arg(long, value_name = "N", verbatim_doc_comment)]
+        history_last: Option<usize>,
+
+        /// Leave persona/role scaffolding out of participant prompts
         ///
-        /// Example: gptengage debate "topic" --synthesize
+        /// Participants keep their labels in the transcript; only the
+        /// role instructions are dropped from what they are sent.
         #[arg(long, verbatim_doc_comment)]
-        synthesize: bool,
+        no_persona_context: bool,
 
-        /// CLI to use for synthesis generation (default: claude)
+        /// Run a two-phase debate: rounds up to N ask for as many
+        /// divergent ideas as possible, later rounds for convergence on
+        /// one actionable recommendation
         ///
-        /// Only used when --synthesize is specified.
-        /// Example: gptengage debate "topic" --synthesize --synthesizer codex
+        /// N must leave at least one convergence round.
         #[arg(
             long,
-            default_value = "claude",
-            requires = "synthesize",
+            value_name = "N",
+            value_parser = clap::value_parser!(u64).range(1..),
             verbatim_doc_comment
         )]
-        synthesizer: String,
+        converge_after: Option<u64>,
+
+        /// Pair participants into rotating 1v1 exchanges each round
+        ///
+        /// Pairs run in parallel and everyone gets a short digest of the
+        /// previous round's other exchanges, keeping prompts small with
+        /// many participants (recommended above ~4). With an odd count,
+        /// one participant sits out each exchange and answers alone.
+        ///
+        /// Example: --agent claude --instances 8 --rounds 7 --round-robin
+        #[arg(long, verbatim_doc_comment)]
+        round_robin: bool,
+
+        /// Print the transcript in participant order once the debate is
+        /// over, instead of each response as it arrives (text output)
+        #[arg(long, verbatim_doc_comment)]
+        ordered: bool,
+
+        /// Watch each participant in its own tmux pane
+        ///
+        /// Opens a pane per participant and one for the synthesis next to
+        /// the current one, each updated as responses arrive. Must run
+        /// inside tmux; in iTerm2, `tmux -CC` shows them as native panes.
+        #[arg(long, verbatim_doc_comment)]
+        sidecar: bool,
+
+        /// Format every participant's answer must be in: json, markdown, or
+        /// language:<LANG> (e.g. language:fr)
+        ///
+        /// An answer in another format is sent back once with a corrective
+        /// prompt. Default: the `response_formats` config entry for this
+        /// command.
+        #[arg(long, value_name = "FORMAT", verbatim_doc_comment)]
+        response_format: Option<String>,
+
+        /// Use a preset from config: a named set of template, rounds,
+        /// output, synthesis, and context flags
+        ///
+        /// Flags given on the command line take precedence over the
+        /// preset's. Define presets under `presets` with
+        /// `gptengage config edit`.
+        ///
+        /// Example: gptengage debate "Split the monolith?" --preset arch
+        #[arg(long, value_name = "NAME", verbatim_doc_comment)]
+        preset: Option<String>,
+
+        /// Narrate more while the debate runs (repeat for more detail)
+        ///
+        /// -v:   round banners only (the default)
+        /// -vv:  plus how long each participant took to answer or fail
+        /// -vvv: plus each prompt's size and what output filters did
+        /// Details go to stderr.
+        #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count, verbatim_doc_comment)]
+        verbose: u8,
     },
 
     /// Invoke a specific CLI with a prompt
@@ -270,11 +720,16 @@ pub enum Commands {
         ///
         /// Sessions maintain full conversation history. Each turn is
         /// injected into subsequent prompts for context continuity.
+        /// Created when it doesn't exist yet.
         ///
         /// Example: --session my-session
-        #[arg(long, short = 's', verbatim_doc_comment)]
+        #[arg(long, short = 's', group = "session_choice", verbatim_doc_comment)]
         session: Option<String>,
 
+        /// Pick an existing session interactively instead of naming one
+        #[arg(long, group = "session_choice", verbatim_doc_comment)]
+        pick_session: bool,
+
         /// Session topic description
         ///
         /// Auto-generated from first prompt if omitted.
@@ -289,12 +744,20 @@ pub enum Commands {
         #[arg(long, short = 'c', verbatim_doc_comment)]
         context_file: Option<String>,
 
+        /// Add the output of a context provider plugin to the prompt
+        ///
+        /// The plugin is run with your request before the CLI is.
+        /// Repeat for several providers.
+        /// Example: --context-provider db-schema
+        #[arg(long, value_name = "PLUGIN", verbatim_doc_comment)]
+        context_provider: Vec<String>,
+
         /// Timeout in seconds
         ///
         /// The CLI process is terminated if it exceeds this duration.
-        /// Default: 120 seconds (2 minutes)
-        #[arg(long, short = 't', default_value = "120", verbatim_doc_comment)]
-        timeout: u64,
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't', verbatim_doc_comment)]
+        timeout: Option<u64>,
 
         /// Allow write access within the current directory (default: read-only)
         #[arg(long, verbatim_doc_comment)]
@@ -305,39 +768,469 @@ pub enum Commands {
         /// Controls behavior when data is piped to gptengage:
         ///   auto    - Use stdin as prompt if no prompt provided, otherwise as context
         ///   context - Always use stdin as additional context
+        ///   file    - Save stdin to a file the CLIs are told to read
         ///   ignore  - Ignore stdin input
         ///
         /// Examples:
         ///   echo "question" | gptengage invoke claude           # stdin becomes prompt
         ///   cat code.rs | gptengage invoke claude "Review" --stdin-as context
+        ///   cat huge.log | gptengage invoke claude "Root cause?" --stdin-as file
         ///   gptengage invoke claude "prompt" --stdin-as ignore  # ignore any piped input
         #[arg(long, value_enum, default_value = "auto", verbatim_doc_comment)]
         stdin_as: StdinMode,
-    },
 
-    /// Manage sessions
-    #[command(subcommand)]
-    Session(SessionCommands),
+        /// Print the assembled prompt with a size breakdown (to stderr)
+        /// before invoking
+        ///
+        /// Shows how much of the prompt comes from session history, the
+        /// context file, piped stdin, and your request.
+        #[arg(long, verbatim_doc_comment)]
+        show_prompt: bool,
 
-    /// Show status of detected CLIs and active sessions
-    Status,
+        /// Print the assembled prompt with a size breakdown instead of
+        /// invoking (the session is not updated)
+        #[arg(long, verbatim_doc_comment)]
+        show_prompt_only: bool,
 
-    /// Manage CLI plugins
+        /// Send the prompt without session history
+        ///
+        /// The new turn is still saved to the session.
+        #[arg(long, conflicts_with = "history_last", verbatim_doc_comment)]
+        no_history: bool,
+
+        /// Include only the last N session turns in the prompt
+        ///
+        /// Each prompt and each response counts as one turn. The session
+        /// itself keeps its full history.
+        #[arg(long, value_name = "N", verbatim_doc_comment)]
+        history_last: Option<usize>,
+
+        /// Continue the CLI's own conversation instead of re-sending the
+        /// session history (claude only)
+        ///
+        /// The CLI's conversation ID is stored in the session, and later
+        /// turns with the same CLI resume it automatically, sending only
+        /// the new prompt. The first native turn of a session that already
+        /// has turns sends the history once to seed the conversation.
+        ///
+        /// Example: gptengage invoke claude "Review auth.rs" --session auth --native-session
+        #[arg(
+            long,
+            requires = "session_choice",
+            conflicts_with = "best_of",
+            verbatim_doc_comment
+        )]
+        native_session: bool,
+
+        /// Generate N candidate answers and have a judge pick the best
+        ///
+        /// Only the winner is printed (and saved to the session); the
+        /// judge's justification goes to stderr.
+        ///
+        /// Example: gptengage invoke claude "Write a retry helper" --best-of 3 --judge gemini
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = clap::value_parser!(u16).range(2..),
+            verbatim_doc_comment
+        )]
+        best_of: Option<u16>,
+
+        /// CLI that judges --best-of candidates (default: the invoked CLI)
+        #[arg(long, requires = "best_of", verbatim_doc_comment)]
+        judge: Option<String>,
+
+        /// Print every --best-of candidate, marking the winner
+        #[arg(long, requires = "best_of", verbatim_doc_comment)]
+        show_candidates: bool,
+
+        /// Reduce the response to one of the given answers, e.g. "yes|no"
+        ///
+        /// The earliest whole-word mention (case-insensitive) is printed,
+        /// spelled as given. The full response is kept in the session.
+        /// Exits with an error if no answer is found.
+        ///
+        /// Example: gptengage invoke claude "Is this migration reversible?" --extract "yes|no"
+        #[arg(
+            long,
+            value_name = "CHOICES",
+            conflicts_with = "extract_regex",
+            verbatim_doc_comment
+        )]
+        extract: Option<String>,
+
+        /// Reduce the response to the first match of a regex (its first
+        /// capture group, if it has one)
+        ///
+        /// Example: --extract-regex 'score:\s*(\d+)'
+        #[arg(long, value_name = "PATTERN", verbatim_doc_comment)]
+        extract_regex: Option<String>,
+
+        /// Format the answer must be in: json, markdown, or
+        /// language:<LANG> (e.g. language:fr)
+        ///
+        /// An answer in another format is sent back once with a corrective
+        /// prompt. Default: the `response_formats` config entry for this
+        /// command.
+        #[arg(long, value_name = "FORMAT", verbatim_doc_comment)]
+        response_format: Option<String>,
+    },
+
+    /// Run independent invocations in parallel from a jobs file
     ///
-    /// Plugins allow adding custom LLM CLIs without modifying GPT Engage source code.
-    /// Plugin files are TOML configurations stored in ~/.gptengage/plugins/
+    /// Each job names its own CLI, prompt, and optional model, context,
+    /// context files, and timeout. Jobs run with bounded concurrency and
+    /// their results are gathered into one JSON report (stdout, or the
+    /// file given with --output). Exits non-zero if any job failed.
     ///
-    /// Examples:
-    ///   # List installed plugins
-    ///   gptengage plugin list
+    /// Jobs file (YAML or JSON):
+    ///   jobs:
+    ///     - name: summary
+    ///       cli: claude
+    ///       prompt: Summarize the changes in this diff
+    ///       context_files: [changes.diff]
+    ///     - cli: gemini
+    ///       model: gemini-2.5-pro
+    ///       context: We deploy to Kubernetes on GKE.
+    ///       prompt: List the operational risks of this design
+    ///       timeout: 300
     ///
-    ///   # Validate a plugin file
-    ///   gptengage plugin validate ~/.gptengage/plugins/ollama.toml
-    #[command(subcommand, verbatim_doc_comment)]
-    Plugin(PluginCommands),
+    /// Examples:
+    ///   gptengage fanout --jobs jobs.yaml
+    ///   gptengage fanout --jobs jobs.yaml --concurrency 8 -o report.json
+    #[command(verbatim_doc_comment)]
+    Fanout {
+        /// Jobs file (YAML or JSON): a list of jobs, or a map with a `jobs` list
+        #[arg(long, verbatim_doc_comment)]
+        jobs: String,
 
-    /// Manage debate templates
-    ///
+        /// Write the JSON report to a file instead of stdout
+        #[arg(long, short = 'o', verbatim_doc_comment)]
+        output: Option<String>,
+
+        /// Maximum number of jobs running at once
+        #[arg(long, short = 'j', default_value = "4", verbatim_doc_comment)]
+        concurrency: usize,
+
+        /// Timeout per job in seconds (a job's own `timeout` overrides it)
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't', verbatim_doc_comment)]
+        timeout: Option<u64>,
+
+        /// Allow write access within the current directory (default: read-only)
+        #[arg(long, verbatim_doc_comment)]
+        write: bool,
+    },
+
+    /// Synthesize several stored debates into one comparison, or any
+    /// transcript on its own
+    ///
+    /// With --debates, reads JSON debate transcripts (or every .json
+    /// transcript in a directory, such as a `debate batch --output json`
+    /// output directory) and asks a CLI for the themes they share, the
+    /// recommendations that conflict between them, and an overall
+    /// recommendation. Debates are represented by their own synthesis when
+    /// they have one, and by their final round otherwise.
+    ///
+    /// With --from, gives one transcript the synthesis `debate --synthesize`
+    /// would: a JSON debate transcript, earlier markdown or text output, or
+    /// meeting notes and discussions from elsewhere.
+    ///
+    /// Examples:
+    ///   gptengage synthesize --debates cache.json shard.json queue.json
+    ///   gptengage synthesize --debates decisions/ --synthesizer codex
+    ///   gptengage synthesize --debates decisions/ --output markdown > overview.md
+    ///   gptengage synthesize --from meeting-notes.md --output json
+    #[command(verbatim_doc_comment)]
+    Synthesize {
+        /// Transcript files or directories of transcripts
+        #[arg(
+            long,
+            required_unless_present = "from",
+            conflicts_with = "from",
+            num_args = 1..,
+            value_name = "TRANSCRIPT"
+        )]
+        debates: Vec<String>,
+
+        /// Synthesize a single transcript in any format
+        #[arg(long, value_name = "FILE")]
+        from: Option<String>,
+
+        /// CLI to use for the synthesis
+        #[arg(long, default_value = "claude")]
+        synthesizer: String,
+
+        /// Output format: text, markdown, json
+        #[arg(long, short = 'o', default_value = "text")]
+        output: String,
+
+        /// Timeout in seconds
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't')]
+        timeout: Option<u64>,
+    },
+
+    /// Keep a decision log in the repository
+    ///
+    /// Records debate outcomes as numbered decisions (topic, decision,
+    /// rationale, dissent, date, transcript link) in decisions.jsonl, and
+    /// rewrites decisions.md next to it after every change.
+    ///
+    /// Examples:
+    ///   gptengage debate "Adopt Rust?" --synthesize -o json > debates/rust.json
+    ///   gptengage decisions add --from-debate debates/rust.json
+    ///   gptengage decisions list
+    ///   gptengage decisions show 3
+    #[command(subcommand, verbatim_doc_comment)]
+    Decisions(DecisionsCommands),
+
+    /// Run a command and ask a CLI to explain its failure
+    ///
+    /// The command runs as given (no shell), its output is shown, and the
+    /// command line, exit status, and captured stdout/stderr are sent to
+    /// the CLI, which explains what went wrong and suggests fixes. Use
+    /// `sh -c "..."` for pipes and redirections.
+    ///
+    /// Examples:
+    ///   gptengage explain -- cargo build
+    ///   gptengage explain --cli gemini -- npm test
+    ///   gptengage explain -- sh -c "make 2>&1 | tail -50"
+    #[command(verbatim_doc_comment)]
+    Explain {
+        /// Command to run, after `--`
+        #[arg(required = true, last = true, value_name = "COMMAND")]
+        command: Vec<String>,
+
+        /// CLI to ask for the explanation
+        #[arg(long, default_value = "claude")]
+        cli: String,
+
+        /// Model to use for the explaining CLI
+        #[arg(long, short = 'm')]
+        model: Option<String>,
+
+        /// Timeout for the command being explained, in seconds
+        #[arg(long, default_value = "600")]
+        command_timeout: u64,
+
+        /// Timeout for the explaining CLI, in seconds
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't')]
+        timeout: Option<u64>,
+    },
+
+    /// Have a CLI write unit tests for a file, then run and repair them
+    ///
+    /// The CLI adds tests with write access to the current directory,
+    /// then the test command runs. While it fails, its output goes back to
+    /// the CLI to fix the tests, up to --max-repairs times. Exits non-zero
+    /// if the tests still fail; the generated tests are left in place.
+    ///
+    /// Examples:
+    ///   gptengage gen-tests --file src/session/mod.rs
+    ///   gptengage gen-tests --file src/parser.rs --cli codex --max-repairs 5
+    ///   gptengage gen-tests --file app/utils.py --test-command "pytest -q"
+    #[command(verbatim_doc_comment)]
+    GenTests {
+        /// File to write tests for
+        #[arg(long, value_name = "FILE")]
+        file: String,
+
+        /// CLI that writes and repairs the tests
+        #[arg(long, default_value = "claude")]
+        cli: String,
+
+        /// Model to use
+        #[arg(long, short = 'm')]
+        model: Option<String>,
+
+        /// Command that runs the tests
+        #[arg(long, value_name = "COMMAND", default_value = "cargo test")]
+        test_command: String,
+
+        /// Times failing tests are sent back to the CLI for repair
+        #[arg(long, value_name = "N", default_value = "3")]
+        max_repairs: usize,
+
+        /// Timeout for each CLI invocation in seconds
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't', verbatim_doc_comment)]
+        timeout: Option<u64>,
+
+        /// Timeout for each test run, in seconds
+        #[arg(long, value_name = "SECONDS", default_value = "600")]
+        test_timeout: u64,
+    },
+
+    /// Write categorized release notes from git history
+    ///
+    /// Sends the commit messages and diff stats between two refs to a CLI,
+    /// which groups the changes into markdown release notes (breaking
+    /// changes, features, fixes, performance, documentation, internal).
+    /// With --compare, each listed CLI drafts notes and a judge picks one.
+    ///
+    /// Examples:
+    ///   gptengage release-notes --from v1.2.0
+    ///   gptengage release-notes --from v1.2.0 --to v1.3.0 > NOTES.md
+    ///   gptengage release-notes --from v1.2.0 --compare claude,codex,gemini
+    #[command(verbatim_doc_comment)]
+    ReleaseNotes {
+        /// Ref the release starts after, e.g. the previous tag
+        #[arg(long, value_name = "REF")]
+        from: String,
+
+        /// Ref the release ends at
+        #[arg(long, value_name = "REF", default_value = "HEAD")]
+        to: String,
+
+        /// CLI that writes the notes
+        #[arg(long, default_value = "claude")]
+        cli: String,
+
+        /// Model to use (not with --compare)
+        #[arg(long, short = 'm', conflicts_with = "compare")]
+        model: Option<String>,
+
+        /// Have each of these CLIs draft the notes and a judge pick the
+        /// best draft (comma-separated)
+        #[arg(long, value_name = "CLIS", verbatim_doc_comment)]
+        compare: Option<String>,
+
+        /// CLI (or judge plugin) that picks the best draft (default: --cli)
+        #[arg(long, value_name = "CLI", requires = "compare")]
+        judge: Option<String>,
+
+        /// Timeout per CLI invocation in seconds
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't')]
+        timeout: Option<u64>,
+    },
+
+    /// Search past sessions and debate transcripts
+    ///
+    /// Ranks session turns and transcript passages by similarity to the
+    /// query (TF-IDF term vectors computed locally; nothing is sent to a
+    /// CLI). With --rank-with, a CLI expands the query and ranks the
+    /// candidates by meaning instead. Sessions are always searched; add
+    /// transcript files or directories of .json, .md, and .txt transcripts
+    /// with --path.
+    ///
+    /// Examples:
+    ///   gptengage search "circuit breaker discussion"
+    ///   gptengage search "rollout plan" --path decisions/ --no-sessions -n 5
+    ///   gptengage search "how we handle flaky dependencies" --rank-with claude
+    #[command(verbatim_doc_comment)]
+    Search {
+        /// What to look for
+        query: String,
+
+        /// Transcript file or directory to search too (repeatable)
+        #[arg(long = "path", short = 'p', value_name = "PATH")]
+        paths: Vec<String>,
+
+        /// Don't search sessions
+        #[arg(long)]
+        no_sessions: bool,
+
+        /// Maximum number of results
+        #[arg(long, short = 'n', default_value = "10")]
+        limit: usize,
+
+        /// Print results as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Search by meaning: this CLI expands the query and ranks the
+        /// candidate passages
+        #[arg(long, value_name = "CLI")]
+        rank_with: Option<String>,
+
+        /// Timeout for each --rank-with call, in seconds
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't')]
+        timeout: Option<u64>,
+    },
+
+    /// Summarize recent use: debates, decisions, sessions, and failures
+    ///
+    /// Aggregates the debate history (~/.gptengage/history.jsonl), sessions,
+    /// and the audit log (when audit_log is enabled) over a period. The CLIs
+    /// don't report costs, so time spent in each CLI is shown instead.
+    ///
+    /// Examples:
+    ///   gptengage report
+    ///   gptengage report --since 7d --output markdown > weekly.md
+    ///   gptengage report --since 2026-01-01 --output json
+    #[command(verbatim_doc_comment)]
+    Report {
+        /// Start of the period: an age (30m, 24h, 7d, 2w) or a date (2026-01-05)
+        #[arg(long, default_value = "7d")]
+        since: String,
+
+        /// Output format: text, markdown, json
+        #[arg(long, short = 'o', default_value = "text")]
+        output: String,
+    },
+
+    /// Undo the changes of a --write run taken with --snapshot
+    ///
+    /// Files the run modified or deleted are restored and files it created
+    /// are removed. In git repositories, ignored files are not covered.
+    ///
+    /// Examples:
+    ///   gptengage debate "Fix the flaky test" --write --snapshot
+    ///   gptengage rollback --list
+    ///   gptengage rollback 20260105-142233-1a2b --dry-run
+    ///   gptengage rollback 20260105-142233-1a2b
+    #[command(verbatim_doc_comment)]
+    Rollback {
+        /// Snapshot id (printed when the run started)
+        #[arg(required_unless_present = "list")]
+        id: Option<String>,
+
+        /// List snapshots instead
+        #[arg(long, conflicts_with = "dry_run")]
+        list: bool,
+
+        /// Show what would be undone without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Run a built-in multi-stage flow
+    #[command(subcommand)]
+    Pipeline(PipelineCommands),
+
+    /// Manage sessions
+    #[command(subcommand)]
+    Session(SessionCommands),
+
+    /// Show status of detected CLIs and active sessions
+    Status,
+
+    /// Manage CLI plugins
+    ///
+    /// Plugins allow adding custom LLM CLIs without modifying GPT Engage source code.
+    /// Plugin files are TOML configurations stored in ~/.gptengage/plugins/
+    ///
+    /// Examples:
+    ///   # List installed plugins
+    ///   gptengage plugin list
+    ///
+    ///   # Validate a plugin file
+    ///   gptengage plugin validate ~/.gptengage/plugins/ollama.toml
+    #[command(subcommand, verbatim_doc_comment)]
+    Plugin(PluginCommands),
+
+    /// Manage debate templates
+    ///
     /// Templates provide pre-configured debate scenarios with defined participants,
     /// personas, and context settings for common use cases.
     ///
@@ -348,15 +1241,157 @@ pub enum Commands {
     ///   # Show template details
     ///   gptengage template show code-review
     ///
+    ///   # Check a template's prompts and participants before using it
+    ///   gptengage template test code-review --topic "Review the session store"
+    ///
     ///   # Use a template in a debate
     ///   gptengage debate "Review my auth code" --template code-review
     #[command(subcommand, verbatim_doc_comment)]
     Template(TemplateCommands),
 
+    /// Sync shared templates, personas, and plugins from a git repository
+    ///
+    /// The repository holds templates/*.toml, personas/*.toml, and
+    /// plugins/*.toml. They are copied into a namespace subdirectory of
+    /// ~/.gptengage/templates/, personas/, and plugins/, and load as
+    /// <namespace>/<template> and @<namespace>/<persona>. The namespace
+    /// defaults to the repository name. Without --remote, every synced
+    /// namespace is updated from the remote it was synced from.
+    ///
+    /// Examples:
+    ///   gptengage sync --remote git@github.com:org/gptengage-assets.git
+    ///   gptengage sync --remote https://github.com/org/assets.git --namespace org --branch stable
+    ///   gptengage sync
+    ///   gptengage sync status
+    #[command(verbatim_doc_comment, args_conflicts_with_subcommands = true)]
+    Sync {
+        #[command(subcommand)]
+        command: Option<SyncCommands>,
+
+        /// Git repository to sync from
+        #[arg(long)]
+        remote: Option<String>,
+
+        /// Namespace to sync into (default: the repository name)
+        #[arg(long)]
+        namespace: Option<String>,
+
+        /// Branch or tag to sync (default: the remote's default branch)
+        #[arg(long, requires = "remote")]
+        branch: Option<String>,
+
+        /// Overwrite local edits to synced files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Manage the persona library
+    ///
+    /// Library personas live in ~/.gptengage/personas/ and are referenced
+    /// as @name from templates, --participants, and generate-agents roles.
+    ///
+    /// Examples:
+    ///   gptengage persona list
+    ///   gptengage persona show security-reviewer
+    ///   gptengage persona import https://example.com/personas/sre.toml
+    ///   gptengage persona import agents.json
+    #[command(subcommand, verbatim_doc_comment)]
+    Persona(PersonaCommands),
+
+    /// Compose and maintain agent files
+    ///
+    /// Examples:
+    ///   # Build a roster from shared building blocks
+    ///   gptengage agents merge leads.json security.json -o combined.json
+    ///
+    ///   # Switch one participant's model, then check the file
+    ///   gptengage agents edit agents.json --set '1.model=gpt-4o'
+    ///   gptengage agents validate agents.json
+    #[command(subcommand, verbatim_doc_comment)]
+    Agents(AgentsCommands),
+
     /// Manage configuration
     #[command(subcommand)]
     Config(ConfigCommands),
 
+    /// Manage command aliases
+    ///
+    /// An alias names a gptengage command line. Running the alias runs the
+    /// command, with any extra arguments appended.
+    ///
+    /// Examples:
+    ///   gptengage alias set review-auth "invoke claude --context-file src/auth.rs"
+    ///   gptengage review-auth "Check the token refresh logic"
+    #[command(subcommand, verbatim_doc_comment)]
+    Alias(AliasCommands),
+
+    /// Print the JSON Schema for a machine-readable format
+    ///
+    /// Schemas are generated from the types gptengage reads and writes, so
+    /// external tools can validate agent files, plugins, and templates
+    /// before use, or debate transcripts and syntheses they consume.
+    ///
+    /// Examples:
+    ///   gptengage schema debate-result > debate-result.schema.json
+    ///   gptengage schema agent-file
+    ///   gptengage schema plugin
+    #[command(verbatim_doc_comment)]
+    Schema {
+        /// Format to describe
+        #[arg(value_enum)]
+        format: SchemaFormat,
+    },
+
+    /// Generate shell completions
+    ///
+    /// Bash, zsh, and fish completions also complete session, template,
+    /// plugin, and alias names and config keys from your current setup.
+    ///
+    /// Examples:
+    ///   gptengage completions bash > ~/.local/share/bash-completion/completions/gptengage
+    ///   gptengage completions zsh > "${fpath[1]}/_gptengage"
+    ///   gptengage completions fish > ~/.config/fish/completions/gptengage.fish
+    #[command(verbatim_doc_comment)]
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Print dynamic completion values (used by completion scripts)
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// Words on the command line before the one being completed
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+
+    /// Serve editor extensions JSON-RPC over stdio
+    ///
+    /// Speaks JSON-RPC 2.0 with LSP-style Content-Length framing on stdin
+    /// and stdout, for Neovim and VS Code extensions. Methods: initialize,
+    /// invoke, review, session/list, session/show, session/delete, and
+    /// shutdown/exit. Progress and each debate response are streamed as
+    /// gptengage/progress and gptengage/response notifications.
+    ///
+    /// Example (Neovim):
+    ///   vim.lsp.rpc.start({ "gptengage", "lsp-bridge" }, handlers)
+    #[command(verbatim_doc_comment)]
+    LspBridge {
+        /// Keep CLI circuit breakers open across requests
+        ///
+        /// By default every request starts with all CLIs callable again.
+        #[arg(long, verbatim_doc_comment)]
+        keep_circuits: bool,
+    },
+
+    /// Inspect the audit log of external process executions
+    ///
+    /// Enable auditing with: gptengage config set audit_log true
+    /// Entries are appended to ~/.gptengage/audit.jsonl. Prompts are
+    /// recorded only as SHA-256 hashes.
+    #[command(subcommand, verbatim_doc_comment)]
+    Audit(AuditCommands),
+
     /// Generate divergent ideas from a seed (evolutionary ideation)
     ///
     /// Uses sigma-based creativity levels to generate a tree of divergent ideas.
@@ -422,8 +1457,10 @@ pub enum Commands {
         output: String,
 
         /// Timeout per CLI invocation in seconds
-        #[arg(long, short = 't', default_value = "120", verbatim_doc_comment)]
-        timeout: u64,
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't', verbatim_doc_comment)]
+        timeout: Option<u64>,
 
         /// Color mode: auto | truecolor | 256 | none
         ///
@@ -496,12 +1533,14 @@ pub enum Commands {
         ///   --roles "CEO,CTO,CFO"
         ///   --roles "Senior Engineer,Product Manager,Designer"
         ///   --roles "Security Expert,Compliance Officer,Legal Counsel"
+        ///   --roles "@security-reviewer,CTO"   (@name copies a library persona)
         #[arg(long, verbatim_doc_comment)]
         roles: String,
 
         /// Output file path for generated agent definitions
         ///
-        /// File will contain validated JSON with schema version 1.0
+        /// File will contain validated definitions with schema version 1.0,
+        /// as JSON, or as TOML/YAML for a .toml/.yaml/.yml path
         /// Example: --output agents.json
         #[arg(long, short = 'o', verbatim_doc_comment)]
         output: String,
@@ -513,19 +1552,61 @@ pub enum Commands {
         #[arg(long, default_value = "claude", verbatim_doc_comment)]
         use_cli: String,
 
+        /// Model for the generating CLI
+        ///
+        /// Example: --model claude-opus-4-20250514
+        #[arg(long, short = 'm', verbatim_doc_comment)]
+        model: Option<String>,
+
         /// Timeout in seconds
         ///
         /// The CLI process is terminated if it exceeds this duration.
-        /// Default: 120 seconds (2 minutes)
-        #[arg(long, short = 't', default_value = "120", verbatim_doc_comment)]
-        timeout: u64,
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't', verbatim_doc_comment)]
+        timeout: Option<u64>,
+
+        /// Times to ask again when the definitions are unusable
+        ///
+        /// A response that isn't a JSON array of the right length, or whose
+        /// definitions fail validation, is sent back with the problem.
+        /// 0 fails on the first unusable response.
+        #[arg(long, default_value = "1", verbatim_doc_comment)]
+        retries: usize,
 
         /// Allow write access within the current directory (default: read-only)
         #[arg(long, verbatim_doc_comment)]
         write: bool,
+
+        /// Also save each generated persona to the persona library
+        ///
+        /// Personas are named after their role (e.g. "Principal Architect"
+        /// becomes @principal-architect). Existing personas are kept.
+        #[arg(long, verbatim_doc_comment)]
+        save_personas: bool,
     },
 }
 
+/// Machine-readable formats with a published JSON Schema
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SchemaFormat {
+    /// Debate transcript (`debate --output json`)
+    DebateResult,
+    /// Agent file (`--agent-file`, `generate-agents`)
+    AgentFile,
+    /// Plugin file (`~/.gptengage/plugins/*.toml`)
+    Plugin,
+    /// Template file (`~/.gptengage/templates/*.toml`)
+    Template,
+    /// Debate synthesis (`synthesis` in a debate transcript)
+    Synthesis,
+    /// Attributed utterances (`debate export --utterances`)
+    Utterances,
+    /// Cross-debate synthesis (`synthesize --output json`)
+    CrossSynthesis,
+    /// Decision log entry (a line of `decisions.jsonl`)
+    Decision,
+}
+
 /// How to interpret stdin input when piped
 #[derive(Clone, Copy, Debug, Default, ValueEnum)]
 pub enum StdinMode {
@@ -534,54 +1615,556 @@ pub enum StdinMode {
     Auto,
     /// Use stdin as additional context prepended to the topic/prompt
     Context,
+    /// Save stdin to a file in the current directory and reference it by
+    /// name in the prompt (for input too large to inline)
+    File,
     /// Ignore stdin input
     Ignore,
 }
 
 #[derive(Subcommand)]
-pub enum SessionCommands {
-    /// List all active sessions
-    List,
+pub enum DebateCommands {
+    /// Run one debate per topic in a topics file
+    ///
+    /// Debates run sequentially with the same participants and settings.
+    /// Each transcript is written to the output directory together with an
+    /// index.md linking every transcript to its outcome (the synthesis
+    /// recommendation with --synthesize).
+    ///
+    /// Topics file: one topic per line; blank lines and lines starting
+    /// with '#' are ignored.
+    ///
+    /// Examples:
+    ///   gptengage debate batch --topics-file topics.txt --template architecture-decision
+    ///   gptengage debate batch --topics-file topics.txt -p "claude:CTO,codex:SRE" --synthesize
+    #[command(verbatim_doc_comment)]
+    Batch {
+        /// File with one topic per line
+        #[arg(long, short = 'f', value_name = "FILE")]
+        topics_file: String,
 
-    /// Show session details and history
-    Show {
-        /// Session name
-        name: String,
+        /// Directory for transcripts and index.md
+        #[arg(long, short = 'd', value_name = "DIR", default_value = "debate-batch")]
+        output_dir: String,
+
+        #[command(flatten)]
+        team: DebateTeamArgs,
+
+        /// Transcript format: text, json, markdown, dot, mermaid
+        #[arg(long, short = 'o', default_value = "markdown")]
+        output: String,
     },
 
-    /// End a session
-    End {
-        /// Session name (or --all for all sessions)
-        name: Option<String>,
+    /// Package a debate transcript into a portable bundle
+    ///
+    /// The bundle (a .tar.zst archive) holds the JSON transcript, the agent
+    /// files and template used, a snapshot of the resolved config, and the
+    /// raw invocation logs the transcript references. The transcript file
+    /// identifies the debate; save one with `debate ... -o json`.
+    ///
+    /// With --utterances, prints who said what instead: a JSON array with
+    /// one entry per response, each with a stable ID and references to the
+    /// earlier responses it quotes.
+    ///
+    /// Examples:
+    ///   gptengage debate "Topic" -p claude,codex -o json > debate.json
+    ///   gptengage debate export debate.json --bundle debate.tar.zst --agent-file team.json
+    ///   gptengage debate export debate.json --utterances > utterances.json
+    #[command(verbatim_doc_comment)]
+    Export {
+        /// JSON debate transcript
+        transcript: String,
 
-        /// End all sessions
+        /// Bundle file to write
+        #[arg(long, value_name = "FILE", required_unless_present = "utterances")]
+        bundle: Option<String>,
+
+        /// Print the transcript's utterances as JSON instead of bundling it
+        #[arg(long, conflicts_with_all = ["bundle", "agent_file", "template"])]
+        utterances: bool,
+
+        /// Agent file the debate used (repeatable)
         #[arg(long)]
-        all: bool,
+        agent_file: Vec<String>,
+
+        /// Template the debate used
+        #[arg(long)]
+        template: Option<String>,
     },
-}
 
-/// Plugin management commands
-#[derive(Subcommand)]
-pub enum PluginCommands {
-    /// List all installed plugins
-    List,
+    /// Unpack a debate bundle for review or replay
+    ///
+    /// Extracts the bundle, prints a summary, and shows the command that
+    /// re-runs the debate with the bundled agent files.
+    ///
+    /// Examples:
+    ///   gptengage debate import debate.tar.zst
+    ///   gptengage debate import debate.tar.zst -d review/
+    #[command(verbatim_doc_comment)]
+    Import {
+        /// Bundle file to unpack
+        bundle: String,
 
-    /// Validate a plugin file without installing
-    Validate {
-        /// Path to the plugin TOML file
-        path: String,
+        /// Directory to unpack into (default: bundle name without extension)
+        #[arg(long, short = 'd', value_name = "DIR")]
+        dir: Option<String>,
+
+        /// Unpack into a directory that isn't empty
+        #[arg(long)]
+        force: bool,
     },
 }
 
-/// Template management commands
 #[derive(Subcommand)]
-pub enum TemplateCommands {
-    /// List all available templates
-    List,
-
-    /// Show template details
-    Show {
-        /// Template name
+pub enum PipelineCommands {
+    /// Review code, have a CLI fix the findings, and verify the fix
+    ///
+    /// 1. Review: a debate with the code-review template over the files
+    ///    under --paths, with a synthesis and action items.
+    /// 2. Fix: a write-mode invoke that edits the files to address the
+    ///    findings, saved as a patch. The working tree is put back
+    ///    afterwards unless --apply is given.
+    /// 3. Verify: a judge compares the patch with the findings and gives
+    ///    a verdict: pass, partial, or fail (which exits non-zero).
+    ///
+    /// Each stage's artifacts are written to --output-dir with an
+    /// index.md linking them. Must run inside a git repository.
+    ///
+    /// Examples:
+    ///   gptengage pipeline review-fix-verify --paths src/
+    ///   gptengage pipeline review-fix-verify --paths src/auth.rs --fixer codex --judge claude
+    #[command(verbatim_doc_comment)]
+    ReviewFixVerify {
+        /// Files and directories to review (repeatable)
+        #[arg(long, value_name = "PATH", required = true, num_args = 1..)]
+        paths: Vec<String>,
+
+        /// Debate template for the review stage
+        #[arg(long, default_value = "code-review")]
+        template: String,
+
+        /// Review rounds (default: the template's)
+        #[arg(long, short = 'r')]
+        rounds: Option<usize>,
+
+        /// CLI that writes the review's synthesis and action items
+        #[arg(long, value_name = "CLI", default_value = "claude")]
+        synthesizer: String,
+
+        /// CLI that writes the fix, with write access to the current directory
+        #[arg(long, value_name = "CLI", default_value = "claude")]
+        fixer: String,
+
+        /// Model for the fixer
+        #[arg(long, short = 'm')]
+        model: Option<String>,
+
+        /// CLI that verifies the fix against the findings
+        #[arg(long, value_name = "CLI", default_value = "claude")]
+        judge: String,
+
+        /// Directory for each stage's artifacts and index.md
+        #[arg(
+            long,
+            short = 'd',
+            value_name = "DIR",
+            default_value = "review-fix-verify"
+        )]
+        output_dir: String,
+
+        /// Leave the fix in the working tree (undo with `gptengage rollback`)
+        #[arg(long)]
+        apply: bool,
+
+        /// Timeout per CLI invocation in seconds (default: default_timeout from config)
+        #[arg(long, short = 't')]
+        timeout: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SessionCommands {
+    /// List all active sessions
+    List,
+
+    /// Show session details and history
+    ///
+    /// Examples:
+    ///   gptengage session show auth-review --last 4
+    ///   gptengage session show auth-review --turns 3..7
+    ///   gptengage session show auth-review --format markdown > review.md
+    #[command(verbatim_doc_comment)]
+    Show {
+        /// Session name
+        name: String,
+
+        /// Output format: text | markdown | json
+        #[arg(long, short = 'f', default_value = "text")]
+        format: String,
+
+        /// Show only the last N turns
+        #[arg(long, conflicts_with = "turns")]
+        last: Option<usize>,
+
+        /// Show a range of turns (1-based, inclusive): 3..7, 3.., ..7, or 5
+        #[arg(long)]
+        turns: Option<String>,
+    },
+
+    /// Choose a session with an interactive fuzzy finder
+    ///
+    /// Prints the chosen session name, so it can be used in scripts:
+    ///   gptengage invoke claude "Next step?" --session "$(gptengage session pick)"
+    #[command(verbatim_doc_comment)]
+    Pick,
+
+    /// Regenerate the prompt sent for each turn of a session
+    ///
+    /// Prompts are rebuilt with the current history scaffolding, so they
+    /// show what would be sent today for the same conversation.
+    ///
+    /// Examples:
+    ///   gptengage session replay auth-review
+    ///   gptengage session replay auth-review --to-file prompts/
+    #[command(verbatim_doc_comment)]
+    Replay {
+        /// Session name
+        name: String,
+
+        /// Write each prompt to DIR/turn-NNN.txt instead of printing
+        #[arg(long, value_name = "DIR")]
+        to_file: Option<String>,
+    },
+
+    /// Ask a question about a session's conversation
+    ///
+    /// The session history is sent to a CLI with the question, e.g. to
+    /// recall a decision. The exchange is not added to the session unless
+    /// --record is given.
+    ///
+    /// Examples:
+    ///   gptengage session ask auth-review "What did we decide about caching?"
+    ///   gptengage session ask auth-review "List open TODOs" --cli gemini --history-last 20
+    #[command(verbatim_doc_comment)]
+    Ask {
+        /// Session name
+        name: String,
+
+        /// The question to ask
+        question: String,
+
+        /// CLI that answers (default: the session's CLI)
+        #[arg(long)]
+        cli: Option<String>,
+
+        /// Model to use for the CLI
+        #[arg(long, short = 'm')]
+        model: Option<String>,
+
+        /// Include only the last N turns of history
+        #[arg(long, value_name = "N")]
+        history_last: Option<usize>,
+
+        /// Append the question and answer to the session
+        #[arg(long)]
+        record: bool,
+
+        /// Timeout in seconds
+        ///
+        /// Default: default_timeout from config (120 seconds)
+        #[arg(long, short = 't')]
+        timeout: Option<u64>,
+    },
+
+    /// End a session
+    End {
+        /// Session name (or --all for all sessions)
+        name: Option<String>,
+
+        /// End all sessions
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Restore a deleted session from the trash
+    ///
+    /// Deleted sessions are kept for trash_retention_days (default 30).
+    /// Without a name, lists the sessions in the trash.
+    #[command(verbatim_doc_comment)]
+    Undelete {
+        /// Session name
+        name: Option<String>,
+    },
+}
+
+/// Plugin management commands
+#[derive(Subcommand)]
+pub enum PluginCommands {
+    /// List all installed plugins
+    List,
+
+    /// Validate a plugin file without installing
+    Validate {
+        /// Path to the plugin TOML file
+        path: String,
+    },
+
+    /// Install a plugin file and pin its checksum
+    ///
+    /// Copies the file to ~/.gptengage/plugins/<name>.toml and records its
+    /// SHA-256. If the installed file is later modified, the plugin is
+    /// refused until it is reinstalled.
+    ///
+    /// Examples:
+    ///   gptengage plugin install ./ollama.toml
+    ///   gptengage plugin install ./ollama.toml --sha256 <published-checksum>
+    #[command(verbatim_doc_comment)]
+    Install {
+        /// Path to the plugin TOML file
+        path: String,
+
+        /// Expected SHA-256 of the file, as published by the plugin author
+        #[arg(long)]
+        sha256: Option<String>,
+
+        /// Minisign signature of the file, checked against plugins.signing_keys
+        #[arg(long, value_name = "FILE")]
+        signature: Option<String>,
+
+        /// Replace an existing plugin with the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Delete a plugin file from ~/.gptengage/plugins/
+    Remove {
+        /// Plugin name
+        name: String,
+    },
+
+    /// Enable a disabled plugin
+    Enable {
+        /// Plugin name
+        name: String,
+    },
+
+    /// Disable a plugin without deleting it
+    Disable {
+        /// Plugin name
+        name: String,
+    },
+}
+
+/// Sync commands
+#[derive(Subcommand)]
+pub enum SyncCommands {
+    /// Show synced namespaces, local edits, and whether the remote moved
+    Status {
+        /// Namespace to check (default: all)
+        namespace: Option<String>,
+    },
+}
+
+/// Template management commands
+#[derive(Subcommand)]
+pub enum TemplateCommands {
+    /// List all available templates
+    List,
+
+    /// Show template details
+    Show {
+        /// Template name
+        name: String,
+    },
+
+    /// Check a template without running a debate
+    ///
+    /// Renders the template against a sample topic, prints each
+    /// participant's exact round-1 prompt, and verifies that every CLI,
+    /// library persona, and model resolves. Exits non-zero if any
+    /// participant would fail.
+    #[command(verbatim_doc_comment)]
+    Test {
+        /// Template name
+        name: String,
+
+        /// Topic to render instead of the built-in sample
+        #[arg(long)]
+        topic: Option<String>,
+    },
+}
+
+/// Persona library commands
+#[derive(Subcommand)]
+pub enum PersonaCommands {
+    /// List library personas
+    List,
+
+    /// Show a persona's details
+    Show {
+        /// Persona name (with or without the leading @)
+        name: String,
+    },
+
+    /// Import personas from a file or an http(s) URL
+    ///
+    /// Accepts a persona TOML file (named after the file unless --name is
+    /// given) or an agent file (JSON), which adds one persona per
+    /// participant, named after its role.
+    #[command(verbatim_doc_comment)]
+    Import {
+        /// Path or http(s) URL
+        source: String,
+
+        /// Name to import a single persona as
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Replace existing personas with the same name
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Agent file commands
+#[derive(Subcommand)]
+pub enum AgentsCommands {
+    /// Merge agent files into one roster
+    ///
+    /// Participants keep their order (file by file). Personas must be
+    /// unique across the files (case-insensitive). Prints the merged JSON
+    /// unless --output is given.
+    #[command(verbatim_doc_comment)]
+    Merge {
+        /// Agent files to merge
+        #[arg(required = true, num_args = 2..)]
+        files: Vec<String>,
+
+        /// Write the merged agent file here
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+    },
+
+    /// Edit fields of an agent file
+    ///
+    /// Each --set is <participant>.<field>=<value>, with participants
+    /// numbered from 1 (or * for all). Fields: cli, model, persona,
+    /// instructions, expertise (comma-separated), communication_style.
+    /// An empty value clears model, expertise, or communication_style.
+    /// The edited file is validated before it is written.
+    ///
+    /// Examples:
+    ///   gptengage agents edit agents.json --set '1.model=gpt-4o'
+    ///   gptengage agents edit agents.json --set '*.cli=codex' --set '2.model=' -o codex.json
+    #[command(verbatim_doc_comment)]
+    Edit {
+        /// Agent file to edit
+        file: String,
+
+        /// Edit to apply (repeatable)
+        #[arg(long = "set", value_name = "EDIT", required = true)]
+        edits: Vec<String>,
+
+        /// Write the result here instead of editing the file in place
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+    },
+
+    /// Check an agent file without running a debate
+    ///
+    /// Runs the same validation as debate --agent-file, and also reports
+    /// duplicate personas and CLIs that are not installed.
+    #[command(verbatim_doc_comment)]
+    Validate {
+        /// Agent file to check
+        file: String,
+    },
+}
+
+/// Audit log commands
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// Show recorded process executions (most recent last)
+    Show {
+        /// Number of most recent entries to show
+        #[arg(long, short = 'n', default_value = "20")]
+        limit: usize,
+
+        /// Print entries as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Decision log commands
+#[derive(Subcommand)]
+pub enum DecisionsCommands {
+    /// Record a debate's outcome as a decision
+    ///
+    /// The decision is the synthesis recommendation, the rationale its
+    /// summary, and the dissent its disagreement points.
+    Add {
+        /// JSON transcript of the debate (`debate --synthesize -o json`)
+        #[arg(long, value_name = "TRANSCRIPT")]
+        from_debate: String,
+
+        /// Decision text, instead of the synthesis recommendation
+        #[arg(long)]
+        decision: Option<String>,
+
+        /// Rationale, instead of the synthesis summary
+        #[arg(long)]
+        rationale: Option<String>,
+
+        /// Decision log file
+        #[arg(long, default_value = "decisions.jsonl")]
+        log: String,
+    },
+
+    /// List recorded decisions
+    List {
+        /// Print decisions as JSON lines
+        #[arg(long)]
+        json: bool,
+
+        /// Decision log file
+        #[arg(long, default_value = "decisions.jsonl")]
+        log: String,
+    },
+
+    /// Show one decision
+    Show {
+        /// Decision number
+        id: usize,
+
+        /// Print the decision as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Decision log file
+        #[arg(long, default_value = "decisions.jsonl")]
+        log: String,
+    },
+}
+
+/// Alias management commands
+#[derive(Subcommand)]
+pub enum AliasCommands {
+    /// Define or replace an alias
+    Set {
+        /// Alias name
+        name: String,
+
+        /// Command line the alias expands to (without "gptengage")
+        expansion: String,
+    },
+
+    /// List all aliases
+    List,
+
+    /// Remove an alias
+    Remove {
+        /// Alias name
         name: String,
     },
 }
@@ -603,48 +2186,330 @@ pub enum ConfigCommands {
         value: String,
     },
 
-    /// List all configuration
+    /// Reset a config value to its default
+    Unset {
+        /// Config key
+        key: String,
+    },
+
+    /// List every config key with its effective value and source
+    ///
+    /// Values are resolved from (lowest to highest precedence): built-in
+    /// defaults, ~/.gptengage/config.json, the profile selected by
+    /// GPTENGAGE_PROFILE, .gptengage.json in the current directory or an
+    /// ancestor, and GPTENGAGE_<KEY> environment variables.
+    #[command(verbatim_doc_comment)]
     List,
+
+    /// Open the config file in $EDITOR and validate it on save
+    Edit,
+
+    /// Check the config file, active profile, and project config for errors
+    Validate,
 }
 
 impl Cli {
+    /// Run the command with the process-wide config
     pub async fn execute(self) -> anyhow::Result<()> {
+        self.run(&mut crate::GptEngage::new()).await
+    }
+
+    /// Run the command, reading settings from `engage`'s config
+    pub async fn run(self, engage: &mut crate::GptEngage) -> anyhow::Result<()> {
         use crate::commands::*;
+        use crate::invokers::policy::{default_network_sandbox, set_policy, ExecutionPolicy};
         use crate::invokers::AccessMode;
+        use crate::orchestrator::best_of::BestOfOptions;
+        use crate::orchestrator::narration::Verbosity;
+        use crate::orchestrator::response_format::ResponseFormat;
+        use crate::orchestrator::PromptContext;
+        use crate::utils::extract::Extractor;
+        use crate::utils::interaction::{set_interaction, Interaction};
+        use crate::utils::prompt_preview::ShowPrompt;
+        use crate::utils::run_snapshot::set_snapshot_runs;
+        use crate::utils::time::{set_time_display, TimeDisplay, TimeFormat};
+
+        let config = match engage.config() {
+            Ok(resolved) => resolved.config.clone(),
+            // Keep `config edit`/`config validate` usable to repair a broken config
+            Err(_) if matches!(self.command, Commands::Config(_)) => Default::default(),
+            Err(e) => return Err(e),
+        };
+        // --timeout and --rounds fall back to these when not given
+        let default_timeout = config.default_timeout;
+        let default_rounds = config.default_debate_rounds;
+        let mut policy = ExecutionPolicy {
+            max_concurrency: self.max_concurrency,
+            max_per_cli: self.max_per_cli,
+            isolate: self.isolate,
+            audit: config.audit_log,
+            hash_only: config.history.is_hash_only(),
+            trust_plugins: self.trust,
+            max_output_bytes: Some(config.max_output_bytes).filter(|&cap| cap > 0),
+            // Raw output logs can't be hashed, so hash-only writes none
+            invocation_logs: Some(config.invocation_logs)
+                .filter(|&keep| keep > 0 && !config.history.is_hash_only()),
+            max_continuations: config.max_continuations,
+            budget: config.budget.clone(),
+            force_budget: self.force_budget,
+            workers: self.workers,
+            circuit_breaker: Some(config.circuit_breaker_threshold).filter(|&limit| limit > 0),
+            fallbacks: config.fallbacks.clone(),
+            ..Default::default()
+        };
+        if self.isolate {
+            policy.isolate_credentials = config.isolate_credentials;
+        }
+        if self.no_network {
+            policy.network_sandbox = Some(match config.network_sandbox {
+                Some(wrapper) => wrapper,
+                None => default_network_sandbox()?,
+            });
+            // Only the user's own allowlist counts; a plugin file can't
+            // exempt itself
+            if let Ok(plugins) = engage.plugins() {
+                policy.network_exempt = config
+                    .plugins
+                    .network_exempt
+                    .iter()
+                    .filter_map(|name| plugins.get_plugin(name))
+                    // Shell plugins spawn sh, which is never exempt
+                    .filter(|p| !p.plugin.shell)
+                    .map(|p| p.plugin.command.clone())
+                    .collect();
+            }
+        }
+        set_policy(policy);
+
+        let time_format = match TimeFormat::parse(&config.time_format) {
+            Ok(format) => format,
+            Err(_) if matches!(self.command, Commands::Config(_)) => TimeFormat::Relative,
+            Err(e) => return Err(e),
+        };
+        set_time_display(TimeDisplay {
+            format: time_format,
+            absolute: self.absolute_times,
+        });
+        set_snapshot_runs(self.snapshot);
+        set_interaction(Interaction::from_flags(self.yes, self.non_interactive));
 
         match self.command {
             Commands::Debate {
+                command:
+                    Some(DebateCommands::Batch {
+                        topics_file,
+                        output_dir,
+                        team:
+                            DebateTeamArgs {
+                                agent,
+                                instances,
+                                model,
+                                participants,
+                                agent_file,
+                                template,
+                                rounds,
+                                timeout,
+                                write,
+                                synthesize,
+                                synthesizer,
+                                collapse,
+                            },
+                        output,
+                    }),
+                ..
+            } => {
+                debate::run_debate_batch(
+                    engage,
+                    debate::BatchOptions {
+                        topics_file,
+                        output_dir,
+                        debate: debate::DebateOptions {
+                            topic: String::new(),
+                            agent,
+                            instances,
+                            model,
+                            participants,
+                            agent_file,
+                            auto_agents: None,
+                            auto_agents_cli: "claude".to_string(),
+                            save_agents: None,
+                            template,
+                            rounds,
+                            default_rounds,
+                            output,
+                            timeout: timeout.unwrap_or(default_timeout),
+                            round_timeout: None,
+                            hedge_after: None,
+                            bench_after: None,
+                            reprobe: false,
+                            access_mode: AccessMode::from_write_flag(write),
+                            stdin_as: StdinMode::Ignore,
+                            context_providers: vec![],
+                            synthesize,
+                            synthesizer,
+                            incremental_synthesis: false,
+                            fact_check: None,
+                            track_stances: false,
+                            stance_cli: "claude".to_string(),
+                            risk_register: false,
+                            risk_cli: "claude".to_string(),
+                            action_items: false,
+                            action_items_cli: "claude".to_string(),
+                            create_issues: None,
+                            post_to_mr: None,
+                            observers: vec![],
+                            collapse,
+                            show_prompt: ShowPrompt::Off,
+                            dump_prompts: None,
+                            context: PromptContext::default(),
+                            round_robin: false,
+                            ordered: true,
+                            sidecar: false,
+                            response_format: None,
+                            preset: None,
+                            verbosity: Verbosity::default(),
+                        },
+                    },
+                )
+                .await
+            }
+
+            Commands::Debate {
+                command:
+                    Some(DebateCommands::Export {
+                        transcript,
+                        bundle: Some(bundle),
+                        agent_file,
+                        template,
+                        ..
+                    }),
+                ..
+            } => bundle::export_debate(engage, transcript, bundle, agent_file, template).await,
+
+            Commands::Debate {
+                command: Some(DebateCommands::Export { transcript, .. }),
+                ..
+            } => attribution::export_utterances(transcript).await,
+
+            Commands::Debate {
+                command: Some(DebateCommands::Import { bundle, dir, force }),
+                ..
+            } => bundle::import_debate(bundle, dir, force).await,
+
+            Commands::Debate {
+                command: None,
                 topic,
-                agent,
-                instances,
-                model,
-                participants,
-                agent_file,
-                template,
-                rounds,
+                team:
+                    DebateTeamArgs {
+                        agent,
+                        instances,
+                        model,
+                        participants,
+                        agent_file,
+                        template,
+                        rounds,
+                        timeout,
+                        write,
+                        synthesize,
+                        synthesizer,
+                        collapse,
+                    },
+                auto_agents,
+                auto_agents_cli,
+                save_agents,
                 output,
-                timeout,
-                write,
+                round_timeout,
+                hedge_after,
+                bench_after,
+                reprobe,
                 stdin_as,
-                synthesize,
-                synthesizer,
+                context_provider,
+                incremental_synthesis,
+                fact_check,
+                track_stances,
+                stance_cli,
+                risk_register,
+                risk_cli,
+                action_items,
+                action_items_cli,
+                create_issues,
+                post_to_mr,
+                observer,
+                show_prompt,
+                show_prompt_only,
+                dump_prompts,
+                no_history,
+                history_last,
+                no_persona_context,
+                converge_after,
+                round_robin,
+                ordered,
+                sidecar,
+                response_format,
+                preset,
+                verbose,
             } => {
-                debate::run_debate(debate::DebateOptions {
-                    topic,
-                    agent,
-                    instances,
-                    model,
-                    participants,
-                    agent_file,
-                    template,
-                    rounds,
-                    output,
-                    timeout,
-                    access_mode: AccessMode::from_write_flag(write),
-                    stdin_as,
-                    synthesize,
-                    synthesizer,
-                })
+                let context = PromptContext {
+                    history_rounds: if no_history {
+                        0
+                    } else {
+                        history_last.unwrap_or(PromptContext::default().history_rounds)
+                    },
+                    persona_context: !no_persona_context,
+                    converge_after: converge_after.map(|n| n as usize),
+                };
+                debate::run_debate(
+                    engage,
+                    debate::DebateOptions {
+                        topic,
+                        agent,
+                        instances,
+                        model,
+                        participants,
+                        agent_file,
+                        auto_agents,
+                        auto_agents_cli,
+                        save_agents,
+                        template,
+                        rounds,
+                        default_rounds,
+                        output,
+                        timeout: timeout.unwrap_or(default_timeout),
+                        round_timeout,
+                        hedge_after,
+                        bench_after: bench_after.map(|n| n as usize),
+                        reprobe,
+                        access_mode: AccessMode::from_write_flag(write),
+                        stdin_as,
+                        context_providers: context_provider,
+                        synthesize,
+                        synthesizer,
+                        incremental_synthesis,
+                        fact_check,
+                        track_stances,
+                        stance_cli,
+                        risk_register,
+                        risk_cli,
+                        action_items,
+                        action_items_cli,
+                        create_issues,
+                        post_to_mr,
+                        observers: observer,
+                        collapse,
+                        show_prompt: ShowPrompt::from_flags(show_prompt, show_prompt_only),
+                        dump_prompts,
+                        context,
+                        round_robin,
+                        ordered,
+                        sidecar,
+                        response_format: response_format
+                            .as_deref()
+                            .map(ResponseFormat::parse)
+                            .transpose()?,
+                        preset,
+                        verbosity: Verbosity::from_count(verbose),
+                    },
+                )
                 .await
             }
 
@@ -653,68 +2518,400 @@ impl Cli {
                 prompt,
                 model,
                 session,
+                pick_session,
                 topic,
                 context_file,
+                context_provider,
                 timeout,
                 write,
                 stdin_as,
+                show_prompt,
+                show_prompt_only,
+                no_history,
+                history_last,
+                native_session,
+                best_of,
+                judge,
+                show_candidates,
+                extract,
+                extract_regex,
+                response_format,
             } => {
+                let extract = match (extract, extract_regex) {
+                    (Some(choices), _) => Some(Extractor::choices(&choices)?),
+                    (None, Some(pattern)) => Some(Extractor::pattern(&pattern)?),
+                    (None, None) => None,
+                };
+                let best_of = best_of.map(|count| BestOfOptions {
+                    candidates: count as usize,
+                    judge: judge.unwrap_or_else(|| cli.clone()),
+                    show_candidates,
+                });
+                let session = if pick_session {
+                    let name = session::pick_session(engage)
+                        .await?
+                        .ok_or_else(|| anyhow::anyhow!("No session selected"))?;
+                    eprintln!("Using session: {}", name);
+                    Some(name)
+                } else {
+                    session
+                };
                 invoke::run_invoke(
+                    engage,
                     cli,
                     model,
                     prompt,
                     session,
                     topic,
                     context_file,
-                    timeout,
+                    context_provider,
+                    timeout.unwrap_or(default_timeout),
                     AccessMode::from_write_flag(write),
                     stdin_as,
+                    ShowPrompt::from_flags(show_prompt, show_prompt_only),
+                    if no_history { Some(0) } else { history_last },
+                    native_session,
+                    best_of,
+                    extract,
+                    response_format
+                        .as_deref()
+                        .map(ResponseFormat::parse)
+                        .transpose()?,
                 )
                 .await
             }
 
+            Commands::Fanout {
+                jobs,
+                output,
+                concurrency,
+                timeout,
+                write,
+            } => {
+                fanout::run_fanout(
+                    engage,
+                    jobs,
+                    output,
+                    concurrency,
+                    timeout.unwrap_or(default_timeout),
+                    AccessMode::from_write_flag(write),
+                )
+                .await
+            }
             Commands::Session(session_cmd) => match session_cmd {
-                SessionCommands::List => session::list_sessions().await,
-                SessionCommands::Show { name } => session::show_session(name).await,
-                SessionCommands::End { name, all } => session::end_session(name, all).await,
+                SessionCommands::List => session::list_sessions(engage).await,
+                SessionCommands::Show {
+                    name,
+                    format,
+                    last,
+                    turns,
+                } => {
+                    session::show_session(
+                        engage,
+                        name,
+                        format,
+                        session::TurnSelection { last, turns },
+                    )
+                    .await
+                }
+                SessionCommands::Pick => session::pick_session_command(engage).await,
+                SessionCommands::Replay { name, to_file } => {
+                    session::replay_session(engage, name, to_file).await
+                }
+                SessionCommands::Ask {
+                    name,
+                    question,
+                    cli,
+                    model,
+                    history_last,
+                    record,
+                    timeout,
+                } => {
+                    session::ask_session(
+                        engage,
+                        name,
+                        question,
+                        session::AskOptions {
+                            cli,
+                            model,
+                            history_last,
+                            record,
+                            timeout: timeout.unwrap_or(default_timeout),
+                        },
+                    )
+                    .await
+                }
+                SessionCommands::End { name, all } => session::end_session(engage, name, all).await,
+                SessionCommands::Undelete { name } => session::undelete_session(engage, name).await,
+            },
+
+            Commands::Explain {
+                command,
+                cli,
+                model,
+                command_timeout,
+                timeout,
+            } => {
+                explain::run_explain(
+                    engage,
+                    command,
+                    explain::ExplainOptions {
+                        cli,
+                        model,
+                        command_timeout,
+                        timeout: timeout.unwrap_or(default_timeout),
+                    },
+                )
+                .await
+            }
+
+            Commands::Search {
+                query,
+                paths,
+                no_sessions,
+                limit,
+                json,
+                rank_with,
+                timeout,
+            } => {
+                search::run_search(
+                    engage,
+                    search::SearchOptions {
+                        query,
+                        paths,
+                        include_sessions: !no_sessions,
+                        limit,
+                        json,
+                        rank_with,
+                        timeout: timeout.unwrap_or(default_timeout),
+                    },
+                )
+                .await
+            }
+
+            Commands::Synthesize {
+                debates,
+                from,
+                synthesizer,
+                output,
+                timeout,
+            } => {
+                synthesize::run_synthesize(
+                    engage,
+                    synthesize::SynthesizeOptions {
+                        debates,
+                        from,
+                        synthesizer,
+                        timeout: timeout.unwrap_or(default_timeout),
+                        output,
+                    },
+                )
+                .await
+            }
+
+            Commands::GenTests {
+                file,
+                cli,
+                model,
+                test_command,
+                max_repairs,
+                timeout,
+                test_timeout,
+            } => {
+                gen_tests::run_gen_tests(
+                    engage,
+                    gen_tests::GenTestsOptions {
+                        file,
+                        cli,
+                        model,
+                        test_command,
+                        max_repairs,
+                        timeout: timeout.unwrap_or(default_timeout),
+                        test_timeout,
+                    },
+                )
+                .await
+            }
+
+            Commands::ReleaseNotes {
+                from,
+                to,
+                cli,
+                model,
+                compare,
+                judge,
+                timeout,
+            } => {
+                release_notes::run_release_notes(
+                    engage,
+                    release_notes::ReleaseNotesOptions {
+                        from,
+                        to,
+                        cli,
+                        model,
+                        compare,
+                        judge,
+                        timeout: timeout.unwrap_or(default_timeout),
+                    },
+                )
+                .await
+            }
+
+            Commands::Report { since, output } => report::run_report(engage, since, output).await,
+
+            Commands::Rollback { id, list, dry_run } => match id {
+                Some(id) if !list => rollback::run_rollback(id, dry_run),
+                _ => rollback::list_snapshots(),
             },
 
-            Commands::Status => status::show_status().await,
+            Commands::Pipeline(PipelineCommands::ReviewFixVerify {
+                paths,
+                template,
+                rounds,
+                synthesizer,
+                fixer,
+                model,
+                judge,
+                output_dir,
+                apply,
+                timeout,
+            }) => {
+                pipeline::run_review_fix_verify(
+                    engage,
+                    pipeline::ReviewFixVerifyOptions {
+                        paths,
+                        template,
+                        rounds,
+                        default_rounds,
+                        synthesizer,
+                        fixer,
+                        model,
+                        judge,
+                        output_dir,
+                        apply,
+                        timeout: timeout.unwrap_or(default_timeout),
+                    },
+                )
+                .await
+            }
+
+            Commands::Status => status::show_status(engage).await,
 
             Commands::Plugin(plugin_cmd) => match plugin_cmd {
-                PluginCommands::List => plugin::list_plugins().await,
+                PluginCommands::List => plugin::list_plugins(engage).await,
                 PluginCommands::Validate { path } => plugin::validate_plugin(path).await,
+                PluginCommands::Install {
+                    path,
+                    sha256,
+                    signature,
+                    force,
+                } => plugin::install_plugin(path, sha256, signature, force).await,
+                PluginCommands::Remove { name } => plugin::remove_plugin(engage, name).await,
+                PluginCommands::Enable { name } => {
+                    plugin::set_plugin_enabled(engage, name, true).await
+                }
+                PluginCommands::Disable { name } => {
+                    plugin::set_plugin_enabled(engage, name, false).await
+                }
             },
 
+            Commands::Sync {
+                command: Some(SyncCommands::Status { namespace }),
+                ..
+            } => sync::show_sync_status(namespace).await,
+
+            Commands::Sync {
+                command: None,
+                remote,
+                namespace,
+                branch,
+                force,
+            } => sync::run_sync(remote, namespace, branch, force).await,
+
             Commands::Template(template_cmd) => match template_cmd {
-                TemplateCommands::List => template::list_templates().await,
-                TemplateCommands::Show { name } => template::show_template(name).await,
+                TemplateCommands::List => template::list_templates(engage).await,
+                TemplateCommands::Show { name } => template::show_template(engage, name).await,
+                TemplateCommands::Test { name, topic } => {
+                    template::test_template(engage, name, topic).await
+                }
             },
 
-            Commands::Config(config_cmd) => match config_cmd {
-                ConfigCommands::Get { key } => {
-                    let config = crate::config::ConfigManager::new()?;
-                    match config.get(&key) {
-                        Some(value) => println!("{}: {}", key, value),
-                        None => println!("Config key '{}' not found", key),
-                    }
-                    Ok(())
+            Commands::Persona(persona_cmd) => match persona_cmd {
+                PersonaCommands::List => persona::list_personas().await,
+                PersonaCommands::Show { name } => persona::show_persona(name).await,
+                PersonaCommands::Import {
+                    source,
+                    name,
+                    force,
+                } => persona::import_personas(source, name, force).await,
+            },
+
+            Commands::Agents(agents_cmd) => match agents_cmd {
+                AgentsCommands::Merge { files, output } => {
+                    agents::merge_agent_files(files, output).await
                 }
-                ConfigCommands::Set { key, value } => {
-                    let mut config = crate::config::ConfigManager::new()?;
-                    config.set(&key, &value)?;
-                    println!("Set {} = {}", key, value);
-                    Ok(())
+                AgentsCommands::Edit {
+                    file,
+                    edits,
+                    output,
+                } => agents::edit_agent_file(file, edits, output).await,
+                AgentsCommands::Validate { file } => {
+                    agents::validate_agent_file(engage, file).await
                 }
-                ConfigCommands::List => {
-                    let config = crate::config::ConfigManager::new()?;
-                    println!("Configuration:");
-                    println!("  default_timeout: {}", config.default_timeout);
-                    println!("  default_debate_rounds: {}", config.default_debate_rounds);
-                    Ok(())
+            },
+
+            Commands::Config(config_cmd) => match config_cmd {
+                ConfigCommands::Get { key } => config::get_config(key).await,
+                ConfigCommands::Set { key, value } => config::set_config(key, value).await,
+                ConfigCommands::Unset { key } => config::unset_config(key).await,
+                ConfigCommands::List => config::list_config().await,
+                ConfigCommands::Edit => config::edit_config().await,
+                ConfigCommands::Validate => config::validate_config().await,
+            },
+
+            Commands::Decisions(decisions_cmd) => match decisions_cmd {
+                DecisionsCommands::Add {
+                    from_debate,
+                    decision,
+                    rationale,
+                    log,
+                } => {
+                    decisions::add_decision(
+                        log,
+                        decisions::AddOptions {
+                            from_debate,
+                            decision,
+                            rationale,
+                        },
+                    )
+                    .await
+                }
+                DecisionsCommands::List { json, log } => decisions::list_decisions(log, json).await,
+                DecisionsCommands::Show { id, json, log } => {
+                    decisions::show_decision(log, id, json).await
                 }
             },
 
+            Commands::Alias(alias_cmd) => match alias_cmd {
+                AliasCommands::Set { name, expansion } => alias::set_alias(name, expansion).await,
+                AliasCommands::List => alias::list_aliases().await,
+                AliasCommands::Remove { name } => alias::remove_alias(name).await,
+            },
+
+            Commands::Schema { format } => schema::print_schema(format).await,
+
+            Commands::Completions { shell } => completions::print_completions(shell).await,
+
+            Commands::Complete { words } => completions::complete_values(engage, words).await,
+
+            Commands::LspBridge { keep_circuits } => {
+                lsp_bridge::run_lsp_bridge(engage, keep_circuits).await
+            }
+
+            Commands::Audit(audit_cmd) => match audit_cmd {
+                AuditCommands::Show { limit, json } => audit::show_audit(limit, json).await,
+            },
+
             Commands::Ideate {
                 seed,
                 sigma,
@@ -727,19 +2924,22 @@ impl Cli {
                 pager,
                 force,
             } => {
-                ideate::run_ideate(ideate::IdeateOptions {
-                    seed,
-                    sigma,
-                    select,
-                    depth,
-                    cli,
-                    output,
-                    timeout,
-                    access_mode: AccessMode::ReadOnly,
-                    color,
-                    pager,
-                    force,
-                })
+                ideate::run_ideate(
+                    engage,
+                    ideate::IdeateOptions {
+                        seed,
+                        sigma,
+                        select,
+                        depth,
+                        cli,
+                        output,
+                        timeout: timeout.unwrap_or(default_timeout),
+                        access_mode: AccessMode::ReadOnly,
+                        color,
+                        pager,
+                        force,
+                    },
+                )
                 .await
             }
 
@@ -748,16 +2948,25 @@ impl Cli {
                 roles,
                 output,
                 use_cli,
+                model,
                 timeout,
+                retries,
                 write,
+                save_personas,
             } => {
                 generate_agents::run_generate_agents(
+                    engage,
                     topic,
                     roles,
                     output,
-                    use_cli,
-                    timeout,
-                    AccessMode::from_write_flag(write),
+                    generate_agents::GenerationOptions {
+                        cli: use_cli,
+                        model,
+                        timeout: timeout.unwrap_or(default_timeout),
+                        access_mode: AccessMode::from_write_flag(write),
+                        retries,
+                    },
+                    save_personas,
                 )
                 .await
             }
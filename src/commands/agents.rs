@@ -0,0 +1,72 @@
+//! Agents command - Compose and maintain agent files
+
+use crate::app::GptEngage;
+use crate::orchestrator::AgentFile;
+
+/// Merge agent files into one, rejecting duplicate personas. Writes to
+/// `output`, or prints the merged JSON when no output is given.
+pub async fn merge_agent_files(files: Vec<String>, output: Option<String>) -> anyhow::Result<()> {
+    let merged = AgentFile::load_all(&files)?;
+
+    match output {
+        Some(path) => {
+            merged.save(&path)?;
+            println!(
+                "✓ Merged {} agent(s) from {} file(s) into {}",
+                merged.participants.len(),
+                files.len(),
+                path
+            );
+            for agent in &merged.participants {
+                println!("  - {} ({})", agent.cli, agent.persona);
+            }
+        }
+        None => println!("{}", serde_json::to_string_pretty(&merged)?),
+    }
+
+    Ok(())
+}
+
+/// Apply `--set` edits to an agent file and write it back (or to
+/// `output`). The result must pass validation before anything is written.
+pub async fn edit_agent_file(
+    file: String,
+    edits: Vec<String>,
+    output: Option<String>,
+) -> anyhow::Result<()> {
+    let mut agent_file = AgentFile::read(&file)?;
+    for edit in &edits {
+        agent_file.apply_edit(edit)?;
+    }
+    agent_file
+        .validate()
+        .map_err(|e| anyhow::anyhow!("Edited agent file is invalid: {}", e))?;
+
+    let path = output.unwrap_or(file);
+    agent_file.save(&path)?;
+    println!("✓ Applied {} edit(s) to {}", edits.len(), path);
+    Ok(())
+}
+
+/// Validate an agent file as a debate would, plus checks for duplicate
+/// personas and unknown CLIs
+pub async fn validate_agent_file(engage: &GptEngage, file: String) -> anyhow::Result<()> {
+    // A single-file merge reports duplicate personas within the file
+    let agent_file = AgentFile::merge(vec![(file.clone(), AgentFile::load(&file)?)])?;
+
+    println!(
+        "✓ {} is valid ({} agent(s))",
+        file,
+        agent_file.participants.len()
+    );
+    for agent in &agent_file.participants {
+        println!("  - {} ({})", agent.cli, agent.persona);
+        if !engage.invokers.is_valid(&agent.cli) {
+            eprintln!(
+                "Warning: '{}' is not a built-in CLI or installed plugin; {} would be skipped in a debate",
+                agent.cli, agent.persona
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! Alias command - Named shortcuts for frequent invocations
+//!
+//! An alias maps a name to the words of a gptengage command line, e.g.
+//! `review-auth` -> `invoke claude --context-file src/auth.rs`. Aliases are
+//! stored in the config file and expanded before argument parsing, so
+//! `gptengage review-auth "Check the token refresh"` behaves like a
+//! built-in subcommand with the extra arguments appended.
+
+use crate::cli::Cli;
+use crate::config::ConfigManager;
+use clap::CommandFactory;
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+/// Define or replace an alias
+pub async fn set_alias(name: String, expansion: String) -> anyhow::Result<()> {
+    let words = validate_alias(&name, &expansion)?;
+    let mut config = ConfigManager::new()?;
+    let replaced = config.aliases.insert(name.clone(), expansion).is_some();
+    config.save()?;
+
+    let verb = if replaced { "Updated" } else { "Added" };
+    println!(
+        "✓ {} alias '{}' -> gptengage {}",
+        verb,
+        name,
+        words.join(" ")
+    );
+    Ok(())
+}
+
+/// Print all aliases
+pub async fn list_aliases() -> anyhow::Result<()> {
+    let resolved = ConfigManager::shared()?;
+    let config = &resolved.config;
+    if config.aliases.is_empty() {
+        println!("No aliases defined.");
+        println!("Add one with: gptengage alias set <name> \"<command>\"");
+        return Ok(());
+    }
+
+    let mut aliases: Vec<_> = config.aliases.iter().collect();
+    aliases.sort();
+    let width = aliases
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    for (name, expansion) in aliases {
+        println!("  {:<width$}  gptengage {}", name, expansion, width = width);
+    }
+    Ok(())
+}
+
+/// Delete an alias
+pub async fn remove_alias(name: String) -> anyhow::Result<()> {
+    let mut config = ConfigManager::new()?;
+    if config.aliases.remove(&name).is_none() {
+        anyhow::bail!("Alias '{}' not found", name);
+    }
+    config.save()?;
+    println!("✓ Removed alias '{}'", name);
+    Ok(())
+}
+
+/// Check an alias definition, returning its expansion split into words
+fn validate_alias(name: &str, expansion: &str) -> anyhow::Result<Vec<String>> {
+    if name.is_empty()
+        || name.starts_with('-')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid alias name '{}'. Use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    if is_builtin_command(name) {
+        anyhow::bail!("'{}' is a built-in command and can't be an alias", name);
+    }
+
+    let words = shlex::split(expansion)
+        .ok_or_else(|| anyhow::anyhow!("Alias has unbalanced quotes: {}", expansion))?;
+    let argv: Vec<OsString> = std::iter::once("gptengage")
+        .chain(words.iter().map(String::as_str))
+        .map(OsString::from)
+        .collect();
+    let first = command_position(&argv)
+        .map(|pos| words[pos - 1].as_str())
+        .ok_or_else(|| anyhow::anyhow!("Alias must contain a gptengage command"))?;
+    // Aliases expand to built-in commands only, so they can't recurse
+    if !is_builtin_command(first) {
+        anyhow::bail!(
+            "Alias must expand to a built-in command (e.g. invoke, debate), not '{}'",
+            first
+        );
+    }
+    Ok(words)
+}
+
+/// Whether `name` is a built-in subcommand (or one of its aliases)
+fn is_builtin_command(name: &str) -> bool {
+    let command = Cli::command();
+    let found = command
+        .get_subcommands()
+        .any(|sub| sub.get_name() == name || sub.get_all_aliases().any(|a| a == name));
+    found || name == "help"
+}
+
+/// Expand a user alias in the process arguments.
+///
+/// Arguments are returned unchanged when the first command word is a
+/// built-in command, isn't an alias, or the config can't be loaded.
+pub fn expand_args(args: Vec<OsString>) -> Vec<OsString> {
+    match ConfigManager::shared() {
+        Ok(resolved) if !resolved.config.aliases.is_empty() => {
+            expand_with(args, &resolved.config.aliases)
+        }
+        _ => args,
+    }
+}
+
+/// Replace the first command word of `args` with its alias expansion
+fn expand_with(args: Vec<OsString>, aliases: &HashMap<String, String>) -> Vec<OsString> {
+    let Some(pos) = command_position(&args) else {
+        return args;
+    };
+    let Some(word) = args[pos].to_str() else {
+        return args;
+    };
+    if is_builtin_command(word) {
+        return args;
+    }
+    let Some(words) = aliases.get(word).and_then(|e| shlex::split(e)) else {
+        return args;
+    };
+
+    let mut expanded = args[..pos].to_vec();
+    expanded.extend(words.into_iter().map(OsString::from));
+    expanded.extend(args[pos + 1..].iter().cloned());
+    expanded
+}
+
+/// Index of the first command word, skipping global options and their values
+pub(crate) fn command_position(args: &[OsString]) -> Option<usize> {
+    let command = Cli::command();
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].to_str()?;
+        if arg == "--" || !arg.starts_with('-') {
+            return (arg != "--").then_some(i);
+        }
+        i += if takes_separate_value(&command, arg) {
+            2
+        } else {
+            1
+        };
+    }
+    None
+}
+
+/// Whether `arg` is an option of `command` whose value is the next word,
+/// like `--max-concurrency 4` or `-t 30`
+pub(crate) fn takes_separate_value(command: &clap::Command, arg: &str) -> bool {
+    let found = if let Some(long) = arg.strip_prefix("--") {
+        (!long.contains('='))
+            .then(|| command.get_arguments().find(|a| a.get_long() == Some(long)))
+            .flatten()
+    } else {
+        let mut short = arg.strip_prefix('-').unwrap_or_default().chars();
+        match (short.next(), short.next()) {
+            (Some(c), None) => command.get_arguments().find(|a| a.get_short() == Some(c)),
+            _ => None,
+        }
+    };
+    found.is_some_and(|a| a.get_action().takes_values())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<OsString> {
+        words.iter().map(OsString::from).collect()
+    }
+
+    fn aliases() -> HashMap<String, String> {
+        let mut aliases = HashMap::new();
+        aliases.insert(
+            "review-auth".to_string(),
+            "invoke claude --context-file 'src/auth module.rs'".to_string(),
+        );
+        aliases
+    }
+
+    #[test]
+    fn test_expand_alias_after_global_options() {
+        let expanded = expand_with(
+            args(&[
+                "gptengage",
+                "--max-concurrency",
+                "2",
+                "review-auth",
+                "Check refresh",
+            ]),
+            &aliases(),
+        );
+        assert_eq!(
+            expanded,
+            args(&[
+                "gptengage",
+                "--max-concurrency",
+                "2",
+                "invoke",
+                "claude",
+                "--context-file",
+                "src/auth module.rs",
+                "Check refresh",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_builtin_commands_are_not_expanded() {
+        let mut shadowing = aliases();
+        shadowing.insert("status".to_string(), "debate x".to_string());
+        let original = args(&["gptengage", "status"]);
+        assert_eq!(expand_with(original.clone(), &shadowing), original);
+
+        let unknown = args(&["gptengage", "--trust", "unknown"]);
+        assert_eq!(expand_with(unknown.clone(), &aliases()), unknown);
+    }
+
+    #[test]
+    fn test_validate_alias() {
+        assert!(validate_alias("review-auth", "invoke claude 'hello world'").is_ok());
+        assert!(validate_alias("--x", "invoke claude").is_err());
+        assert!(validate_alias("debate", "invoke claude").is_err());
+        assert!(validate_alias("loop", "loop again").is_err());
+        assert!(validate_alias("quote", "invoke 'claude").is_err());
+        assert!(validate_alias("fast", "--max-concurrency 2 debate x").is_ok());
+        assert!(validate_alias("flags", "--trust").is_err());
+    }
+}
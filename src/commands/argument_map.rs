@@ -0,0 +1,368 @@
+//! Argument maps - Debates as Graphviz or Mermaid graphs
+//!
+//! Participants are nodes, and the synthesis's consensus and disagreement
+//! points become claim nodes. A participant is linked to a claim when their
+//! responses are similar enough to it, scored the way `search` ranks
+//! passages. Participants are linked to each other by their final stances
+//! when stances were tracked, and otherwise by how similar their last
+//! responses are.
+
+use super::search::{Passage, SearchIndex};
+use crate::history::topic_summary;
+use crate::orchestrator::stance::participant_labels;
+use crate::orchestrator::DebateResult;
+use std::fmt::Write;
+
+/// Similarity at which a participant's responses back a claim
+const CLAIM_THRESHOLD: f32 = 0.2;
+
+/// Similarity at which two participants' last responses count as agreeing
+const AGREE_THRESHOLD: f32 = 0.35;
+
+/// Longest node label, in characters
+const MAX_LABEL_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NodeKind {
+    Participant,
+    Consensus,
+    Disagreement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Relation {
+    /// Participant to a consensus point
+    Supports,
+    /// Participant to a disagreement point
+    Contests,
+    Agrees,
+    Disagrees,
+}
+
+impl Relation {
+    fn label(self) -> &'static str {
+        match self {
+            Relation::Supports => "supports",
+            Relation::Contests => "contests",
+            Relation::Agrees => "agrees",
+            Relation::Disagrees => "disagrees",
+        }
+    }
+
+    fn is_conflict(self) -> bool {
+        matches!(self, Relation::Contests | Relation::Disagrees)
+    }
+}
+
+struct Node {
+    id: String,
+    label: String,
+    kind: NodeKind,
+}
+
+/// Participants, claims, and the relations between them
+pub struct ArgumentMap {
+    title: String,
+    nodes: Vec<Node>,
+    /// Node indices and how they relate
+    edges: Vec<(usize, usize, Relation)>,
+}
+
+/// A label cut to `MAX_LABEL_CHARS`
+fn shorten(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX_LABEL_CHARS {
+        return text;
+    }
+    let mut cut: String = text.chars().take(MAX_LABEL_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+impl ArgumentMap {
+    pub fn from_result(result: &DebateResult) -> Self {
+        // Each participant's responses across rounds, and their last one
+        let mut participants: Vec<(String, String, String)> = Vec::new();
+        for responses in &result.rounds {
+            for (label, response) in participant_labels(responses).into_iter().zip(responses) {
+                match participants.iter_mut().find(|p| p.0 == label) {
+                    Some(participant) => {
+                        participant.1.push('\n');
+                        participant.1.push_str(&response.response);
+                        participant.2 = response.response.clone();
+                    }
+                    None => participants.push((
+                        label,
+                        response.response.clone(),
+                        response.response.clone(),
+                    )),
+                }
+            }
+        }
+        let final_stance = |label: &str| {
+            result
+                .stances
+                .iter()
+                .rev()
+                .find(|s| s.participant == label)
+                .map(|s| s.position.clone())
+        };
+
+        let mut nodes = Vec::new();
+        for (i, (label, _, _)) in participants.iter().enumerate() {
+            let label = match final_stance(label) {
+                Some(position) => format!("{}: {}", label, position),
+                None => label.clone(),
+            };
+            nodes.push(Node {
+                id: format!("p{}", i + 1),
+                label: shorten(&label),
+                kind: NodeKind::Participant,
+            });
+        }
+        let (consensus, disagreements) = result
+            .synthesis
+            .as_ref()
+            .map(|s| (s.consensus_points.clone(), s.disagreement_points.clone()))
+            .unwrap_or_default();
+        let claims: Vec<(&String, NodeKind)> = consensus
+            .iter()
+            .map(|c| (c, NodeKind::Consensus))
+            .chain(disagreements.iter().map(|d| (d, NodeKind::Disagreement)))
+            .collect();
+        for (i, (claim, kind)) in claims.iter().enumerate() {
+            nodes.push(Node {
+                id: format!("c{}", i + 1),
+                label: shorten(claim),
+                kind: *kind,
+            });
+        }
+
+        // Index layout: participants' responses, then claims, then
+        // participants' last responses
+        let count = participants.len();
+        let passage = |text: &str| Passage {
+            source: String::new(),
+            location: String::new(),
+            text: text.to_string(),
+        };
+        let index = SearchIndex::new(
+            participants
+                .iter()
+                .map(|p| passage(&p.1))
+                .chain(claims.iter().map(|(claim, _)| passage(claim)))
+                .chain(participants.iter().map(|p| passage(&p.2)))
+                .collect(),
+        );
+        let last = |i: usize| count + claims.len() + i;
+
+        let mut edges = Vec::new();
+        for i in 0..count {
+            for (c, (_, kind)) in claims.iter().enumerate() {
+                if index.similarity(i, count + c) < CLAIM_THRESHOLD {
+                    continue;
+                }
+                let relation = if *kind == NodeKind::Consensus {
+                    Relation::Supports
+                } else {
+                    Relation::Contests
+                };
+                edges.push((i, count + c, relation));
+            }
+        }
+        for i in 0..count {
+            for j in i + 1..count {
+                let stances =
+                    final_stance(&participants[i].0).zip(final_stance(&participants[j].0));
+                let relation = match stances {
+                    Some((a, b)) if a.trim().eq_ignore_ascii_case(b.trim()) => Relation::Agrees,
+                    Some(_) => Relation::Disagrees,
+                    None if index.similarity(last(i), last(j)) >= AGREE_THRESHOLD => {
+                        Relation::Agrees
+                    }
+                    None => continue,
+                };
+                edges.push((i, j, relation));
+            }
+        }
+
+        Self {
+            title: shorten(&topic_summary(&result.topic)),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Graphviz DOT source
+    pub fn to_dot(&self) -> anyhow::Result<String> {
+        let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::new();
+        writeln!(out, "graph argument_map {{")?;
+        writeln!(out, "  label=\"{}\";", escape(&self.title))?;
+        writeln!(out, "  labelloc=t;")?;
+        writeln!(out, "  node [fontname=\"Helvetica\"];")?;
+        for node in &self.nodes {
+            let style = match node.kind {
+                NodeKind::Participant => "shape=ellipse, style=filled, fillcolor=\"#dbe9f6\"",
+                NodeKind::Consensus => "shape=box, color=\"#2e7d32\"",
+                NodeKind::Disagreement => "shape=box, color=\"#c62828\"",
+            };
+            writeln!(
+                out,
+                "  {} [label=\"{}\", {}];",
+                node.id,
+                escape(&node.label),
+                style
+            )?;
+        }
+        for (from, to, relation) in &self.edges {
+            let style = if relation.is_conflict() {
+                "color=\"#c62828\", style=dashed"
+            } else {
+                "color=\"#2e7d32\""
+            };
+            writeln!(
+                out,
+                "  {} -- {} [label=\"{}\", {}];",
+                self.nodes[*from].id,
+                self.nodes[*to].id,
+                relation.label(),
+                style
+            )?;
+        }
+        writeln!(out, "}}")?;
+        Ok(out)
+    }
+
+    /// Mermaid flowchart source
+    pub fn to_mermaid(&self) -> anyhow::Result<String> {
+        let escape = |text: &str| text.replace('"', "#quot;");
+        let mut out = String::new();
+        writeln!(out, "---")?;
+        writeln!(out, "title: \"{}\"", escape(&self.title))?;
+        writeln!(out, "---")?;
+        writeln!(out, "graph LR")?;
+        for node in &self.nodes {
+            let label = escape(&node.label);
+            match node.kind {
+                NodeKind::Participant => writeln!(out, "  {}([\"{}\"])", node.id, label)?,
+                _ => writeln!(out, "  {}[\"{}\"]", node.id, label)?,
+            }
+        }
+        for (from, to, relation) in &self.edges {
+            let (from, to) = (&self.nodes[*from].id, &self.nodes[*to].id);
+            if relation.is_conflict() {
+                writeln!(out, "  {} -. {} .- {}", from, relation.label(), to)?;
+            } else {
+                writeln!(out, "  {} -- {} --- {}", from, relation.label(), to)?;
+            }
+        }
+        for (kind, class, color) in [
+            (NodeKind::Consensus, "consensus", "#2e7d32"),
+            (NodeKind::Disagreement, "disagreement", "#c62828"),
+        ] {
+            let ids: Vec<&str> = self
+                .nodes
+                .iter()
+                .filter(|n| n.kind == kind)
+                .map(|n| n.id.as_str())
+                .collect();
+            if !ids.is_empty() {
+                writeln!(out, "  classDef {} stroke:{}", class, color)?;
+                writeln!(out, "  class {} {}", ids.join(","), class)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::stance::Stance;
+    use crate::orchestrator::{RoundResponse, Synthesis, DEBATE_RESULT_SCHEMA_VERSION};
+
+    fn response(cli: &str, text: &str) -> RoundResponse {
+        RoundResponse {
+            cli: cli.to_string(),
+            persona: None,
+            response: text.to_string(),
+            log: None,
+        }
+    }
+
+    fn stance(participant: &str, position: &str) -> Stance {
+        Stance {
+            round: 1,
+            participant: participant.to_string(),
+            position: position.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_argument_map_links_participants_and_claims() {
+        let result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
+            gptengage_version: None,
+            topic: "Should we \"shard\" the database?".to_string(),
+            rounds: vec![vec![
+                response(
+                    "claude",
+                    "Shard by tenant; tenant isolation limits blast radius.",
+                ),
+                response(
+                    "codex",
+                    "Read replicas and caching first; sharding is premature.",
+                ),
+                response("gemini", "Add caching before anything else."),
+            ]],
+            synthesis: Some(Synthesis {
+                summary: String::new(),
+                consensus_points: vec!["Add caching".to_string()],
+                disagreement_points: vec!["Whether to shard by tenant".to_string()],
+                key_insights: vec![],
+                recommendation: None,
+                position_changes: vec![],
+                unverified_citations: vec![],
+            }),
+            stances: vec![
+                stance("claude", "for"),
+                stance("codex", "against"),
+                stance("gemini", "Against"),
+            ],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
+        };
+        let map = ArgumentMap::from_result(&result);
+
+        let dot = map.to_dot().unwrap();
+        assert!(dot.contains("label=\"Should we \\\"shard\\\" the database?\";"));
+        assert!(dot.contains("p1 [label=\"claude: for\""));
+        assert!(dot.contains("c2 [label=\"Whether to shard by tenant\", shape=box"));
+        assert!(dot.contains("p1 -- c2 [label=\"contests\""));
+        assert!(dot.contains("p3 -- c1 [label=\"supports\""));
+        assert!(dot.contains("p1 -- p2 [label=\"disagrees\""));
+        assert!(dot.contains("p2 -- p3 [label=\"agrees\""));
+        assert!(!dot.contains("p1 -- c1"));
+
+        let mermaid = map.to_mermaid().unwrap();
+        assert!(mermaid.contains("title: \"Should we #quot;shard#quot; the database?\""));
+        assert!(mermaid.contains("  p1([\"claude: for\"])"));
+        assert!(mermaid.contains("  p1 -. contests .- c2"));
+        assert!(mermaid.contains("  p2 -- agrees --- p3"));
+        assert!(mermaid.contains("  class c1 consensus"));
+    }
+
+    #[test]
+    fn test_shorten_labels() {
+        assert_eq!(shorten("a  b\nc"), "a b c");
+        let long = shorten(&"word ".repeat(30));
+        assert_eq!(long.chars().count(), MAX_LABEL_CHARS);
+        assert!(long.ends_with('…'));
+    }
+}
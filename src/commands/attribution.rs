@@ -0,0 +1,182 @@
+//! Attribution export - Who said what, as a flat list of utterances
+//!
+//! Each response in a debate transcript becomes an utterance with a stable
+//! ID, so argument-mapping tools can draw the debate as a graph. An
+//! utterance refers to an earlier one when it quotes it: a quoted span or
+//! blockquote line that appears in the earlier response's text.
+
+use crate::commands::bundle::parse_transcript;
+use crate::orchestrator::DebateResult;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+/// Words a quote needs before it counts as a reference
+const MIN_QUOTE_WORDS: usize = 4;
+
+static QUOTE: OnceLock<Regex> = OnceLock::new();
+
+/// One participant's response in one round
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Utterance {
+    /// `<debate>:r<round>:p<position>`, the same for every export of a transcript
+    pub id: String,
+    /// Debate ID, derived from the topic and responses
+    pub debate: String,
+    pub round: usize,
+    /// Participant display name, e.g. "claude (CTO)"
+    pub participant: String,
+    /// Order of the response within its round, from 1
+    pub position: usize,
+    pub text: String,
+    /// IDs of earlier utterances this one quotes
+    #[serde(default)]
+    pub refs: Vec<String>,
+}
+
+/// Stable short ID for a transcript
+pub fn debate_id(result: &DebateResult) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(result.topic.as_bytes());
+    for response in result.rounds.iter().flatten() {
+        hasher.update([0]);
+        hasher.update(response.display_name().as_bytes());
+        hasher.update([0]);
+        hasher.update(response.response.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())[..12].to_string()
+}
+
+/// Lowercased words joined by single spaces, for quote matching
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Normalized quotes in a response long enough to identify a source
+fn quotes(text: &str) -> Vec<String> {
+    // Quoted spans on one line: "straight", “curly”, or a > blockquote
+    let quote = QUOTE.get_or_init(|| {
+        Regex::new(r#"(?m)"([^"\n]+)"|“([^”\n]+)”|^[ \t]*>[ \t]?(.+)$"#).expect("valid regex")
+    });
+    quote
+        .captures_iter(text)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2)).or_else(|| c.get(3)))
+        .map(|m| normalize(m.as_str()))
+        .filter(|quote| quote.split(' ').count() >= MIN_QUOTE_WORDS)
+        .collect()
+}
+
+/// Flatten a transcript into utterances, linking quotes to the most recent
+/// earlier utterance containing them
+pub fn utterances(result: &DebateResult) -> Vec<Utterance> {
+    let debate = debate_id(result);
+    let mut out: Vec<Utterance> = Vec::new();
+    let mut normalized: Vec<String> = Vec::new();
+    for (round, responses) in result.rounds.iter().enumerate() {
+        for (position, response) in responses.iter().enumerate() {
+            let mut refs: Vec<String> = Vec::new();
+            for quote in quotes(&response.response) {
+                let source = normalized
+                    .iter()
+                    .rposition(|earlier| earlier.contains(&quote))
+                    .map(|i| out[i].id.clone());
+                if let Some(id) = source.filter(|id| !refs.contains(id)) {
+                    refs.push(id);
+                }
+            }
+            out.push(Utterance {
+                id: format!("{}:r{}:p{}", debate, round + 1, position + 1),
+                debate: debate.clone(),
+                round: round + 1,
+                participant: response.display_name(),
+                position: position + 1,
+                text: response.response.clone(),
+                refs,
+            });
+            normalized.push(normalize(&response.response));
+        }
+    }
+    out
+}
+
+/// Print a JSON transcript's utterances as a JSON array
+pub async fn export_utterances(transcript: String) -> anyhow::Result<()> {
+    let content = tokio::fs::read_to_string(&transcript)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read transcript {}: {}", transcript, e))?;
+    let result = parse_transcript(&content).map_err(|e| {
+        anyhow::anyhow!(
+            "{} is not a JSON debate transcript (save one with --output json): {}",
+            transcript,
+            e
+        )
+    })?;
+    println!("{}", serde_json::to_string_pretty(&utterances(&result))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::{RoundResponse, DEBATE_RESULT_SCHEMA_VERSION};
+
+    fn response(cli: &str, text: &str) -> RoundResponse {
+        RoundResponse {
+            cli: cli.to_string(),
+            persona: None,
+            response: text.to_string(),
+            log: None,
+        }
+    }
+
+    #[test]
+    fn test_utterances_link_quotes_to_sources() {
+        let result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
+            gptengage_version: None,
+            topic: "Monolith or microservices?".to_string(),
+            rounds: vec![
+                vec![
+                    response("claude", "Start with a modular monolith and split later."),
+                    response("codex", "Microservices let teams deploy independently."),
+                ],
+                vec![
+                    response(
+                        "claude",
+                        "Codex says \"microservices let teams deploy independently\", \
+                         but that needs mature CI.",
+                    ),
+                    response(
+                        "codex",
+                        "> Start with a modular monolith\n\nAgreed. \"Too short\" isn't a quote.",
+                    ),
+                ],
+            ],
+            synthesis: None,
+            stances: Vec::new(),
+            benched: Vec::new(),
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
+        };
+
+        let utterances = utterances(&result);
+        let debate = debate_id(&result);
+        assert_eq!(debate.len(), 12);
+        assert_eq!(utterances.len(), 4);
+        assert_eq!(utterances[3].id, format!("{}:r2:p2", debate));
+        assert_eq!(utterances[3].participant, "codex");
+        assert!(utterances[0].refs.is_empty());
+        assert_eq!(utterances[2].refs, vec![format!("{}:r1:p2", debate)]);
+        assert_eq!(utterances[3].refs, vec![format!("{}:r1:p1", debate)]);
+    }
+}
@@ -0,0 +1,58 @@
+//! Audit command - Inspect recorded process executions
+
+use crate::audit::AuditLog;
+use crate::utils::time::format_timestamp;
+
+/// Show the most recent audit log entries
+pub async fn show_audit(limit: usize, json: bool) -> anyhow::Result<()> {
+    let log = AuditLog::open()?;
+    let entries = log.read()?;
+
+    if entries.is_empty() {
+        println!("No audit entries recorded.");
+        println!();
+        println!("Enable auditing with: gptengage config set audit_log true");
+        return Ok(());
+    }
+
+    let skip = entries.len().saturating_sub(limit);
+    for entry in entries.iter().skip(skip) {
+        if json {
+            println!("{}", serde_json::to_string(entry)?);
+            continue;
+        }
+
+        let exit = entry
+            .exit_code
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{}  {}  {} (exit {}, {}ms)",
+            format_timestamp(entry.timestamp),
+            entry.outcome,
+            entry.command,
+            exit,
+            entry.duration_ms
+        );
+        println!("    Args: {}", entry.args.join(" "));
+        if let Some(ref hash) = entry.prompt_sha256 {
+            println!("    Prompt SHA-256: {}", hash);
+        }
+        println!(
+            "    User: {}  Access: {}  Cwd: {}",
+            entry.user, entry.access_mode, entry.cwd
+        );
+    }
+
+    if !json {
+        println!();
+        println!(
+            "Showing {} of {} entries ({})",
+            entries.len() - skip,
+            entries.len(),
+            log.path().display()
+        );
+    }
+
+    Ok(())
+}
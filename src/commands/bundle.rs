@@ -0,0 +1,424 @@
+//! Debate bundles - Portable archives of a finished debate
+//!
+//! A bundle is a zstd-compressed tar of a JSON transcript plus what is
+//! needed to review or re-run it elsewhere: the agent files and template
+//! used, the shareable part of the config, and the raw invocation logs the
+//! transcript points at.
+
+use crate::app::GptEngage;
+use crate::config::ConfigManager;
+use crate::orchestrator::DebateResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Bundle layout version written to the manifest
+const BUNDLE_VERSION: &str = "1";
+
+const MANIFEST: &str = "manifest.json";
+const TRANSCRIPT: &str = "transcript.json";
+
+/// Config keys snapshotted into a bundle. Only settings that shape how a
+/// debate runs travel; paths, plugin trust, profiles, aliases and issue
+/// tracker URLs and headers (where tokens live) stay on this machine.
+const BUNDLED_CONFIG_KEYS: &[&str] = &[
+    "default_timeout",
+    "default_debate_rounds",
+    "latency_scheduling",
+    "time_format",
+    "max_output_bytes",
+    "max_continuations",
+    "circuit_breaker_threshold",
+    "dialects",
+    "context_windows",
+    "response_formats",
+    "presets",
+    "fallbacks",
+];
+
+/// Describes a bundle's contents
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub bundle_version: String,
+    pub gptengage_version: String,
+    pub created_at: DateTime<Utc>,
+    pub topic: String,
+    pub rounds: usize,
+    /// Template the debate used, snapshotted as templates/<name>.toml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Every file in the bundle besides the manifest
+    pub files: Vec<String>,
+}
+
+/// Point transcript log references at `logs/<file>` in the bundle,
+/// returning bundle path -> local path for each log that still exists
+fn relocate_logs(result: &mut DebateResult) -> BTreeMap<String, PathBuf> {
+    let mut logs = BTreeMap::new();
+    for response in result.rounds.iter_mut().flatten() {
+        let Some(log) = response.log.take() else {
+            continue;
+        };
+        let local = PathBuf::from(&log);
+        let Some(file_name) = local.file_name() else {
+            continue;
+        };
+        if !local.exists() {
+            continue;
+        }
+        let bundled = format!("logs/{}", file_name.to_string_lossy());
+        response.log = Some(bundled.clone());
+        logs.insert(bundled, local);
+    }
+    logs
+}
+
+/// Parse a JSON transcript (migrating older schema versions), skipping the
+/// banner `debate` prints to stdout when its output was redirected to the file
+pub(crate) fn parse_transcript(content: &str) -> anyhow::Result<DebateResult> {
+    let start = content
+        .find("\n{\n")
+        .filter(|_| !content.starts_with('{'))
+        .map_or(0, |i| i + 1);
+    DebateResult::from_json(&content[start..])
+}
+
+/// Bundle file name for an agent file, keeping repeated base names apart
+fn agent_entry(index: usize, path: &str) -> String {
+    let name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "agents.json".to_string());
+    if index == 0 {
+        format!("agents/{}", name)
+    } else {
+        format!("agents/{}-{}", index + 1, name)
+    }
+}
+
+fn append_file(
+    builder: &mut tar::Builder<impl std::io::Write>,
+    name: &str,
+    data: &[u8],
+) -> anyhow::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// Write a bundle for a JSON debate transcript
+pub async fn export_debate(
+    engage: &GptEngage,
+    transcript: String,
+    bundle: String,
+    agent_files: Vec<String>,
+    template: Option<String>,
+) -> anyhow::Result<()> {
+    let content = tokio::fs::read_to_string(&transcript)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read transcript {}: {}", transcript, e))?;
+    let mut result = parse_transcript(&content).map_err(|e| {
+        anyhow::anyhow!(
+            "{} is not a JSON debate transcript (save one with --output json): {}",
+            transcript,
+            e
+        )
+    })?;
+
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    for (bundled, local) in relocate_logs(&mut result) {
+        files.push((bundled, std::fs::read(&local)?));
+    }
+    for (i, path) in agent_files.iter().enumerate() {
+        let data = std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read agent file {}: {}", path, e))?;
+        files.push((agent_entry(i, path), data));
+    }
+    if let Some(ref name) = template {
+        let manager = engage.templates()?;
+        let snapshot = manager
+            .get_template(name)
+            .ok_or_else(|| anyhow::anyhow!("Template '{}' not found", name))?;
+        files.push((
+            format!("templates/{}.toml", name),
+            toml::to_string_pretty(snapshot)?.into_bytes(),
+        ));
+    }
+    let resolved = engage.config()?;
+    files.push((
+        "config.json".to_string(),
+        serde_json::to_vec_pretty(&shareable_config(&resolved.config)?)?,
+    ));
+    files.push((TRANSCRIPT.to_string(), serde_json::to_vec_pretty(&result)?));
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let manifest = BundleManifest {
+        bundle_version: BUNDLE_VERSION.to_string(),
+        gptengage_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        topic: result.topic.clone(),
+        rounds: result.rounds.len(),
+        template,
+        files: files.iter().map(|(name, _)| name.clone()).collect(),
+    };
+
+    let file = std::fs::File::create(&bundle)
+        .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", bundle, e))?;
+    let encoder = zstd::Encoder::new(file, 0)?.auto_finish();
+    let mut builder = tar::Builder::new(encoder);
+    append_file(
+        &mut builder,
+        MANIFEST,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    for (name, data) in &files {
+        append_file(&mut builder, name, data)?;
+    }
+    builder.into_inner()?;
+
+    println!("✓ Exported debate to {}", bundle);
+    for name in &manifest.files {
+        println!("  {}", name);
+    }
+    Ok(())
+}
+
+/// Unpack a bundle into `dir`, returning its manifest
+fn unpack_bundle(bundle: &Path, dir: &Path) -> anyhow::Result<BundleManifest> {
+    let file = std::fs::File::open(bundle)
+        .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", bundle.display(), e))?;
+    let mut archive = tar::Archive::new(zstd::Decoder::new(file)?);
+    std::fs::create_dir_all(dir)?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        // unpack_in refuses entries that would land outside `dir`
+        if !entry.unpack_in(dir)? {
+            anyhow::bail!(
+                "Bundle entry '{}' escapes the target directory",
+                entry.path()?.display()
+            );
+        }
+    }
+
+    let manifest_path = dir.join(MANIFEST);
+    let manifest: BundleManifest = std::fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .ok_or_else(|| anyhow::anyhow!("{} is not a debate bundle", bundle.display()))?;
+    if manifest.bundle_version != BUNDLE_VERSION {
+        anyhow::bail!(
+            "Unsupported bundle version {} (this gptengage reads version {})",
+            manifest.bundle_version,
+            BUNDLE_VERSION
+        );
+    }
+    Ok(manifest)
+}
+
+/// Default import directory: the bundle's file name without extensions
+fn default_import_dir(bundle: &str) -> String {
+    let name = Path::new(bundle)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = name
+        .strip_suffix(".tar.zst")
+        .or_else(|| name.strip_suffix(".tzst"))
+        .unwrap_or(&name);
+    if stem.is_empty() || stem == name {
+        format!("{}.d", name)
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Unpack a bundle for review and print how to re-run the debate
+pub async fn import_debate(bundle: String, dir: Option<String>, force: bool) -> anyhow::Result<()> {
+    let dir = PathBuf::from(dir.unwrap_or_else(|| default_import_dir(&bundle)));
+    if !force
+        && dir
+            .read_dir()
+            .is_ok_and(|mut entries| entries.next().is_some())
+    {
+        anyhow::bail!(
+            "{} already exists and is not empty. Use --force to unpack into it anyway.",
+            dir.display()
+        );
+    }
+    let manifest = unpack_bundle(Path::new(&bundle), &dir)?;
+
+    println!("✓ Imported debate to {}", dir.display());
+    println!("  Topic: {}", manifest.topic);
+    println!(
+        "  Rounds: {} (exported {} by gptengage {})",
+        manifest.rounds,
+        manifest.created_at.format("%Y-%m-%d %H:%M UTC"),
+        manifest.gptengage_version
+    );
+    println!("  Transcript: {}", dir.join(TRANSCRIPT).display());
+
+    let agent_files: Vec<&String> = manifest
+        .files
+        .iter()
+        .filter(|f| f.starts_with("agents/"))
+        .collect();
+    let mut rerun = vec![
+        "gptengage".to_string(),
+        "debate".to_string(),
+        manifest.topic.clone(),
+    ];
+    for file in &agent_files {
+        rerun.push("--agent-file".to_string());
+        rerun.push(dir.join(file).display().to_string());
+    }
+    if let Some(ref template) = manifest.template {
+        println!(
+            "  Template snapshot: {} (copy it to ~/.gptengage/templates/ to use it)",
+            dir.join(format!("templates/{}.toml", template)).display()
+        );
+        if agent_files.is_empty() {
+            rerun.push("--template".to_string());
+            rerun.push(template.clone());
+        }
+    }
+    rerun.push("--rounds".to_string());
+    rerun.push(manifest.rounds.to_string());
+    println!();
+    println!("Re-run it with:");
+    println!(
+        "  {}",
+        shlex::try_join(rerun.iter().map(String::as_str)).unwrap_or_else(|_| rerun.join(" "))
+    );
+    Ok(())
+}
+
+/// The part of the config that is safe to hand to someone else
+fn shareable_config(config: &ConfigManager) -> anyhow::Result<serde_json::Value> {
+    let mut value = serde_json::to_value(config)?;
+    if let Some(map) = value.as_object_mut() {
+        map.retain(|key, _| BUNDLED_CONFIG_KEYS.contains(&key.as_str()));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::{RoundResponse, DEBATE_RESULT_SCHEMA_VERSION};
+
+    #[test]
+    fn test_relocate_logs_keeps_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("20260101T000000.000Z-1-000000-claude.log");
+        std::fs::write(&log, "raw").unwrap();
+        let response = |log: Option<String>| RoundResponse {
+            cli: "claude".to_string(),
+            persona: None,
+            response: "text".to_string(),
+            log,
+        };
+        let mut result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
+            gptengage_version: None,
+            topic: "T".to_string(),
+            rounds: vec![vec![
+                response(Some(log.display().to_string())),
+                response(Some("/gone/missing.log".to_string())),
+            ]],
+            synthesis: None,
+            stances: Vec::new(),
+            benched: Vec::new(),
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
+        };
+
+        let logs = relocate_logs(&mut result);
+        let bundled = "logs/20260101T000000.000Z-1-000000-claude.log";
+        assert_eq!(logs.get(bundled), Some(&log));
+        assert_eq!(result.rounds[0][0].log.as_deref(), Some(bundled));
+        assert_eq!(result.rounds[0][1].log, None);
+    }
+
+    #[test]
+    fn test_shareable_config_drops_tracker_headers() {
+        let mut config = ConfigManager::default();
+        config.issue_trackers.insert(
+            "jira".to_string(),
+            crate::config::IssueTrackerConfig {
+                url: "https://jira.example.com/rest/api/2/issue".to_string(),
+                template: serde_json::json!({}),
+                headers: [(
+                    "Authorization".to_string(),
+                    "Bearer secret-token".to_string(),
+                )]
+                .into(),
+                response_url: None,
+            },
+        );
+        config.aliases.insert("r".to_string(), "debate".to_string());
+        config.default_debate_rounds = 5;
+
+        let shared = shareable_config(&config).unwrap();
+        let text = shared.to_string();
+        assert!(!text.contains("secret-token"));
+        assert!(!text.contains("jira.example.com"));
+        assert!(shared.get("issue_trackers").is_none());
+        assert!(shared.get("aliases").is_none());
+        assert_eq!(shared["default_debate_rounds"], 5);
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("debate.tar.zst");
+        let manifest = BundleManifest {
+            bundle_version: BUNDLE_VERSION.to_string(),
+            gptengage_version: "0.0.0".to_string(),
+            created_at: Utc::now(),
+            topic: "T".to_string(),
+            rounds: 1,
+            template: None,
+            files: vec![TRANSCRIPT.to_string(), "agents/team.json".to_string()],
+        };
+        {
+            let file = std::fs::File::create(&bundle).unwrap();
+            let encoder = zstd::Encoder::new(file, 0).unwrap().auto_finish();
+            let mut builder = tar::Builder::new(encoder);
+            append_file(
+                &mut builder,
+                MANIFEST,
+                &serde_json::to_vec(&manifest).unwrap(),
+            )
+            .unwrap();
+            append_file(&mut builder, TRANSCRIPT, b"{}").unwrap();
+            append_file(&mut builder, "agents/team.json", b"{}").unwrap();
+            builder.into_inner().unwrap();
+        }
+
+        let out = dir.path().join("out");
+        let unpacked = unpack_bundle(&bundle, &out).unwrap();
+        assert_eq!(unpacked.topic, "T");
+        assert!(out.join("agents/team.json").exists());
+
+        assert_eq!(default_import_dir("x/debate.tar.zst"), "debate");
+        assert_eq!(default_import_dir("debate.zip"), "debate.zip.d");
+    }
+
+    #[test]
+    fn test_parse_transcript_skips_banner() {
+        let json = "{\n  \"topic\": \"T\",\n  \"rounds\": []\n}\n";
+        assert_eq!(parse_transcript(json).unwrap().topic, "T");
+        let redirected = format!("GPT ENGAGE DEBATE\nTopic: T\n\n{}", json);
+        assert_eq!(parse_transcript(&redirected).unwrap().topic, "T");
+        assert!(parse_transcript("# Markdown").is_err());
+    }
+}
@@ -0,0 +1,268 @@
+//! Completions command - Shell completion scripts
+//!
+//! Static completions (subcommands and flags) are generated by
+//! clap_complete. For bash, zsh, and fish a small hook is appended that
+//! first asks the hidden `__complete` command for dynamic values (session,
+//! template, plugin, alias, and CLI names, and config keys) and falls
+//! back to the static completions when there are none.
+
+use crate::app::GptEngage;
+use crate::cli::Cli;
+use crate::commands::alias::takes_separate_value;
+use crate::config::KNOWN_KEYS;
+use crate::invokers::detect::BUILTIN_CLIS;
+use crate::personas::PersonaRegistry;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+/// Kinds of values completed dynamically
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Sessions,
+    Templates,
+    Plugins,
+    Clis,
+    ConfigKeys,
+    Aliases,
+    Personas,
+}
+
+/// Print the completion script for `shell`
+pub async fn print_completions(shell: Shell) -> anyhow::Result<()> {
+    let mut command = Cli::command();
+    let mut stdout = std::io::stdout();
+    clap_complete::generate(shell, &mut command, "gptengage", &mut stdout);
+
+    if let Some(hook) = dynamic_hook(shell) {
+        print!("{}", hook);
+    }
+    Ok(())
+}
+
+/// Print dynamic candidates for the word after `words`.
+///
+/// Fails when the position has no dynamic values, so the command exits
+/// non-zero and the shell hook uses the static completions instead.
+pub async fn complete_values(engage: &GptEngage, words: Vec<String>) -> anyhow::Result<()> {
+    let Some(kind) = value_kind(&words) else {
+        anyhow::bail!("No dynamic completions here");
+    };
+    for value in candidates(engage, kind).await {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Decide which values complete the next word, given the words before it
+/// (without the program name)
+fn value_kind(words: &[String]) -> Option<ValueKind> {
+    match words.last().map(String::as_str) {
+        Some("--session" | "-s") => return Some(ValueKind::Sessions),
+        Some("--template") => return Some(ValueKind::Templates),
+        Some(
+            "--cli" | "--synthesizer" | "--stance-cli" | "--agent" | "--judge"
+            | "--auto-agents-cli",
+        ) => return Some(ValueKind::Clis),
+        _ => {}
+    }
+
+    match positionals(words).as_slice() {
+        ["invoke"] => Some(ValueKind::Clis),
+        ["session", "show" | "replay" | "ask" | "end"] => Some(ValueKind::Sessions),
+        ["template", "show"] => Some(ValueKind::Templates),
+        ["plugin", "remove" | "enable" | "disable"] => Some(ValueKind::Plugins),
+        ["config", "get" | "set" | "unset"] => Some(ValueKind::ConfigKeys),
+        ["alias", "set" | "remove"] => Some(ValueKind::Aliases),
+        ["persona", "show"] => Some(ValueKind::Personas),
+        _ => None,
+    }
+}
+
+/// The words that aren't options or option values, e.g. `session show`
+/// for `--timeout 30 session show`
+fn positionals(words: &[String]) -> Vec<&str> {
+    let root = Cli::command();
+    let mut command = &root;
+    let mut positional = Vec::new();
+    let mut words = words.iter().map(String::as_str);
+    while let Some(word) = words.next() {
+        if word.starts_with('-') {
+            // Global options are declared on the root command
+            if takes_separate_value(command, word) || takes_separate_value(&root, word) {
+                words.next();
+            }
+            continue;
+        }
+        positional.push(word);
+        if let Some(subcommand) = command.find_subcommand(word) {
+            command = subcommand;
+        }
+    }
+    positional
+}
+
+/// Current values of a kind. Errors produce no candidates rather than
+/// noise in the user's shell.
+async fn candidates(engage: &GptEngage, kind: ValueKind) -> Vec<String> {
+    let mut values: Vec<String> = match kind {
+        ValueKind::Sessions => match engage.sessions() {
+            Ok(manager) => manager
+                .list_sessions()
+                .await
+                .map(|sessions| sessions.into_iter().map(|s| s.name).collect())
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        },
+        ValueKind::Templates => engage
+            .templates()
+            .map(|manager| {
+                manager
+                    .list_templates()
+                    .into_iter()
+                    .map(|t| t.name)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ValueKind::Plugins => plugin_names(engage, false),
+        ValueKind::Clis => {
+            let mut clis: Vec<String> = BUILTIN_CLIS.iter().map(|c| c.to_string()).collect();
+            clis.extend(plugin_names(engage, true));
+            clis
+        }
+        ValueKind::ConfigKeys => KNOWN_KEYS.iter().map(|k| k.to_string()).collect(),
+        ValueKind::Aliases => engage
+            .config()
+            .map(|resolved| resolved.config.aliases.keys().cloned().collect())
+            .unwrap_or_default(),
+        ValueKind::Personas => PersonaRegistry::new()
+            .map(|registry| registry.list().iter().map(|p| p.name.clone()).collect())
+            .unwrap_or_default(),
+    };
+    if kind != ValueKind::Clis {
+        values.sort();
+    }
+    values
+}
+
+/// Installed plugin names, optionally only enabled ones
+fn plugin_names(engage: &GptEngage, enabled_only: bool) -> Vec<String> {
+    let Ok(manager) = engage.plugins() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = manager
+        .list_plugins()
+        .into_iter()
+        .map(|p| p.plugin.name.clone())
+        .filter(|name| !enabled_only || !manager.is_disabled(name))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Shell code that consults `gptengage __complete` before the static
+/// completions
+fn dynamic_hook(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(BASH_HOOK),
+        Shell::Zsh => Some(ZSH_HOOK),
+        Shell::Fish => Some(FISH_HOOK),
+        _ => None,
+    }
+}
+
+const BASH_HOOK: &str = r#"
+# Dynamic values (sessions, templates, plugins, config keys)
+_gptengage_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" values
+    if values=$(gptengage __complete -- "${COMP_WORDS[@]:1:COMP_CWORD-1}" 2>/dev/null); then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "${values}" -- "${cur}"))
+        return 0
+    fi
+    _gptengage "$@"
+}
+complete -F _gptengage_dynamic -o bashdefault -o default gptengage
+"#;
+
+const ZSH_HOOK: &str = r#"
+# Dynamic values (sessions, templates, plugins, config keys)
+_gptengage_dynamic() {
+    local values
+    if values=$(gptengage __complete -- "${(@)words[2,CURRENT-1]}" 2>/dev/null); then
+        compadd -- ${(f)values}
+        return 0
+    fi
+    _gptengage "$@"
+}
+compdef _gptengage_dynamic gptengage
+"#;
+
+const FISH_HOOK: &str = r#"
+# Dynamic values (sessions, templates, plugins, config keys)
+function __gptengage_dynamic
+    set -l words (commandline -opc)
+    gptengage __complete -- $words[2..-1] 2>/dev/null
+end
+complete -c gptengage -f -n '__gptengage_dynamic >/dev/null' -a '(__gptengage_dynamic)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_value_kind() {
+        assert_eq!(
+            value_kind(&words("session show")),
+            Some(ValueKind::Sessions)
+        );
+        assert_eq!(
+            value_kind(&words("invoke claude hi --session")),
+            Some(ValueKind::Sessions)
+        );
+        assert_eq!(
+            value_kind(&words("debate topic --template")),
+            Some(ValueKind::Templates)
+        );
+        assert_eq!(
+            value_kind(&words("--trust plugin disable")),
+            Some(ValueKind::Plugins)
+        );
+        assert_eq!(
+            value_kind(&words("config set")),
+            Some(ValueKind::ConfigKeys)
+        );
+        assert_eq!(value_kind(&words("invoke")), Some(ValueKind::Clis));
+        assert_eq!(value_kind(&words("alias remove")), Some(ValueKind::Aliases));
+        assert_eq!(value_kind(&words("")), None);
+        assert_eq!(value_kind(&words("session")), None);
+        assert_eq!(value_kind(&words("config set audit_log")), None);
+        // Option values aren't positionals
+        assert_eq!(
+            value_kind(&words("--max-concurrency 4 session show")),
+            Some(ValueKind::Sessions)
+        );
+        assert_eq!(
+            value_kind(&words("invoke --timeout 30")),
+            Some(ValueKind::Clis)
+        );
+        assert_eq!(value_kind(&words("invoke -t 30")), Some(ValueKind::Clis));
+        assert_eq!(
+            value_kind(&words("plugin --options-file opts.txt remove")),
+            Some(ValueKind::Plugins)
+        );
+    }
+
+    #[test]
+    fn test_every_shell_generates() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut out = Vec::new();
+            clap_complete::generate(shell, &mut Cli::command(), "gptengage", &mut out);
+            assert!(!out.is_empty());
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Config command - Inspect and edit configuration
+
+use crate::audit::ProcessAudit;
+use crate::config::{ConfigManager, KNOWN_KEYS, PROFILE_ENV, PROJECT_CONFIG_FILE};
+use crate::utils::interaction::{can_ask, confirm};
+use std::path::Path;
+
+/// Top-level keys understood in config.json
+const FILE_KEYS: &[&str] = &[
+    "default_timeout",
+    "default_debate_rounds",
+    "clis",
+    "isolate_credentials",
+    "network_sandbox",
+    "audit_log",
+    "debate_history",
+    "latency_scheduling",
+    "time_format",
+    "plugins",
+    "profiles",
+    "aliases",
+    "max_output_bytes",
+    "invocation_logs",
+    "max_continuations",
+    "circuit_breaker_threshold",
+    "session_store",
+    "budget",
+    "history",
+    "dialects",
+    "context_windows",
+    "issue_trackers",
+    "forge",
+    "response_formats",
+    "trash_retention_days",
+    "presets",
+    "fallbacks",
+];
+
+/// Print the effective value of a key
+pub async fn get_config(key: String) -> anyhow::Result<()> {
+    let resolved = ConfigManager::shared()?;
+    match resolved.config.get(&key) {
+        Some(value) => println!("{}: {}", key, value),
+        None => println!("Config key '{}' not found", key),
+    }
+    Ok(())
+}
+
+/// Set a key in the config file
+pub async fn set_config(key: String, value: String) -> anyhow::Result<()> {
+    let mut config = ConfigManager::new()?;
+    config.set(&key, &value)?;
+    println!("Set {} = {}", key, value);
+    Ok(())
+}
+
+/// Reset a key in the config file to its default
+pub async fn unset_config(key: String) -> anyhow::Result<()> {
+    let mut config = ConfigManager::new()?;
+    config.unset(&key)?;
+    println!(
+        "Unset {} (now {})",
+        key,
+        config.get(&key).unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// List every known key with its effective value and source
+pub async fn list_config() -> anyhow::Result<()> {
+    let resolved = ConfigManager::shared()?;
+
+    println!("Configuration:");
+    let width = KNOWN_KEYS.iter().map(|k| k.len()).max().unwrap_or(0);
+    for (key, value, source) in resolved.entries() {
+        println!(
+            "  {:<width$}  {:<20}  ({})",
+            key,
+            value,
+            source,
+            width = width
+        );
+    }
+    println!();
+    println!("Precedence (lowest to highest): default, file, profile, project, env");
+    println!("  File: {}", ConfigManager::config_path()?.display());
+    match &resolved.profile {
+        Some(name) => println!("  Profile: {} (from {})", name, PROFILE_ENV),
+        None => println!("  Profile: none (set {} to select one)", PROFILE_ENV),
+    }
+    match &resolved.project_file {
+        Some(path) => println!("  Project: {}", path.display()),
+        None => println!("  Project: none ({} not found)", PROJECT_CONFIG_FILE),
+    }
+    println!("  Env: GPTENGAGE_<KEY> (e.g. GPTENGAGE_DEFAULT_TIMEOUT)");
+    println!("  Flags: --timeout overrides default_timeout, and --rounds (or a");
+    println!("         template's rounds) overrides default_debate_rounds, for one run");
+
+    Ok(())
+}
+
+/// Open the config file in $EDITOR and validate it on save
+pub async fn edit_config() -> anyhow::Result<()> {
+    let path = ConfigManager::config_path()?;
+    if !path.exists() {
+        // Write the default config so there is something to edit
+        ConfigManager::new()?;
+    }
+    let original = std::fs::read_to_string(&path)?;
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    loop {
+        let mut parts = editor.split_whitespace();
+        let program = parts.next().unwrap_or("vi");
+        let mut command = std::process::Command::new(program);
+        command.args(parts).arg(&path);
+        let audit = ProcessAudit::start(&command);
+        let status = command
+            .status()
+            .map_err(|e| anyhow::anyhow!("Failed to launch editor '{}': {}", editor, e))?;
+        audit.finish(&status);
+        if !status.success() {
+            std::fs::write(&path, &original)?;
+            anyhow::bail!("Editor exited with {}; config left unchanged", status);
+        }
+
+        let problems = check_config_file(&path);
+        if problems.is_empty() {
+            println!("✓ Config saved: {}", path.display());
+            return Ok(());
+        }
+
+        eprintln!("Config is invalid:");
+        for problem in &problems {
+            eprintln!("  - {}", problem);
+        }
+
+        if !can_ask() || !confirm("Edit again?", true)? {
+            std::fs::write(&path, &original)?;
+            anyhow::bail!("Config left unchanged");
+        }
+    }
+}
+
+/// Validate the config file and any project config
+pub async fn validate_config() -> anyhow::Result<()> {
+    let path = ConfigManager::config_path()?;
+    let mut problems = Vec::new();
+
+    if path.exists() {
+        problems.extend(
+            check_config_file(&path)
+                .into_iter()
+                .map(|p| format!("{}: {}", path.display(), p)),
+        );
+    }
+
+    // Resolving applies the profile, project, and env layers
+    let resolved = ConfigManager::resolve();
+    if let Err(e) = &resolved {
+        problems.push(format!("{:#}", e));
+    }
+
+    if problems.is_empty() {
+        println!("✓ Configuration is valid");
+        if let Ok(resolved) = resolved {
+            if let Some(project) = resolved.project_file {
+                println!("  (including {})", project.display());
+            }
+        }
+        return Ok(());
+    }
+
+    println!("✗ Configuration is invalid");
+    println!();
+    for problem in &problems {
+        println!("  - {}", problem);
+    }
+    Err(anyhow::anyhow!(
+        "{} problem(s) found in configuration",
+        problems.len()
+    ))
+}
+
+/// Parse and check a config file, returning every problem found
+fn check_config_file(path: &Path) -> Vec<String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return vec![format!("Failed to read: {}", e)],
+    };
+
+    let raw: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(raw) => raw,
+        Err(e) => return vec![format!("Invalid JSON: {}", e)],
+    };
+
+    let mut problems = Vec::new();
+    if let Some(object) = raw.as_object() {
+        for key in object.keys() {
+            if !FILE_KEYS.contains(&key.as_str()) {
+                problems.push(format!("Unknown key '{}'", key));
+            }
+        }
+    }
+
+    match serde_json::from_value::<ConfigManager>(raw) {
+        Ok(config) => problems.extend(config.validate()),
+        Err(e) => problems.push(format!("Invalid value: {}", e)),
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_config_file_reports_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        std::fs::write(&path, r#"{"default_timeout": 0, "colour": true}"#).unwrap();
+        let problems = check_config_file(&path);
+        assert_eq!(problems.len(), 2);
+        assert!(problems.iter().any(|p| p.contains("colour")));
+        assert!(problems.iter().any(|p| p.contains("default_timeout")));
+
+        std::fs::write(&path, r#"{"default_timeout": "soon"}"#).unwrap();
+        assert_eq!(check_config_file(&path).len(), 1);
+
+        std::fs::write(&path, r#"{"default_timeout": 30}"#).unwrap();
+        assert!(check_config_file(&path).is_empty());
+
+        // Everything `save` writes is a known key
+        let saved = serde_json::to_string(&ConfigManager::default()).unwrap();
+        std::fs::write(&path, saved).unwrap();
+        assert_eq!(check_config_file(&path), Vec::<String>::new());
+    }
+}
@@ -1,32 +1,260 @@
 //! Debate command - Multi-AI debate orchestration
 
+use crate::app::GptEngage;
 use crate::cli::StdinMode;
-use crate::invokers::{is_valid_cli, AccessMode};
-use crate::orchestrator::{AgentFile, DebateOrchestrator, Participant};
-use crate::templates::TemplateManager;
-use crate::utils::stdin::{format_piped_context, read_stdin_if_piped};
+use crate::commands::argument_map::ArgumentMap;
+use crate::commands::generate_agents::{
+    assign_installed_clis, build_agent_file, installed_clis, GenerationOptions,
+};
+use crate::commands::{issues, merge_request};
+use crate::config::DebatePreset;
+use crate::history::{topic_summary, DebateHistory, DebateRecord};
+use crate::invokers::dialect::Dialect;
+use crate::invokers::AccessMode;
+use crate::orchestrator::actions;
+use crate::orchestrator::citations;
+use crate::orchestrator::fact_check::FactCheck;
+use crate::orchestrator::frontmatter::split_frontmatter;
+use crate::orchestrator::health::Benching;
+use crate::orchestrator::incremental::SynthesizerOptions;
+use crate::orchestrator::narration::Verbosity;
+use crate::orchestrator::response_format::ResponseFormat;
+use crate::orchestrator::risks;
+use crate::orchestrator::round_robin::{pair_context, participant_names, round_robin_pairs};
+use crate::orchestrator::stance::{
+    compute_position_changes, participant_labels, track_stances, PositionChange,
+};
+use crate::orchestrator::{
+    AgentFile, DebateOrchestrator, DebateResult, Participant, PromptContext, RoundOptions,
+    RoundResponse,
+};
+use crate::personas::{persona_reference, PersonaRegistry};
+use crate::plugins::context::gather_context;
+use crate::plugins::PluginManager;
+use crate::utils::prompt_preview::{PromptPreview, ShowPrompt};
+use crate::utils::run_snapshot::{record_run, snapshot_run};
+use crate::utils::sidecar::Sidecar;
+use crate::utils::stdin::{format_piped_context, read_stdin_if_piped, StdinFile};
+use crate::utils::tokens::counter_for;
+use crate::utils::workspace::{track_changes, tracked_changes};
+use chrono::Utc;
+use std::fmt::Write;
+
+/// Formats `--output` accepts
+pub const OUTPUT_FORMATS: &[&str] = &["text", "json", "markdown", "dot", "mermaid", "csv"];
 
 /// Debate configuration options
+#[derive(Clone)]
 pub struct DebateOptions {
     pub topic: String,
     pub agent: Option<String>,
     pub instances: Option<usize>,
     pub model: Option<String>,
     pub participants: Option<String>,
-    pub agent_file: Option<String>,
+    /// Agent files to load; several are merged into one roster
+    pub agent_file: Vec<String>,
+    /// Comma-separated roles to generate agent definitions for in-memory
+    pub auto_agents: Option<String>,
+    /// CLI that generates the --auto-agents definitions
+    pub auto_agents_cli: String,
+    /// Also write the generated agent file here
+    pub save_agents: Option<String>,
     pub template: Option<String>,
     pub rounds: Option<usize>,
+    /// Rounds when neither --rounds nor a template sets them
+    /// (`default_debate_rounds` in config)
+    pub default_rounds: usize,
     pub output: String,
     pub timeout: u64,
+    /// Close each round after this many seconds with the responses so far
+    pub round_timeout: Option<u64>,
+    /// Duplicate a request that hasn't been answered after this many seconds
+    pub hedge_after: Option<u64>,
+    /// Bench participants after this many failed rounds in a row
+    pub bench_after: Option<usize>,
+    /// Re-probe benched participants before the final round
+    pub reprobe: bool,
     pub access_mode: AccessMode,
     pub stdin_as: StdinMode,
+    /// Context provider plugins whose output is added to the topic
+    pub context_providers: Vec<String>,
     pub synthesize: bool,
     pub synthesizer: String,
+    /// Update the synthesis after every round
+    pub incremental_synthesis: bool,
+    /// Check the synthesis against the transcript with this CLI
+    pub fact_check: Option<String>,
+    pub track_stances: bool,
+    pub stance_cli: String,
+    /// Extract a risk register after the debate
+    pub risk_register: bool,
+    pub risk_cli: String,
+    /// Extract follow-up tasks after the debate
+    pub action_items: bool,
+    pub action_items_cli: String,
+    /// File the action items as issues: `github` (with `gh`) or a
+    /// configured issue tracker
+    pub create_issues: Option<String>,
+    /// Comment the debate on a pull/merge request: `Some(None)` for the
+    /// current branch's
+    pub post_to_mr: Option<Option<u64>>,
+    /// Observers who critique the debate afterwards, as `cli:persona`
+    pub observers: Vec<String>,
+    /// Wrap each response in a collapsible block (markdown output)
+    pub collapse: bool,
+    /// Preview the assembled round-1 prompts
+    pub show_prompt: ShowPrompt,
+    /// Write the round-1 prompts to this directory instead of debating
+    pub dump_prompts: Option<String>,
+    /// Which context components go into participant prompts
+    pub context: PromptContext,
+    /// Pair participants into rotating 1v1 exchanges
+    pub round_robin: bool,
+    /// Buffer text output until the debate is over instead of printing
+    /// each response as it arrives
+    pub ordered: bool,
+    /// Show each participant's responses in its own tmux pane
+    pub sidecar: bool,
+    /// Format every answer must be in (default: `response_formats.debate`)
+    pub response_format: Option<ResponseFormat>,
+    /// Preset from config whose flags fill in those not given
+    pub preset: Option<String>,
+    /// How much is narrated while participants run
+    pub verbosity: Verbosity,
+}
+
+impl DebateOptions {
+    /// Options for a debate on `topic` with every flag at its default
+    pub fn new(topic: String, default_rounds: usize, timeout: u64) -> Self {
+        Self {
+            topic,
+            agent: None,
+            instances: None,
+            model: None,
+            participants: None,
+            agent_file: vec![],
+            auto_agents: None,
+            auto_agents_cli: "claude".to_string(),
+            save_agents: None,
+            template: None,
+            rounds: None,
+            default_rounds,
+            output: "text".to_string(),
+            timeout,
+            round_timeout: None,
+            hedge_after: None,
+            bench_after: None,
+            reprobe: false,
+            access_mode: AccessMode::ReadOnly,
+            stdin_as: StdinMode::Auto,
+            context_providers: vec![],
+            synthesize: false,
+            synthesizer: "claude".to_string(),
+            incremental_synthesis: false,
+            fact_check: None,
+            track_stances: false,
+            stance_cli: "claude".to_string(),
+            risk_register: false,
+            risk_cli: "claude".to_string(),
+            action_items: false,
+            action_items_cli: "claude".to_string(),
+            create_issues: None,
+            post_to_mr: None,
+            observers: vec![],
+            collapse: false,
+            show_prompt: ShowPrompt::Off,
+            dump_prompts: None,
+            context: PromptContext::default(),
+            round_robin: false,
+            ordered: false,
+            sidecar: false,
+            response_format: None,
+            preset: None,
+            verbosity: Verbosity::default(),
+        }
+    }
+
+    /// Whether responses are printed as they arrive (text output only)
+    fn streams(&self) -> bool {
+        self.output == "text" && !self.ordered
+    }
+
+    /// Whether the participants were chosen on the command line
+    fn has_roster(&self) -> bool {
+        self.agent.is_some()
+            || self.participants.is_some()
+            || !self.agent_file.is_empty()
+            || self.auto_agents.is_some()
+            || self.template.is_some()
+    }
+
+    /// Fill in the flags `preset` bundles. Options still at their defaults
+    /// take the preset's values; context providers are added ahead of the
+    /// command line's.
+    fn apply_preset(&mut self, preset: &DebatePreset) {
+        if !self.has_roster() {
+            self.template = preset.template.clone();
+        }
+        if self.rounds.is_none() {
+            self.rounds = preset.rounds;
+        }
+        if let Some(output) = &preset.output {
+            if self.output == "text" {
+                self.output = output.clone();
+            }
+        }
+        if let Some(synthesizer) = &preset.synthesizer {
+            if self.synthesizer == "claude" {
+                self.synthesizer = synthesizer.clone();
+            }
+        }
+        self.synthesize |= preset.synthesize || preset.synthesizer.is_some();
+        let mut providers = preset.context_providers.clone();
+        for provider in self.context_providers.drain(..) {
+            if !providers.contains(&provider) {
+                providers.push(provider);
+            }
+        }
+        self.context_providers = providers;
+        if let Some(rounds) = preset.history_last {
+            if self.context.history_rounds == PromptContext::default().history_rounds {
+                self.context.history_rounds = rounds;
+            }
+        }
+        self.context.persona_context &= !preset.no_persona_context;
+    }
 }
 
-/// Parse participants from format "cli:persona,cli:persona" or "cli:persona:model"
-fn parse_participants(participants_str: &str) -> anyhow::Result<Vec<Participant>> {
+/// Apply the `--preset` named in `options`, if any
+fn resolve_preset(engage: &GptEngage, options: &mut DebateOptions) -> anyhow::Result<()> {
+    let Some(name) = options.preset.take() else {
+        return Ok(());
+    };
+    let resolved = engage.config()?;
+    let Some(preset) = resolved.config.presets.get(&name) else {
+        let known: Vec<&str> = resolved.config.presets.keys().map(String::as_str).collect();
+        if known.is_empty() {
+            anyhow::bail!(
+                "No preset named '{}'. Define presets under `presets` with `gptengage config edit`",
+                name
+            );
+        }
+        anyhow::bail!(
+            "No preset named '{}'. Configured presets: {}",
+            name,
+            known.join(", ")
+        );
+    };
+    options.apply_preset(preset);
+    Ok(())
+}
+
+/// Parse participants from format "cli:persona,cli:persona" or "cli:persona:model".
+///
+/// A persona written as `@name` is taken from the persona library.
+pub(crate) fn parse_participants(participants_str: &str) -> anyhow::Result<Vec<Participant>> {
     let mut participants = Vec::new();
+    let mut registry: Option<PersonaRegistry> = None;
 
     for part in participants_str.split(',') {
         let part = part.trim();
@@ -34,12 +262,39 @@ fn parse_participants(participants_str: &str) -> anyhow::Result<Vec<Participant>
             continue;
         }
 
-        let parts: Vec<&str> = part.split(':').collect();
+        let parts: Vec<&str> = part.split(':').map(str::trim).collect();
+        if parts[0].is_empty() {
+            anyhow::bail!("Participant '{}' has no CLI name", part);
+        }
+        if parts.get(1).is_some_and(|persona| persona.is_empty()) {
+            anyhow::bail!(
+                "Participant '{}' has an empty persona. Use '{}' for no persona",
+                part,
+                parts[0]
+            );
+        }
+        if parts.get(2).is_some_and(|model| model.is_empty()) {
+            anyhow::bail!("Participant '{}' has an empty model", part);
+        }
         match parts.len() {
             1 => {
                 // Just CLI name, no persona
                 participants.push(Participant::new(parts[0].to_string(), None));
             }
+            2 | 3 if persona_reference(parts[1]).is_some() => {
+                // CLI:@persona or CLI:@persona:model format
+                let name = persona_reference(parts[1]).unwrap_or_default();
+                if registry.is_none() {
+                    registry = Some(PersonaRegistry::new()?);
+                }
+                let persona = registry.as_ref().unwrap().resolve(name)?;
+                let model = parts.get(2).map(|m| m.to_string());
+                participants.push(
+                    persona
+                        .to_agent_definition(parts[0], model)
+                        .to_participant(),
+                );
+            }
             2 => {
                 // CLI:persona format
                 participants.push(Participant::new(
@@ -72,16 +327,84 @@ fn parse_participants(participants_str: &str) -> anyhow::Result<Vec<Participant>
 }
 
 /// Run a debate between specified participants or default CLIs
-pub async fn run_debate(mut options: DebateOptions) -> anyhow::Result<()> {
-    // Handle stdin input based on mode
-    if let Some(stdin_content) = read_stdin_if_piped() {
+pub async fn run_debate(engage: &GptEngage, mut options: DebateOptions) -> anyhow::Result<()> {
+    resolve_preset(engage, &mut options)?;
+    let output = options.output.clone();
+    let collapse = options.collapse;
+    let streamed = options.streams();
+    let merge_request = match options.post_to_mr {
+        Some(number) => Some((merge_request::forge(engage, number)?, number)),
+        None => None,
+    };
+    if let Some(result) = conduct_debate(engage, options).await? {
+        if streamed {
+            // The rounds were already printed as responses arrived
+            print!("{}", render_text(&result, false)?);
+        } else {
+            print!("{}", render_result(&result, &output, collapse)?);
+        }
+        if let Some((forge, number)) = merge_request {
+            merge_request::post_comment(&forge, number, &render_markdown(&result, true)?).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Run a debate and return its result, or `None` when only prompts were
+/// previewed (--show-prompt-only)
+pub(crate) async fn conduct_debate(
+    engage: &GptEngage,
+    mut options: DebateOptions,
+) -> anyhow::Result<Option<DebateResult>> {
+    let started = std::time::Instant::now();
+    let mut round_options = RoundOptions {
+        stream: options.streams(),
+        round_timeout: options.round_timeout.map(std::time::Duration::from_secs),
+        hedge_after: options.hedge_after.map(std::time::Duration::from_secs),
+        bench_after: options.bench_after,
+        reprobe: options.reprobe,
+        latency: engage
+            .config()
+            .is_ok_and(|resolved| resolved.config.latency_scheduling),
+        events: None,
+        sidecar: None,
+    };
+    // Frontmatter may open the topic argument or piped stdin
+    let (mut frontmatter, topic) = split_frontmatter(&options.topic)?;
+    options.topic = topic;
+
+    // The topic as asked, without piped context, for the debate history
+    let mut asked_topic = options.topic.clone();
+
+    // Size of piped context in the topic, for --show-prompt
+    let mut stdin_len = 0;
+    let mut request_len = options.topic.len();
+
+    // Handle stdin input based on mode; a saved stdin file lives until the
+    // debate is done
+    let mut _stdin_file = None;
+    if let Some(mut stdin_content) = read_stdin_if_piped() {
+        if matches!(options.stdin_as, StdinMode::Auto | StdinMode::Context) {
+            let (piped, content) = split_frontmatter(&stdin_content)?;
+            if piped.is_some() {
+                if frontmatter.is_some() {
+                    anyhow::bail!(
+                        "Both the topic and piped stdin start with frontmatter; give it in one of them"
+                    );
+                }
+                frontmatter = piped;
+                stdin_content = content;
+            }
+        }
         match options.stdin_as {
             StdinMode::Auto => {
                 if options.topic.is_empty() {
                     // No topic provided, use stdin as topic
+                    request_len = stdin_content.len();
                     options.topic = stdin_content;
                 } else {
                     // Topic provided, prepend stdin as context
+                    stdin_len = stdin_content.len();
                     options.topic = format!(
                         "{}\n\n{}",
                         format_piped_context(&stdin_content),
@@ -91,12 +414,22 @@ pub async fn run_debate(mut options: DebateOptions) -> anyhow::Result<()> {
             }
             StdinMode::Context => {
                 // Always prepend stdin as context
+                stdin_len = stdin_content.len();
                 options.topic = format!(
                     "{}\n\n{}",
                     format_piped_context(&stdin_content),
                     options.topic
                 );
             }
+            StdinMode::File => {
+                if options.topic.is_empty() {
+                    anyhow::bail!("--stdin-as file needs a topic to go with the piped input");
+                }
+                let file = StdinFile::create(&stdin_content)?;
+                stdin_len = file.reference().len();
+                options.topic = format!("{}\n\n{}", file.reference(), options.topic);
+                _stdin_file = Some(file);
+            }
             StdinMode::Ignore => {
                 // Do nothing with stdin
             }
@@ -109,21 +442,48 @@ pub async fn run_debate(mut options: DebateOptions) -> anyhow::Result<()> {
             "Topic is required. Provide as argument or pipe via stdin."
         ));
     }
+    if asked_topic.is_empty() {
+        asked_topic = options.topic.clone();
+    }
+
+    // Size of context provider output in the topic, for --show-prompt
+    let mut provided_len = 0;
+    if !options.context_providers.is_empty() {
+        let provided = gather_context(
+            engage.plugins()?,
+            &options.context_providers,
+            &asked_topic,
+            options.timeout,
+        )
+        .await?;
+        if !provided.is_empty() {
+            provided_len = provided.len();
+            options.topic = format!("{}\n\n{}", provided, options.topic);
+        }
+    }
+
+    // Size of the frontmatter brief in the topic, for --show-prompt
+    let mut brief_len = 0;
+    if let Some(ref frontmatter) = frontmatter {
+        let brief = frontmatter.prompt_block();
+        brief_len = brief.len();
+        options.topic = format!("{}\n\n{}", brief, options.topic);
+    }
 
     println!("GPT ENGAGE DEBATE");
     println!("Topic: {}", options.topic);
 
     // Default rounds if not specified
-    let default_rounds = 3;
+    let default_rounds = options.default_rounds;
 
     // Parse participants from various sources
-    let result = if let Some(agent_cli) = options.agent {
+    let (topic, participants, rounds) = if let Some(agent_cli) = options.agent {
         // Multi-instance mode: create N instances of the same CLI
         let num_instances = options.instances.unwrap_or(3);
         let rounds = options.rounds.unwrap_or(default_rounds);
 
         // Validate CLI name (built-in or plugin)
-        if !is_valid_cli(&agent_cli) {
+        if !engage.invokers.is_valid(&agent_cli) {
             return Err(anyhow::anyhow!(
                 "Invalid CLI '{}'. Must be a built-in CLI (claude, codex, gemini) or an installed plugin.",
                 agent_cli
@@ -148,17 +508,10 @@ pub async fn run_debate(mut options: DebateOptions) -> anyhow::Result<()> {
             .map(|_| Participant::with_model(agent_cli.clone(), model.clone(), None))
             .collect();
 
-        DebateOrchestrator::run_debate_with_participants(
-            &options.topic,
-            participants,
-            rounds,
-            options.timeout,
-            options.access_mode,
-        )
-        .await?
+        (options.topic.clone(), participants, rounds)
     } else if let Some(template_name) = options.template {
         // Load and use template
-        let template_manager = TemplateManager::new()?;
+        let template_manager = engage.templates()?;
         let template = template_manager.get_template(&template_name).ok_or_else(|| {
             anyhow::anyhow!(
                 "Template '{}' not found. Use 'gptengage template list' to see available templates.",
@@ -173,7 +526,7 @@ pub async fn run_debate(mut options: DebateOptions) -> anyhow::Result<()> {
         let topic = template.apply_context(&options.topic);
 
         // Convert template participants to debate participants
-        let participants = template.to_participants();
+        let participants = template.to_participants()?;
 
         println!(
             "Using template: {} ({})",
@@ -185,34 +538,56 @@ pub async fn run_debate(mut options: DebateOptions) -> anyhow::Result<()> {
         }
         println!();
 
-        DebateOrchestrator::run_debate_with_participants(
-            &topic,
-            participants,
-            rounds,
+        (topic, participants, rounds)
+    } else if let Some(roles) = options.auto_agents {
+        println!("Generating agents for roles: {}", roles);
+        let generation = GenerationOptions::new(
+            &options.auto_agents_cli,
             options.timeout,
             options.access_mode,
-        )
-        .await?
-    } else if let Some(agent_file) = options.agent_file {
-        // Load and validate agent file
-        let agent_file = AgentFile::load(&agent_file)?;
+        );
+        let (mut agent_file, _) =
+            build_agent_file(engage, &options.topic, &roles, &generation).await?;
+        assign_installed_clis(
+            &mut agent_file.participants,
+            &installed_clis(engage).await,
+            &options.auto_agents_cli,
+        );
+        if let Some(ref path) = options.save_agents {
+            agent_file.save(path)?;
+            println!("Saved agent file: {}", path);
+        }
         let participants = agent_file.to_participants();
         let rounds = options.rounds.unwrap_or(default_rounds);
 
-        println!("Loaded {} agent(s) from file:", participants.len());
+        println!("Generated {} agent(s):", participants.len());
         for p in &participants {
             println!("  - {}", p.display_name());
         }
         println!();
 
-        DebateOrchestrator::run_debate_with_participants(
-            &options.topic,
-            participants,
-            rounds,
-            options.timeout,
-            options.access_mode,
-        )
-        .await?
+        (options.topic.clone(), participants, rounds)
+    } else if !options.agent_file.is_empty() {
+        // Load, validate, and merge agent files
+        let agent_file = AgentFile::load_all(&options.agent_file)?;
+        let participants = agent_file.to_participants();
+        let rounds = options.rounds.unwrap_or(default_rounds);
+
+        if options.agent_file.len() == 1 {
+            println!("Loaded {} agent(s) from file:", participants.len());
+        } else {
+            println!(
+                "Loaded {} agent(s) from {} files:",
+                participants.len(),
+                options.agent_file.len()
+            );
+        }
+        for p in &participants {
+            println!("  - {}", p.display_name());
+        }
+        println!();
+
+        (options.topic.clone(), participants, rounds)
     } else if let Some(participants_str) = options.participants {
         let participants = parse_participants(&participants_str)?;
         let rounds = options.rounds.unwrap_or(default_rounds);
@@ -222,179 +597,1241 @@ pub async fn run_debate(mut options: DebateOptions) -> anyhow::Result<()> {
             println!("  - {}", p.display_name());
         }
         println!();
-        DebateOrchestrator::run_debate_with_participants(
-            &options.topic,
-            participants,
-            rounds,
-            options.timeout,
-            options.access_mode,
-        )
-        .await?
+        (options.topic.clone(), participants, rounds)
     } else {
         let rounds = options.rounds.unwrap_or(default_rounds);
 
         println!("Using default participants: Claude, Codex, Gemini");
         println!();
-        DebateOrchestrator::run_debate(&options.topic, rounds, options.timeout, options.access_mode)
-            .await?
+        (
+            options.topic.clone(),
+            DebateOrchestrator::default_participants(),
+            rounds,
+        )
     };
 
-    // Generate synthesis if requested
-    let mut result = result;
-    if options.synthesize {
-        let synthesis = DebateOrchestrator::generate_synthesis(
+    if options.output == "csv" && !options.risk_register {
+        anyhow::bail!("--output csv writes the risk register; add --risk-register");
+    }
+
+    if let Some(boundary) = options.context.converge_after {
+        if boundary >= rounds {
+            anyhow::bail!(
+                "--converge-after {} leaves no convergence rounds in a {}-round debate",
+                boundary,
+                rounds
+            );
+        }
+    }
+
+    // Look the tracker up before the debate, not after it has been paid for
+    let issue_tracker = match options.create_issues.as_deref() {
+        None | Some(issues::GITHUB) => None,
+        Some(name) => Some(issues::tracker(engage, name)?),
+    };
+    let observers = match options.observers.is_empty() {
+        true => Vec::new(),
+        false => parse_participants(&options.observers.join(","))?,
+    };
+    for observer in &observers {
+        if !engage.invokers.is_valid(&observer.cli) {
+            anyhow::bail!(
+                "Invalid observer CLI '{}'. Must be a built-in CLI (claude, codex, gemini) or an installed plugin.",
+                observer.cli
+            );
+        }
+    }
+
+    if options.show_prompt != ShowPrompt::Off || options.dump_prompts.is_some() {
+        let template_len = topic.len().saturating_sub(options.topic.len());
+        let prompts = round_one_prompts(
+            &topic,
+            &participants,
+            options.context,
+            options.round_robin,
+            engage.plugins().ok(),
+        );
+        if options.show_prompt != ShowPrompt::Off {
+            for (participant, (base_len, prompt)) in participants.iter().zip(&prompts) {
+                let preview = PromptPreview::new(
+                    format!("{} (round 1)", participant.display_name()),
+                    prompt.as_str(),
+                )
+                .counted_with(counter_for(&participant.cli, participant.model.as_deref()).as_ref())
+                .part(
+                    "persona scaffolding",
+                    prompt.len().saturating_sub(*base_len),
+                )
+                .part("template context", template_len)
+                .part("topic brief", brief_len)
+                .part("context providers", provided_len)
+                .part("piped stdin", stdin_len)
+                .part("topic", request_len)
+                .render()?;
+                options.show_prompt.emit(&preview);
+            }
+        }
+        if let Some(ref dir) = options.dump_prompts {
+            let prompts: Vec<String> = prompts.into_iter().map(|(_, prompt)| prompt).collect();
+            let files = dump_prompts(std::path::Path::new(dir), &participants, &prompts)?;
+            println!("Wrote {} round-1 prompt(s) to {}:", files.len(), dir);
+            for file in &files {
+                println!("  - {}", file);
+            }
+            return Ok(None);
+        }
+        if options.show_prompt == ShowPrompt::Only {
+            return Ok(None);
+        }
+    }
+
+    let names = participant_names(&participants);
+    let sidecar = match options.sidecar {
+        true => Some(std::sync::Arc::new(Sidecar::open(&names)?)),
+        false => None,
+    };
+    round_options.sidecar = sidecar.clone();
+    let (run, snapshot) = match options.access_mode {
+        AccessMode::WorkspaceWrite => (
+            snapshot_run(&format!("debate: {}", topic_summary(&asked_topic)))?,
+            track_changes(),
+        ),
+        AccessMode::ReadOnly => (None, None),
+    };
+    let response_format = ResponseFormat::for_command(
+        options.response_format.clone(),
+        &engage.config()?.config.response_formats,
+        "debate",
+    )?;
+    let orchestrator = engage
+        .orchestrator()
+        .with_response_format(response_format)
+        .with_verbosity(options.verbosity);
+    let synthesizer = SynthesizerOptions {
+        cli: options.synthesizer.clone(),
+        timeout: options.timeout,
+        access_mode: options.access_mode,
+    };
+
+    // The debate's orchestrator (and its clones) hold the only sender, so
+    // the incremental synthesis sees the rounds end once the debate does.
+    // Returning early drops the set, cancelling the synthesis and its CLI.
+    let mut draft = tokio::task::JoinSet::new();
+    let debater = if options.synthesize && options.incremental_synthesis {
+        let (sink, rounds) = tokio::sync::mpsc::unbounded_channel();
+        draft.spawn(orchestrator.clone().synthesize_incrementally(
+            topic.clone(),
+            rounds,
+            synthesizer.clone(),
+        ));
+        orchestrator.clone().with_round_sink(sink)
+    } else {
+        orchestrator.clone()
+    };
+
+    let debate = if options.round_robin {
+        debater
+            .run_round_robin_debate(
+                &topic,
+                participants,
+                rounds,
+                options.timeout,
+                options.access_mode,
+                options.context,
+                round_options,
+            )
+            .await
+    } else {
+        debater
+            .run_debate_with_participants(
+                &topic,
+                participants,
+                rounds,
+                options.timeout,
+                options.access_mode,
+                options.context,
+                round_options,
+            )
+            .await
+    };
+    drop(debater);
+    let mut result = debate?;
+    // The transcript keeps the frontmatter as metadata, not in the topic
+    if let Some(frontmatter) = frontmatter {
+        let brief = format!("{}\n\n", frontmatter.prompt_block());
+        result.topic = result.topic.replacen(&brief, "", 1);
+        result.frontmatter = Some(frontmatter);
+    }
+
+    // Classify per-round positions if requested
+    if options.track_stances {
+        result.stances = track_stances(
+            &engage.invokers,
             &result,
-            &options.synthesizer,
+            &options.stance_cli,
             options.timeout,
             options.access_mode,
         )
         .await?;
-        result.synthesis = Some(synthesis);
     }
 
-    // Output results based on format
-    match options.output.as_str() {
-        "json" => {
-            let json = serde_json::to_string_pretty(&result)?;
-            println!("{}", json);
+    // Generate synthesis if requested
+    if let Some(draft) = draft.join_next().await {
+        let draft = draft?;
+        let position_changes = compute_position_changes(&result.stances);
+        let mut synthesis = orchestrator
+            .finish_synthesis(&topic, draft, position_changes, &synthesizer)
+            .await?;
+        citations::verify(&mut synthesis, &result.rounds);
+        result.synthesis = Some(synthesis);
+    } else if options.synthesize {
+        let synthesis = orchestrator
+            .generate_synthesis(
+                &result,
+                &options.synthesizer,
+                options.timeout,
+                options.access_mode,
+            )
+            .await?;
+        result.synthesis = Some(synthesis);
+    }
+    if let Some(sidecar) = &sidecar {
+        if let Some(synthesis) = &result.synthesis {
+            sidecar.synthesis(synthesis);
         }
-        "markdown" => {
-            print_markdown(&result)?;
+        sidecar.finish();
+    }
+    if let Some(ref checker) = options.fact_check {
+        result.fact_check = Some(
+            orchestrator
+                .fact_check(&result, checker, options.timeout, options.access_mode)
+                .await?,
+        );
+    }
+
+    if options.risk_register {
+        result.risks = orchestrator
+            .extract_risks(
+                &result,
+                &options.risk_cli,
+                options.timeout,
+                options.access_mode,
+            )
+            .await?;
+    }
+    if options.action_items {
+        result.action_items = orchestrator
+            .extract_action_items(
+                &result,
+                &options.action_items_cli,
+                options.timeout,
+                options.access_mode,
+            )
+            .await?;
+        if options.create_issues.is_some() {
+            issues::create_issues(&mut result, issue_tracker.as_ref()).await?;
         }
-        _ => {
-            print_text(&result)?;
+    }
+    if !observers.is_empty() {
+        result.critiques = orchestrator
+            .observe(&result, &observers, options.timeout, options.access_mode)
+            .await;
+    }
+
+    result.workspace_changes = tracked_changes(snapshot.as_ref());
+    record_run(run, result.workspace_changes.as_ref());
+    record_history(engage, &result, &asked_topic, names, rounds, started);
+    Ok(Some(result))
+}
+
+/// Each participant's round-1 prompt, with the length of the shared
+/// context it was built from
+fn round_one_prompts(
+    topic: &str,
+    participants: &[Participant],
+    context: PromptContext,
+    round_robin: bool,
+    plugins: Option<&PluginManager>,
+) -> Vec<(usize, String)> {
+    let names = participant_names(participants);
+    let mut partners: Vec<Option<usize>> = vec![None; participants.len()];
+    for (a, b) in round_robin_pairs(participants.len(), 0) {
+        partners[a] = b;
+    }
+    participants
+        .iter()
+        .enumerate()
+        .map(|(i, participant)| {
+            // Round-robin prompts are the opening side of each exchange
+            let base_context = context.phase_context(
+                1,
+                if round_robin {
+                    pair_context(topic, 1, partners[i].map(|p| names[p].as_str()), None, "")
+                } else {
+                    DebateOrchestrator::round_context(topic, 1, &[], context.history_rounds)
+                },
+            );
+            let prompt = Dialect::for_cli(&participant.cli, plugins)
+                .adapt(&context.participant_prompt(participant, &base_context));
+            (base_context.len(), prompt)
+        })
+        .collect()
+}
+
+/// Write each prompt to `<dir>/<NN>-<participant>.txt`, exactly as it
+/// would be sent, and return the file names
+fn dump_prompts(
+    dir: &std::path::Path,
+    participants: &[Participant],
+    prompts: &[String],
+) -> anyhow::Result<Vec<String>> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", dir.display(), e))?;
+    let mut files = Vec::new();
+    for (i, (participant, prompt)) in participants.iter().zip(prompts).enumerate() {
+        let file = format!("{:02}-{}.txt", i + 1, slugify(&participant.display_name()));
+        let path = dir.join(&file);
+        std::fs::write(&path, prompt)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+        files.push(file);
+    }
+    Ok(files)
+}
+
+/// Add a finished debate to the debate history, unless it is turned off.
+/// Failing to write it only warns.
+fn record_history(
+    engage: &GptEngage,
+    result: &DebateResult,
+    topic: &str,
+    participants: Vec<String>,
+    rounds: usize,
+    started: std::time::Instant,
+) {
+    let Ok(resolved) = engage.config() else {
+        return;
+    };
+    let config = &resolved.config;
+    if !config.debate_history {
+        return;
+    }
+
+    let responses: usize = result.rounds.iter().map(Vec::len).sum();
+    let mut record = DebateRecord {
+        timestamp: Utc::now(),
+        topic: topic_summary(topic),
+        topic_bytes: topic.len(),
+        failures: (participants.len() * rounds).saturating_sub(responses),
+        participants,
+        rounds,
+        responses,
+        recommendation: result
+            .synthesis
+            .as_ref()
+            .and_then(|s| s.recommendation.clone()),
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    if config.history.is_hash_only() {
+        if let Err(e) = record.redact(topic) {
+            eprintln!("Warning: Failed to record debate history: {}", e);
+            return;
         }
     }
+    if let Err(e) = DebateHistory::open().and_then(|history| history.append(&record)) {
+        eprintln!("Warning: Failed to record debate history: {}", e);
+    }
+}
+
+/// Render a debate result in an output format (text, json, markdown, dot,
+/// mermaid, or csv)
+fn render_result(result: &DebateResult, output: &str, collapse: bool) -> anyhow::Result<String> {
+    match output {
+        "json" => Ok(format!("{}\n", serde_json::to_string_pretty(result)?)),
+        "csv" => Ok(risks::to_csv(&result.risks)),
+        "markdown" => render_markdown(result, collapse),
+        "dot" => ArgumentMap::from_result(result).to_dot(),
+        "mermaid" => ArgumentMap::from_result(result).to_mermaid(),
+        _ => render_text(result, true),
+    }
+}
+
+/// Batch debate options (`debate batch`)
+pub struct BatchOptions {
+    /// File with one topic per line
+    pub topics_file: String,
+    /// Directory for transcripts and the index
+    pub output_dir: String,
+    /// Settings shared by every debate; the topic is replaced per debate
+    pub debate: DebateOptions,
+}
 
+/// Outcome of one debate in a batch
+struct BatchEntry {
+    topic: String,
+    /// Transcript file name, relative to the output directory
+    file: Option<String>,
+    /// One-line summary, or the error for a failed debate
+    outcome: Result<String, String>,
+}
+
+/// Run one debate per topic in a topics file, sequentially, writing each
+/// transcript and an `index.md` summarizing the batch
+pub async fn run_debate_batch(engage: &GptEngage, options: BatchOptions) -> anyhow::Result<()> {
+    let content = std::fs::read_to_string(&options.topics_file).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to read topics file '{}': {}",
+            options.topics_file,
+            e
+        )
+    })?;
+    let topics = parse_topics(&content);
+    if topics.is_empty() {
+        anyhow::bail!("No topics found in '{}'", options.topics_file);
+    }
+
+    let dir = std::path::Path::new(&options.output_dir);
+    std::fs::create_dir_all(dir)?;
+    let extension = match options.debate.output.as_str() {
+        "json" => "json",
+        "markdown" => "md",
+        "dot" => "dot",
+        "mermaid" => "mmd",
+        "csv" => "csv",
+        _ => "txt",
+    };
+
+    let mut entries = Vec::new();
+    for (i, topic) in topics.iter().enumerate() {
+        println!("[{}/{}] {}", i + 1, topics.len(), topic);
+        let mut debate = options.debate.clone();
+        debate.topic = topic.clone();
+
+        let entry = match conduct_debate(engage, debate).await {
+            Ok(Some(result)) => {
+                let file = format!("{:02}-{}.{}", i + 1, topic_file_stem(topic), extension);
+                let rendered =
+                    render_result(&result, &options.debate.output, options.debate.collapse)?;
+                std::fs::write(dir.join(&file), rendered)?;
+                println!("  ✓ Wrote {}", dir.join(&file).display());
+                BatchEntry {
+                    topic: topic.clone(),
+                    file: Some(file),
+                    outcome: Ok(batch_summary(&result)),
+                }
+            }
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("  ✗ Debate failed: {}", e);
+                BatchEntry {
+                    topic: topic.clone(),
+                    file: None,
+                    outcome: Err(e.to_string()),
+                }
+            }
+        };
+        entries.push(entry);
+        println!();
+    }
+
+    let index = dir.join("index.md");
+    std::fs::write(&index, render_batch_index(&entries)?)?;
+
+    let failed = entries.iter().filter(|e| e.outcome.is_err()).count();
+    println!(
+        "✓ {} of {} debate(s) completed. Index: {}",
+        entries.len() - failed,
+        entries.len(),
+        index.display()
+    );
+    if failed > 0 {
+        anyhow::bail!("{} debate(s) failed; see {}", failed, index.display());
+    }
     Ok(())
 }
 
-fn print_text(result: &crate::orchestrator::DebateResult) -> anyhow::Result<()> {
-    for (round_num, responses) in result.rounds.iter().enumerate() {
-        println!("ROUND {}", round_num + 1);
-        println!("────────────────────────────────────────");
+/// Topics from a topics file: one per line, skipping blank lines and
+/// `#` comments
+fn parse_topics(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Short file-name-safe stem for a topic
+fn topic_file_stem(topic: &str) -> String {
+    let slug: String = slugify(topic).chars().take(50).collect();
+    match slug.trim_end_matches('-') {
+        "" => "debate".to_string(),
+        stem => stem.to_string(),
+    }
+}
+
+/// One-line summary of a debate for the batch index
+fn batch_summary(result: &DebateResult) -> String {
+    match &result.synthesis {
+        Some(synthesis) => synthesis
+            .recommendation
+            .clone()
+            .unwrap_or_else(|| synthesis.summary.clone()),
+        None => format!(
+            "{} round(s), {} participant(s)",
+            result.rounds.len(),
+            result.rounds.first().map(|r| r.len()).unwrap_or(0)
+        ),
+    }
+}
+
+/// Render the batch index as a markdown table
+fn render_batch_index(entries: &[BatchEntry]) -> anyhow::Result<String> {
+    // Keep each cell on one line and away from the column separators
+    let cell = |text: &str| {
+        text.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace('|', "\\|")
+    };
+
+    let mut out = String::new();
+    writeln!(out, "# Debate Batch")?;
+    writeln!(out)?;
+    writeln!(out, "| # | Topic | Transcript | Outcome |")?;
+    writeln!(out, "|---|-------|------------|---------|")?;
+    for (i, entry) in entries.iter().enumerate() {
+        let transcript = match &entry.file {
+            Some(file) => format!("[{}]({})", file, file),
+            None => "-".to_string(),
+        };
+        let outcome = match &entry.outcome {
+            Ok(summary) => cell(summary),
+            Err(error) => format!("Failed: {}", cell(error)),
+        };
+        writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            i + 1,
+            cell(&entry.topic),
+            transcript,
+            outcome
+        )?;
+    }
+    Ok(out)
+}
+
+/// Render a debate result as plain text.
+///
+/// Without `include_rounds` only the summary and synthesis are rendered,
+/// for when the rounds were already streamed as they arrived.
+fn render_text(
+    result: &crate::orchestrator::DebateResult,
+    include_rounds: bool,
+) -> anyhow::Result<String> {
+    let mut out = String::new();
+    let rounds: &[Vec<RoundResponse>] = if include_rounds { &result.rounds } else { &[] };
+    for (round_num, responses) in rounds.iter().enumerate() {
+        writeln!(out, "ROUND {}", round_num + 1)?;
+        writeln!(out, "────────────────────────────────────────")?;
 
         for response in responses {
-            println!("{}:", response.display_name());
-            println!("{}", response.response);
-            println!();
+            writeln!(out, "{}:", response.display_name())?;
+            writeln!(out, "{}", response.response)?;
+            writeln!(out)?;
         }
     }
 
-    println!("DEBATE COMPLETE");
-    println!();
-    println!(
+    writeln!(out, "DEBATE COMPLETE")?;
+    writeln!(out)?;
+    writeln!(
+        out,
         "Summary: {} round(s), {} participant(s)",
         result.rounds.len(),
         result.rounds.first().map(|r| r.len()).unwrap_or(0)
-    );
+    )?;
+    if !result.benched.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Benched:")?;
+        for benching in &result.benched {
+            writeln!(out, "  • {}", benching_note(benching))?;
+        }
+    }
+    if let Some(ref changes) = result.workspace_changes {
+        writeln!(out)?;
+        writeln!(out, "Workspace changes:")?;
+        write!(out, "{}", changes.render()?)?;
+    }
+
+    if !result.risks.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Risk register:")?;
+        for (i, risk) in result.risks.iter().enumerate() {
+            writeln!(
+                out,
+                "  {}. {} (likelihood {}, impact {})",
+                i + 1,
+                risk.description,
+                risk.likelihood,
+                risk.impact
+            )?;
+            if !risk.mitigation.is_empty() {
+                writeln!(out, "     Mitigation: {}", risk.mitigation)?;
+            }
+            if let Some(ref owner) = risk.owner {
+                writeln!(out, "     Owner: {}", owner)?;
+            }
+        }
+    }
+
+    if !result.action_items.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Action items:")?;
+        for (i, item) in result.action_items.iter().enumerate() {
+            let owner = match &item.owner {
+                Some(owner) => format!(", {}", owner),
+                None => String::new(),
+            };
+            writeln!(
+                out,
+                "  {}. [{}{}] {}",
+                i + 1,
+                item.priority,
+                owner,
+                item.task
+            )?;
+            if let Some(ref issue) = item.issue {
+                writeln!(out, "     Issue: {}", issue)?;
+            }
+        }
+    }
+
+    for critique in &result.critiques {
+        writeln!(out)?;
+        writeln!(out, "Critique from observer {}:", critique.observer)?;
+        for line in critique.critique.lines() {
+            writeln!(out, "  {}", line)?;
+        }
+    }
 
     // Print synthesis if present
     if let Some(ref synthesis) = result.synthesis {
-        println!();
-        println!("────────────────────────────────────────");
-        println!("SYNTHESIS");
-        println!("────────────────────────────────────────");
-        println!();
-        println!("Summary:");
-        println!("  {}", synthesis.summary);
-        println!();
+        writeln!(out)?;
+        writeln!(out, "────────────────────────────────────────")?;
+        writeln!(out, "SYNTHESIS")?;
+        writeln!(out, "────────────────────────────────────────")?;
+        writeln!(out)?;
+        writeln!(out, "Summary:")?;
+        writeln!(out, "  {}", synthesis.summary)?;
+        writeln!(out)?;
 
         if !synthesis.consensus_points.is_empty() {
-            println!("Consensus:");
+            writeln!(out, "Consensus:")?;
             for point in &synthesis.consensus_points {
-                println!("  • {}", point);
+                writeln!(out, "  • {}", point)?;
             }
-            println!();
+            writeln!(out)?;
         }
 
         if !synthesis.disagreement_points.is_empty() {
-            println!("Disagreements:");
+            writeln!(out, "Disagreements:")?;
             for point in &synthesis.disagreement_points {
-                println!("  • {}", point);
+                writeln!(out, "  • {}", point)?;
             }
-            println!();
+            writeln!(out)?;
         }
 
         if !synthesis.key_insights.is_empty() {
-            println!("Key Insights:");
+            writeln!(out, "Key Insights:")?;
             for insight in &synthesis.key_insights {
-                println!("  • {}", insight);
+                writeln!(out, "  • {}", insight)?;
             }
-            println!();
+            writeln!(out)?;
         }
 
         if let Some(ref recommendation) = synthesis.recommendation {
-            println!("Recommendation:");
-            println!("  {}", recommendation);
-            println!();
+            writeln!(out, "Recommendation:")?;
+            writeln!(out, "  {}", recommendation)?;
+            writeln!(out)?;
+        }
+
+        render_text_position_changes(&mut out, &synthesis.position_changes)?;
+        if !synthesis.unverified_citations.is_empty() {
+            writeln!(
+                out,
+                "Citations not found in the transcript: {}",
+                synthesis.unverified_citations.join(" ")
+            )?;
+            writeln!(out)?;
+        }
+        if let Some(ref check) = result.fact_check {
+            render_text_fact_check(&mut out, check)?;
         }
     } else {
-        println!("Tip: Use --output json for machine-readable output");
+        render_text_position_changes(&mut out, &compute_position_changes(&result.stances))?;
+        writeln!(out, "Tip: Use --output json for machine-readable output")?;
+    }
+
+    Ok(out)
+}
+
+/// The fact check of a synthesis, for text output
+fn render_text_fact_check(out: &mut String, check: &FactCheck) -> std::fmt::Result {
+    if check.flagged.is_empty() {
+        writeln!(
+            out,
+            "Fact check ({}): every claim is supported by the transcript",
+            check.checker
+        )?;
+        return writeln!(out);
+    }
+    writeln!(
+        out,
+        "Fact check ({}): {} unsupported claim(s)",
+        check.checker,
+        check.flagged.len()
+    )?;
+    for flagged in &check.flagged {
+        writeln!(out, "  ⚠ {}", flagged.claim)?;
+        writeln!(out, "    {}", flagged.reason)?;
+        for passage in &flagged.passages {
+            writeln!(out, "    > {}", passage)?;
+        }
+    }
+    writeln!(out)
+}
+
+/// One line describing a benched participant
+fn benching_note(benching: &Benching) -> String {
+    let mut note = format!(
+        "{} sat out from round {} after {} consecutive failure(s)",
+        benching.participant, benching.from_round, benching.failures
+    );
+    if let Some(round) = benching.rejoined {
+        note.push_str(&format!(", rejoined for round {}", round));
     }
+    note
+}
 
+fn render_text_position_changes(out: &mut String, changes: &[PositionChange]) -> std::fmt::Result {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "Position Changes:")?;
+    for change in changes {
+        writeln!(
+            out,
+            "  • {} (round {}): {} → {}",
+            change.participant, change.round, change.from, change.to
+        )?;
+    }
+    writeln!(out)?;
     Ok(())
 }
 
-fn print_markdown(result: &crate::orchestrator::DebateResult) -> anyhow::Result<()> {
-    println!("# {}", result.topic);
-    println!();
+/// Anchor-safe slug for markdown links (lowercase alphanumerics and dashes)
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Render a debate result as markdown.
+///
+/// Starts with a table of contents linking to explicit anchors for every
+/// round and response. With `collapse`, each response is wrapped in a
+/// `<details>` block.
+pub(crate) fn render_markdown(
+    result: &crate::orchestrator::DebateResult,
+    collapse: bool,
+) -> anyhow::Result<String> {
+    let position_changes = compute_position_changes(&result.stances);
 
+    let mut out = String::new();
+    writeln!(out, "# {}", result.topic)?;
+    writeln!(out)?;
+    if let Some(ref frontmatter) = result.frontmatter {
+        write!(out, "{}", frontmatter.markdown())?;
+    }
+
+    writeln!(out, "## Contents")?;
+    writeln!(out)?;
     for (round_num, responses) in result.rounds.iter().enumerate() {
-        println!("## Round {}", round_num + 1);
-        println!();
+        let round = round_num + 1;
+        writeln!(out, "- [Round {}](#round-{})", round, round)?;
+        for label in participant_labels(responses) {
+            writeln!(out, "  - [{}](#round-{}-{})", label, round, slugify(&label))?;
+        }
+    }
+    if !result.benched.is_empty() {
+        writeln!(out, "- [Benched Participants](#benched)")?;
+    }
+    if result.workspace_changes.is_some() {
+        writeln!(out, "- [Workspace Changes](#workspace-changes)")?;
+    }
+    if !position_changes.is_empty() {
+        writeln!(out, "- [Position Changes](#position-changes)")?;
+    }
+    if !result.risks.is_empty() {
+        writeln!(out, "- [Risk Register](#risk-register)")?;
+    }
+    if !result.action_items.is_empty() {
+        writeln!(out, "- [Action Items](#action-items)")?;
+    }
+    if !result.critiques.is_empty() {
+        writeln!(out, "- [Observer Critiques](#observer-critiques)")?;
+    }
+    if result.synthesis.is_some() {
+        writeln!(out, "- [Synthesis](#synthesis)")?;
+    }
+    if result.fact_check.is_some() {
+        writeln!(out, "- [Fact Check](#fact-check)")?;
+    }
+    writeln!(out)?;
 
-        for response in responses {
-            println!("### {}", response.display_name());
-            println!();
-            println!("{}", response.response);
-            println!();
+    for (round_num, responses) in result.rounds.iter().enumerate() {
+        let round = round_num + 1;
+        writeln!(out, "<a id=\"round-{}\"></a>", round)?;
+        writeln!(out)?;
+        writeln!(out, "## Round {}", round)?;
+        writeln!(out)?;
+
+        for (label, response) in participant_labels(responses).iter().zip(responses) {
+            writeln!(out, "<a id=\"round-{}-{}\"></a>", round, slugify(label))?;
+            writeln!(out)?;
+            writeln!(out, "### {}", label)?;
+            writeln!(out)?;
+            if collapse {
+                writeln!(out, "<details>")?;
+                writeln!(out, "<summary>Show response</summary>")?;
+                writeln!(out)?;
+                writeln!(out, "{}", response.response)?;
+                writeln!(out)?;
+                writeln!(out, "</details>")?;
+            } else {
+                writeln!(out, "{}", response.response)?;
+            }
+            writeln!(out)?;
+            if let Some(ref log) = response.log {
+                writeln!(out, "<!-- raw output: {} -->", log)?;
+                writeln!(out)?;
+            }
+        }
+    }
+
+    if !result.benched.is_empty() {
+        writeln!(out, "<a id=\"benched\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Benched Participants")?;
+        writeln!(out)?;
+        for benching in &result.benched {
+            writeln!(out, "- {}", benching_note(benching))?;
+        }
+        writeln!(out)?;
+    }
+
+    if let Some(ref changes) = result.workspace_changes {
+        writeln!(out, "<a id=\"workspace-changes\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Workspace Changes")?;
+        writeln!(out)?;
+        if changes.is_empty() {
+            writeln!(out, "No files were changed.")?;
+        }
+        for (change, paths) in [
+            ("created", &changes.created),
+            ("modified", &changes.modified),
+            ("deleted", &changes.deleted),
+        ] {
+            for path in paths {
+                writeln!(out, "- {} `{}`", change, path)?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    if !position_changes.is_empty() {
+        writeln!(out, "<a id=\"position-changes\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Position Changes")?;
+        writeln!(out)?;
+        writeln!(out, "| Participant | Round | From | To |")?;
+        writeln!(out, "|-------------|-------|------|----|")?;
+        for change in &position_changes {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                change.participant, change.round, change.from, change.to
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    if !result.risks.is_empty() {
+        writeln!(out, "<a id=\"risk-register\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Risk Register")?;
+        writeln!(out)?;
+        write!(out, "{}", risks::markdown_table(&result.risks))?;
+        writeln!(out)?;
+    }
+
+    if !result.action_items.is_empty() {
+        writeln!(out, "<a id=\"action-items\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Action Items")?;
+        writeln!(out)?;
+        write!(out, "{}", actions::markdown_table(&result.action_items))?;
+        writeln!(out)?;
+    }
+
+    if !result.critiques.is_empty() {
+        writeln!(out, "<a id=\"observer-critiques\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Observer Critiques")?;
+        writeln!(out)?;
+        for critique in &result.critiques {
+            writeln!(out, "### {}", critique.observer)?;
+            writeln!(out)?;
+            writeln!(out, "{}", critique.critique)?;
+            writeln!(out)?;
         }
     }
 
     // Print synthesis if present
     if let Some(ref synthesis) = result.synthesis {
-        println!("## Synthesis");
-        println!();
-        println!("### Summary");
-        println!();
-        println!("{}", synthesis.summary);
-        println!();
+        // Citations link to the responses they name
+        let cited = |text: &str| {
+            citations::link_citations(text, &result.rounds, |round, label| {
+                format!("round-{}-{}", round, slugify(label))
+            })
+        };
+        writeln!(out, "<a id=\"synthesis\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Synthesis")?;
+        writeln!(out)?;
+        writeln!(out, "### Summary")?;
+        writeln!(out)?;
+        writeln!(out, "{}", cited(&synthesis.summary))?;
+        writeln!(out)?;
 
         if !synthesis.consensus_points.is_empty() {
-            println!("### Consensus");
-            println!();
+            writeln!(out, "### Consensus")?;
+            writeln!(out)?;
             for point in &synthesis.consensus_points {
-                println!("- {}", point);
+                writeln!(out, "- {}", cited(point))?;
             }
-            println!();
+            writeln!(out)?;
         }
 
         if !synthesis.disagreement_points.is_empty() {
-            println!("### Disagreements");
-            println!();
+            writeln!(out, "### Disagreements")?;
+            writeln!(out)?;
             for point in &synthesis.disagreement_points {
-                println!("- {}", point);
+                writeln!(out, "- {}", cited(point))?;
             }
-            println!();
+            writeln!(out)?;
         }
 
         if !synthesis.key_insights.is_empty() {
-            println!("### Key Insights");
-            println!();
+            writeln!(out, "### Key Insights")?;
+            writeln!(out)?;
             for insight in &synthesis.key_insights {
-                println!("- {}", insight);
+                writeln!(out, "- {}", cited(insight))?;
             }
-            println!();
+            writeln!(out)?;
         }
 
         if let Some(ref recommendation) = synthesis.recommendation {
-            println!("### Recommendation");
-            println!();
-            println!("{}", recommendation);
-            println!();
+            writeln!(out, "### Recommendation")?;
+            writeln!(out)?;
+            writeln!(out, "{}", cited(recommendation))?;
+            writeln!(out)?;
+        }
+
+        if !synthesis.unverified_citations.is_empty() {
+            writeln!(
+                out,
+                "> Citations not found in the transcript: {}",
+                synthesis
+                    .unverified_citations
+                    .iter()
+                    .map(|citation| format!("`{}`", citation))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+            writeln!(out)?;
         }
     }
 
-    Ok(())
+    if let Some(ref check) = result.fact_check {
+        writeln!(out, "<a id=\"fact-check\"></a>")?;
+        writeln!(out)?;
+        writeln!(out, "## Fact Check")?;
+        writeln!(out)?;
+        if check.flagged.is_empty() {
+            writeln!(
+                out,
+                "{} found every claim in the synthesis supported by the transcript.",
+                check.checker
+            )?;
+            writeln!(out)?;
+        } else {
+            writeln!(
+                out,
+                "{} flagged {} claim(s) the transcript doesn't support:",
+                check.checker,
+                check.flagged.len()
+            )?;
+            writeln!(out)?;
+        }
+        for flagged in &check.flagged {
+            writeln!(out, "- **{}**: {}", flagged.claim, flagged.reason)?;
+            for passage in &flagged.passages {
+                writeln!(out, "  > {}", passage)?;
+            }
+        }
+        if !check.flagged.is_empty() {
+            writeln!(out)?;
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::{Synthesis, DEBATE_RESULT_SCHEMA_VERSION};
+    use proptest::prelude::*;
+
+    fn synthesized_result() -> DebateResult {
+        DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
+            gptengage_version: None,
+            topic: "Tabs vs spaces".to_string(),
+            rounds: vec![vec![RoundResponse {
+                cli: "claude".to_string(),
+                persona: None,
+                response: "Spaces.".to_string(),
+                log: None,
+            }]],
+            synthesis: Some(Synthesis {
+                summary: "Spaces won.".to_string(),
+                consensus_points: vec!["Be consistent".to_string()],
+                disagreement_points: vec!["Tab width".to_string()],
+                key_insights: vec!["Formatters settle it".to_string()],
+                recommendation: Some("Use rustfmt".to_string()),
+                position_changes: vec![],
+                unverified_citations: vec![],
+            }),
+            stances: vec![],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_includes_synthesis() {
+        let markdown = render_markdown(&synthesized_result(), false).unwrap();
+        assert!(markdown.contains("## Synthesis"));
+        assert!(markdown.contains("Spaces won."));
+        assert!(markdown.contains("### Consensus\n\n- Be consistent"));
+        assert!(markdown.contains("### Disagreements\n\n- Tab width"));
+        assert!(markdown.contains("### Key Insights\n\n- Formatters settle it"));
+        assert!(markdown.contains("### Recommendation\n\nUse rustfmt"));
+    }
+
+    #[test]
+    fn test_render_text_includes_synthesis() {
+        let text = render_text(&synthesized_result(), true).unwrap();
+        assert!(text.contains("SYNTHESIS"));
+        assert!(text.contains("  Spaces won."));
+        assert!(text.contains("Recommendation:\n  Use rustfmt"));
+        assert!(!text.contains("Tip: Use --output json"));
+    }
+
+    #[test]
+    fn test_render_markdown_toc_and_anchors() {
+        let mut result = synthesized_result();
+        result.rounds[0].push(RoundResponse {
+            cli: "claude".to_string(),
+            persona: None,
+            response: "Tabs.".to_string(),
+            log: None,
+        });
+
+        let markdown = render_markdown(&result, true).unwrap();
+        assert!(markdown.contains("- [Round 1](#round-1)"));
+        assert!(markdown.contains("  - [claude #2](#round-1-claude-2)"));
+        assert!(markdown.contains("<a id=\"round-1-claude-2\"></a>"));
+        assert!(markdown.contains("- [Synthesis](#synthesis)"));
+        assert!(markdown.contains("<details>\n<summary>Show response</summary>\n\nTabs."));
+    }
+
+    #[test]
+    fn test_parse_topics_skips_blanks_and_comments() {
+        let topics = parse_topics("# Q3 decisions\n\nMonorepo or polyrepo?\n  Adopt gRPC?  \n");
+        assert_eq!(topics, vec!["Monorepo or polyrepo?", "Adopt gRPC?"]);
+        assert_eq!(topic_file_stem("Adopt gRPC?"), "adopt-grpc");
+        assert_eq!(topic_file_stem("???"), "debate");
+    }
+
+    #[test]
+    fn test_render_batch_index() {
+        let entries = vec![
+            BatchEntry {
+                topic: "Tabs vs spaces".to_string(),
+                file: Some("01-tabs-vs-spaces.md".to_string()),
+                outcome: Ok(batch_summary(&synthesized_result())),
+            },
+            BatchEntry {
+                topic: "A | B".to_string(),
+                file: None,
+                outcome: Err("codex timed out\nafter 120s".to_string()),
+            },
+        ];
+        let index = render_batch_index(&entries).unwrap();
+        assert!(index.contains(
+            "| 1 | Tabs vs spaces | [01-tabs-vs-spaces.md](01-tabs-vs-spaces.md) | Use rustfmt |"
+        ));
+        assert!(index.contains("| 2 | A \\| B | - | Failed: codex timed out after 120s |"));
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("codex (CTO / Security)"), "codex-cto-security");
+        assert_eq!(slugify("claude #2"), "claude-2");
+    }
+
+    #[test]
+    fn test_dump_prompts_writes_one_file_per_participant() {
+        let dir = tempfile::tempdir().unwrap();
+        let participants = vec![
+            Participant::new("claude".to_string(), Some("CTO".to_string())),
+            Participant::new("codex".to_string(), None),
+        ];
+        let prompts = round_one_prompts(
+            "Tabs vs spaces",
+            &participants,
+            PromptContext::default(),
+            false,
+            None,
+        );
+        let prompts: Vec<String> = prompts.into_iter().map(|(_, prompt)| prompt).collect();
+        let files = dump_prompts(&dir.path().join("prompts"), &participants, &prompts).unwrap();
+
+        assert_eq!(files, vec!["01-claude-cto.txt", "02-codex.txt"]);
+        let written =
+            std::fs::read_to_string(dir.path().join("prompts/01-claude-cto.txt")).unwrap();
+        assert_eq!(written, prompts[0]);
+        assert!(written.contains("as a CTO") && written.contains("Topic: Tabs vs spaces"));
+    }
+
+    #[test]
+    fn test_apply_preset_keeps_command_line_flags() {
+        let preset = DebatePreset {
+            template: Some("architecture-decision".to_string()),
+            rounds: Some(4),
+            output: Some("markdown".to_string()),
+            synthesizer: Some("codex".to_string()),
+            context_providers: vec!["git-diff".to_string()],
+            history_last: Some(2),
+            ..DebatePreset::default()
+        };
+
+        let mut options = DebateOptions::new("Split the monolith?".to_string(), 3, 120);
+        options.apply_preset(&preset);
+        assert_eq!(options.template.as_deref(), Some("architecture-decision"));
+        assert_eq!(options.rounds, Some(4));
+        assert_eq!(options.output, "markdown");
+        assert!(options.synthesize);
+        assert_eq!(options.synthesizer, "codex");
+        assert_eq!(options.context.history_rounds, 2);
+
+        let mut options = DebateOptions::new("Split the monolith?".to_string(), 3, 120);
+        options.participants = Some("claude:CTO,codex:SRE".to_string());
+        options.rounds = Some(2);
+        options.output = "json".to_string();
+        options.context_providers = vec!["jira".to_string(), "git-diff".to_string()];
+        options.apply_preset(&preset);
+        assert_eq!(options.template, None);
+        assert_eq!(options.rounds, Some(2));
+        assert_eq!(options.output, "json");
+        assert_eq!(options.context_providers, vec!["git-diff", "jira"]);
+    }
+
+    #[test]
+    fn test_parse_participants_rejects_empty_fields() {
+        let participants = parse_participants(" claude : CTO , codex:SRE:o3 ").unwrap();
+        assert_eq!(participants[0].cli, "claude");
+        assert_eq!(participants[0].persona.as_deref(), Some("CTO"));
+        assert_eq!(participants[1].model.as_deref(), Some("o3"));
+
+        for (spec, error) in [
+            (":CTO", "no CLI name"),
+            ("claude:", "empty persona"),
+            ("claude::o3", "empty persona"),
+            ("claude:CTO:", "empty model"),
+            ("claude:a:b:c", "Invalid participant format"),
+            (" , ", "At least one participant"),
+        ] {
+            let err = parse_participants(spec).unwrap_err().to_string();
+            assert!(err.contains(error), "{}: {}", spec, err);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_participants_never_panics(spec in "[^@]*") {
+            let _ = parse_participants(&spec);
+        }
+
+        #[test]
+        fn prop_parse_participants_round_trips(
+            specs in prop::collection::vec(
+                (
+                    "[a-z][a-z0-9-]{0,10}",
+                    prop::option::of((
+                        "[A-Za-z]([A-Za-z /]{0,14}[A-Za-z])?",
+                        prop::option::of("[a-z0-9.-]{1,10}"),
+                    )),
+                ),
+                1..5,
+            )
+        ) {
+            let joined = specs
+                .iter()
+                .map(|(cli, persona)| match persona {
+                    None => cli.clone(),
+                    Some((persona, None)) => format!("{}:{}", cli, persona),
+                    Some((persona, Some(model))) => format!("{}:{}:{}", cli, persona, model),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let participants = parse_participants(&joined).unwrap();
+            prop_assert_eq!(participants.len(), specs.len());
+            for (participant, (cli, persona)) in participants.iter().zip(&specs) {
+                prop_assert_eq!(&participant.cli, cli);
+                prop_assert_eq!(
+                    participant.persona.as_deref(),
+                    persona.as_ref().map(|(p, _)| p.as_str())
+                );
+                prop_assert_eq!(
+                    participant.model.as_deref(),
+                    persona.as_ref().and_then(|(_, m)| m.as_deref())
+                );
+            }
+        }
+    }
 }
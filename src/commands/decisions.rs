@@ -0,0 +1,374 @@
+//! Decisions command - A decision log kept in the repository
+//!
+//! `decisions add --from-debate` records the outcome of a debate as a
+//! numbered decision: the topic, the synthesis recommendation as the
+//! decision, its summary as the rationale, and the points participants
+//! still disagreed on as dissent, with the date and a link to the
+//! transcript. The log is a JSON Lines file (`decisions.jsonl` by
+//! default), meant to be committed, and a markdown rendering of it is
+//! rewritten next to it (`decisions.md`) after every change.
+
+use crate::commands::attribution::debate_id;
+use crate::commands::bundle::parse_transcript;
+use crate::history::topic_summary;
+use chrono::{Local, NaiveDate};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+/// Longest decision shown in `decisions list`
+const MAX_LIST_CHARS: usize = 60;
+
+/// One recorded decision
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct Decision {
+    /// Sequential number, 1 for the first decision in the log
+    pub id: usize,
+    /// YYYY-MM-DD
+    #[schemars(with = "String")]
+    pub date: NaiveDate,
+    pub topic: String,
+    pub decision: String,
+    pub rationale: String,
+    /// Points participants still disagreed on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dissent: Vec<String>,
+    /// Debate ID of the transcript (as in `debate export --utterances`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debate: Option<String>,
+    /// Transcript file, as given to `decisions add`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<String>,
+}
+
+/// Options for `decisions add`
+pub struct AddOptions {
+    /// JSON transcript of the debate
+    pub from_debate: String,
+    /// Decision text, instead of the synthesis recommendation
+    pub decision: Option<String>,
+    /// Rationale, instead of the synthesis summary
+    pub rationale: Option<String>,
+}
+
+/// The decision log
+pub struct DecisionLog {
+    path: PathBuf,
+}
+
+impl DecisionLog {
+    /// Use the decision log at `path`
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Markdown rendering kept next to the log: `decisions.md` for
+    /// `decisions.jsonl`
+    pub fn markdown_path(&self) -> PathBuf {
+        self.path.with_extension("md")
+    }
+
+    /// Every decision, oldest first. A malformed line is an error, since
+    /// the log is edited by hand and reviewed like code.
+    pub fn load(&self) -> anyhow::Result<Vec<Decision>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path).map_err(|e| {
+            anyhow::anyhow!("Failed to read decision log {}: {}", self.path.display(), e)
+        })?;
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| anyhow::anyhow!("{} line {}: {}", self.path.display(), i + 1, e))
+            })
+            .collect()
+    }
+
+    /// Append a decision and rewrite the markdown rendering
+    pub fn append(&self, decision: &Decision) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to open decision log {}: {}", self.path.display(), e)
+            })?;
+        writeln!(file, "{}", serde_json::to_string(decision)?)?;
+        std::fs::write(self.markdown_path(), render_markdown(&self.load()?)?)?;
+        Ok(())
+    }
+}
+
+/// A decision for the debate in `content`, numbered `id`
+fn decision_from_transcript(
+    content: &str,
+    transcript: &str,
+    id: usize,
+    options: &AddOptions,
+) -> anyhow::Result<Decision> {
+    let result = parse_transcript(content).map_err(|e| {
+        anyhow::anyhow!(
+            "{} is not a JSON debate transcript (save one with --output json): {}",
+            transcript,
+            e
+        )
+    })?;
+    let synthesis = result.synthesis.as_ref();
+    let decision = options
+        .decision
+        .clone()
+        .or_else(|| synthesis.and_then(|s| s.recommendation.clone()))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} has no synthesis recommendation. Run the debate with --synthesize, or pass --decision",
+                transcript
+            )
+        })?;
+    let rationale = options
+        .rationale
+        .clone()
+        .or_else(|| synthesis.map(|s| s.summary.clone()))
+        .unwrap_or_default();
+    Ok(Decision {
+        id,
+        date: Local::now().date_naive(),
+        topic: topic_summary(&result.topic),
+        decision,
+        rationale,
+        dissent: synthesis
+            .map(|s| s.disagreement_points.clone())
+            .unwrap_or_default(),
+        debate: Some(debate_id(&result)),
+        transcript: Some(transcript.to_string()),
+    })
+}
+
+/// The log as a markdown document
+pub fn render_markdown(decisions: &[Decision]) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "# Decision Log")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "<!-- Generated by `gptengage decisions` from the .jsonl log; edit that instead. -->"
+    )?;
+    for decision in decisions {
+        writeln!(out)?;
+        writeln!(out, "## {}. {}", decision.id, decision.topic)?;
+        writeln!(out)?;
+        writeln!(out, "- **Date:** {}", decision.date)?;
+        writeln!(out, "- **Decision:** {}", decision.decision)?;
+        if !decision.rationale.is_empty() {
+            writeln!(out, "- **Rationale:** {}", decision.rationale)?;
+        }
+        if !decision.dissent.is_empty() {
+            writeln!(out, "- **Dissent:**")?;
+            for point in &decision.dissent {
+                writeln!(out, "  - {}", point)?;
+            }
+        }
+        match (&decision.transcript, &decision.debate) {
+            (Some(transcript), Some(id)) => writeln!(
+                out,
+                "- **Transcript:** [{}]({}) (debate `{}`)",
+                transcript, transcript, id
+            )?,
+            (Some(transcript), None) => {
+                writeln!(out, "- **Transcript:** [{}]({})", transcript, transcript)?
+            }
+            (None, Some(id)) => writeln!(out, "- **Debate:** `{}`", id)?,
+            (None, None) => {}
+        }
+    }
+    Ok(out)
+}
+
+fn render_text(decision: &Decision) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "Decision {}: {}", decision.id, decision.topic)?;
+    writeln!(out, "Date: {}", decision.date)?;
+    writeln!(out)?;
+    writeln!(out, "Decision:")?;
+    writeln!(out, "  {}", decision.decision)?;
+    if !decision.rationale.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Rationale:")?;
+        writeln!(out, "  {}", decision.rationale)?;
+    }
+    if !decision.dissent.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Dissent:")?;
+        for point in &decision.dissent {
+            writeln!(out, "  • {}", point)?;
+        }
+    }
+    if let Some(transcript) = &decision.transcript {
+        writeln!(out)?;
+        writeln!(out, "Transcript: {}", transcript)?;
+    }
+    if let Some(id) = &decision.debate {
+        writeln!(out, "Debate ID: {}", id)?;
+    }
+    Ok(out)
+}
+
+/// `text` on one line, cut to `max_chars`
+fn one_line(text: &str, max_chars: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line,
+    }
+}
+
+/// Record a debate's outcome in the decision log
+pub async fn add_decision(log: String, options: AddOptions) -> anyhow::Result<()> {
+    let log = DecisionLog::at(log);
+    let decisions = log.load()?;
+    let content = std::fs::read_to_string(&options.from_debate)
+        .map_err(|e| anyhow::anyhow!("Failed to read transcript {}: {}", options.from_debate, e))?;
+    let id = decisions.iter().map(|d| d.id).max().unwrap_or(0) + 1;
+    let decision = decision_from_transcript(&content, &options.from_debate, id, &options)?;
+    if let Some(existing) = decisions
+        .iter()
+        .find(|d| d.debate.is_some() && d.debate == decision.debate)
+    {
+        anyhow::bail!(
+            "This debate is already recorded as decision {} ({})",
+            existing.id,
+            existing.topic
+        );
+    }
+
+    log.append(&decision)?;
+    println!("✓ Recorded decision {}: {}", decision.id, decision.topic);
+    println!("  {}", one_line(&decision.decision, MAX_LIST_CHARS));
+    println!(
+        "  Log: {} (rendered to {})",
+        log.path.display(),
+        log.markdown_path().display()
+    );
+    Ok(())
+}
+
+/// Print every decision in the log
+pub async fn list_decisions(log: String, json: bool) -> anyhow::Result<()> {
+    let decisions = DecisionLog::at(log).load()?;
+    if json {
+        for decision in &decisions {
+            println!("{}", serde_json::to_string(decision)?);
+        }
+        return Ok(());
+    }
+    if decisions.is_empty() {
+        println!("No decisions recorded.");
+        println!("Add one with: gptengage decisions add --from-debate <TRANSCRIPT>");
+        return Ok(());
+    }
+    for decision in &decisions {
+        println!(
+            "  {:>3}  {}  {}",
+            decision.id, decision.date, decision.topic
+        );
+        println!("       → {}", one_line(&decision.decision, MAX_LIST_CHARS));
+    }
+    Ok(())
+}
+
+/// Print one decision
+pub async fn show_decision(log: String, id: usize, json: bool) -> anyhow::Result<()> {
+    let decisions = DecisionLog::at(&log).load()?;
+    let decision = decisions
+        .iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Decision {} not found in {}", id, log))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(decision)?);
+    } else {
+        print!("{}", render_text(decision)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSCRIPT: &str = r#"{
+  "topic": "Adopt Rust for the CLI?\nContext follows",
+  "rounds": [[{"cli": "claude", "persona": "CTO", "response": "Yes"}]],
+  "synthesis": {
+    "summary": "Rust fits the team's reliability goals.",
+    "consensus_points": ["Start with one tool"],
+    "disagreement_points": ["Hiring cost"],
+    "key_insights": [],
+    "recommendation": "Adopt Rust for new CLIs"
+  }
+}"#;
+
+    fn options() -> AddOptions {
+        AddOptions {
+            from_debate: "adopt.json".to_string(),
+            decision: None,
+            rationale: None,
+        }
+    }
+
+    #[test]
+    fn test_decision_from_transcript() {
+        let decision = decision_from_transcript(TRANSCRIPT, "adopt.json", 3, &options()).unwrap();
+        assert_eq!(decision.id, 3);
+        assert_eq!(decision.topic, "Adopt Rust for the CLI?");
+        assert_eq!(decision.decision, "Adopt Rust for new CLIs");
+        assert_eq!(
+            decision.rationale,
+            "Rust fits the team's reliability goals."
+        );
+        assert_eq!(decision.dissent, vec!["Hiring cost".to_string()]);
+        assert_eq!(decision.debate.as_ref().map(String::len), Some(12));
+
+        let unsynthesized = r#"{"topic": "T", "rounds": []}"#;
+        let err = decision_from_transcript(unsynthesized, "t.json", 1, &options()).unwrap_err();
+        assert!(err.to_string().contains("--decision"), "{}", err);
+        let overridden = AddOptions {
+            decision: Some("Keep Go".to_string()),
+            ..options()
+        };
+        let decision = decision_from_transcript(unsynthesized, "t.json", 1, &overridden).unwrap();
+        assert_eq!(decision.decision, "Keep Go");
+        assert!(decision.rationale.is_empty());
+    }
+
+    #[test]
+    fn test_log_appends_and_renders_markdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = DecisionLog::at(dir.path().join("decisions.jsonl"));
+        assert!(log.load().unwrap().is_empty());
+
+        let decision = decision_from_transcript(TRANSCRIPT, "adopt.json", 1, &options()).unwrap();
+        log.append(&decision).unwrap();
+        assert_eq!(log.load().unwrap(), vec![decision.clone()]);
+
+        let markdown = std::fs::read_to_string(dir.path().join("decisions.md")).unwrap();
+        assert!(markdown.contains("## 1. Adopt Rust for the CLI?\n"));
+        assert!(markdown.contains("- **Decision:** Adopt Rust for new CLIs\n"));
+        assert!(markdown.contains("- **Dissent:**\n  - Hiring cost\n"));
+        assert!(markdown.contains(&format!(
+            "- **Transcript:** [adopt.json](adopt.json) (debate `{}`)",
+            decision.debate.unwrap()
+        )));
+
+        std::fs::write(dir.path().join("decisions.jsonl"), "{not json}\n").unwrap();
+        let err = log.load().unwrap_err().to_string();
+        assert!(err.contains("line 1"), "{}", err);
+    }
+}
@@ -0,0 +1,198 @@
+//! Explain command - Ask a CLI why a shell command failed
+//!
+//! Runs the command, shows its output, then sends the command line, exit
+//! status, and captured stdout/stderr to a CLI for an explanation and
+//! suggested fixes.
+
+use crate::app::GptEngage;
+use crate::audit::ProcessAudit;
+use crate::invokers::{AccessMode, CommandOutput};
+use crate::utils::stdin::format_context_block;
+
+/// Bytes kept from the end of each output stream in the prompt
+const MAX_STREAM_BYTES: usize = 20_000;
+
+/// Options for `explain`
+pub struct ExplainOptions {
+    pub cli: String,
+    pub model: Option<String>,
+    /// Timeout for the command being explained, in seconds
+    pub command_timeout: u64,
+    /// Timeout for the explaining CLI, in seconds
+    pub timeout: u64,
+}
+
+/// The last `max_bytes` of a stream (cut at a line start when possible),
+/// noting how much was left out
+pub(crate) fn tail(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    if let Some(newline) = text[start..].find('\n') {
+        start += newline + 1;
+    }
+    format!("[... {} earlier bytes omitted]\n{}", start, &text[start..])
+}
+
+/// How the command ended, for the prompt and the status line
+pub(crate) fn outcome(output: &CommandOutput) -> String {
+    match output.exit_code {
+        Some(0) => "succeeded (exit code 0)".to_string(),
+        Some(code) => format!("failed with exit code {}", code),
+        None => "was killed by a signal".to_string(),
+    }
+}
+
+/// Prompt asking for an explanation of a finished command
+pub fn explain_prompt(command_line: &str, cwd: &str, output: &CommandOutput) -> String {
+    let request = if output.exit_code == Some(0) {
+        "Explain what this output means and point out any warnings worth acting on."
+    } else {
+        "Explain why it failed, citing the relevant lines of output, and suggest \
+         concrete fixes, most likely first."
+    };
+    let mut prompt = format!(
+        "I ran a shell command and it {}. {}\n\nCommand: {}\nWorking directory: {}\n",
+        outcome(output),
+        request,
+        command_line,
+        cwd
+    );
+    for (label, stream) in [("STDOUT", &output.stdout), ("STDERR", &output.stderr)] {
+        let stream = stream.trim_end();
+        if stream.is_empty() {
+            prompt.push_str(&format!("\n({} was empty)\n", label.to_lowercase()));
+        } else {
+            prompt.push('\n');
+            prompt.push_str(&format_context_block(
+                label,
+                &tail(stream, MAX_STREAM_BYTES),
+            ));
+            prompt.push('\n');
+        }
+    }
+    prompt
+}
+
+/// Run the user's own command as a plain child process, returning its
+/// output whether or not it succeeded.
+///
+/// It is not a CLI invocation, so none of the CLI execution policy applies:
+/// no sandboxed HOME, network namespace, concurrency slot, new session, or
+/// output normalization.
+pub(crate) async fn run_user_command(
+    program: &str,
+    args: &[&str],
+    timeout: u64,
+) -> anyhow::Result<CommandOutput> {
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let audit = ProcessAudit::start(command.as_std());
+    let output = tokio::time::timeout(std::time::Duration::from_secs(timeout), command.output())
+        .await
+        .map_err(|_| anyhow::anyhow!("Command timed out after {} seconds", timeout))??;
+    audit.finish(&output.status);
+    Ok(CommandOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        exit_code: output.status.code(),
+    })
+}
+
+/// Run a command and have a CLI explain its outcome
+pub async fn run_explain(
+    engage: &GptEngage,
+    command: Vec<String>,
+    options: ExplainOptions,
+) -> anyhow::Result<()> {
+    let (program, args) = command.split_first().ok_or_else(|| {
+        anyhow::anyhow!("No command given. Usage: gptengage explain -- <command>")
+    })?;
+    let invoker = engage
+        .invokers
+        .get(&options.cli)
+        .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", options.cli))?;
+    if !invoker.is_available().await {
+        anyhow::bail!("CLI '{}' not found in PATH", options.cli);
+    }
+
+    let command_line =
+        shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = run_user_command(program, &args, options.command_timeout)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", command_line, e))?;
+
+    print!("{}", output.stdout);
+    eprint!("{}", output.stderr);
+    eprintln!();
+    eprintln!("$ {} {}", command_line, outcome(&output));
+    eprintln!("Asking {} to explain...", options.cli);
+
+    let cwd = std::env::current_dir()
+        .map(|d| d.display().to_string())
+        .unwrap_or_default();
+    let prompt = explain_prompt(&command_line, &cwd, &output);
+    let explanation = invoker
+        .invoke(
+            &prompt,
+            options.timeout,
+            AccessMode::ReadOnly,
+            options.model.as_deref(),
+        )
+        .await?;
+    println!();
+    println!("{}", explanation.trim_end());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_prompt_includes_streams() {
+        let output = CommandOutput {
+            stdout: String::new(),
+            stderr: "error[E0425]: cannot find value `x`\n".to_string(),
+            exit_code: Some(101),
+        };
+        let prompt = explain_prompt("cargo build", "/repo", &output);
+        assert!(prompt.starts_with("I ran a shell command and it failed with exit code 101."));
+        assert!(prompt.contains("Command: cargo build\nWorking directory: /repo\n"));
+        assert!(prompt.contains("(stdout was empty)"));
+        assert!(prompt.contains("[STDERR]\nerror[E0425]: cannot find value `x`\n[/STDERR]"));
+    }
+
+    #[tokio::test]
+    async fn test_run_user_command_keeps_environment() {
+        let output = run_user_command("sh", &["-c", "echo \"$HOME\"; exit 3"], 5)
+            .await
+            .unwrap();
+        let home = std::env::var("HOME").unwrap_or_default();
+        assert_eq!(output.stdout, format!("{}\n", home));
+        assert_eq!(output.exit_code, Some(3));
+
+        let err = run_user_command("sleep", &["5"], 1).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+
+    #[test]
+    fn test_tail_keeps_end_of_stream() {
+        assert_eq!(tail("short", 10), "short");
+        let text = "line one\nline two\nline three\n";
+        assert_eq!(
+            tail(text, 15),
+            "[... 18 earlier bytes omitted]\nline three\n"
+        );
+        // Never splits a multi-byte character
+        assert!(tail("ééééé\nab", 4).ends_with("ab"));
+    }
+}
@@ -0,0 +1,270 @@
+//! Fanout command - Run independent invocations in parallel
+//!
+//! Each job in a jobs file names its own CLI, prompt, and context. Jobs run
+//! with bounded concurrency and their results are gathered into a single
+//! JSON report.
+
+use crate::app::GptEngage;
+use crate::invokers::logs::capture_log_paths;
+use crate::invokers::AccessMode;
+use crate::utils::run_snapshot::{record_run, snapshot_run};
+use crate::utils::workspace::{track_changes, tracked_changes, WorkspaceChanges};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// One independent invocation
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FanoutJob {
+    /// Label used in the report (defaults to "job-N")
+    #[serde(default)]
+    pub name: Option<String>,
+    pub cli: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    pub prompt: String,
+    /// Inline context placed before the prompt
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Files placed before the prompt, like `invoke --context-file`
+    #[serde(default)]
+    pub context_files: Vec<String>,
+    /// Per-job timeout in seconds (defaults to --timeout)
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+/// Outcome of one job
+#[derive(Debug, Serialize)]
+pub struct JobResult {
+    pub name: String,
+    pub cli: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// "ok" or "failed"
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Raw output log of the invocation, when invocation logs are on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+}
+
+/// Report written by `fanout`
+#[derive(Debug, Serialize)]
+pub struct FanoutReport {
+    pub gptengage_version: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub jobs: Vec<JobResult>,
+    /// Files created, modified, or deleted by the jobs (with --write)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_changes: Option<WorkspaceChanges>,
+}
+
+/// Parse a jobs file (YAML, or JSON, which YAML accepts) holding a list
+/// of jobs or a map with a `jobs` list, naming unnamed jobs after their
+/// position
+pub fn parse_jobs(content: &str) -> anyhow::Result<Vec<FanoutJob>> {
+    let mut value: serde_norway::Value = serde_norway::from_str(content)?;
+    if let Some(jobs) = value.get_mut("jobs") {
+        value = std::mem::take(jobs);
+    }
+    let mut jobs: Vec<FanoutJob> = serde_norway::from_value(value)?;
+    if jobs.is_empty() {
+        anyhow::bail!("Jobs file has no jobs");
+    }
+    for (i, job) in jobs.iter_mut().enumerate() {
+        if job.prompt.trim().is_empty() {
+            anyhow::bail!("Job {} has an empty prompt", i + 1);
+        }
+        if job.name.is_none() {
+            job.name = Some(format!("job-{}", i + 1));
+        }
+    }
+    Ok(jobs)
+}
+
+/// Full prompt for a job, with its files and inline context first
+pub async fn job_prompt(job: &FanoutJob) -> anyhow::Result<String> {
+    let mut prompt = job.prompt.clone();
+    if let Some(ref context) = job.context {
+        prompt = format!("{}\n\n{}", context.trim_end(), prompt);
+    }
+    for file in job.context_files.iter().rev() {
+        let content = tokio::fs::read_to_string(file)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read context file {}: {}", file, e))?;
+        prompt = format!("File: {}\n\n{}\n\n{}", file, content, prompt);
+    }
+    Ok(prompt)
+}
+
+/// Run one job, capturing failures in the result
+async fn run_job(
+    engage: &GptEngage,
+    job: FanoutJob,
+    prompt: String,
+    timeout: u64,
+    access_mode: AccessMode,
+) -> JobResult {
+    let name = job.name.clone().unwrap_or_default();
+    let started = std::time::Instant::now();
+    let (result, logs) = match engage.invokers.get(&job.cli) {
+        Some(invoker) if invoker.is_available().await => {
+            capture_log_paths(invoker.invoke(
+                &prompt,
+                job.timeout.unwrap_or(timeout),
+                access_mode,
+                job.model.as_deref(),
+            ))
+            .await
+        }
+        _ => (
+            Err(anyhow::anyhow!("CLI '{}' is not available", job.cli)),
+            Vec::new(),
+        ),
+    };
+
+    let (status, response, error) = match result {
+        Ok(response) => {
+            eprintln!("✓ {} ({})", name, job.cli);
+            ("ok", Some(response), None)
+        }
+        Err(e) => {
+            eprintln!("✗ {} ({}): {}", name, job.cli, e);
+            ("failed", None, Some(e.to_string()))
+        }
+    };
+    JobResult {
+        name,
+        cli: job.cli,
+        model: job.model,
+        status: status.to_string(),
+        response,
+        error,
+        duration_ms: started.elapsed().as_millis() as u64,
+        log: logs.last().map(|path| path.display().to_string()),
+    }
+}
+
+/// Run every job in a jobs file and write the combined report
+pub async fn run_fanout(
+    engage: &GptEngage,
+    jobs_file: String,
+    output: Option<String>,
+    concurrency: usize,
+    timeout: u64,
+    access_mode: AccessMode,
+) -> anyhow::Result<()> {
+    let content = tokio::fs::read_to_string(&jobs_file)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read jobs file {}: {}", jobs_file, e))?;
+    let jobs = parse_jobs(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid jobs file {}: {}", jobs_file, e))?;
+
+    // Catch unknown CLIs and unreadable files before anything runs
+    let mut prepared = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        if !engage.invokers.is_valid(&job.cli) {
+            anyhow::bail!(
+                "Unknown CLI '{}' in job '{}'",
+                job.cli,
+                job.name.as_deref().unwrap_or_default()
+            );
+        }
+        let prompt = job_prompt(&job).await?;
+        prepared.push((job, prompt));
+    }
+
+    eprintln!(
+        "Running {} jobs ({} at a time)...",
+        prepared.len(),
+        concurrency.max(1)
+    );
+    let (run, snapshot) = match access_mode {
+        AccessMode::WorkspaceWrite => (
+            snapshot_run(&format!("fanout {}", jobs_file))?,
+            track_changes(),
+        ),
+        AccessMode::ReadOnly => (None, None),
+    };
+    let results: Vec<JobResult> = futures::stream::iter(prepared)
+        .map(|(job, prompt)| run_job(engage, job, prompt, timeout, access_mode))
+        .buffered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let failed = results.iter().filter(|r| r.status != "ok").count();
+    let workspace_changes = tracked_changes(snapshot.as_ref());
+    record_run(run, workspace_changes.as_ref());
+    let report = FanoutReport {
+        gptengage_version: env!("CARGO_PKG_VERSION").to_string(),
+        succeeded: results.len() - failed,
+        failed,
+        jobs: results,
+        workspace_changes,
+    };
+    let json = serde_json::to_string_pretty(&report)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, format!("{}\n", json))?;
+            eprintln!("Report written to {}", path);
+        }
+        None => println!("{}", json),
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} jobs failed", failed, report.jobs.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jobs_list_and_map() {
+        let list = "- cli: claude\n  prompt: Summarize the README\n- name: review\n  cli: codex\n  prompt: Review main.rs\n  timeout: 300\n";
+        let jobs = parse_jobs(list).unwrap();
+        assert_eq!(jobs[0].name.as_deref(), Some("job-1"));
+        assert_eq!(jobs[1].name.as_deref(), Some("review"));
+        assert_eq!(jobs[1].timeout, Some(300));
+
+        let map = r#"{"jobs": [{"cli": "gemini", "prompt": "Hi", "model": "flash"}]}"#;
+        let jobs = parse_jobs(map).unwrap();
+        assert_eq!(jobs[0].model.as_deref(), Some("flash"));
+
+        assert!(parse_jobs("jobs: []").is_err());
+        assert!(parse_jobs("- cli: claude\n  prompt: ' '\n").is_err());
+        assert!(parse_jobs("- cli: claude\n  prompt: x\n  promt: typo\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_job_prompt_puts_context_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "note body").unwrap();
+        let job = FanoutJob {
+            name: None,
+            cli: "claude".to_string(),
+            model: None,
+            prompt: "Question?".to_string(),
+            context: Some("Background.\n".to_string()),
+            context_files: vec![file.display().to_string()],
+            timeout: None,
+        };
+        let prompt = job_prompt(&job).await.unwrap();
+        assert_eq!(
+            prompt,
+            format!(
+                "File: {}\n\nnote body\n\nBackground.\n\nQuestion?",
+                file.display()
+            )
+        );
+    }
+}
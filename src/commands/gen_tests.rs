@@ -0,0 +1,183 @@
+//! Gen-tests command - Unit tests written, run, and repaired
+//!
+//! `gen-tests --file src/session/mod.rs` asks a CLI, in write mode, to add
+//! unit tests for a file, then runs the test command (`cargo test` by
+//! default). While it fails, the end of its output goes back to the CLI
+//! with a request to fix the tests, up to `--max-repairs` times. The final
+//! pass/fail state is reported and sets the exit status.
+
+use super::explain::{outcome, run_user_command, tail};
+use crate::app::GptEngage;
+use crate::invokers::{AccessMode, CommandOutput};
+use crate::utils::run_snapshot::{record_run, snapshot_run};
+use crate::utils::stdin::format_context_block;
+use crate::utils::workspace::{track_changes, tracked_changes};
+
+/// Bytes kept from the end of the test output in a repair prompt
+const MAX_FAILURE_BYTES: usize = 15_000;
+
+/// Options for `gen-tests`
+pub struct GenTestsOptions {
+    /// File to write tests for
+    pub file: String,
+    pub cli: String,
+    pub model: Option<String>,
+    /// Command that runs the tests, split like a shell would
+    pub test_command: String,
+    /// Repair attempts after the first test run fails
+    pub max_repairs: usize,
+    /// Timeout for the CLI, in seconds
+    pub timeout: u64,
+    /// Timeout for each test run, in seconds
+    pub test_timeout: u64,
+}
+
+/// Prompt asking for tests for `file`
+fn generate_prompt(file: &str, test_command: &str) -> String {
+    format!(
+        r#"[TEST GENERATION REQUEST]
+Write unit tests for {}.
+
+Read the file and add tests for its public behavior: normal cases, edge
+cases, and error paths. Put them where this project keeps its tests and
+follow the style of its existing ones (for Rust, a `#[cfg(test)] mod
+tests` at the bottom of the file unless the project does otherwise).
+Edit the files directly. Don't change the code under test; if it has a
+bug, leave a comment in the test instead of fixing it.
+
+The tests will be run with `{}`.
+[/TEST GENERATION REQUEST]"#,
+        file, test_command
+    )
+}
+
+/// Prompt asking for the tests to be fixed after a failed run
+fn repair_prompt(file: &str, test_command: &str, output: &CommandOutput) -> String {
+    let mut failure = String::new();
+    for (label, stream) in [("STDOUT", &output.stdout), ("STDERR", &output.stderr)] {
+        let stream = stream.trim_end();
+        if !stream.is_empty() {
+            failure.push_str(&format_context_block(
+                label,
+                &tail(stream, MAX_FAILURE_BYTES),
+            ));
+            failure.push('\n');
+        }
+    }
+    format!(
+        r#"[TEST REPAIR REQUEST]
+You added unit tests for {}, but `{}` {}:
+
+{}
+Fix the tests so they build and pass, editing the files directly. Don't
+change the code under test; remove a test only if it checks behavior the
+code doesn't have.
+[/TEST REPAIR REQUEST]"#,
+        file,
+        test_command,
+        outcome(output),
+        failure
+    )
+}
+
+/// Generate tests for a file and repair them until they pass
+pub async fn run_gen_tests(engage: &GptEngage, options: GenTestsOptions) -> anyhow::Result<()> {
+    if !std::path::Path::new(&options.file).is_file() {
+        anyhow::bail!("No such file: {}", options.file);
+    }
+    let invoker = engage
+        .invokers
+        .get(&options.cli)
+        .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", options.cli))?;
+    if !invoker.is_available().await {
+        anyhow::bail!("CLI '{}' not found in PATH", options.cli);
+    }
+    let words = shlex::split(&options.test_command).unwrap_or_default();
+    let Some((program, args)) = words.split_first() else {
+        anyhow::bail!("Invalid --test-command: {}", options.test_command);
+    };
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let run = snapshot_run(&format!("gen-tests {}", options.file))?;
+    let snapshot = track_changes();
+    let model = options.model.as_deref();
+
+    eprintln!("Asking {} for tests for {}...", options.cli, options.file);
+    let reply = invoker
+        .invoke(
+            &generate_prompt(&options.file, &options.test_command),
+            options.timeout,
+            AccessMode::WorkspaceWrite,
+            model,
+        )
+        .await?;
+    println!("{}", reply.trim_end());
+
+    let mut repairs = 0;
+    let passed = loop {
+        eprintln!("\nRunning {}...", options.test_command);
+        let output = run_user_command(program, &args, options.test_timeout).await?;
+        eprintln!("$ {} {}", options.test_command, outcome(&output));
+        if output.exit_code == Some(0) {
+            break true;
+        }
+        if repairs == options.max_repairs {
+            for stream in [&output.stdout, &output.stderr] {
+                let stream = stream.trim_end();
+                if !stream.is_empty() {
+                    eprintln!("{}", tail(stream, MAX_FAILURE_BYTES));
+                }
+            }
+            break false;
+        }
+        repairs += 1;
+        eprintln!(
+            "Asking {} to repair the tests ({} of {})...",
+            options.cli, repairs, options.max_repairs
+        );
+        let reply = invoker
+            .invoke(
+                &repair_prompt(&options.file, &options.test_command, &output),
+                options.timeout,
+                AccessMode::WorkspaceWrite,
+                model,
+            )
+            .await?;
+        println!("{}", reply.trim_end());
+    };
+
+    let changes = tracked_changes(snapshot.as_ref());
+    record_run(run, changes.as_ref());
+    if let Some(changes) = changes {
+        eprint!("\nWorkspace changes:\n{}", changes.render()?);
+    }
+    if !passed {
+        anyhow::bail!(
+            "Tests still fail after {} repair attempt(s). The generated tests are left in place.",
+            repairs
+        );
+    }
+    println!("✓ Tests pass ({} repair attempt(s))", repairs);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repair_prompt_includes_failure() {
+        let output = CommandOutput {
+            stdout: "test session::tests::test_trash ... FAILED\n".to_string(),
+            stderr: String::new(),
+            exit_code: Some(101),
+        };
+        let prompt = repair_prompt("src/session/mod.rs", "cargo test", &output);
+        assert!(prompt.contains(
+            "You added unit tests for src/session/mod.rs, but `cargo test` failed with exit code 101:"
+        ));
+        assert!(prompt.contains("[STDOUT]\ntest session::tests::test_trash ... FAILED\n[/STDOUT]"));
+        assert!(!prompt.contains("[STDERR]"));
+        assert!(generate_prompt("src/lib.rs", "cargo test").contains("`cargo test`"));
+    }
+}
@@ -1,56 +1,137 @@
 //! Generate agent definitions command
 
-use crate::invokers::{AccessMode, ClaudeInvoker, CodexInvoker, GeminiInvoker, Invoker};
+use crate::app::GptEngage;
+use crate::commands::persona::save_agent_personas;
+use crate::invokers::detect::BUILTIN_CLIS;
+use crate::invokers::{AccessMode, Invoker};
 use crate::orchestrator::{AgentDefinition, AgentFile};
+use crate::personas::{persona_reference, PersonaRegistry};
+use crate::utils::json::extract_json_array;
+
+/// Follow-up requests when generated definitions don't parse or validate
+pub const DEFAULT_GENERATION_RETRIES: usize = 1;
+
+/// How agent definitions are generated
+#[derive(Debug, Clone)]
+pub struct GenerationOptions {
+    /// CLI asked to write the definitions
+    pub cli: String,
+    /// Model for that CLI
+    pub model: Option<String>,
+    pub timeout: u64,
+    pub access_mode: AccessMode,
+    /// Follow-up requests, quoting the problem, when a response can't be
+    /// parsed or fails validation
+    pub retries: usize,
+}
+
+impl GenerationOptions {
+    /// Defaults for `cli` (used by `debate --auto-agents`)
+    pub fn new(cli: &str, timeout: u64, access_mode: AccessMode) -> Self {
+        Self {
+            cli: cli.to_string(),
+            model: None,
+            timeout,
+            access_mode,
+            retries: DEFAULT_GENERATION_RETRIES,
+        }
+    }
+}
 
 /// Generate agent definitions for debate participants
 pub async fn run_generate_agents(
+    engage: &GptEngage,
     topic: String,
     roles: String,
     output_path: String,
-    use_cli: String,
-    timeout: u64,
-    access_mode: AccessMode,
+    options: GenerationOptions,
+    save_personas: bool,
 ) -> anyhow::Result<()> {
     println!("Generating agent definitions...");
     println!("Topic: {}", topic);
     println!("Roles: {}", roles);
     println!();
 
+    let (agent_file, generated_definitions) =
+        build_agent_file(engage, &topic, &roles, &options).await?;
+    agent_file.save(&output_path)?;
+
+    println!();
+    println!(
+        "✅ Generated {} agent definition(s)",
+        agent_file.participants.len()
+    );
+    println!("📄 Saved to: {}", output_path);
+    if save_personas {
+        save_agent_personas(&generated_definitions);
+    }
+    println!();
+    println!("Agents:");
+    for agent in &agent_file.participants {
+        println!("  - {} ({})", agent.cli, agent.persona);
+    }
+    println!();
+    println!(
+        "Use with: gptengage debate \"{}\" --agent-file {}",
+        topic, output_path
+    );
+
+    Ok(())
+}
+
+/// Build a validated agent file for comma-separated `roles` without
+/// writing it anywhere.
+///
+/// Returns the file and the definitions that were generated (roles taken
+/// from the persona library are not included in the latter).
+pub async fn build_agent_file(
+    engage: &GptEngage,
+    topic: &str,
+    roles: &str,
+    options: &GenerationOptions,
+) -> anyhow::Result<(AgentFile, Vec<AgentDefinition>)> {
+    let use_cli = options.cli.as_str();
     // Parse roles
     let role_list: Vec<&str> = roles.split(',').map(|s| s.trim()).collect();
     if role_list.is_empty() {
         return Err(anyhow::anyhow!("No roles specified"));
     }
 
-    // Select the invoker
-    let invoker: Box<dyn Invoker> = match use_cli.to_lowercase().as_str() {
-        "claude" => Box::new(ClaudeInvoker::new()),
-        "codex" => Box::new(CodexInvoker::new()),
-        "gemini" => Box::new(GeminiInvoker::new()),
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Unknown CLI '{}'. Use 'claude', 'codex', or 'gemini'",
-                use_cli
-            ))
-        }
+    // Roles written as @name come from the persona library; only the
+    // others are generated
+    let registry = if role_list.iter().any(|r| persona_reference(r).is_some()) {
+        Some(PersonaRegistry::new()?)
+    } else {
+        None
     };
+    let library: Vec<Option<AgentDefinition>> = role_list
+        .iter()
+        .map(|role| match (persona_reference(role), &registry) {
+            (Some(name), Some(registry)) => registry
+                .resolve(name)
+                .map(|p| Some(p.to_agent_definition(&use_cli.to_lowercase(), None))),
+            _ => Ok(None),
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let generate_roles: Vec<&str> = role_list
+        .iter()
+        .zip(&library)
+        .filter(|(_, from_library)| from_library.is_none())
+        .map(|(role, _)| *role)
+        .collect();
 
-    if !invoker.is_available() {
-        return Err(anyhow::anyhow!(
-            "CLI '{}' is not available. Please ensure it's installed and in your PATH",
-            use_cli
-        ));
-    }
-
-    // Build the prompt for generating agent definitions
-    let prompt = build_generation_prompt(&topic, &role_list);
-
-    println!("Using {} to generate agent definitions...", use_cli);
-    let response = invoker.invoke(&prompt, timeout, access_mode, None).await?;
+    let generated_definitions = if generate_roles.is_empty() {
+        Vec::new()
+    } else {
+        generate_definitions(engage, topic, &generate_roles, options).await?
+    };
+    let mut generated = generated_definitions.clone().into_iter();
 
-    // Parse the response as JSON
-    let agent_definitions = parse_agent_response(&response, &role_list)?;
+    // Keep the order the roles were given in
+    let agent_definitions: Vec<AgentDefinition> = library
+        .into_iter()
+        .filter_map(|from_library| from_library.or_else(|| generated.next()))
+        .collect();
 
     // Create the agent file
     let agent_file = AgentFile {
@@ -71,28 +152,112 @@ pub async fn run_generate_agents(
         })?;
     }
 
-    // Write to file
-    let json = serde_json::to_string_pretty(&agent_file)?;
-    std::fs::write(&output_path, json)?;
+    Ok((agent_file, generated_definitions))
+}
 
-    println!();
-    println!(
-        "✅ Generated {} agent definition(s)",
-        agent_file.participants.len()
-    );
-    println!("📄 Saved to: {}", output_path);
-    println!();
-    println!("Agents:");
-    for agent in &agent_file.participants {
-        println!("  - {} ({})", agent.cli, agent.persona);
+/// The built-in CLIs installed here, which generated agents may use
+pub async fn installed_clis(engage: &GptEngage) -> Vec<String> {
+    let mut installed = Vec::new();
+    for cli in BUILTIN_CLIS {
+        if let Some(invoker) = engage.invokers.get(cli) {
+            if invoker.is_available().await {
+                installed.push(cli.to_string());
+            }
+        }
     }
-    println!();
-    println!(
-        "Use with: gptengage debate \"{}\" --agent-file {}",
-        topic, output_path
-    );
+    installed
+}
 
-    Ok(())
+/// Move agents whose CLI isn't `installed` onto the installed CLIs in
+/// turn, so a debate doesn't skip them. With nothing installed, they use
+/// `fallback`, the CLI that generated them.
+pub fn assign_installed_clis(agents: &mut [AgentDefinition], installed: &[String], fallback: &str) {
+    let mut next = installed.iter().cycle();
+    for agent in agents {
+        if installed.contains(&agent.cli) {
+            continue;
+        }
+        let cli = next.next().map_or(fallback, String::as_str);
+        if agent.cli != cli {
+            eprintln!(
+                "{} isn't installed; {} will use {}",
+                agent.cli, agent.persona, cli
+            );
+            agent.cli = cli.to_string();
+            // Model names belong to the CLI they were chosen for
+            agent.model = None;
+        }
+    }
+}
+
+/// Ask the configured CLI to write agent definitions for `roles`
+async fn generate_definitions(
+    engage: &GptEngage,
+    topic: &str,
+    roles: &[&str],
+    options: &GenerationOptions,
+) -> anyhow::Result<Vec<AgentDefinition>> {
+    let invoker = engage.invokers.get(&options.cli).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown CLI '{}'. Use 'claude', 'codex', 'gemini', or an installed plugin",
+            options.cli
+        )
+    })?;
+
+    if !invoker.is_available().await {
+        return Err(anyhow::anyhow!(
+            "CLI '{}' is not available. Please ensure it's installed and in your PATH",
+            options.cli
+        ));
+    }
+
+    println!("Using {} to generate agent definitions...", options.cli);
+    generate_with(invoker.as_ref(), topic, roles, options).await
+}
+
+/// Generate definitions with `invoker`, asking again (up to
+/// `options.retries` times) when the response is unusable
+async fn generate_with(
+    invoker: &dyn Invoker,
+    topic: &str,
+    roles: &[&str],
+    options: &GenerationOptions,
+) -> anyhow::Result<Vec<AgentDefinition>> {
+    let prompt = build_generation_prompt(topic, roles);
+    let mut request = prompt.clone();
+    let mut attempt = 0;
+    loop {
+        let response = invoker
+            .invoke(
+                &request,
+                options.timeout,
+                options.access_mode,
+                options.model.as_deref(),
+            )
+            .await?;
+        match parse_agent_response(&response, roles) {
+            Ok(definitions) => return Ok(definitions),
+            Err(e) if attempt < options.retries => {
+                attempt += 1;
+                eprintln!(
+                    "Agent definitions were unusable ({}); asking again ({}/{})...",
+                    e, attempt, options.retries
+                );
+                request = retry_prompt(&prompt, &response, &e.to_string());
+            }
+            Err(e) => return Err(anyhow::anyhow!("{}\nResponse: {}", e, response)),
+        }
+    }
+}
+
+/// Follow-up prompt quoting an unusable response and what was wrong
+fn retry_prompt(prompt: &str, response: &str, problem: &str) -> String {
+    format!(
+        "{}\n\nA previous attempt returned the response below, which could not be \
+         used: {}\n\nPREVIOUS RESPONSE:\n{}\n\n\
+         Return a corrected JSON array only.",
+        prompt, problem, response
+    )
 }
 
 fn build_generation_prompt(topic: &str, roles: &[&str]) -> String {
@@ -139,13 +304,8 @@ fn parse_agent_response(
     let json_str = extract_json_array(response)?;
 
     // Parse the JSON
-    let agents: Vec<AgentDefinition> = serde_json::from_str(&json_str).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to parse agent definitions from response. Error: {}\nResponse: {}",
-            e,
-            response
-        )
-    })?;
+    let agents: Vec<AgentDefinition> = serde_json::from_str(json_str)
+        .map_err(|e| anyhow::anyhow!("Failed to parse agent definitions: {}", e))?;
 
     // Validate count
     if agents.len() != expected_roles.len() {
@@ -156,20 +316,158 @@ fn parse_agent_response(
         ));
     }
 
+    for (idx, agent) in agents.iter().enumerate() {
+        agent.validate().map_err(|e| {
+            anyhow::anyhow!(
+                "Generated agent {} ({}) failed validation: {}",
+                idx + 1,
+                agent.persona,
+                e
+            )
+        })?;
+    }
+
     Ok(agents)
 }
 
-fn extract_json_array(text: &str) -> anyhow::Result<String> {
-    // Find the first '[' and last ']' to extract the JSON array
-    if let Some(start) = text.find('[') {
-        if let Some(end) = text.rfind(']') {
-            if start < end {
-                return Ok(text[start..=end].to_string());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Prompt, timeout, access mode, and model of one invocation
+    type Call = (String, u64, AccessMode, Option<String>);
+
+    /// Answers with each of `responses` in turn, recording every call
+    struct Scripted {
+        responses: Mutex<Vec<String>>,
+        calls: Mutex<Vec<Call>>,
+    }
+
+    impl Scripted {
+        fn new(responses: &[&str]) -> Self {
+            Self {
+                responses: Mutex::new(responses.iter().map(|r| r.to_string()).collect()),
+                calls: Mutex::new(Vec::new()),
             }
         }
     }
 
-    Err(anyhow::anyhow!(
-        "Could not find valid JSON array in response. Expected format: [{{...}}]"
-    ))
+    #[async_trait::async_trait]
+    impl Invoker for Scripted {
+        async fn invoke(
+            &self,
+            prompt: &str,
+            timeout: u64,
+            access_mode: AccessMode,
+            model: Option<&str>,
+        ) -> anyhow::Result<String> {
+            self.calls.lock().unwrap().push((
+                prompt.to_string(),
+                timeout,
+                access_mode,
+                model.map(String::from),
+            ));
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    const VALID: &str = r#"Here you go:
+[{"cli": "claude", "persona": "CTO", "instructions": "Weigh long-term maintainability.",
+  "expertise": ["architecture"]}]"#;
+
+    fn options(retries: usize) -> GenerationOptions {
+        GenerationOptions {
+            cli: "scripted".to_string(),
+            model: Some("big-model".to_string()),
+            timeout: 42,
+            access_mode: AccessMode::WorkspaceWrite,
+            retries,
+        }
+    }
+
+    fn agent(cli: &str, persona: &str) -> AgentDefinition {
+        AgentDefinition {
+            cli: cli.to_string(),
+            model: Some("big".to_string()),
+            persona: persona.to_string(),
+            instructions: "Weigh in".to_string(),
+            expertise: vec![],
+            communication_style: None,
+            output_filters: vec![],
+        }
+    }
+
+    #[test]
+    fn test_assign_installed_clis() {
+        let mut agents = vec![
+            agent("claude", "CEO"),
+            agent("codex", "CTO"),
+            agent("gemini", "SRE"),
+        ];
+        let installed = vec!["claude".to_string(), "gemini".to_string()];
+        assign_installed_clis(&mut agents, &installed, "claude");
+        let clis: Vec<&str> = agents.iter().map(|a| a.cli.as_str()).collect();
+        assert_eq!(clis, ["claude", "claude", "gemini"]);
+        // Only the moved agent loses its model
+        assert_eq!(agents[0].model.as_deref(), Some("big"));
+        assert_eq!(agents[1].model, None);
+    }
+
+    #[test]
+    fn test_assign_installed_clis_falls_back_when_none_are_found() {
+        let mut agents = vec![agent("codex", "CTO"), agent("gemini", "SRE")];
+        assign_installed_clis(&mut agents, &[], "my-llm");
+        assert!(agents.iter().all(|a| a.cli == "my-llm"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_passes_options_to_invoker() {
+        let scripted = Scripted::new(&[VALID]);
+        let definitions = generate_with(&scripted, "Monorepo?", &["CTO"], &options(0))
+            .await
+            .unwrap();
+        assert_eq!(definitions[0].persona, "CTO");
+
+        let calls = scripted.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 1);
+        let (prompt, timeout, access_mode, model) = &calls[0];
+        assert!(prompt.contains("Topic: \"Monorepo?\""));
+        assert_eq!(*timeout, 42);
+        assert_eq!(*access_mode, AccessMode::WorkspaceWrite);
+        assert_eq!(model.as_deref(), Some("big-model"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_retries_unusable_responses() {
+        // Too-short instructions fail validation, then a valid answer
+        let invalid = r#"[{"cli": "claude", "persona": "CTO", "instructions": "Be brief"}]"#;
+        let scripted = Scripted::new(&[invalid, VALID]);
+        let definitions = generate_with(&scripted, "Monorepo?", &["CTO"], &options(1))
+            .await
+            .unwrap();
+        assert_eq!(definitions.len(), 1);
+        let calls = scripted.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[1].0.contains("failed validation"));
+        assert!(calls[1].0.contains("PREVIOUS RESPONSE:\n[{\"cli\""));
+        assert_eq!(calls[1].3.as_deref(), Some("big-model"));
+
+        // Retries exhausted: the last problem and response are reported
+        let scripted = Scripted::new(&["no json here", "[]"]);
+        let err = generate_with(&scripted, "Monorepo?", &["CTO"], &options(1))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Expected 1 agents, but got 0"));
+        assert!(err.contains("Response: []"));
+    }
 }
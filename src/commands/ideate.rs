@@ -1,13 +1,15 @@
 //! Ideate command - Generate divergent idea trees from a seed
 
+use crate::app::GptEngage;
 use crate::invokers::AccessMode;
 use crate::orchestrator::ideation::{
     expand_leaves, generate_l1, generate_l2, validate_sigma, IdeationResult,
 };
 use crate::utils::colors::ColorMode;
+use crate::utils::interaction::ask;
 use crate::utils::pager::run_pager;
 use crate::utils::tree_renderer::render_idea_tree;
-use std::io::{self, BufRead, Write};
+use std::io;
 
 /// Options for the ideate command
 pub struct IdeateOptions {
@@ -25,7 +27,7 @@ pub struct IdeateOptions {
 }
 
 /// Run the ideate command
-pub async fn run_ideate(opts: IdeateOptions) -> anyhow::Result<()> {
+pub async fn run_ideate(engage: &GptEngage, opts: IdeateOptions) -> anyhow::Result<()> {
     // Validate inputs
     validate_sigma(opts.sigma, opts.force)?;
 
@@ -62,6 +64,7 @@ pub async fn run_ideate(opts: IdeateOptions) -> anyhow::Result<()> {
         eprintln!("Level 1: Generating 3 divergent ideas...");
     }
     let mut l1_ideas = generate_l1(
+        &engage.invokers,
         &opts.seed,
         opts.sigma,
         &opts.cli,
@@ -95,6 +98,7 @@ pub async fn run_ideate(opts: IdeateOptions) -> anyhow::Result<()> {
             }
 
             generate_l2(
+                &engage.invokers,
                 &mut l1_ideas,
                 &selected,
                 opts.sigma,
@@ -111,6 +115,7 @@ pub async fn run_ideate(opts: IdeateOptions) -> anyhow::Result<()> {
                 eprintln!("Level {}: Expanding leaf nodes ...", level);
             }
             expand_leaves(
+                &engage.invokers,
                 &mut l1_ideas,
                 opts.sigma,
                 &opts.cli,
@@ -155,16 +160,15 @@ pub async fn run_ideate(opts: IdeateOptions) -> anyhow::Result<()> {
 
 /// Prompt the user to select which L1 ideas to expand
 fn prompt_selection(count: usize) -> anyhow::Result<Vec<usize>> {
-    eprint!(
-        "Which ideas should we expand to level 2? [1-{} or \"all\"]: ",
+    let question = format!(
+        "Which ideas should we expand to level 2? [1-{} or \"all\"]:",
         count
     );
-    io::stderr().flush()?;
-
-    let stdin = io::stdin();
-    let mut line = String::new();
-    stdin.lock().read_line(&mut line)?;
-    let input = line.trim();
+    let Some(input) = ask(&question)? else {
+        eprintln!("Not asking which ideas to expand (not interactive); expanding all of them.");
+        return Ok((0..count).collect());
+    };
+    let input = input.as_str();
 
     if input.eq_ignore_ascii_case("all") {
         return Ok((0..count).collect());
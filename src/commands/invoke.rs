@@ -1,39 +1,88 @@
 //! Invoke command - Invoke a specific CLI with optional session support
 
+use crate::app::GptEngage;
 use crate::cli::StdinMode;
-use crate::invokers::{get_invoker, AccessMode};
-use crate::session::SessionManager;
-use crate::utils::stdin::{format_piped_context, read_stdin_if_piped};
+use crate::invokers::dialect::Dialect;
+use crate::invokers::logs::capture_log_paths;
+use crate::invokers::policy::policy;
+use crate::invokers::AccessMode;
+use crate::orchestrator::best_of::{generate_candidates, judge_candidates, BestOfOptions};
+use crate::orchestrator::continuation::invoke_with_continuation;
+use crate::orchestrator::response_format::{enforce, ResponseFormat};
+use crate::plugins::context::gather_context;
+use crate::plugins::middleware::apply_middleware;
+use crate::session::{prompt_with_turns, recent_turns, unseen_turns, NativeConversation};
+use crate::utils::extract::Extractor;
+use crate::utils::prompt_preview::{PromptPreview, ShowPrompt};
+use crate::utils::run_snapshot::{record_run, snapshot_run, RunSnapshot};
+use crate::utils::stdin::{format_piped_context, read_stdin_if_piped, StdinFile};
+use crate::utils::tokens::counter_for;
+use crate::utils::workspace::{
+    track_changes, tracked_changes, WorkspaceChanges, WorkspaceSnapshot,
+};
 
 /// Invoke a specific CLI with a prompt
 #[allow(clippy::too_many_arguments)]
 pub async fn run_invoke(
+    engage: &GptEngage,
     cli: String,
     model: Option<String>,
     mut prompt: String,
     session_name: Option<String>,
     topic: Option<String>,
     context_file: Option<String>,
+    context_providers: Vec<String>,
     timeout: u64,
     access_mode: AccessMode,
     stdin_as: StdinMode,
+    show_prompt: ShowPrompt,
+    history_last: Option<usize>,
+    native_session: bool,
+    best_of: Option<BestOfOptions>,
+    extract: Option<Extractor>,
+    response_format: Option<ResponseFormat>,
 ) -> anyhow::Result<()> {
-    // Handle stdin input based on mode
+    let response_format = ResponseFormat::for_command(
+        response_format,
+        &engage.config()?.config.response_formats,
+        "invoke",
+    )?;
+    // Sizes of the prompt components, for --show-prompt
+    let mut stdin_len = 0;
+    let mut request_len = prompt.len();
+    // The request as asked, without piped context, for context providers
+    let asked = prompt.clone();
+
+    // Handle stdin input based on mode; a saved stdin file lives until the
+    // invocation is done
+    let mut _stdin_file = None;
     if let Some(stdin_content) = read_stdin_if_piped() {
         match stdin_as {
             StdinMode::Auto => {
                 if prompt.is_empty() {
                     // No prompt provided, use stdin as prompt
+                    request_len = stdin_content.len();
                     prompt = stdin_content;
                 } else {
                     // Prompt provided, prepend stdin as context
+                    stdin_len = stdin_content.len();
                     prompt = format!("{}\n\n{}", format_piped_context(&stdin_content), prompt);
                 }
             }
             StdinMode::Context => {
                 // Always prepend stdin as context
+                stdin_len = stdin_content.len();
                 prompt = format!("{}\n\n{}", format_piped_context(&stdin_content), prompt);
             }
+            StdinMode::File => {
+                if prompt.is_empty() {
+                    anyhow::bail!("--stdin-as file needs a prompt to go with the piped input");
+                }
+                let file = StdinFile::create(&stdin_content)?;
+                stdin_len = file.reference().len();
+                prompt = format!("{}\n\n{}", file.reference(), prompt);
+                _stdin_file = Some(file);
+            }
             StdinMode::Ignore => {
                 // Do nothing with stdin
             }
@@ -47,16 +96,30 @@ pub async fn run_invoke(
         ));
     }
 
+    // Add the output of context providers
+    let mut provided_len = 0;
+    if !context_providers.is_empty() {
+        let request = if asked.is_empty() { &prompt } else { &asked };
+        let provided =
+            gather_context(engage.plugins()?, &context_providers, request, timeout).await?;
+        if !provided.is_empty() {
+            provided_len = provided.len();
+            prompt = format!("{}\n\n{}", provided, prompt);
+        }
+    }
+
     // Load context from file if provided
+    let mut file_part = None;
     if let Some(file) = context_file {
         let file_content = tokio::fs::read_to_string(&file).await?;
+        file_part = Some((format!("context file {}", file), file_content.len()));
         prompt = format!("File: {}\n\n{}\n\n{}", file, file_content, prompt);
     }
 
     // Handle session if provided
     let mut session_manager = None;
     if session_name.is_some() || topic.is_some() {
-        session_manager = Some(SessionManager::new()?);
+        session_manager = Some(engage.sessions()?);
     }
 
     // Load existing session if it exists
@@ -71,18 +134,78 @@ pub async fn run_invoke(
         None
     };
 
-    // Build full prompt with history if session exists
-    let full_prompt = if let Some(ref s) = session {
+    // Build full prompt with history if session exists (the session
+    // itself keeps every turn, whatever --history-last/--no-history say)
+    let history_prompt = if let Some(ref s) = session {
         session_manager
             .as_ref()
             .unwrap()
-            .build_prompt_with_history(s, &prompt)
+            .build_prompt_with_recent_history(s, &prompt, history_last)
     } else {
         prompt.clone()
     };
 
+    // A session continuing this CLI's own conversation resumes it, sending
+    // only the turns the conversation hasn't seen
+    let resume = session.as_ref().and_then(|s| {
+        let unseen = unseen_turns(s, &cli)?;
+        Some((s.native.as_ref()?.id.clone(), unseen))
+    });
+    let native = best_of.is_none() && (native_session || resume.is_some());
+    let resume = resume.filter(|_| native);
+    let full_prompt = match &resume {
+        Some((_, unseen)) => prompt_with_turns(unseen, &prompt),
+        None => history_prompt.clone(),
+    };
+    let plugins = engage.plugins()?;
+    let full_prompt = Dialect::for_cli(&cli, Some(plugins)).adapt(&full_prompt);
+
+    let cli_display = match &model {
+        Some(m) => format!("{}:{}", cli, m),
+        None => cli.clone(),
+    };
+
+    if show_prompt != ShowPrompt::Off {
+        let (history_label, history_len) = match (&session, &resume) {
+            (Some(s), Some((_, unseen))) => (
+                format!(
+                    "session turns new to the {} conversation ({} of {})",
+                    cli,
+                    unseen.len(),
+                    s.turns.len()
+                ),
+                unseen.iter().map(|t| t.content.len()).sum(),
+            ),
+            (Some(s), None) => {
+                let turns = recent_turns(s, history_last);
+                (
+                    format!(
+                        "session history ({} of {} turns)",
+                        turns.len(),
+                        s.turns.len()
+                    ),
+                    turns.iter().map(|t| t.content.len()).sum(),
+                )
+            }
+            (None, _) => (String::new(), 0),
+        };
+        let (file_label, file_len) = file_part.unwrap_or_default();
+        let preview = PromptPreview::new(&cli_display, &full_prompt)
+            .counted_with(counter_for(&cli, model.as_deref()).as_ref())
+            .part(history_label, history_len)
+            .part(file_label, file_len)
+            .part("context providers", provided_len)
+            .part("piped stdin", stdin_len)
+            .part("request", request_len)
+            .render()?;
+        show_prompt.emit(&preview);
+        if show_prompt == ShowPrompt::Only {
+            return Ok(());
+        }
+    }
+
     // Get the appropriate invoker (built-in or plugin)
-    let invoker = get_invoker(&cli).ok_or_else(|| {
+    let invoker = engage.invokers.get(&cli).ok_or_else(|| {
         anyhow::anyhow!(
             "Unknown CLI: '{}'. Use a built-in CLI (claude, codex, gemini) or an installed plugin.",
             cli
@@ -90,25 +213,170 @@ pub async fn run_invoke(
     })?;
 
     // Check if the CLI is available
-    if !invoker.is_available() {
+    if !invoker.is_available().await {
         return Err(anyhow::anyhow!(
             "CLI '{}' not found in PATH. Ensure it is installed and accessible.",
             cli
         ));
     }
+    if native && !invoker.supports_native_sessions() {
+        return Err(anyhow::anyhow!(
+            "CLI '{}' doesn't support native sessions. Omit --native-session.",
+            cli
+        ));
+    }
 
-    // Invoke the CLI
-    let cli_display = match &model {
-        Some(m) => format!("{}:{}", cli, m),
-        None => cli.clone(),
+    // Middleware rewrites the prompt as it will be sent
+    let sent_prompt = apply_middleware(&full_prompt, timeout, plugins).await?;
+
+    let (mut run, snapshot) = match access_mode {
+        AccessMode::WorkspaceWrite => (snapshot_run(&format!("invoke {}", cli))?, track_changes()),
+        AccessMode::ReadOnly => (None, None),
     };
-    eprintln!("Invoking {}...", cli_display);
-    let response = invoker
-        .invoke(&full_prompt, timeout, access_mode, model.as_deref())
-        .await?;
+    let show_candidates = best_of.as_ref().is_some_and(|b| b.show_candidates);
+    let mut log = None;
+    let mut native_id = None;
+    let response = match best_of {
+        Some(best_of) => {
+            // Sample several candidates and let the judge pick one
+            eprintln!(
+                "Invoking {} ({} candidates)...",
+                cli_display, best_of.candidates
+            );
+            let candidates = generate_candidates(
+                invoker.as_ref(),
+                &sent_prompt,
+                best_of.candidates,
+                timeout,
+                access_mode,
+                model.as_deref(),
+            )
+            .await?;
+            let judgement = judge_candidates(
+                &engage.invokers,
+                &best_of.judge,
+                &prompt,
+                &candidates,
+                timeout,
+                access_mode,
+            )
+            .await?;
 
-    // Print response
-    println!("{}", response);
+            if best_of.show_candidates {
+                for (i, candidate) in candidates.iter().enumerate() {
+                    let mut notes = Vec::new();
+                    if i == judgement.winner {
+                        notes.push("winner".to_string());
+                    }
+                    // Judge plugins score every candidate
+                    if let Some(score) = judgement.scores.get(i) {
+                        notes.push(format!("score {}", score));
+                    }
+                    let marker = if notes.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", notes.join(", "))
+                    };
+                    println!("=== Candidate {}{} ===", i + 1, marker);
+                    println!("{}", candidate);
+                    println!();
+                }
+            }
+            eprintln!(
+                "\n{} picked candidate {} of {}: {}",
+                best_of.judge,
+                judgement.winner + 1,
+                candidates.len(),
+                judgement.justification
+            );
+            candidates[judgement.winner].clone()
+        }
+        None => {
+            // Invoke the CLI
+            eprintln!("Invoking {}...", cli_display);
+            let resume_id = resume.as_ref().map(|(id, _)| id.as_str());
+            let (result, logs) = capture_log_paths(async {
+                if !native {
+                    return invoke_with_continuation(
+                        invoker.as_ref(),
+                        &sent_prompt,
+                        timeout,
+                        access_mode,
+                        model.as_deref(),
+                        policy().max_continuations,
+                    )
+                    .await;
+                }
+                let model = model.as_deref();
+                let reply = match invoker
+                    .invoke_native(&sent_prompt, timeout, access_mode, model, resume_id)
+                    .await
+                {
+                    // The CLI may have expired the conversation; start over
+                    // from the session history
+                    Err(e) if resume_id.is_some() => {
+                        eprintln!(
+                            "Could not resume the {} conversation ({}); starting a new one",
+                            cli, e
+                        );
+                        invoker
+                            .invoke_native(&history_prompt, timeout, access_mode, model, None)
+                            .await?
+                    }
+                    result => result?,
+                };
+                native_id = Some(reply.conversation_id);
+                Ok(reply.response)
+            })
+            .await;
+            log = logs.last().map(|path| path.display().to_string());
+            match result {
+                Ok(response) => response,
+                Err(e) => {
+                    if let Some(log) = log {
+                        eprintln!("Raw output: {}", log);
+                    }
+                    report_changes(snapshot.as_ref(), run.take())?;
+                    return Err(e);
+                }
+            }
+        }
+    };
+
+    // An answer in the wrong format is asked for once more
+    let response = match &response_format {
+        Some(format) => {
+            let (invoker, model) = (invoker.as_ref(), model.as_deref());
+            enforce(format, &full_prompt, response, &cli_display, |corrective| {
+                let corrective = Dialect::for_cli(&cli, Some(plugins)).adapt(&corrective);
+                async move {
+                    let corrective = apply_middleware(&corrective, timeout, plugins).await?;
+                    invoke_with_continuation(
+                        invoker,
+                        &corrective,
+                        timeout,
+                        access_mode,
+                        model,
+                        policy().max_continuations,
+                    )
+                    .await
+                }
+            })
+            .await
+        }
+        None => response,
+    };
+
+    let changes = report_changes(snapshot.as_ref(), run)?;
+
+    // Print response. With --extract, stdout carries only the short
+    // answer; the full response still goes into the session.
+    let answer = extract.as_ref().map(|e| e.extract(&response));
+    match &answer {
+        Some(Some(answer)) => println!("{}", answer),
+        None if !show_candidates => println!("{}", response),
+        _ => {}
+    }
 
     // Update session if applicable
     if let (Some(name), Some(manager)) = (&session_name, &session_manager) {
@@ -122,12 +390,49 @@ pub async fn run_invoke(
 
         // Add user message and response to session
         manager.add_turn(&mut s, "user".to_string(), prompt);
-        manager.add_turn(&mut s, "assistant".to_string(), response);
+        manager.add_turn(&mut s, "assistant".to_string(), response.clone());
+        if let Some(turn) = s.turns.last_mut() {
+            turn.log = log;
+            turn.changes = changes;
+        }
+        if let Some(id) = native_id {
+            s.native = Some(NativeConversation {
+                cli: cli.clone(),
+                id,
+                turns: s.turns.len(),
+            });
+        }
 
         // Save session
         manager.save_session(&s).await?;
-        println!("\n(Session '{}' saved)", name);
+        if extract.is_some() {
+            eprintln!("(Session '{}' saved)", name);
+        } else {
+            println!("\n(Session '{}' saved)", name);
+        }
+    }
+
+    if let (Some(extractor), Some(None)) = (&extract, &answer) {
+        eprintln!("{}", response);
+        return Err(anyhow::anyhow!(
+            "Could not extract an answer ({}) from the response",
+            extractor.describe()
+        ));
     }
 
     Ok(())
 }
+
+/// Print the files changed since `snapshot` to stderr and record them
+/// with the `--snapshot` run, returning them
+fn report_changes(
+    snapshot: Option<&WorkspaceSnapshot>,
+    run: Option<RunSnapshot>,
+) -> anyhow::Result<Option<WorkspaceChanges>> {
+    let changes = tracked_changes(snapshot);
+    record_run(run, changes.as_ref());
+    if let Some(ref changes) = changes {
+        eprint!("\nWorkspace changes:\n{}", changes.render()?);
+    }
+    Ok(changes)
+}
@@ -0,0 +1,331 @@
+//! Issues - Filing a debate's action items
+//!
+//! `--create-issues` runs `gh issue create` once per action item, in the
+//! current directory's repository and with the user's own `gh` login.
+//! `--create-issues <NAME>` instead POSTs each item to the webhook
+//! configured under `issue_trackers.<NAME>` (Jira, Linear, or anything
+//! else that takes JSON), built from that tracker's body template. Each
+//! created issue's URL or key is recorded on its item. An item the tracker
+//! rejects is reported and skipped; the rest are still filed.
+
+use crate::app::GptEngage;
+use crate::audit::ProcessAudit;
+use crate::config::IssueTrackerConfig;
+use crate::orchestrator::actions::ActionItem;
+use crate::orchestrator::DebateResult;
+
+/// `--create-issues` target that files issues with `gh`
+pub const GITHUB: &str = "github";
+
+/// Placeholders a tracker's template may use
+const PLACEHOLDERS: [&str; 5] = ["task", "owner", "priority", "topic", "body"];
+
+/// The configured tracker called `name`
+pub fn tracker(engage: &GptEngage, name: &str) -> anyhow::Result<IssueTrackerConfig> {
+    let resolved = engage.config()?;
+    resolved
+        .config
+        .issue_trackers
+        .get(name)
+        .cloned()
+        .ok_or_else(|| {
+            let mut known: Vec<&str> = vec![GITHUB];
+            known.extend(resolved.config.issue_trackers.keys().map(String::as_str));
+            anyhow::anyhow!(
+                "Unknown issue tracker '{}'. Use one of: {} (add trackers under issue_trackers in ~/.gptengage/config.json)",
+                name,
+                known.join(", ")
+            )
+        })
+}
+
+/// Body of the issue for `item`
+fn issue_body(topic: &str, item: &ActionItem) -> String {
+    let mut body = format!("Follow-up from the debate: {}\n\n", topic);
+    if let Some(owner) = &item.owner {
+        body.push_str(&format!("- Suggested owner: {}\n", owner));
+    }
+    body.push_str(&format!("- Priority: {}\n", item.priority));
+    body.push_str("\n_Extracted by gptengage._\n");
+    body
+}
+
+/// Create one issue, returning its URL, or gh's error when it rejected
+/// the issue. Failing to run gh at all is an error of its own.
+async fn create_issue(title: &str, body: &str) -> anyhow::Result<Result<String, String>> {
+    let mut command = tokio::process::Command::new("gh");
+    command
+        .args(["issue", "create", "--title", title, "--body", body])
+        .stdin(std::process::Stdio::null());
+    let audit = ProcessAudit::start(command.as_std())
+        .redact(title)
+        .redact(body);
+    let output = command.output().await.map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to run gh (install the GitHub CLI and run `gh auth login`): {}",
+            e
+        )
+    })?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        return Ok(Err(String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_string()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Ok(stdout
+        .lines()
+        .rev()
+        .find(|line| line.starts_with("http"))
+        .unwrap_or(stdout.trim())
+        .to_string()))
+}
+
+/// `template` with the placeholders in its strings filled in from `item`
+fn fill_template(
+    template: &serde_json::Value,
+    topic: &str,
+    item: &ActionItem,
+) -> serde_json::Value {
+    use serde_json::Value;
+    match template {
+        Value::String(text) => {
+            let body = issue_body(topic, item);
+            let values = [
+                item.task.as_str(),
+                item.owner.as_deref().unwrap_or(""),
+                &item.priority,
+                topic,
+                &body,
+            ];
+            let mut text = text.clone();
+            for (name, value) in PLACEHOLDERS.iter().zip(values) {
+                text = text.replace(&format!("{{{}}}", name), value);
+            }
+            Value::String(text)
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| fill_template(value, topic, item))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), fill_template(value, topic, item)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// `value` with each `${VAR}` replaced by that environment variable
+fn expand_env(value: &str, lookup: impl Fn(&str) -> Option<String>) -> anyhow::Result<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        let expanded = lookup(name)
+            .ok_or_else(|| anyhow::anyhow!("Environment variable {} is not set", name))?;
+        out.push_str(&rest[..start]);
+        out.push_str(&expanded);
+        rest = &rest[start + 3 + len..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// curl config setting each header, so secret header values reach curl
+/// on stdin rather than in its (world-readable) command line
+fn curl_header_config(headers: &[(String, String)]) -> anyhow::Result<String> {
+    let mut config = String::new();
+    for (name, value) in headers {
+        let header = format!("{}: {}", name, value);
+        if header.contains(['\r', '\n']) {
+            anyhow::bail!("Issue tracker header {} contains a line break", name);
+        }
+        let quoted = header.replace('\\', "\\\\").replace('"', "\\\"");
+        config.push_str(&format!("header = \"{}\"\n", quoted));
+    }
+    Ok(config)
+}
+
+/// POST one issue to a webhook tracker, returning its URL or key, or the
+/// tracker's error when it rejected the issue. Failing to run curl at
+/// all is an error of its own.
+///
+/// Headers are passed as a curl config on stdin (`-K -`) and the payload
+/// through a private temp file, so neither shows up in `ps`.
+async fn post_issue(
+    tracker: &IssueTrackerConfig,
+    payload: &serde_json::Value,
+) -> anyhow::Result<Result<String, String>> {
+    use std::io::Write;
+    use tokio::io::AsyncWriteExt;
+
+    let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+    for (name, value) in &tracker.headers {
+        let value = expand_env(value, |var| std::env::var(var).ok())
+            .map_err(|e| anyhow::anyhow!("Issue tracker header {}: {}", name, e))?;
+        headers.push((name.clone(), value));
+    }
+    let config = curl_header_config(&headers)?;
+
+    let mut body = tempfile::NamedTempFile::new()?;
+    body.write_all(payload.to_string().as_bytes())?;
+    body.flush()?;
+
+    let mut command = tokio::process::Command::new("curl");
+    command
+        .args(["-fsS", "--max-time", "30", "-X", "POST", "-K", "-"])
+        .arg("--data-binary")
+        .arg(format!("@{}", body.path().display()))
+        .arg(&tracker.url)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    let audit = ProcessAudit::start(command.as_std());
+    let mut child = command
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run curl to post to {}: {}", tracker.url, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        return Ok(Err(String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_string()));
+    }
+    Ok(Ok(issue_reference(
+        &String::from_utf8_lossy(&output.stdout),
+        tracker.response_url.as_deref(),
+    )))
+}
+
+/// What to record for a created issue: the value at `pointer` in the
+/// response, or the whole (trimmed) response without one
+fn issue_reference(response: &str, pointer: Option<&str>) -> String {
+    let found = pointer.and_then(|pointer| {
+        let json: serde_json::Value = serde_json::from_str(response).ok()?;
+        match json.pointer(pointer)? {
+            serde_json::Value::String(text) => Some(text.clone()),
+            other => Some(other.to_string()),
+        }
+    });
+    found.unwrap_or_else(|| response.trim().to_string())
+}
+
+/// File each of the result's action items that has no issue yet, with
+/// `gh` or, when given, a webhook tracker
+pub async fn create_issues(
+    result: &mut DebateResult,
+    tracker: Option<&IssueTrackerConfig>,
+) -> anyhow::Result<()> {
+    let topic = crate::history::topic_summary(&result.topic);
+    for item in result.action_items.iter_mut().filter(|i| i.issue.is_none()) {
+        let created = match tracker {
+            Some(tracker) => {
+                post_issue(tracker, &fill_template(&tracker.template, &topic, item)).await?
+            }
+            None => create_issue(&item.task, &issue_body(&topic, item)).await?,
+        };
+        match created {
+            Ok(url) => {
+                eprintln!("Created issue: {}", url);
+                item.issue = Some(url);
+            }
+            Err(e) => eprintln!(
+                "Warning: could not create an issue for '{}': {}",
+                item.task, e
+            ),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_body() {
+        let item = ActionItem {
+            task: "Add a CI check".to_string(),
+            owner: Some("SRE".to_string()),
+            priority: "high".to_string(),
+            issue: None,
+        };
+        assert_eq!(
+            issue_body("Tabs?", &item),
+            "Follow-up from the debate: Tabs?\n\n- Suggested owner: SRE\n- Priority: high\n\n_Extracted by gptengage._\n"
+        );
+    }
+
+    #[test]
+    fn test_fill_template_and_headers() {
+        let item = ActionItem {
+            task: "Fix \"quoting\"".to_string(),
+            owner: None,
+            priority: "low".to_string(),
+            issue: None,
+        };
+        let template = serde_json::json!({
+            "fields": {
+                "project": {"key": "OPS"},
+                "summary": "{task}",
+                "labels": ["gptengage", "{priority}"],
+                "description": "{body}{missing}",
+                "assignee": "{owner}"
+            },
+            "query": "mutation { issueCreate { issue { url } } }",
+            "notify": true
+        });
+        let filled = fill_template(&template, "Tabs?", &item);
+        assert_eq!(filled["fields"]["summary"], "Fix \"quoting\"");
+        assert_eq!(filled["fields"]["labels"][1], "low");
+        assert_eq!(filled["fields"]["assignee"], "");
+        assert!(filled["fields"]["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("Follow-up from the debate: Tabs?"));
+        assert!(filled["fields"]["description"]
+            .as_str()
+            .unwrap()
+            .ends_with("{missing}"));
+        assert_eq!(filled["query"], template["query"]);
+        assert_eq!(filled["notify"], true);
+
+        let lookup = |name: &str| (name == "TOKEN").then(|| "s3cret".to_string());
+        assert_eq!(
+            expand_env("Bearer ${TOKEN}", lookup).unwrap(),
+            "Bearer s3cret"
+        );
+        assert_eq!(expand_env("no vars", lookup).unwrap(), "no vars");
+        assert!(expand_env("${UNSET}", lookup).is_err());
+
+        let headers = vec![
+            ("Authorization".to_string(), "Bearer s3cret".to_string()),
+            ("X-Note".to_string(), r#"say "hi" \ bye"#.to_string()),
+        ];
+        assert_eq!(
+            curl_header_config(&headers).unwrap(),
+            "header = \"Authorization: Bearer s3cret\"\nheader = \"X-Note: say \\\"hi\\\" \\\\ bye\"\n"
+        );
+        let split = vec![("X-Bad".to_string(), "a\r\nInjected: 1".to_string())];
+        assert!(curl_header_config(&split).is_err());
+
+        let response =
+            r#"{"data": {"issueCreate": {"issue": {"url": "https://linear.app/t/OPS-7"}}}}"#;
+        assert_eq!(
+            issue_reference(response, Some("/data/issueCreate/issue/url")),
+            "https://linear.app/t/OPS-7"
+        );
+        assert_eq!(issue_reference(" OPS-7\n", None), "OPS-7");
+    }
+}
@@ -0,0 +1,521 @@
+//! LSP bridge - JSON-RPC over stdio for editor extensions
+//!
+//! `gptengage lsp-bridge` speaks JSON-RPC 2.0 on stdin and stdout, framed
+//! with `Content-Length` headers as in the Language Server Protocol, so a
+//! Neovim or VS Code extension can drive gptengage without scraping
+//! terminal output. Requests are handled one at a time, in order:
+//!
+//! - `initialize` returns the server's name, version, and methods
+//! - `invoke` runs one CLI, optionally within a session
+//! - `review` runs a code-review debate on a diff
+//! - `session/list`, `session/show`, and `session/delete` manage sessions
+//! - `shutdown` and the `exit` notification end the bridge
+//!
+//! While a request runs, `gptengage/progress` notifications report what it
+//! is doing and `review` sends each participant's response as a
+//! `gptengage/response` notification as soon as it arrives. Stdout carries
+//! nothing but protocol messages; diagnostics go to stderr.
+//!
+//! Each request is its own run as far as CLI circuit breakers go, unless
+//! `--keep-circuits` keeps them open for the bridge's lifetime.
+
+use crate::app::GptEngage;
+use crate::commands::debate::parse_participants;
+use crate::invokers::circuit::circuits;
+use crate::invokers::dialect::Dialect;
+use crate::invokers::policy::policy;
+use crate::invokers::AccessMode;
+use crate::orchestrator::continuation::invoke_with_continuation;
+use crate::orchestrator::{PromptContext, RoundEvent, RoundOptions};
+use crate::plugins::middleware::apply_middleware;
+use crate::utils::run_snapshot::{record_run, snapshot_run};
+use crate::utils::stdin::format_piped_context;
+use crate::utils::workspace::{track_changes, tracked_changes};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Methods the bridge answers, as returned by `initialize`
+const METHODS: [&str; 7] = [
+    "initialize",
+    "shutdown",
+    "invoke",
+    "review",
+    "session/list",
+    "session/show",
+    "session/delete",
+];
+
+/// A JSON-RPC error response
+#[derive(Debug, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    const PARSE_ERROR: i64 = -32700;
+    const INVALID_REQUEST: i64 = -32600;
+    const METHOD_NOT_FOUND: i64 = -32601;
+    const INVALID_PARAMS: i64 = -32602;
+    /// A request that was understood but failed
+    const REQUEST_FAILED: i64 = -32000;
+
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(Self::REQUEST_FAILED, format!("{:#}", e))
+    }
+}
+
+/// Deserialize a request's params, rejecting unknown or mistyped fields
+fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(RpcError::INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+/// Read one framed message. Returns `None` at the end of input.
+async fn read_message<R: AsyncBufRead + Unpin>(input: &mut R) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line).await? == 0 {
+            return match length {
+                None => Ok(None),
+                Some(_) => anyhow::bail!("Input ended inside a message header"),
+            };
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse::<usize>().map_err(|_| {
+                    anyhow::anyhow!("Invalid Content-Length header: {}", value.trim())
+                })?);
+            }
+        }
+    }
+    let length = length.ok_or_else(|| anyhow::anyhow!("Message has no Content-Length header"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body).await?;
+    Ok(Some(body))
+}
+
+/// Writes framed messages to the editor
+struct Bridge<W> {
+    output: W,
+}
+
+impl<W: AsyncWrite + Unpin> Bridge<W> {
+    async fn send(&mut self, message: Value) -> anyhow::Result<()> {
+        let body = message.to_string();
+        self.output
+            .write_all(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).as_bytes())
+            .await?;
+        self.output.flush().await?;
+        Ok(())
+    }
+
+    async fn respond(&mut self, id: Value, result: Result<Value, RpcError>) -> anyhow::Result<()> {
+        self.send(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {"code": e.code, "message": e.message},
+            }),
+        })
+        .await
+    }
+
+    async fn notify(&mut self, method: &str, params: Value) -> anyhow::Result<()> {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}))
+            .await
+    }
+
+    /// Report progress on the request `id`
+    async fn progress(&mut self, id: &Value, message: &str) -> anyhow::Result<()> {
+        self.notify("gptengage/progress", json!({"id": id, "message": message}))
+            .await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InvokeParams {
+    cli: String,
+    prompt: String,
+    model: Option<String>,
+    /// Session to continue, created when it doesn't exist yet
+    session: Option<String>,
+    timeout: Option<u64>,
+    #[serde(default)]
+    write: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReviewParams {
+    /// The change to review
+    diff: String,
+    /// What to ask about the change
+    #[serde(default = "default_review_request")]
+    request: String,
+    #[serde(default = "default_review_template")]
+    template: String,
+    /// Participants instead of the template's, as in `--participants`
+    participants: Option<String>,
+    rounds: Option<usize>,
+    #[serde(default = "default_true")]
+    synthesize: bool,
+    #[serde(default = "default_synthesizer")]
+    synthesizer: String,
+    timeout: Option<u64>,
+}
+
+fn default_review_request() -> String {
+    "Review this change".to_string()
+}
+
+fn default_review_template() -> String {
+    "code-review".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_synthesizer() -> String {
+    "claude".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SessionParams {
+    name: String,
+}
+
+/// Run one CLI, continuing and saving `session` when given
+async fn invoke<W: AsyncWrite + Unpin>(
+    engage: &GptEngage,
+    bridge: &mut Bridge<W>,
+    id: &Value,
+    params: InvokeParams,
+) -> Result<Value, RpcError> {
+    let invoker = engage.invokers.get(&params.cli).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown CLI: '{}'. Use a built-in CLI (claude, codex, gemini) or an installed plugin.",
+            params.cli
+        )
+    })?;
+    if !invoker.is_available().await {
+        return Err(anyhow::anyhow!("CLI '{}' not found in PATH.", params.cli).into());
+    }
+    let timeout = match params.timeout {
+        Some(timeout) => timeout,
+        None => engage.config()?.config.default_timeout,
+    };
+    let access_mode = AccessMode::from_write_flag(params.write);
+
+    let sessions = match params.session {
+        Some(_) => Some(engage.sessions()?),
+        None => None,
+    };
+    let mut session = match (&params.session, sessions) {
+        (Some(name), Some(manager)) => Some(match manager.load_session(name).await {
+            Ok(session) => session,
+            Err(_) => {
+                let topic = params.prompt.lines().next().unwrap_or("Chat").to_string();
+                manager.create_session(name.clone(), params.cli.clone(), topic)?
+            }
+        }),
+        _ => None,
+    };
+    let prompt = match (&session, sessions) {
+        (Some(session), Some(manager)) => {
+            manager.build_prompt_with_history(session, &params.prompt)
+        }
+        _ => params.prompt.clone(),
+    };
+    let plugins = engage.plugins()?;
+    let prompt = Dialect::for_cli(&params.cli, Some(plugins)).adapt(&prompt);
+    let prompt = apply_middleware(&prompt, timeout, plugins).await?;
+
+    let (run, snapshot) = match access_mode {
+        AccessMode::WorkspaceWrite => (
+            snapshot_run(&format!("lsp-bridge invoke {}", params.cli))?,
+            track_changes(),
+        ),
+        AccessMode::ReadOnly => (None, None),
+    };
+    bridge
+        .progress(id, &format!("Invoking {}...", params.cli))
+        .await?;
+    let response = invoke_with_continuation(
+        invoker.as_ref(),
+        &prompt,
+        timeout,
+        access_mode,
+        params.model.as_deref(),
+        policy().max_continuations,
+    )
+    .await;
+    record_run(run, tracked_changes(snapshot.as_ref()).as_ref());
+    let response = response?;
+
+    if let (Some(session), Some(manager)) = (&mut session, sessions) {
+        manager.add_turn(session, "user".to_string(), params.prompt);
+        manager.add_turn(session, "assistant".to_string(), response.clone());
+        manager.save_session(session).await?;
+    }
+    Ok(json!({
+        "cli": params.cli,
+        "response": response,
+        "session": session.map(|session| session.name),
+    }))
+}
+
+/// Debate a diff with a review template, streaming each response
+async fn review<W: AsyncWrite + Unpin>(
+    engage: &GptEngage,
+    bridge: &mut Bridge<W>,
+    id: &Value,
+    params: ReviewParams,
+) -> Result<Value, RpcError> {
+    if params.diff.trim().is_empty() {
+        return Err(RpcError::new(
+            RpcError::INVALID_PARAMS,
+            "Invalid params: diff is empty",
+        ));
+    }
+    let template = engage
+        .templates()?
+        .get_template(&params.template)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Template '{}' not found", params.template))?;
+    let participants = match &params.participants {
+        Some(participants) => parse_participants(participants)?,
+        None => template.to_participants()?,
+    };
+    let rounds = params.rounds.unwrap_or(template.default_rounds);
+    if rounds == 0 {
+        return Err(RpcError::new(
+            RpcError::INVALID_PARAMS,
+            "Invalid params: rounds must be at least 1",
+        ));
+    }
+    let timeout = match params.timeout {
+        Some(timeout) => timeout,
+        None => engage.config()?.config.default_timeout,
+    };
+    let topic = template.apply_context(&format!(
+        "{}\n\n{}",
+        format_piped_context(&params.diff),
+        params.request
+    ));
+
+    let (events, mut received) = tokio::sync::mpsc::unbounded_channel();
+    let options = RoundOptions {
+        latency: engage
+            .config()
+            .is_ok_and(|resolved| resolved.config.latency_scheduling),
+        events: Some(events),
+        ..RoundOptions::default()
+    };
+    let orchestrator = engage.orchestrator();
+    let debate = orchestrator.run_debate_with_participants(
+        &topic,
+        participants,
+        rounds,
+        timeout,
+        AccessMode::ReadOnly,
+        PromptContext::default(),
+        options,
+    );
+    // The events end when the debate is over and drops its sender
+    let forward = async {
+        while let Some(event) = received.recv().await {
+            match event {
+                RoundEvent::Progress(line) => bridge.progress(id, &line).await?,
+                RoundEvent::Response { round, response } => {
+                    bridge
+                        .notify(
+                            "gptengage/response",
+                            json!({
+                                "id": id,
+                                "round": round,
+                                "participant": response.display_name(),
+                                "response": response.response,
+                            }),
+                        )
+                        .await?
+                }
+            }
+        }
+        anyhow::Ok(())
+    };
+    let (result, forwarded) = tokio::join!(debate, forward);
+    forwarded?;
+    let mut result = result?;
+
+    if params.synthesize {
+        bridge
+            .progress(id, &format!("Synthesizing with {}...", params.synthesizer))
+            .await?;
+        result.synthesis = Some(
+            orchestrator
+                .generate_synthesis(&result, &params.synthesizer, timeout, AccessMode::ReadOnly)
+                .await?,
+        );
+    }
+    serde_json::to_value(&result).map_err(|e| anyhow::Error::from(e).into())
+}
+
+/// Answer one request
+async fn dispatch<W: AsyncWrite + Unpin>(
+    engage: &GptEngage,
+    bridge: &mut Bridge<W>,
+    id: &Value,
+    method: &str,
+    request: Value,
+) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(json!({
+            "serverInfo": {"name": "gptengage", "version": env!("CARGO_PKG_VERSION")},
+            "capabilities": {"methods": METHODS},
+        })),
+        "invoke" => invoke(engage, bridge, id, params(request)?).await,
+        "review" => review(engage, bridge, id, params(request)?).await,
+        "session/list" => {
+            let sessions = engage.sessions()?.list_sessions().await?;
+            Ok(Value::Array(
+                sessions
+                    .into_iter()
+                    .map(|session| {
+                        json!({
+                            "name": session.name,
+                            "cli": session.cli,
+                            "topic": session.topic,
+                            "last_interaction": session.last_interaction,
+                        })
+                    })
+                    .collect(),
+            ))
+        }
+        "session/show" => {
+            let SessionParams { name } = params(request)?;
+            let session = engage.sessions()?.load_session(&name).await?;
+            serde_json::to_value(&session).map_err(|e| anyhow::Error::from(e).into())
+        }
+        "session/delete" => {
+            let SessionParams { name } = params(request)?;
+            engage.sessions()?.delete_session(&name).await?;
+            Ok(Value::Null)
+        }
+        _ => Err(RpcError::new(
+            RpcError::METHOD_NOT_FOUND,
+            format!("Unknown method: {}", method),
+        )),
+    }
+}
+
+/// Serve JSON-RPC requests on stdin until `exit` or the end of input
+pub async fn run_lsp_bridge(engage: &mut GptEngage, keep_circuits: bool) -> anyhow::Result<()> {
+    let mut input = tokio::io::BufReader::new(tokio::io::stdin());
+    let mut bridge = Bridge {
+        output: tokio::io::stdout(),
+    };
+    let mut shut_down = false;
+    eprintln!("gptengage lsp-bridge: serving JSON-RPC on stdio");
+
+    while let Some(body) = read_message(&mut input).await? {
+        let message: Value = match serde_json::from_slice(&body) {
+            Ok(message) => message,
+            Err(e) => {
+                let error = RpcError::new(RpcError::PARSE_ERROR, format!("Parse error: {}", e));
+                bridge.respond(Value::Null, Err(error)).await?;
+                continue;
+            }
+        };
+        let method = message.get("method").and_then(Value::as_str);
+        if method == Some("exit") {
+            break;
+        }
+        // Other notifications need no answer
+        let Some(id) = message.get("id").cloned() else {
+            continue;
+        };
+        let result = match method {
+            None => Err(RpcError::new(
+                RpcError::INVALID_REQUEST,
+                "Invalid request: no method",
+            )),
+            Some(_) if shut_down => Err(RpcError::new(
+                RpcError::INVALID_REQUEST,
+                "The bridge is shutting down; only exit is accepted",
+            )),
+            Some("shutdown") => {
+                shut_down = true;
+                Ok(Value::Null)
+            }
+            Some(method) => {
+                if !keep_circuits {
+                    circuits().reset();
+                }
+                // Pick up templates and plugins edited while the bridge runs
+                if let Err(e) = engage.reload_if_changed() {
+                    eprintln!("Warning: Failed to reload templates and plugins: {:#}", e);
+                }
+                let request = message.get("params").cloned().unwrap_or(Value::Null);
+                dispatch(engage, &mut bridge, &id, method, request).await
+            }
+        };
+        bridge.respond(id, result).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_message_framing() {
+        let mut bridge = Bridge { output: Vec::new() };
+        bridge
+            .respond(
+                json!(1),
+                Err(RpcError::new(RpcError::METHOD_NOT_FOUND, "nope")),
+            )
+            .await
+            .unwrap();
+        bridge.progress(&json!(2), "Running").await.unwrap();
+
+        let mut input = bridge.output.as_slice();
+        let first: Value =
+            serde_json::from_slice(&read_message(&mut input).await.unwrap().unwrap()).unwrap();
+        assert_eq!(first["error"]["code"], -32601);
+        assert_eq!(first["id"], 1);
+        let second: Value =
+            serde_json::from_slice(&read_message(&mut input).await.unwrap().unwrap()).unwrap();
+        assert_eq!(second["method"], "gptengage/progress");
+        assert_eq!(second["params"]["message"], "Running");
+        assert!(read_message(&mut input).await.unwrap().is_none());
+
+        let mut unframed: &[u8] = b"{\"jsonrpc\":\"2.0\"}\r\n\r\n";
+        assert!(read_message(&mut unframed).await.is_err());
+
+        let error = params::<SessionParams>(json!({"name": "a", "extra": 1})).unwrap_err();
+        assert_eq!(error.code, RpcError::INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,123 @@
+//! Merge requests - Posting a debate to a pull or merge request
+//!
+//! `--post-to-mr` posts a debate's markdown output as a comment on a pull
+//! or merge request, with the CLI of the forge named by the `forge` config
+//! key: `gh` for GitHub, `glab` for GitLab, or `tea` for Gitea. The CLI
+//! runs in the current directory's repository with the user's own login.
+//! Without a number, `gh` and `glab` comment on the request for the current
+//! branch; `tea` needs the number.
+
+use crate::app::GptEngage;
+use crate::audit::ProcessAudit;
+
+/// Forges accepted by the `forge` config key
+pub const FORGES: &[&str] = &["github", "gitlab", "gitea"];
+
+/// Comments are cut short past this many characters, well inside the
+/// forges' limits (GitHub's is 65,536)
+const MAX_COMMENT_CHARS: usize = 60_000;
+
+/// A forge's CLI, and how to install and log in to it
+fn forge_cli(forge: &str) -> (&'static str, &'static str) {
+    match forge {
+        "gitlab" => ("glab", "install the GitLab CLI and run `glab auth login`"),
+        "gitea" => ("tea", "install the Gitea CLI and run `tea login add`"),
+        _ => ("gh", "install the GitHub CLI and run `gh auth login`"),
+    }
+}
+
+/// The configured forge, checked against `number` before any debate is
+/// paid for
+pub fn forge(engage: &GptEngage, number: Option<u64>) -> anyhow::Result<String> {
+    let forge = engage.config()?.config.forge.clone();
+    if forge == "gitea" && number.is_none() {
+        anyhow::bail!(
+            "Posting to Gitea needs the pull request number, e.g. --post-to-mr 42 (tea can't find it from the branch)"
+        );
+    }
+    Ok(forge)
+}
+
+/// `body`, cut short with a note when it is over the comment limit
+fn comment_body(body: &str) -> String {
+    match body.char_indices().nth(MAX_COMMENT_CHARS) {
+        Some((cut, _)) => format!(
+            "{}\n\n_Truncated to fit a comment; run the debate with `--output markdown` for the full transcript._\n",
+            body[..cut].trim_end()
+        ),
+        None => body.to_string(),
+    }
+}
+
+/// Arguments for the forge CLI that comments `body` on request `number`
+fn comment_args(forge: &str, number: Option<u64>, body: &str) -> Vec<String> {
+    let number = number.map(|n| n.to_string());
+    let mut args: Vec<String> = match forge {
+        "gitlab" => vec!["mr".into(), "note".into()],
+        "gitea" => vec!["comment".into()],
+        _ => vec!["pr".into(), "comment".into()],
+    };
+    args.extend(number);
+    match forge {
+        "gitlab" => args.extend(["--message".to_string(), body.to_string()]),
+        "gitea" => args.push(body.to_string()),
+        _ => args.extend(["--body".to_string(), body.to_string()]),
+    }
+    args
+}
+
+/// Comment `body` on pull or merge request `number` (or the current
+/// branch's) with the CLI of `forge`
+pub async fn post_comment(forge: &str, number: Option<u64>, body: &str) -> anyhow::Result<()> {
+    let (program, setup) = forge_cli(forge);
+    let comment = comment_body(body);
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(comment_args(forge, number, &comment))
+        .stdin(std::process::Stdio::null());
+    let audit = ProcessAudit::start(command.as_std()).redact(&comment);
+    let output = command
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run {} ({}): {}", program, setup, e))?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} could not post the comment: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().rev().find(|line| line.starts_with("http")) {
+        Some(url) => eprintln!("Posted the debate: {}", url),
+        None => eprintln!("Posted the debate with {}", program),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_args() {
+        assert_eq!(
+            comment_args("github", Some(7), "hi"),
+            vec!["pr", "comment", "7", "--body", "hi"]
+        );
+        assert_eq!(
+            comment_args("gitlab", None, "hi"),
+            vec!["mr", "note", "--message", "hi"]
+        );
+        assert_eq!(
+            comment_args("gitea", Some(7), "hi"),
+            vec!["comment", "7", "hi"]
+        );
+
+        assert_eq!(comment_body("short"), "short");
+        let long = comment_body(&"é".repeat(MAX_COMMENT_CHARS + 10));
+        assert!(long.starts_with(&"é".repeat(MAX_COMMENT_CHARS)));
+        assert!(long.contains("_Truncated to fit a comment"));
+    }
+}
@@ -1,12 +1,37 @@
 //! Command implementations
 
+pub mod agents;
+pub mod alias;
+pub mod argument_map;
+pub mod attribution;
+pub mod audit;
+pub mod bundle;
+pub mod completions;
+pub mod config;
 pub mod debate;
+pub mod decisions;
+pub mod explain;
+pub mod fanout;
+pub mod gen_tests;
 pub mod generate_agents;
 pub mod ideate;
 pub mod invoke;
+pub mod issues;
+pub mod lsp_bridge;
+pub mod merge_request;
+pub mod options_file;
+pub mod persona;
+pub mod pipeline;
 pub mod plugin;
+pub mod release_notes;
+pub mod report;
+pub mod rollback;
+pub mod schema;
+pub mod search;
 pub mod session;
 pub mod status;
+pub mod sync;
+pub mod synthesize;
 pub mod template;
 
 pub use debate::*;
@@ -0,0 +1,246 @@
+//! Options files - Command lines kept in version control
+//!
+//! `--options-file run.toml` reads the options of the command it is given
+//! to from a TOML file, so a complex invocation can live next to a repo
+//! instead of in shell history:
+//!
+//! ```toml
+//! participants = ["claude:CTO:opus", "codex:Architect"]
+//! rounds = 4
+//! synthesize = true
+//! context_provider = ["git-diff"]
+//! ```
+//!
+//! Keys are the command's long option names (`context-provider` or
+//! `context_provider`). The options are expanded into the command line
+//! before it is parsed, ahead of those typed on it, so typed options win
+//! and repeatable ones add to the file's. Like any option, they override
+//! the config.
+
+use super::alias::command_position;
+use crate::cli::Cli;
+use clap::parser::ValueSource;
+use clap::{ArgAction, Command, CommandFactory};
+use std::ffi::OsString;
+
+/// The option naming the file
+const OPTION: &str = "--options-file";
+
+/// Options and positional values from a file
+#[derive(Debug, Default, PartialEq)]
+struct FileArgs {
+    /// `--option value` words
+    options: Vec<String>,
+    /// Values for positional arguments, by argument ID
+    positionals: Vec<(String, Vec<String>)>,
+}
+
+/// The words of one TOML value
+fn words(name: &str, value: &toml::Value) -> anyhow::Result<Vec<String>> {
+    match value {
+        toml::Value::String(text) => Ok(vec![text.clone()]),
+        toml::Value::Integer(n) => Ok(vec![n.to_string()]),
+        toml::Value::Float(n) => Ok(vec![n.to_string()]),
+        toml::Value::Boolean(b) => Ok(vec![b.to_string()]),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    anyhow::bail!("'{}' can't contain nested arrays or tables", name)
+                }
+                item => Ok(words(name, item)?.remove(0)),
+            })
+            .collect(),
+        toml::Value::Table(_) | toml::Value::Datetime(_) => {
+            anyhow::bail!("'{}' must be a string, number, boolean, or array", name)
+        }
+    }
+}
+
+/// Convert a file's keys into arguments of `command`
+fn file_args(command: &Command, table: &toml::Table) -> anyhow::Result<FileArgs> {
+    let mut args = FileArgs::default();
+    for (key, value) in table {
+        let id = key.replace('-', "_");
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_id() == id.as_str() && !arg.is_hide_set())
+            .filter(|_| id != "options_file")
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown option '{}' for `gptengage {}`. See `gptengage {} --help`",
+                    key,
+                    command.get_name(),
+                    command.get_name()
+                )
+            })?;
+        let values = words(key, value)?;
+        let Some(long) = arg.get_long() else {
+            args.positionals.push((id, values));
+            continue;
+        };
+        let option = format!("--{}", long);
+        match arg.get_action() {
+            ArgAction::SetTrue => match value {
+                toml::Value::Boolean(true) => args.options.push(option),
+                toml::Value::Boolean(false) => {}
+                _ => anyhow::bail!("'{}' is a switch; set it to true or false", key),
+            },
+            // `create_issues = true` gives an option its value-less form
+            _ if *value == toml::Value::Boolean(true)
+                && arg.get_num_args().is_some_and(|n| n.min_values() == 0) =>
+            {
+                args.options.push(option)
+            }
+            // Repeatable options are given once per item
+            ArgAction::Append => {
+                for value in values {
+                    args.options.push(option.clone());
+                    args.options.push(value);
+                }
+            }
+            _ => {
+                args.options.push(option);
+                args.options.push(values.join(","));
+            }
+        }
+    }
+    Ok(args)
+}
+
+/// Whether the command line in `args` gives positional argument `id` of
+/// the subcommand at `path`
+fn gives_positional(args: &[OsString], path: &[String], id: &str) -> bool {
+    let Ok(mut matches) = Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+    else {
+        return false;
+    };
+    for name in path {
+        match matches.remove_subcommand() {
+            Some((found, sub)) if &found == name => matches = sub,
+            _ => return false,
+        }
+    }
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+/// Expand `--options-file <PATH>` in the process arguments into the
+/// options it holds, inserted right after the command they belong to
+pub fn expand_options_file(args: Vec<OsString>) -> anyhow::Result<Vec<OsString>> {
+    let mut paths = args.iter().enumerate().filter_map(|(i, arg)| {
+        let arg = arg.to_str()?;
+        match arg.strip_prefix(OPTION)? {
+            "" => Some(args.get(i + 1)?.to_string_lossy().into_owned()),
+            value => Some(value.strip_prefix('=')?.to_string()),
+        }
+    });
+    let Some(path) = paths.next() else {
+        return Ok(args);
+    };
+    if paths.next().is_some() {
+        anyhow::bail!("Use {} only once", OPTION);
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read options file {}: {}", path, e))?;
+    let table: toml::Table = toml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid options file {}: {}", path, e))?;
+
+    // The file applies to the innermost command named on the command line
+    let mut root = Cli::command();
+    // Building the command gives its subcommands the global options too
+    root.build();
+    let mut pos = command_position(&args).ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} needs a command, e.g. `gptengage debate {} {}`",
+            OPTION,
+            OPTION,
+            path
+        )
+    })?;
+    let mut command = args[pos]
+        .to_str()
+        .and_then(|name| root.find_subcommand(name))
+        .ok_or_else(|| anyhow::anyhow!("{} needs a built-in command", OPTION))?
+        .clone();
+    let mut path_names = vec![command.get_name().to_string()];
+    while let Some(sub) = args
+        .get(pos + 1)
+        .and_then(|word| word.to_str())
+        .and_then(|name| command.find_subcommand(name))
+        .cloned()
+    {
+        path_names.push(sub.get_name().to_string());
+        command = sub;
+        pos += 1;
+    }
+    let file = file_args(&command, &table)
+        .map_err(|e| anyhow::anyhow!("Invalid options file {}: {}", path, e))?;
+
+    let mut expanded = args[..=pos].to_vec();
+    expanded.extend(file.options.iter().map(OsString::from));
+    // Positionals from the file only fill in those the command line lacks
+    let mut candidate = expanded.clone();
+    candidate.extend(args[pos + 1..].iter().cloned());
+    for (id, values) in &file.positionals {
+        if !gives_positional(&candidate, &path_names, id) {
+            expanded.extend(values.iter().map(OsString::from));
+        }
+    }
+    expanded.extend(args[pos + 1..].iter().cloned());
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_args() {
+        let mut root = Cli::command();
+        root.build();
+        let debate = root.find_subcommand("debate").unwrap().clone();
+        let table: toml::Table = toml::from_str(
+            r#"
+            topic = "Shard the orders table?"
+            participants = ["claude:CTO:opus", "codex"]
+            rounds = 4
+            synthesize = true
+            collapse = false
+            context-provider = ["git-diff", "jira"]
+            isolate = true
+            "#,
+        )
+        .unwrap();
+        let args = file_args(&debate, &table).unwrap();
+        assert_eq!(
+            args.options,
+            vec![
+                "--context-provider",
+                "git-diff",
+                "--context-provider",
+                "jira",
+                "--isolate",
+                "--participants",
+                "claude:CTO:opus,codex",
+                "--rounds",
+                "4",
+                "--synthesize",
+            ]
+        );
+        assert_eq!(
+            args.positionals,
+            vec![(
+                "topic".to_string(),
+                vec!["Shard the orders table?".to_string()]
+            )]
+        );
+
+        let unknown: toml::Table = toml::from_str("roundz = 4").unwrap();
+        assert!(file_args(&debate, &unknown).is_err());
+        let not_a_switch: toml::Table = toml::from_str("synthesize = \"yes\"").unwrap();
+        assert!(file_args(&debate, &not_a_switch).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Persona command - Manage the persona library
+
+use crate::audit::ProcessAudit;
+use crate::orchestrator::{AgentDefinition, AgentFile, AgentFileFormat};
+use crate::personas::{parse_persona, Persona, PersonaRegistry};
+
+/// List all library personas
+pub async fn list_personas() -> anyhow::Result<()> {
+    let registry = PersonaRegistry::new()?;
+    let personas = registry.list();
+
+    if personas.is_empty() {
+        println!("No personas in the library.");
+        println!(
+            "Add one with: gptengage persona import <file-or-url>, or create {}/<name>.toml",
+            PersonaRegistry::get_personas_dir()?.display()
+        );
+        return Ok(());
+    }
+
+    println!("Personas:");
+    println!();
+    for persona in personas {
+        println!("  @{} ({})", persona.name, persona.role());
+        if !persona.expertise.is_empty() {
+            println!("      Expertise: {}", persona.expertise.join(", "));
+        }
+    }
+    println!();
+    println!("Use a persona: gptengage debate \"topic\" -p \"claude:@<name>\"");
+
+    Ok(())
+}
+
+/// Show a persona's details
+pub async fn show_persona(name: String) -> anyhow::Result<()> {
+    let name = name.trim_start_matches('@');
+    let registry = PersonaRegistry::new()?;
+    let persona = registry.resolve(name)?;
+
+    println!("Persona: @{}", persona.name);
+    println!("Role: {}", persona.role());
+    println!("Instructions: {}", persona.instructions);
+    if !persona.expertise.is_empty() {
+        println!("Expertise: {}", persona.expertise.join(", "));
+    }
+    if let Some(ref style) = persona.style {
+        println!("Style: {}", style);
+    }
+
+    Ok(())
+}
+
+/// Import personas from a persona TOML file or an agent file (JSON), given
+/// as a local path or an http(s) URL
+pub async fn import_personas(
+    source: String,
+    name: Option<String>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let content = read_source(&source).await?;
+    let personas = parse_import(&source, &content, name)?;
+
+    // Check every name first so a multi-persona import isn't left half done
+    if !force {
+        let registry = PersonaRegistry::new()?;
+        if let Some(existing) = personas.iter().find(|p| registry.get(&p.name).is_some()) {
+            anyhow::bail!(
+                "Persona '@{}' already exists. Use --force to replace it.",
+                existing.name
+            );
+        }
+    }
+
+    for persona in &personas {
+        let path = PersonaRegistry::save(persona, force)?;
+        println!(
+            "✓ Imported persona '@{}' ({})",
+            persona.name,
+            persona.role()
+        );
+        println!("  File: {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Save the personas of generated agent definitions to the library,
+/// skipping (with a warning) any that already exist
+pub fn save_agent_personas(agents: &[AgentDefinition]) {
+    for agent in agents {
+        let persona = Persona::from_agent_definition(agent);
+        match PersonaRegistry::save(&persona, false) {
+            Ok(_) => println!("✓ Saved persona '@{}'", persona.name),
+            Err(e) => eprintln!("Warning: Persona '{}' not saved: {}", agent.persona, e),
+        }
+    }
+}
+
+/// Fetch an http(s) URL with curl, or read a local file
+async fn read_source(source: &str) -> anyhow::Result<String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let mut command = tokio::process::Command::new("curl");
+        command.args(["-fsSL", "--max-time", "30", source]);
+        let audit = ProcessAudit::start(command.as_std());
+        let output = command
+            .output()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to run curl to fetch {}: {}", source, e))?;
+        audit.finish(&output.status);
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to fetch {}: {}",
+                source,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    } else {
+        tokio::fs::read_to_string(source)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", source, e))
+    }
+}
+
+/// Parse imported content. Agent files contribute one persona per
+/// participant; a persona file is named after the source file unless
+/// `name` is given.
+fn parse_import(source: &str, content: &str, name: Option<String>) -> anyhow::Result<Vec<Persona>> {
+    let file_name = source
+        .rsplit('/')
+        .next()
+        .unwrap_or(source)
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default();
+
+    // Agent files: JSON or YAML by extension (or JSON content), or TOML
+    // with a participants table
+    let agent_format = match AgentFileFormat::from_path(file_name) {
+        AgentFileFormat::Toml if !content.contains("[[participants]]") => None,
+        AgentFileFormat::Json
+            if !file_name.ends_with(".json") && !content.trim_start().starts_with('{') =>
+        {
+            None
+        }
+        format => Some(format),
+    };
+    if let Some(format) = agent_format {
+        let agent_file = AgentFile::parse(content, format)
+            .map_err(|e| anyhow::anyhow!("Failed to parse agent file {}: {}", source, e))?;
+        if name.is_some() && agent_file.participants.len() != 1 {
+            anyhow::bail!("--name can only be used when importing a single persona");
+        }
+        return Ok(agent_file
+            .participants
+            .iter()
+            .map(|agent| {
+                let mut persona = Persona::from_agent_definition(agent);
+                if let Some(ref name) = name {
+                    persona.name = name.clone();
+                }
+                persona
+            })
+            .collect());
+    }
+
+    let name = match name {
+        Some(name) => name,
+        None => file_name
+            .strip_suffix(".toml")
+            .unwrap_or(file_name)
+            .to_string(),
+    };
+    let persona = parse_persona(content, &name)
+        .map_err(|e| anyhow::anyhow!("Failed to parse persona {}: {}", source, e))?;
+    Ok(vec![persona])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_import_persona_file_named_from_url() {
+        let content =
+            "role = \"SRE\"\ninstructions = \"Focus on operability and failure modes.\"\n";
+        let personas =
+            parse_import("https://example.com/personas/sre.toml?raw=1", content, None).unwrap();
+        assert_eq!(personas.len(), 1);
+        assert_eq!(personas[0].name, "sre");
+
+        let renamed = parse_import("sre.toml", content, Some("oncall".to_string())).unwrap();
+        assert_eq!(renamed[0].name, "oncall");
+    }
+
+    #[test]
+    fn test_parse_import_agent_file() {
+        let content = r#"{
+            "schema_version": "1.0",
+            "participants": [
+                {"cli": "claude", "persona": "CEO", "instructions": "Focus on business impact."},
+                {"cli": "codex", "persona": "Staff Engineer", "instructions": "Focus on delivery risk."}
+            ]
+        }"#;
+        let personas = parse_import("agents.json", content, None).unwrap();
+        let names: Vec<&str> = personas.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["ceo", "staff-engineer"]);
+        assert!(parse_import("agents.json", content, Some("x".to_string())).is_err());
+
+        let yaml = "participants:\n  - cli: claude\n    persona: SRE\n    instructions: Focus on operability.\n";
+        let personas = parse_import("team.yaml", yaml, None).unwrap();
+        assert_eq!(personas[0].name, "sre");
+    }
+}
@@ -0,0 +1,520 @@
+//! Pipeline command - Built-in multi-stage flows
+//!
+//! `pipeline review-fix-verify --paths src/` chains three stages:
+//!
+//! 1. Review: a debate with the code-review template over the files, with
+//!    a synthesis and action items as the findings.
+//! 2. Fix: a write-mode invoke that edits the files to address the
+//!    findings. The edits are saved as a patch and, unless `--apply` is
+//!    given, taken back out of the working tree.
+//! 3. Verify: a judge compares the patch with the findings and gives a
+//!    verdict of pass, partial, or fail.
+//!
+//! Each stage's artifacts go to the output directory, with an `index.md`
+//! linking them.
+
+use super::debate::{conduct_debate, render_markdown, DebateOptions};
+use crate::app::GptEngage;
+use crate::cli::StdinMode;
+use crate::invokers::AccessMode;
+use crate::orchestrator::{synthesis_transcript, DebateResult};
+use crate::utils::json::extract_json_object;
+use crate::utils::run_snapshot::{in_git_worktree, snapshots_dir, RunSnapshot};
+use crate::utils::stdin::format_context_block;
+use crate::utils::workspace::WorkspaceSnapshot;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Bytes of source a review may include
+const MAX_REVIEW_BYTES: usize = 200_000;
+
+/// Options for `pipeline review-fix-verify`
+pub struct ReviewFixVerifyOptions {
+    /// Files and directories to review
+    pub paths: Vec<String>,
+    /// Debate template for the review
+    pub template: String,
+    pub rounds: Option<usize>,
+    /// Rounds when neither --rounds nor the template sets them
+    pub default_rounds: usize,
+    /// CLI that writes the review's synthesis and action items
+    pub synthesizer: String,
+    /// CLI that writes the fix
+    pub fixer: String,
+    pub model: Option<String>,
+    /// CLI that verifies the fix
+    pub judge: String,
+    /// Directory for the artifacts and index.md
+    pub output_dir: String,
+    /// Leave the fix in the working tree
+    pub apply: bool,
+    pub timeout: u64,
+}
+
+/// How well a fix addresses the review's findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// Every finding is addressed without new problems
+    Pass,
+    /// Some findings are addressed
+    Partial,
+    /// The findings aren't addressed, or the patch breaks something
+    Fail,
+}
+
+/// The verification stage's result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verification {
+    /// The CLI that judged the fix
+    #[serde(default)]
+    pub judge: String,
+    pub verdict: Verdict,
+    pub summary: String,
+    /// Findings the patch addresses
+    #[serde(default)]
+    pub addressed: Vec<String>,
+    /// Findings the patch leaves open
+    #[serde(default)]
+    pub unaddressed: Vec<String>,
+    /// Problems the patch introduces
+    #[serde(default)]
+    pub concerns: Vec<String>,
+}
+
+/// Files under `paths`, relative to the current directory, skipping
+/// version control and build directories
+fn review_files(paths: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for path in paths {
+        let root = Path::new(path);
+        if root.is_dir() {
+            let snapshot = WorkspaceSnapshot::capture(root)?;
+            files.extend(
+                snapshot
+                    .paths()
+                    .map(|file| root.join(file).display().to_string()),
+            );
+        } else if root.is_file() {
+            files.push(path.clone());
+        } else {
+            anyhow::bail!("No such file or directory: {}", path);
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// The review topic: the files' contents, each in its own block. Files
+/// that aren't UTF-8 text are left out.
+fn review_topic(files: &[String]) -> anyhow::Result<String> {
+    let mut blocks = Vec::new();
+    let mut included = Vec::new();
+    let mut total = 0;
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(file) else {
+            eprintln!("Skipping {} (not text)", file);
+            continue;
+        };
+        total += content.len();
+        if total > MAX_REVIEW_BYTES {
+            anyhow::bail!(
+                "The files under review are over {} KB; narrow --paths",
+                MAX_REVIEW_BYTES / 1000
+            );
+        }
+        blocks.push(format_context_block(&format!("FILE {}", file), &content));
+        included.push(file.as_str());
+    }
+    if included.is_empty() {
+        anyhow::bail!("No text files to review under the given --paths");
+    }
+    Ok(format!(
+        "Review the following files for bugs, security problems, and maintainability issues: {}. \
+         Name the file and, where you can, the function or line of each issue, and say how to fix it.\n\n{}",
+        included.join(", "),
+        blocks.join("\n\n")
+    ))
+}
+
+/// The review's findings, for the fix and verification prompts
+fn findings(result: &DebateResult) -> String {
+    let Some(synthesis) = &result.synthesis else {
+        // Without a synthesis the transcript is the review
+        return synthesis_transcript(&result.rounds, 1);
+    };
+    let mut out = format!("Summary: {}\n", synthesis.summary);
+    for (heading, points) in [
+        ("Agreed issues", &synthesis.consensus_points),
+        ("Key insights", &synthesis.key_insights),
+        ("Disputed points", &synthesis.disagreement_points),
+    ] {
+        if !points.is_empty() {
+            out.push_str(&format!("\n{}:\n", heading));
+            for point in points {
+                out.push_str(&format!("- {}\n", point));
+            }
+        }
+    }
+    if let Some(recommendation) = &synthesis.recommendation {
+        out.push_str(&format!("\nRecommendation: {}\n", recommendation));
+    }
+    if !result.action_items.is_empty() {
+        out.push_str("\nAction items:\n");
+        for item in &result.action_items {
+            out.push_str(&format!("- [{}] {}\n", item.priority, item.task));
+        }
+    }
+    out
+}
+
+/// Prompt asking a write-mode CLI to fix the findings
+fn fix_prompt(files: &[String], findings: &str) -> String {
+    format!(
+        r#"[FIX REQUEST]
+A code review of these files found the issues below:
+{}
+
+FINDINGS:
+{}
+Fix the issues by editing the files in place in the current directory.
+Change only what the findings call for; don't reformat or refactor
+unrelated code. If a finding shouldn't be fixed, leave it and say why.
+When you're done, list each change you made in one line.
+[/FIX REQUEST]"#,
+        files
+            .iter()
+            .map(|file| format!("- {}", file))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        findings
+    )
+}
+
+/// Prompt asking a judge whether a patch addresses the findings
+fn verify_prompt(findings: &str, patch: &str) -> String {
+    format!(
+        r#"[VERIFICATION REQUEST]
+You are verifying a proposed fix against the code review findings it was
+written for.
+
+FINDINGS:
+{}
+PATCH:
+{}
+
+For each finding, decide whether the patch addresses it. Also note any
+problem the patch introduces: bugs, behavior changes the findings didn't
+ask for, or unrelated edits. Give a verdict: "pass" if every finding that
+should be fixed is addressed without new problems, "partial" if some are,
+"fail" if none are or the patch breaks something.
+
+Respond with JSON in this exact format:
+{{
+  "verdict": "pass",
+  "summary": "...",
+  "addressed": ["..."],
+  "unaddressed": ["..."],
+  "concerns": ["..."]
+}}
+[/VERIFICATION REQUEST]"#,
+        findings, patch
+    )
+}
+
+/// Parse a judge's verification
+fn parse_verification(response: &str, judge: &str) -> anyhow::Result<Verification> {
+    let json = extract_json_object(response)?;
+    let mut verification: Verification = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("Invalid verification JSON: {}", e))?;
+    verification.judge = judge.to_string();
+    Ok(verification)
+}
+
+/// Render a verification as markdown
+fn render_verification(verification: &Verification) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "# Verification")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Judge: {}. Checks [02-fix.patch](02-fix.patch) against the findings in [01-review.md](01-review.md).",
+        verification.judge
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "**Verdict: {}.** {}",
+        verdict_name(verification.verdict),
+        verification.summary
+    )?;
+    for (heading, points) in [
+        ("Addressed", &verification.addressed),
+        ("Unaddressed", &verification.unaddressed),
+        ("Concerns", &verification.concerns),
+    ] {
+        if !points.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## {}", heading)?;
+            writeln!(out)?;
+            for point in points {
+                writeln!(out, "- {}", point)?;
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn verdict_name(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Pass => "pass",
+        Verdict::Partial => "partial",
+        Verdict::Fail => "fail",
+    }
+}
+
+/// One row of index.md
+struct StageEntry {
+    stage: &'static str,
+    result: String,
+    artifacts: Vec<&'static str>,
+}
+
+/// Render index.md, linking every stage's artifacts
+fn render_index(paths: &[String], stages: &[StageEntry]) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "# Review, fix, verify: {}", paths.join(", "))?;
+    writeln!(out)?;
+    writeln!(out, "| Stage | Result | Artifacts |")?;
+    writeln!(out, "|-------|--------|-----------|")?;
+    for entry in stages {
+        let links: Vec<String> = entry
+            .artifacts
+            .iter()
+            .map(|file| format!("[{}]({})", file, file))
+            .collect();
+        writeln!(
+            out,
+            "| {} | {} | {} |",
+            entry.stage,
+            entry.result.replace('|', "\\|").replace('\n', " "),
+            links.join(", ")
+        )?;
+    }
+    Ok(out)
+}
+
+/// Run the review, fix, and verify stages over `options.paths`
+pub async fn run_review_fix_verify(
+    engage: &GptEngage,
+    options: ReviewFixVerifyOptions,
+) -> anyhow::Result<()> {
+    let root = std::env::current_dir()?;
+    if !in_git_worktree(&root) {
+        anyhow::bail!(
+            "review-fix-verify saves the fix as a patch, so run it inside a git repository"
+        );
+    }
+    for cli in [&options.fixer, &options.judge] {
+        let invoker = engage
+            .invokers
+            .get(cli)
+            .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", cli))?;
+        if !invoker.is_available().await {
+            anyhow::bail!("CLI '{}' not found in PATH", cli);
+        }
+    }
+    let files = review_files(&options.paths)?;
+    let topic = review_topic(&files)?;
+    let dir = Path::new(&options.output_dir);
+    std::fs::create_dir_all(dir)?;
+    let mut stages = Vec::new();
+
+    // Stage 1: review
+    println!("[1/3] Reviewing {} file(s)...", files.len());
+    let mut review = DebateOptions::new(topic, options.default_rounds, options.timeout);
+    review.template = Some(options.template.clone());
+    review.rounds = options.rounds;
+    review.output = "markdown".to_string();
+    review.ordered = true;
+    review.stdin_as = StdinMode::Ignore;
+    review.synthesize = true;
+    review.synthesizer = options.synthesizer.clone();
+    review.action_items = true;
+    review.action_items_cli = options.synthesizer.clone();
+    let result = conduct_debate(engage, review)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("The review produced no debate"))?;
+    std::fs::write(dir.join("01-review.md"), render_markdown(&result, true)?)?;
+    std::fs::write(
+        dir.join("01-review.json"),
+        format!("{}\n", serde_json::to_string_pretty(&result)?),
+    )?;
+    let findings = findings(&result);
+    stages.push(StageEntry {
+        stage: "1. Review",
+        result: format!(
+            "{} template, {} action item(s)",
+            options.template,
+            result.action_items.len()
+        ),
+        artifacts: vec!["01-review.md", "01-review.json"],
+    });
+    println!("  ✓ Wrote {}", dir.join("01-review.md").display());
+
+    // Stage 2: fix, captured as a patch against a snapshot of the tree
+    println!("[2/3] Asking {} for a fix...", options.fixer);
+    let snapshots = snapshots_dir()?;
+    let snapshot = RunSnapshot::take(&root, &snapshots, "pipeline review-fix-verify")?;
+    let fixer = engage
+        .invokers
+        .get(&options.fixer)
+        .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", options.fixer))?;
+    let fixed = fixer
+        .invoke(
+            &fix_prompt(&files, &findings),
+            options.timeout,
+            AccessMode::WorkspaceWrite,
+            options.model.as_deref(),
+        )
+        .await;
+    let patch = snapshot.patch(&snapshots);
+    let changes = snapshot.changes(&snapshots);
+    if options.apply && fixed.is_ok() {
+        eprintln!(
+            "Fix left in the working tree; undo it with: gptengage rollback {}",
+            snapshot.id
+        );
+    } else {
+        snapshot.restore(&snapshots)?;
+        snapshot.delete(&snapshots)?;
+    }
+    let (fix_summary, patch, changes) = (fixed?, patch?, changes?);
+    std::fs::write(dir.join("02-fix.patch"), &patch)?;
+    let mut fix_doc = format!(
+        "# Fix\n\nBy {} for the findings in [01-review.md](01-review.md). The edits are in [02-fix.patch](02-fix.patch).\n\n",
+        options.fixer
+    );
+    if !changes.is_empty() {
+        fix_doc.push_str(&format!(
+            "Changed files:\n\n```\n{}```\n\n",
+            changes.render()?
+        ));
+    }
+    fix_doc.push_str(fix_summary.trim_end());
+    fix_doc.push('\n');
+    std::fs::write(dir.join("02-fix.md"), fix_doc)?;
+    let changed = changes.created.len() + changes.modified.len() + changes.deleted.len();
+    stages.push(StageEntry {
+        stage: "2. Fix",
+        result: format!("{}, {} file(s) changed", options.fixer, changed),
+        artifacts: vec!["02-fix.patch", "02-fix.md"],
+    });
+    println!("  ✓ Wrote {}", dir.join("02-fix.patch").display());
+
+    // Stage 3: verify
+    let verification = if patch.trim().is_empty() {
+        eprintln!(
+            "Warning: {} made no changes; skipping verification",
+            options.fixer
+        );
+        stages.push(StageEntry {
+            stage: "3. Verify",
+            result: "skipped: no changes to verify".to_string(),
+            artifacts: vec![],
+        });
+        None
+    } else {
+        println!("[3/3] Asking {} to verify the fix...", options.judge);
+        let judge = engage
+            .invokers
+            .get(&options.judge)
+            .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", options.judge))?;
+        let response = judge
+            .invoke(
+                &verify_prompt(&findings, &patch),
+                options.timeout,
+                AccessMode::ReadOnly,
+                None,
+            )
+            .await?;
+        let verification = parse_verification(&response, &options.judge)?;
+        std::fs::write(
+            dir.join("03-verify.md"),
+            render_verification(&verification)?,
+        )?;
+        std::fs::write(
+            dir.join("03-verify.json"),
+            format!("{}\n", serde_json::to_string_pretty(&verification)?),
+        )?;
+        stages.push(StageEntry {
+            stage: "3. Verify",
+            result: format!(
+                "{}: {}",
+                verdict_name(verification.verdict),
+                verification.summary
+            ),
+            artifacts: vec!["03-verify.md", "03-verify.json"],
+        });
+        println!("  ✓ Wrote {}", dir.join("03-verify.md").display());
+        Some(verification)
+    };
+
+    let index = dir.join("index.md");
+    std::fs::write(&index, render_index(&options.paths, &stages)?)?;
+    println!();
+    if !options.apply && !patch.trim().is_empty() {
+        println!(
+            "Apply the fix with: git apply {}",
+            dir.join("02-fix.patch").display()
+        );
+    }
+    match verification {
+        Some(verification) if verification.verdict == Verdict::Fail => anyhow::bail!(
+            "Verification failed: {} See {}",
+            verification.summary,
+            index.display()
+        ),
+        Some(verification) => println!(
+            "✓ Verdict: {}. Index: {}",
+            verdict_name(verification.verdict),
+            index.display()
+        ),
+        None => println!("Index: {}", index.display()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verification_and_index() {
+        let response = r#"Here is my assessment:
+{"verdict": "partial", "summary": "One of two fixed.", "addressed": ["SQL injection in find_user"], "unaddressed": ["Unbounded cache"]}"#;
+        let verification = parse_verification(response, "codex").unwrap();
+        assert_eq!(verification.verdict, Verdict::Partial);
+        assert_eq!(verification.judge, "codex");
+        assert!(verification.concerns.is_empty());
+        let markdown = render_verification(&verification).unwrap();
+        assert!(markdown.contains("**Verdict: partial.** One of two fixed."));
+        assert!(markdown.contains("## Unaddressed\n\n- Unbounded cache"));
+        assert!(parse_verification(r#"{"verdict": "maybe", "summary": ""}"#, "codex").is_err());
+
+        let index = render_index(
+            &["src/".to_string()],
+            &[StageEntry {
+                stage: "3. Verify",
+                result: verdict_name(verification.verdict).to_string(),
+                artifacts: vec!["03-verify.md", "03-verify.json"],
+            }],
+        )
+        .unwrap();
+        assert!(index.contains(
+            "| 3. Verify | partial | [03-verify.md](03-verify.md), [03-verify.json](03-verify.json) |"
+        ));
+    }
+}
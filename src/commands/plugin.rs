@@ -1,11 +1,16 @@
 //! Plugin command - Manage CLI plugins
 
-use crate::invokers::base::command_exists;
-use crate::plugins::PluginManager;
+use crate::app::GptEngage;
+use crate::config::ConfigManager;
+use crate::plugins::detection::is_available;
+use crate::plugins::{
+    plugin_checksum, signature_path, verify_signature, PluginKind, PluginManager,
+};
+use std::path::PathBuf;
 
 /// List all installed plugins
-pub async fn list_plugins() -> anyhow::Result<()> {
-    let manager = PluginManager::new()?;
+pub async fn list_plugins(engage: &GptEngage) -> anyhow::Result<()> {
+    let manager = engage.plugins()?;
     let plugins = manager.list_plugins();
 
     if plugins.is_empty() {
@@ -20,18 +25,29 @@ pub async fn list_plugins() -> anyhow::Result<()> {
     println!();
 
     for plugin in plugins {
-        let available = command_exists(&plugin.detection.check_command);
+        let available = is_available(&plugin.detection).await;
         let status = if available { "✓" } else { "✗" };
+        let disabled = if manager.is_disabled(&plugin.plugin.name) {
+            " [disabled]"
+        } else {
+            ""
+        };
 
         println!(
-            "  {} {} ({})",
-            status, plugin.plugin.name, plugin.plugin.description
+            "  {} {} ({}){}",
+            status, plugin.plugin.name, plugin.plugin.description, disabled
         );
         println!("      Command: {}", plugin.plugin.command);
+        if plugin.plugin.kind != PluginKind::Invoker {
+            println!("      Kind: {}", plugin.plugin.kind);
+        }
+        if plugin.plugin.shell {
+            println!("      Runs through: sh -c (shell = true)");
+        }
         println!("      Prompt mode: {:?}", plugin.invoke.prompt_mode);
         if !available {
             println!(
-                "      Warning: '{}' not found in PATH",
+                "      Warning: '{}' not found in PATH or availability check failed",
                 plugin.detection.check_command
             );
         }
@@ -51,10 +67,26 @@ pub async fn validate_plugin(path: String) -> anyhow::Result<()> {
             println!("  Name: {}", config.plugin.name);
             println!("  Description: {}", config.plugin.description);
             println!("  Command: {}", config.plugin.command);
+            match config.plugin.kind {
+                PluginKind::Invoker => {}
+                PluginKind::ContextProvider => {
+                    println!("  Kind: context-provider (use with --context-provider)")
+                }
+                PluginKind::Middleware => {
+                    println!("  Kind: middleware (enable with plugins.middleware)")
+                }
+                PluginKind::Judge => println!("  Kind: judge (use with --judge)"),
+            }
             println!("  Prompt mode: {:?}", config.invoke.prompt_mode);
+            if config.plugin.shell {
+                println!();
+                println!("  Warning: shell = true runs the command line through sh -c.");
+                println!("  Pipes, redirections, and $VARIABLES in it are interpreted by the");
+                println!("  shell; review the whole line before adding it to the allowlist.");
+            }
 
             // Check if the command is available
-            let available = command_exists(&config.detection.check_command);
+            let available = is_available(&config.detection).await;
             if available {
                 println!("  Status: ✓ CLI available");
             } else {
@@ -75,8 +107,158 @@ pub async fn validate_plugin(path: String) -> anyhow::Result<()> {
         Err(e) => {
             println!("✗ Plugin file is invalid");
             println!();
-            println!("Error: {}", e);
+            println!("Error: {:#}", e);
             Err(e)
         }
     }
 }
+
+/// Install a plugin file into the plugins directory and pin its checksum
+pub async fn install_plugin(
+    path: String,
+    sha256: Option<String>,
+    signature: Option<String>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let config = PluginManager::validate_plugin_file(&path)?;
+    let content = std::fs::read_to_string(&path)?;
+    let checksum = plugin_checksum(&content);
+
+    if let Some(expected) = sha256 {
+        if !checksum.eq_ignore_ascii_case(expected.trim()) {
+            anyhow::bail!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                path,
+                expected.trim(),
+                checksum
+            );
+        }
+    }
+
+    let mut manager = ConfigManager::new()?;
+    let signature = match signature {
+        Some(signature_file) => {
+            let signature = std::fs::read_to_string(&signature_file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", signature_file, e))?;
+            verify_signature(&content, &signature, &manager.plugins.signing_keys)
+                .map_err(|e| anyhow::anyhow!("Signature check failed for {}: {:#}", path, e))?;
+            Some(signature)
+        }
+        None => None,
+    };
+
+    let plugins_dir = PluginManager::get_plugins_dir()?;
+    std::fs::create_dir_all(&plugins_dir)?;
+    let file_name = format!("{}.toml", config.plugin.name);
+    let target = plugins_dir.join(&file_name);
+
+    let same_file = std::fs::canonicalize(&path).ok() == std::fs::canonicalize(&target).ok();
+    if target.exists() && !same_file {
+        let existing = std::fs::read_to_string(&target).unwrap_or_default();
+        if existing != content && !force {
+            anyhow::bail!(
+                "Plugin '{}' is already installed. Use --force to replace it.",
+                config.plugin.name
+            );
+        }
+    }
+    if !same_file {
+        std::fs::write(&target, &content)?;
+    }
+    // A signature left from an earlier install no longer matches
+    let target_signature = signature_path(&target);
+    match &signature {
+        Some(signature) => std::fs::write(&target_signature, signature)?,
+        None if target_signature.exists() => std::fs::remove_file(&target_signature)?,
+        None => {}
+    }
+
+    manager
+        .plugins
+        .checksums
+        .insert(file_name, checksum.clone());
+    manager.save()?;
+
+    println!("✓ Installed plugin '{}'", config.plugin.name);
+    println!("  File: {}", target.display());
+    println!("  SHA-256: {}", checksum);
+    if signature.is_some() {
+        println!("  Signature: verified");
+    }
+    if !manager.plugins.is_allowed(&config.plugin.command) {
+        println!();
+        println!(
+            "'{}' is not yet allowlisted; you will be asked to trust it on first use.",
+            config.plugin.command
+        );
+    }
+
+    Ok(())
+}
+
+/// The file `plugin install` would have written for `name`, if it exists
+fn installed_plugin_file(name: &str) -> anyhow::Result<Option<PathBuf>> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Ok(None);
+    }
+    let path = PluginManager::get_plugins_dir()?.join(format!("{}.toml", name));
+    Ok(path.is_file().then_some(path))
+}
+
+/// Delete an installed plugin and forget its settings
+pub async fn remove_plugin(engage: &GptEngage, name: String) -> anyhow::Result<()> {
+    let loaded = engage
+        .plugins()
+        .ok()
+        .and_then(|manager| manager.plugin_file(&name).cloned());
+    // A plugin that fails to load (bad checksum, invalid TOML) isn't in the
+    // manager, but its file can still be removed by name
+    let path = match loaded {
+        Some(path) => path,
+        None => installed_plugin_file(&name)?
+            .ok_or_else(|| anyhow::anyhow!("Plugin '{}' not found", name))?,
+    };
+
+    std::fs::remove_file(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to remove {}: {}", path.display(), e))?;
+    let _ = std::fs::remove_file(signature_path(&path));
+
+    let mut config = ConfigManager::new()?;
+    if let Some(file_name) = path.file_name() {
+        config
+            .plugins
+            .checksums
+            .remove(&file_name.to_string_lossy().to_string());
+    }
+    config.plugins.disabled.retain(|n| n != &name);
+    config.save()?;
+
+    println!("✓ Removed plugin '{}' ({})", name, path.display());
+    Ok(())
+}
+
+/// Enable or disable a plugin without touching its file
+pub async fn set_plugin_enabled(
+    engage: &GptEngage,
+    name: String,
+    enabled: bool,
+) -> anyhow::Result<()> {
+    let manager = engage.plugins()?;
+    if manager.plugin_file(&name).is_none() {
+        anyhow::bail!("Plugin '{}' not found", name);
+    }
+
+    let mut config = ConfigManager::new()?;
+    config.plugins.disabled.retain(|n| n != &name);
+    if !enabled {
+        config.plugins.disabled.push(name.clone());
+    }
+    config.save()?;
+
+    if enabled {
+        println!("✓ Enabled plugin '{}'", name);
+    } else {
+        println!("✓ Disabled plugin '{}'", name);
+    }
+    Ok(())
+}
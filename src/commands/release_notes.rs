@@ -0,0 +1,274 @@
+//! Release notes command - Categorized notes from git history
+//!
+//! Gathers the commit messages and diff stats between two refs and asks a
+//! CLI to turn them into markdown release notes grouped by kind of change.
+//! With `--compare`, several CLIs draft notes in parallel and a judge picks
+//! the best draft.
+
+use crate::app::GptEngage;
+use crate::audit::ProcessAudit;
+use crate::invokers::AccessMode;
+use crate::orchestrator::best_of::judge_candidates;
+
+/// Commits included in the prompt; older ones are summarized by count
+const MAX_COMMITS: usize = 500;
+
+/// Characters kept from each commit body
+const MAX_BODY_CHARS: usize = 1_000;
+
+/// Separates fields of a commit in `git log` output
+const FIELD_SEP: char = '\u{1f}';
+
+/// Ends a commit in `git log` output
+const RECORD_SEP: char = '\u{1e}';
+
+/// Options for `release-notes`
+pub struct ReleaseNotesOptions {
+    /// Ref the release starts after (e.g. the previous tag)
+    pub from: String,
+    /// Ref the release ends at
+    pub to: String,
+    pub cli: String,
+    pub model: Option<String>,
+    /// Comma-separated CLIs that each draft notes for a judge to pick from
+    pub compare: Option<String>,
+    /// CLI that picks the best draft (default: `cli`)
+    pub judge: Option<String>,
+    pub timeout: u64,
+}
+
+/// One commit in the range
+#[derive(Debug, Clone, PartialEq)]
+struct Commit {
+    hash: String,
+    author: String,
+    subject: String,
+    body: String,
+}
+
+/// Run git in the current directory, returning trimmed stdout
+async fn git(args: &[&str]) -> anyhow::Result<String> {
+    let mut command = tokio::process::Command::new("git");
+    command.args(args).stdin(std::process::Stdio::null());
+    let audit = ProcessAudit::start(command.as_std());
+    let output = command
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {}", e))?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Parse `git log` output in the `--format` used by `release_notes`
+fn parse_commits(log: &str) -> Vec<Commit> {
+    log.split(RECORD_SEP)
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').split(FIELD_SEP);
+            let hash = fields.next()?.trim();
+            if hash.is_empty() {
+                return None;
+            }
+            let author = fields.next().unwrap_or_default().trim();
+            let subject = fields.next().unwrap_or_default().trim();
+            let body: String = fields
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .chars()
+                .take(MAX_BODY_CHARS)
+                .collect();
+            Some(Commit {
+                hash: hash.to_string(),
+                author: author.to_string(),
+                subject: subject.to_string(),
+                body,
+            })
+        })
+        .collect()
+}
+
+/// Prompt asking for release notes covering `commits`
+fn release_notes_prompt(
+    from: &str,
+    to: &str,
+    commits: &[Commit],
+    omitted: usize,
+    stats: &str,
+) -> String {
+    let mut listing = String::new();
+    for commit in commits {
+        listing.push_str(&format!(
+            "- {} {} ({})\n",
+            commit.hash, commit.subject, commit.author
+        ));
+        for line in commit.body.lines().filter(|line| !line.trim().is_empty()) {
+            listing.push_str(&format!("    {}\n", line));
+        }
+    }
+    if omitted > 0 {
+        listing.push_str(&format!("- ... and {} older commits\n", omitted));
+    }
+    format!(
+        r#"[RELEASE NOTES REQUEST]
+Write release notes for the changes from {} to {}.
+
+COMMITS ({}):
+{}
+DIFF STATS:
+{}
+
+Group the changes under these headings, in this order, leaving out empty
+ones: Breaking Changes, Features, Fixes, Performance, Documentation,
+Internal. Write one bullet per user-visible change in plain language,
+merging commits that make up one change, and end each bullet with the
+short hashes of its commits in parentheses. Put changes users won't
+notice (refactoring, tests, CI) under Internal.
+
+Respond with the markdown only, starting with the first heading. Use
+level-two headings: ## Features, ## Fixes, and so on.
+[/RELEASE NOTES REQUEST]"#,
+        from,
+        to,
+        commits.len() + omitted,
+        listing,
+        stats
+    )
+}
+
+/// Generate release notes for `options.from..options.to`
+pub async fn run_release_notes(
+    engage: &GptEngage,
+    options: ReleaseNotesOptions,
+) -> anyhow::Result<()> {
+    for reference in [&options.from, &options.to] {
+        git(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", reference),
+        ])
+        .await
+        .map_err(|_| anyhow::anyhow!("'{}' is not a commit in this repository", reference))?;
+    }
+    let range = format!("{}..{}", options.from, options.to);
+    let format = format!("--format=%h{0}%an{0}%s{0}%b{1}", FIELD_SEP, RECORD_SEP);
+    let mut commits = parse_commits(&git(&["log", "--no-merges", &format, &range]).await?);
+    if commits.is_empty() {
+        anyhow::bail!("No commits in {}", range);
+    }
+    let omitted = commits.len().saturating_sub(MAX_COMMITS);
+    commits.truncate(MAX_COMMITS);
+    let stats = git(&["diff", "--stat=100", "--stat-count=50", &range]).await?;
+    let prompt = release_notes_prompt(&options.from, &options.to, &commits, omitted, &stats);
+
+    let clis: Vec<String> = match &options.compare {
+        Some(list) => list
+            .split(',')
+            .map(|cli| cli.trim().to_string())
+            .filter(|cli| !cli.is_empty())
+            .collect(),
+        None => vec![options.cli.clone()],
+    };
+    if clis.is_empty() {
+        anyhow::bail!("--compare needs at least one CLI, e.g. --compare claude,codex");
+    }
+    let mut invokers = Vec::new();
+    for cli in &clis {
+        let invoker = engage
+            .invokers
+            .get(cli)
+            .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", cli))?;
+        if !invoker.is_available().await {
+            anyhow::bail!("CLI '{}' not found in PATH", cli);
+        }
+        invokers.push(invoker);
+    }
+
+    eprintln!(
+        "Drafting release notes for {} commit(s) with {}...",
+        commits.len() + omitted,
+        clis.join(", ")
+    );
+    let model = options.model.as_deref();
+    let results = futures::future::join_all(
+        invokers
+            .iter()
+            .map(|invoker| invoker.invoke(&prompt, options.timeout, AccessMode::ReadOnly, model)),
+    )
+    .await;
+    let mut drafts = Vec::new();
+    let mut authors = Vec::new();
+    let mut last_error = None;
+    for (cli, result) in clis.iter().zip(results) {
+        match result {
+            Ok(draft) => {
+                drafts.push(draft);
+                authors.push(cli.as_str());
+            }
+            Err(e) => {
+                eprintln!("Warning: {} failed: {}", cli, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    let notes = match drafts.len() {
+        0 => return Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No drafts"))),
+        1 => drafts.remove(0),
+        _ => {
+            let judge = options.judge.as_deref().unwrap_or(&options.cli);
+            let judgement = judge_candidates(
+                &engage.invokers,
+                judge,
+                &prompt,
+                &drafts,
+                options.timeout,
+                AccessMode::ReadOnly,
+            )
+            .await?;
+            eprintln!(
+                "Picked {}'s draft: {}",
+                authors[judgement.winner], judgement.justification
+            );
+            drafts.swap_remove(judgement.winner)
+        }
+    };
+    println!("{}", notes.trim());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commits_and_prompt() {
+        let log = format!(
+            "a1b2c3d{0}Ada{0}Add session trash{0}Deleted sessions are kept.\n\nFor 30 days.{1}\ne4f5a6b{0}Bob{0}Fix typo{0}{1}",
+            FIELD_SEP, RECORD_SEP
+        );
+        let commits = parse_commits(&log);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].subject, "Add session trash");
+        assert_eq!(
+            commits[0].body,
+            "Deleted sessions are kept.\n\nFor 30 days."
+        );
+        assert_eq!(commits[1].hash, "e4f5a6b");
+        assert!(commits[1].body.is_empty());
+
+        let prompt = release_notes_prompt("v1.2.0", "HEAD", &commits, 3, " 2 files changed");
+        assert!(prompt.contains("from v1.2.0 to HEAD"));
+        assert!(prompt.contains("COMMITS (5):"));
+        assert!(prompt.contains(
+            "- a1b2c3d Add session trash (Ada)\n    Deleted sessions are kept.\n    For 30 days.\n- e4f5a6b Fix typo (Bob)\n"
+        ));
+        assert!(prompt.contains("- ... and 3 older commits"));
+    }
+}
@@ -0,0 +1,481 @@
+//! Report command - Digest of recent gptengage use
+//!
+//! Aggregates the debate history, sessions, and audit log over a period:
+//! debates run and their recommendations, the busiest sessions, CLI usage,
+//! and failures. The CLIs don't report costs, so time spent in them is
+//! shown instead.
+
+use crate::app::GptEngage;
+use crate::audit::{AuditEntry, AuditLog};
+use crate::history::{DebateHistory, DebateRecord};
+use crate::invokers::policy::binary_name;
+use crate::session::Session;
+use crate::utils::time::{format_duration, parse_since};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Sessions listed under "Top sessions"
+const TOP_SESSIONS: usize = 5;
+
+/// Failed invocations listed individually
+const LISTED_FAILURES: usize = 10;
+
+/// A session's activity in the period
+#[derive(Debug, Serialize)]
+pub struct SessionActivity {
+    pub name: String,
+    pub cli: String,
+    pub topic: String,
+    pub turns: usize,
+}
+
+/// Usage of one CLI binary in the period
+#[derive(Debug, Default, Serialize)]
+pub struct CliUsage {
+    pub runs: usize,
+    pub failed: usize,
+    pub duration_ms: u64,
+}
+
+/// A failed or errored process execution
+#[derive(Debug, Serialize)]
+pub struct FailedInvocation {
+    pub timestamp: DateTime<Utc>,
+    pub command: String,
+    pub outcome: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+/// Everything in a report
+#[derive(Debug, Serialize)]
+pub struct Digest {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub debates: Vec<DebateRecord>,
+    pub sessions: Vec<SessionActivity>,
+    /// Whether the audit log had entries to count invocations from
+    pub audited: bool,
+    /// Usage keyed by CLI binary
+    pub invocations: BTreeMap<String, CliUsage>,
+    pub failures: Vec<FailedInvocation>,
+}
+
+impl Digest {
+    /// Aggregate the records that fall within `since..=until`
+    pub fn build(
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        history: Vec<DebateRecord>,
+        sessions: Vec<Session>,
+        audit: Vec<AuditEntry>,
+    ) -> Self {
+        let in_period = |time: &DateTime<Utc>| *time >= since && *time <= until;
+
+        let debates: Vec<DebateRecord> = history
+            .into_iter()
+            .filter(|r| in_period(&r.timestamp))
+            .collect();
+
+        let mut sessions: Vec<SessionActivity> = sessions
+            .into_iter()
+            .map(|s| SessionActivity {
+                turns: s.turns.iter().filter(|t| in_period(&t.timestamp)).count(),
+                name: s.name,
+                cli: s.cli,
+                topic: s.topic,
+            })
+            .filter(|s| s.turns > 0)
+            .collect();
+        sessions.sort_by(|a, b| b.turns.cmp(&a.turns).then_with(|| a.name.cmp(&b.name)));
+
+        let audited = !audit.is_empty();
+        let mut invocations: BTreeMap<String, CliUsage> = BTreeMap::new();
+        let mut failures = Vec::new();
+        for entry in audit
+            .into_iter()
+            .filter(|e| e.is_cli_invocation() && in_period(&e.timestamp))
+        {
+            let command = binary_name(&entry.command).to_string();
+            let usage = invocations.entry(command.clone()).or_default();
+            usage.runs += 1;
+            usage.duration_ms += entry.duration_ms;
+            if entry.outcome != "ok" {
+                usage.failed += 1;
+                failures.push(FailedInvocation {
+                    timestamp: entry.timestamp,
+                    command,
+                    outcome: entry.outcome,
+                    exit_code: entry.exit_code,
+                });
+            }
+        }
+
+        Self {
+            since,
+            until,
+            debates,
+            sessions,
+            audited,
+            invocations,
+            failures,
+        }
+    }
+
+    fn total_runs(&self) -> usize {
+        self.invocations.values().map(|u| u.runs).sum()
+    }
+
+    fn total_failed(&self) -> usize {
+        self.invocations.values().map(|u| u.failed).sum()
+    }
+
+    fn total_duration_ms(&self) -> u64 {
+        self.invocations.values().map(|u| u.duration_ms).sum()
+    }
+
+    fn debate_failures(&self) -> usize {
+        self.debates.iter().map(|d| d.failures).sum()
+    }
+}
+
+/// Escape a value for a markdown table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Render a digest as markdown
+pub fn render_markdown(digest: &Digest) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "# GPT Engage report: {} to {}",
+        digest.since.format("%Y-%m-%d"),
+        digest.until.format("%Y-%m-%d")
+    )?;
+    writeln!(out)?;
+    writeln!(out, "## Summary")?;
+    writeln!(out)?;
+    let rounds: usize = digest.debates.iter().map(|d| d.rounds).sum();
+    writeln!(
+        out,
+        "- Debates: {} ({} rounds, {} failed responses)",
+        digest.debates.len(),
+        rounds,
+        digest.debate_failures()
+    )?;
+    let turns: usize = digest.sessions.iter().map(|s| s.turns).sum();
+    writeln!(
+        out,
+        "- Active sessions: {} ({} turns)",
+        digest.sessions.len(),
+        turns
+    )?;
+    if digest.audited {
+        writeln!(
+            out,
+            "- CLI invocations: {} ({} failed), {} of CLI time",
+            digest.total_runs(),
+            digest.total_failed(),
+            format_duration(digest.total_duration_ms())
+        )?;
+    } else {
+        writeln!(
+            out,
+            "- CLI invocations: not recorded (enable with `gptengage config set audit_log true`)"
+        )?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "## Decisions")?;
+    writeln!(out)?;
+    let decisions: Vec<&DebateRecord> = digest
+        .debates
+        .iter()
+        .filter(|d| d.recommendation.is_some())
+        .collect();
+    if decisions.is_empty() {
+        writeln!(out, "_No synthesized recommendations in this period._")?;
+    }
+    for debate in decisions {
+        writeln!(
+            out,
+            "- **{}**: {}",
+            debate.topic,
+            debate.recommendation.as_deref().unwrap_or_default().trim()
+        )?;
+    }
+    writeln!(out)?;
+
+    if !digest.debates.is_empty() {
+        writeln!(out, "## Debates")?;
+        writeln!(out)?;
+        writeln!(out, "| When | Topic | Participants | Rounds | Failed |")?;
+        writeln!(out, "|------|-------|--------------|--------|--------|")?;
+        for debate in &digest.debates {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                debate.timestamp.format("%Y-%m-%d %H:%M"),
+                cell(&debate.topic),
+                cell(&debate.participants.join(", ")),
+                debate.rounds,
+                debate.failures
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    if !digest.sessions.is_empty() {
+        writeln!(out, "## Top sessions")?;
+        writeln!(out)?;
+        writeln!(out, "| Session | CLI | Turns | Topic |")?;
+        writeln!(out, "|---------|-----|-------|-------|")?;
+        for session in digest.sessions.iter().take(TOP_SESSIONS) {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                cell(&session.name),
+                session.cli,
+                session.turns,
+                cell(&session.topic)
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    if !digest.invocations.is_empty() {
+        writeln!(out, "## CLI usage")?;
+        writeln!(out)?;
+        writeln!(out, "| CLI | Runs | Failed | Time |")?;
+        writeln!(out, "|-----|------|--------|------|")?;
+        for (cli, usage) in &digest.invocations {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                cli,
+                usage.runs,
+                usage.failed,
+                format_duration(usage.duration_ms)
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    if !digest.failures.is_empty() {
+        writeln!(out, "## Failures")?;
+        writeln!(out)?;
+        let skip = digest.failures.len().saturating_sub(LISTED_FAILURES);
+        for failure in &digest.failures[skip..] {
+            let exit = failure
+                .exit_code
+                .map(|c| format!(", exit {}", c))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "- {} {} ({}{})",
+                failure.timestamp.format("%Y-%m-%d %H:%M"),
+                failure.command,
+                failure.outcome,
+                exit
+            )?;
+        }
+        if skip > 0 {
+            writeln!(out, "- ...and {} earlier", skip)?;
+        }
+        writeln!(out)?;
+    }
+
+    Ok(out)
+}
+
+/// Render a digest as plain text
+pub fn render_text(digest: &Digest) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "GPT Engage report: {} to {}",
+        digest.since.format("%Y-%m-%d %H:%M UTC"),
+        digest.until.format("%Y-%m-%d %H:%M UTC")
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Debates: {} ({} failed responses)",
+        digest.debates.len(),
+        digest.debate_failures()
+    )?;
+    for debate in &digest.debates {
+        writeln!(
+            out,
+            "  {}  {} ({} rounds, {})",
+            debate.timestamp.format("%Y-%m-%d %H:%M"),
+            debate.topic,
+            debate.rounds,
+            debate.participants.join(", ")
+        )?;
+        if let Some(ref recommendation) = debate.recommendation {
+            writeln!(out, "    Recommendation: {}", recommendation.trim())?;
+        }
+    }
+    writeln!(out)?;
+
+    writeln!(out, "Active sessions: {}", digest.sessions.len())?;
+    for session in digest.sessions.iter().take(TOP_SESSIONS) {
+        writeln!(
+            out,
+            "  {} ({}): {} turns - {}",
+            session.name, session.cli, session.turns, session.topic
+        )?;
+    }
+    writeln!(out)?;
+
+    if !digest.audited {
+        writeln!(
+            out,
+            "CLI invocations: not recorded (enable with: gptengage config set audit_log true)"
+        )?;
+        return Ok(out);
+    }
+    writeln!(
+        out,
+        "CLI invocations: {} ({} failed), {} of CLI time",
+        digest.total_runs(),
+        digest.total_failed(),
+        format_duration(digest.total_duration_ms())
+    )?;
+    for (cli, usage) in &digest.invocations {
+        writeln!(
+            out,
+            "  {}: {} runs, {} failed, {}",
+            cli,
+            usage.runs,
+            usage.failed,
+            format_duration(usage.duration_ms)
+        )?;
+    }
+    Ok(out)
+}
+
+/// Print a digest of activity since `since`
+pub async fn run_report(engage: &GptEngage, since: String, output: String) -> anyhow::Result<()> {
+    let until = Utc::now();
+    let since = parse_since(&since, until)?;
+
+    let history = DebateHistory::open()?.read()?;
+    let sessions = engage.sessions()?.all_sessions().await?;
+    let audit = AuditLog::open()?.read()?;
+    let digest = Digest::build(since, until, history, sessions, audit);
+
+    match output.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&digest)?),
+        "markdown" => print!("{}", render_markdown(&digest)?),
+        "text" => print!("{}", render_text(&digest)?),
+        other => anyhow::bail!(
+            "Invalid output format '{}'. Use text, markdown, or json",
+            other
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invokers::AccessMode;
+    use crate::session::Turn;
+    use chrono::{Duration, TimeZone};
+
+    fn record(timestamp: DateTime<Utc>, topic: &str, recommendation: Option<&str>) -> DebateRecord {
+        DebateRecord {
+            timestamp,
+            topic: topic.to_string(),
+            topic_bytes: topic.len(),
+            participants: vec!["claude".to_string(), "codex".to_string()],
+            rounds: 2,
+            responses: 3,
+            failures: 1,
+            recommendation: recommendation.map(String::from),
+            duration_ms: 5000,
+        }
+    }
+
+    fn audit(timestamp: DateTime<Utc>, command: &str, ok: bool) -> AuditEntry {
+        let result = if ok {
+            Ok(String::new())
+        } else {
+            Err(anyhow::anyhow!("boom"))
+        };
+        let mut entry = AuditEntry::new(
+            timestamp,
+            std::time::Duration::from_secs(90),
+            command,
+            &[],
+            "prompt",
+            None,
+            AccessMode::ReadOnly,
+            Some(if ok { 0 } else { 1 }),
+            &result,
+        );
+        entry.timestamp = timestamp;
+        entry
+    }
+
+    #[test]
+    fn test_digest_aggregates_period() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+        let since = now - Duration::days(7);
+        let old = now - Duration::days(30);
+        let recent = now - Duration::days(1);
+
+        let turn = |timestamp| Turn {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+            timestamp,
+            log: None,
+            changes: None,
+        };
+        let session = Session {
+            name: "review".to_string(),
+            cli: "claude".to_string(),
+            topic: "Code review".to_string(),
+            created_at: old,
+            last_interaction: recent,
+            turns: vec![turn(old), turn(recent), turn(recent)],
+            native: None,
+        };
+
+        let digest = Digest::build(
+            since,
+            now,
+            vec![
+                record(old, "Old", None),
+                record(recent, "Shard the DB?", Some("Shard by tenant")),
+            ],
+            vec![session],
+            vec![
+                audit(old, "/usr/bin/claude", false),
+                audit(recent, "/usr/bin/claude", true),
+                audit(recent, "codex", false),
+                AuditEntry {
+                    access_mode: crate::audit::NO_ACCESS_MODE.to_string(),
+                    ..audit(recent, "git", true)
+                },
+            ],
+        );
+
+        assert_eq!(digest.debates.len(), 1);
+        assert_eq!(digest.sessions[0].turns, 2);
+        assert_eq!(digest.total_runs(), 2);
+        assert_eq!(digest.invocations["claude"].failed, 0);
+        assert_eq!(digest.failures.len(), 1);
+
+        let markdown = render_markdown(&digest).unwrap();
+        assert!(markdown.contains("- **Shard the DB?**: Shard by tenant"));
+        assert!(markdown.contains("- CLI invocations: 2 (1 failed), 3m 0s of CLI time"));
+        assert!(markdown.contains("| review | claude | 2 | Code review |"));
+        assert!(!markdown.contains("Old"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Rollback command - Undo what a `--snapshot` write-mode run changed
+
+use crate::utils::interaction::{can_ask, confirm};
+use crate::utils::run_snapshot::{snapshots_dir, RunSnapshot};
+use crate::utils::table::{render_table, terminal_width};
+use crate::utils::time::format_when;
+
+/// List snapshots, newest first
+pub fn list_snapshots() -> anyhow::Result<()> {
+    let snapshots = RunSnapshot::list(&snapshots_dir()?)?;
+    if snapshots.is_empty() {
+        println!("No snapshots. Pass --snapshot to a --write run to take one.");
+        return Ok(());
+    }
+
+    let rows: Vec<Vec<String>> = snapshots
+        .iter()
+        .map(|snapshot| {
+            vec![
+                snapshot.id.clone(),
+                format_when(snapshot.created_at),
+                snapshot.root.display().to_string(),
+                snapshot.label.clone(),
+            ]
+        })
+        .collect();
+
+    // Shrink the run label first, then the directory
+    print!(
+        "{}",
+        render_table(
+            &["Snapshot", "Taken", "Directory", "Run"],
+            &rows,
+            terminal_width(),
+            &[3, 2],
+            8,
+        )
+    );
+    Ok(())
+}
+
+/// Restore the working directory of snapshot `id` after confirming, or
+/// with `dry_run` only show what would change
+pub fn run_rollback(id: String, dry_run: bool) -> anyhow::Result<()> {
+    let dir = snapshots_dir()?;
+    let snapshot = RunSnapshot::load(&dir, &id)?;
+    let changes = snapshot.plan(&dir)?;
+    if changes.is_empty() {
+        println!(
+            "{} already matches snapshot {}.",
+            snapshot.root.display(),
+            id
+        );
+        return Ok(());
+    }
+    println!("Rolling back {} would undo:", id);
+    print!("{}", changes.render()?);
+    if dry_run {
+        return Ok(());
+    }
+
+    // Edits made by hand since the run are overwritten too
+    let question = format!("Roll back {} in {}?", id, snapshot.root.display());
+    if !confirm(&question, false)? {
+        if can_ask() {
+            println!("Nothing rolled back.");
+            return Ok(());
+        }
+        anyhow::bail!(
+            "Not rolling back {} without confirmation. Pass --yes to roll back",
+            id
+        );
+    }
+
+    snapshot.restore(&dir)?;
+    println!("Rolled back {} in {}.", id, snapshot.root.display());
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Schema command - JSON Schemas for gptengage's machine formats
+//!
+//! Schemas are generated from the same types that read and write each
+//! format, so they can't drift from what gptengage actually accepts.
+
+use crate::cli::SchemaFormat;
+use crate::commands::attribution::Utterance;
+use crate::commands::decisions::Decision;
+use crate::commands::synthesize::CrossSynthesis;
+use crate::orchestrator::{AgentFile, DebateResult, Synthesis};
+use crate::plugins::PluginConfig;
+use crate::templates::TemplateFile;
+use schemars::{schema_for, Schema};
+
+/// JSON Schema for a format
+pub fn schema(format: SchemaFormat) -> Schema {
+    match format {
+        SchemaFormat::DebateResult => schema_for!(DebateResult),
+        SchemaFormat::AgentFile => schema_for!(AgentFile),
+        SchemaFormat::Plugin => schema_for!(PluginConfig),
+        SchemaFormat::Template => schema_for!(TemplateFile),
+        SchemaFormat::Synthesis => schema_for!(Synthesis),
+        SchemaFormat::Utterances => schema_for!(Vec<Utterance>),
+        SchemaFormat::CrossSynthesis => schema_for!(CrossSynthesis),
+        SchemaFormat::Decision => schema_for!(Decision),
+    }
+}
+
+/// Print the JSON Schema for a format
+pub async fn print_schema(format: SchemaFormat) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(&schema(format))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn test_schemas_describe_formats() {
+        for format in SchemaFormat::value_variants() {
+            let schema = serde_json::to_value(schema(*format)).unwrap();
+            // Object formats list properties; list formats describe their items
+            let described = schema["properties"].is_object() || schema["items"].is_object();
+            assert!(described, "{:?}", format);
+        }
+
+        let result = serde_json::to_value(schema(SchemaFormat::DebateResult)).unwrap();
+        let required: Vec<&str> = result["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert_eq!(required, vec!["topic", "rounds"]);
+
+        let template = serde_json::to_value(schema(SchemaFormat::Template)).unwrap();
+        assert!(template["properties"]["template"].is_object());
+    }
+}
@@ -0,0 +1,648 @@
+//! Search command - Find past discussions across sessions and transcripts
+//!
+//! Every session turn and transcript passage becomes a TF-IDF term vector,
+//! computed locally, and passages are ranked by cosine similarity to the
+//! query. Nothing is sent to a CLI.
+//!
+//! With `--rank-with <CLI>` the search is semantic: the CLI first expands
+//! the query into related words and phrases, so passages that discuss the
+//! topic in other terms become candidates, then ranks the candidates by
+//! meaning and leaves out those that aren't about the query.
+
+use crate::app::GptEngage;
+use crate::invokers::{AccessMode, Invoker};
+use crate::orchestrator::DebateResult;
+use crate::utils::json::find_json;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Words too common to say anything about a passage
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been",
+    "but", "by", "can", "could", "do", "does", "for", "from", "had", "has", "have", "how", "if",
+    "in", "into", "is", "it", "its", "just", "may", "more", "most", "no", "not", "of", "on", "or",
+    "our", "should", "so", "some", "such", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "to", "was", "we", "were", "what", "when", "which", "while", "who",
+    "will", "with", "would", "you", "your",
+];
+
+/// Characters of context shown around the best match
+const EXCERPT_CHARS: usize = 240;
+
+/// Candidates sent to the ranking CLI per requested result
+const CANDIDATES_PER_RESULT: usize = 3;
+
+/// Most candidates sent to the ranking CLI
+const MAX_CANDIDATES: usize = 40;
+
+/// Characters of each candidate shown to the ranking CLI
+const CANDIDATE_CHARS: usize = 600;
+
+/// Options for `search`
+pub struct SearchOptions {
+    pub query: String,
+    /// Transcript files or directories searched too
+    pub paths: Vec<String>,
+    pub include_sessions: bool,
+    pub limit: usize,
+    pub json: bool,
+    /// CLI that expands the query and ranks candidates by meaning
+    pub rank_with: Option<String>,
+    pub timeout: u64,
+}
+
+/// A searchable passage
+#[derive(Debug, Clone, Serialize)]
+pub struct Passage {
+    /// Session name or transcript path
+    pub source: String,
+    /// Where in the source, e.g. "turn 3 (assistant, 2026-01-05)" or "round 2, codex (SRE)"
+    pub location: String,
+    pub text: String,
+}
+
+/// A ranked search result
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub score: f32,
+    pub source: String,
+    pub location: String,
+    pub excerpt: String,
+}
+
+/// Lowercased word stems, without stopwords
+fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 1)
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .map(|word| stem(&word).to_string())
+        .collect()
+}
+
+/// Strip common English suffixes so "breakers" matches "breaker"
+fn stem(word: &str) -> &str {
+    for suffix in ["ing", "ed", "es", "s"] {
+        if let Some(stripped) = word.strip_suffix(suffix) {
+            if stripped.chars().count() >= 3 && !stripped.ends_with('s') {
+                return stripped;
+            }
+        }
+    }
+    word
+}
+
+type Vector = HashMap<String, f32>;
+
+/// TF-IDF index over a set of passages
+pub struct SearchIndex {
+    passages: Vec<Passage>,
+    vectors: Vec<Vector>,
+    idf: HashMap<String, f32>,
+}
+
+impl SearchIndex {
+    pub fn new(passages: Vec<Passage>) -> Self {
+        let counts: Vec<HashMap<String, f32>> = passages
+            .iter()
+            .map(|p| {
+                let mut counts = HashMap::new();
+                for term in terms(&p.text) {
+                    *counts.entry(term).or_insert(0.0) += 1.0;
+                }
+                counts
+            })
+            .collect();
+
+        let mut document_frequency: HashMap<String, f32> = HashMap::new();
+        for counts in &counts {
+            for term in counts.keys() {
+                *document_frequency.entry(term.clone()).or_insert(0.0) += 1.0;
+            }
+        }
+        let total = passages.len() as f32;
+        let idf: HashMap<String, f32> = document_frequency
+            .into_iter()
+            .map(|(term, df)| (term, ((1.0 + total) / (1.0 + df)).ln() + 1.0))
+            .collect();
+
+        let vectors = counts
+            .into_iter()
+            .map(|counts| weigh(counts, &idf))
+            .collect();
+        Self {
+            passages,
+            vectors,
+            idf,
+        }
+    }
+
+    /// Term vector of a query, weighted by this index
+    fn vectorize(&self, query: &str) -> Vector {
+        let mut counts = HashMap::new();
+        for term in terms(query) {
+            *counts.entry(term).or_insert(0.0) += 1.0;
+        }
+        weigh(counts, &self.idf)
+    }
+
+    /// Indices and scores of the `limit` passages most similar to
+    /// `query`, best first
+    fn candidates(&self, query: &str, limit: usize) -> Vec<(usize, f32)> {
+        let query_vector = self.vectorize(query);
+        if query_vector.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(usize, f32)> = self
+            .vectors
+            .iter()
+            .enumerate()
+            .map(|(i, vector)| (i, cosine(&query_vector, vector)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        scored
+    }
+
+    /// The `limit` passages most similar to `query`, best first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let query_vector = self.vectorize(query);
+        self.candidates(query, limit)
+            .into_iter()
+            .map(|(i, score)| {
+                let passage = &self.passages[i];
+                SearchHit {
+                    score,
+                    source: passage.source.clone(),
+                    location: passage.location.clone(),
+                    excerpt: excerpt(&passage.text, &query_vector),
+                }
+            })
+            .collect()
+    }
+
+    /// Cosine similarity of two indexed passages
+    pub fn similarity(&self, a: usize, b: usize) -> f32 {
+        cosine(&self.vectors[a], &self.vectors[b])
+    }
+}
+
+/// Log-scaled term frequency times IDF, normalized to unit length.
+/// Terms unknown to the index are dropped.
+fn weigh(counts: HashMap<String, f32>, idf: &HashMap<String, f32>) -> Vector {
+    let mut vector: Vector = counts
+        .into_iter()
+        .filter_map(|(term, count)| idf.get(&term).map(|w| (term, (1.0 + count.ln()) * w)))
+        .collect();
+    let norm = vector.values().map(|w| w * w).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.values_mut().for_each(|w| *w /= norm);
+    }
+    vector
+}
+
+fn cosine(a: &Vector, b: &Vector) -> f32 {
+    a.iter()
+        .filter_map(|(term, w)| b.get(term).map(|v| w * v))
+        .sum()
+}
+
+/// A window of the passage around its first query term, whitespace collapsed
+fn excerpt(text: &str, query: &Vector) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let hit = words
+        .iter()
+        .position(|word| terms(word).iter().any(|t| query.contains_key(t)))
+        .unwrap_or(0);
+
+    // Start a few words before the match so it reads in context
+    let start = hit.saturating_sub(8);
+    let mut out = String::new();
+    for word in &words[start..] {
+        if out.chars().count() + word.chars().count() > EXCERPT_CHARS {
+            out.push('…');
+            break;
+        }
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    if start > 0 {
+        out.insert(0, '…');
+    }
+    out
+}
+
+/// Prompt asking for words and phrases related to a query
+fn expansion_prompt(query: &str) -> String {
+    format!(
+        r#"[SEARCH EXPANSION REQUEST]
+Someone is searching past technical discussions for: {}
+
+List up to 15 words and short phrases a discussion of this might use
+instead of the query's own words: synonyms, related concepts, and the
+technical terms involved.
+
+Respond with a JSON array of strings only.
+[/SEARCH EXPANSION REQUEST]"#,
+        query
+    )
+}
+
+/// Prompt asking for candidate passages ranked by relevance to a query
+fn ranking_prompt(query: &str, candidates: &[&Passage]) -> String {
+    let mut passages = String::new();
+    for (i, passage) in candidates.iter().enumerate() {
+        let text: String = passage.text.chars().take(CANDIDATE_CHARS).collect();
+        passages.push_str(&format!("[{}] {}\n\n", i + 1, text.trim()));
+    }
+    format!(
+        r#"[SEARCH RANKING REQUEST]
+Someone is searching past technical discussions for: {}
+
+PASSAGES:
+{}Rank the passages by how well they answer the search, judging by meaning
+rather than shared words. Leave out passages that are not about it.
+
+Respond with a JSON array of passage numbers only, most relevant first.
+[/SEARCH RANKING REQUEST]"#,
+        query, passages
+    )
+}
+
+/// Passage numbers (from 1) in a ranking response, as indices into
+/// `count` candidates, dropping repeats and numbers out of range
+fn parse_ranking(response: &str, count: usize) -> anyhow::Result<Vec<usize>> {
+    let json = find_json(response, "array of numbers", |value| {
+        value
+            .as_array()
+            .is_some_and(|items| items.iter().all(serde_json::Value::is_u64))
+    })?;
+    let numbers: Vec<usize> =
+        serde_json::from_str(json).map_err(|e| anyhow::anyhow!("Invalid ranking JSON: {}", e))?;
+    let mut ranked = Vec::new();
+    for number in numbers {
+        if (1..=count).contains(&number) && !ranked.contains(&(number - 1)) {
+            ranked.push(number - 1);
+        }
+    }
+    Ok(ranked)
+}
+
+/// Search `index` by meaning with `invoker`: expand the query, take the
+/// best lexical candidates for the expanded query, and have the CLI rank
+/// them. Scores run from 1.0 for the CLI's first pick down.
+async fn semantic_search(
+    index: &SearchIndex,
+    query: &str,
+    limit: usize,
+    invoker: &dyn Invoker,
+    timeout: u64,
+) -> anyhow::Result<Vec<SearchHit>> {
+    let response = invoker
+        .invoke(
+            &expansion_prompt(query),
+            timeout,
+            AccessMode::ReadOnly,
+            None,
+        )
+        .await?;
+    let related: Vec<String> = find_json(&response, "array of strings", |value| {
+        value
+            .as_array()
+            .is_some_and(|items| items.iter().all(serde_json::Value::is_string))
+    })
+    .ok()
+    .and_then(|json| serde_json::from_str(json).ok())
+    .unwrap_or_default();
+    let expanded = std::iter::once(query.to_string())
+        .chain(related)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let pool = (limit * CANDIDATES_PER_RESULT).min(MAX_CANDIDATES);
+    let candidates = index.candidates(&expanded, pool);
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let passages: Vec<&Passage> = candidates
+        .iter()
+        .map(|&(i, _)| &index.passages[i])
+        .collect();
+    let response = invoker
+        .invoke(
+            &ranking_prompt(query, &passages),
+            timeout,
+            AccessMode::ReadOnly,
+            None,
+        )
+        .await?;
+    let ranked = parse_ranking(&response, candidates.len())?;
+
+    let query_vector = index.vectorize(&expanded);
+    let shown = ranked.len().min(limit);
+    Ok(ranked
+        .into_iter()
+        .take(limit)
+        .enumerate()
+        .map(|(rank, candidate)| {
+            let passage = passages[candidate];
+            SearchHit {
+                score: 1.0 - rank as f32 / shown as f32,
+                source: passage.source.clone(),
+                location: passage.location.clone(),
+                excerpt: excerpt(&passage.text, &query_vector),
+            }
+        })
+        .collect())
+}
+
+/// Passages from every session turn
+async fn session_passages(engage: &GptEngage) -> anyhow::Result<Vec<Passage>> {
+    let manager = engage.sessions()?;
+    let mut passages = Vec::new();
+    for session in manager.all_sessions().await? {
+        for (i, turn) in session.turns.iter().enumerate() {
+            passages.push(Passage {
+                source: format!("session:{}", session.name),
+                location: format!(
+                    "turn {} ({}, {})",
+                    i + 1,
+                    turn.role,
+                    turn.timestamp.format("%Y-%m-%d")
+                ),
+                text: turn.content.clone(),
+            });
+        }
+    }
+    Ok(passages)
+}
+
+/// Passages from a transcript file: each response of a JSON debate
+/// transcript, or each paragraph of a text or markdown file
+pub fn file_passages(path: &Path, content: &str) -> Vec<Passage> {
+    let source = path.display().to_string();
+    if let Ok(result) = DebateResult::from_json(content) {
+        let mut passages = Vec::new();
+        for (round, responses) in result.rounds.iter().enumerate() {
+            for response in responses {
+                passages.push(Passage {
+                    source: source.clone(),
+                    location: format!("round {}, {}", round + 1, response.display_name()),
+                    text: response.response.clone(),
+                });
+            }
+        }
+        if let Some(synthesis) = result.synthesis {
+            let mut text = synthesis.summary;
+            for point in synthesis
+                .consensus_points
+                .iter()
+                .chain(&synthesis.disagreement_points)
+                .chain(&synthesis.key_insights)
+                .chain(&synthesis.recommendation)
+            {
+                text.push('\n');
+                text.push_str(point);
+            }
+            passages.push(Passage {
+                source: source.clone(),
+                location: "synthesis".to_string(),
+                text,
+            });
+        }
+        return passages;
+    }
+    // Other JSON (agent files, configs) isn't prose worth searching
+    if path.extension().is_some_and(|e| e == "json") {
+        return Vec::new();
+    }
+
+    let mut passages = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut start_line = 1;
+    for (i, line) in content.lines().chain(std::iter::once("")).enumerate() {
+        if line.trim().is_empty() {
+            if !paragraph.is_empty() {
+                passages.push(Passage {
+                    source: source.clone(),
+                    location: format!("line {}", start_line),
+                    text: paragraph.join("\n"),
+                });
+                paragraph.clear();
+            }
+            start_line = i + 2;
+        } else {
+            paragraph.push(line);
+        }
+    }
+    passages
+}
+
+/// Transcript files under `path`: the file itself, or the .json, .md, and
+/// .txt files in a directory tree
+fn transcript_files(path: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let entries = std::fs::read_dir(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            transcript_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|e| e == "json" || e == "md" || e == "txt")
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Search sessions and transcript files for passages similar to the query
+pub async fn run_search(engage: &GptEngage, options: SearchOptions) -> anyhow::Result<()> {
+    let SearchOptions {
+        query,
+        paths,
+        include_sessions,
+        limit,
+        json,
+        rank_with,
+        timeout,
+    } = options;
+    let ranker = match &rank_with {
+        Some(cli) => {
+            let invoker = engage
+                .invokers
+                .get(cli)
+                .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", cli))?;
+            if !invoker.is_available().await {
+                anyhow::bail!("CLI '{}' not found in PATH", cli);
+            }
+            Some((cli, invoker))
+        }
+        None => None,
+    };
+
+    let mut passages = if include_sessions {
+        session_passages(engage).await?
+    } else {
+        Vec::new()
+    };
+    let mut files = Vec::new();
+    for path in &paths {
+        transcript_files(Path::new(path), &mut files)?;
+    }
+    for file in &files {
+        // Unreadable or binary files are not transcripts; skip them
+        if let Ok(content) = std::fs::read_to_string(file) {
+            passages.extend(file_passages(file, &content));
+        }
+    }
+
+    let searched = passages.len();
+    let index = SearchIndex::new(passages);
+    let hits = match ranker {
+        Some((cli, invoker)) => {
+            eprintln!("Ranking with {}...", cli);
+            semantic_search(&index, &query, limit, invoker.as_ref(), timeout).await?
+        }
+        None => index.search(&query, limit),
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+    if hits.is_empty() {
+        println!("No matches in {} passages.", searched);
+        return Ok(());
+    }
+    for (i, hit) in hits.iter().enumerate() {
+        println!(
+            "{}. {} — {} (score {:.2})",
+            i + 1,
+            hit.source,
+            hit.location,
+            hit.score
+        );
+        println!("   {}", hit.excerpt);
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(source: &str, text: &str) -> Passage {
+        Passage {
+            source: source.to_string(),
+            location: String::new(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_search_ranks_relevant_passages() {
+        let index = SearchIndex::new(vec![
+            passage(
+                "a",
+                "We should add a circuit breaker around the payments client.",
+            ),
+            passage("b", "The database schema needs an index on created_at."),
+            passage("c", "Retries without circuit breakers amplify outages."),
+        ]);
+        let hits = index.search("circuit breaker discussion", 5);
+        let sources: Vec<&str> = hits.iter().map(|h| h.source.as_str()).collect();
+        assert_eq!(sources.len(), 2);
+        assert!(sources.contains(&"a") && sources.contains(&"c"));
+        assert!(index.search("the and of", 5).is_empty());
+    }
+
+    #[test]
+    fn test_parse_ranking() {
+        assert_eq!(
+            parse_ranking("Most relevant first:\n[3, 1, 3, 9, 0]", 4).unwrap(),
+            vec![2, 0]
+        );
+        assert!(parse_ranking("none of them", 4).is_err());
+        let a = passage("a", "Breakers trip after five failures.");
+        let prompt = ranking_prompt("circuit breaker", &[&a]);
+        assert!(prompt.contains("[1] Breakers trip after five failures.\n"));
+    }
+
+    /// Expands "flaky dependencies" and ranks the breaker passage first
+    struct Ranker;
+
+    #[async_trait::async_trait]
+    impl Invoker for Ranker {
+        async fn invoke(
+            &self,
+            prompt: &str,
+            _timeout: u64,
+            _access_mode: AccessMode,
+            _model: Option<&str>,
+        ) -> anyhow::Result<String> {
+            if prompt.starts_with("[SEARCH EXPANSION REQUEST]") {
+                Ok(r#"["circuit breaker", "retries", "outage"]"#.to_string())
+            } else {
+                assert!(prompt.contains("] Retries without"));
+                Ok("[2, 1]".to_string())
+            }
+        }
+
+        fn name(&self) -> &str {
+            "claude"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_finds_passages_without_shared_words() {
+        let index = SearchIndex::new(vec![
+            passage("a", "Retries without circuit breakers amplify outages."),
+            passage("b", "Wrap the payments client in a circuit breaker."),
+            passage("c", "The schema needs an index on created_at."),
+        ]);
+        assert!(index.search("flaky dependencies", 5).is_empty());
+        let hits = semantic_search(&index, "flaky dependencies", 5, &Ranker, 5)
+            .await
+            .unwrap();
+        let sources: Vec<&str> = hits.iter().map(|h| h.source.as_str()).collect();
+        assert_eq!(sources, vec!["b", "a"]);
+        assert_eq!(hits[0].score, 1.0);
+    }
+
+    #[test]
+    fn test_file_passages_split_paragraphs() {
+        let passages = file_passages(
+            Path::new("notes.md"),
+            "# Title\n\nFirst line\nsecond\n\n\nLast",
+        );
+        let locations: Vec<&str> = passages.iter().map(|p| p.location.as_str()).collect();
+        assert_eq!(locations, vec!["line 1", "line 3", "line 7"]);
+        assert_eq!(passages[1].text, "First line\nsecond");
+        assert!(file_passages(Path::new("config.json"), "{\"a\": 1}").is_empty());
+    }
+
+    #[test]
+    fn test_excerpt_centers_on_match() {
+        let text = format!("{} circuit breaker tail", "filler ".repeat(100));
+        let mut query = Vector::new();
+        query.insert("circuit".to_string(), 1.0);
+        let excerpt = excerpt(&text, &query);
+        assert!(excerpt.starts_with('…'));
+        assert!(excerpt.contains("circuit breaker tail"));
+    }
+}
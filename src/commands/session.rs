@@ -1,10 +1,26 @@
 //! Session management commands
 
-use crate::session::SessionManager;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use crate::app::GptEngage;
+use crate::invokers::logs::capture_log_paths;
+use crate::invokers::AccessMode;
+use crate::session::{prompt_with_turns, recent_turns, Session, Turn};
+use crate::utils::interaction::{can_ask, confirm};
+use crate::utils::picker::{pick, PickItem};
+use crate::utils::table::{pad_to_width, render_table, terminal_width, wrap_to_width};
+use crate::utils::time::{format_timestamp, format_when};
+
+/// Maximum width of the `session show` transcript box
+const MAX_BOX_WIDTH: usize = 100;
 
 /// List all active sessions
-pub async fn list_sessions() -> anyhow::Result<()> {
-    let manager = SessionManager::new()?;
+pub async fn list_sessions(engage: &GptEngage) -> anyhow::Result<()> {
+    let manager = engage.sessions()?;
     let sessions = manager.list_sessions().await?;
 
     if sessions.is_empty() {
@@ -12,85 +28,426 @@ pub async fn list_sessions() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    println!("┌──────────────┬────────┬─────────────────────────┬──────────────┐");
-    println!("│ Session      │ CLI    │ Topic                   │ Last Used    │");
-    println!("├──────────────┼────────┼─────────────────────────┼──────────────┤");
+    let rows: Vec<Vec<String>> = sessions
+        .iter()
+        .map(|session| {
+            vec![
+                session.name.clone(),
+                session.cli.clone(),
+                session.topic.clone(),
+                format_when(session.last_interaction),
+            ]
+        })
+        .collect();
+
+    // Shrink the topic first, then the session name, to fit the terminal
+    print!(
+        "{}",
+        render_table(
+            &["Session", "CLI", "Topic", "Last Used"],
+            &rows,
+            terminal_width(),
+            &[2, 0],
+            8,
+        )
+    );
+    Ok(())
+}
+
+/// Choose a session interactively and print its name
+pub async fn pick_session_command(engage: &GptEngage) -> anyhow::Result<()> {
+    match pick_session(engage).await? {
+        Some(name) => {
+            println!("{}", name);
+            Ok(())
+        }
+        None => Err(anyhow::anyhow!("No session selected")),
+    }
+}
+
+/// Let the user fuzzy-find a session, most recently used first.
+///
+/// Returns None if the selection was cancelled.
+pub async fn pick_session(engage: &GptEngage) -> anyhow::Result<Option<String>> {
+    let manager = engage.sessions()?;
+    let sessions = manager.list_sessions().await?;
+    if sessions.is_empty() {
+        anyhow::bail!("No sessions to pick from");
+    }
+
+    let items: Vec<PickItem> = sessions
+        .iter()
+        .map(|session| PickItem {
+            label: session.name.clone(),
+            detail: format!(
+                "{} · {} · {}",
+                session.cli,
+                format_when(session.last_interaction),
+                session.topic
+            ),
+        })
+        .collect();
 
-    for session in sessions {
-        let time_ago = format_time_ago(session.last_interaction);
-        // Use chars().take() for UTF-8 safe truncation
-        let truncated_topic: String = session.topic.chars().take(23).collect();
-        println!(
-            "│ {:<12} │ {:<6} │ {:<23} │ {:<12} │",
-            session.name, session.cli, truncated_topic, time_ago
+    let choice = tokio::task::spawn_blocking(move || pick("Session", &items)).await??;
+    Ok(choice.map(|idx| sessions[idx].name.clone()))
+}
+
+/// Which turns of a session to show
+#[derive(Debug, Default, Clone)]
+pub struct TurnSelection {
+    /// Only the last N turns
+    pub last: Option<usize>,
+    /// Inclusive 1-based range, e.g. "3..7", "3..", "..7", or "5"
+    pub turns: Option<String>,
+}
+
+impl TurnSelection {
+    /// Resolve to a 0-based index range over `total` turns
+    fn range(&self, total: usize) -> anyhow::Result<Range<usize>> {
+        if let Some(last) = self.last {
+            return Ok(total.saturating_sub(last)..total);
+        }
+        match &self.turns {
+            Some(spec) => parse_turn_range(spec, total),
+            None => Ok(0..total),
+        }
+    }
+}
+
+/// Parse an inclusive 1-based turn range into 0-based indices
+fn parse_turn_range(spec: &str, total: usize) -> anyhow::Result<Range<usize>> {
+    let parse = |s: &str, default: usize| -> anyhow::Result<usize> {
+        if s.trim().is_empty() {
+            return Ok(default);
+        }
+        let n: usize = s
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid turn range '{}' (expected e.g. 3..7)", spec))?;
+        if n == 0 {
+            anyhow::bail!("Turn numbers start at 1");
+        }
+        Ok(n)
+    };
+
+    let (start, end) = match spec.split_once("..") {
+        Some((start, end)) => (parse(start, 1)?, parse(end, total)?),
+        None => {
+            let n = parse(spec, 1)?;
+            (n, n)
+        }
+    };
+    if start > end {
+        anyhow::bail!("Invalid turn range '{}': start is after end", spec);
+    }
+    if start > total {
+        anyhow::bail!(
+            "Turn {} is out of range (session has {} turns)",
+            start,
+            total
         );
     }
+    Ok(start - 1..end.min(total))
+}
 
-    println!("└──────────────┴────────┴─────────────────────────┴──────────────┘");
-    Ok(())
+/// A turn as exported by `session show --format json`
+#[derive(Serialize)]
+struct ExportedTurn<'a> {
+    /// 1-based position in the full session
+    number: usize,
+    role: &'a str,
+    content: &'a str,
+    timestamp: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    log: Option<&'a str>,
+}
+
+/// A session as exported by `session show --format json`
+#[derive(Serialize)]
+struct ExportedSession<'a> {
+    name: &'a str,
+    cli: &'a str,
+    topic: &'a str,
+    created_at: DateTime<Utc>,
+    last_interaction: DateTime<Utc>,
+    total_turns: usize,
+    turns: Vec<ExportedTurn<'a>>,
 }
 
 /// Show a specific session's history
-pub async fn show_session(name: String) -> anyhow::Result<()> {
-    let manager = SessionManager::new()?;
+pub async fn show_session(
+    engage: &GptEngage,
+    name: String,
+    format: String,
+    selection: TurnSelection,
+) -> anyhow::Result<()> {
+    let manager = engage.sessions()?;
     let session = manager.load_session(&name).await?;
+    let range = selection.range(session.turns.len())?;
 
-    println!("Session: {}", session.name);
-    println!("CLI: {}", session.cli);
-    println!("Topic: {}", session.topic);
-    println!(
-        "Created: {}",
-        session.created_at.format("%Y-%m-%d %H:%M:%S UTC")
-    );
-    println!(
+    match format.as_str() {
+        "json" => println!("{}", render_json(&session, range)?),
+        "markdown" => print!("{}", render_markdown(&session, range)?),
+        "text" => print!("{}", render_text(&session, range)?),
+        other => anyhow::bail!(
+            "Unknown format '{}'. Expected text, markdown, or json",
+            other
+        ),
+    }
+    Ok(())
+}
+
+fn role_label(session: &Session, turn: &Turn) -> String {
+    if turn.role == "user" {
+        "You".to_string()
+    } else {
+        session.cli.clone()
+    }
+}
+
+/// Render a session as a boxed transcript
+fn render_text(session: &Session, range: Range<usize>) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "Session: {}", session.name)?;
+    writeln!(out, "CLI: {}", session.cli)?;
+    writeln!(out, "Topic: {}", session.topic)?;
+    writeln!(out, "Created: {}", format_timestamp(session.created_at))?;
+    writeln!(
+        out,
         "Last interaction: {}",
-        session.last_interaction.format("%Y-%m-%d %H:%M:%S UTC")
-    );
-    println!("Turns: {}", session.turns.len());
-    println!();
-    println!("┌─────────────────────────────────────────────────────────────┐");
-
-    for (idx, turn) in session.turns.iter().enumerate() {
-        let role_str = if turn.role == "user" {
-            "[You]".to_string()
-        } else {
-            format!("[{}]", session.cli)
-        };
+        format_timestamp(session.last_interaction)
+    )?;
+    if range.len() == session.turns.len() {
+        writeln!(out, "Turns: {}", session.turns.len())?;
+    } else if range.is_empty() {
+        writeln!(out, "Turns: none of {} shown", session.turns.len())?;
+    } else {
+        writeln!(
+            out,
+            "Turns: {}-{} of {}",
+            range.start + 1,
+            range.end,
+            session.turns.len()
+        )?;
+    }
+    writeln!(out)?;
 
-        println!(
-            "│ {}: {}                                    │",
-            idx + 1,
-            role_str
-        );
-        println!("│                                                             │");
+    // Box spans the terminal, capped for readability
+    let inner = terminal_width().clamp(24, MAX_BOX_WIDTH) - 4;
+    let rule = "─".repeat(inner + 2);
+    writeln!(out, "┌{}┐", rule)?;
+
+    for (idx, turn) in session.turns[range.clone()].iter().enumerate() {
+        let heading = format!("{}: [{}]", range.start + idx + 1, role_label(session, turn));
+        writeln!(out, "│ {} │", pad_to_width(&heading, inner))?;
+        writeln!(out, "│ {} │", pad_to_width("", inner))?;
 
-        // Word wrap content
+        // Word wrap content to the box width
         for line in turn.content.lines() {
-            let mut current = line.to_string();
-            while current.len() > 58 {
-                let (chunk, rest) = current.split_at(58);
-                println!("│ {} │", chunk);
-                current = rest.to_string();
+            for wrapped in wrap_to_width(line, inner) {
+                writeln!(out, "│ {} │", pad_to_width(&wrapped, inner))?;
             }
-            println!("│ {:<57} │", current);
         }
-        println!("│                                                             │");
+        writeln!(out, "│ {} │", pad_to_width("", inner))?;
     }
 
-    println!("└─────────────────────────────────────────────────────────────┘");
-    println!();
-    println!("To continue this session, run:");
-    println!(
+    writeln!(out, "└{}┘", rule)?;
+    writeln!(out)?;
+    writeln!(out, "To continue this session, run:")?;
+    writeln!(
+        out,
         "  gptengage invoke {} \"<your message>\" --session {}",
         session.cli, session.name
-    );
+    )?;
+    Ok(out)
+}
+
+/// Render a session as a Markdown document
+fn render_markdown(session: &Session, range: Range<usize>) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "# Session: {}", session.name)?;
+    writeln!(out)?;
+    writeln!(out, "- **CLI:** {}", session.cli)?;
+    writeln!(out, "- **Topic:** {}", session.topic)?;
+    writeln!(
+        out,
+        "- **Created:** {}",
+        format_timestamp(session.created_at)
+    )?;
+    writeln!(
+        out,
+        "- **Last interaction:** {}",
+        format_timestamp(session.last_interaction)
+    )?;
+    writeln!(out, "- **Turns:** {}", session.turns.len())?;
 
+    for (idx, turn) in session.turns[range.clone()].iter().enumerate() {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "## Turn {}: {}",
+            range.start + idx + 1,
+            role_label(session, turn)
+        )?;
+        writeln!(out)?;
+        writeln!(out, "{}", turn.content.trim_end())?;
+        if let Some(ref log) = turn.log {
+            writeln!(out)?;
+            writeln!(out, "<!-- raw output: {} -->", log)?;
+        }
+    }
+    Ok(out)
+}
+
+/// Render a session as pretty-printed JSON
+fn render_json(session: &Session, range: Range<usize>) -> anyhow::Result<String> {
+    let turns = session.turns[range.clone()]
+        .iter()
+        .enumerate()
+        .map(|(idx, turn)| ExportedTurn {
+            number: range.start + idx + 1,
+            role: &turn.role,
+            content: &turn.content,
+            timestamp: turn.timestamp,
+            log: turn.log.as_deref(),
+        })
+        .collect();
+    let export = ExportedSession {
+        name: &session.name,
+        cli: &session.cli,
+        topic: &session.topic,
+        created_at: session.created_at,
+        last_interaction: session.last_interaction,
+        total_turns: session.turns.len(),
+        turns,
+    };
+    Ok(serde_json::to_string_pretty(&export)?)
+}
+
+/// Regenerate the prompt for each user turn of a session, using the
+/// current history scaffolding
+pub async fn replay_session(
+    engage: &GptEngage,
+    name: String,
+    to_file: Option<String>,
+) -> anyhow::Result<()> {
+    let manager = engage.sessions()?;
+    let session = manager.load_session(&name).await?;
+    let prompts = replay_prompts(&session);
+    if prompts.is_empty() {
+        println!("Session '{}' has no prompts to replay.", name);
+        return Ok(());
+    }
+
+    match to_file {
+        Some(dir) => {
+            let dir = PathBuf::from(dir);
+            tokio::fs::create_dir_all(&dir).await?;
+            for (number, prompt) in &prompts {
+                tokio::fs::write(dir.join(format!("turn-{:03}.txt", number)), prompt).await?;
+            }
+            println!("✓ Wrote {} prompt(s) to {}", prompts.len(), dir.display());
+        }
+        None => {
+            for (number, prompt) in &prompts {
+                println!("=== Turn {} ===", number);
+                println!("{}", prompt);
+                println!();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// (turn number, prompt) for every user turn, each built with the turns
+/// before it as history
+fn replay_prompts(session: &Session) -> Vec<(usize, String)> {
+    session
+        .turns
+        .iter()
+        .enumerate()
+        .filter(|(_, turn)| turn.role == "user")
+        .map(|(i, turn)| (i + 1, prompt_with_turns(&session.turns[..i], &turn.content)))
+        .collect()
+}
+
+/// Options for `session ask`
+pub struct AskOptions {
+    /// CLI that answers (defaults to the session's CLI)
+    pub cli: Option<String>,
+    pub model: Option<String>,
+    /// Only include the last N turns as history
+    pub history_last: Option<usize>,
+    /// Append the question and answer to the session
+    pub record: bool,
+    pub timeout: u64,
+}
+
+/// Answer a question about a session's conversation
+pub async fn ask_session(
+    engage: &GptEngage,
+    name: String,
+    question: String,
+    options: AskOptions,
+) -> anyhow::Result<()> {
+    let manager = engage.sessions()?;
+    let mut session = manager.load_session(&name).await?;
+    if session.turns.is_empty() {
+        anyhow::bail!("Session '{}' has no history to ask about", name);
+    }
+
+    let cli = options.cli.unwrap_or_else(|| session.cli.clone());
+    let invoker = engage
+        .invokers
+        .get(&cli)
+        .ok_or_else(|| anyhow::anyhow!("Unknown CLI: '{}'", cli))?;
+    if !invoker.is_available().await {
+        anyhow::bail!("CLI '{}' not found in PATH", cli);
+    }
+
+    let prompt = ask_prompt(&session, &question, options.history_last);
+    eprintln!("Asking {} about session '{}'...", cli, name);
+    let (result, logs) = capture_log_paths(invoker.invoke(
+        &prompt,
+        options.timeout,
+        AccessMode::ReadOnly,
+        options.model.as_deref(),
+    ))
+    .await;
+    let answer = result?;
+    println!("{}", answer);
+
+    if options.record {
+        manager.add_turn(&mut session, "user".to_string(), question);
+        manager.add_turn(&mut session, "assistant".to_string(), answer);
+        if let Some(turn) = session.turns.last_mut() {
+            turn.log = logs.last().map(|path| path.display().to_string());
+        }
+        manager.save_session(&session).await?;
+        eprintln!("(Recorded in session '{}')", name);
+    }
     Ok(())
 }
 
+/// Prompt asking about the session's (recent) history rather than
+/// continuing it
+fn ask_prompt(session: &Session, question: &str, history_last: Option<usize>) -> String {
+    let request = format!(
+        "Answer this question about the conversation history above (topic: {}). \
+         Base the answer only on what was said there; if the conversation \
+         doesn't settle it, say so.\n\nQuestion: {}",
+        session.topic, question
+    );
+    prompt_with_turns(recent_turns(session, history_last), &request)
+}
+
 /// End a session
-pub async fn end_session(name: Option<String>, all: bool) -> anyhow::Result<()> {
-    let manager = SessionManager::new()?;
+pub async fn end_session(
+    engage: &GptEngage,
+    name: Option<String>,
+    all: bool,
+) -> anyhow::Result<()> {
+    let manager = engage.sessions()?;
 
     if all {
         let sessions = manager.list_sessions().await?;
@@ -98,6 +455,17 @@ pub async fn end_session(name: Option<String>, all: bool) -> anyhow::Result<()>
             println!("No sessions to delete.");
             return Ok(());
         }
+        let question = format!("Delete all {} sessions?", sessions.len());
+        if !confirm(&question, false)? {
+            if can_ask() {
+                println!("No sessions deleted.");
+                return Ok(());
+            }
+            anyhow::bail!(
+                "Not deleting all {} sessions without confirmation. Pass --yes to delete them",
+                sessions.len()
+            );
+        }
 
         for session in sessions {
             manager.delete_session(&session.name).await?;
@@ -110,21 +478,150 @@ pub async fn end_session(name: Option<String>, all: bool) -> anyhow::Result<()>
     } else {
         return Err(anyhow::anyhow!("Specify a session name or use --all"));
     }
+    if manager.has_trash() {
+        println!("Restore with: gptengage session undelete <name>");
+    }
+
+    Ok(())
+}
 
+/// Restore a deleted session, or list the trash without a name
+pub async fn undelete_session(engage: &GptEngage, name: Option<String>) -> anyhow::Result<()> {
+    let manager = engage.sessions()?;
+    let Some(name) = name else {
+        let trashed = manager.trashed_sessions()?;
+        if trashed.is_empty() {
+            println!("The trash is empty.");
+            return Ok(());
+        }
+        let rows: Vec<Vec<String>> = trashed
+            .iter()
+            .rev()
+            .map(|entry| {
+                vec![
+                    entry.session.name.clone(),
+                    entry.session.cli.clone(),
+                    entry.session.topic.clone(),
+                    format_when(entry.deleted_at),
+                ]
+            })
+            .collect();
+        print!(
+            "{}",
+            render_table(
+                &["Session", "CLI", "Topic", "Deleted"],
+                &rows,
+                terminal_width(),
+                &[2, 0],
+                8,
+            )
+        );
+        return Ok(());
+    };
+    let session = manager.undelete_session(&name).await?;
+    println!(
+        "✓ Session '{}' restored ({} turns).",
+        session.name,
+        session.turns.len()
+    );
     Ok(())
 }
 
-fn format_time_ago(time: chrono::DateTime<chrono::Utc>) -> String {
-    let now = chrono::Utc::now();
-    let duration = now.signed_duration_since(time);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if duration.num_seconds() < 60 {
-        format!("{}s ago", duration.num_seconds())
-    } else if duration.num_minutes() < 60 {
-        format!("{}m ago", duration.num_minutes())
-    } else if duration.num_hours() < 24 {
-        format!("{}h ago", duration.num_hours())
-    } else {
-        format!("{}d ago", duration.num_days())
+    fn session_with_turns(count: usize) -> Session {
+        let now = Utc::now();
+        Session {
+            name: "review".to_string(),
+            cli: "claude".to_string(),
+            topic: "Code review".to_string(),
+            created_at: now,
+            last_interaction: now,
+            turns: (1..=count)
+                .map(|n| Turn {
+                    role: if n % 2 == 1 { "user" } else { "assistant" }.to_string(),
+                    content: format!("message {}", n),
+                    timestamp: now,
+                    log: None,
+                    changes: None,
+                })
+                .collect(),
+            native: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_turn_range() {
+        assert_eq!(parse_turn_range("3..7", 10).unwrap(), 2..7);
+        assert_eq!(parse_turn_range("3..", 10).unwrap(), 2..10);
+        assert_eq!(parse_turn_range("..4", 10).unwrap(), 0..4);
+        assert_eq!(parse_turn_range("5", 10).unwrap(), 4..5);
+        assert_eq!(parse_turn_range("8..20", 10).unwrap(), 7..10);
+        assert!(parse_turn_range("0..2", 10).is_err());
+        assert!(parse_turn_range("7..3", 10).is_err());
+        assert!(parse_turn_range("11..", 10).is_err());
+        assert!(parse_turn_range("a..b", 10).is_err());
+    }
+
+    #[test]
+    fn test_selection_last() {
+        let selection = TurnSelection {
+            last: Some(3),
+            turns: None,
+        };
+        assert_eq!(selection.range(10).unwrap(), 7..10);
+        assert_eq!(selection.range(2).unwrap(), 0..2);
+        assert_eq!(TurnSelection::default().range(4).unwrap(), 0..4);
+    }
+
+    #[test]
+    fn test_render_markdown_keeps_turn_numbers() {
+        let session = session_with_turns(6);
+        let md = render_markdown(&session, 2..4).unwrap();
+        assert!(md.starts_with("# Session: review"));
+        assert!(md.contains("## Turn 3: You\n\nmessage 3"));
+        assert!(md.contains("## Turn 4: claude\n\nmessage 4"));
+        assert!(!md.contains("message 2"));
+        assert!(!md.contains("message 5"));
+    }
+
+    #[test]
+    fn test_render_json_selection() {
+        let session = session_with_turns(5);
+        let json = render_json(&session, 3..5).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["total_turns"], 5);
+        let turns = value["turns"].as_array().unwrap();
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0]["number"], 4);
+        assert_eq!(turns[1]["content"], "message 5");
+    }
+
+    #[test]
+    fn test_replay_prompts() {
+        let session = session_with_turns(4);
+        let prompts = replay_prompts(&session);
+        assert_eq!(prompts.len(), 2);
+        assert_eq!(prompts[0], (1, "message 1".to_string()));
+        assert_eq!(prompts[1].0, 3);
+        assert!(prompts[1]
+            .1
+            .contains("User: message 1\n\nAssistant: message 2"));
+        assert!(prompts[1]
+            .1
+            .ends_with("[CURRENT REQUEST]\nmessage 3\n[/CURRENT REQUEST]"));
+        assert!(!prompts[1].1.contains("message 4"));
+    }
+
+    #[test]
+    fn test_ask_prompt_uses_recent_history() {
+        let session = session_with_turns(4);
+        let prompt = ask_prompt(&session, "What did we decide?", Some(2));
+        assert!(!prompt.contains("message 2"));
+        assert!(prompt.contains("User: message 3"));
+        assert!(prompt.contains("topic: Code review"));
+        assert!(prompt.ends_with("Question: What did we decide?\n[/CURRENT REQUEST]"));
     }
 }
@@ -1,50 +1,66 @@
 //! Status command - Show detected CLIs and active sessions
 
+use crate::app::GptEngage;
 use crate::config::ConfigManager;
-use crate::invokers::{ClaudeInvoker, CodexInvoker, GeminiInvoker, Invoker};
-use crate::plugins::PluginManager;
-use crate::session::SessionManager;
+use crate::history::usage::UsageLog;
+use crate::invokers::detect::{detect_and_store, invoker_flags};
+use crate::utils::time::format_when;
 
 /// Show status of detected CLIs and active sessions
-pub async fn show_status() -> anyhow::Result<()> {
+pub async fn show_status(engage: &GptEngage) -> anyhow::Result<()> {
     println!("GPT Engage v{}", env!("CARGO_PKG_VERSION"));
     println!();
 
-    // Detect available CLIs
-    let claude = ClaudeInvoker::new();
-    let codex = CodexInvoker::new();
-    let gemini = GeminiInvoker::new();
+    // Detect available CLIs and record them in the config
+    let detected = detect_and_store()?;
 
     println!("Detected LLM CLIs:");
-    if claude.is_available() {
-        println!("  ✓ {} (Claude Code)", claude.name());
-    } else {
-        println!("  ✗ claude (not found in PATH)");
-    }
-    if codex.is_available() {
-        println!("  ✓ {} (Codex CLI)", codex.name());
-    } else {
-        println!("  ✗ codex (not found in PATH)");
-    }
-    if gemini.is_available() {
-        println!("  ✓ {} (Gemini CLI)", gemini.name());
-    } else {
-        println!("  ✗ gemini (not found in PATH)");
+    for (name, cli) in &detected {
+        let label = match name.as_str() {
+            "claude" => "Claude Code",
+            "codex" => "Codex CLI",
+            "gemini" => "Gemini CLI",
+            _ => name.as_str(),
+        };
+        if cli.detected {
+            println!("  ✓ {} ({})", name, label);
+            if let Some(ref version) = cli.version {
+                println!("      Version: {}", version);
+            }
+            println!("      Path: {}", cli.command);
+            let missing: Vec<&str> = invoker_flags(name)
+                .iter()
+                .filter(|f| !cli.flags.iter().any(|s| s == *f))
+                .copied()
+                .collect();
+            if !missing.is_empty() {
+                println!(
+                    "      Warning: flags not found in --help: {}",
+                    missing.join(" ")
+                );
+            }
+        } else {
+            println!("  ✗ {} (not found in PATH)", name);
+        }
     }
     println!();
 
     // Show installed plugins
-    if let Ok(plugin_manager) = PluginManager::new() {
+    if let Ok(plugin_manager) = engage.plugins() {
         let plugins = plugin_manager.list_plugins();
         if !plugins.is_empty() {
             println!("Installed Plugins:");
             for plugin in plugins {
-                let available =
-                    crate::invokers::base::command_exists(&plugin.detection.check_command);
+                let available = crate::plugins::detection::is_available(&plugin.detection).await;
                 let status = if available { "✓" } else { "✗" };
+                let disabled = if plugin_manager.is_disabled(&plugin.plugin.name) {
+                    " [disabled]"
+                } else {
+                    ""
+                };
                 println!(
-                    "  {} {} ({})",
-                    status, plugin.plugin.name, plugin.plugin.description
+                    "  {} {} ({}){}",
+                    status, plugin.plugin.name, plugin.plugin.description, disabled
                 );
             }
             println!();
@@ -52,15 +68,40 @@ pub async fn show_status() -> anyhow::Result<()> {
     }
 
     // Show configuration
-    let config = ConfigManager::new()?;
+    let resolved = engage.config()?;
+    let config = &resolved.config;
     println!("Configuration:");
     println!("  Default timeout: {}s", config.default_timeout);
     println!("  Default debate rounds: {}", config.default_debate_rounds);
     println!("  Config directory: {:?}", ConfigManager::get_config_dir()?);
     println!();
 
+    // Show today's budget use
+    let budget = &config.budget;
+    if budget.is_set() {
+        let used = UsageLog::open()?.today()?;
+        println!("Budget (today):");
+        if budget.daily_invocations > 0 {
+            println!(
+                "  Invocations: {} of {} used, {} left",
+                used.invocations,
+                budget.daily_invocations,
+                budget.daily_invocations.saturating_sub(used.invocations)
+            );
+        }
+        if budget.daily_cost > 0.0 {
+            println!(
+                "  Estimated cost: ${:.2} of ${:.2} used, ${:.2} left",
+                used.cost,
+                budget.daily_cost,
+                (budget.daily_cost - used.cost).max(0.0)
+            );
+        }
+        println!();
+    }
+
     // Show active sessions
-    let session_manager = SessionManager::new()?;
+    let session_manager = engage.sessions()?;
     let sessions = session_manager.list_sessions().await?;
 
     println!("Active Sessions: {}", sessions.len());
@@ -68,7 +109,7 @@ pub async fn show_status() -> anyhow::Result<()> {
         println!("  (None)");
     } else {
         for session in sessions {
-            let time_ago = format_time_ago(session.last_interaction);
+            let time_ago = format_when(session.last_interaction);
             println!("  • {} ({}): {}", session.name, session.cli, time_ago);
             println!("    Topic: {}", session.topic);
         }
@@ -82,18 +123,3 @@ pub async fn show_status() -> anyhow::Result<()> {
 
     Ok(())
 }
-
-fn format_time_ago(time: chrono::DateTime<chrono::Utc>) -> String {
-    let now = chrono::Utc::now();
-    let duration = now.signed_duration_since(time);
-
-    if duration.num_seconds() < 60 {
-        format!("{} seconds ago", duration.num_seconds())
-    } else if duration.num_minutes() < 60 {
-        format!("{} minutes ago", duration.num_minutes())
-    } else if duration.num_hours() < 24 {
-        format!("{} hours ago", duration.num_hours())
-    } else {
-        format!("{} days ago", duration.num_days())
-    }
-}
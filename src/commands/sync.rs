@@ -0,0 +1,487 @@
+//! Sync command - Shared templates, personas, and plugins from a git repo
+//!
+//! A team keeps its debate setups in a git repository laid out as
+//! `templates/*.toml`, `personas/*.toml`, and `plugins/*.toml`. `sync`
+//! clones it into `~/.gptengage/sync/<namespace>/` and copies the files into
+//! `~/.gptengage/<kind>/<namespace>/`, where they load as
+//! `<namespace>/<template>`, `@<namespace>/<persona>`, and plugins. The
+//! synced commit and file checksums are recorded so `sync status` can report
+//! local edits and upstream changes.
+
+use crate::audit::ProcessAudit;
+use crate::config::ConfigManager;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Asset directories copied from the repository
+pub const ASSET_KINDS: &[&str] = &["templates", "personas", "plugins"];
+
+/// What was synced into a namespace, stored as `sync/<namespace>.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncState {
+    pub remote: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub commit: String,
+    pub synced_at: DateTime<Utc>,
+    /// Synced files (`<kind>/<namespace>/<file>`) and their SHA-256
+    pub files: BTreeMap<String, String>,
+}
+
+/// How a synced file differs from what was synced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drift {
+    Modified,
+    Missing,
+    /// Present in the namespace directory but not from the repository
+    Untracked,
+}
+
+impl Drift {
+    fn label(self) -> &'static str {
+        match self {
+            Drift::Modified => "modified",
+            Drift::Missing => "missing",
+            Drift::Untracked => "untracked",
+        }
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Namespace for a remote: the repository name without `.git`
+pub fn default_namespace(remote: &str) -> String {
+    let name = remote
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default();
+    name.strip_suffix(".git").unwrap_or(name).to_string()
+}
+
+fn validate_namespace(namespace: &str) -> anyhow::Result<()> {
+    if namespace.is_empty()
+        || !namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        || namespace.starts_with('.')
+    {
+        anyhow::bail!(
+            "Invalid namespace '{}'. Use letters, digits, '-', '_' and '.', or pass --namespace",
+            namespace
+        );
+    }
+    Ok(())
+}
+
+/// Branches are passed to git as arguments, so one that looks like an
+/// option is refused
+fn validate_branch(branch: &str) -> anyhow::Result<()> {
+    if branch.is_empty() || branch.starts_with('-') {
+        anyhow::bail!("Invalid branch '{}'", branch);
+    }
+    Ok(())
+}
+
+fn sync_dir(root: &Path) -> PathBuf {
+    root.join("sync")
+}
+
+fn state_path(root: &Path, namespace: &str) -> PathBuf {
+    sync_dir(root).join(format!("{}.json", namespace))
+}
+
+fn load_state(root: &Path, namespace: &str) -> anyhow::Result<Option<SyncState>> {
+    let path = state_path(root, namespace);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Namespaces that have been synced, sorted
+fn synced_namespaces(root: &Path) -> Vec<String> {
+    let mut namespaces: Vec<String> = std::fs::read_dir(sync_dir(root))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .collect();
+    namespaces.sort();
+    namespaces
+}
+
+/// Compare a namespace's files under `root` against its sync state
+pub fn local_drift(root: &Path, namespace: &str, state: &SyncState) -> Vec<(String, Drift)> {
+    let mut drift = Vec::new();
+    for (file, checksum) in &state.files {
+        match std::fs::read(root.join(file)) {
+            Ok(data) if sha256_hex(&data) != *checksum => {
+                drift.push((file.clone(), Drift::Modified))
+            }
+            Ok(_) => {}
+            Err(_) => drift.push((file.clone(), Drift::Missing)),
+        }
+    }
+    for kind in ASSET_KINDS {
+        let Ok(entries) = std::fs::read_dir(root.join(kind).join(namespace)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file = format!(
+                "{}/{}/{}",
+                kind,
+                namespace,
+                entry.file_name().to_string_lossy()
+            );
+            if !state.files.contains_key(&file) {
+                drift.push((file, Drift::Untracked));
+            }
+        }
+    }
+    drift.sort_by(|a, b| a.0.cmp(&b.0));
+    drift
+}
+
+/// Copy a checkout's assets into `root`, replacing the previous sync of the
+/// namespace, and return the synced files with their checksums
+pub fn install_assets(
+    checkout: &Path,
+    root: &Path,
+    namespace: &str,
+    previous: Option<&SyncState>,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    for kind in ASSET_KINDS {
+        let Ok(entries) = std::fs::read_dir(checkout.join(kind)) else {
+            continue;
+        };
+        let mut sources: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        sources.sort();
+        if sources.is_empty() {
+            continue;
+        }
+
+        let target = root.join(kind).join(namespace);
+        std::fs::create_dir_all(&target)?;
+        for source in sources {
+            let Some(name) = source.file_name() else {
+                continue;
+            };
+            let data = std::fs::read(&source)?;
+            std::fs::write(target.join(name), &data)?;
+            files.insert(
+                format!("{}/{}/{}", kind, namespace, name.to_string_lossy()),
+                sha256_hex(&data),
+            );
+        }
+    }
+
+    // Drop files that were removed upstream
+    if let Some(previous) = previous {
+        for file in previous.files.keys() {
+            if !files.contains_key(file) {
+                let _ = std::fs::remove_file(root.join(file));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Run git, returning trimmed stdout
+async fn git(args: &[&str], dir: Option<&Path>) -> anyhow::Result<String> {
+    let mut command = tokio::process::Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null());
+    let audit = ProcessAudit::start(command.as_std());
+    let output = command
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {}", e))?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Clone or update the namespace's checkout, returning its commit
+async fn fetch_checkout(
+    checkout: &Path,
+    remote: &str,
+    branch: Option<&str>,
+) -> anyhow::Result<String> {
+    // `--` keeps a remote or branch starting with '-' from being read as an option
+    if checkout.join(".git").exists() {
+        git(
+            &["remote", "set-url", "origin", "--", remote],
+            Some(checkout),
+        )
+        .await?;
+        git(
+            &[
+                "fetch",
+                "--depth",
+                "1",
+                "--",
+                "origin",
+                branch.unwrap_or("HEAD"),
+            ],
+            Some(checkout),
+        )
+        .await?;
+        git(&["reset", "--hard", "FETCH_HEAD"], Some(checkout)).await?;
+    } else {
+        let checkout = checkout.to_string_lossy();
+        let mut args = vec!["clone", "--depth", "1"];
+        if let Some(branch) = branch {
+            args.extend(["--branch", branch]);
+        }
+        args.extend(["--", remote, checkout.as_ref()]);
+        git(&args, None).await?;
+    }
+    git(&["rev-parse", "HEAD"], Some(checkout)).await
+}
+
+/// Sync one namespace from its remote
+async fn sync_namespace(
+    root: &Path,
+    namespace: &str,
+    remote: &str,
+    branch: Option<String>,
+    force: bool,
+) -> anyhow::Result<()> {
+    validate_namespace(namespace)?;
+    if let Some(ref branch) = branch {
+        validate_branch(branch)?;
+    }
+    let previous = load_state(root, namespace)?;
+    if let Some(ref previous) = previous {
+        let edited: Vec<String> = local_drift(root, namespace, previous)
+            .into_iter()
+            .filter(|(_, drift)| *drift == Drift::Modified)
+            .map(|(file, _)| file)
+            .collect();
+        if !edited.is_empty() && !force {
+            anyhow::bail!(
+                "Local edits in namespace '{}' would be overwritten: {}. \
+                 Move them out of the namespace or re-run with --force.",
+                namespace,
+                edited.join(", ")
+            );
+        }
+    }
+
+    eprintln!("Syncing '{}' from {}...", namespace, remote);
+    let checkout = sync_dir(root).join(namespace);
+    std::fs::create_dir_all(sync_dir(root))?;
+    let commit = fetch_checkout(&checkout, remote, branch.as_deref()).await?;
+    let files = install_assets(&checkout, root, namespace, previous.as_ref())?;
+
+    let state = SyncState {
+        remote: remote.to_string(),
+        branch,
+        commit,
+        synced_at: Utc::now(),
+        files,
+    };
+    std::fs::write(
+        state_path(root, namespace),
+        serde_json::to_string_pretty(&state)?,
+    )?;
+
+    println!(
+        "✓ Synced '{}' at {} ({} files)",
+        namespace,
+        short_commit(&state.commit),
+        state.files.len()
+    );
+    for kind in ASSET_KINDS {
+        let prefix = format!("{}/", kind);
+        let count = state
+            .files
+            .keys()
+            .filter(|f| f.starts_with(&prefix))
+            .count();
+        if count > 0 {
+            println!("  {}: {}", kind, count);
+        }
+    }
+    if state.files.keys().any(|f| f.starts_with("plugins/")) {
+        println!("  Plugin commands still need trusting on first use (plugins.allowed_commands).");
+    }
+    Ok(())
+}
+
+fn short_commit(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+/// Sync from `remote`, or re-sync every previously synced namespace
+pub async fn run_sync(
+    remote: Option<String>,
+    namespace: Option<String>,
+    branch: Option<String>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let root = ConfigManager::get_config_dir()?;
+    if let Some(remote) = remote {
+        let namespace = namespace.unwrap_or_else(|| default_namespace(&remote));
+        return sync_namespace(&root, &namespace, &remote, branch, force).await;
+    }
+
+    let namespaces = match namespace {
+        Some(namespace) => vec![namespace],
+        None => synced_namespaces(&root),
+    };
+    if namespaces.is_empty() {
+        anyhow::bail!(
+            "Nothing synced yet. Use: gptengage sync --remote <git-url> [--namespace <name>]"
+        );
+    }
+    for namespace in namespaces {
+        let state = load_state(&root, &namespace)?
+            .ok_or_else(|| anyhow::anyhow!("Namespace '{}' has not been synced", namespace))?;
+        sync_namespace(&root, &namespace, &state.remote, state.branch, force).await?;
+    }
+    Ok(())
+}
+
+/// Show each synced namespace's local drift and whether its remote moved
+pub async fn show_sync_status(namespace: Option<String>) -> anyhow::Result<()> {
+    let root = ConfigManager::get_config_dir()?;
+    let namespaces = match namespace {
+        Some(namespace) => vec![namespace],
+        None => synced_namespaces(&root),
+    };
+    if namespaces.is_empty() {
+        println!("Nothing synced. Use: gptengage sync --remote <git-url>");
+        return Ok(());
+    }
+
+    for namespace in namespaces {
+        let state = load_state(&root, &namespace)?
+            .ok_or_else(|| anyhow::anyhow!("Namespace '{}' has not been synced", namespace))?;
+        println!("{}", namespace);
+        println!("  Remote: {}", state.remote);
+        println!(
+            "  Synced: {} at {}",
+            short_commit(&state.commit),
+            state.synced_at.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        let reference = state.branch.as_deref().unwrap_or("HEAD");
+        let listed = match validate_branch(reference) {
+            Ok(()) => git(&["ls-remote", "--", &state.remote, reference], None).await,
+            Err(e) => Err(e),
+        };
+        let upstream = match listed {
+            Ok(output) => match output.split_whitespace().next() {
+                Some(head) if head == state.commit => "up to date".to_string(),
+                Some(head) => format!("remote is at {} (run gptengage sync)", short_commit(head)),
+                None => format!("'{}' not found on remote", reference),
+            },
+            Err(e) => format!("unknown ({})", e),
+        };
+        println!("  Upstream: {}", upstream);
+
+        let drift = local_drift(&root, &namespace, &state);
+        if drift.is_empty() {
+            println!("  Local: no changes");
+        } else {
+            println!("  Local:");
+            for (file, kind) in drift {
+                println!("    {:<10} {}", kind.label(), file);
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_namespace() {
+        assert_eq!(
+            default_namespace("git@github.com:org/gptengage-assets.git"),
+            "gptengage-assets"
+        );
+        assert_eq!(
+            default_namespace("https://example.com/team/assets/"),
+            "assets"
+        );
+        assert!(validate_namespace("acme").is_ok());
+        assert!(validate_namespace("../x").is_err());
+        assert!(validate_branch("release/1.0").is_ok());
+        assert!(validate_branch("--upload-pack=touch x").is_err());
+    }
+
+    #[test]
+    fn test_install_assets_and_drift() {
+        let checkout = tempfile::tempdir().unwrap();
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(checkout.path().join("templates")).unwrap();
+        std::fs::create_dir_all(checkout.path().join("personas")).unwrap();
+        std::fs::write(checkout.path().join("templates/review.toml"), "a").unwrap();
+        std::fs::write(checkout.path().join("personas/sre.toml"), "b").unwrap();
+        std::fs::write(checkout.path().join("personas/README.md"), "c").unwrap();
+
+        let files = install_assets(checkout.path(), root.path(), "acme", None).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec!["personas/acme/sre.toml", "templates/acme/review.toml"]
+        );
+        let state = SyncState {
+            remote: "r".to_string(),
+            branch: None,
+            commit: "abc".to_string(),
+            synced_at: Utc::now(),
+            files,
+        };
+        assert!(local_drift(root.path(), "acme", &state).is_empty());
+
+        std::fs::write(root.path().join("templates/acme/review.toml"), "edited").unwrap();
+        std::fs::remove_file(root.path().join("personas/acme/sre.toml")).unwrap();
+        std::fs::write(root.path().join("personas/acme/mine.toml"), "d").unwrap();
+        assert_eq!(
+            local_drift(root.path(), "acme", &state),
+            vec![
+                ("personas/acme/mine.toml".to_string(), Drift::Untracked),
+                ("personas/acme/sre.toml".to_string(), Drift::Missing),
+                ("templates/acme/review.toml".to_string(), Drift::Modified),
+            ]
+        );
+
+        // A file removed upstream is removed locally on the next sync
+        std::fs::remove_file(checkout.path().join("templates/review.toml")).unwrap();
+        let files = install_assets(checkout.path(), root.path(), "acme", Some(&state)).unwrap();
+        assert!(!files.contains_key("templates/acme/review.toml"));
+        assert!(!root.path().join("templates/acme/review.toml").exists());
+    }
+}
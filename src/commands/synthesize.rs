@@ -0,0 +1,595 @@
+//! Synthesize command - One synthesis across several debates
+//!
+//! Reads stored JSON transcripts (such as a `debate batch --output json`
+//! directory) and asks a CLI what they show together: themes that recur,
+//! recommendations that conflict, and an overall recommendation. Each
+//! debate is represented by its own synthesis when it has one and by its
+//! final round otherwise, so the prompt stays small however long the
+//! debates ran.
+//!
+//! With `--from`, a single transcript from anywhere - meeting notes, a
+//! discussion pasted into a markdown file, or earlier gptengage output -
+//! gets the same structured synthesis a debate run with `--synthesize`
+//! gets. JSON debate transcripts are synthesized exactly as `debate`
+//! would; anything else is sent as written.
+
+use crate::app::GptEngage;
+use crate::commands::attribution::debate_id;
+use crate::commands::bundle::parse_transcript;
+use crate::history::topic_summary;
+use crate::invokers::AccessMode;
+use crate::orchestrator::{DebateOrchestrator, DebateResult, Synthesis, SYNTHESIS_FORMAT};
+use crate::utils::json::find_json;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Characters kept from each final-round response of a debate without
+/// a synthesis
+const MAX_RESPONSE_CHARS: usize = 2_000;
+
+/// Options for `synthesize`
+pub struct SynthesizeOptions {
+    /// Transcript files, or directories of them
+    pub debates: Vec<String>,
+    /// A single transcript in any format, synthesized on its own
+    pub from: Option<String>,
+    pub synthesizer: String,
+    pub timeout: u64,
+    /// Output format: text, markdown, json
+    pub output: String,
+}
+
+/// A debate covered by a cross-debate synthesis
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DebateSource {
+    /// Debate ID, derived from the topic and responses
+    pub id: String,
+    /// First line of the topic
+    pub topic: String,
+    /// Transcript file it was read from
+    pub file: String,
+}
+
+/// Synthesis across several debates
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrossSynthesis {
+    /// Debates covered, numbered from 1 in this order in the other fields
+    #[serde(default)]
+    pub debates: Vec<DebateSource>,
+    /// Brief summary of what the debates show together
+    pub summary: String,
+    /// Themes that recur across debates
+    #[serde(default)]
+    pub common_themes: Vec<String>,
+    /// Recommendations that conflict between debates
+    #[serde(default)]
+    pub conflicting_recommendations: Vec<String>,
+    /// Insights that only emerge from comparing the debates
+    #[serde(default)]
+    pub key_insights: Vec<String>,
+    /// Overall recommendation (if applicable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<String>,
+}
+
+/// JSON transcripts named by `paths`: each file itself, or the .json
+/// files directly inside a directory
+fn debate_files(paths: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths.iter().map(Path::new) {
+        if !path.is_dir() {
+            files.push(path.to_path_buf());
+            continue;
+        }
+        let entries = std::fs::read_dir(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|file| file.is_file() && file.extension().is_some_and(|e| e == "json"))
+            .collect();
+        if found.is_empty() {
+            anyhow::bail!("No JSON transcripts in {}", path.display());
+        }
+        found.sort();
+        files.extend(found);
+    }
+    Ok(files)
+}
+
+/// Read the transcripts named by `paths`, skipping repeats of a debate
+fn load_debates(paths: &[String]) -> anyhow::Result<Vec<(DebateSource, DebateResult)>> {
+    let mut debates: Vec<(DebateSource, DebateResult)> = Vec::new();
+    for file in debate_files(paths)? {
+        let content = std::fs::read_to_string(&file)
+            .map_err(|e| anyhow::anyhow!("Failed to read transcript {}: {}", file.display(), e))?;
+        let result = parse_transcript(&content).map_err(|e| {
+            anyhow::anyhow!(
+                "{} is not a JSON debate transcript (save one with --output json): {}",
+                file.display(),
+                e
+            )
+        })?;
+        let id = debate_id(&result);
+        if debates.iter().any(|(source, _)| source.id == id) {
+            eprintln!(
+                "Skipping {}: same debate as an earlier file",
+                file.display()
+            );
+            continue;
+        }
+        let source = DebateSource {
+            id,
+            topic: topic_summary(&result.topic),
+            file: file.display().to_string(),
+        };
+        debates.push((source, result));
+    }
+    if debates.len() < 2 {
+        anyhow::bail!("A cross-debate synthesis needs at least two different debates");
+    }
+    Ok(debates)
+}
+
+/// `text` cut to at most `max_chars`, noting the cut
+fn clip(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{} [...]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// One debate's outcome for the prompt: its synthesis, or its final round
+fn debate_outcome(result: &DebateResult) -> String {
+    let mut out = String::new();
+    match &result.synthesis {
+        Some(synthesis) => {
+            let _ = writeln!(out, "Summary: {}", synthesis.summary);
+            for (label, points) in [
+                ("Consensus", &synthesis.consensus_points),
+                ("Disagreements", &synthesis.disagreement_points),
+                ("Key insights", &synthesis.key_insights),
+            ] {
+                if !points.is_empty() {
+                    let _ = writeln!(out, "{}:", label);
+                    for point in points {
+                        let _ = writeln!(out, "- {}", point);
+                    }
+                }
+            }
+            if let Some(recommendation) = &synthesis.recommendation {
+                let _ = writeln!(out, "Recommendation: {}", recommendation);
+            }
+        }
+        None => {
+            let _ = writeln!(
+                out,
+                "Final round ({} round(s) in all):",
+                result.rounds.len()
+            );
+            for response in result.rounds.last().into_iter().flatten() {
+                let _ = writeln!(
+                    out,
+                    "{}:\n{}\n",
+                    response.display_name(),
+                    clip(&response.response, MAX_RESPONSE_CHARS)
+                );
+            }
+        }
+    }
+    out
+}
+
+/// Prompt asking for a synthesis across `debates`
+pub fn cross_synthesis_prompt(debates: &[(DebateSource, DebateResult)]) -> String {
+    let mut outcomes = String::new();
+    for (i, (source, result)) in debates.iter().enumerate() {
+        let _ = writeln!(outcomes, "DEBATE {}: {}", i + 1, source.topic);
+        let _ = writeln!(outcomes, "{}", debate_outcome(result));
+    }
+    format!(
+        r#"[CROSS-DEBATE SYNTHESIS REQUEST]
+You are comparing the outcomes of {} related debates.
+
+{}
+Generate a cross-debate synthesis with:
+1. A 2-3 sentence summary of what the debates show together
+2. Themes that recur across debates
+3. Recommendations that conflict between debates, naming them (e.g. "Debate 1 favors X, but Debate 3 favors Y")
+4. Key insights that only emerge from comparing the debates
+5. An overall recommendation (if applicable)
+
+Respond with JSON in this exact format:
+{{
+  "summary": "...",
+  "common_themes": ["...", "..."],
+  "conflicting_recommendations": ["...", "..."],
+  "key_insights": ["...", "..."],
+  "recommendation": "..." or null
+}}
+[/CROSS-DEBATE SYNTHESIS REQUEST]"#,
+        debates.len(),
+        outcomes
+    )
+}
+
+/// Parse the synthesizer's answer, falling back to the whole response as
+/// the summary when it holds no usable JSON
+fn parse_cross_synthesis(response: &str) -> CrossSynthesis {
+    let json = find_json(response, "synthesis", |value| {
+        value
+            .get("summary")
+            .is_some_and(serde_json::Value::is_string)
+    });
+    if let Some(synthesis) = json
+        .ok()
+        .and_then(|json| serde_json::from_str::<CrossSynthesis>(json).ok())
+    {
+        return synthesis;
+    }
+    CrossSynthesis {
+        debates: Vec::new(),
+        summary: response.trim().to_string(),
+        common_themes: Vec::new(),
+        conflicting_recommendations: Vec::new(),
+        key_insights: Vec::new(),
+        recommendation: None,
+    }
+}
+
+/// Topic of a free-form transcript: its first markdown heading, as in
+/// `debate --output markdown`
+fn transcript_topic(text: &str) -> Option<String> {
+    text.lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|heading| heading.trim().to_string())
+        .filter(|heading| !heading.is_empty())
+}
+
+/// Prompt asking for the debate synthesis of a free-form transcript
+pub fn transcript_synthesis_prompt(text: &str) -> String {
+    let topic = match transcript_topic(text) {
+        Some(topic) => format!("TOPIC: {}\n\n", topic),
+        None => String::new(),
+    };
+    format!(
+        r#"[SYNTHESIS REQUEST]
+You are synthesizing a discussion between several participants. The
+transcript may be meeting notes, a chat log, or a debate; work out who said
+what from it.
+
+{}DISCUSSION TRANSCRIPT:
+{}
+
+{}
+[/SYNTHESIS REQUEST]"#,
+        topic,
+        text.trim(),
+        SYNTHESIS_FORMAT
+    )
+}
+
+/// Synthesize the transcript in `file`
+async fn synthesize_transcript(
+    engage: &GptEngage,
+    file: &str,
+    options: &SynthesizeOptions,
+) -> anyhow::Result<Synthesis> {
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read transcript {}: {}", file, e))?;
+    let orchestrator = engage.orchestrator();
+    if let Ok(result) = parse_transcript(&content) {
+        return orchestrator
+            .generate_synthesis(
+                &result,
+                &options.synthesizer,
+                options.timeout,
+                AccessMode::ReadOnly,
+            )
+            .await;
+    }
+    if content.trim().is_empty() {
+        anyhow::bail!("Transcript {} is empty", file);
+    }
+
+    let invoker = orchestrator.synthesizer(&options.synthesizer).await?;
+    eprintln!("Generating synthesis with {}...", options.synthesizer);
+    let response = invoker
+        .invoke(
+            &transcript_synthesis_prompt(&content),
+            options.timeout,
+            AccessMode::ReadOnly,
+            None,
+        )
+        .await?;
+    DebateOrchestrator::parse_synthesis_response(&response)
+}
+
+/// What the renderers show of a synthesis
+struct View<'a> {
+    title: &'static str,
+    /// Debates covered (shown when not empty)
+    debates: &'a [DebateSource],
+    summary: &'a str,
+    /// Titled lists, in display order
+    sections: Vec<(&'static str, &'a Vec<String>)>,
+    recommendation: Option<&'a str>,
+}
+
+impl CrossSynthesis {
+    fn view(&self) -> View<'_> {
+        View {
+            title: "Cross-Debate Synthesis",
+            debates: &self.debates,
+            summary: &self.summary,
+            sections: vec![
+                ("Common Themes", &self.common_themes),
+                (
+                    "Conflicting Recommendations",
+                    &self.conflicting_recommendations,
+                ),
+                ("Key Insights", &self.key_insights),
+            ],
+            recommendation: self.recommendation.as_deref(),
+        }
+    }
+}
+
+fn synthesis_view(synthesis: &Synthesis) -> View<'_> {
+    View {
+        title: "Synthesis",
+        debates: &[],
+        summary: &synthesis.summary,
+        sections: vec![
+            ("Consensus", &synthesis.consensus_points),
+            ("Disagreements", &synthesis.disagreement_points),
+            ("Key Insights", &synthesis.key_insights),
+        ],
+        recommendation: synthesis.recommendation.as_deref(),
+    }
+}
+
+fn render_text(view: &View) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "{}", view.title.to_uppercase())?;
+    writeln!(out, "────────────────────────────────────────")?;
+    writeln!(out)?;
+    if !view.debates.is_empty() {
+        writeln!(out, "Debates:")?;
+        for (i, source) in view.debates.iter().enumerate() {
+            writeln!(out, "  {}. [{}] {}", i + 1, source.id, source.topic)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "Summary:")?;
+    writeln!(out, "  {}", view.summary)?;
+    writeln!(out)?;
+    for &(title, points) in &view.sections {
+        if !points.is_empty() {
+            writeln!(out, "{}:", title)?;
+            for point in points {
+                writeln!(out, "  • {}", point)?;
+            }
+            writeln!(out)?;
+        }
+    }
+    if let Some(recommendation) = view.recommendation {
+        writeln!(out, "Recommendation:")?;
+        writeln!(out, "  {}", recommendation)?;
+        writeln!(out)?;
+    }
+    Ok(out)
+}
+
+fn render_markdown(view: &View) -> anyhow::Result<String> {
+    let mut out = String::new();
+    writeln!(out, "# {}", view.title)?;
+    writeln!(out)?;
+    if !view.debates.is_empty() {
+        writeln!(out, "## Debates")?;
+        writeln!(out)?;
+        for (i, source) in view.debates.iter().enumerate() {
+            writeln!(
+                out,
+                "{}. {} (`{}`, [transcript]({}))",
+                i + 1,
+                source.topic,
+                source.id,
+                source.file
+            )?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "## Summary")?;
+    writeln!(out)?;
+    writeln!(out, "{}", view.summary)?;
+    writeln!(out)?;
+    for &(title, points) in &view.sections {
+        if !points.is_empty() {
+            writeln!(out, "## {}", title)?;
+            writeln!(out)?;
+            for point in points {
+                writeln!(out, "- {}", point)?;
+            }
+            writeln!(out)?;
+        }
+    }
+    if let Some(recommendation) = view.recommendation {
+        writeln!(out, "## Recommendation")?;
+        writeln!(out)?;
+        writeln!(out, "{}", recommendation)?;
+    }
+    Ok(out)
+}
+
+/// Synthesize several stored debates into one comparison, or one
+/// transcript of any kind on its own
+pub async fn run_synthesize(engage: &GptEngage, options: SynthesizeOptions) -> anyhow::Result<()> {
+    if !["text", "markdown", "json"].contains(&options.output.as_str()) {
+        anyhow::bail!(
+            "Invalid output format '{}'. Use text, markdown, or json",
+            options.output
+        );
+    }
+    if let Some(file) = &options.from {
+        let synthesis = synthesize_transcript(engage, file, &options).await?;
+        match options.output.as_str() {
+            "json" => println!("{}", serde_json::to_string_pretty(&synthesis)?),
+            "markdown" => print!("{}", render_markdown(&synthesis_view(&synthesis))?),
+            _ => print!("{}", render_text(&synthesis_view(&synthesis))?),
+        }
+        return Ok(());
+    }
+
+    let debates = load_debates(&options.debates)?;
+    let invoker = engage
+        .orchestrator()
+        .synthesizer(&options.synthesizer)
+        .await?;
+
+    eprintln!(
+        "Synthesizing {} debates with {}...",
+        debates.len(),
+        options.synthesizer
+    );
+    let prompt = cross_synthesis_prompt(&debates);
+    let response = invoker
+        .invoke(&prompt, options.timeout, AccessMode::ReadOnly, None)
+        .await?;
+    let mut synthesis = parse_cross_synthesis(&response);
+    synthesis.debates = debates.into_iter().map(|(source, _)| source).collect();
+
+    match options.output.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&synthesis)?),
+        "markdown" => print!("{}", render_markdown(&synthesis.view())?),
+        _ => print!("{}", render_text(&synthesis.view())?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::{RoundResponse, Synthesis};
+
+    fn debate(topic: &str, response: &str, recommendation: Option<&str>) -> DebateResult {
+        let mut result = DebateResult::from_json(&format!(
+            r#"{{"topic": {}, "rounds": [[{{"cli": "claude", "persona": null, "response": {}}}]]}}"#,
+            serde_json::json!(topic),
+            serde_json::json!(response)
+        ))
+        .unwrap();
+        result.synthesis = recommendation.map(|recommendation| Synthesis {
+            summary: format!("{} settled", topic),
+            consensus_points: vec!["Measure first".to_string()],
+            disagreement_points: vec![],
+            key_insights: vec![],
+            recommendation: Some(recommendation.to_string()),
+            position_changes: vec![],
+            unverified_citations: vec![],
+        });
+        result
+    }
+
+    #[test]
+    fn test_load_debates_reads_files_and_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, result: &DebateResult| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, serde_json::to_string(result).unwrap()).unwrap();
+            path.display().to_string()
+        };
+        let first = write("01-cache.json", &debate("Cache?", "Yes", Some("Add Redis")));
+        write("02-shard.json", &debate("Shard?", "No", None));
+        std::fs::write(dir.path().join("index.md"), "# Debate Batch").unwrap();
+
+        let dir_path = dir.path().display().to_string();
+        let debates = load_debates(std::slice::from_ref(&dir_path)).unwrap();
+        let topics: Vec<&str> = debates.iter().map(|(s, _)| s.topic.as_str()).collect();
+        assert_eq!(topics, vec!["Cache?", "Shard?"]);
+
+        // The same debate given twice counts once
+        let err = load_debates(&[first.clone(), first]).unwrap_err();
+        assert!(err.to_string().contains("at least two"), "{}", err);
+        assert!(load_debates(&[dir_path.clone(), "missing.json".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_prompt_uses_synthesis_or_final_round() {
+        let mut long = debate("Shard?", &"x".repeat(MAX_RESPONSE_CHARS + 50), None);
+        long.rounds.insert(
+            0,
+            vec![RoundResponse {
+                cli: "codex".to_string(),
+                persona: None,
+                response: "Opening statement".to_string(),
+                log: None,
+            }],
+        );
+        let debates: Vec<(DebateSource, DebateResult)> =
+            [debate("Cache?", "Yes", Some("Add Redis")), long]
+                .into_iter()
+                .map(|result| {
+                    let source = DebateSource {
+                        id: debate_id(&result),
+                        topic: result.topic.clone(),
+                        file: String::new(),
+                    };
+                    (source, result)
+                })
+                .collect();
+
+        let prompt = cross_synthesis_prompt(&debates);
+        assert!(prompt.contains("DEBATE 1: Cache?\nSummary: Cache? settled"));
+        assert!(prompt.contains("Recommendation: Add Redis"));
+        assert!(prompt.contains("DEBATE 2: Shard?\nFinal round (2 round(s) in all):"));
+        assert!(!prompt.contains("Opening statement"));
+        assert!(prompt.contains(" [...]"));
+    }
+
+    #[test]
+    fn test_parse_cross_synthesis() {
+        let synthesis = parse_cross_synthesis(
+            "Here you go:\n{\"summary\": \"Both favor caching\", \
+             \"conflicting_recommendations\": [\"Debate 1 adds Redis, Debate 2 avoids new services\"]}",
+        );
+        assert_eq!(synthesis.summary, "Both favor caching");
+        assert_eq!(synthesis.conflicting_recommendations.len(), 1);
+        assert!(synthesis.recommendation.is_none());
+
+        let fallback = parse_cross_synthesis("  No JSON at all  ");
+        assert_eq!(fallback.summary, "No JSON at all");
+    }
+
+    #[test]
+    fn test_transcript_synthesis_prompt() {
+        let notes = "Notes from Tuesday\n\n# Should we adopt Rust?\n\nAna: yes\nBo: not yet\n";
+        assert_eq!(
+            transcript_topic(notes).as_deref(),
+            Some("Should we adopt Rust?")
+        );
+        let prompt = transcript_synthesis_prompt(notes);
+        assert!(prompt.contains("TOPIC: Should we adopt Rust?\n\nDISCUSSION TRANSCRIPT:\nNotes"));
+        assert!(prompt.contains("Bo: not yet\n\nGenerate a structured synthesis"));
+
+        let prompt = transcript_synthesis_prompt("Ana: yes\nBo: no");
+        assert!(!prompt.contains("TOPIC:"));
+
+        let synthesis = Synthesis {
+            summary: "Adopt slowly".to_string(),
+            consensus_points: vec!["Start with tools".to_string()],
+            disagreement_points: vec![],
+            key_insights: vec![],
+            recommendation: None,
+            position_changes: vec![],
+            unverified_citations: vec![],
+        };
+        let markdown = render_markdown(&synthesis_view(&synthesis)).unwrap();
+        assert!(markdown.starts_with("# Synthesis\n\n## Summary\n\nAdopt slowly"));
+        assert!(markdown.contains("## Consensus\n\n- Start with tools"));
+        assert!(!markdown.contains("## Disagreements"));
+    }
+}
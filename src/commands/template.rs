@@ -1,10 +1,19 @@
 //! Template command - Manage debate templates
 
-use crate::templates::TemplateManager;
+use crate::app::GptEngage;
+use crate::orchestrator::{DebateOrchestrator, Participant, PromptContext};
+use crate::personas::{persona_reference, PersonaRegistry};
+use crate::templates::DebateTemplate;
+use crate::utils::prompt_preview::PromptPreview;
+use crate::utils::tokens::counter_for;
+
+/// Topic used by `template test` when none is given
+pub const SAMPLE_TOPIC: &str =
+    "Should we move our nightly batch jobs to an event-driven pipeline this quarter?";
 
 /// List all available templates
-pub async fn list_templates() -> anyhow::Result<()> {
-    let manager = TemplateManager::new()?;
+pub async fn list_templates(engage: &GptEngage) -> anyhow::Result<()> {
+    let manager = engage.templates()?;
     let templates = manager.list_templates();
 
     if templates.is_empty() {
@@ -16,10 +25,10 @@ pub async fn list_templates() -> anyhow::Result<()> {
     println!();
 
     for template in templates {
-        let source = if template.is_builtin {
-            "built-in"
-        } else {
-            "user"
+        let source = match (&template.plugin, template.is_builtin) {
+            (Some(plugin), _) => format!("plugin: {}", plugin),
+            (None, true) => "built-in".to_string(),
+            (None, false) => "user".to_string(),
         };
 
         println!("  {} ({})", template.name, source);
@@ -37,8 +46,8 @@ pub async fn list_templates() -> anyhow::Result<()> {
 }
 
 /// Show template details
-pub async fn show_template(name: String) -> anyhow::Result<()> {
-    let manager = TemplateManager::new()?;
+pub async fn show_template(engage: &GptEngage, name: String) -> anyhow::Result<()> {
+    let manager = engage.templates()?;
 
     match manager.get_template(&name) {
         Some(template) => {
@@ -51,7 +60,12 @@ pub async fn show_template(name: String) -> anyhow::Result<()> {
             for (i, p) in template.participants.iter().enumerate() {
                 println!();
                 println!("  {}. {} ({})", i + 1, p.persona, p.cli);
-                println!("     Instructions: {}", p.instructions);
+                if persona_reference(&p.persona).is_some() {
+                    println!("     From the persona library");
+                }
+                if !p.instructions.is_empty() {
+                    println!("     Instructions: {}", p.instructions);
+                }
                 if !p.expertise.is_empty() {
                     println!("     Expertise: {}", p.expertise.join(", "));
                 }
@@ -79,3 +93,198 @@ pub async fn show_template(name: String) -> anyhow::Result<()> {
         )),
     }
 }
+
+/// What `template test` found for one template participant
+#[derive(Debug)]
+pub struct ParticipantCheck {
+    /// The participant as written in the template, e.g. "claude (@sre)"
+    pub label: String,
+    /// The resolved participant, when its persona resolved
+    pub participant: Option<Participant>,
+    /// Problems that would break or silently drop this participant
+    pub problems: Vec<String>,
+    /// Things worth knowing that don't fail the test
+    pub warnings: Vec<String>,
+}
+
+/// Check that every participant's CLI, persona, and model resolve
+pub async fn check_template(
+    engage: &GptEngage,
+    template: &DebateTemplate,
+) -> Vec<ParticipantCheck> {
+    let registry = if template.uses_persona_library() {
+        PersonaRegistry::new().map_err(|e| e.to_string())
+    } else {
+        Err(String::new())
+    };
+    let plugins = engage.plugins().ok();
+
+    let mut checks = Vec::new();
+    for p in &template.participants {
+        let mut problems = Vec::new();
+        let mut warnings = Vec::new();
+
+        if !engage.invokers.is_valid(&p.cli) {
+            problems.push(format!(
+                "Unknown CLI '{}': not a built-in CLI or an installed plugin",
+                p.cli
+            ));
+        } else if !match engage.invokers.get(&p.cli) {
+            Some(invoker) => invoker.is_available().await,
+            None => false,
+        } {
+            warnings.push(format!(
+                "CLI '{}' is not installed here; the debate would skip it",
+                p.cli
+            ));
+        }
+
+        let participant = match (persona_reference(&p.persona), &registry) {
+            (Some(_), Err(e)) => {
+                problems.push(format!("Failed to load the persona library: {}", e));
+                None
+            }
+            _ => match p.to_participant(registry.as_ref().ok()) {
+                Ok(participant) => Some(participant),
+                Err(e) => {
+                    problems.push(e.to_string());
+                    None
+                }
+            },
+        };
+
+        // Plugins only take a model when they declare how to pass one
+        if let Some(model) = participant.as_ref().and_then(|p| p.model.as_ref()) {
+            let plugin = plugins.as_ref().and_then(|m| m.get_plugin(&p.cli));
+            if plugin.is_some_and(|plugin| plugin.invoke.model_arg.is_none()) {
+                problems.push(format!(
+                    "Model '{}' set, but plugin '{}' has no model_arg",
+                    model, p.cli
+                ));
+            }
+        }
+
+        checks.push(ParticipantCheck {
+            label: format!("{} ({})", p.cli, p.persona),
+            participant,
+            problems,
+            warnings,
+        });
+    }
+    checks
+}
+
+/// Render a template against a sample topic, show each participant's
+/// round-1 prompt, and fail if any participant doesn't resolve
+pub async fn test_template(
+    engage: &GptEngage,
+    name: String,
+    topic: Option<String>,
+) -> anyhow::Result<()> {
+    let manager = engage.templates()?;
+    let template = manager.get_template(&name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Template '{}' not found. Use 'gptengage template list' to see available templates.",
+            name
+        )
+    })?;
+    let topic = topic.unwrap_or_else(|| SAMPLE_TOPIC.to_string());
+    let full_topic = template.apply_context(&topic);
+    let context = PromptContext::default();
+    let base_context =
+        DebateOrchestrator::round_context(&full_topic, 1, &[], context.history_rounds);
+
+    let checks = check_template(engage, template).await;
+    for check in &checks {
+        let Some(participant) = &check.participant else {
+            continue;
+        };
+        let prompt = context.participant_prompt(participant, &base_context);
+        let preview = PromptPreview::new(
+            format!("{} (round 1)", participant.display_name()),
+            prompt.as_str(),
+        )
+        .counted_with(counter_for(&participant.cli, participant.model.as_deref()).as_ref())
+        .part(
+            "persona scaffolding",
+            prompt.len().saturating_sub(base_context.len()),
+        )
+        .part("template context", full_topic.len() - topic.len())
+        .part("topic", topic.len())
+        .render()?;
+        print!("{}", preview);
+    }
+
+    println!("Template: {}", template.name);
+    println!(
+        "Rounds: {}, Participants: {}",
+        template.default_rounds,
+        checks.len()
+    );
+    println!();
+    let mut failed = 0;
+    for (i, check) in checks.iter().enumerate() {
+        let status = if check.problems.is_empty() {
+            "ok"
+        } else {
+            failed += 1;
+            "FAILED"
+        };
+        println!("  {}. {} ... {}", i + 1, check.label, status);
+        for problem in &check.problems {
+            println!("     error: {}", problem);
+        }
+        for warning in &check.warnings {
+            println!("     warning: {}", warning);
+        }
+    }
+    println!();
+
+    if failed > 0 {
+        anyhow::bail!(
+            "Template '{}' has {} participant(s) that would fail",
+            template.name,
+            failed
+        );
+    }
+    println!("Template '{}' is ready to use.", template.name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::TemplateParticipant;
+
+    fn participant(cli: &str, persona: &str) -> TemplateParticipant {
+        TemplateParticipant {
+            cli: cli.to_string(),
+            persona: persona.to_string(),
+            instructions: "Review the proposal carefully".to_string(),
+            expertise: vec![],
+            output_filters: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_template_reports_unresolved_participants() {
+        let template = DebateTemplate {
+            name: "broken".to_string(),
+            description: "Broken".to_string(),
+            default_rounds: 1,
+            participants: vec![
+                participant("claude", "Architect"),
+                participant("no-such-cli", "Reviewer"),
+                participant("codex", "@no-such-persona-xyz"),
+            ],
+            context: None,
+        };
+
+        let checks = check_template(&GptEngage::default(), &template).await;
+        assert!(checks[0].problems.is_empty());
+        assert!(checks[0].participant.is_some());
+        assert!(checks[1].problems[0].contains("Unknown CLI 'no-such-cli'"));
+        assert!(checks[2].participant.is_none());
+        assert!(checks[2].problems[0].contains("@no-such-persona-xyz"));
+    }
+}
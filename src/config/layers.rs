@@ -0,0 +1,319 @@
+//! Layered config resolution
+//!
+//! Effective values are resolved in order of increasing precedence:
+//! built-in defaults, the user config file, the active profile
+//! (`profiles.<name>` selected by `GPTENGAGE_PROFILE`), a project file
+//! (`.gptengage.json` in the current directory or an ancestor), and
+//! `GPTENGAGE_<KEY>` environment variables.
+
+use super::ConfigManager;
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Keys readable and writable with `config get/set/unset`
+pub const KNOWN_KEYS: &[&str] = &[
+    "default_timeout",
+    "default_debate_rounds",
+    "audit_log",
+    "debate_history",
+    "latency_scheduling",
+    "time_format",
+    "max_output_bytes",
+    "invocation_logs",
+    "max_continuations",
+    "circuit_breaker_threshold",
+    "session_store",
+    "forge",
+    "trash_retention_days",
+    "plugins.allowed_commands",
+    "plugins.middleware",
+    "plugins.network_exempt",
+    "plugins.signing_keys",
+    "budget.daily_invocations",
+    "budget.daily_cost",
+    "budget.invocation_costs",
+    "history.privacy",
+    "dialects",
+    "context_windows",
+    "response_formats",
+    "fallbacks",
+];
+
+/// Keys a project file may override. Security settings are excluded so a
+/// checked-out repository can't allowlist commands or disable auditing.
+const PROJECT_KEYS: &[&str] = &[
+    "default_timeout",
+    "default_debate_rounds",
+    "time_format",
+    "forge",
+    "response_formats",
+];
+
+/// Per-project config file name
+pub const PROJECT_CONFIG_FILE: &str = ".gptengage.json";
+
+/// Environment variable selecting the active profile
+pub const PROFILE_ENV: &str = "GPTENGAGE_PROFILE";
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    File,
+    Profile,
+    Project,
+    Env,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ConfigSource::Default => "default",
+            ConfigSource::File => "file",
+            ConfigSource::Profile => "profile",
+            ConfigSource::Project => "project",
+            ConfigSource::Env => "env",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Effective configuration with the source of every known key
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub config: ConfigManager,
+    sources: HashMap<&'static str, ConfigSource>,
+    /// Active profile name, if any
+    pub profile: Option<String>,
+    /// Project config file applied, if any
+    pub project_file: Option<PathBuf>,
+}
+
+impl ResolvedConfig {
+    /// Source of a known key's effective value
+    pub fn source(&self, key: &str) -> ConfigSource {
+        self.sources
+            .get(key)
+            .copied()
+            .unwrap_or(ConfigSource::Default)
+    }
+
+    /// Every known key with its effective value and source
+    pub fn entries(&self) -> Vec<(&'static str, String, ConfigSource)> {
+        KNOWN_KEYS
+            .iter()
+            .map(|key| {
+                (
+                    *key,
+                    self.config.get(key).unwrap_or_default(),
+                    self.source(key),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Environment variable overriding a key (e.g. GPTENGAGE_DEFAULT_TIMEOUT)
+pub fn env_var_name(key: &str) -> String {
+    format!("GPTENGAGE_{}", key.replace('.', "_").to_uppercase())
+}
+
+/// String form of a JSON override value, as accepted by `ConfigManager::apply`
+pub fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(value_to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        Value::Object(entries) => entries
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value_to_string(value)))
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+/// Find the nearest project config file from `dir` upwards
+pub fn find_project_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(PROJECT_CONFIG_FILE))
+        .find(|p| p.is_file())
+}
+
+impl ConfigManager {
+    /// Load the effective configuration from all layers
+    pub fn resolve() -> Result<ResolvedConfig> {
+        let file = Self::load()?;
+        let profile = std::env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty());
+
+        let project_file = std::env::current_dir()
+            .ok()
+            .and_then(|dir| find_project_file(&dir));
+        let project = match &project_file {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let values: Map<String, Value> = serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                Some(values)
+            }
+            None => None,
+        };
+
+        let mut resolved =
+            Self::resolve_layers(file, profile.as_deref(), project.as_ref(), |name| {
+                std::env::var(name).ok()
+            })?;
+        resolved.project_file = project_file;
+        Ok(resolved)
+    }
+
+    /// Apply profile, project, and environment layers over a file config
+    pub fn resolve_layers(
+        file: ConfigManager,
+        profile: Option<&str>,
+        project: Option<&Map<String, Value>>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<ResolvedConfig> {
+        let defaults = ConfigManager::default();
+        let mut sources = HashMap::new();
+        for key in KNOWN_KEYS {
+            let source = if file.get(key) == defaults.get(key) {
+                ConfigSource::Default
+            } else {
+                ConfigSource::File
+            };
+            sources.insert(*key, source);
+        }
+
+        let mut config = file;
+
+        if let Some(name) = profile {
+            let overrides = config
+                .profiles
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found in config", name))?;
+            for (key, value) in &overrides {
+                let key = known_key(key).ok_or_else(|| {
+                    anyhow::anyhow!("Unknown key '{}' in profile '{}'", key, name)
+                })?;
+                config
+                    .apply(key, &value_to_string(value))
+                    .with_context(|| format!("Invalid value for {} in profile '{}'", key, name))?;
+                sources.insert(key, ConfigSource::Profile);
+            }
+        }
+
+        if let Some(values) = project {
+            for (key, value) in values {
+                match known_key(key).filter(|k| PROJECT_KEYS.contains(k)) {
+                    Some(key) => {
+                        config
+                            .apply(key, &value_to_string(value))
+                            .with_context(|| {
+                                format!("Invalid value for {} in project config", key)
+                            })?;
+                        sources.insert(key, ConfigSource::Project);
+                    }
+                    None => eprintln!(
+                        "Warning: ignoring '{}' in {} (not allowed in project config)",
+                        key, PROJECT_CONFIG_FILE
+                    ),
+                }
+            }
+        }
+
+        for key in KNOWN_KEYS {
+            let name = env_var_name(key);
+            if let Some(value) = env(&name) {
+                config
+                    .apply(key, &value)
+                    .with_context(|| format!("Invalid value for {}", name))?;
+                sources.insert(*key, ConfigSource::Env);
+            }
+        }
+
+        Ok(ResolvedConfig {
+            config,
+            sources,
+            profile: profile.map(|p| p.to_string()),
+            project_file: None,
+        })
+    }
+}
+
+fn known_key(key: &str) -> Option<&'static str> {
+    KNOWN_KEYS.iter().copied().find(|k| *k == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_layers_precedence() {
+        let mut file = ConfigManager {
+            default_timeout: 60,
+            ..Default::default()
+        };
+        let mut work = Map::new();
+        work.insert("default_timeout".to_string(), Value::from(300));
+        work.insert("default_debate_rounds".to_string(), Value::from(5));
+        file.profiles.insert("work".to_string(), work);
+
+        let mut project = Map::new();
+        project.insert("default_debate_rounds".to_string(), Value::from(2));
+        // Security settings are ignored in project files
+        project.insert(
+            "plugins.allowed_commands".to_string(),
+            Value::from("rm".to_string()),
+        );
+
+        let resolved = ConfigManager::resolve_layers(file, Some("work"), Some(&project), |name| {
+            (name == "GPTENGAGE_AUDIT_LOG").then(|| "true".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(resolved.config.default_timeout, 300);
+        assert_eq!(resolved.source("default_timeout"), ConfigSource::Profile);
+        assert_eq!(resolved.config.default_debate_rounds, 2);
+        assert_eq!(
+            resolved.source("default_debate_rounds"),
+            ConfigSource::Project
+        );
+        assert!(resolved.config.audit_log);
+        assert_eq!(resolved.source("audit_log"), ConfigSource::Env);
+        assert!(resolved.config.plugins.allowed_commands.is_empty());
+        assert_eq!(
+            resolved.source("plugins.allowed_commands"),
+            ConfigSource::Default
+        );
+    }
+
+    #[test]
+    fn test_resolve_layers_file_source_and_unknown_profile() {
+        let file = ConfigManager {
+            default_timeout: 60,
+            ..Default::default()
+        };
+        let resolved = ConfigManager::resolve_layers(file.clone(), None, None, |_| None).unwrap();
+        assert_eq!(resolved.source("default_timeout"), ConfigSource::File);
+        assert_eq!(resolved.source("audit_log"), ConfigSource::Default);
+
+        assert!(ConfigManager::resolve_layers(file, Some("missing"), None, |_| None).is_err());
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(
+            env_var_name("plugins.allowed_commands"),
+            "GPTENGAGE_PLUGINS_ALLOWED_COMMANDS"
+        );
+    }
+}
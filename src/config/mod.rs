@@ -1,5 +1,11 @@
 //! Configuration management
 
+mod layers;
+mod shared;
+
+pub use layers::{ConfigSource, ResolvedConfig, KNOWN_KEYS, PROFILE_ENV, PROJECT_CONFIG_FILE};
+pub use shared::{shared_config, ConfigHandle};
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -12,6 +18,264 @@ pub struct ConfigManager {
     pub default_debate_rounds: usize,
     #[serde(default)]
     pub clis: std::collections::HashMap<String, CliConfig>,
+    /// Credential files (relative to HOME) copied into `--isolate` sandboxes,
+    /// keyed by CLI binary. Overrides the built-in defaults for that CLI.
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub isolate_credentials: std::collections::HashMap<String, Vec<String>>,
+    /// Wrapper command used by `--no-network` (e.g. ["firejail", "--net=none"]).
+    /// Defaults to an unprivileged network namespace on Linux.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_sandbox: Option<Vec<String>>,
+    /// Record every external process execution in ~/.gptengage/audit.jsonl
+    #[serde(default)]
+    pub audit_log: bool,
+    /// Record a summary of every debate in ~/.gptengage/history.jsonl
+    #[serde(default = "default_debate_history")]
+    pub debate_history: bool,
+    /// Record response times in ~/.gptengage/latency.jsonl and start the
+    /// slowest debate participants first
+    #[serde(default = "default_latency_scheduling")]
+    pub latency_scheduling: bool,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+    /// Named sets of key overrides, selected with GPTENGAGE_PROFILE
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub profiles: std::collections::HashMap<String, serde_json::Map<String, serde_json::Value>>,
+    /// How times are shown: relative, utc, local, or a strftime pattern
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+    /// Command aliases managed with `gptengage alias`
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub aliases: std::collections::HashMap<String, String>,
+    /// Bytes of stdout and of stderr kept from each child process (0 = no cap)
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// Raw output logs kept in ~/.gptengage/logs (0 = don't write them)
+    #[serde(default = "default_invocation_logs")]
+    pub invocation_logs: usize,
+    /// Follow-ups sent when a response looks truncated (0 = never)
+    #[serde(default = "default_max_continuations")]
+    pub max_continuations: usize,
+    /// Failures in a row after which a CLI isn't called again for the rest
+    /// of the run (0 = always call it)
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: usize,
+    /// Where sessions are stored: file (JSON files) or sqlite
+    #[serde(default = "default_session_store")]
+    pub session_store: String,
+    /// Daily limits on CLI invocations and their estimated cost
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    /// What the debate history and audit log may store
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// Prompt markup dialect (brackets, xml, or markdown), keyed by CLI name
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub dialects: std::collections::BTreeMap<String, String>,
+    /// Context window in tokens, keyed by CLI name or `cli:model`
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub context_windows: std::collections::BTreeMap<String, usize>,
+    /// Webhooks action items can be filed to with `--create-issues <NAME>`
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub issue_trackers: std::collections::BTreeMap<String, IssueTrackerConfig>,
+    /// Where `--post-to-mr` comments: github, gitlab, or gitea
+    #[serde(default = "default_forge")]
+    pub forge: String,
+    /// Days deleted sessions are kept in the trash (0 deletes outright)
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u64,
+    /// Format answers must be in (json, markdown, or language:<LANG>),
+    /// keyed by command
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub response_formats: std::collections::BTreeMap<String, String>,
+    /// Debate flag combinations used with `debate --preset <NAME>`
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub presets: std::collections::BTreeMap<String, DebatePreset>,
+    /// CLI called instead of another once that one's circuit breaker opens,
+    /// keyed by the failing CLI
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub fallbacks: std::collections::BTreeMap<String, String>,
+}
+
+impl Default for ConfigManager {
+    fn default() -> Self {
+        Self {
+            default_timeout: default_timeout(),
+            default_debate_rounds: default_debate_rounds(),
+            clis: std::collections::HashMap::new(),
+            isolate_credentials: std::collections::HashMap::new(),
+            network_sandbox: None,
+            audit_log: false,
+            debate_history: default_debate_history(),
+            latency_scheduling: default_latency_scheduling(),
+            plugins: PluginsConfig::default(),
+            profiles: std::collections::HashMap::new(),
+            time_format: default_time_format(),
+            aliases: std::collections::HashMap::new(),
+            max_output_bytes: default_max_output_bytes(),
+            invocation_logs: default_invocation_logs(),
+            max_continuations: default_max_continuations(),
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            session_store: default_session_store(),
+            budget: BudgetConfig::default(),
+            history: HistoryConfig::default(),
+            dialects: std::collections::BTreeMap::new(),
+            context_windows: std::collections::BTreeMap::new(),
+            issue_trackers: std::collections::BTreeMap::new(),
+            forge: default_forge(),
+            trash_retention_days: default_trash_retention_days(),
+            response_formats: std::collections::BTreeMap::new(),
+            presets: std::collections::BTreeMap::new(),
+            fallbacks: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+/// Plugin security settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginsConfig {
+    /// Commands that plugins are allowed to execute
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    /// SHA-256 of installed plugin files, keyed by file name
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub checksums: std::collections::HashMap<String, String>,
+    /// Plugins kept on disk but not offered as CLIs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled: Vec<String>,
+    /// Middleware plugins every prompt is passed through, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub middleware: Vec<String>,
+    /// Plugins trusted to talk only to local services, so they run outside
+    /// the `--no-network` sandbox. Plugin files cannot exempt themselves.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network_exempt: Vec<String>,
+    /// Minisign public keys (base64) that plugin signatures are checked
+    /// against
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signing_keys: Vec<String>,
+}
+
+impl PluginsConfig {
+    pub fn is_allowed(&self, command: &str) -> bool {
+        self.allowed_commands.iter().any(|c| c == command)
+    }
+
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.disabled.iter().any(|n| n == name)
+    }
+}
+
+/// Daily invocation budget, counted in ~/.gptengage/usage.jsonl
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// CLI invocations allowed per day (0 = no limit)
+    #[serde(default)]
+    pub daily_invocations: u64,
+    /// Estimated spend allowed per day, in dollars (0 = no limit)
+    #[serde(default)]
+    pub daily_cost: f64,
+    /// Estimated cost of one invocation in dollars, keyed by command name
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub invocation_costs: std::collections::BTreeMap<String, f64>,
+}
+
+impl BudgetConfig {
+    /// Whether any daily limit is set
+    pub fn is_set(&self) -> bool {
+        self.daily_invocations > 0 || self.daily_cost > 0.0
+    }
+
+    /// Estimated cost of invoking `command` (0 when no cost is configured)
+    pub fn cost_of(&self, command: &str) -> f64 {
+        self.invocation_costs.get(command).copied().unwrap_or(0.0)
+    }
+}
+
+/// History privacy settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// full (store topics) or hash-only (store salted hashes instead)
+    #[serde(default = "default_history_privacy")]
+    pub privacy: String,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            privacy: default_history_privacy(),
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// Whether topics and prompts are replaced by salted hashes
+    pub fn is_hash_only(&self) -> bool {
+        self.privacy == "hash-only"
+    }
+}
+
+/// An issue tracker reached through a webhook (Jira, Linear, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueTrackerConfig {
+    /// URL each issue is POSTed to
+    pub url: String,
+    /// JSON request body; `{task}`, `{owner}`, `{priority}`, `{topic}`, and
+    /// `{body}` in its strings are replaced for each action item
+    pub template: serde_json::Value,
+    /// Extra request headers; `${VAR}` is replaced from the environment
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub headers: std::collections::BTreeMap<String, String>,
+    /// JSON pointer to the created issue's URL or key in the response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_url: Option<String>,
+}
+
+/// Debate flags bundled under a name (`debate --preset <NAME>`). Flags
+/// given on the command line take precedence.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DebatePreset {
+    /// Template to debate with when no participants are given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Number of rounds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounds: Option<usize>,
+    /// Output format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Generate a synthesis
+    #[serde(default)]
+    pub synthesize: bool,
+    /// CLI that writes the synthesis; setting it turns on `synthesize`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synthesizer: Option<String>,
+    /// Context provider plugins run ahead of those on the command line
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_providers: Vec<String>,
+    /// Previous rounds of responses in each prompt (0 for none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_last: Option<usize>,
+    /// Leave out persona scaffolding (`--no-persona-context`)
+    #[serde(default)]
+    pub no_persona_context: bool,
+}
+
+fn default_history_privacy() -> String {
+    "full".to_string()
+}
+
+/// Parse a non-negative dollar amount
+fn parse_cost(value: &str) -> Result<f64> {
+    let cost: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("'{}' is not a number", value.trim()))?;
+    if !cost.is_finite() || cost < 0.0 {
+        anyhow::bail!("Costs must be zero or more, got {}", value.trim());
+    }
+    Ok(cost)
 }
 
 fn default_timeout() -> u64 {
@@ -22,28 +286,88 @@ fn default_debate_rounds() -> usize {
     3
 }
 
+fn default_debate_history() -> bool {
+    true
+}
+
+fn default_latency_scheduling() -> bool {
+    true
+}
+
+fn default_time_format() -> String {
+    "relative".to_string()
+}
+
+fn default_max_output_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+fn default_invocation_logs() -> usize {
+    100
+}
+
+fn default_max_continuations() -> usize {
+    1
+}
+
+fn default_circuit_breaker_threshold() -> usize {
+    3
+}
+
+fn default_trash_retention_days() -> u64 {
+    30
+}
+
+fn default_session_store() -> String {
+    "file".to_string()
+}
+
+fn default_forge() -> String {
+    "github".to_string()
+}
+
+/// Detected settings for a built-in CLI (filled in by `gptengage status`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CliConfig {
+    /// Command or absolute path used to run the CLI
     pub command: String,
+    /// Extra arguments appended to every invocation
+    #[serde(default)]
     pub invoke_args: Vec<String>,
+    #[serde(default)]
     pub detected: bool,
+    /// First line of `<cli> --version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Flags used by gptengage that the installed version accepts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
 }
 
 impl ConfigManager {
+    /// Read the user config file, or defaults when there isn't one yet.
+    ///
+    /// Unlike [`ConfigManager::new`], never creates the file, so read-only
+    /// commands (and `--help`) leave the home directory alone.
+    pub fn load() -> Result<Self> {
+        let config_path = Self::config_path()?;
+        if !config_path.exists() {
+            return Ok(ConfigManager::default());
+        }
+        let content = std::fs::read_to_string(&config_path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
     pub fn new() -> Result<Self> {
         let config_dir = Self::get_config_dir()?;
-        let config_path = config_dir.join("config.json");
+        let config_path = Self::config_path()?;
 
         if config_path.exists() {
             let content = std::fs::read_to_string(&config_path)?;
             let config: ConfigManager = serde_json::from_str(&content)?;
             Ok(config)
         } else {
-            let config = ConfigManager {
-                default_timeout: 120,
-                default_debate_rounds: 3,
-                clis: std::collections::HashMap::new(),
-            };
+            let config = ConfigManager::default();
 
             // Create config dir if needed
             if !config_dir.exists() {
@@ -58,8 +382,18 @@ impl ConfigManager {
         }
     }
 
+    /// Path of the user config file
+    pub fn config_path() -> Result<PathBuf> {
+        Ok(Self::get_config_dir()?.join("config.json"))
+    }
+
     pub fn get_config_dir() -> Result<PathBuf> {
-        let home = std::env::var("HOME")
+        Ok(Self::get_home_dir()?.join(".gptengage"))
+    }
+
+    /// Get the user's home directory
+    pub fn get_home_dir() -> Result<PathBuf> {
+        std::env::var("HOME")
             .ok()
             .and_then(|h| {
                 if h.is_empty() {
@@ -78,19 +412,88 @@ impl ConfigManager {
                     }
                 })
             })
-            .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-        Ok(home.join(".gptengage"))
+            .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))
     }
 
     pub fn get(&self, key: &str) -> Option<String> {
         match key {
             "default_timeout" => Some(self.default_timeout.to_string()),
             "default_debate_rounds" => Some(self.default_debate_rounds.to_string()),
+            "audit_log" => Some(self.audit_log.to_string()),
+            "debate_history" => Some(self.debate_history.to_string()),
+            "latency_scheduling" => Some(self.latency_scheduling.to_string()),
+            "time_format" => Some(self.time_format.clone()),
+            "max_output_bytes" => Some(self.max_output_bytes.to_string()),
+            "invocation_logs" => Some(self.invocation_logs.to_string()),
+            "max_continuations" => Some(self.max_continuations.to_string()),
+            "circuit_breaker_threshold" => Some(self.circuit_breaker_threshold.to_string()),
+            "session_store" => Some(self.session_store.clone()),
+            "forge" => Some(self.forge.clone()),
+            "trash_retention_days" => Some(self.trash_retention_days.to_string()),
+            "plugins.allowed_commands" => Some(self.plugins.allowed_commands.join(",")),
+            "plugins.middleware" => Some(self.plugins.middleware.join(",")),
+            "plugins.network_exempt" => Some(self.plugins.network_exempt.join(",")),
+            "plugins.signing_keys" => Some(self.plugins.signing_keys.join(",")),
+            "history.privacy" => Some(self.history.privacy.clone()),
+            "budget.daily_invocations" => Some(self.budget.daily_invocations.to_string()),
+            "budget.daily_cost" => Some(self.budget.daily_cost.to_string()),
+            "budget.invocation_costs" => Some(
+                self.budget
+                    .invocation_costs
+                    .iter()
+                    .map(|(command, cost)| format!("{}={}", command, cost))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            "dialects" => Some(
+                self.dialects
+                    .iter()
+                    .map(|(cli, dialect)| format!("{}={}", cli, dialect))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            "fallbacks" => Some(
+                self.fallbacks
+                    .iter()
+                    .map(|(cli, fallback)| format!("{}={}", cli, fallback))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            "context_windows" => Some(
+                self.context_windows
+                    .iter()
+                    .map(|(cli, tokens)| format!("{}={}", cli, tokens))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            "response_formats" => Some(
+                self.response_formats
+                    .iter()
+                    .map(|(command, format)| format!("{}={}", command, format))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
             _ => None,
         }
     }
 
     pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        self.apply(key, value)?;
+        // Save config to disk after updating
+        self.save()?;
+        Ok(())
+    }
+
+    /// Reset a key to its default value and save
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        let default = ConfigManager::default()
+            .get(key)
+            .ok_or_else(|| anyhow::anyhow!("Unknown config key: {}", key))?;
+        self.set(key, &default)
+    }
+
+    /// Update a key in memory from its string form
+    pub fn apply(&mut self, key: &str, value: &str) -> Result<()> {
         match key {
             "default_timeout" => {
                 self.default_timeout = value.parse()?;
@@ -98,16 +501,304 @@ impl ConfigManager {
             "default_debate_rounds" => {
                 self.default_debate_rounds = value.parse()?;
             }
+            "audit_log" => {
+                self.audit_log = value.parse()?;
+            }
+            "debate_history" => {
+                self.debate_history = value.parse()?;
+            }
+            "latency_scheduling" => {
+                self.latency_scheduling = value.parse()?;
+            }
+            "time_format" => {
+                crate::utils::time::TimeFormat::parse(value)?;
+                self.time_format = value.to_string();
+            }
+            "max_output_bytes" => {
+                self.max_output_bytes = value.parse()?;
+            }
+            "invocation_logs" => {
+                self.invocation_logs = value.parse()?;
+            }
+            "max_continuations" => {
+                self.max_continuations = value.parse()?;
+            }
+            "circuit_breaker_threshold" => {
+                self.circuit_breaker_threshold = value.parse()?;
+            }
+            "trash_retention_days" => {
+                self.trash_retention_days = value.parse()?;
+            }
+            "session_store" => {
+                if !crate::session::store::STORE_KINDS.contains(&value) {
+                    anyhow::bail!(
+                        "Unknown session_store '{}'. Use one of: {}",
+                        value,
+                        crate::session::store::STORE_KINDS.join(", ")
+                    );
+                }
+                self.session_store = value.to_string();
+            }
+            "forge" => {
+                if !crate::commands::merge_request::FORGES.contains(&value) {
+                    anyhow::bail!(
+                        "Unknown forge '{}'. Use one of: {}",
+                        value,
+                        crate::commands::merge_request::FORGES.join(", ")
+                    );
+                }
+                self.forge = value.to_string();
+            }
+            "plugins.allowed_commands" => {
+                self.plugins.allowed_commands = value
+                    .split(',')
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect();
+            }
+            "plugins.middleware" => {
+                self.plugins.middleware = value
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+            }
+            "plugins.network_exempt" => {
+                self.plugins.network_exempt = value
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+            }
+            "plugins.signing_keys" => {
+                self.plugins.signing_keys = value
+                    .split(',')
+                    .map(|key| key.trim().to_string())
+                    .filter(|key| !key.is_empty())
+                    .collect();
+            }
+            "history.privacy" => {
+                if !crate::history::redact::PRIVACY_MODES.contains(&value) {
+                    anyhow::bail!(
+                        "Unknown history.privacy '{}'. Use one of: {}",
+                        value,
+                        crate::history::redact::PRIVACY_MODES.join(", ")
+                    );
+                }
+                self.history.privacy = value.to_string();
+            }
+            "budget.daily_invocations" => {
+                self.budget.daily_invocations = value.parse()?;
+            }
+            "budget.daily_cost" => {
+                self.budget.daily_cost = parse_cost(value)?;
+            }
+            "budget.invocation_costs" => {
+                let mut costs = std::collections::BTreeMap::new();
+                for entry in value.split(',').filter(|e| !e.trim().is_empty()) {
+                    let (command, cost) = entry.split_once('=').ok_or_else(|| {
+                        anyhow::anyhow!("Expected command=cost (e.g. claude=0.05), got '{}'", entry)
+                    })?;
+                    costs.insert(command.trim().to_string(), parse_cost(cost)?);
+                }
+                self.budget.invocation_costs = costs;
+            }
+            "dialects" => {
+                let mut dialects = std::collections::BTreeMap::new();
+                for entry in value.split(',').filter(|e| !e.trim().is_empty()) {
+                    let (cli, dialect) = entry.split_once('=').ok_or_else(|| {
+                        anyhow::anyhow!("Expected cli=dialect (e.g. claude=xml), got '{}'", entry)
+                    })?;
+                    crate::invokers::dialect::Dialect::parse(dialect.trim())?;
+                    dialects.insert(cli.trim().to_string(), dialect.trim().to_string());
+                }
+                self.dialects = dialects;
+            }
+            "fallbacks" => {
+                let mut fallbacks = std::collections::BTreeMap::new();
+                for entry in value.split(',').filter(|e| !e.trim().is_empty()) {
+                    let (cli, fallback) = entry.split_once('=').ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Expected cli=fallback (e.g. gemini=claude), got '{}'",
+                            entry
+                        )
+                    })?;
+                    if cli.trim() == fallback.trim() {
+                        anyhow::bail!("{} can't be its own fallback", cli.trim());
+                    }
+                    fallbacks.insert(cli.trim().to_string(), fallback.trim().to_string());
+                }
+                self.fallbacks = fallbacks;
+            }
+            "context_windows" => {
+                let mut windows = std::collections::BTreeMap::new();
+                for entry in value.split(',').filter(|e| !e.trim().is_empty()) {
+                    let (cli, tokens) = entry.split_once('=').ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Expected cli=tokens (e.g. ollama:llama3=8192), got '{}'",
+                            entry
+                        )
+                    })?;
+                    let tokens: usize = tokens.trim().parse().map_err(|_| {
+                        anyhow::anyhow!("Invalid context window for {}: {}", cli.trim(), tokens)
+                    })?;
+                    windows.insert(cli.trim().to_string(), tokens);
+                }
+                self.context_windows = windows;
+            }
+            "response_formats" => {
+                let mut formats = std::collections::BTreeMap::new();
+                for entry in value.split(',').filter(|e| !e.trim().is_empty()) {
+                    let (command, format) = entry.split_once('=').ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Expected command=format (e.g. invoke=json), got '{}'",
+                            entry
+                        )
+                    })?;
+                    let command = command.trim();
+                    if !crate::orchestrator::response_format::COMMANDS.contains(&command) {
+                        anyhow::bail!(
+                            "Response formats can't be set for '{}'. Use one of: {}",
+                            command,
+                            crate::orchestrator::response_format::COMMANDS.join(", ")
+                        );
+                    }
+                    crate::orchestrator::response_format::ResponseFormat::parse(format)?;
+                    formats.insert(command.to_string(), format.trim().to_string());
+                }
+                self.response_formats = formats;
+            }
             _ => return Err(anyhow::anyhow!("Unknown config key: {}", key)),
         }
-        // Save config to disk after updating
-        self.save()?;
         Ok(())
     }
 
+    /// Check values for problems serde can't catch
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.default_timeout == 0 {
+            problems.push("default_timeout must be greater than 0".to_string());
+        }
+        if self.default_debate_rounds == 0 {
+            problems.push("default_debate_rounds must be at least 1".to_string());
+        }
+        if let Err(e) = crate::utils::time::TimeFormat::parse(&self.time_format) {
+            problems.push(e.to_string());
+        }
+        if !crate::session::store::STORE_KINDS.contains(&self.session_store.as_str()) {
+            problems.push(format!(
+                "session_store must be one of: {}",
+                crate::session::store::STORE_KINDS.join(", ")
+            ));
+        }
+        if !crate::commands::merge_request::FORGES.contains(&self.forge.as_str()) {
+            problems.push(format!(
+                "forge must be one of: {}",
+                crate::commands::merge_request::FORGES.join(", ")
+            ));
+        }
+        if !crate::history::redact::PRIVACY_MODES.contains(&self.history.privacy.as_str()) {
+            problems.push(format!(
+                "history.privacy must be one of: {}",
+                crate::history::redact::PRIVACY_MODES.join(", ")
+            ));
+        }
+        if self.budget.daily_cost < 0.0 || !self.budget.daily_cost.is_finite() {
+            problems.push("budget.daily_cost must be zero or more".to_string());
+        }
+        if self.budget.daily_cost > 0.0 && self.budget.invocation_costs.is_empty() {
+            problems.push(
+                "budget.daily_cost has no effect until budget.invocation_costs prices a CLI"
+                    .to_string(),
+            );
+        }
+        for (cli, dialect) in &self.dialects {
+            if let Err(e) = crate::invokers::dialect::Dialect::parse(dialect) {
+                problems.push(format!("dialects.{}: {}", cli, e));
+            }
+        }
+        for (cli, fallback) in &self.fallbacks {
+            if cli == fallback {
+                problems.push(format!("fallbacks.{} can't be its own fallback", cli));
+            }
+        }
+        for (cli, tokens) in &self.context_windows {
+            if *tokens == 0 {
+                problems.push(format!("context_windows.{} must be at least 1", cli));
+            }
+        }
+        for (command, format) in &self.response_formats {
+            if !crate::orchestrator::response_format::COMMANDS.contains(&command.as_str()) {
+                problems.push(format!(
+                    "response_formats.{}: only {} can be set",
+                    command,
+                    crate::orchestrator::response_format::COMMANDS.join(" and ")
+                ));
+            }
+            if let Err(e) = crate::orchestrator::response_format::ResponseFormat::parse(format) {
+                problems.push(format!("response_formats.{}: {}", command, e));
+            }
+        }
+        for (name, preset) in &self.presets {
+            if preset.rounds == Some(0) {
+                problems.push(format!("presets.{}.rounds must be at least 1", name));
+            }
+            if let Some(output) = &preset.output {
+                if !crate::commands::debate::OUTPUT_FORMATS.contains(&output.as_str()) {
+                    problems.push(format!(
+                        "presets.{}.output must be one of: {}",
+                        name,
+                        crate::commands::debate::OUTPUT_FORMATS.join(", ")
+                    ));
+                }
+            }
+        }
+        for (name, tracker) in &self.issue_trackers {
+            if name == "github" {
+                problems.push(
+                    "issue_trackers.github: the name is reserved for `gh` issues".to_string(),
+                );
+            }
+            if !tracker.url.starts_with("https://") && !tracker.url.starts_with("http://") {
+                problems.push(format!(
+                    "issue_trackers.{}.url must be an http(s) URL",
+                    name
+                ));
+            }
+            if !tracker.template.is_object() {
+                problems.push(format!(
+                    "issue_trackers.{}.template must be a JSON object",
+                    name
+                ));
+            }
+            if let Some(pointer) = &tracker.response_url {
+                if !pointer.is_empty() && !pointer.starts_with('/') {
+                    problems.push(format!(
+                        "issue_trackers.{}.response_url must be a JSON pointer like /key",
+                        name
+                    ));
+                }
+            }
+        }
+        if let Some(wrapper) = &self.network_sandbox {
+            if wrapper.is_empty() {
+                problems.push("network_sandbox must name a command".to_string());
+            }
+        }
+        for (name, overrides) in &self.profiles {
+            let mut profiled = self.clone();
+            for (key, value) in overrides {
+                if let Err(e) = profiled.apply(key, &layers::value_to_string(value)) {
+                    problems.push(format!("profiles.{}.{}: {}", name, key, e));
+                }
+            }
+        }
+        problems
+    }
+
     pub fn save(&self) -> Result<()> {
         let config_dir = Self::get_config_dir()?;
-        let config_path = config_dir.join("config.json");
+        let config_path = Self::config_path()?;
 
         // Create config dir if needed
         if !config_dir.exists() {
@@ -117,6 +808,8 @@ impl ConfigManager {
         // Write config
         let content = serde_json::to_string_pretty(&self)?;
         std::fs::write(&config_path, content)?;
+        // Later reads in this process see the new values
+        shared_config().invalidate();
         Ok(())
     }
 }
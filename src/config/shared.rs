@@ -0,0 +1,106 @@
+//! Shared config - One resolve of the effective config per process
+//!
+//! Resolving reads the user config file and the project file and applies
+//! the profile and environment layers. Commands, invokers, plugins, and the
+//! session store all need the result, so the first read resolves it and
+//! later reads share that copy. `ConfigManager::save` invalidates it, so a
+//! value written during a run (trusting a plugin, say) is seen by later
+//! reads; long-running callers can `reload` to pick up outside edits.
+
+use super::{ConfigManager, ResolvedConfig};
+use anyhow::Result;
+use std::sync::{Arc, OnceLock, RwLock};
+
+static SHARED: OnceLock<ConfigHandle> = OnceLock::new();
+
+/// Lazily resolved configuration, cheap to clone and share
+#[derive(Debug, Clone, Default)]
+pub struct ConfigHandle {
+    cached: Arc<RwLock<Option<Arc<ResolvedConfig>>>>,
+}
+
+impl ConfigHandle {
+    /// A handle that resolves on first use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A handle preloaded with `resolved`, which is used until a reload
+    pub fn with_config(resolved: ResolvedConfig) -> Self {
+        Self {
+            cached: Arc::new(RwLock::new(Some(Arc::new(resolved)))),
+        }
+    }
+
+    /// The effective config, resolved on first use. Failures are not
+    /// cached, so a later call tries again.
+    pub fn get(&self) -> Result<Arc<ResolvedConfig>> {
+        if let Some(resolved) = self
+            .cached
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            return Ok(resolved.clone());
+        }
+        self.reload()
+    }
+
+    /// Resolve the config again from its files and the environment
+    pub fn reload(&self) -> Result<Arc<ResolvedConfig>> {
+        let resolved = Arc::new(ConfigManager::resolve()?);
+        *self.cached.write().unwrap_or_else(|e| e.into_inner()) = Some(resolved.clone());
+        Ok(resolved)
+    }
+
+    /// Drop the cached config so the next `get` resolves it again
+    pub fn invalidate(&self) {
+        *self.cached.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Whether the config has been resolved (and not invalidated) yet
+    pub fn is_loaded(&self) -> bool {
+        self.cached
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+}
+
+/// The process-wide config handle
+pub fn shared_config() -> &'static ConfigHandle {
+    SHARED.get_or_init(ConfigHandle::new)
+}
+
+impl ConfigManager {
+    /// The effective config from the process-wide handle, resolved once
+    /// and shared by every later caller
+    pub fn shared() -> Result<Arc<ResolvedConfig>> {
+        shared_config().get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_caches_until_invalidated() {
+        let file = ConfigManager {
+            default_timeout: 30,
+            ..Default::default()
+        };
+        let resolved = ConfigManager::resolve_layers(file, None, None, |_| None).unwrap();
+        let handle = ConfigHandle::with_config(resolved);
+        assert!(handle.is_loaded());
+
+        let first = handle.get().unwrap();
+        assert_eq!(first.config.default_timeout, 30);
+        // Clones share the cache, and reads don't resolve again
+        let clone = handle.clone();
+        assert!(Arc::ptr_eq(&first, &clone.get().unwrap()));
+
+        clone.invalidate();
+        assert!(!handle.is_loaded());
+    }
+}
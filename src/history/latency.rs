@@ -0,0 +1,287 @@
+//! Response latency - How long each CLI usually takes to answer
+//!
+//! While the `latency_scheduling` config key is on, every debate response
+//! records how long its CLI ran in `~/.gptengage/latency.jsonl`. Time spent
+//! waiting for a `--max-concurrency` slot is not counted. Debates start the
+//! historically slowest participants first, so the long waits overlap when
+//! concurrency is limited, and estimate how long each round will take.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+tokio::task_local! {
+    static RUN_TIME: Cell<Duration>;
+}
+
+/// Serializes appends so compaction can't drop a concurrent response's line
+static APPEND: Mutex<()> = Mutex::new(());
+
+/// Samples per CLI (and model) used for estimates
+const RECENT_SAMPLES: usize = 10;
+
+/// The file is compacted to the recent samples past this many lines
+const MAX_LINES: usize = 500;
+
+/// How long one response took
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyRecord {
+    pub timestamp: DateTime<Utc>,
+    /// CLI name, e.g. "claude"
+    pub cli: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// The latency file
+pub struct LatencyHistory {
+    path: PathBuf,
+}
+
+/// Recent response times, for estimates
+#[derive(Debug, Clone, Default)]
+pub struct LatencyTable {
+    records: Vec<LatencyRecord>,
+}
+
+impl LatencyHistory {
+    /// Open the latency history in the config directory
+    pub fn open() -> Result<Self> {
+        let config_dir = crate::config::ConfigManager::get_config_dir()?;
+        Ok(Self::at(config_dir.join("latency.jsonl")))
+    }
+
+    /// Use a latency history at a specific path
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn read(&self) -> Result<Vec<LatencyRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read latency history: {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Recorded response times, oldest first. Malformed lines are skipped.
+    pub fn load(&self) -> Result<LatencyTable> {
+        Ok(LatencyTable {
+            records: self.read()?,
+        })
+    }
+
+    /// Append a record, compacting the file to the recent samples of each
+    /// CLI once it grows past its limit
+    pub fn append(&self, record: &LatencyRecord) -> Result<()> {
+        let _guard = APPEND.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut records = self.read()?;
+        let compact = records.len() >= MAX_LINES;
+
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true);
+        if compact {
+            options.write(true).truncate(true);
+        } else {
+            options.append(true);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&self.path)
+            .with_context(|| format!("Failed to open latency history: {}", self.path.display()))?;
+        if compact {
+            records.push(record.clone());
+            let table = LatencyTable { records };
+            for kept in table.recent_records() {
+                writeln!(file, "{}", serde_json::to_string(kept)?)?;
+            }
+        } else {
+            writeln!(file, "{}", serde_json::to_string(record)?)?;
+        }
+        Ok(())
+    }
+
+    /// Record that `cli` (running `model`) answered in `duration`
+    pub fn record(&self, cli: &str, model: Option<&str>, duration: Duration) -> Result<()> {
+        self.append(&LatencyRecord {
+            timestamp: Utc::now(),
+            cli: cli.to_string(),
+            model: model.map(str::to_string),
+            duration_ms: duration.as_millis() as u64,
+        })
+    }
+}
+
+impl LatencyTable {
+    /// The most recent samples of each CLI and model, oldest first
+    fn recent_records(&self) -> Vec<&LatencyRecord> {
+        let mut seen: std::collections::HashMap<(&str, Option<&str>), usize> =
+            std::collections::HashMap::new();
+        let mut kept: Vec<&LatencyRecord> = self
+            .records
+            .iter()
+            .rev()
+            .filter(|r| {
+                let count = seen.entry((&r.cli, r.model.as_deref())).or_default();
+                *count += 1;
+                *count <= RECENT_SAMPLES
+            })
+            .collect();
+        kept.reverse();
+        kept
+    }
+
+    /// Median of the recent response times of `cli` running `model`,
+    /// falling back to all of `cli`'s samples when that model has none
+    pub fn expected(&self, cli: &str, model: Option<&str>) -> Option<Duration> {
+        let samples = |same_model: bool| -> Vec<u64> {
+            let mut samples: Vec<u64> = self
+                .records
+                .iter()
+                .rev()
+                .filter(|r| r.cli == cli && (!same_model || r.model.as_deref() == model))
+                .take(RECENT_SAMPLES)
+                .map(|r| r.duration_ms)
+                .collect();
+            samples.sort_unstable();
+            samples
+        };
+        let mut recent = samples(true);
+        if recent.is_empty() {
+            recent = samples(false);
+        }
+        recent
+            .get(recent.len().checked_sub(1)? / 2)
+            .map(|&ms| Duration::from_millis(ms))
+    }
+}
+
+/// Indices of participants in the order to start them: slowest expected
+/// first. Participants without history count as slowest, and ties keep
+/// their order.
+pub fn slowest_first(expected: &[Option<Duration>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..expected.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(expected[i].unwrap_or(Duration::MAX)));
+    order
+}
+
+/// How long a round takes when `durations` start in order on `slots`
+/// parallel slots (unlimited when None), each taking the next free slot
+pub fn estimate_round(durations: &[Duration], slots: Option<usize>) -> Duration {
+    let slots = slots.unwrap_or(durations.len()).min(durations.len()).max(1);
+    let mut finishes = vec![Duration::ZERO; slots];
+    for &duration in durations {
+        if let Some(next) = finishes.iter_mut().min() {
+            *next += duration;
+        }
+    }
+    finishes.into_iter().max().unwrap_or_default()
+}
+
+/// Add time a child process spent running to the enclosing
+/// `measure_run_time`, if any
+pub fn add_run_time(duration: Duration) {
+    let _ = RUN_TIME.try_with(|total| total.set(total.get() + duration));
+}
+
+/// Run `future`, adding up how long the child processes it ran directly
+/// (not by tasks it spawns) spent running
+pub async fn measure_run_time<F: Future>(future: F) -> (F::Output, Duration) {
+    RUN_TIME
+        .scope(Cell::new(Duration::ZERO), async {
+            let output = future.await;
+            (output, RUN_TIME.with(Cell::get))
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_latency_and_schedule() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = LatencyHistory::at(dir.path().join("latency.jsonl"));
+        assert!(history.load().unwrap().expected("claude", None).is_none());
+
+        for ms in [900, 100, 200] {
+            history
+                .record("claude", None, Duration::from_millis(ms))
+                .unwrap();
+        }
+        history
+            .record("gemini", Some("pro"), Duration::from_secs(30))
+            .unwrap();
+        let table = history.load().unwrap();
+        assert_eq!(
+            table.expected("claude", None),
+            Some(Duration::from_millis(200))
+        );
+        // No samples for this model: any gemini sample will do
+        assert_eq!(
+            table.expected("gemini", Some("flash")),
+            Some(Duration::from_secs(30))
+        );
+
+        let expected = [
+            table.expected("claude", None),
+            table.expected("codex", None),
+            table.expected("gemini", Some("pro")),
+        ];
+        assert_eq!(slowest_first(&expected), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_estimate_round() {
+        let slowest = [30, 10, 5].map(Duration::from_secs);
+        assert_eq!(estimate_round(&slowest, None), Duration::from_secs(30));
+        // Two slots: 30 | 10 + 5
+        assert_eq!(estimate_round(&slowest, Some(2)), Duration::from_secs(30));
+        // Fastest first instead: 5 + 30 | 10
+        let fastest = [5, 10, 30].map(Duration::from_secs);
+        assert_eq!(estimate_round(&fastest, Some(2)), Duration::from_secs(35));
+        assert_eq!(estimate_round(&[], Some(2)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_append_compacts_to_recent_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("latency.jsonl");
+        let lines: String = (0..MAX_LINES)
+            .map(|ms| {
+                format!(
+                    "{{\"timestamp\":\"2026-01-01T00:00:00Z\",\"cli\":\"claude\",\"duration_ms\":{}}}\n",
+                    ms
+                )
+            })
+            .collect();
+        std::fs::write(&path, lines).unwrap();
+        let history = LatencyHistory::at(path);
+        history.record("codex", None, Duration::ZERO).unwrap();
+        let table = history.load().unwrap();
+        assert_eq!(table.records.len(), RECENT_SAMPLES + 1);
+        assert_eq!(
+            table.records[0].duration_ms,
+            (MAX_LINES - RECENT_SAMPLES) as u64
+        );
+    }
+}
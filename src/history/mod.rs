@@ -0,0 +1,146 @@
+//! Debate history - A summary line for every finished debate
+//!
+//! Entries are stored as JSON lines in `~/.gptengage/history.jsonl` while
+//! the `debate_history` config key is on. Only the topic's first line and
+//! the synthesis recommendation are kept, not the responses; with
+//! `history.privacy = "hash-only"`, not even those (see [`redact`]).
+
+pub mod latency;
+pub mod redact;
+pub mod usage;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Longest topic kept in a record
+const MAX_TOPIC_CHARS: usize = 200;
+
+/// Summary of one finished debate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebateRecord {
+    pub timestamp: DateTime<Utc>,
+    pub topic: String,
+    /// Size of the full topic in bytes
+    #[serde(default)]
+    pub topic_bytes: usize,
+    /// Participant display names, e.g. "claude (CTO)"
+    pub participants: Vec<String>,
+    pub rounds: usize,
+    /// Responses received
+    pub responses: usize,
+    /// Invocations that failed and were left out of the transcript
+    pub failures: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl DebateRecord {
+    /// Replace the topic and recommendation with salted hashes. The hash
+    /// covers the full `topic`, so repeats of a question still match.
+    pub fn redact(&mut self, topic: &str) -> Result<()> {
+        self.topic = redact::redact(topic)?;
+        if let Some(recommendation) = self.recommendation.take() {
+            self.recommendation = Some(redact::redact(&recommendation)?);
+        }
+        Ok(())
+    }
+}
+
+/// First line of a topic, cut to a bounded length
+pub fn topic_summary(topic: &str) -> String {
+    let line = topic.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let line = line.trim();
+    if line.chars().count() <= MAX_TOPIC_CHARS {
+        return line.to_string();
+    }
+    let mut cut: String = line.chars().take(MAX_TOPIC_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Append-only debate history file
+pub struct DebateHistory {
+    path: PathBuf,
+}
+
+impl DebateHistory {
+    /// Open the history in the config directory
+    pub fn open() -> Result<Self> {
+        let config_dir = crate::config::ConfigManager::get_config_dir()?;
+        Ok(Self::at(config_dir.join("history.jsonl")))
+    }
+
+    /// Use a history file at a specific path
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Append a record as a single JSON line
+    pub fn append(&self, record: &DebateRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options
+            .open(&self.path)
+            .with_context(|| format!("Failed to open debate history: {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// Read all records, oldest first. Malformed lines are skipped.
+    pub fn read(&self) -> Result<Vec<DebateRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read debate history: {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = DebateHistory::at(dir.path().join("history.jsonl"));
+        assert!(history.read().unwrap().is_empty());
+
+        let record = DebateRecord {
+            timestamp: Utc::now(),
+            topic: topic_summary("\n  Should we shard the DB?\nMore detail"),
+            topic_bytes: 38,
+            participants: vec!["claude".to_string(), "codex (SRE)".to_string()],
+            rounds: 3,
+            responses: 5,
+            failures: 1,
+            recommendation: Some("Shard by tenant".to_string()),
+            duration_ms: 1200,
+        };
+        history.append(&record).unwrap();
+        history.append(&record).unwrap();
+
+        let records = history.read().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].topic, "Should we shard the DB?");
+        assert_eq!(topic_summary(&"x".repeat(300)).chars().count(), 200);
+    }
+}
@@ -0,0 +1,102 @@
+//! Hash-only history - Salted hashes in place of prompt text
+//!
+//! With `history.privacy = "hash-only"`, the debate history and audit log
+//! keep a salted SHA-256 of each topic and prompt instead of the text, so
+//! identical prompts can still be matched up but not recovered by hashing
+//! guesses. The salt is generated once per installation and stored in
+//! `~/.gptengage/history.salt`.
+
+use crate::audit::sha256_hex;
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// `history.privacy` values
+pub const PRIVACY_MODES: &[&str] = &["full", "hash-only"];
+
+static SALT: OnceLock<String> = OnceLock::new();
+
+/// 32 random bytes, hex-encoded
+fn new_salt() -> String {
+    let mut bytes = [0u8; 32];
+    let random = std::fs::File::open("/dev/urandom").and_then(|mut f| f.read_exact(&mut bytes));
+    if random.is_ok() {
+        return bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    }
+    // No /dev/urandom (Windows): std's hasher keys are seeded by the OS
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    sha256_hex(&format!("{}:{}", hasher.finish(), std::process::id()))
+}
+
+/// Read the salt at `path`, creating it if it doesn't exist
+pub fn load_salt(path: &Path) -> Result<String> {
+    if let Ok(salt) = std::fs::read_to_string(path) {
+        let salt = salt.trim();
+        if !salt.is_empty() {
+            return Ok(salt.to_string());
+        }
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let salt = new_salt();
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create history salt: {}", path.display()))?;
+    std::io::Write::write_all(&mut file, salt.as_bytes())?;
+    Ok(salt)
+}
+
+/// This installation's salt
+pub fn salt() -> Result<&'static str> {
+    if let Some(salt) = SALT.get() {
+        return Ok(salt);
+    }
+    let path = crate::config::ConfigManager::get_config_dir()?.join("history.salt");
+    let salt = load_salt(&path)?;
+    Ok(SALT.get_or_init(|| salt))
+}
+
+/// Hex-encoded SHA-256 of `text` with `salt` mixed in
+pub fn salted_sha256(salt: &str, text: &str) -> String {
+    sha256_hex(&format!("{}\0{}", salt, text))
+}
+
+/// Stand-in for text that mustn't be stored: `<sha256:...>` of its salted hash
+pub fn redact(text: &str) -> Result<String> {
+    Ok(format!("<sha256:{}>", salted_sha256(salt()?, text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_salt_is_created_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.salt");
+        let salt = load_salt(&path).unwrap();
+        assert_eq!(salt.len(), 64);
+        assert_eq!(load_salt(&path).unwrap(), salt);
+
+        let hash = salted_sha256(&salt, "topic");
+        assert_ne!(hash, sha256_hex("topic"));
+        assert_eq!(hash, salted_sha256(&salt, "topic"));
+        assert_ne!(hash, salted_sha256("other salt", "topic"));
+    }
+}
@@ -0,0 +1,250 @@
+//! Invocation usage - Today's CLI invocations, for daily budgets
+//!
+//! While a `budget.*` limit is set, every CLI invocation is recorded in
+//! `~/.gptengage/usage.jsonl` before it runs, with the estimated cost from
+//! `budget.invocation_costs`. Days are local calendar days; records from
+//! earlier days are dropped on the next write.
+
+use crate::config::BudgetConfig;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Serializes check-and-record so parallel invocations can't overrun a limit
+static CHARGE: Mutex<()> = Mutex::new(());
+
+/// One CLI invocation counted against the budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Command name, e.g. "claude"
+    pub command: String,
+    /// Estimated cost in dollars
+    pub cost: f64,
+}
+
+impl UsageRecord {
+    fn day(&self) -> NaiveDate {
+        self.timestamp.with_timezone(&Local).date_naive()
+    }
+}
+
+/// Invocations and estimated spend so far today
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DailyUsage {
+    pub invocations: u64,
+    pub cost: f64,
+}
+
+impl DailyUsage {
+    fn add(&mut self, record: &UsageRecord) {
+        self.invocations += 1;
+        self.cost += record.cost;
+    }
+
+    /// Why an invocation costing `cost` would exceed `budget`, if it would
+    pub fn exceeded_by(&self, budget: &BudgetConfig, cost: f64) -> Option<String> {
+        if budget.daily_invocations > 0 && self.invocations >= budget.daily_invocations {
+            return Some(format!(
+                "Daily invocation budget reached ({} of {} used today)",
+                self.invocations, budget.daily_invocations
+            ));
+        }
+        if budget.daily_cost > 0.0 && self.cost + cost > budget.daily_cost {
+            return Some(format!(
+                "Daily cost budget reached (${:.2} of ${:.2} used today; this call costs ${:.2})",
+                self.cost, budget.daily_cost, cost
+            ));
+        }
+        None
+    }
+}
+
+/// The usage file
+pub struct UsageLog {
+    path: PathBuf,
+}
+
+impl UsageLog {
+    /// Open the usage log in the config directory
+    pub fn open() -> Result<Self> {
+        let config_dir = crate::config::ConfigManager::get_config_dir()?;
+        Ok(Self::at(config_dir.join("usage.jsonl")))
+    }
+
+    /// Use a usage log at a specific path
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn read(&self) -> Result<Vec<UsageRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read usage log: {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Usage recorded on `day`
+    pub fn usage_on(&self, day: NaiveDate) -> Result<DailyUsage> {
+        let mut usage = DailyUsage::default();
+        for record in self.read()?.iter().filter(|r| r.day() == day) {
+            usage.add(record);
+        }
+        Ok(usage)
+    }
+
+    /// Usage recorded today
+    pub fn today(&self) -> Result<DailyUsage> {
+        self.usage_on(Local::now().date_naive())
+    }
+
+    /// Append a record, dropping records from days before it
+    pub fn append(&self, record: &UsageRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let records = self.read()?;
+        let stale = records.iter().any(|r| r.day() < record.day());
+
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true);
+        if stale {
+            options.write(true).truncate(true);
+        } else {
+            options.append(true);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&self.path)
+            .with_context(|| format!("Failed to open usage log: {}", self.path.display()))?;
+        if stale {
+            for kept in records.iter().filter(|r| r.day() >= record.day()) {
+                writeln!(file, "{}", serde_json::to_string(kept)?)?;
+            }
+        }
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// Check `budget` and count an invocation of `command` against it.
+    /// With `force`, the invocation is counted even over budget.
+    pub fn charge(&self, budget: &BudgetConfig, command: &str, force: bool) -> Result<()> {
+        self.charge_or_ask(budget, command, |_| Ok(force))
+    }
+
+    /// Like `charge`, with `approve` deciding, given the limit that would
+    /// be exceeded, whether to run over budget. `approve` is called
+    /// without holding the lock, so other invocations aren't stalled
+    /// while it asks.
+    pub fn charge_or_ask(
+        &self,
+        budget: &BudgetConfig,
+        command: &str,
+        approve: impl FnOnce(&str) -> Result<bool>,
+    ) -> Result<()> {
+        let cost = budget.cost_of(command);
+        let record = UsageRecord {
+            timestamp: Utc::now(),
+            command: command.to_string(),
+            cost,
+        };
+        let reason = {
+            let _guard = CHARGE.lock().unwrap_or_else(|e| e.into_inner());
+            match self.today()?.exceeded_by(budget, cost) {
+                Some(reason) => reason,
+                None => return self.append(&record),
+            }
+        };
+        if !approve(&reason)? {
+            anyhow::bail!(
+                "{}. Raise the budget.* config keys, or pass --force-budget (or --yes) to run anyway.",
+                reason
+            );
+        }
+        eprintln!("Warning: {}; running anyway", reason);
+        let _guard = CHARGE.lock().unwrap_or_else(|e| e.into_inner());
+        self.append(&record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charge_enforces_daily_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::at(dir.path().join("usage.jsonl"));
+        let mut budget = BudgetConfig {
+            daily_invocations: 2,
+            ..Default::default()
+        };
+        budget.invocation_costs.insert("claude".to_string(), 0.5);
+
+        log.charge(&budget, "claude", false).unwrap();
+        log.charge(&budget, "codex", false).unwrap();
+        let err = log.charge(&budget, "claude", false).unwrap_err();
+        assert!(err.to_string().contains("2 of 2 used today"));
+        log.charge(&budget, "claude", true).unwrap();
+        assert_eq!(
+            log.today().unwrap(),
+            DailyUsage {
+                invocations: 3,
+                cost: 1.0
+            }
+        );
+
+        budget.daily_invocations = 0;
+        budget.daily_cost = 1.25;
+        assert!(log.charge(&budget, "codex", false).is_ok());
+        assert!(log.charge(&budget, "claude", false).is_err());
+    }
+
+    #[test]
+    fn test_approval_asked_outside_the_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::at(dir.path().join("usage.jsonl"));
+        let budget = BudgetConfig {
+            daily_invocations: 1,
+            ..Default::default()
+        };
+        log.charge(&budget, "claude", false).unwrap();
+
+        log.charge_or_ask(&budget, "claude", |_| {
+            // Other invocations can still charge while this one asks
+            assert!(CHARGE.try_lock().is_ok());
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(log.today().unwrap().invocations, 2);
+    }
+
+    #[test]
+    fn test_append_drops_earlier_days() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::at(dir.path().join("usage.jsonl"));
+        let record = |days_ago: i64| UsageRecord {
+            timestamp: Utc::now() - chrono::Duration::days(days_ago),
+            command: "claude".to_string(),
+            cost: 0.0,
+        };
+        log.append(&record(2)).unwrap();
+        log.append(&record(2)).unwrap();
+        assert_eq!(log.read().unwrap().len(), 2);
+        log.append(&record(0)).unwrap();
+        assert_eq!(log.read().unwrap().len(), 1);
+        assert_eq!(log.today().unwrap().invocations, 1);
+    }
+}
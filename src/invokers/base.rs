@@ -1,21 +1,330 @@
 //! Base invoker implementation with common utilities
 
+use super::encoding::{normalize_output, utf8_locale_env};
+use super::isolation::IsolatedHome;
+use super::logs::{get_logs_dir, record as record_log, InvocationLog};
+use super::AccessMode;
+use crate::audit::{AuditEntry, AuditLog, ProcessAudit};
+use crate::history::latency::add_run_time;
+use crate::history::usage::UsageLog;
+use crate::utils::interaction::confirm;
 use anyhow::Result;
 use std::process::{Command, Stdio};
+use std::sync::Mutex;
 
 /// Environment variables that Claude Code sets to detect nesting.
 /// We strip these so child processes (e.g. `claude -p`) don't think
 /// they're running inside another Claude instance.
 const CLAUDE_NESTING_ENV_VARS: &[&str] = &["CLAUDECODE", "CLAUDE_CODE_ENTRYPOINT"];
 
-/// Execute a command with timeout
+/// Command and extra arguments configured for a built-in CLI.
+///
+/// Uses the path recorded by detection when it still exists, falling back
+/// to looking the CLI up in PATH by name.
+pub fn configured_cli(name: &str) -> (String, Vec<String>) {
+    let entry = crate::config::ConfigManager::shared()
+        .ok()
+        .and_then(|resolved| resolved.config.clis.get(name).cloned());
+
+    match entry {
+        Some(cli) => {
+            let command = if cli.detected && std::path::Path::new(&cli.command).exists() {
+                cli.command
+            } else {
+                name.to_string()
+            };
+            (command, cli.invoke_args)
+        }
+        None => (name.to_string(), Vec::new()),
+    }
+}
+
+/// Execute a command with timeout.
+///
+/// This is how CLIs are invoked, so each call counts against the daily
+/// budget when one is set.
 pub async fn execute_command(
     cmd: &str,
     args: &[&str],
     input: &str,
     timeout: u64,
+    access_mode: AccessMode,
+) -> Result<String> {
+    charge_budget(cmd).await?;
+    run_recorded(cmd, args, input, None, timeout, access_mode)
+        .await
+        .0
+}
+
+/// Execute a command that is given `prompt` within its arguments rather
+/// than on stdin, so every occurrence is hashed in the audit log
+pub async fn execute_command_with_prompt_args(
+    cmd: &str,
+    args: &[&str],
+    prompt: &str,
+    timeout: u64,
+    access_mode: AccessMode,
 ) -> Result<String> {
-    let mut command = tokio::process::Command::new(cmd);
+    charge_budget(cmd).await?;
+    run_recorded(cmd, args, "", Some(prompt), timeout, access_mode)
+        .await
+        .0
+}
+
+/// Count an invocation of `cmd` against the daily budget, if one is set.
+///
+/// Runs on a blocking thread: going over budget may wait for an answer on
+/// the terminal.
+pub(super) async fn charge_budget(cmd: &str) -> Result<()> {
+    let policy = super::policy::policy();
+    if !policy.budget.is_set() {
+        return Ok(());
+    }
+    let command = super::policy::binary_name(cmd).to_string();
+    tokio::task::spawn_blocking(move || {
+        UsageLog::open()?.charge_or_ask(&policy.budget, &command, |reason| {
+            Ok(policy.force_budget || approve_overrun(reason)?)
+        })
+    })
+    .await?
+}
+
+/// The answer to running over budget, kept so a debate asks only once
+static OVERRUN_DECISION: Mutex<Option<bool>> = Mutex::new(None);
+
+/// Ask whether to run over budget (`--yes` approves)
+fn approve_overrun(reason: &str) -> Result<bool> {
+    // Held while asking, so parallel participants wait for the one answer
+    let mut decision = OVERRUN_DECISION.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(approved) = *decision {
+        return Ok(approved);
+    }
+    let approved = confirm(&format!("{}. Run anyway?", reason), false)?;
+    *decision = Some(approved);
+    Ok(approved)
+}
+
+/// Captured output of a command that ran to completion
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// None when the process was killed by a signal
+    pub exit_code: Option<i32>,
+}
+
+/// Outcome of one run: the result, the exit code, and the stdout/stderr
+/// captured, which is partial when the run timed out and absent when
+/// nothing ran
+pub(super) type RunOutcome = (Result<String>, Option<i32>, Option<(String, String)>);
+
+/// Run a command, writing its invocation log and audit entry
+async fn run_recorded(
+    cmd: &str,
+    args: &[&str],
+    input: &str,
+    prompt_in_args: Option<&str>,
+    timeout: u64,
+    access_mode: AccessMode,
+) -> RunOutcome {
+    record_run(
+        cmd,
+        args,
+        input,
+        prompt_in_args,
+        access_mode,
+        spawn_and_wait(cmd, args, input, timeout),
+    )
+    .await
+}
+
+/// Await `run`, writing the invocation log and audit entry for it.
+/// `prompt_in_args` is the prompt when it was placed within `args`.
+pub(super) async fn record_run(
+    cmd: &str,
+    args: &[&str],
+    input: &str,
+    prompt_in_args: Option<&str>,
+    access_mode: AccessMode,
+    run: impl std::future::Future<Output = RunOutcome>,
+) -> RunOutcome {
+    let started_at = chrono::Utc::now();
+    let started = std::time::Instant::now();
+
+    let (result, exit_code, output) = run.await;
+    let policy = super::policy::policy();
+
+    // Failed spawns and timeouts are logged too: they are what a
+    // post-mortem is usually about
+    if let Some(keep) = policy.invocation_logs {
+        let (stdout, stderr) = output
+            .as_ref()
+            .map(|(stdout, stderr)| (stdout.as_str(), stderr.as_str()))
+            .unwrap_or_default();
+        let error = match (&result, exit_code) {
+            (Err(e), None) => Some(e.to_string()),
+            _ => None,
+        };
+        let log = InvocationLog {
+            started_at,
+            duration: started.elapsed(),
+            command: cmd,
+            exit_code,
+            error: error.as_deref(),
+            stdout,
+            stderr,
+        };
+        match get_logs_dir().and_then(|dir| log.write(&dir, keep)) {
+            Ok(path) => record_log(path),
+            Err(e) => eprintln!("Warning: failed to write invocation log: {}", e),
+        }
+    }
+
+    if policy.audit {
+        let salt = if policy.hash_only {
+            match crate::history::redact::salt() {
+                Ok(salt) => Some(salt),
+                Err(e) => {
+                    eprintln!("Warning: failed to write audit log: {}", e);
+                    return (result, exit_code, output);
+                }
+            }
+        } else {
+            None
+        };
+        let mut entry = AuditEntry::new(
+            started_at,
+            started.elapsed(),
+            cmd,
+            args,
+            input,
+            salt,
+            access_mode,
+            exit_code,
+            &result,
+        );
+        if let Some(prompt) = prompt_in_args {
+            entry.redact_prompt_args(args, prompt, salt);
+        }
+        if let Err(e) = AuditLog::open().and_then(|log| log.append(&entry)) {
+            eprintln!("Warning: failed to write audit log: {}", e);
+        }
+    }
+
+    // The invocation log keeps the raw output; callers get it normalized
+    let result = result.map(|stdout| normalize_output(&stdout));
+    let output =
+        output.map(|(stdout, stderr)| (normalize_output(&stdout), normalize_output(&stderr)));
+    (result, exit_code, output)
+}
+
+/// Spawn a child process and wait for it, returning its exit code and
+/// captured stdout/stderr if it ran to completion
+async fn spawn_and_wait(cmd: &str, args: &[&str], input: &str, timeout: u64) -> RunOutcome {
+    // Respect global and per-binary concurrency limits
+    let _slot = super::policy::acquire_slot(cmd).await;
+    // Run time starts once a slot is free, for the latency history
+    let started = std::time::Instant::now();
+
+    let policy = super::policy::policy();
+    let (program, program_args) = policy.wrap_command(cmd, args);
+    let mut command = child_command(&program, &program_args);
+
+    // Redirect HOME/XDG dirs into a scratch sandbox when isolation is on.
+    // The sandbox is removed when this guard drops after the child exits.
+    let _sandbox = if policy.isolate {
+        let sandbox = crate::config::ConfigManager::get_home_dir()
+            .and_then(|home| IsolatedHome::create(&home, &policy.credential_files(cmd)));
+        match sandbox {
+            Ok(sandbox) => {
+                sandbox.apply(&mut command);
+                Some(sandbox)
+            }
+            Err(e) => return (Err(e), None, None),
+        }
+    } else {
+        None
+    };
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return (Err(e.into()), None, None),
+    };
+
+    // Kill the process group on timeout, or if this future is dropped
+    // before the child exits (e.g. a straggler cancelled at a round deadline)
+    let mut group = ProcessGroupGuard::new(child.id());
+
+    // Write input to stdin. A child that exits without reading it (a
+    // context provider that ignores the request) closes the pipe early;
+    // its exit status still decides the outcome.
+    if let Some(mut stdin) = child.stdin.take() {
+        use tokio::io::AsyncWriteExt;
+        if let Err(e) = stdin.write_all(input.as_bytes()).await {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return (Err(e.into()), None, None);
+            }
+        }
+    }
+
+    // Wait for completion with timeout. Output is read alongside the wait
+    // so a chatty child never blocks on a full pipe, and anything past the
+    // cap is drained and dropped instead of buffered. The buffers are
+    // owned here, so what arrived before a timeout is still there for the
+    // invocation log.
+    let timeout_duration = std::time::Duration::from_secs(timeout);
+    let cap = policy.max_output_bytes;
+    let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+    let (mut stdout_buf, mut stderr_buf) = (CappedOutput::default(), CappedOutput::default());
+    let output = async {
+        tokio::join!(
+            stdout_buf.read(stdout, cap),
+            stderr_buf.read(stderr, cap),
+            child.wait()
+        )
+    };
+
+    let finished = tokio::select! {
+        (stdout, stderr, status) = output => Some((stdout, stderr, status)),
+        _ = tokio::time::sleep(timeout_duration) => None,
+    };
+    let captured = (stdout_buf.text(), stderr_buf.text());
+    match finished {
+        Some((stdout, stderr, status)) => {
+            group.disarm();
+            add_run_time(started.elapsed());
+            match (stdout, stderr, status) {
+                (Ok(()), Ok(()), Ok(status)) => {
+                    let exit_code = status.code();
+                    let (stdout, stderr) = captured;
+                    let result = if status.success() {
+                        Ok(stdout.clone())
+                    } else {
+                        Err(anyhow::anyhow!("Command failed: {}", stderr))
+                    };
+                    (result, exit_code, Some((stdout, stderr)))
+                }
+                (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                    (Err(e.into()), None, Some(captured))
+                }
+            }
+        }
+        // The guard kills the process group as it drops
+        None => (
+            Err(anyhow::anyhow!(
+                "Command timed out after {} seconds",
+                timeout
+            )),
+            None,
+            Some(captured),
+        ),
+    }
+}
+
+/// A child process with piped stdio, the environment every CLI gets, and
+/// its own session
+pub(super) fn child_command(program: &str, args: &[String]) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new(program);
     command.args(args);
     command.stdin(Stdio::piped());
     command.stdout(Stdio::piped());
@@ -26,6 +335,11 @@ pub async fn execute_command(
         command.env_remove(var);
     }
 
+    // Give children a UTF-8 locale so non-ASCII text survives
+    for (var, value) in utf8_locale_env(|name| std::env::var(name).ok()) {
+        command.env(var, value);
+    }
+
     // Create a new session so the child is a process group leader,
     // isolated from the parent's terminal. This also lets us kill
     // the entire process group on timeout.
@@ -37,71 +351,121 @@ pub async fn execute_command(
         });
     }
 
-    let mut child = command.spawn()?;
+    command
+}
 
-    // Save the PID before child is consumed by wait_with_output
-    let pid = child.id();
+/// Kills a child's process group when dropped, unless disarmed after the
+/// child exited
+pub(super) struct ProcessGroupGuard {
+    pid: Option<u32>,
+}
 
-    // Write input to stdin
-    if let Some(mut stdin) = child.stdin.take() {
-        use tokio::io::AsyncWriteExt;
-        stdin.write_all(input.as_bytes()).await?;
+impl ProcessGroupGuard {
+    pub(super) fn new(pid: Option<u32>) -> Self {
+        Self { pid }
     }
 
-    // Wait for completion with timeout
-    let timeout_duration = std::time::Duration::from_secs(timeout);
-
-    tokio::select! {
-        result = child.wait_with_output() => {
-            match result {
-                Ok(output) => {
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    fn disarm(&mut self) {
+        self.pid = None;
+    }
+}
 
-                    if output.status.success() {
-                        Ok(stdout)
-                    } else {
-                        Err(anyhow::anyhow!("Command failed: {}", stderr))
-                    }
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        // Kill the entire process group (negative PID) to prevent
+        // orphaned child processes (e.g. claude spawning node, etc.)
+        if let Some(child_pid) = self.pid.take() {
+            #[cfg(unix)]
+            {
+                let pgid = -(child_pid as i32);
+                unsafe {
+                    libc::kill(pgid, libc::SIGTERM);
+                    // Brief pause then SIGKILL to ensure cleanup
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                    libc::kill(pgid, libc::SIGKILL);
                 }
-                Err(e) => Err(e.into()),
+            }
+            #[cfg(windows)]
+            {
+                let _ = child_pid;
+                // TODO(windows): Process termination is not implemented on Windows.
+                // The child process will NOT be killed on timeout, which may leave
+                // orphaned AI CLI processes. A proper fix requires either the
+                // `windows-sys` crate (TerminateProcess) or Tokio's Child::kill().
             }
         }
-        _ = tokio::time::sleep(timeout_duration) => {
-            // Kill the entire process group (negative PID) to prevent
-            // orphaned child processes (e.g. claude spawning node, etc.)
-            if let Some(child_pid) = pid {
-                #[cfg(unix)]
-                {
-                    let pgid = -(child_pid as i32);
-                    unsafe {
-                        libc::kill(pgid, libc::SIGTERM);
-                        // Brief pause then SIGKILL to ensure cleanup
-                        std::thread::sleep(std::time::Duration::from_millis(100));
-                        libc::kill(pgid, libc::SIGKILL);
-                    }
-                }
-                #[cfg(windows)]
-                {
-                    let _ = child_pid;
-                    // TODO(windows): Process termination is not implemented on Windows.
-                    // The child process will NOT be killed on timeout, which may leave
-                    // orphaned AI CLI processes. A proper fix requires either the
-                    // `windows-sys` crate (TerminateProcess) or Tokio's Child::kill().
-                }
+    }
+}
+
+/// Output of one stream, keeping at most a capped number of bytes
+#[derive(Default)]
+struct CappedOutput {
+    kept: Vec<u8>,
+    omitted: u64,
+}
+
+impl CappedOutput {
+    /// Read a child's output stream to the end, keeping at most `cap`
+    /// bytes. The rest is read and discarded so the child can finish.
+    async fn read<R>(&mut self, reader: Option<R>, cap: Option<usize>) -> std::io::Result<()>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
+
+        let Some(mut reader) = reader else {
+            return Ok(());
+        };
+        let cap = cap.unwrap_or(usize::MAX);
+        let mut chunk = [0u8; 8192];
+        loop {
+            let n = reader.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(());
             }
-            Err(anyhow::anyhow!("Command timed out after {} seconds", timeout))
+            let keep = n.min(cap - self.kept.len());
+            self.kept.extend_from_slice(&chunk[..keep]);
+            self.omitted += (n - keep) as u64;
         }
     }
+
+    /// What was kept, with a marker noting how much was dropped
+    fn text(&self) -> String {
+        let mut output = String::from_utf8_lossy(&self.kept).into_owned();
+        if self.omitted > 0 {
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&format!(
+                "[output truncated: {} bytes omitted]\n",
+                self.omitted
+            ));
+        }
+        output
+    }
+}
+
+/// Read a child's output stream to the end, keeping at most `cap` bytes
+#[cfg(test)]
+async fn read_capped<R>(reader: Option<R>, cap: Option<usize>) -> std::io::Result<String>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut output = CappedOutput::default();
+    output.read(reader, cap).await?;
+    Ok(output.text())
 }
 
 /// Check if a command exists in PATH
 pub fn command_exists(cmd: &str) -> bool {
-    Command::new("which")
-        .arg(cmd)
-        .output()
-        .ok()
-        .is_some_and(|o| o.status.success())
+    let mut command = Command::new("which");
+    command.arg(cmd);
+    let audit = ProcessAudit::start(&command);
+    let Ok(output) = command.output() else {
+        return false;
+    };
+    audit.finish(&output.status);
+    output.status.success()
 }
 
 #[cfg(test)]
@@ -110,14 +474,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_execute_command_basic() {
-        let result = execute_command("echo", &["hello world"], "", 5).await;
+        let result = execute_command("echo", &["hello world"], "", 5, AccessMode::ReadOnly).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().trim(), "hello world");
     }
 
     #[tokio::test]
     async fn test_execute_command_timeout() {
-        let result = execute_command("sleep", &["30"], "", 1).await;
+        let result = execute_command("sleep", &["30"], "", 1, AccessMode::ReadOnly).await;
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(
@@ -126,13 +490,28 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_read_capped_truncates_with_marker() {
+        let data = "x".repeat(20_000);
+        let output = read_capped(Some(data.as_bytes()), Some(10)).await.unwrap();
+        assert_eq!(
+            output,
+            "xxxxxxxxxx\n[output truncated: 19990 bytes omitted]\n"
+        );
+
+        let output = read_capped(Some(&b"short"[..]), Some(10)).await.unwrap();
+        assert_eq!(output, "short");
+        let output = read_capped(Some(data.as_bytes()), None).await.unwrap();
+        assert_eq!(output.len(), 20_000);
+    }
+
     #[tokio::test]
     async fn test_claude_env_vars_not_inherited() {
         // Set the nesting env vars in our process
         std::env::set_var("CLAUDECODE", "1");
         std::env::set_var("CLAUDE_CODE_ENTRYPOINT", "cli");
 
-        let result = execute_command("env", &[], "", 5).await;
+        let result = execute_command("env", &[], "", 5, AccessMode::ReadOnly).await;
         assert!(result.is_ok());
         let output = result.unwrap();
 
@@ -0,0 +1,106 @@
+//! Circuit breaker - Stop calling a CLI that keeps failing
+//!
+//! Failures are counted per CLI across the whole process. After
+//! `circuit_breaker_threshold` failures in a row, the CLI's circuit opens:
+//! later calls fail at once with the reason instead of waiting out another
+//! timeout, or go to the CLI's entry in `fallbacks` when that one's circuit
+//! is still closed. An open circuit stays open for the rest of the command;
+//! `lsp-bridge` closes every circuit between requests unless started with
+//! `--keep-circuits`.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Failures of one CLI
+#[derive(Debug, Clone, Default)]
+struct Circuit {
+    /// Failures since the last success
+    failures: usize,
+    /// Message of the most recent failure
+    last_error: String,
+}
+
+/// Circuits for every CLI called so far
+#[derive(Debug, Default)]
+pub struct Circuits {
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl Circuits {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Circuit>> {
+        self.circuits.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Fail if `cli` has failed `threshold` times in a row
+    pub fn check(&self, cli: &str, threshold: usize) -> anyhow::Result<()> {
+        match self.lock().get(cli) {
+            Some(circuit) if circuit.failures >= threshold => anyhow::bail!(
+                "Skipping {}: it failed {} time(s) in a row (last: {}). \
+                 Its circuit stays open for the rest of this run.",
+                cli,
+                circuit.failures,
+                circuit.last_error
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `cli` can still be called
+    pub fn is_closed(&self, cli: &str, threshold: usize) -> bool {
+        self.check(cli, threshold).is_ok()
+    }
+
+    /// Count a call's outcome. Returns true when this failure opened the
+    /// circuit.
+    pub fn record<T>(&self, cli: &str, threshold: usize, result: &anyhow::Result<T>) -> bool {
+        let mut circuits = self.lock();
+        match result {
+            Ok(_) => {
+                circuits.remove(cli);
+                false
+            }
+            Err(e) => {
+                let circuit = circuits.entry(cli.to_string()).or_default();
+                circuit.failures += 1;
+                circuit.last_error = e.to_string().lines().next().unwrap_or_default().to_string();
+                circuit.failures == threshold
+            }
+        }
+    }
+
+    /// Close every circuit
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+}
+
+static CIRCUITS: OnceLock<Circuits> = OnceLock::new();
+
+/// The process-wide circuits
+pub fn circuits() -> &'static Circuits {
+    CIRCUITS.get_or_init(Circuits::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_opens_after_threshold() {
+        let circuits = Circuits::default();
+        let failure: anyhow::Result<()> = Err(anyhow::anyhow!("gemini timed out after 120s"));
+        assert!(!circuits.record("gemini", 2, &failure));
+        assert!(circuits.is_closed("gemini", 2));
+        // A success in between starts the count over
+        circuits.record("gemini", 2, &Ok(()));
+        assert!(!circuits.record("gemini", 2, &failure));
+        assert!(circuits.record("gemini", 2, &failure));
+
+        let error = circuits.check("gemini", 2).unwrap_err().to_string();
+        assert!(error.contains("failed 2 time(s) in a row (last: gemini timed out after 120s)"));
+        assert!(circuits.is_closed("claude", 2));
+
+        circuits.reset();
+        assert!(circuits.is_closed("gemini", 2));
+    }
+}
@@ -1,11 +1,41 @@
 //! Claude Code CLI invoker
 
-use super::base::{command_exists, execute_command};
-use super::{AccessMode, Invoker};
+use super::base::{command_exists, configured_cli, execute_command};
+use super::detect::check_write_support;
+use super::{AccessMode, Invoker, NativeReply};
 use async_trait::async_trait;
+use serde::Deserialize;
 
 #[derive(Clone)]
-pub struct ClaudeInvoker;
+pub struct ClaudeInvoker {
+    /// Command or detected path of the `claude` binary
+    command: String,
+    /// Extra arguments from the `clis.claude` config entry
+    extra_args: Vec<String>,
+}
+
+/// `claude -p --output-format json` output
+#[derive(Debug, Deserialize)]
+struct JsonResult {
+    #[serde(default)]
+    result: String,
+    session_id: String,
+    #[serde(default)]
+    is_error: bool,
+}
+
+/// Parse `claude -p --output-format json` output
+fn parse_json_result(output: &str) -> anyhow::Result<NativeReply> {
+    let parsed: JsonResult = serde_json::from_str(output.trim())
+        .map_err(|e| anyhow::anyhow!("claude did not return a JSON result ({}): {}", e, output))?;
+    if parsed.is_error {
+        anyhow::bail!("claude reported an error: {}", parsed.result);
+    }
+    Ok(NativeReply {
+        response: parsed.result,
+        conversation_id: parsed.session_id,
+    })
+}
 
 #[async_trait]
 impl Invoker for ClaudeInvoker {
@@ -16,6 +46,66 @@ impl Invoker for ClaudeInvoker {
         access_mode: AccessMode,
         model: Option<&str>,
     ) -> anyhow::Result<String> {
+        let args = self.args(access_mode, model);
+        self.check_access_mode(access_mode)?;
+        execute_command(&self.command, &args, prompt, timeout, access_mode).await
+    }
+
+    fn supports_native_sessions(&self) -> bool {
+        true
+    }
+
+    async fn invoke_native(
+        &self,
+        prompt: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+        model: Option<&str>,
+        conversation: Option<&str>,
+    ) -> anyhow::Result<NativeReply> {
+        let mut args = self.args(access_mode, model);
+        args.extend_from_slice(&["--output-format", "json"]);
+        if let Some(id) = conversation {
+            args.extend_from_slice(&["--resume", id]);
+        }
+        self.check_access_mode(access_mode)?;
+        let output = execute_command(&self.command, &args, prompt, timeout, access_mode).await?;
+        parse_json_result(&output)
+    }
+
+    fn check_access_mode(&self, access_mode: AccessMode) -> anyhow::Result<()> {
+        match access_mode {
+            AccessMode::ReadOnly => Ok(()),
+            AccessMode::WorkspaceWrite => check_write_support(self.name(), &self.command),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "claude"
+    }
+
+    async fn is_available(&self) -> bool {
+        command_exists(&self.command)
+    }
+}
+
+impl Default for ClaudeInvoker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClaudeInvoker {
+    pub fn new() -> Self {
+        let (command, extra_args) = configured_cli("claude");
+        Self {
+            command,
+            extra_args,
+        }
+    }
+
+    /// Arguments for a print-mode invocation
+    fn args<'a>(&'a self, access_mode: AccessMode, model: Option<&'a str>) -> Vec<&'a str> {
         let mut args: Vec<&str> = vec!["-p"];
 
         // Add model if specified
@@ -35,26 +125,31 @@ impl Invoker for ClaudeInvoker {
             }
         };
 
-        execute_command("claude", &args, prompt, timeout).await
-    }
-
-    fn name(&self) -> &str {
-        "claude"
-    }
-
-    fn is_available(&self) -> bool {
-        command_exists("claude")
+        args.extend(self.extra_args.iter().map(String::as_str));
+        args
     }
 }
 
-impl Default for ClaudeInvoker {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl ClaudeInvoker {
-    pub fn new() -> Self {
-        ClaudeInvoker
+    #[test]
+    fn test_parse_json_result() {
+        let output = r#"{"type":"result","subtype":"success","is_error":false,
+            "result":"Hello","session_id":"3f1c-77aa","total_cost_usd":0.01}"#;
+        assert_eq!(
+            parse_json_result(output).unwrap(),
+            NativeReply {
+                response: "Hello".to_string(),
+                conversation_id: "3f1c-77aa".to_string(),
+            }
+        );
+        let error = r#"{"is_error":true,"result":"No conversation found","session_id":"x"}"#;
+        assert!(parse_json_result(error)
+            .unwrap_err()
+            .to_string()
+            .contains("No conversation found"));
+        assert!(parse_json_result("plain text").is_err());
     }
 }
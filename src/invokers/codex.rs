@@ -1,11 +1,17 @@
 //! Codex CLI invoker
 
-use super::base::{command_exists, execute_command};
+use super::base::{command_exists, configured_cli, execute_command};
+use super::detect::check_write_support;
 use super::{AccessMode, Invoker};
 use async_trait::async_trait;
 
 #[derive(Clone)]
-pub struct CodexInvoker;
+pub struct CodexInvoker {
+    /// Command or detected path of the `codex` binary
+    command: String,
+    /// Extra arguments from the `clis.codex` config entry
+    extra_args: Vec<String>,
+}
 
 #[async_trait]
 impl Invoker for CodexInvoker {
@@ -35,15 +41,25 @@ impl Invoker for CodexInvoker {
             }
         };
 
-        execute_command("codex", &args, prompt, timeout).await
+        args.extend(self.extra_args.iter().map(String::as_str));
+
+        self.check_access_mode(access_mode)?;
+        execute_command(&self.command, &args, prompt, timeout, access_mode).await
+    }
+
+    fn check_access_mode(&self, access_mode: AccessMode) -> anyhow::Result<()> {
+        match access_mode {
+            AccessMode::ReadOnly => Ok(()),
+            AccessMode::WorkspaceWrite => check_write_support(self.name(), &self.command),
+        }
     }
 
     fn name(&self) -> &str {
         "codex"
     }
 
-    fn is_available(&self) -> bool {
-        command_exists("codex")
+    async fn is_available(&self) -> bool {
+        command_exists(&self.command)
     }
 }
 
@@ -55,6 +71,10 @@ impl Default for CodexInvoker {
 
 impl CodexInvoker {
     pub fn new() -> Self {
-        CodexInvoker
+        let (command, extra_args) = configured_cli("codex");
+        Self {
+            command,
+            extra_args,
+        }
     }
 }
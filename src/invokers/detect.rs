@@ -0,0 +1,258 @@
+//! Built-in CLI detection
+//!
+//! Detection resolves each built-in CLI's path, version, and the flags the
+//! invokers depend on, and records them in `ConfigManager.clis` so later
+//! invocations use the detected binary. Write-mode flags are probed again
+//! before a CLI's first `--write` invocation, since an older version that
+//! rejects them would otherwise fail with its own usage error.
+
+use crate::audit::ProcessAudit;
+use crate::config::{CliConfig, ConfigManager};
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Names of the built-in CLIs
+pub const BUILTIN_CLIS: &[&str] = &["claude", "codex", "gemini"];
+
+/// Maximum time a `--version`/`--help` probe may run
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Flags each built-in invoker passes to its CLI
+pub fn invoker_flags(cli: &str) -> &'static [&'static str] {
+    match cli {
+        "claude" => &["-p", "--model", "--tools", "--allowed-tools"],
+        "codex" => &["--model", "--sandbox", "--cd"],
+        "gemini" => &[
+            "--model",
+            "--sandbox",
+            "--include-directories",
+            "--approval-mode",
+        ],
+        _ => &[],
+    }
+}
+
+/// Flags, and values of those flags, each built-in invoker adds for `--write`
+pub fn write_mode_args(cli: &str) -> &'static [&'static str] {
+    match cli {
+        "claude" => &["--tools", "--allowed-tools"],
+        "codex" => &["--sandbox", "workspace-write"],
+        "gemini" => &["--approval-mode", "auto_edit"],
+        _ => &[],
+    }
+}
+
+/// Write-mode arguments missing from a CLI's help text. Flags must appear
+/// as words; values may appear anywhere (help often quotes them).
+pub fn missing_write_args(help: &str, cli: &str) -> Vec<&'static str> {
+    let wanted = write_mode_args(cli);
+    let flags: Vec<&str> = wanted
+        .iter()
+        .copied()
+        .filter(|a| a.starts_with('-'))
+        .collect();
+    let supported = supported_flags(help, &flags);
+    wanted
+        .iter()
+        .copied()
+        .filter(|arg| {
+            if arg.starts_with('-') {
+                !supported.iter().any(|s| s == arg)
+            } else {
+                !help.contains(arg)
+            }
+        })
+        .collect()
+}
+
+/// Results of write-mode probes, by command
+static WRITE_PROBES: OnceLock<Mutex<HashMap<String, Result<(), String>>>> = OnceLock::new();
+
+/// Check that the installed `cli` (run as `command`) accepts the arguments
+/// its invoker passes for `--write`. Probed once per command per process;
+/// a CLI whose help can't be read is assumed to accept them.
+pub fn check_write_support(cli: &str, command: &str) -> anyhow::Result<()> {
+    let mut probes = WRITE_PROBES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let result = probes
+        .entry(command.to_string())
+        .or_insert_with(|| {
+            let help = probe(command, "--help").unwrap_or_default();
+            let missing = missing_write_args(&help, cli);
+            if help.trim().is_empty() || missing.is_empty() {
+                return Ok(());
+            }
+            let version = probe(command, "--version")
+                .and_then(|out| {
+                    out.lines()
+                        .map(str::trim)
+                        .find(|l| !l.is_empty())
+                        .map(str::to_string)
+                })
+                .map(|v| format!(" ({})", v))
+                .unwrap_or_default();
+            Err(format!(
+                "{} at {}{} doesn't accept {}, which --write needs. \
+                 Upgrade {}, or run without --write for read-only access.",
+                cli,
+                command,
+                version,
+                missing.join(" "),
+                cli
+            ))
+        })
+        .clone();
+    result.map_err(anyhow::Error::msg)
+}
+
+/// Detect a built-in CLI, preserving user-set `invoke_args` from `existing`
+pub fn detect_cli(cli: &str, existing: Option<&CliConfig>) -> CliConfig {
+    let invoke_args = existing.map(|c| c.invoke_args.clone()).unwrap_or_default();
+
+    let Some(path) = resolve_path(cli) else {
+        return CliConfig {
+            command: cli.to_string(),
+            invoke_args,
+            detected: false,
+            version: None,
+            flags: Vec::new(),
+        };
+    };
+
+    let version = probe(&path, "--version").and_then(|out| {
+        out.lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .map(str::to_string)
+    });
+    let help = probe(&path, "--help").unwrap_or_default();
+
+    CliConfig {
+        command: path,
+        invoke_args,
+        detected: true,
+        version,
+        flags: supported_flags(&help, invoker_flags(cli)),
+    }
+}
+
+/// Detect all built-in CLIs and persist the results in the config file
+pub fn detect_and_store() -> anyhow::Result<Vec<(String, CliConfig)>> {
+    let mut config = ConfigManager::new()?;
+    let mut detected = Vec::new();
+    for cli in BUILTIN_CLIS {
+        let entry = detect_cli(cli, config.clis.get(*cli));
+        config.clis.insert(cli.to_string(), entry.clone());
+        detected.push((cli.to_string(), entry));
+    }
+    config.save()?;
+    Ok(detected)
+}
+
+/// Flags from `wanted` that appear in a CLI's help text
+pub fn supported_flags(help: &str, wanted: &[&str]) -> Vec<String> {
+    wanted
+        .iter()
+        .filter(|flag| {
+            help.split(|c: char| c.is_whitespace() || c == ',' || c == '=' || c == '[')
+                .any(|word| word == **flag)
+        })
+        .map(|flag| flag.to_string())
+        .collect()
+}
+
+/// Absolute path of a command found in PATH
+fn resolve_path(cmd: &str) -> Option<String> {
+    let mut command = Command::new("which");
+    command.arg(cmd);
+    let audit = ProcessAudit::start(&command);
+    let output = command.output().ok()?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        return None;
+    }
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!path.is_empty()).then_some(path)
+}
+
+/// Run `cmd arg` with a short timeout and return its combined output
+fn probe(cmd: &str, arg: &str) -> Option<String> {
+    let mut command = Command::new(cmd);
+    command
+        .arg(arg)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let audit = ProcessAudit::start(&command);
+    let mut child = command.spawn().ok()?;
+
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                audit.finish(&status);
+                break;
+            }
+            Ok(None) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_flags() {
+        let help =
+            "Usage: claude [options]\n  -p, --print  Print\n  --model <model>\n  --tools=LIST";
+        let flags = supported_flags(help, invoker_flags("claude"));
+        assert_eq!(flags, vec!["-p", "--model", "--tools"]);
+    }
+
+    #[test]
+    fn test_missing_write_args() {
+        let help = "  --sandbox <SANDBOX_MODE>  [possible values: read-only, workspace-write]";
+        assert!(missing_write_args(help, "codex").is_empty());
+        assert_eq!(
+            missing_write_args("  --sandbox <MODE>  [possible values: read-only]", "codex"),
+            vec!["workspace-write"]
+        );
+        let help = r#"  --approval-mode  [choices: "default", "auto_edit", "yolo"]"#;
+        assert!(missing_write_args(help, "gemini").is_empty());
+        assert_eq!(
+            missing_write_args("  -p, --print\n  --allowedTools", "claude"),
+            vec!["--tools", "--allowed-tools"]
+        );
+    }
+
+    #[test]
+    fn test_detect_missing_cli_keeps_invoke_args() {
+        let existing = CliConfig {
+            command: "gptengage-no-such-cli".to_string(),
+            invoke_args: vec!["--verbose".to_string()],
+            detected: true,
+            version: None,
+            flags: Vec::new(),
+        };
+        let detected = detect_cli("gptengage-no-such-cli", Some(&existing));
+        assert!(!detected.detected);
+        assert_eq!(detected.invoke_args, vec!["--verbose"]);
+    }
+}
@@ -0,0 +1,200 @@
+//! Prompt dialects - Scaffolding markup tailored to each CLI
+//!
+//! gptengage marks up the parts of a prompt (persona, piped context,
+//! phases, candidates, ...) as `[LABEL]` ... `[/LABEL]` blocks. Not every
+//! model reads that markup equally well, so each CLI can be given its own
+//! dialect, set in config (`dialects`) or in a plugin's `[invoke]`
+//! section. Prompts are rewritten into the dialect just before they are
+//! sent:
+//!
+//! - `brackets` (default): `[AGENT CONTEXT]` ... `[/AGENT CONTEXT]`
+//! - `xml`: `<agent_context>` ... `</agent_context>`
+//! - `markdown`: a `## Agent context` header, with no closing marker
+//!
+//! Only markers ending a line and with both an opener and a closer in the
+//! prompt are rewritten, so a stray `[TODO]` in piped input is left alone.
+
+use std::collections::HashSet;
+
+/// Dialect names accepted in config and plugin files
+pub const DIALECTS: &[&str] = &["brackets", "xml", "markdown"];
+
+/// How a CLI's prompt scaffolding is marked up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dialect {
+    #[default]
+    Brackets,
+    Xml,
+    Markdown,
+}
+
+/// A `[LABEL]`, `[LABEL: value]`, or `[/LABEL]` ending a line
+struct Marker<'a> {
+    /// Text before the marker on its line (`Topic: ` in
+    /// `Topic: [PIPED CONTEXT]`)
+    prefix: &'a str,
+    closing: bool,
+    label: &'a str,
+    value: Option<&'a str>,
+}
+
+/// The marker ending `line`, if there is one
+fn marker(line: &str) -> Option<Marker<'_>> {
+    let line = line.trim_end();
+    let start = line.rfind('[')?;
+    let inner = line[start..].strip_prefix('[')?.strip_suffix(']')?;
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let (label, value) = match inner.split_once(": ") {
+        Some((label, value)) if !closing => (label, Some(value.trim())),
+        _ => (inner, None),
+    };
+    let well_formed = label.starts_with(|c: char| c.is_ascii_uppercase())
+        && label
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || " _-".contains(c));
+    well_formed.then_some(Marker {
+        prefix: &line[..start],
+        closing,
+        label,
+        value,
+    })
+}
+
+impl Dialect {
+    /// Parse a dialect name
+    pub fn parse(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "brackets" => Ok(Dialect::Brackets),
+            "xml" => Ok(Dialect::Xml),
+            "markdown" => Ok(Dialect::Markdown),
+            _ => anyhow::bail!(
+                "Unknown dialect '{}'. Use one of: {}",
+                name,
+                DIALECTS.join(", ")
+            ),
+        }
+    }
+
+    /// The dialect configured for `cli`: its `dialects` entry in config,
+    /// else its plugin's in `plugins`, else brackets
+    pub fn for_cli(cli: &str, plugins: Option<&crate::plugins::PluginManager>) -> Self {
+        let configured = crate::config::ConfigManager::shared()
+            .ok()
+            .and_then(|resolved| resolved.config.dialects.get(cli).cloned());
+        let declared = || plugins?.get_plugin(cli)?.invoke.dialect.clone();
+        configured
+            .or_else(declared)
+            .and_then(|name| Dialect::parse(&name).ok())
+            .unwrap_or_default()
+    }
+
+    /// `prompt` with its scaffolding rewritten into this dialect
+    pub fn adapt(self, prompt: &str) -> String {
+        if self == Dialect::Brackets {
+            return prompt.to_string();
+        }
+
+        // Labels marked both open and closed somewhere in the prompt
+        let mut opened = HashSet::new();
+        let mut closed = HashSet::new();
+        for marker in prompt.lines().filter_map(marker) {
+            if marker.closing {
+                closed.insert(marker.label);
+            } else {
+                opened.insert(marker.label);
+            }
+        }
+
+        let mut out = Vec::new();
+        for line in prompt.split('\n') {
+            let Some(marker) =
+                marker(line).filter(|m| opened.contains(m.label) && closed.contains(m.label))
+            else {
+                out.push(line.to_string());
+                continue;
+            };
+            let prefix = marker.prefix;
+            match (self, marker.closing) {
+                (Dialect::Xml, false) => {
+                    let tag = xml_tag(marker.label);
+                    out.push(match marker.value {
+                        Some(value) => format!(
+                            "{}<{} value=\"{}\">",
+                            prefix,
+                            tag,
+                            value.replace('"', "&quot;")
+                        ),
+                        None => format!("{}<{}>", prefix, tag),
+                    });
+                }
+                (Dialect::Xml, true) => out.push(format!("{}</{}>", prefix, xml_tag(marker.label))),
+                (Dialect::Markdown, closing) => {
+                    // Headers need a line of their own
+                    if !prefix.trim().is_empty() {
+                        out.push(prefix.trim_end().to_string());
+                    }
+                    // Markdown sections end at the next header
+                    if !closing {
+                        let title = sentence_case(marker.label);
+                        out.push(match marker.value {
+                            Some(value) => format!("## {}: {}", title, value),
+                            None => format!("## {}", title),
+                        });
+                    }
+                }
+                (Dialect::Brackets, _) => out.push(line.to_string()),
+            }
+        }
+        out.join("\n")
+    }
+}
+
+/// `AGENT CONTEXT` -> `agent_context`
+fn xml_tag(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| match c {
+            ' ' | '-' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// `AGENT CONTEXT` -> `Agent context`
+fn sentence_case(label: &str) -> String {
+    let lower = label.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROMPT: &str = "[AGENT CONTEXT]\nRole: CTO\n[/AGENT CONTEXT]\n\n\
+        Topic: [PIPED CONTEXT]\nlog\n[/PIPED CONTEXT]\n\n\
+        [PHASE: DIVERGE]\nExplore.\n[/PHASE]\n\nSee [TODO]";
+
+    #[test]
+    fn test_adapt_dialects() {
+        assert_eq!(Dialect::Brackets.adapt(PROMPT), PROMPT);
+        assert_eq!(
+            Dialect::Xml.adapt(PROMPT),
+            "<agent_context>\nRole: CTO\n</agent_context>\n\n\
+             Topic: <piped_context>\nlog\n</piped_context>\n\n\
+             <phase value=\"DIVERGE\">\nExplore.\n</phase>\n\nSee [TODO]"
+        );
+        assert_eq!(
+            Dialect::Markdown.adapt(PROMPT),
+            "## Agent context\nRole: CTO\n\nTopic:\n## Piped context\nlog\n\n\
+             ## Phase: DIVERGE\nExplore.\n\nSee [TODO]"
+        );
+        assert!(Dialect::parse("yaml").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Encoding - UTF-8 locale for child CLIs and normalized captured output
+//!
+//! CLIs started under a C/POSIX locale (common in containers and CI) may
+//! print non-ASCII text as `?` or fail outright, and some emit CRLF line
+//! endings or ANSI escape sequences even when not attached to a terminal.
+//! Children get a UTF-8 character locale when theirs isn't one, and their
+//! output is normalized before it reaches transcripts and sessions.
+
+use crate::utils::sanitize::sanitize;
+
+/// Locale used when the inherited one isn't UTF-8
+#[cfg(target_os = "macos")]
+const UTF8_LOCALE: &str = "en_US.UTF-8";
+#[cfg(not(target_os = "macos"))]
+const UTF8_LOCALE: &str = "C.UTF-8";
+
+fn is_utf8(locale: &str) -> bool {
+    let lower = locale.to_ascii_lowercase();
+    lower.contains("utf-8") || lower.contains("utf8")
+}
+
+/// Environment overrides giving a child a UTF-8 character locale, given a
+/// lookup of the current environment. Empty when the locale already is
+/// UTF-8. Only character handling changes, not the message language.
+pub fn utf8_locale_env(env: impl Fn(&str) -> Option<String>) -> Vec<(&'static str, String)> {
+    let set = |name: &str| env(name).filter(|value| !value.is_empty());
+    // LC_ALL overrides LC_CTYPE, which overrides LANG
+    let lc_all = set("LC_ALL");
+    let effective = lc_all
+        .clone()
+        .or_else(|| set("LC_CTYPE"))
+        .or_else(|| set("LANG"));
+    let mut overrides = Vec::new();
+    if !effective.as_deref().is_some_and(is_utf8) {
+        let var = if lc_all.is_some() {
+            "LC_ALL"
+        } else {
+            "LC_CTYPE"
+        };
+        overrides.push((var, UTF8_LOCALE.to_string()));
+    }
+    // Python CLIs pick their stdio encoding from this rather than the locale
+    if set("PYTHONIOENCODING").is_none() {
+        overrides.push(("PYTHONIOENCODING", "utf-8".to_string()));
+    }
+    overrides
+}
+
+/// Normalize captured output: CRLF line endings become LF, then terminal
+/// escapes, overwritten text, and spinners are cleaned up (see
+/// [`sanitize`](crate::utils::sanitize::sanitize))
+pub fn normalize_output(text: &str) -> String {
+    sanitize(&text.replace("\r\n", "\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_utf8_locale_env() {
+        // No locale at all, as in many containers
+        let overrides = utf8_locale_env(env(&[]));
+        assert_eq!(overrides[0], ("LC_CTYPE", UTF8_LOCALE.to_string()));
+        assert_eq!(overrides[1], ("PYTHONIOENCODING", "utf-8".to_string()));
+
+        let utf8 = [("LANG", "en_GB.UTF-8"), ("PYTHONIOENCODING", "utf-8")];
+        assert!(utf8_locale_env(env(&utf8)).is_empty());
+
+        // A non-UTF-8 LC_ALL would override LC_CTYPE, so it is replaced
+        let c = [
+            ("LANG", "en_US.utf8"),
+            ("LC_ALL", "C"),
+            ("PYTHONIOENCODING", "x"),
+        ];
+        assert_eq!(
+            utf8_locale_env(env(&c)),
+            vec![("LC_ALL", UTF8_LOCALE.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_normalize_output() {
+        assert_eq!(normalize_output("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(
+            normalize_output("\x1b[32m⠋ Working...\r\x1b[2K\rDone\x1b[0m\r\n"),
+            "Done\n"
+        );
+    }
+}
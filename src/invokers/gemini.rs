@@ -1,11 +1,17 @@
 //! Gemini CLI invoker
 
-use super::base::{command_exists, execute_command};
+use super::base::{command_exists, configured_cli, execute_command};
+use super::detect::check_write_support;
 use super::{AccessMode, Invoker};
 use async_trait::async_trait;
 
 #[derive(Clone)]
-pub struct GeminiInvoker;
+pub struct GeminiInvoker {
+    /// Command or detected path of the `gemini` binary
+    command: String,
+    /// Extra arguments from the `clis.gemini` config entry
+    extra_args: Vec<String>,
+}
 
 #[async_trait]
 impl Invoker for GeminiInvoker {
@@ -41,15 +47,25 @@ impl Invoker for GeminiInvoker {
             }
         };
 
-        execute_command("gemini", &args, prompt, timeout).await
+        args.extend(self.extra_args.iter().map(String::as_str));
+
+        self.check_access_mode(access_mode)?;
+        execute_command(&self.command, &args, prompt, timeout, access_mode).await
+    }
+
+    fn check_access_mode(&self, access_mode: AccessMode) -> anyhow::Result<()> {
+        match access_mode {
+            AccessMode::ReadOnly => Ok(()),
+            AccessMode::WorkspaceWrite => check_write_support(self.name(), &self.command),
+        }
     }
 
     fn name(&self) -> &str {
         "gemini"
     }
 
-    fn is_available(&self) -> bool {
-        command_exists("gemini")
+    async fn is_available(&self) -> bool {
+        command_exists(&self.command)
     }
 }
 
@@ -61,6 +77,10 @@ impl Default for GeminiInvoker {
 
 impl GeminiInvoker {
     pub fn new() -> Self {
-        GeminiInvoker
+        let (command, extra_args) = configured_cli("gemini");
+        Self {
+            command,
+            extra_args,
+        }
     }
 }
@@ -0,0 +1,126 @@
+//! Isolated HOME/XDG sandbox for child CLI processes
+//!
+//! With `--isolate`, each child runs with a scratch HOME and XDG base
+//! directories so it cannot write state into the user's real config.
+//! Only credential files are copied in, so the CLI can still authenticate.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// XDG base directory variables redirected into the sandbox
+const XDG_DIRS: &[(&str, &str)] = &[
+    ("XDG_CONFIG_HOME", ".config"),
+    ("XDG_DATA_HOME", ".local/share"),
+    ("XDG_STATE_HOME", ".local/state"),
+    ("XDG_CACHE_HOME", ".cache"),
+];
+
+/// Credential files (relative to HOME) copied for built-in CLIs
+pub fn default_credential_files(cli: &str) -> Vec<String> {
+    let files: &[&str] = match cli {
+        "claude" => &[".claude.json", ".claude/.credentials.json"],
+        "codex" => &[".codex/auth.json", ".codex/config.toml"],
+        "gemini" => &[".gemini/oauth_creds.json", ".gemini/settings.json"],
+        _ => &[],
+    };
+    files.iter().map(|f| f.to_string()).collect()
+}
+
+/// A scratch HOME directory removed when dropped
+pub struct IsolatedHome {
+    root: TempDir,
+}
+
+impl IsolatedHome {
+    /// Create a sandbox and copy the given credential files into it.
+    ///
+    /// Missing credential files are skipped; paths escaping HOME are rejected.
+    /// The sandbox gets a random name and is created exclusively with mode
+    /// 0700, so no other user can pre-create it or read the copied files.
+    pub fn create(real_home: &Path, credential_files: &[String]) -> Result<Self> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("gptengage-isolate-");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            builder.permissions(std::fs::Permissions::from_mode(0o700));
+        }
+        let root = builder.tempdir().context("Failed to create sandbox")?;
+        let sandbox = Self { root };
+
+        for (_, dir) in XDG_DIRS {
+            std::fs::create_dir_all(sandbox.path().join(dir))?;
+        }
+
+        for file in credential_files {
+            if file.split(['/', '\\']).any(|part| part == "..") || Path::new(file).is_absolute() {
+                anyhow::bail!("Credential path '{}' must be relative to HOME", file);
+            }
+            let source = real_home.join(file);
+            if !source.is_file() {
+                continue;
+            }
+            let target = sandbox.path().join(file);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&source, &target)
+                .with_context(|| format!("Failed to copy credential {}", source.display()))?;
+        }
+
+        Ok(sandbox)
+    }
+
+    /// Path of the sandbox HOME
+    pub fn path(&self) -> &Path {
+        self.root.path()
+    }
+
+    /// Point HOME and the XDG base directories of a command at the sandbox
+    pub fn apply(&self, command: &mut tokio::process::Command) {
+        command.env("HOME", self.path());
+        for (var, dir) in XDG_DIRS {
+            command.env(var, self.path().join(dir));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isolated_home_copies_only_credentials() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join(".codex")).unwrap();
+        std::fs::write(home.path().join(".codex/auth.json"), "{}").unwrap();
+        std::fs::write(home.path().join(".codex/history.jsonl"), "x").unwrap();
+
+        let files = default_credential_files("codex");
+        let sandbox = IsolatedHome::create(home.path(), &files).unwrap();
+        assert!(sandbox.path().join(".codex/auth.json").is_file());
+        assert!(!sandbox.path().join(".codex/history.jsonl").exists());
+        assert!(sandbox.path().join(".config").is_dir());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(sandbox.path())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+
+        let root = sandbox.path().to_path_buf();
+        drop(sandbox);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_isolated_home_rejects_escaping_paths() {
+        let home = tempfile::tempdir().unwrap();
+        let result = IsolatedHome::create(home.path(), &["../secret".to_string()]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Invocation logs - Raw child process output kept for post-mortems
+//!
+//! Each child process's captured stdout and stderr are written to their
+//! own file in `~/.gptengage/logs/`, oldest files pruned beyond the
+//! configured count. Callers that want to reference the files from a
+//! transcript run the invocation under [`capture_log_paths`].
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+tokio::task_local! {
+    static CAPTURED: RefCell<Vec<PathBuf>>;
+}
+
+/// Sequence number keeping file names unique within a process
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Get the invocation logs directory path
+pub fn get_logs_dir() -> Result<PathBuf> {
+    Ok(crate::config::ConfigManager::get_config_dir()?.join("logs"))
+}
+
+/// A child process's raw output, partial if it timed out
+pub struct InvocationLog<'a> {
+    pub started_at: DateTime<Utc>,
+    pub duration: std::time::Duration,
+    pub command: &'a str,
+    pub exit_code: Option<i32>,
+    /// Why the process produced no exit code: it failed to spawn or was
+    /// killed at a timeout
+    pub error: Option<&'a str>,
+    pub stdout: &'a str,
+    pub stderr: &'a str,
+}
+
+impl InvocationLog<'_> {
+    /// File contents: a short header followed by both streams
+    pub fn render(&self) -> String {
+        let exit = self
+            .exit_code
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let error = self
+            .error
+            .map(|e| format!("error: {}\n", e))
+            .unwrap_or_default();
+        format!(
+            "started: {}\ncommand: {}\nexit: {}\n{}duration_ms: {}\n\n=== stdout ===\n{}\n=== stderr ===\n{}",
+            self.started_at.to_rfc3339(),
+            self.command,
+            exit,
+            error,
+            self.duration.as_millis(),
+            self.stdout,
+            self.stderr
+        )
+    }
+
+    /// Write the log into `dir`, keep the newest `keep` files, and return
+    /// the new file's path
+    pub fn write(&self, dir: &Path, keep: usize) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "{}-{}-{:06}-{}.log",
+            self.started_at.format("%Y%m%dT%H%M%S%.3fZ"),
+            std::process::id(),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed),
+            super::policy::binary_name(self.command)
+        ));
+
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).write(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&path)
+            .with_context(|| format!("Failed to create log file: {}", path.display()))?;
+        std::io::Write::write_all(&mut file, self.render().as_bytes())?;
+
+        prune(dir, keep)?;
+        Ok(path)
+    }
+}
+
+/// Delete all but the newest `keep` log files (names sort by start time)
+fn prune(dir: &Path, keep: usize) -> Result<()> {
+    let mut logs: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "log"))
+        .collect();
+    if logs.len() <= keep {
+        return Ok(());
+    }
+    logs.sort();
+    for path in &logs[..logs.len() - keep] {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// Note a written log for the enclosing [`capture_log_paths`], if any
+pub(crate) fn record(path: PathBuf) {
+    let _ = CAPTURED.try_with(|paths| paths.borrow_mut().push(path));
+}
+
+/// Run `future`, collecting the log files written by invocations made
+/// directly within it (not by tasks it spawns)
+pub async fn capture_log_paths<F: Future>(future: F) -> (F::Output, Vec<PathBuf>) {
+    CAPTURED
+        .scope(RefCell::new(Vec::new()), async {
+            let output = future.await;
+            let paths = CAPTURED.with(|paths| paths.take());
+            (output, paths)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log<'a>(stdout: &'a str) -> InvocationLog<'a> {
+        InvocationLog {
+            started_at: Utc::now(),
+            duration: std::time::Duration::from_millis(12),
+            command: "/usr/bin/claude",
+            exit_code: Some(1),
+            error: None,
+            stdout,
+            stderr: "rate limited",
+        }
+    }
+
+    #[test]
+    fn test_write_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = log("one").write(dir.path(), 2).unwrap();
+        assert!(first.to_string_lossy().ends_with("-claude.log"));
+        let content = std::fs::read_to_string(&first).unwrap();
+        assert!(content.contains("exit: 1\n"));
+        assert!(content.contains("=== stdout ===\none\n=== stderr ===\nrate limited"));
+
+        let timed_out = InvocationLog {
+            exit_code: None,
+            error: Some("Command timed out after 5 seconds"),
+            ..log("partial")
+        };
+        let content = timed_out.render();
+        assert!(content.contains("exit: -\nerror: Command timed out after 5 seconds\n"));
+        assert!(content.contains("=== stdout ===\npartial\n"));
+
+        let second = log("two").write(dir.path(), 2).unwrap();
+        let third = log("three").write(dir.path(), 2).unwrap();
+        assert!(!first.exists());
+        assert!(second.exists() && third.exists());
+    }
+
+    #[tokio::test]
+    async fn test_capture_log_paths() {
+        let ((), paths) = capture_log_paths(async {
+            record(PathBuf::from("a.log"));
+            record(PathBuf::from("b.log"));
+        })
+        .await;
+        assert_eq!(paths, vec![PathBuf::from("a.log"), PathBuf::from("b.log")]);
+
+        // Outside a capture scope, recording is a no-op
+        record(PathBuf::from("c.log"));
+    }
+}
@@ -1,18 +1,28 @@
 //! CLI Invokers - Execute external LLM CLIs
 
 pub mod base;
+pub mod circuit;
 pub mod claude;
 pub mod codex;
+pub mod detect;
+pub mod dialect;
+pub mod encoding;
 pub mod gemini;
+pub mod isolation;
+pub mod logs;
 pub mod plugin;
+pub mod policy;
+pub mod registry;
+pub mod worker;
 
 pub use base::*;
 pub use claude::*;
 pub use codex::*;
 pub use gemini::*;
 pub use plugin::*;
+pub use registry::InvokerRegistry;
 
-use crate::plugins::PluginManager;
+use crate::plugins::{PluginKind, PluginManager};
 use async_trait::async_trait;
 
 /// Access mode for invoked CLIs.
@@ -32,6 +42,21 @@ impl AccessMode {
             AccessMode::ReadOnly
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessMode::ReadOnly => "read-only",
+            AccessMode::WorkspaceWrite => "workspace-write",
+        }
+    }
+}
+
+/// A response within a CLI's own conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativeReply {
+    pub response: String,
+    /// ID the CLI uses to resume the conversation
+    pub conversation_id: String,
 }
 
 /// Trait for CLI invokers
@@ -46,42 +71,68 @@ pub trait Invoker: Send + Sync {
         model: Option<&str>,
     ) -> anyhow::Result<String>;
 
+    /// Check that the installed CLI supports `access_mode`, so an
+    /// unsupported mode fails with an actionable error before anything runs
+    fn check_access_mode(&self, _access_mode: AccessMode) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Whether the CLI keeps its own conversations that can be resumed
+    /// by ID (see `invoke_native`)
+    fn supports_native_sessions(&self) -> bool {
+        false
+    }
+
+    /// Invoke the CLI within its own conversation, resuming `conversation`
+    /// when given and starting a new one otherwise
+    async fn invoke_native(
+        &self,
+        _prompt: &str,
+        _timeout: u64,
+        _access_mode: AccessMode,
+        _model: Option<&str>,
+        _conversation: Option<&str>,
+    ) -> anyhow::Result<NativeReply> {
+        anyhow::bail!("{} does not support native sessions", self.name())
+    }
+
     /// Get the CLI name
     fn name(&self) -> &str;
 
     /// Check if the CLI is available
-    fn is_available(&self) -> bool;
+    async fn is_available(&self) -> bool;
 }
 
 /// Get an invoker by name.
 ///
 /// Returns a built-in invoker for claude, codex, or gemini.
-/// Falls back to checking plugins for custom CLIs.
-pub fn get_invoker(name: &str) -> Option<Box<dyn Invoker>> {
+/// Falls back to checking `plugins` for custom CLIs.
+pub fn get_invoker(name: &str, plugins: Option<&PluginManager>) -> Option<Box<dyn Invoker>> {
     match name.to_lowercase().as_str() {
         "claude" => Some(Box::new(ClaudeInvoker::new())),
         "codex" => Some(Box::new(CodexInvoker::new())),
         "gemini" => Some(Box::new(GeminiInvoker::new())),
         _ => {
             // Check plugins
-            let plugin_manager = PluginManager::new().ok()?;
-            let config = plugin_manager.get_plugin(name)?.clone();
-            Some(Box::new(PluginInvoker::new(config)))
+            let config = plugins?
+                .get_plugin_of_kind(name, PluginKind::Invoker)?
+                .clone();
+            Some(Box::new(PluginInvoker::new(config).with_allowlist()))
         }
     }
 }
 
 /// Check if a CLI name is valid (built-in or plugin).
-pub fn is_valid_cli(name: &str) -> bool {
+pub fn is_valid_cli(name: &str, plugins: Option<&PluginManager>) -> bool {
     let builtin = ["claude", "codex", "gemini"];
     if builtin.contains(&name.to_lowercase().as_str()) {
         return true;
     }
 
     // Check plugins
-    if let Ok(plugin_manager) = PluginManager::new() {
-        return plugin_manager.has_plugin(name);
-    }
-
-    false
+    plugins.is_some_and(|plugins| {
+        plugins
+            .get_plugin_of_kind(name, PluginKind::Invoker)
+            .is_some()
+    })
 }
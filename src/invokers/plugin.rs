@@ -2,8 +2,10 @@
 //!
 //! Allows invoking custom CLIs defined via TOML plugin files.
 
-use super::base::{command_exists, execute_command};
+use super::base::{execute_command, execute_command_with_prompt_args};
+use super::worker::{accepts_prompt, execute_in_worker};
 use super::{AccessMode, Invoker};
+use crate::plugins::trust::ensure_trusted;
 use crate::plugins::{PluginConfig, PromptMode};
 use async_trait::async_trait;
 
@@ -11,12 +13,23 @@ use async_trait::async_trait;
 #[derive(Clone)]
 pub struct PluginInvoker {
     config: PluginConfig,
+    enforce_allowlist: bool,
 }
 
 impl PluginInvoker {
     /// Create a new PluginInvoker from a plugin configuration
     pub fn new(config: PluginConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            enforce_allowlist: false,
+        }
+    }
+
+    /// Require the plugin's command to be in `plugins.allowed_commands`
+    /// (asking to trust it on first use) before every invocation
+    pub fn with_allowlist(mut self) -> Self {
+        self.enforce_allowlist = true;
+        self
     }
 }
 
@@ -29,11 +42,34 @@ impl Invoker for PluginInvoker {
         access_mode: AccessMode,
         model: Option<&str>,
     ) -> anyhow::Result<String> {
+        if self.enforce_allowlist {
+            ensure_trusted(&self.config)?;
+        }
+
+        // Keep-alive workers replace base_args with their own start args.
+        // A worker would outlive an isolated sandbox, so isolation wins.
+        let policy = super::policy::policy();
+        let worker = self
+            .config
+            .worker
+            .as_ref()
+            .filter(|_| policy.workers && !policy.isolate)
+            .filter(|worker| accepts_prompt(worker, prompt));
+        let base_args = match worker {
+            Some(worker) => &worker.args,
+            None => &self.config.invoke.base_args,
+        };
+
         // Build argument list
-        let mut args: Vec<String> = self.config.invoke.base_args.clone();
+        let cwd = std::env::current_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        let mut args = expand_base_args(base_args, model, &cwd, timeout);
 
-        // Add model if specified and plugin supports it
-        if let Some(m) = model {
+        // Add model if specified and plugin supports it, unless base_args
+        // already place it with {model}
+        let model_in_base_args = base_args.iter().any(|arg| arg.contains("{model}"));
+        if let Some(m) = model.filter(|_| !model_in_base_args) {
             if let Some(ref model_arg) = self.config.invoke.model_arg {
                 args.push(model_arg.clone());
                 args.push(m.to_string());
@@ -51,6 +87,13 @@ impl Invoker for PluginInvoker {
             }
         }
 
+        if let Some(worker) = worker {
+            let (command, args) = self.command_line(args);
+            let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            return execute_in_worker(command, &args_ref, worker, prompt, timeout, access_mode)
+                .await;
+        }
+
         // Handle prompt based on mode
         let input = match self.config.invoke.prompt_mode {
             PromptMode::Stdin => {
@@ -70,27 +113,85 @@ impl Invoker for PluginInvoker {
                 args.push(prompt.to_string());
                 String::new()
             }
+            PromptMode::Template => {
+                // Prompt placed wherever the template asks for it, so the
+                // audit log has to find it among the arguments
+                let template = self.config.invoke.args_template.as_deref().unwrap_or(&[]);
+                args.extend(template.iter().map(|arg| arg.replace("{prompt}", prompt)));
+                let (command, args) = self.command_line(args);
+                let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                return execute_command_with_prompt_args(
+                    command,
+                    &args_ref,
+                    prompt,
+                    timeout,
+                    access_mode,
+                )
+                .await;
+            }
         };
 
+        let (command, args) = self.command_line(args);
+
         // Convert Vec<String> to Vec<&str> for execute_command
         let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
 
-        execute_command(&self.config.plugin.command, &args_ref, &input, timeout).await
+        execute_command(command, &args_ref, &input, timeout, access_mode).await
     }
 
     fn name(&self) -> &str {
         &self.config.plugin.name
     }
 
-    fn is_available(&self) -> bool {
-        command_exists(&self.config.detection.check_command)
+    async fn is_available(&self) -> bool {
+        crate::plugins::detection::is_available(&self.config.detection).await
     }
 }
 
+impl PluginInvoker {
+    /// Program and arguments to spawn. Shell plugins run their command
+    /// line through `sh -c`, with the arguments available to it as "$@".
+    fn command_line(&self, args: Vec<String>) -> (&str, Vec<String>) {
+        if self.config.plugin.shell {
+            let mut shell_args = vec![
+                "-c".to_string(),
+                self.config.plugin.command.clone(),
+                self.config.plugin.name.clone(),
+            ];
+            shell_args.extend(args);
+            ("sh", shell_args)
+        } else {
+            (self.config.plugin.command.as_str(), args)
+        }
+    }
+}
+
+/// Substitute `{model}`, `{cwd}`, and `{timeout}` in plugin base args.
+///
+/// Args that reference `{model}` are dropped when no model is given, so a
+/// flag and its value should share one arg (e.g. `--model={model}`).
+/// Other braces are left untouched.
+fn expand_base_args(
+    base_args: &[String],
+    model: Option<&str>,
+    cwd: &str,
+    timeout: u64,
+) -> Vec<String> {
+    base_args
+        .iter()
+        .filter(|arg| model.is_some() || !arg.contains("{model}"))
+        .map(|arg| {
+            arg.replace("{model}", model.unwrap_or_default())
+                .replace("{cwd}", cwd)
+                .replace("{timeout}", &timeout.to_string())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::plugins::{AccessConfig, DetectionConfig, InvokeConfig, PluginMeta};
+    use crate::plugins::{AccessConfig, DetectionConfig, InvokeConfig, PluginKind, PluginMeta};
 
     fn create_test_config() -> PluginConfig {
         PluginConfig {
@@ -98,12 +199,17 @@ mod tests {
                 name: "test-plugin".to_string(),
                 description: "A test plugin".to_string(),
                 command: "echo".to_string(),
+                shell: false,
+                kind: PluginKind::Invoker,
             },
             invoke: InvokeConfig {
                 base_args: vec![],
                 prompt_mode: PromptMode::ArgLast,
                 prompt_arg: None,
+                args_template: None,
                 model_arg: None,
+                dialect: None,
+                context_window: None,
             },
             access: AccessConfig {
                 readonly_args: vec![],
@@ -113,6 +219,8 @@ mod tests {
                 check_command: "echo".to_string(),
                 check_args: vec![],
             },
+            worker: None,
+            templates: vec![],
         }
     }
 
@@ -123,12 +231,65 @@ mod tests {
         assert_eq!(invoker.name(), "test-plugin");
     }
 
-    #[test]
-    fn test_plugin_invoker_is_available() {
+    #[tokio::test]
+    async fn test_plugin_invoker_is_available() {
         let config = create_test_config();
         let invoker = PluginInvoker::new(config);
         // echo should always be available
-        assert!(invoker.is_available());
+        assert!(invoker.is_available().await);
+    }
+
+    #[tokio::test]
+    async fn test_plugin_invoker_shell_command() {
+        let mut config = create_test_config();
+        config.plugin.command = r#"printf '%s\n' "$@" | tr a-z A-Z"#.to_string();
+        config.plugin.shell = true;
+        config.invoke.base_args = vec!["-x".to_string()];
+        let invoker = PluginInvoker::new(config);
+
+        let result = invoker
+            .invoke("hello $HOME", 30, AccessMode::ReadOnly, None)
+            .await
+            .unwrap();
+        assert_eq!(result.trim(), "-X\nHELLO $HOME");
+    }
+
+    #[test]
+    fn test_expand_base_args() {
+        let base_args: Vec<String> = [
+            "run",
+            "--model={model}",
+            "--dir",
+            "{cwd}",
+            "-t{timeout}s",
+            "{json}",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        assert_eq!(
+            expand_base_args(&base_args, Some("llama3"), "/work", 90),
+            vec!["run", "--model=llama3", "--dir", "/work", "-t90s", "{json}"]
+        );
+        assert_eq!(
+            expand_base_args(&base_args, None, "/work", 90),
+            vec!["run", "--dir", "/work", "-t90s", "{json}"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plugin_invoker_model_placeholder() {
+        let mut config = create_test_config();
+        config.invoke.base_args = vec!["model={model}".to_string()];
+        config.invoke.model_arg = Some("--model".to_string());
+        let invoker = PluginInvoker::new(config);
+
+        let result = invoker
+            .invoke("hi", 30, AccessMode::ReadOnly, Some("m1"))
+            .await
+            .unwrap();
+        assert_eq!(result.trim(), "model=m1 hi");
     }
 
     #[tokio::test]
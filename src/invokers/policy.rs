@@ -0,0 +1,248 @@
+//! Process-wide execution policy for child CLI processes
+//!
+//! The policy is configured once from global command-line flags and
+//! consulted by `execute_command` for every child process it spawns.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Settings applied to every child CLI process
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionPolicy {
+    /// Maximum number of child processes running at once (all CLIs)
+    pub max_concurrency: Option<usize>,
+    /// Maximum number of child processes running at once per binary
+    pub max_per_cli: Option<usize>,
+    /// Run each child with a scratch HOME/XDG sandbox
+    pub isolate: bool,
+    /// Credential files copied into the sandbox, keyed by binary
+    /// (falls back to the built-in defaults for unlisted binaries)
+    pub isolate_credentials: HashMap<String, Vec<String>>,
+    /// Wrapper command used to cut children off from the network
+    /// (None means network access is not restricted)
+    pub network_sandbox: Option<Vec<String>>,
+    /// Binaries exempt from the network sandbox (local-only plugins)
+    pub network_exempt: Vec<String>,
+    /// Record every execution in the audit log
+    pub audit: bool,
+    /// Salt prompt hashes in the audit log (`history.privacy = "hash-only"`)
+    pub hash_only: bool,
+    /// Trust plugin commands not yet in the allowlist without asking
+    pub trust_plugins: bool,
+    /// Bytes of stdout and of stderr kept from each child
+    /// (None means output is buffered in full)
+    pub max_output_bytes: Option<usize>,
+    /// Number of raw output logs to keep in ~/.gptengage/logs
+    /// (None means no logs are written)
+    pub invocation_logs: Option<usize>,
+    /// Follow-ups sent when a response looks truncated
+    pub max_continuations: usize,
+    /// Daily invocation limits (unlimited unless a limit is set)
+    pub budget: crate::config::BudgetConfig,
+    /// Run invocations even when they exceed the budget
+    pub force_budget: bool,
+    /// Keep plugins with a `[worker]` section running between prompts
+    pub workers: bool,
+    /// Failures in a row after which a CLI isn't called again
+    /// (None means CLIs are always called)
+    pub circuit_breaker: Option<usize>,
+    /// CLI called instead of another whose circuit is open, keyed by the
+    /// other's name
+    pub fallbacks: BTreeMap<String, String>,
+}
+
+impl ExecutionPolicy {
+    /// Credential files to copy into an isolated sandbox for `cmd`
+    pub fn credential_files(&self, cmd: &str) -> Vec<String> {
+        let binary = binary_name(cmd);
+        self.isolate_credentials
+            .get(binary)
+            .cloned()
+            .unwrap_or_else(|| super::isolation::default_credential_files(binary))
+    }
+
+    /// Resolve the program and arguments actually spawned for `cmd`,
+    /// prefixing the network sandbox wrapper when one applies.
+    pub fn wrap_command(&self, cmd: &str, args: &[&str]) -> (String, Vec<String>) {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        match &self.network_sandbox {
+            Some(wrapper)
+                if !wrapper.is_empty() && !self.network_exempt.iter().any(|e| e == cmd) =>
+            {
+                let mut wrapped: Vec<String> = wrapper[1..].to_vec();
+                wrapped.push(cmd.to_string());
+                wrapped.extend(args);
+                (wrapper[0].clone(), wrapped)
+            }
+            _ => (cmd.to_string(), args),
+        }
+    }
+}
+
+/// File name of a command, for keying per-binary settings
+pub fn binary_name(cmd: &str) -> &str {
+    std::path::Path::new(cmd)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(cmd)
+}
+
+/// Default network sandbox wrapper for this platform.
+///
+/// On Linux, an unprivileged user + network namespace leaves the child
+/// with only an unconfigured loopback interface.
+pub fn default_network_sandbox() -> anyhow::Result<Vec<String>> {
+    if cfg!(target_os = "linux") {
+        Ok(vec![
+            "unshare".to_string(),
+            "--net".to_string(),
+            "--map-root-user".to_string(),
+        ])
+    } else {
+        Err(anyhow::anyhow!(
+            "--no-network has no built-in sandbox on this platform. \
+             Set network_sandbox in ~/.gptengage/config.json to a wrapper command."
+        ))
+    }
+}
+
+static POLICY: OnceLock<ExecutionPolicy> = OnceLock::new();
+static LIMITER: OnceLock<ConcurrencyLimiter> = OnceLock::new();
+
+/// Install the process-wide policy. Only the first call takes effect.
+pub fn set_policy(policy: ExecutionPolicy) {
+    let _ = POLICY.set(policy);
+}
+
+/// Get the active policy (defaults if none was installed)
+pub fn policy() -> &'static ExecutionPolicy {
+    POLICY.get_or_init(ExecutionPolicy::default)
+}
+
+/// Permits held for the lifetime of a child process
+pub struct ConcurrencySlot {
+    _cli: Option<OwnedSemaphorePermit>,
+    _global: Option<OwnedSemaphorePermit>,
+}
+
+/// Limits concurrent child processes globally and per binary
+pub struct ConcurrencyLimiter {
+    global: Option<Arc<Semaphore>>,
+    max_per_cli: Option<usize>,
+    cli_slots: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(max_concurrency: Option<usize>, max_per_cli: Option<usize>) -> Self {
+        Self {
+            global: max_concurrency.map(|limit| Arc::new(Semaphore::new(limit.max(1)))),
+            max_per_cli,
+            cli_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait for a free slot to run `cmd`.
+    ///
+    /// Slots are per binary, so `/usr/local/bin/claude` and `claude` share
+    /// one. The per-binary permit is always taken before the global one so
+    /// that waiters on a saturated binary don't hold global slots hostage.
+    pub async fn acquire(&self, cmd: &str) -> ConcurrencySlot {
+        let cli = match self.max_per_cli {
+            Some(limit) => {
+                let semaphore = self
+                    .cli_slots
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .entry(binary_name(cmd).to_string())
+                    .or_insert_with(|| Arc::new(Semaphore::new(limit.max(1))))
+                    .clone();
+                semaphore.acquire_owned().await.ok()
+            }
+            None => None,
+        };
+
+        let global = match &self.global {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+
+        ConcurrencySlot {
+            _cli: cli,
+            _global: global,
+        }
+    }
+}
+
+/// Wait for a free slot to run `cmd` under the process-wide limits
+pub async fn acquire_slot(cmd: &str) -> ConcurrencySlot {
+    LIMITER
+        .get_or_init(|| {
+            let policy = policy();
+            ConcurrencyLimiter::new(policy.max_concurrency, policy.max_per_cli)
+        })
+        .acquire(cmd)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_wrap_command_with_network_sandbox() {
+        let policy = ExecutionPolicy {
+            network_sandbox: Some(vec!["unshare".to_string(), "--net".to_string()]),
+            network_exempt: vec!["ollama".to_string()],
+            ..Default::default()
+        };
+
+        let (program, args) = policy.wrap_command("claude", &["-p"]);
+        assert_eq!(program, "unshare");
+        assert_eq!(args, vec!["--net", "claude", "-p"]);
+
+        let (program, args) = policy.wrap_command("ollama", &["run"]);
+        assert_eq!(program, "ollama");
+        assert_eq!(args, vec!["run"]);
+    }
+
+    #[test]
+    fn test_wrap_command_without_sandbox() {
+        let policy = ExecutionPolicy::default();
+        let (program, args) = policy.wrap_command("codex", &["exec"]);
+        assert_eq!(program, "codex");
+        assert_eq!(args, vec!["exec"]);
+    }
+
+    #[tokio::test]
+    async fn test_per_cli_limit_is_isolated() {
+        let limiter = ConcurrencyLimiter::new(None, Some(1));
+        let _held = limiter.acquire("claude").await;
+
+        // A different binary is not blocked by the saturated one
+        let other =
+            tokio::time::timeout(Duration::from_millis(100), limiter.acquire("codex")).await;
+        assert!(other.is_ok());
+
+        // The same binary must wait, however its path is spelled
+        let same =
+            tokio::time::timeout(Duration::from_millis(100), limiter.acquire("claude")).await;
+        assert!(same.is_err());
+        let same = tokio::time::timeout(
+            Duration::from_millis(100),
+            limiter.acquire("/usr/local/bin/claude"),
+        )
+        .await;
+        assert!(same.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_global_limit_applies_across_clis() {
+        let limiter = ConcurrencyLimiter::new(Some(1), None);
+        let _held = limiter.acquire("claude").await;
+        let other =
+            tokio::time::timeout(Duration::from_millis(100), limiter.acquire("codex")).await;
+        assert!(other.is_err());
+    }
+}
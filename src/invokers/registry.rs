@@ -0,0 +1,288 @@
+//! Invoker registry - Which invoker runs a CLI name
+//!
+//! Names resolve to the built-in invokers and installed plugins, as with
+//! `get_invoker`. Invokers registered on a registry take precedence, so
+//! tests and embedding applications can stand in their own. Every invoker
+//! handed out is behind its CLI's circuit breaker (see `circuit`). The
+//! plugin manager is loaded on first use and shared by every clone.
+
+use super::circuit::circuits;
+use super::policy::policy;
+use super::{get_invoker, is_valid_cli, AccessMode, Invoker, NativeReply};
+use crate::app::load_once;
+use crate::plugins::PluginManager;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// CLI names mapped to invokers, cheap to clone and share
+#[derive(Clone, Default)]
+pub struct InvokerRegistry {
+    registered: Arc<HashMap<String, Arc<dyn Invoker>>>,
+    plugins: Arc<OnceLock<PluginManager>>,
+}
+
+impl std::fmt::Debug for InvokerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<&String> = self.registered.keys().collect();
+        names.sort();
+        f.debug_struct("InvokerRegistry")
+            .field("registered", &names)
+            .finish()
+    }
+}
+
+impl InvokerRegistry {
+    /// A registry of the built-in invokers and installed plugins only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `invoker.name()` with `invoker`, replacing any built-in, plugin,
+    /// or previously registered invoker of that name
+    pub fn register(&mut self, invoker: Arc<dyn Invoker>) {
+        Arc::make_mut(&mut self.registered).insert(invoker.name().to_string(), invoker);
+    }
+
+    /// `register` as a builder
+    pub fn with(mut self, invoker: Arc<dyn Invoker>) -> Self {
+        self.register(invoker);
+        self
+    }
+
+    /// Use already-loaded plugins
+    pub fn with_plugins(mut self, plugins: PluginManager) -> Self {
+        self.plugins = Arc::new(OnceLock::from(plugins));
+        self
+    }
+
+    /// Installed plugins
+    pub fn plugins(&self) -> anyhow::Result<&PluginManager> {
+        load_once(&self.plugins, PluginManager::new)
+    }
+
+    /// Reload plugins if their directory changed since they were loaded.
+    /// Clones handed out earlier keep the plugins they had.
+    pub fn reload_plugins_if_changed(&mut self) -> anyhow::Result<bool> {
+        if self.plugins.get().is_none() {
+            return Ok(false);
+        }
+        match Arc::make_mut(&mut self.plugins).get_mut() {
+            Some(plugins) => plugins.reload_if_changed(),
+            None => Ok(false),
+        }
+    }
+
+    /// The invoker for a CLI name, or None for an unknown name
+    pub fn get(&self, name: &str) -> Option<Box<dyn Invoker>> {
+        let invoker: Box<dyn Invoker> = match self.registered.get(name) {
+            Some(invoker) => Box::new(Registered(invoker.clone())),
+            None => get_invoker(name, self.plugins().ok())?,
+        };
+        Some(Box::new(Guarded {
+            invoker,
+            registry: self.clone(),
+        }))
+    }
+
+    /// Whether a CLI name is registered, built in, or an installed plugin
+    pub fn is_valid(&self, name: &str) -> bool {
+        self.registered.contains_key(name) || is_valid_cli(name, self.plugins().ok())
+    }
+}
+
+/// A registered invoker, handed out boxed like the built-in ones
+struct Registered(Arc<dyn Invoker>);
+
+#[async_trait]
+impl Invoker for Registered {
+    async fn invoke(
+        &self,
+        prompt: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+        model: Option<&str>,
+    ) -> anyhow::Result<String> {
+        self.0.invoke(prompt, timeout, access_mode, model).await
+    }
+
+    fn check_access_mode(&self, access_mode: AccessMode) -> anyhow::Result<()> {
+        self.0.check_access_mode(access_mode)
+    }
+
+    fn supports_native_sessions(&self) -> bool {
+        self.0.supports_native_sessions()
+    }
+
+    async fn invoke_native(
+        &self,
+        prompt: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+        model: Option<&str>,
+        conversation: Option<&str>,
+    ) -> anyhow::Result<NativeReply> {
+        self.0
+            .invoke_native(prompt, timeout, access_mode, model, conversation)
+            .await
+    }
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.0.is_available().await
+    }
+}
+
+/// An invoker behind its CLI's circuit breaker
+struct Guarded {
+    invoker: Box<dyn Invoker>,
+    /// Resolves the CLI's fallback
+    registry: InvokerRegistry,
+}
+
+impl Guarded {
+    /// The CLI to call while this one's circuit is open, if one is
+    /// configured and can still be called
+    async fn fallback(&self, threshold: usize) -> Option<Box<dyn Invoker>> {
+        let name = policy().fallbacks.get(self.invoker.name())?;
+        if !circuits().is_closed(name, threshold) {
+            return None;
+        }
+        let fallback = self.registry.get(name)?;
+        fallback.is_available().await.then_some(fallback)
+    }
+
+    fn record<T>(&self, threshold: usize, result: &anyhow::Result<T>) {
+        let name = self.invoker.name();
+        if circuits().record(name, threshold, result) {
+            eprintln!(
+                "Warning: {} failed {} time(s) in a row; not calling it again this run",
+                name, threshold
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl Invoker for Guarded {
+    async fn invoke(
+        &self,
+        prompt: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+        model: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let Some(threshold) = policy().circuit_breaker else {
+            return self
+                .invoker
+                .invoke(prompt, timeout, access_mode, model)
+                .await;
+        };
+        if let Err(open) = circuits().check(self.invoker.name(), threshold) {
+            let Some(fallback) = self.fallback(threshold).await else {
+                return Err(open);
+            };
+            eprintln!(
+                "{}'s circuit is open; asking {} instead",
+                self.invoker.name(),
+                fallback.name()
+            );
+            // Model names belong to the CLI that was asked for
+            return fallback.invoke(prompt, timeout, access_mode, None).await;
+        }
+        let result = self
+            .invoker
+            .invoke(prompt, timeout, access_mode, model)
+            .await;
+        self.record(threshold, &result);
+        result
+    }
+
+    fn check_access_mode(&self, access_mode: AccessMode) -> anyhow::Result<()> {
+        self.invoker.check_access_mode(access_mode)
+    }
+
+    fn supports_native_sessions(&self) -> bool {
+        self.invoker.supports_native_sessions()
+    }
+
+    /// Native conversations can't move to another CLI, so an open circuit
+    /// fails without a fallback
+    async fn invoke_native(
+        &self,
+        prompt: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+        model: Option<&str>,
+        conversation: Option<&str>,
+    ) -> anyhow::Result<NativeReply> {
+        let threshold = policy().circuit_breaker;
+        if let Some(threshold) = threshold {
+            circuits().check(self.invoker.name(), threshold)?;
+        }
+        let result = self
+            .invoker
+            .invoke_native(prompt, timeout, access_mode, model, conversation)
+            .await;
+        if let Some(threshold) = threshold {
+            self.record(threshold, &result);
+        }
+        result
+    }
+
+    fn name(&self) -> &str {
+        self.invoker.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.invoker.is_available().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Canned;
+
+    #[async_trait]
+    impl Invoker for Canned {
+        async fn invoke(
+            &self,
+            prompt: &str,
+            _timeout: u64,
+            _access_mode: AccessMode,
+            _model: Option<&str>,
+        ) -> anyhow::Result<String> {
+            Ok(format!("canned: {}", prompt))
+        }
+
+        fn name(&self) -> &str {
+            "claude"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_invoker_takes_precedence() {
+        let registry = InvokerRegistry::new().with(Arc::new(Canned));
+        let invoker = registry.get("claude").unwrap();
+        assert_eq!(
+            invoker
+                .invoke("hi", 5, AccessMode::ReadOnly, None)
+                .await
+                .unwrap(),
+            "canned: hi"
+        );
+        // Other names still resolve as usual
+        assert_eq!(registry.get("codex").unwrap().name(), "codex");
+        assert!(registry.is_valid("claude"));
+        assert!(InvokerRegistry::new().get("no-such-cli").is_none());
+    }
+}
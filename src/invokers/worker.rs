@@ -0,0 +1,269 @@
+//! Keep-alive workers - Interactive CLI processes reused across prompts
+//!
+//! Some CLIs take seconds to start. With `--workers`, a plugin that
+//! describes its interactive mode in a `[worker]` section is started once
+//! and kept running: each prompt is written to its stdin followed by the
+//! `submit` line, and everything it prints up to the `end_marker` line is
+//! the response. Idle workers are kept per command line, so different
+//! models or access modes get workers of their own. A worker that fails,
+//! times out, or is cancelled mid-response is killed, and the next prompt
+//! starts a fresh one. So is a worker with output left over after its end
+//! marker, which would otherwise be read as the next prompt's response.
+//! Prompts containing a protocol line run as one-shot processes instead.
+//! All workers are stopped when the command finishes.
+
+use super::base::{charge_budget, child_command, record_run, ProcessGroupGuard, RunOutcome};
+use super::AccessMode;
+use crate::history::latency::add_run_time;
+use crate::plugins::WorkerConfig;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+
+/// How long an idle worker is watched for stray output before reuse
+const PENDING_OUTPUT_WAIT: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// A running interactive CLI
+struct Worker {
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    _child: Child,
+    /// Kills the worker's process group when it is dropped
+    _group: ProcessGroupGuard,
+}
+
+/// Idle workers, keyed by program and arguments
+static IDLE: OnceLock<Mutex<HashMap<Vec<String>, Vec<Worker>>>> = OnceLock::new();
+
+fn idle() -> std::sync::MutexGuard<'static, HashMap<Vec<String>, Vec<Worker>>> {
+    IDLE.get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+impl Worker {
+    fn spawn(program: &str, args: &[String]) -> Result<Self> {
+        let mut command = child_command(program, args);
+        // Nothing reads stderr between prompts, so a chatty worker
+        // would block on a full pipe
+        command.stderr(Stdio::null());
+        let mut child = command.spawn()?;
+        let group = ProcessGroupGuard::new(child.id());
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            anyhow::bail!("'{}' started without piped stdio", program);
+        };
+        Ok(Self {
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            _child: child,
+            _group: group,
+        })
+    }
+
+    /// Whether the worker has printed anything not yet read, or exited.
+    /// Either way it is out of step with the protocol and can't be reused.
+    async fn has_pending_output(&mut self) -> bool {
+        let reader = self.stdout.get_mut();
+        if !reader.buffer().is_empty() {
+            return true;
+        }
+        tokio::time::timeout(PENDING_OUTPUT_WAIT, reader.fill_buf())
+            .await
+            .is_ok()
+    }
+
+    /// Send one prompt and read the response up to the end marker
+    async fn ask(&mut self, config: &WorkerConfig, input: &str) -> Result<String> {
+        let mut message = input.to_string();
+        if !message.ends_with('\n') {
+            message.push('\n');
+        }
+        message.push_str(&config.submit);
+        message.push('\n');
+        self.stdin.write_all(message.as_bytes()).await?;
+        self.stdin.flush().await?;
+
+        let mut lines = Vec::new();
+        loop {
+            match self.stdout.next_line().await? {
+                Some(line) if line.trim_end() == config.end_marker => break,
+                Some(line) => lines.push(line),
+                None => anyhow::bail!("Worker exited before finishing its response"),
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Run a prompt through a keep-alive worker for `cmd args`, starting one
+/// if none is idle.
+///
+/// Like `execute_command`, each prompt counts against the daily budget and
+/// is logged and audited as one invocation.
+pub async fn execute_in_worker(
+    cmd: &str,
+    args: &[&str],
+    config: &WorkerConfig,
+    input: &str,
+    timeout: u64,
+    access_mode: AccessMode,
+) -> Result<String> {
+    charge_budget(cmd).await?;
+    let run = run_in_worker(cmd, args, config, input, timeout);
+    record_run(cmd, args, input, None, access_mode, run).await.0
+}
+
+async fn run_in_worker(
+    cmd: &str,
+    args: &[&str],
+    config: &WorkerConfig,
+    input: &str,
+    timeout: u64,
+) -> RunOutcome {
+    // A busy worker holds a slot just like a one-shot process
+    let _slot = super::policy::acquire_slot(cmd).await;
+    let started = std::time::Instant::now();
+
+    let (program, program_args) = super::policy::policy().wrap_command(cmd, args);
+    let key: Vec<String> = std::iter::once(program.clone())
+        .chain(program_args.iter().cloned())
+        .collect();
+    // A worker with stale output would hand it to this prompt
+    let mut reused = idle().get_mut(&key).and_then(Vec::pop);
+    if let Some(worker) = &mut reused {
+        if worker.has_pending_output().await {
+            reused = None;
+        }
+    }
+    let mut worker = match reused {
+        Some(worker) => worker,
+        None => match Worker::spawn(&program, &program_args) {
+            Ok(worker) => worker,
+            Err(e) => return (Err(e), None, None),
+        },
+    };
+
+    let timeout_duration = std::time::Duration::from_secs(timeout);
+    match tokio::time::timeout(timeout_duration, worker.ask(config, input)).await {
+        Ok(Ok(response)) => {
+            add_run_time(started.elapsed());
+            // Output already read past the end marker means the response
+            // itself held a marker line; the worker is out of step
+            if worker.stdout.get_mut().buffer().is_empty() {
+                idle().entry(key).or_default().push(worker);
+            }
+            (
+                Ok(response.clone()),
+                Some(0),
+                Some((response, String::new())),
+            )
+        }
+        // The worker is dropped, killing it, since it may still be mid-response
+        Ok(Err(e)) => (Err(e), None, None),
+        Err(_) => (
+            Err(anyhow::anyhow!(
+                "Command timed out after {} seconds",
+                timeout
+            )),
+            None,
+            None,
+        ),
+    }
+}
+
+/// Whether `prompt` can be sent to a worker: a line equal to the submit
+/// line would send it early, and one equal to the end marker would end
+/// the response early for CLIs that echo their input
+pub fn accepts_prompt(config: &WorkerConfig, prompt: &str) -> bool {
+    !prompt.lines().any(|line| {
+        let line = line.trim_end();
+        line == config.submit || line == config.end_marker
+    })
+}
+
+/// Stop every idle worker
+pub fn stop_workers() {
+    if let Some(idle) = IDLE.get() {
+        idle.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts the lines of each prompt, answering with its own PID
+    const REPL: &str = r#"n=0
+while IFS= read -r line; do
+  if [ "$line" = "/send" ]; then
+    echo "pid $$"
+    echo "$n lines"
+    echo "/done"
+    n=0
+  elif [ "$line" = "hang" ]; then
+    sleep 30
+  else
+    n=$((n+1))
+  fi
+done"#;
+
+    /// Prints an end marker in the middle of each response
+    const ECHO_MARKER: &str = r#"while IFS= read -r line; do
+  if [ "$line" = "/send" ]; then
+    echo "pid $$"
+    echo "/done"
+    echo "tail"
+    echo "/done"
+  fi
+done"#;
+
+    fn config() -> WorkerConfig {
+        WorkerConfig {
+            args: Vec::new(),
+            submit: "/send".to_string(),
+            end_marker: "/done".to_string(),
+        }
+    }
+
+    async fn ask(input: &str, timeout: u64) -> Result<String> {
+        let args = ["-c", REPL];
+        execute_in_worker("sh", &args, &config(), input, timeout, AccessMode::ReadOnly).await
+    }
+
+    #[test]
+    fn test_accepts_prompt() {
+        assert!(accepts_prompt(&config(), "Tabs or spaces?\n\n/sending"));
+        assert!(!accepts_prompt(&config(), "Tabs?\n/send\nmore"));
+        assert!(!accepts_prompt(&config(), "/done  "));
+    }
+
+    #[tokio::test]
+    async fn test_worker_is_reused_until_it_fails() {
+        let first = ask("Tabs or spaces?\n\nDiscuss.", 5).await.unwrap();
+        assert!(first.ends_with("\n3 lines"), "{}", first);
+        let second = ask("Again", 5).await.unwrap();
+        assert_eq!(second.lines().next(), first.lines().next());
+        assert!(second.ends_with("\n1 lines"), "{}", second);
+
+        let err = ask("hang", 1).await.unwrap_err().to_string();
+        assert!(err.contains("timed out"), "{}", err);
+        // The hung worker was killed, so a fresh one answers
+        let third = ask("After", 5).await.unwrap();
+        assert_ne!(third.lines().next(), first.lines().next());
+
+        // A response holding a marker line leaves output behind; the
+        // worker is replaced rather than handing it to the next prompt
+        let args = ["-c", ECHO_MARKER];
+        let config = config();
+        let run = || execute_in_worker("sh", &args, &config, "x", 5, AccessMode::ReadOnly);
+        let early = run().await.unwrap();
+        assert!(early.starts_with("pid "), "{}", early);
+        let next = run().await.unwrap();
+        assert!(next.starts_with("pid "), "{}", next);
+        assert_ne!(next, early);
+        stop_workers();
+    }
+}
@@ -3,16 +3,21 @@
 //! A standalone CLI tool that orchestrates multiple LLM CLIs (Claude Code, Codex, Gemini)
 //! without modifying their configuration directories.
 
+pub mod app;
+pub mod audit;
 pub mod cli;
 pub mod commands;
 pub mod config;
+pub mod history;
 pub mod invokers;
 pub mod orchestrator;
+pub mod personas;
 pub mod plugins;
 pub mod session;
 pub mod templates;
 pub mod utils;
 
+pub use app::GptEngage;
 pub use cli::Cli;
 pub use config::*;
 pub use session::*;
@@ -21,22 +26,5 @@ pub use session::*;
 pub use commands::{debate, generate_agents, ideate, invoke, status};
 pub use invokers::{
     get_invoker, is_valid_cli, AccessMode, ClaudeInvoker, CodexInvoker, GeminiInvoker, Invoker,
+    InvokerRegistry,
 };
-
-#[derive(Debug)]
-pub struct GptEngage {
-    pub config: ConfigManager,
-    pub session_manager: SessionManager,
-}
-
-impl GptEngage {
-    pub async fn new() -> anyhow::Result<Self> {
-        let config = ConfigManager::new()?;
-        let session_manager = SessionManager::new()?;
-
-        Ok(Self {
-            config,
-            session_manager,
-        })
-    }
-}
@@ -1,8 +1,16 @@
 use clap::Parser;
 use gptengage::cli::Cli;
+use gptengage::commands::alias::expand_args;
+use gptengage::commands::options_file::expand_options_file;
+use gptengage::GptEngage;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
-    cli.execute().await
+    // Alias expansion resolves the config, and the command reuses it
+    let mut engage = GptEngage::new();
+    let args = expand_options_file(expand_args(std::env::args_os().collect()))?;
+    let cli = Cli::parse_from(args);
+    let result = cli.run(&mut engage).await;
+    gptengage::invokers::worker::stop_workers();
+    result
 }
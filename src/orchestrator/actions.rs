@@ -0,0 +1,172 @@
+//! Action items - Follow-up tasks extracted from a debate
+//!
+//! With `--action-items`, one more CLI call reads the finished debate (or
+//! review) and lists the concrete follow-up tasks it calls for, each with
+//! a suggested owner and a priority. Items are kept in the transcript,
+//! rendered in text and markdown output, and can be filed as GitHub,
+//! Jira, Linear, ... issues with `--create-issues`.
+
+use super::debate::{synthesis_transcript, DebateOrchestrator, DebateResult};
+use super::risks::{level_score, normalize_level, table_cell};
+use crate::invokers::AccessMode;
+use crate::utils::json::extract_json_array;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// One follow-up task
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ActionItem {
+    pub task: String,
+    /// Suggested owner, usually a participant's persona
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// low, medium, or high
+    pub priority: String,
+    /// URL or key of the issue created for it (with --create-issues)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+}
+
+/// Prompt asking for the follow-up tasks of a debate
+pub fn action_items_prompt(result: &DebateResult) -> String {
+    let outcome = match &result.synthesis {
+        Some(synthesis) => format!(
+            "\nSYNTHESIS:\n{}\n",
+            serde_json::to_string_pretty(synthesis).unwrap_or_default()
+        ),
+        None => String::new(),
+    };
+    format!(
+        r#"[ACTION ITEMS REQUEST]
+You are turning a debate into a list of follow-up tasks.
+
+TOPIC: {}
+
+DEBATE TRANSCRIPT:
+{}{}
+List the concrete tasks that should follow from the debate: things someone
+has to do, check, or decide next. Each task should be a single actionable
+sentence that makes sense on its own as an issue title. Suggest an owner (a
+role, preferably one of the participants' personas) and a priority (low,
+medium, or high). Leave out tasks nobody in the debate argued for.
+
+Respond with a JSON array in this exact format:
+[
+  {{
+    "task": "...",
+    "owner": "..." or null,
+    "priority": "low" | "medium" | "high"
+  }}
+]
+[/ACTION ITEMS REQUEST]"#,
+        result.topic,
+        synthesis_transcript(&result.rounds, 1),
+        outcome
+    )
+}
+
+/// Parse the action items in a response, highest priority first
+pub fn parse_action_items(response: &str) -> anyhow::Result<Vec<ActionItem>> {
+    let json = extract_json_array(response)?;
+    let mut items: Vec<ActionItem> = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("Invalid action items JSON: {}", e))?;
+    for item in &mut items {
+        item.task = item.task.trim().to_string();
+        item.priority = normalize_level(&item.priority);
+        item.owner = item
+            .owner
+            .take()
+            .map(|owner| owner.trim().to_string())
+            .filter(|owner| !owner.is_empty());
+        // Issues are only ever recorded by --create-issues
+        item.issue = None;
+    }
+    items.retain(|item| !item.task.is_empty());
+    items.sort_by_key(|item| std::cmp::Reverse(level_score(&item.priority)));
+    Ok(items)
+}
+
+impl DebateOrchestrator {
+    /// Ask `cli` for a debate's action items
+    pub async fn extract_action_items(
+        &self,
+        result: &DebateResult,
+        cli: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+    ) -> anyhow::Result<Vec<ActionItem>> {
+        let invoker = self.invokers().get(cli).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Action items CLI '{}' not found. Use claude, codex, gemini, or an installed plugin.",
+                cli
+            )
+        })?;
+        if !invoker.is_available().await {
+            anyhow::bail!("Action items CLI '{}' is not available in PATH.", cli);
+        }
+
+        eprintln!("Extracting action items with {}...", cli);
+        let response = invoker
+            .invoke(&action_items_prompt(result), timeout, access_mode, None)
+            .await?;
+        parse_action_items(&response)
+    }
+}
+
+/// The action items as a markdown table
+pub fn markdown_table(items: &[ActionItem]) -> String {
+    let with_issues = items.iter().any(|item| item.issue.is_some());
+    let mut out = String::new();
+    if with_issues {
+        out.push_str("| # | Task | Owner | Priority | Issue |\n|---|---|---|---|---|\n");
+    } else {
+        out.push_str("| # | Task | Owner | Priority |\n|---|---|---|---|\n");
+    }
+    for (i, item) in items.iter().enumerate() {
+        let _ = write!(
+            out,
+            "| {} | {} | {} | {} |",
+            i + 1,
+            table_cell(&item.task),
+            table_cell(item.owner.as_deref().unwrap_or("")),
+            item.priority
+        );
+        if with_issues {
+            let _ = write!(out, " {} |", item.issue.as_deref().unwrap_or(""));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_render_action_items() {
+        let response = r#"[
+  {"task": "Write the migration plan", "owner": "CTO", "priority": "Medium"},
+  {"task": "  ", "priority": "high"},
+  {"task": "Add a CI check for formatting", "owner": "", "priority": "HIGH",
+   "issue": "https://example.com/not-created"}
+]"#;
+        let items = parse_action_items(response).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].task, "Add a CI check for formatting");
+        assert_eq!(items[0].priority, "high");
+        assert_eq!(items[0].owner, None);
+        assert_eq!(items[0].issue, None);
+
+        let table = markdown_table(&items);
+        assert!(table.starts_with("| # | Task | Owner | Priority |\n"));
+        assert!(table.contains("| 2 | Write the migration plan | CTO | medium |\n"));
+
+        let mut filed = items;
+        filed[1].issue = Some("https://github.com/o/r/issues/7".to_string());
+        let table = markdown_table(&filed);
+        assert!(table.contains("| 1 | Add a CI check for formatting |  | high |  |\n"));
+        assert!(table.ends_with("| medium | https://github.com/o/r/issues/7 |\n"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Best-of-N - Sample several answers and have a judge pick the winner
+
+use super::judge::judge_with_plugin;
+use crate::invokers::{AccessMode, Invoker, InvokerRegistry};
+use crate::plugins::PluginKind;
+use crate::utils::json::extract_json_object;
+use serde::Deserialize;
+
+/// Options for `invoke --best-of`
+#[derive(Debug, Clone)]
+pub struct BestOfOptions {
+    /// Number of candidates to generate
+    pub candidates: usize,
+    /// CLI that picks the winner
+    pub judge: String,
+    /// Print every candidate, not just the winner
+    pub show_candidates: bool,
+}
+
+/// The judge's verdict
+#[derive(Debug, Clone, PartialEq)]
+pub struct Judgement {
+    /// 0-based index of the winning candidate
+    pub winner: usize,
+    /// Why the judge picked it
+    pub justification: String,
+    /// Each candidate's score, from judge plugins (LLM judges only pick
+    /// a winner)
+    pub scores: Vec<f64>,
+}
+
+/// Raw judge response
+#[derive(Debug, Deserialize)]
+struct RawJudgement {
+    winner: usize,
+    #[serde(default)]
+    justification: String,
+}
+
+/// Generate `count` candidate responses to the same prompt in parallel.
+///
+/// Failed candidates are skipped with a warning; an error is returned
+/// only if none succeed.
+pub async fn generate_candidates(
+    invoker: &dyn Invoker,
+    prompt: &str,
+    count: usize,
+    timeout: u64,
+    access_mode: AccessMode,
+    model: Option<&str>,
+) -> anyhow::Result<Vec<String>> {
+    let results = futures::future::join_all(
+        (0..count).map(|_| invoker.invoke(prompt, timeout, access_mode, model)),
+    )
+    .await;
+
+    let mut candidates = Vec::new();
+    for (i, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(response) => candidates.push(response),
+            Err(e) => eprintln!("Warning: candidate {} failed: {}", i + 1, e),
+        }
+    }
+
+    if candidates.is_empty() {
+        return Err(anyhow::anyhow!("All {} candidates failed", count));
+    }
+    Ok(candidates)
+}
+
+/// Build the judge prompt for a request and its candidate answers
+pub fn build_judge_prompt(request: &str, candidates: &[String]) -> String {
+    let mut answers = String::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        answers.push_str(&format!(
+            "[CANDIDATE {}]\n{}\n[/CANDIDATE {}]\n\n",
+            i + 1,
+            candidate,
+            i + 1
+        ));
+    }
+
+    format!(
+        r#"[JUDGE REQUEST]
+Several candidate answers were generated for the same request. Pick the
+single best one for correctness, completeness, and clarity.
+
+REQUEST:
+{request}
+
+{answers}Respond with JSON only, using the candidate number (1 to {count}):
+{{
+  "winner": 1,
+  "justification": "one or two sentences on why it is best"
+}}
+[/JUDGE REQUEST]"#,
+        request = request,
+        answers = answers,
+        count = candidates.len(),
+    )
+}
+
+/// Parse the judge response, checking the winner against `count` candidates
+pub fn parse_judge_response(response: &str, count: usize) -> anyhow::Result<Judgement> {
+    let json_str = extract_json_object(response)?;
+    let raw: RawJudgement = serde_json::from_str(json_str)
+        .map_err(|e| anyhow::anyhow!("Failed to parse judge response: {}", e))?;
+
+    if raw.winner == 0 || raw.winner > count {
+        return Err(anyhow::anyhow!(
+            "Judge picked candidate {}, but there are only {}",
+            raw.winner,
+            count
+        ));
+    }
+    Ok(Judgement {
+        winner: raw.winner - 1,
+        justification: raw.justification.trim().to_string(),
+        scores: Vec::new(),
+    })
+}
+
+/// Ask `judge_cli` to pick the best candidate for `request`. A judge
+/// plugin of that name in the registry's plugins scores the candidates
+/// instead.
+pub async fn judge_candidates(
+    invokers: &InvokerRegistry,
+    judge_cli: &str,
+    request: &str,
+    candidates: &[String],
+    timeout: u64,
+    access_mode: AccessMode,
+) -> anyhow::Result<Judgement> {
+    if let Some(config) = invokers
+        .plugins()?
+        .get_plugin_of_kind(judge_cli, PluginKind::Judge)
+    {
+        eprintln!(
+            "Scoring {} candidates with {}...",
+            candidates.len(),
+            judge_cli
+        );
+        return judge_with_plugin(config, request, candidates, timeout).await;
+    }
+
+    let invoker = invokers.get(judge_cli).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Judge CLI '{}' not found. Use claude, codex, gemini, or an installed plugin (an LLM CLI or a judge).",
+            judge_cli
+        )
+    })?;
+
+    if !invoker.is_available().await {
+        return Err(anyhow::anyhow!(
+            "Judge CLI '{}' is not available in PATH.",
+            judge_cli
+        ));
+    }
+
+    eprintln!(
+        "Judging {} candidates with {}...",
+        candidates.len(),
+        judge_cli
+    );
+    let prompt = build_judge_prompt(request, candidates);
+    let response = invoker.invoke(&prompt, timeout, access_mode, None).await?;
+    parse_judge_response(&response, candidates.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_judge_prompt_numbers_candidates() {
+        let prompt = build_judge_prompt("Sort a list", &["a".to_string(), "b".to_string()]);
+        assert!(prompt.contains("REQUEST:\nSort a list"));
+        assert!(prompt.contains("[CANDIDATE 1]\na\n[/CANDIDATE 1]"));
+        assert!(prompt.contains("[CANDIDATE 2]\nb\n[/CANDIDATE 2]"));
+        assert!(prompt.contains("(1 to 2)"));
+    }
+
+    #[test]
+    fn test_parse_judge_response() {
+        let text = r#"Verdict: {"winner": 2, "justification": " Handles the empty case. "}"#;
+        assert_eq!(
+            parse_judge_response(text, 3).unwrap(),
+            Judgement {
+                winner: 1,
+                justification: "Handles the empty case.".to_string(),
+                scores: Vec::new(),
+            }
+        );
+        assert!(parse_judge_response(r#"{"winner": 0}"#, 3).is_err());
+        assert!(parse_judge_response(r#"{"winner": 4}"#, 3).is_err());
+        assert!(parse_judge_response("no json", 3).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Synthesis citations - Grounding each synthesis point in the transcript
+//!
+//! Debate syntheses cite the responses behind every consensus and
+//! disagreement point as `[R<round>:<participant>]`, e.g. `[R2:gemini]` or
+//! `[R1:claude (CTO)]`. Citations are checked against the transcript once
+//! the synthesis arrives: those naming a round or participant that isn't
+//! there are recorded as unverified, and points without any citation are
+//! reported. Markdown output links each verified citation to the response
+//! it names.
+
+use super::debate::{RoundResponse, Synthesis};
+use super::stance::participant_labels;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// How synthesis points cite the transcript, added to debate synthesis
+/// prompts
+pub(crate) const CITATION_FORMAT: &str = r#"End every consensus and disagreement point (and any other statement about
+what a participant said) with citations of the responses it is based on, as
+[R<round>:<participant>] with the participant named exactly as in the
+transcript, e.g. [R2:gemini] or [R1:claude (CTO)]. Use one bracket per
+citation: "... [R1:claude] [R2:codex]"."#;
+
+static CITATION: OnceLock<Regex> = OnceLock::new();
+
+/// A `[R<round>:<participant>]` citation in synthesis text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation<'a> {
+    /// The citation as written, brackets included
+    pub text: &'a str,
+    pub round: usize,
+    pub participant: &'a str,
+}
+
+/// The citations in `text`, in order
+pub fn citations(text: &str) -> Vec<Citation<'_>> {
+    let citation = CITATION
+        .get_or_init(|| Regex::new(r"\[R(\d+)\s*:\s*([^\[\]]+?)\s*\]").expect("valid regex"));
+    citation
+        .captures_iter(text)
+        .filter_map(|c| {
+            Some(Citation {
+                text: c.get(0)?.as_str(),
+                round: c[1].parse().ok()?,
+                participant: c.get(2)?.as_str(),
+            })
+        })
+        .collect()
+}
+
+/// The transcript label of the response `citation` names, if the round
+/// has one: an exact (case-insensitive) label match, or the only response
+/// from a CLI of that name
+pub fn resolve(citation: &Citation, rounds: &[Vec<RoundResponse>]) -> Option<String> {
+    let responses = rounds.get(citation.round.checked_sub(1)?)?;
+    let labels = participant_labels(responses);
+    if let Some(label) = labels
+        .iter()
+        .find(|label| label.eq_ignore_ascii_case(citation.participant))
+    {
+        return Some(label.clone());
+    }
+    let mut by_cli = labels
+        .iter()
+        .zip(responses)
+        .filter(|(_, response)| response.cli.eq_ignore_ascii_case(citation.participant));
+    match (by_cli.next(), by_cli.next()) {
+        (Some((label, _)), None) => Some(label.clone()),
+        _ => None,
+    }
+}
+
+/// Check a synthesis's citations against the debate `rounds`: unverified
+/// citations are recorded on it, and they and uncited points are reported
+/// on stderr
+pub fn verify(synthesis: &mut Synthesis, rounds: &[Vec<RoundResponse>]) {
+    let mut unverified = Vec::new();
+    let texts = std::iter::once(&synthesis.summary)
+        .chain(&synthesis.consensus_points)
+        .chain(&synthesis.disagreement_points)
+        .chain(&synthesis.key_insights)
+        .chain(&synthesis.recommendation);
+    for text in texts {
+        for citation in citations(text) {
+            if resolve(&citation, rounds).is_none() && !unverified.contains(&citation.text) {
+                unverified.push(citation.text);
+            }
+        }
+    }
+    if !unverified.is_empty() {
+        eprintln!(
+            "Warning: synthesis cites responses that aren't in the transcript: {}",
+            unverified.join(" ")
+        );
+    }
+
+    let uncited = synthesis
+        .consensus_points
+        .iter()
+        .chain(&synthesis.disagreement_points)
+        .filter(|point| citations(point).is_empty())
+        .count();
+    if uncited > 0 {
+        eprintln!(
+            "Warning: {} synthesis point(s) cite no responses from the transcript",
+            uncited
+        );
+    }
+    synthesis.unverified_citations = unverified.into_iter().map(str::to_string).collect();
+}
+
+/// `text` with each verified citation turned into a markdown link to the
+/// response it names; `anchor` gives the link target for a round and label
+pub fn link_citations(
+    text: &str,
+    rounds: &[Vec<RoundResponse>],
+    anchor: impl Fn(usize, &str) -> String,
+) -> String {
+    let mut linked = String::with_capacity(text.len());
+    let mut rest = text;
+    for citation in citations(text) {
+        let Some(label) = resolve(&citation, rounds) else {
+            continue;
+        };
+        let (before, after) = rest.split_once(citation.text).unwrap_or((rest, ""));
+        linked.push_str(before);
+        linked.push_str(&format!(
+            "[R{}:{}](#{})",
+            citation.round,
+            citation.participant,
+            anchor(citation.round, &label)
+        ));
+        rest = after;
+    }
+    linked.push_str(rest);
+    linked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(cli: &str, persona: Option<&str>) -> RoundResponse {
+        RoundResponse {
+            cli: cli.to_string(),
+            persona: persona.map(str::to_string),
+            response: "...".to_string(),
+            log: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_and_link_citations() {
+        let rounds = vec![
+            vec![response("claude", Some("CTO")), response("gemini", None)],
+            vec![
+                response("claude", Some("CTO")),
+                response("claude", Some("CTO")),
+            ],
+        ];
+        let mut synthesis = Synthesis {
+            summary: "Split on timing".to_string(),
+            consensus_points: vec!["Migrate [R1:claude] [R1:Gemini]".to_string()],
+            disagreement_points: vec!["Timing [R2:claude (CTO) #2] [R3:codex]".to_string()],
+            key_insights: vec![],
+            recommendation: None,
+            position_changes: vec![],
+            unverified_citations: vec![],
+        };
+        verify(&mut synthesis, &rounds);
+        assert_eq!(synthesis.unverified_citations, vec!["[R3:codex]"]);
+
+        // "claude" is ambiguous in round 2, where it answered twice
+        let cited = citations("[R2:claude]")[0].clone();
+        assert_eq!(resolve(&cited, &rounds), None);
+
+        let linked = link_citations(&synthesis.consensus_points[0], &rounds, |round, label| {
+            format!("round-{}-{}", round, label.replace(' ', "-"))
+        });
+        assert_eq!(
+            linked,
+            "Migrate [R1:claude](#round-1-claude-(CTO)) [R1:Gemini](#round-1-gemini)"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Continuation - Re-prompt when a response looks cut off
+//!
+//! CLIs stop mid-answer when they hit an output token limit, sometimes
+//! saying so and sometimes not. A response that ends with an open code
+//! fence, partway through a sentence, or with a max-token notice is sent
+//! back to the same CLI with a request to continue, and the parts are
+//! stitched together. Up to `max_continuations` follow-ups are sent per
+//! response (default 1, `0` turns this off).
+
+use crate::invokers::{AccessMode, Invoker};
+
+/// Responses shorter than this are never treated as cut off mid-sentence;
+/// short answers ("42", "Yes") routinely end without punctuation
+const MIN_PROSE_CHARS: usize = 200;
+
+/// Characters from the end of a response searched for max-token notices
+const MARKER_WINDOW: usize = 300;
+
+/// Notices CLIs print when a response hit the output token limit
+const TOKEN_LIMIT_MARKERS: &[&str] = &[
+    "output token maximum",
+    "maximum output tokens",
+    "max output tokens",
+    "max_tokens",
+    "max_output_tokens",
+    "finish_reason: length",
+    "response was truncated",
+];
+
+/// Shortest overlap trusted when the continuation repeats the tail
+const MIN_OVERLAP_CHARS: usize = 12;
+
+/// Why a response looks truncated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Truncation {
+    /// The CLI reported hitting its output token limit
+    TokenLimit,
+    /// A code fence was opened and never closed
+    OpenCodeFence,
+    /// The last sentence stops without ending
+    MidSentence,
+}
+
+impl Truncation {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Truncation::TokenLimit => "hit the output token limit",
+            Truncation::OpenCodeFence => "unclosed code block",
+            Truncation::MidSentence => "ends mid-sentence",
+        }
+    }
+}
+
+/// Index of the line holding a max-token notice near the end of `text`
+fn token_limit_line(text: &str) -> Option<usize> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut searched = 0;
+    for (i, line) in lines.iter().enumerate().rev() {
+        let lower = line.to_lowercase();
+        if TOKEN_LIMIT_MARKERS.iter().any(|m| lower.contains(m)) {
+            return Some(i);
+        }
+        searched += line.chars().count() + 1;
+        if searched >= MARKER_WINDOW {
+            break;
+        }
+    }
+    None
+}
+
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Whether the last line reads as unfinished prose
+fn ends_mid_sentence(text: &str) -> bool {
+    if text.chars().count() < MIN_PROSE_CHARS {
+        return false;
+    }
+    let Some(last) = text.lines().last().map(str::trim) else {
+        return false;
+    };
+    // Headings, lists, tables, and quotes often end without punctuation
+    let structured = last.starts_with(['#', '|', '-', '*', '>', '+'])
+        || last
+            .split_once(['.', ')'])
+            .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    if structured || last.split_whitespace().count() < 3 {
+        return false;
+    }
+    last.chars()
+        .last()
+        .is_some_and(|c| c.is_alphanumeric() || matches!(c, ',' | ';' | ':' | '(' | '—' | '–'))
+}
+
+/// Why `response` looks truncated, if it does
+pub fn detect_truncation(response: &str) -> Option<Truncation> {
+    let text = response.trim_end();
+    if text.is_empty() {
+        return None;
+    }
+    if token_limit_line(text).is_some() {
+        return Some(Truncation::TokenLimit);
+    }
+    if text.lines().filter(|line| is_fence(line)).count() % 2 == 1 {
+        return Some(Truncation::OpenCodeFence);
+    }
+    if ends_mid_sentence(text) {
+        return Some(Truncation::MidSentence);
+    }
+    None
+}
+
+/// The response without a trailing max-token notice
+fn without_marker(response: &str) -> String {
+    let text = response.trim_end();
+    match token_limit_line(text) {
+        Some(line) => text.lines().take(line).collect::<Vec<_>>().join("\n"),
+        None => response.to_string(),
+    }
+}
+
+/// Follow-up prompt asking for the rest of `partial`.
+///
+/// CLIs are invoked without memory of earlier calls, so the original
+/// request and the partial answer are both included.
+pub fn continuation_prompt(prompt: &str, partial: &str) -> String {
+    format!(
+        "Your previous response to the request below was cut off.\n\n\
+         REQUEST:\n{}\n\n\
+         YOUR RESPONSE SO FAR:\n{}\n\n\
+         Continue the response exactly where it stops. Begin by repeating \
+         its last few words, then carry on. Do not restart, summarize, or \
+         comment on the interruption. If it stops inside a code block, \
+         continue the code without opening a new block.",
+        prompt, partial
+    )
+}
+
+/// Join a partial response and its continuation, dropping any tail the
+/// continuation repeats and a code fence it reopens
+pub fn stitch(partial: &str, continuation: &str) -> String {
+    let partial = partial.trim_end_matches([' ', '\t']);
+    let mut rest = continuation.trim_start_matches([' ', '\t', '\n']);
+    let open_fence = partial.lines().filter(|line| is_fence(line)).count() % 2 == 1;
+    if open_fence && is_fence(rest.lines().next().unwrap_or_default()) {
+        rest = rest.split_once('\n').map_or("", |(_, after)| after);
+    }
+
+    // Longest prefix of the continuation that repeats the partial's tail
+    let overlap = rest
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .rfind(|&end| {
+            (MIN_OVERLAP_CHARS..=partial.len()).contains(&end) && partial.ends_with(&rest[..end])
+        });
+    if let Some(end) = overlap {
+        return format!("{}{}", partial, &rest[end..]);
+    }
+
+    let joined = partial.ends_with('\n')
+        || rest.starts_with('\n')
+        || rest.starts_with(|c: char| c.is_ascii_punctuation() && c != '`');
+    if joined {
+        format!("{}{}", partial, rest)
+    } else {
+        format!("{} {}", partial, rest)
+    }
+}
+
+/// Invoke a CLI, sending up to `max_continuations` follow-ups while the
+/// response looks truncated
+pub async fn invoke_with_continuation(
+    invoker: &dyn Invoker,
+    prompt: &str,
+    timeout: u64,
+    access_mode: AccessMode,
+    model: Option<&str>,
+    max_continuations: usize,
+) -> anyhow::Result<String> {
+    let mut response = invoker.invoke(prompt, timeout, access_mode, model).await?;
+    for _ in 0..max_continuations {
+        let Some(truncation) = detect_truncation(&response) else {
+            break;
+        };
+        eprintln!(
+            "{} response looks truncated ({}), asking it to continue...",
+            invoker.name(),
+            truncation.describe()
+        );
+        let partial = without_marker(&response);
+        let follow_up = continuation_prompt(prompt, &partial);
+        match invoker
+            .invoke(&follow_up, timeout, access_mode, model)
+            .await
+        {
+            Ok(rest) if !rest.trim().is_empty() => response = stitch(&partial, &rest),
+            Ok(_) => break,
+            Err(e) => {
+                eprintln!(
+                    "Warning: continuation failed, keeping partial response: {}",
+                    e
+                );
+                break;
+            }
+        }
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const SENTENCE: &str = "The cache sits in front of the database and absorbs reads. ";
+
+    #[test]
+    fn test_detect_truncation() {
+        let prose = SENTENCE.repeat(4);
+        assert_eq!(detect_truncation(&prose), None);
+        assert_eq!(detect_truncation("42"), None);
+        assert_eq!(
+            detect_truncation(&format!("{}It also means that", prose)),
+            Some(Truncation::MidSentence)
+        );
+        // Lists and headings end without punctuation
+        assert_eq!(
+            detect_truncation(&format!("{}\n- use a read replica", prose)),
+            None
+        );
+        assert_eq!(
+            detect_truncation(&format!("{}\n\n1. Add caching first", prose)),
+            None
+        );
+        assert_eq!(
+            detect_truncation("Here:\n```rust\nfn main() {\n"),
+            Some(Truncation::OpenCodeFence)
+        );
+        assert_eq!(detect_truncation("```\ncode\n```\nDone."), None);
+        assert_eq!(
+            detect_truncation(&format!(
+                "{}\n\nError: Claude's response exceeded the 32000 output token maximum.",
+                prose
+            )),
+            Some(Truncation::TokenLimit)
+        );
+    }
+
+    #[test]
+    fn test_stitch() {
+        // Repeated tail is dropped
+        assert_eq!(
+            stitch(
+                "Shard by tenant because it",
+                "tenant because it limits blast radius."
+            ),
+            "Shard by tenant because it limits blast radius."
+        );
+        assert_eq!(stitch("One, two", "three."), "One, two three.");
+        assert_eq!(stitch("A list:\n", "- item"), "A list:\n- item");
+        // A reopened code fence is dropped
+        assert_eq!(
+            stitch("```rust\nfn main() {\n", "```rust\n}\n```"),
+            "```rust\nfn main() {\n}\n```"
+        );
+        assert_eq!(
+            without_marker("Partial\nmax_tokens reached\n"),
+            "Partial".to_string()
+        );
+    }
+
+    /// Answers with each of `responses` in turn, recording prompts
+    struct Scripted {
+        responses: Mutex<Vec<String>>,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Invoker for Scripted {
+        async fn invoke(
+            &self,
+            prompt: &str,
+            _timeout: u64,
+            _access_mode: AccessMode,
+            _model: Option<&str>,
+        ) -> anyhow::Result<String> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_with_continuation() {
+        let scripted = Scripted {
+            responses: Mutex::new(vec![
+                "Step one:\n```sh\nmake build\n".to_string(),
+                "make test\n```\nThat's all.".to_string(),
+            ]),
+            prompts: Mutex::new(Vec::new()),
+        };
+        let response = invoke_with_continuation(
+            &scripted,
+            "How do I build?",
+            10,
+            AccessMode::ReadOnly,
+            None,
+            2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            response,
+            "Step one:\n```sh\nmake build\nmake test\n```\nThat's all."
+        );
+        let prompts = scripted.prompts.lock().unwrap().clone();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].contains("REQUEST:\nHow do I build?"));
+        assert!(prompts[1].contains("YOUR RESPONSE SO FAR:\nStep one:"));
+
+        // Disabled: the partial response is returned as is
+        let scripted = Scripted {
+            responses: Mutex::new(vec!["```sh\nmake".to_string()]),
+            prompts: Mutex::new(Vec::new()),
+        };
+        let response = invoke_with_continuation(&scripted, "p", 10, AccessMode::ReadOnly, None, 0)
+            .await
+            .unwrap();
+        assert_eq!(response, "```sh\nmake");
+    }
+}
@@ -1,13 +1,53 @@
 //! Debate orchestration - Run multi-round debates
 
-use crate::invokers::{get_invoker, AccessMode};
+use super::actions::ActionItem;
+use super::citations::{self, CITATION_FORMAT};
+use super::continuation::invoke_with_continuation;
+use super::fact_check::FactCheck;
+use super::frontmatter::TopicFrontmatter;
+use super::health::{Benching, ParticipantHealth};
+use super::narration::{Narration, Verbosity};
+use super::observers::Critique;
+use super::overflow::ContextWindow;
+use super::postprocess::{apply_filters, ResponseFilter};
+use super::response_format::{enforce, ResponseFormat};
+use super::risks::Risk;
+use super::round_robin::participant_names;
+use super::stance::{compute_position_changes, participant_labels, PositionChange, Stance};
+use crate::history::latency::{
+    estimate_round, measure_run_time, slowest_first, LatencyHistory, LatencyTable,
+};
+use crate::invokers::dialect::Dialect;
+use crate::invokers::logs::capture_log_paths;
+use crate::invokers::policy::policy;
+use crate::invokers::{AccessMode, Invoker, InvokerRegistry};
+use crate::plugins::middleware::apply_middleware;
+use crate::utils::json::find_json;
+use crate::utils::sidecar::Sidecar;
+use crate::utils::time::format_duration;
+use crate::utils::tokens::counter_for;
+use crate::utils::workspace::WorkspaceChanges;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::task;
-
-pub struct DebateOrchestrator;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinSet;
+
+/// Runs debates, looking up each participant's CLI in an invoker registry
+#[derive(Debug, Clone, Default)]
+pub struct DebateOrchestrator {
+    invokers: InvokerRegistry,
+    /// Receives each round's responses as soon as the round closes
+    round_sink: Option<UnboundedSender<Vec<RoundResponse>>>,
+    /// Format every participant's answer is checked against
+    response_format: Option<ResponseFormat>,
+    /// Details reported while participants run (see `narration`)
+    verbosity: Verbosity,
+}
 
 /// Full agent definition with persona, instructions, and metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AgentDefinition {
     pub cli: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -18,6 +58,11 @@ pub struct AgentDefinition {
     pub expertise: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub communication_style: Option<String>,
+    /// Filters applied to this participant's responses before other
+    /// participants see them (`strip-preamble`, `json`, `max-lines:40`, ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(with = "Vec<String>")]
+    pub output_filters: Vec<ResponseFilter>,
 }
 
 impl AgentDefinition {
@@ -26,6 +71,15 @@ impl AgentDefinition {
         if self.cli.trim().is_empty() {
             return Err(anyhow::anyhow!("Agent 'cli' field cannot be empty"));
         }
+        if self
+            .cli
+            .contains(|c: char| c.is_whitespace() || c == ':' || c == ',')
+        {
+            return Err(anyhow::anyhow!(
+                "Agent 'cli' field '{}' can't contain whitespace, ':' or ','",
+                self.cli
+            ));
+        }
         if self.persona.trim().is_empty() {
             return Err(anyhow::anyhow!(
                 "Agent 'persona' field cannot be empty (required for agent definitions)"
@@ -36,10 +90,11 @@ impl AgentDefinition {
                 "Agent 'instructions' field cannot be empty (required for agent definitions)"
             ));
         }
-        if self.instructions.len() < 10 {
+        let length = self.instructions.trim().chars().count();
+        if length < 10 {
             return Err(anyhow::anyhow!(
                 "Agent 'instructions' must be at least 10 characters (got {})",
-                self.instructions.len()
+                length
             ));
         }
         Ok(())
@@ -57,7 +112,7 @@ impl AgentDefinition {
 }
 
 /// Agent file schema
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AgentFile {
     #[serde(default = "default_schema_version")]
     pub schema_version: String,
@@ -66,6 +121,31 @@ pub struct AgentFile {
     pub participants: Vec<AgentDefinition>,
 }
 
+/// Agent file serialization format, chosen by file extension.
+///
+/// JSON stays the default for machine-generated files; TOML and YAML are
+/// easier for humans to edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentFileFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl AgentFileFormat {
+    /// `.toml` is TOML, `.yaml`/`.yml` is YAML, anything else is JSON
+    pub fn from_path(path: &str) -> Self {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("toml") => AgentFileFormat::Toml,
+            Some("yaml" | "yml") => AgentFileFormat::Yaml,
+            _ => AgentFileFormat::Json,
+        }
+    }
+}
+
 fn default_schema_version() -> String {
     "1.0".to_string()
 }
@@ -73,34 +153,183 @@ fn default_schema_version() -> String {
 impl AgentFile {
     /// Load and validate an agent file
     pub fn load(path: &str) -> anyhow::Result<Self> {
+        let agent_file = Self::read(path)?;
+        agent_file.validate()?;
+        Ok(agent_file)
+    }
+
+    /// Read and parse an agent file without validating it
+    pub fn read(path: &str) -> anyhow::Result<Self> {
         let content = std::fs::read_to_string(path)
             .map_err(|e| anyhow::anyhow!("Failed to read agent file '{}': {}", path, e))?;
 
-        let agent_file: AgentFile = serde_json::from_str(&content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse agent file '{}': {}", path, e))?;
+        Self::parse(&content, AgentFileFormat::from_path(path))
+            .map_err(|e| anyhow::anyhow!("Failed to parse agent file '{}': {}", path, e))
+    }
 
+    /// Parse agent file content in a given format
+    pub fn parse(content: &str, format: AgentFileFormat) -> anyhow::Result<Self> {
+        Ok(match format {
+            AgentFileFormat::Json => serde_json::from_str(content)?,
+            AgentFileFormat::Toml => toml::from_str(content)?,
+            AgentFileFormat::Yaml => serde_norway::from_str(content)?,
+        })
+    }
+
+    /// Serialize the agent file in a given format
+    pub fn render(&self, format: AgentFileFormat) -> anyhow::Result<String> {
+        Ok(match format {
+            AgentFileFormat::Json => serde_json::to_string_pretty(self)?,
+            AgentFileFormat::Toml => toml::to_string_pretty(self)?,
+            AgentFileFormat::Yaml => serde_norway::to_string(self)?,
+        })
+    }
+
+    /// Validate the schema version and every participant
+    pub fn validate(&self) -> anyhow::Result<()> {
         // Validate schema version
-        if agent_file.schema_version != "1.0" {
+        if self.schema_version != "1.0" {
             return Err(anyhow::anyhow!(
                 "Unsupported schema version '{}'. Expected '1.0'",
-                agent_file.schema_version
+                self.schema_version
             ));
         }
 
         // Validate all participants
-        if agent_file.participants.is_empty() {
+        if self.participants.is_empty() {
             return Err(anyhow::anyhow!(
                 "Agent file must contain at least one participant"
             ));
         }
 
-        for (idx, agent) in agent_file.participants.iter().enumerate() {
+        for (idx, agent) in self.participants.iter().enumerate() {
             agent.validate().map_err(|e| {
                 anyhow::anyhow!("Validation failed for participant {}: {}", idx + 1, e)
             })?;
         }
 
-        Ok(agent_file)
+        Ok(())
+    }
+
+    /// Apply an edit of the form `<participant>.<field>=<value>`.
+    ///
+    /// Participants are numbered from 1, or `*` for all of them. Fields are
+    /// cli, model, persona, instructions, expertise (comma-separated), and
+    /// communication_style; an empty value clears model, expertise, and
+    /// communication_style.
+    pub fn apply_edit(&mut self, edit: &str) -> anyhow::Result<()> {
+        let (target, value) = edit.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid edit '{}'. Expected <participant>.<field>=<value>, e.g. 1.model=gpt-4o",
+                edit
+            )
+        })?;
+        let (index, field) = target.trim().split_once('.').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid edit '{}'. Expected <participant>.<field>=<value>, e.g. 1.model=gpt-4o",
+                edit
+            )
+        })?;
+
+        let count = self.participants.len();
+        let agents: Vec<&mut AgentDefinition> = if index == "*" {
+            self.participants.iter_mut().collect()
+        } else {
+            let n: usize = index
+                .parse()
+                .ok()
+                .filter(|n| (1..=count).contains(n))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Invalid participant '{}' in '{}'. Use 1 to {} or *",
+                        index,
+                        edit,
+                        count
+                    )
+                })?;
+            vec![&mut self.participants[n - 1]]
+        };
+
+        let value = value.trim();
+        let optional = (!value.is_empty()).then(|| value.to_string());
+        for agent in agents {
+            match field {
+                "cli" => agent.cli = value.to_string(),
+                "model" => agent.model = optional.clone(),
+                "persona" => agent.persona = value.to_string(),
+                "instructions" => agent.instructions = value.to_string(),
+                "expertise" => {
+                    agent.expertise = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|e| !e.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                "communication_style" => agent.communication_style = optional.clone(),
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Unknown field '{}'. Use cli, model, persona, instructions, expertise, or communication_style",
+                        field
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load several agent files and merge them into one roster
+    pub fn load_all(paths: &[String]) -> anyhow::Result<Self> {
+        let files = paths
+            .iter()
+            .map(|path| Ok((path.clone(), Self::load(path)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Self::merge(files)
+    }
+
+    /// Merge agent files, given with their source names, in order.
+    ///
+    /// Personas must be unique across all files (case-insensitive), so a
+    /// roster composed from shared building blocks never has two agents
+    /// answering as the same role.
+    pub fn merge(files: Vec<(String, AgentFile)>) -> anyhow::Result<Self> {
+        let mut participants: Vec<AgentDefinition> = Vec::new();
+        let mut sources: Vec<&str> = Vec::new();
+        for (source, file) in &files {
+            for agent in &file.participants {
+                if let Some(i) = participants
+                    .iter()
+                    .position(|p| p.persona.eq_ignore_ascii_case(&agent.persona))
+                {
+                    return Err(anyhow::anyhow!(
+                        "Duplicate persona '{}' in '{}' (already defined in '{}')",
+                        agent.persona,
+                        source,
+                        sources[i]
+                    ));
+                }
+                participants.push(agent.clone());
+                sources.push(source);
+            }
+        }
+
+        // A single file keeps its provenance; a merged roster has several
+        let generated_by = match files.as_slice() {
+            [(_, file)] => file.generated_by.clone(),
+            _ => None,
+        };
+        Ok(AgentFile {
+            schema_version: default_schema_version(),
+            generated_by,
+            participants,
+        })
+    }
+
+    /// Write the agent file in the format matching the path's extension
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        let content = self.render(AgentFileFormat::from_path(path))?;
+        std::fs::write(path, content)
+            .map_err(|e| anyhow::anyhow!("Failed to write agent file '{}': {}", path, e))
     }
 
     /// Convert to a list of Participants
@@ -185,11 +414,14 @@ impl Participant {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RoundResponse {
     pub cli: String,
     pub persona: Option<String>,
     pub response: String,
+    /// Raw output log of the invocation, when invocation logs are on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
 }
 
 impl RoundResponse {
@@ -202,7 +434,7 @@ impl RoundResponse {
 }
 
 /// Synthesis of a debate
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Synthesis {
     /// Brief summary of the debate
     pub summary: String,
@@ -218,114 +450,664 @@ pub struct Synthesis {
     /// Final recommendation (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recommendation: Option<String>,
+    /// Participants who changed position, and when (requires stance tracking)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub position_changes: Vec<PositionChange>,
+    /// Citations naming a round or participant not in the transcript
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unverified_citations: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Current `DebateResult` schema version
+pub const DEBATE_RESULT_SCHEMA_VERSION: u32 = 2;
+
+/// Transcripts written before `schema_version` existed
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct DebateResult {
+    /// Transcript format version; older transcripts are migrated on load
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gptengage_version: Option<String>,
     pub topic: String,
     pub rounds: Vec<Vec<RoundResponse>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub synthesis: Option<Synthesis>,
+    /// Per-round participant positions (populated with --track-stances)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stances: Vec<Stance>,
+    /// Participants benched after repeated failures (with --bench-after)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub benched: Vec<Benching>,
+    /// Files created, modified, or deleted during the debate (with --write)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_changes: Option<WorkspaceChanges>,
+    /// Risks raised in the debate (with --risk-register)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub risks: Vec<Risk>,
+    /// Follow-up tasks (with --action-items)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub action_items: Vec<ActionItem>,
+    /// Observers' critiques of the whole debate (with --observer)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub critiques: Vec<Critique>,
+    /// Synthesis claims the transcript doesn't support (with --fact-check)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fact_check: Option<FactCheck>,
+    /// Constraints, success criteria, and non-goals from the topic's
+    /// frontmatter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frontmatter: Option<TopicFrontmatter>,
+}
+
+/// Upgrades a transcript's JSON from one schema version to the next
+type Migration = fn(&mut serde_json::Value) -> anyhow::Result<()>;
+
+/// Migrations keyed by the version they upgrade from, oldest first
+const MIGRATIONS: &[(u32, Migration)] = &[(1, migrate_v1_to_v2)];
+
+/// Version 1 covers every transcript written before `schema_version`
+/// existed. Its fields are a subset of version 2, so only the version
+/// stamp changes.
+fn migrate_v1_to_v2(value: &mut serde_json::Value) -> anyhow::Result<()> {
+    if !value.is_object() {
+        anyhow::bail!("Transcript is not a JSON object");
+    }
+    Ok(())
+}
+
+impl DebateResult {
+    /// Parse a JSON transcript of any known schema version, migrating
+    /// older versions to the current one
+    pub fn from_json(content: &str) -> anyhow::Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(content)?;
+        Self::from_value(value)
+    }
+
+    /// Migrate a JSON transcript to the current schema version and parse it
+    pub fn from_value(mut value: serde_json::Value) -> anyhow::Result<Self> {
+        let mut version = match value.get("schema_version") {
+            None => legacy_schema_version(),
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| anyhow::anyhow!("Invalid transcript schema_version: {}", v))?,
+        };
+        if version > DEBATE_RESULT_SCHEMA_VERSION {
+            anyhow::bail!(
+                "Transcript schema_version {} is newer than this gptengage supports ({}). Upgrade gptengage to read it.",
+                version,
+                DEBATE_RESULT_SCHEMA_VERSION
+            );
+        }
+
+        for (from, migrate) in MIGRATIONS {
+            if *from == version {
+                migrate(&mut value)?;
+                version += 1;
+            }
+        }
+        if let Some(object) = value.as_object_mut() {
+            object.insert("schema_version".to_string(), version.into());
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Which context components go into each participant's prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptContext {
+    /// How many previous rounds of responses to include (0 for none)
+    pub history_rounds: usize,
+    /// Whether to add persona/role scaffolding
+    pub persona_context: bool,
+    /// Ask for divergent ideas through this round and for convergence on
+    /// a recommendation after it (None for no phases)
+    pub converge_after: Option<usize>,
+}
+
+impl Default for PromptContext {
+    fn default() -> Self {
+        Self {
+            history_rounds: 1,
+            persona_context: true,
+            converge_after: None,
+        }
+    }
+}
+
+/// Instructions for the rounds of a two-phase debate before the boundary
+const DIVERGE_PHASE: &str = "[PHASE: DIVERGE]
+This is an exploration round. Put forward as many distinct ideas and approaches as you can, including unconventional ones. Don't settle on a single answer or converge on positions already taken; add options nobody has raised yet.
+[/PHASE]";
+
+/// Instructions for the rounds after the boundary
+const CONVERGE_PHASE: &str = "[PHASE: CONVERGE]
+The exploration phase is over. Work toward one actionable recommendation: weigh the ideas raised so far, drop the weak ones, say which option you support and the concrete next steps it needs, and note where you now agree with others.
+[/PHASE]";
+
+/// Something that happened during a debate, for `RoundOptions::events`
+#[derive(Debug, Clone)]
+pub enum RoundEvent {
+    /// A progress line, such as a round starting
+    Progress(String),
+    /// A participant's response to a round, as soon as it arrives
+    Response {
+        round: usize,
+        response: RoundResponse,
+    },
+}
+
+/// How rounds are run, beyond what goes into prompts
+#[derive(Debug, Clone, Default)]
+pub struct RoundOptions {
+    /// Print each response to stdout as soon as it arrives, instead of
+    /// leaving all output until the debate is over
+    pub stream: bool,
+    /// Close each round this long after it starts, with whichever
+    /// responses arrived, cancelling the participants still running
+    pub round_timeout: Option<Duration>,
+    /// Start a duplicate request to a participant that hasn't answered
+    /// this long after its request started, keeping whichever answers first
+    pub hedge_after: Option<Duration>,
+    /// Bench a participant after this many failed rounds in a row
+    pub bench_after: Option<usize>,
+    /// Re-probe benched participants before the final round
+    pub reprobe: bool,
+    /// Start the historically slowest participants first, estimate how
+    /// long each round takes, and record response times for later debates
+    pub latency: bool,
+    /// Send progress and each response here as they happen, instead of
+    /// printing them to stdout
+    pub events: Option<tokio::sync::mpsc::UnboundedSender<RoundEvent>>,
+    /// Also show each response in its participant's tmux pane
+    pub sidecar: Option<Arc<Sidecar>>,
+}
+
+impl RoundOptions {
+    /// Print a response as it arrives, when streaming. The block goes out
+    /// in one write so concurrent responses don't interleave mid-response.
+    pub(crate) fn emit(
+        &self,
+        round: usize,
+        participant: usize,
+        response: &RoundResponse,
+        elapsed: Duration,
+    ) {
+        if let Some(sidecar) = &self.sidecar {
+            sidecar.response(participant, round, &response.response);
+        }
+        if let Some(events) = &self.events {
+            // A receiver that went away just isn't listening any more
+            let _ = events.send(RoundEvent::Response {
+                round,
+                response: response.clone(),
+            });
+        } else if self.stream {
+            print!("{}", streamed_response(round, response, elapsed));
+        }
+    }
+
+    /// Report progress on stdout, or as an event when they are sent
+    pub(crate) fn progress(&self, line: String) {
+        match &self.events {
+            Some(events) => {
+                let _ = events.send(RoundEvent::Progress(line));
+            }
+            None => println!("{}", line),
+        }
+    }
+}
+
+/// Wait for a round's tasks, each tagged with its index, until all finish
+/// or the round deadline passes.
+///
+/// Returns the finished outputs and, if the deadline passed, the indexes of
+/// the tasks that were still running. Those are aborted, which kills their
+/// CLI processes.
+pub(crate) async fn join_round<T: Send + 'static>(
+    mut tasks: JoinSet<(usize, T)>,
+    count: usize,
+    round_timeout: Option<Duration>,
+) -> (Vec<(usize, T)>, Vec<usize>) {
+    let deadline = round_timeout.map(|t| tokio::time::Instant::now() + t);
+    let mut finished = Vec::new();
+    let mut done = vec![false; count];
+    loop {
+        let next = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, tasks.join_next()).await {
+                Ok(next) => next,
+                Err(_) => break,
+            },
+            None => tasks.join_next().await,
+        };
+        match next {
+            Some(Ok((i, output))) => {
+                done[i] = true;
+                finished.push((i, output));
+            }
+            // A panicked task counts as finished without output
+            Some(Err(_)) => {}
+            None => return (finished, Vec::new()),
+        }
+    }
+
+    tasks.abort_all();
+    while tasks.join_next().await.is_some() {}
+    let cancelled = (0..count).filter(|i| !done[*i]).collect();
+    (finished, cancelled)
+}
+
+/// Note the participants cancelled when a round closed at its deadline
+pub(crate) fn report_cancelled(
+    round: usize,
+    participants: &[Participant],
+    cancelled: &[usize],
+    round_timeout: Option<Duration>,
+) {
+    if cancelled.is_empty() {
+        return;
+    }
+    let names: Vec<String> = cancelled
+        .iter()
+        .map(|i| participants[*i].display_name())
+        .collect();
+    eprintln!(
+        "Round {} closed after {}s; cancelled: {}",
+        round,
+        round_timeout.map(|t| t.as_secs()).unwrap_or_default(),
+        names.join(", ")
+    );
+}
+
+/// " (benched: ...)" for the round banner while anyone is benched
+pub(crate) fn benched_note(health: &ParticipantHealth, names: &[String]) -> String {
+    let benched: Vec<&str> = health
+        .benched()
+        .into_iter()
+        .map(|i| names[i].as_str())
+        .collect();
+    if benched.is_empty() {
+        String::new()
+    } else {
+        format!(" (benched: {})", benched.join(", "))
+    }
+}
+
+/// The latency history's recorded response times. Failing to read it only
+/// warns, and the debate runs in participant order.
+fn load_latency() -> Option<LatencyTable> {
+    match LatencyHistory::open().and_then(|history| history.load()) {
+        Ok(table) => Some(table),
+        Err(e) => {
+            eprintln!("Warning: Failed to read response times: {}", e);
+            None
+        }
+    }
+}
+
+/// Add a response time to the latency history, warning instead of failing
+fn record_latency(cli: &str, model: Option<&str>, duration: Duration) {
+    if let Err(e) = LatencyHistory::open().and_then(|history| history.record(cli, model, duration))
+    {
+        eprintln!("Warning: Failed to record response time: {}", e);
+    }
+}
+
+/// The `active` participants in the order to start them, slowest expected
+/// first, and how long the round should take under `--max-concurrency`
+/// when every one of them has a recorded response time
+fn schedule_round(
+    table: &LatencyTable,
+    participants: &[Participant],
+    active: Vec<usize>,
+    timeout: u64,
+    round_timeout: Option<Duration>,
+) -> (Vec<usize>, Option<Duration>) {
+    let expected: Vec<Option<Duration>> = active
+        .iter()
+        .map(|&i| {
+            let participant = &participants[i];
+            table
+                .expected(&participant.cli, participant.model.as_deref())
+                .map(|d| d.min(Duration::from_secs(timeout)))
+        })
+        .collect();
+    let order: Vec<usize> = slowest_first(&expected)
+        .into_iter()
+        .map(|k| active[k])
+        .collect();
+    let estimate = expected
+        .into_iter()
+        .collect::<Option<Vec<Duration>>>()
+        .map(|mut durations| {
+            durations.sort_by(|a, b| b.cmp(a));
+            let estimate = estimate_round(&durations, policy().max_concurrency);
+            round_timeout.map_or(estimate, |limit| estimate.min(limit))
+        });
+    (order, estimate)
+}
+
+/// " (expected ~3m 12s)" for a round's progress line
+fn expected_note(expected: Option<Duration>) -> String {
+    match expected {
+        Some(duration) => format!(
+            " (expected ~{})",
+            format_duration(duration.as_millis() as u64)
+        ),
+        None => String::new(),
+    }
+}
+
+/// What a synthesis covers, and the JSON it is returned as
+pub(crate) const SYNTHESIS_FORMAT: &str = r#"Generate a structured synthesis with:
+1. A 2-3 sentence summary of the debate
+2. Points where participants reached consensus
+3. Points where participants disagreed
+4. Key insights that emerged
+5. A recommendation (if applicable)
+
+Respond with JSON in this exact format:
+{
+  "summary": "...",
+  "consensus_points": ["...", "..."],
+  "disagreement_points": ["...", "..."],
+  "key_insights": ["...", "..."],
+  "recommendation": "..." or null
+}"#;
+
+/// Transcript of `rounds` for a synthesis prompt, numbered from
+/// `first_round`. Repeated names are numbered, so every response can be
+/// cited.
+pub(crate) fn synthesis_transcript(rounds: &[Vec<RoundResponse>], first_round: usize) -> String {
+    let mut transcript = String::new();
+    for (round_num, round_responses) in rounds.iter().enumerate() {
+        transcript.push_str(&format!("ROUND {}:\n", first_round + round_num));
+        for (label, response) in participant_labels(round_responses)
+            .iter()
+            .zip(round_responses)
+        {
+            transcript.push_str(&format!("{}:\n{}\n\n", label, response.response));
+        }
+        transcript.push('\n');
+    }
+    transcript
+}
+
+/// Position changes for a synthesis prompt (empty when there are none)
+pub(crate) fn position_changes_section(position_changes: &[PositionChange]) -> String {
+    if position_changes.is_empty() {
+        return String::new();
+    }
+    let mut section = "POSITION CHANGES:\n".to_string();
+    for change in position_changes {
+        section.push_str(&format!(
+            "- {} moved from \"{}\" to \"{}\" in round {}\n",
+            change.participant, change.from, change.to, change.round
+        ));
+    }
+    section.push('\n');
+    section
+}
+
+/// A labeled response block for streamed output
+pub fn streamed_response(round: usize, response: &RoundResponse, elapsed: Duration) -> String {
+    format!(
+        "── Round {} · {} ({:.1}s) ──\n{}\n\n",
+        round,
+        response.display_name(),
+        elapsed.as_secs_f64(),
+        response.response.trim_end()
+    )
+}
+
+impl PromptContext {
+    /// A round's shared context with the instructions for its phase, in a
+    /// two-phase debate
+    pub fn phase_context(&self, round: usize, base_context: String) -> String {
+        match self.converge_after {
+            Some(boundary) if round <= boundary => format!("{}\n\n{}", base_context, DIVERGE_PHASE),
+            Some(_) => format!("{}\n\n{}", base_context, CONVERGE_PHASE),
+            None => base_context,
+        }
+    }
+
+    /// A participant's full prompt for a round's shared context
+    pub fn participant_prompt(&self, participant: &Participant, base_context: &str) -> String {
+        if self.persona_context {
+            participant.build_prompt_with_persona(base_context)
+        } else {
+            base_context.to_string()
+        }
+    }
 }
 
 impl DebateOrchestrator {
+    /// An orchestrator invoking CLIs through `invokers`
+    pub fn new(invokers: InvokerRegistry) -> Self {
+        Self {
+            invokers,
+            round_sink: None,
+            response_format: None,
+            verbosity: Verbosity::default(),
+        }
+    }
+
+    /// Check each participant's answer against `format`, asking once more
+    /// when it doesn't match
+    pub fn with_response_format(mut self, format: Option<ResponseFormat>) -> Self {
+        self.response_format = format;
+        self
+    }
+
+    /// Send each round's responses to `sink` as soon as the round closes.
+    /// The channel closes once this orchestrator and its clones are dropped.
+    pub fn with_round_sink(mut self, sink: UnboundedSender<Vec<RoundResponse>>) -> Self {
+        self.round_sink = Some(sink);
+        self
+    }
+
+    /// Report participant details up to `verbosity` while debating
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    /// Pass a closed round on to the round sink, if any
+    pub(crate) fn round_closed(&self, responses: &[RoundResponse]) {
+        if let Some(sink) = &self.round_sink {
+            let _ = sink.send(responses.to_vec());
+        }
+    }
+
+    /// The registry participants' CLIs are looked up in
+    pub fn invokers(&self) -> &InvokerRegistry {
+        &self.invokers
+    }
+
+    /// Shared prompt for a round, before persona scaffolding is added.
+    ///
+    /// `previous` holds the responses of every earlier round; only the
+    /// last `history_rounds` of them are included.
+    pub fn round_context(
+        topic: &str,
+        round: usize,
+        previous: &[Vec<RoundResponse>],
+        history_rounds: usize,
+    ) -> String {
+        let mut base_context = format!("Topic: {}\n\nRound {}\n\n", topic, round);
+
+        let start = previous.len().saturating_sub(history_rounds);
+        for (i, prev_round) in previous.iter().enumerate().skip(start) {
+            if history_rounds == 1 {
+                base_context.push_str("Previous responses:\n");
+            } else {
+                base_context.push_str(&format!("Previous responses (round {}):\n", i + 1));
+            }
+            for response in prev_round.iter() {
+                base_context.push_str(&format!(
+                    "{}: {}\n\n",
+                    response.display_name(),
+                    response.response
+                ));
+            }
+        }
+
+        base_context.push_str("Please provide your perspective on this topic.");
+        base_context
+    }
+
+    /// Default participants (Claude, Codex, Gemini without personas)
+    pub fn default_participants() -> Vec<Participant> {
+        vec![
+            Participant::new("claude".to_string(), None),
+            Participant::new("codex".to_string(), None),
+            Participant::new("gemini".to_string(), None),
+        ]
+    }
+
     /// Run a debate with specific participants
+    #[allow(clippy::too_many_arguments)]
     pub async fn run_debate_with_participants(
+        &self,
         topic: &str,
         participants: Vec<Participant>,
         num_rounds: usize,
         timeout: u64,
         access_mode: AccessMode,
+        context: PromptContext,
+        options: RoundOptions,
     ) -> anyhow::Result<DebateResult> {
         if participants.is_empty() {
             return Err(anyhow::anyhow!("At least one participant is required"));
         }
+        self.check_access_modes(&participants, access_mode)?;
 
         let mut rounds: Vec<Vec<RoundResponse>> = Vec::new();
 
-        for round in 1..=num_rounds {
-            println!("Running round {} of {}...", round, num_rounds);
+        let names = participant_names(&participants);
+        let plugins = self.invokers.plugins().ok();
+        let windows: Vec<Option<ContextWindow>> = participants
+            .iter()
+            .map(|participant| ContextWindow::of(participant, plugins))
+            .collect();
+        let mut health = ParticipantHealth::new(participants.len(), options.bench_after);
+        // Response times from earlier debates
+        let latency = if options.latency {
+            load_latency()
+        } else {
+            None
+        };
 
-            // Build base context for this round
-            let mut base_context = format!("Topic: {}\n\nRound {}\n\n", topic, round);
-
-            if round > 1 {
-                if let Some(prev_round) = rounds.last() {
-                    base_context.push_str("Previous responses:\n");
-                    for response in prev_round.iter() {
-                        base_context.push_str(&format!(
-                            "{}: {}\n\n",
-                            response.display_name(),
-                            response.response
-                        ));
-                    }
-                }
+        for round in 1..=num_rounds {
+            if options.reprobe && round == num_rounds {
+                self.reprobe_benched(
+                    &mut health,
+                    &participants,
+                    &names,
+                    round,
+                    timeout,
+                    access_mode,
+                )
+                .await;
             }
+            let active: Vec<usize> = (0..participants.len())
+                .filter(|&i| !health.is_benched(i))
+                .collect();
+            let (order, expected) = match &latency {
+                Some(table) => {
+                    schedule_round(table, &participants, active, timeout, options.round_timeout)
+                }
+                None => (active, None),
+            };
+            options.progress(format!(
+                "Running round {} of {}{}{}...",
+                round,
+                num_rounds,
+                expected_note(expected),
+                benched_note(&health, &names)
+            ));
 
-            base_context.push_str("Please provide your perspective on this topic.");
+            // Build base context for this round
+            let base_context = context.phase_context(
+                round,
+                Self::round_context(topic, round, &rounds, context.history_rounds),
+            );
 
             // Spawn tasks for all participants in parallel
-            let mut tasks = Vec::new();
-
-            for participant in &participants {
-                let participant_clone = participant.clone();
-                let ctx = participant_clone.build_prompt_with_persona(&base_context);
-
-                let task = task::spawn(async move {
-                    let invoker = match get_invoker(&participant_clone.cli) {
-                        Some(inv) => inv,
-                        None => {
-                            eprintln!(
-                                "Unknown CLI '{}', skipping participant",
-                                participant_clone.cli
-                            );
-                            return None;
-                        }
-                    };
-
-                    if !invoker.is_available() {
-                        eprintln!(
-                            "{} is not available, skipping",
-                            participant_clone.display_name()
+            let mut tasks = JoinSet::new();
+            let started = Instant::now();
+
+            // Tasks start in order, so the slowest take the first free
+            // concurrency slots
+            for i in order {
+                let options = options.clone();
+                let participant_clone = participants[i].clone();
+                // Participants with a small context window get their own view
+                let ctx = match &windows[i] {
+                    Some(window) => window.fit(&names[i], context.history_rounds, |view| {
+                        let base = Self::round_context(
+                            topic,
+                            round,
+                            &view.shorten_rounds(&rounds),
+                            view.history_rounds,
                         );
-                        return None;
-                    }
-
-                    match invoker
-                        .invoke(
-                            &ctx,
-                            timeout,
-                            access_mode,
-                            participant_clone.model.as_deref(),
+                        context.participant_prompt(
+                            &participant_clone,
+                            &context.phase_context(round, base),
                         )
-                        .await
-                    {
-                        Ok(response) => Some(RoundResponse {
-                            cli: participant_clone.cli.clone(),
-                            persona: participant_clone.persona.clone(),
-                            response,
-                        }),
-                        Err(e) => {
-                            eprintln!(
-                                "{} invocation failed: {}",
-                                participant_clone.display_name(),
-                                e
-                            );
-                            None
+                    }),
+                    None => context.participant_prompt(&participant_clone, &base_context),
+                };
+                let orchestrator = self.clone();
+
+                tasks.spawn(async move {
+                    let (cli, model) = (
+                        participant_clone.cli.clone(),
+                        participant_clone.model.clone(),
+                    );
+                    let (response, ran) = measure_run_time(orchestrator.invoke_hedged(
+                        participant_clone,
+                        ctx,
+                        timeout,
+                        access_mode,
+                        options.hedge_after,
+                    ))
+                    .await;
+                    if let Some(ref response) = response {
+                        // Stand-in invokers that run no CLI have nothing to record
+                        if options.latency && !ran.is_zero() {
+                            record_latency(&cli, model.as_deref(), ran);
                         }
+                        options.emit(round, i, response, started.elapsed());
                     }
+                    (i, response)
                 });
-
-                tasks.push(task);
             }
 
-            // Wait for all tasks to complete
-            let results = futures::future::join_all(tasks).await;
+            // Wait for the round to finish or close; responses stay in
+            // participant order
+            let (mut results, cancelled) =
+                join_round(tasks, participants.len(), options.round_timeout).await;
+            report_cancelled(round, &participants, &cancelled, options.round_timeout);
+            results.sort_by_key(|(i, _)| *i);
+
+            let mut responded = vec![false; participants.len()];
+            for (i, response) in &results {
+                responded[*i] = response.is_some();
+            }
+            health.end_round(round, &responded, &names);
 
             let round_responses: Vec<RoundResponse> =
-                results.into_iter().flatten().flatten().collect();
+                results.into_iter().filter_map(|(_, r)| r).collect();
 
             // Ensure at least one responder per round
             if round_responses.is_empty() {
@@ -335,37 +1117,218 @@ impl DebateOrchestrator {
                 ));
             }
 
+            self.round_closed(&round_responses);
             rounds.push(round_responses);
         }
 
         Ok(DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
             gptengage_version: Some(env!("CARGO_PKG_VERSION").to_string()),
             topic: topic.to_string(),
             rounds,
             synthesis: None,
+            stances: Vec::new(),
+            benched: health.benchings,
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
         })
     }
 
+    /// Fail before the first round if any participant's CLI can't run in
+    /// `access_mode`, rather than losing it to a usage error mid-debate
+    pub(crate) fn check_access_modes(
+        &self,
+        participants: &[Participant],
+        access_mode: AccessMode,
+    ) -> anyhow::Result<()> {
+        let mut errors: Vec<String> = Vec::new();
+        for participant in participants {
+            let Some(invoker) = self.invokers.get(&participant.cli) else {
+                continue;
+            };
+            if let Err(e) = invoker.check_access_mode(access_mode) {
+                // Participants often share a CLI
+                if !errors.contains(&e.to_string()) {
+                    errors.push(e.to_string());
+                }
+            }
+        }
+        if !errors.is_empty() {
+            anyhow::bail!("{}", errors.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Invoke one participant with its prompt.
+    ///
+    /// Unknown, unavailable, or failing participants are reported on stderr
+    /// and yield `None` so the round can continue without them.
+    pub(crate) async fn invoke_participant(
+        &self,
+        participant: Participant,
+        prompt: String,
+        timeout: u64,
+        access_mode: AccessMode,
+    ) -> Option<RoundResponse> {
+        let invoker = match self.invokers.get(&participant.cli) {
+            Some(inv) => inv,
+            None => {
+                eprintln!("Unknown CLI '{}', skipping participant", participant.cli);
+                return None;
+            }
+        };
+
+        if !invoker.is_available().await {
+            eprintln!("{} is not available, skipping", participant.display_name());
+            return None;
+        }
+
+        let name = participant.display_name();
+        let ask = |prompt: String| {
+            let (participant, invoker, name) = (&participant, &invoker, &name);
+            async move {
+                // Scaffolding in the CLI's dialect, then middleware rewrites
+                // the prompt as it will be sent
+                let plugins = self.invokers.plugins()?;
+                let prompt = Dialect::for_cli(&participant.cli, Some(plugins)).adapt(&prompt);
+                let prompt = apply_middleware(&prompt, timeout, plugins).await?;
+                if self.narrates(Verbosity::Prompts) {
+                    let counter = counter_for(&participant.cli, participant.model.as_deref());
+                    self.narrate(Narration::Prompt {
+                        participant: name.clone(),
+                        chars: prompt.chars().count(),
+                        tokens: counter.count(&prompt),
+                    });
+                }
+                let response = invoke_with_continuation(
+                    invoker.as_ref(),
+                    &prompt,
+                    timeout,
+                    access_mode,
+                    participant.model.as_deref(),
+                    policy().max_continuations,
+                )
+                .await?;
+                Ok(match &participant.agent_definition {
+                    Some(agent) if !agent.output_filters.is_empty() => {
+                        agent.output_filters.iter().fold(response, |text, filter| {
+                            let before = text.chars().count();
+                            let text = apply_filters(std::slice::from_ref(filter), text, name);
+                            self.narrate(Narration::Filter {
+                                participant: name.clone(),
+                                filter: String::from(filter.clone()),
+                                before,
+                                after: text.chars().count(),
+                            });
+                            text
+                        })
+                    }
+                    _ => response,
+                })
+            }
+        };
+        let started = Instant::now();
+        let (result, logs) = capture_log_paths(async {
+            let response = ask(prompt.clone()).await?;
+            Ok::<_, anyhow::Error>(match &self.response_format {
+                Some(format) => enforce(format, &prompt, response, &name, ask).await,
+                None => response,
+            })
+        })
+        .await;
+        let log = logs.last().map(|path| path.display().to_string());
+        self.narrate(Narration::Timing {
+            participant: name.clone(),
+            elapsed: started.elapsed(),
+            answered: result.is_ok(),
+        });
+
+        match result {
+            Ok(response) => Some(RoundResponse {
+                cli: participant.cli.clone(),
+                persona: participant.persona.clone(),
+                response,
+                log,
+            }),
+            Err(e) => {
+                eprintln!("{} invocation failed: {}", participant.display_name(), e);
+                if let Some(log) = log {
+                    eprintln!("  Raw output: {}", log);
+                }
+                None
+            }
+        }
+    }
+
+    /// Invoke one participant, starting a duplicate request if the first
+    /// hasn't answered after `hedge_after`. Whichever answers first wins
+    /// and the other is cancelled (its process group killed); if one
+    /// fails, the other is still awaited.
+    ///
+    /// Write access is never hedged, so two CLIs don't edit the workspace
+    /// at the same time.
+    pub(crate) async fn invoke_hedged(
+        &self,
+        participant: Participant,
+        prompt: String,
+        timeout: u64,
+        access_mode: AccessMode,
+        hedge_after: Option<Duration>,
+    ) -> Option<RoundResponse> {
+        let hedge_after = hedge_after.filter(|_| access_mode != AccessMode::WorkspaceWrite);
+        let Some(delay) = hedge_after else {
+            return self
+                .invoke_participant(participant, prompt, timeout, access_mode)
+                .await;
+        };
+
+        let name = participant.display_name();
+        let first =
+            self.invoke_participant(participant.clone(), prompt.clone(), timeout, access_mode);
+        tokio::pin!(first);
+        tokio::select! {
+            response = &mut first => return response,
+            _ = tokio::time::sleep(delay) => {}
+        }
+
+        eprintln!(
+            "{} hasn't answered after {}s; starting a duplicate request",
+            name,
+            delay.as_secs()
+        );
+        let second = self.invoke_participant(participant, prompt, timeout, access_mode);
+        tokio::pin!(second);
+        tokio::select! {
+            response = &mut first => match response {
+                Some(response) => Some(response),
+                None => second.await,
+            },
+            response = &mut second => match response {
+                Some(response) => Some(response),
+                None => first.await,
+            },
+        }
+    }
+
     /// Generate a synthesis of a debate result
     pub async fn generate_synthesis(
+        &self,
         result: &DebateResult,
         synthesizer_cli: &str,
         timeout: u64,
         access_mode: AccessMode,
     ) -> anyhow::Result<Synthesis> {
         // Build debate transcript for synthesis
-        let mut transcript = String::new();
-        for (round_num, round_responses) in result.rounds.iter().enumerate() {
-            transcript.push_str(&format!("ROUND {}:\n", round_num + 1));
-            for response in round_responses {
-                transcript.push_str(&format!(
-                    "{}:\n{}\n\n",
-                    response.display_name(),
-                    response.response
-                ));
-            }
-            transcript.push('\n');
-        }
+        let position_changes = compute_position_changes(&result.stances);
+        let transcript = format!(
+            "{}{}",
+            synthesis_transcript(&result.rounds, 1),
+            position_changes_section(&position_changes)
+        );
 
         let synthesis_prompt = format!(
             r#"[SYNTHESIS REQUEST]
@@ -376,61 +1339,59 @@ TOPIC: {}
 DEBATE TRANSCRIPT:
 {}
 
-Generate a structured synthesis with:
-1. A 2-3 sentence summary of the debate
-2. Points where participants reached consensus
-3. Points where participants disagreed
-4. Key insights that emerged
-5. A recommendation (if applicable)
+{}
 
-Respond with JSON in this exact format:
-{{
-  "summary": "...",
-  "consensus_points": ["...", "..."],
-  "disagreement_points": ["...", "..."],
-  "key_insights": ["...", "..."],
-  "recommendation": "..." or null
-}}
+{}
 [/SYNTHESIS REQUEST]"#,
-            result.topic, transcript
+            result.topic, transcript, SYNTHESIS_FORMAT, CITATION_FORMAT
         );
 
-        // Get the synthesizer invoker
-        let invoker = get_invoker(synthesizer_cli).ok_or_else(|| {
+        eprintln!("Generating synthesis with {}...", synthesizer_cli);
+        let response = self
+            .synthesizer(synthesizer_cli)
+            .await?
+            .invoke(&synthesis_prompt, timeout, access_mode, None)
+            .await?;
+
+        // Parse the JSON from the response
+        let mut synthesis = Self::parse_synthesis_response(&response)?;
+        synthesis.position_changes = position_changes;
+        citations::verify(&mut synthesis, &result.rounds);
+        Ok(synthesis)
+    }
+
+    /// The synthesizer's invoker, checked to be available
+    pub(crate) async fn synthesizer(
+        &self,
+        synthesizer_cli: &str,
+    ) -> anyhow::Result<Box<dyn Invoker>> {
+        let invoker = self.invokers.get(synthesizer_cli).ok_or_else(|| {
             anyhow::anyhow!(
                 "Synthesizer CLI '{}' not found. Use claude, codex, gemini, or an installed plugin.",
                 synthesizer_cli
             )
         })?;
 
-        if !invoker.is_available() {
+        if !invoker.is_available().await {
             return Err(anyhow::anyhow!(
                 "Synthesizer CLI '{}' is not available in PATH.",
                 synthesizer_cli
             ));
         }
-
-        eprintln!("Generating synthesis with {}...", synthesizer_cli);
-        let response = invoker
-            .invoke(&synthesis_prompt, timeout, access_mode, None)
-            .await?;
-
-        // Parse the JSON from the response
-        Self::parse_synthesis_response(&response)
+        Ok(invoker)
     }
 
     /// Parse synthesis JSON from LLM response
-    fn parse_synthesis_response(response: &str) -> anyhow::Result<Synthesis> {
-        // Try to extract JSON from the response (it may be wrapped in markdown or text)
-        let json_start = response.find('{');
-        let json_end = response.rfind('}');
-
-        if let (Some(start), Some(end)) = (json_start, json_end) {
-            if start < end {
-                let json_str = &response[start..=end];
-                if let Ok(synthesis) = serde_json::from_str::<Synthesis>(json_str) {
-                    return Ok(synthesis);
-                }
+    pub(crate) fn parse_synthesis_response(response: &str) -> anyhow::Result<Synthesis> {
+        // The JSON may be wrapped in markdown or text
+        let json = find_json(response, "synthesis", |value| {
+            value
+                .get("summary")
+                .is_some_and(serde_json::Value::is_string)
+        });
+        if let Ok(json) = json {
+            if let Ok(synthesis) = serde_json::from_str::<Synthesis>(json) {
+                return Ok(synthesis);
             }
         }
 
@@ -441,30 +1402,36 @@ Respond with JSON in this exact format:
             disagreement_points: vec![],
             key_insights: vec![],
             recommendation: None,
+            position_changes: vec![],
+            unverified_citations: vec![],
         })
     }
 
     /// Run a debate with default participants (Claude, Codex, Gemini without personas)
     pub async fn run_debate(
+        &self,
         topic: &str,
         num_rounds: usize,
         timeout: u64,
         access_mode: AccessMode,
     ) -> anyhow::Result<DebateResult> {
-        let participants = vec![
-            Participant::new("claude".to_string(), None),
-            Participant::new("codex".to_string(), None),
-            Participant::new("gemini".to_string(), None),
-        ];
-
-        Self::run_debate_with_participants(topic, participants, num_rounds, timeout, access_mode)
-            .await
+        self.run_debate_with_participants(
+            topic,
+            Self::default_participants(),
+            num_rounds,
+            timeout,
+            access_mode,
+            PromptContext::default(),
+            RoundOptions::default(),
+        )
+        .await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_round_response_creation() {
@@ -472,6 +1439,7 @@ mod tests {
             cli: "Claude".to_string(),
             persona: None,
             response: "This is Claude's perspective".to_string(),
+            log: None,
         };
 
         assert_eq!(response.cli, "Claude");
@@ -486,6 +1454,7 @@ mod tests {
             cli: "Claude".to_string(),
             persona: Some("CEO".to_string()),
             response: "From a CEO perspective...".to_string(),
+            log: None,
         };
 
         assert_eq!(response.cli, "Claude");
@@ -499,6 +1468,7 @@ mod tests {
             cli: "Codex".to_string(),
             persona: Some("Architect".to_string()),
             response: "This is Codex's perspective".to_string(),
+            log: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -512,6 +1482,7 @@ mod tests {
     #[test]
     fn test_debate_result_creation() {
         let result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
             gptengage_version: Some("0.1.0".to_string()),
             topic: "Should we use Rust?".to_string(),
             rounds: vec![vec![
@@ -519,14 +1490,24 @@ mod tests {
                     cli: "Claude".to_string(),
                     persona: None,
                     response: "Yes, Rust is great".to_string(),
+                    log: None,
                 },
                 RoundResponse {
                     cli: "Gemini".to_string(),
                     persona: None,
                     response: "Go is simpler".to_string(),
+                    log: None,
                 },
             ]],
             synthesis: None,
+            stances: vec![],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
         };
 
         assert_eq!(result.topic, "Should we use Rust?");
@@ -544,11 +1525,13 @@ mod tests {
                     cli: "Claude".to_string(),
                     persona: None,
                     response: "Round 1: Claude's view".to_string(),
+                    log: None,
                 },
                 RoundResponse {
                     cli: "Codex".to_string(),
                     persona: None,
                     response: "Round 1: Codex's view".to_string(),
+                    log: None,
                 },
             ],
             // Round 2
@@ -557,20 +1540,31 @@ mod tests {
                     cli: "Claude".to_string(),
                     persona: None,
                     response: "Round 2: Claude's refined view".to_string(),
+                    log: None,
                 },
                 RoundResponse {
                     cli: "Codex".to_string(),
                     persona: None,
                     response: "Round 2: Codex's refined view".to_string(),
+                    log: None,
                 },
             ],
         ];
 
         let result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
             gptengage_version: None,
             topic: "Test Topic".to_string(),
             rounds,
             synthesis: None,
+            stances: vec![],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
         };
 
         assert_eq!(result.rounds.len(), 2);
@@ -582,6 +1576,7 @@ mod tests {
     #[test]
     fn test_debate_result_serialization() {
         let result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
             gptengage_version: Some("0.1.0".to_string()),
             topic: "Tabs vs Spaces".to_string(),
             rounds: vec![vec![
@@ -589,14 +1584,24 @@ mod tests {
                     cli: "Claude".to_string(),
                     persona: None,
                     response: "Tabs are consistent".to_string(),
+                    log: None,
                 },
                 RoundResponse {
                     cli: "Gemini".to_string(),
                     persona: None,
                     response: "Spaces are standard".to_string(),
+                    log: None,
                 },
             ]],
             synthesis: None,
+            stances: vec![],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -607,25 +1612,289 @@ mod tests {
         assert_eq!(deserialized.rounds[0].len(), 2);
     }
 
+    #[test]
+    fn test_debate_result_migrates_older_versions() {
+        // As written by releases before schema_version existed
+        let legacy = r#"{
+            "gptengage_version": "1.1.2",
+            "topic": "Should we use Rust?",
+            "rounds": [[{"cli": "claude", "persona": null, "response": "Yes"}]],
+            "synthesis": null
+        }"#;
+        let result = DebateResult::from_json(legacy).unwrap();
+        assert_eq!(result.schema_version, DEBATE_RESULT_SCHEMA_VERSION);
+        assert_eq!(result.rounds[0][0].response, "Yes");
+
+        let current = serde_json::to_string(&result).unwrap();
+        assert!(current.starts_with("{\"schema_version\":2,"));
+        assert_eq!(
+            DebateResult::from_json(&current).unwrap().topic,
+            result.topic
+        );
+
+        let newer = r#"{"schema_version": 99, "topic": "T", "rounds": []}"#;
+        let err = DebateResult::from_json(newer).unwrap_err().to_string();
+        assert!(err.contains("newer than this gptengage supports"));
+    }
+
     #[test]
     fn test_debate_result_empty_rounds() {
         let result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
             gptengage_version: None,
             topic: "Empty debate".to_string(),
             rounds: vec![],
             synthesis: None,
+            stances: vec![],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
         };
 
         assert_eq!(result.rounds.len(), 0);
         assert_eq!(result.topic, "Empty debate");
     }
 
+    fn round(text: &str) -> Vec<RoundResponse> {
+        vec![RoundResponse {
+            cli: "claude".to_string(),
+            persona: None,
+            response: text.to_string(),
+            log: None,
+        }]
+    }
+
+    #[test]
+    fn test_round_context_history_rounds() {
+        let previous = vec![round("first"), round("second"), round("third")];
+
+        let default = DebateOrchestrator::round_context("T", 4, &previous, 1);
+        assert!(default.contains("Previous responses:\nclaude: third"));
+        assert!(!default.contains("second"));
+
+        let two = DebateOrchestrator::round_context("T", 4, &previous, 2);
+        assert!(two.contains("Previous responses (round 2):\nclaude: second"));
+        assert!(two.contains("Previous responses (round 3):\nclaude: third"));
+        assert!(!two.contains("first"));
+
+        let none = DebateOrchestrator::round_context("T", 4, &previous, 0);
+        assert!(!none.contains("Previous responses"));
+    }
+
+    #[test]
+    fn test_phase_context() {
+        let two_phase = PromptContext {
+            converge_after: Some(2),
+            ..PromptContext::default()
+        };
+        let phases: Vec<String> = (1..=3)
+            .map(|round| two_phase.phase_context(round, format!("Round {}", round)))
+            .collect();
+        assert!(phases[0].starts_with("Round 1\n\n[PHASE: DIVERGE]"));
+        assert!(phases[1].contains("[PHASE: DIVERGE]"));
+        assert!(phases[2].starts_with("Round 3\n\n[PHASE: CONVERGE]"));
+        assert_eq!(
+            PromptContext::default().phase_context(1, "Round 1".to_string()),
+            "Round 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_round_cancels_stragglers() {
+        let mut tasks = JoinSet::new();
+        for (i, delay) in [(0, 10), (1, 5_000), (2, 20)] {
+            tasks.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                (i, format!("response {}", i))
+            });
+        }
+        let (mut finished, cancelled) =
+            join_round(tasks, 3, Some(Duration::from_millis(500))).await;
+        finished.sort();
+        assert_eq!(
+            finished,
+            vec![(0, "response 0".to_string()), (2, "response 2".to_string())]
+        );
+        assert_eq!(cancelled, vec![1]);
+
+        let mut tasks = JoinSet::new();
+        tasks.spawn(async { (0, ()) });
+        let (finished, cancelled) = join_round(tasks, 1, None).await;
+        assert_eq!((finished.len(), cancelled.len()), (1, 0));
+    }
+
+    #[test]
+    fn test_streamed_response_is_labeled() {
+        let response = RoundResponse {
+            cli: "codex".to_string(),
+            persona: Some("SRE".to_string()),
+            response: "Add a queue.\n".to_string(),
+            log: None,
+        };
+        assert_eq!(
+            streamed_response(2, &response, Duration::from_millis(12_340)),
+            "── Round 2 · codex (SRE) (12.3s) ──\nAdd a queue.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_prompt_context_persona_toggle() {
+        let participant = Participant::new("claude".to_string(), Some("CTO".to_string()));
+        let with_persona = PromptContext::default().participant_prompt(&participant, "base");
+        assert!(with_persona.contains("[ROLE CONTEXT]"));
+
+        let bare = PromptContext {
+            persona_context: false,
+            ..PromptContext::default()
+        };
+        assert_eq!(bare.participant_prompt(&participant, "base"), "base");
+    }
+
+    fn agent_file(personas: &[&str]) -> AgentFile {
+        AgentFile {
+            schema_version: "1.0".to_string(),
+            generated_by: Some("gptengage-claude".to_string()),
+            participants: personas
+                .iter()
+                .map(|persona| AgentDefinition {
+                    cli: "claude".to_string(),
+                    model: None,
+                    persona: persona.to_string(),
+                    instructions: "Argue from this role's priorities.".to_string(),
+                    expertise: vec![],
+                    communication_style: None,
+                    output_filters: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_agent_file_merge() {
+        let merged = AgentFile::merge(vec![
+            ("leads.json".to_string(), agent_file(&["CEO", "CTO"])),
+            ("ops.json".to_string(), agent_file(&["SRE"])),
+        ])
+        .unwrap();
+        let personas: Vec<&str> = merged
+            .participants
+            .iter()
+            .map(|a| a.persona.as_str())
+            .collect();
+        assert_eq!(personas, vec!["CEO", "CTO", "SRE"]);
+        assert_eq!(merged.generated_by, None);
+
+        let err = AgentFile::merge(vec![
+            ("leads.json".to_string(), agent_file(&["CEO", "CTO"])),
+            ("tech.json".to_string(), agent_file(&["cto"])),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Duplicate persona 'cto' in 'tech.json' (already defined in 'leads.json')"
+        );
+    }
+
+    #[test]
+    fn test_agent_file_formats_round_trip() {
+        assert_eq!(AgentFileFormat::from_path("a.TOML"), AgentFileFormat::Toml);
+        assert_eq!(AgentFileFormat::from_path("a.yml"), AgentFileFormat::Yaml);
+        assert_eq!(AgentFileFormat::from_path("agents"), AgentFileFormat::Json);
+
+        let mut file = agent_file(&["CEO", "CTO"]);
+        file.participants[1].model = Some("gpt-4o".to_string());
+        for format in [
+            AgentFileFormat::Json,
+            AgentFileFormat::Toml,
+            AgentFileFormat::Yaml,
+        ] {
+            let parsed = AgentFile::parse(&file.render(format).unwrap(), format).unwrap();
+            parsed.validate().unwrap();
+            assert_eq!(parsed.participants.len(), 2);
+            assert_eq!(parsed.participants[1].model.as_deref(), Some("gpt-4o"));
+            assert_eq!(parsed.generated_by, file.generated_by);
+        }
+
+        let toml = r#"
+[[participants]]
+cli = "claude"
+persona = "SRE"
+instructions = "Focus on operability and failure modes."
+"#;
+        let parsed = AgentFile::parse(toml, AgentFileFormat::Toml).unwrap();
+        assert_eq!(parsed.schema_version, "1.0");
+        assert_eq!(parsed.participants[0].persona, "SRE");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_agent_file_never_panics(content in "\\PC*") {
+            for format in [AgentFileFormat::Json, AgentFileFormat::Toml, AgentFileFormat::Yaml] {
+                if let Ok(file) = AgentFile::parse(&content, format) {
+                    let _ = file.validate();
+                }
+            }
+        }
+
+        #[test]
+        fn prop_agent_file_round_trips(
+            cli in "\\PC{0,10}",
+            persona in "\\PC{0,20}",
+            instructions in "\\PC{0,40}",
+            model in prop::option::of("\\PC{0,10}"),
+        ) {
+            let mut file = agent_file(&[&persona]);
+            file.participants[0].cli = cli;
+            file.participants[0].instructions = instructions;
+            file.participants[0].model = model;
+            for format in [AgentFileFormat::Json, AgentFileFormat::Toml, AgentFileFormat::Yaml] {
+                let parsed = AgentFile::parse(&file.render(format).unwrap(), format).unwrap();
+                let (agent, original) = (&parsed.participants[0], &file.participants[0]);
+                prop_assert_eq!(&agent.cli, &original.cli);
+                prop_assert_eq!(&agent.persona, &original.persona);
+                prop_assert_eq!(&agent.instructions, &original.instructions);
+                prop_assert_eq!(&agent.model, &original.model);
+                prop_assert_eq!(parsed.validate().is_ok(), file.validate().is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_agent_file_apply_edit() {
+        let mut file = agent_file(&["CEO", "CTO"]);
+        file.apply_edit("2.model=gpt-4o").unwrap();
+        file.apply_edit("*.expertise=strategy, hiring").unwrap();
+        file.apply_edit("1.communication_style=Blunt").unwrap();
+        assert_eq!(file.participants[0].model, None);
+        assert_eq!(file.participants[1].model.as_deref(), Some("gpt-4o"));
+        assert_eq!(file.participants[1].expertise, vec!["strategy", "hiring"]);
+        assert_eq!(
+            file.participants[0].communication_style.as_deref(),
+            Some("Blunt")
+        );
+
+        file.apply_edit("2.model=").unwrap();
+        assert_eq!(file.participants[1].model, None);
+
+        assert!(file.apply_edit("3.model=x").is_err());
+        assert!(file.apply_edit("1.color=red").is_err());
+        assert!(file.apply_edit("model=x").is_err());
+
+        file.apply_edit("1.instructions=short").unwrap();
+        assert!(file.validate().is_err());
+    }
+
     #[test]
     fn test_round_response_clone() {
         let response1 = RoundResponse {
             cli: "Claude".to_string(),
             persona: Some("CEO".to_string()),
             response: "Test response".to_string(),
+            log: None,
         };
 
         let response2 = response1.clone();
@@ -642,6 +1911,7 @@ mod tests {
             cli: "Claude".to_string(),
             persona: None,
             response: long_response.clone(),
+            log: None,
         };
 
         assert_eq!(response.response.len(), 10000);
@@ -655,14 +1925,24 @@ mod tests {
     #[test]
     fn test_debate_result_with_special_chars() {
         let result = DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
             gptengage_version: Some("0.1.0".to_string()),
             topic: "Test with 特殊 characters & symbols! 🚀".to_string(),
             rounds: vec![vec![RoundResponse {
                 cli: "Claude".to_string(),
                 persona: None,
                 response: "Response with unicode: émojis: 🎉".to_string(),
+                log: None,
             }]],
             synthesis: None,
+            stances: vec![],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -699,4 +1979,80 @@ mod tests {
         assert!(prompt2.contains("CEO"));
         assert!(prompt2.contains(base));
     }
+
+    /// Hangs on its first call and answers every later one at once
+    struct SlowFirst {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::invokers::Invoker for SlowFirst {
+        async fn invoke(
+            &self,
+            _prompt: &str,
+            _timeout: u64,
+            _access_mode: AccessMode,
+            _model: Option<&str>,
+        ) -> anyhow::Result<String> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if call == 0 {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            Ok(format!("answer {}", call))
+        }
+
+        fn name(&self) -> &str {
+            "claude"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_hedged_takes_first_answer() {
+        let invoker = std::sync::Arc::new(SlowFirst {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let orchestrator = DebateOrchestrator::new(InvokerRegistry::new().with(invoker.clone()));
+        let participant = Participant::new("claude".to_string(), None);
+
+        let started = Instant::now();
+        let response = orchestrator
+            .invoke_hedged(
+                participant,
+                "Tabs or spaces?".to_string(),
+                60,
+                AccessMode::ReadOnly,
+                Some(Duration::from_millis(50)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.response, "answer 1");
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(invoker.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_hedged_never_duplicates_writes() {
+        let invoker = std::sync::Arc::new(SlowFirst {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let orchestrator = DebateOrchestrator::new(InvokerRegistry::new().with(invoker.clone()));
+        let participant = Participant::new("claude".to_string(), None);
+
+        let hedged = orchestrator.invoke_hedged(
+            participant,
+            "Rename the module".to_string(),
+            60,
+            AccessMode::WorkspaceWrite,
+            Some(Duration::from_millis(50)),
+        );
+        // The only request hangs; no duplicate is started to overtake it
+        assert!(tokio::time::timeout(Duration::from_millis(500), hedged)
+            .await
+            .is_err());
+        assert_eq!(invoker.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,181 @@
+//! Fact check - Verifying a synthesis against its debate
+//!
+//! With `--fact-check <CLI>`, one more CLI call reads the synthesis next to
+//! the transcript it summarizes and flags each claim the transcript doesn't
+//! support: things nobody said, consensus that wasn't reached, or positions
+//! attributed to the wrong participant. Every flagged claim quotes the
+//! passages it was checked against. Quotes that don't appear in the
+//! transcript are dropped, so a checker can't invent evidence either.
+
+use super::debate::{synthesis_transcript, DebateOrchestrator, DebateResult};
+use crate::invokers::AccessMode;
+use crate::utils::json::extract_json_object;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A synthesis claim the transcript doesn't support
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct FlaggedClaim {
+    /// The claim, as worded in the synthesis
+    pub claim: String,
+    /// Why the transcript doesn't support it
+    pub reason: String,
+    /// Transcript passages relevant to the claim, quoted verbatim
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub passages: Vec<String>,
+}
+
+/// The verification section of a fact-checked debate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct FactCheck {
+    /// The CLI that checked the synthesis
+    pub checker: String,
+    /// Unsupported claims; empty when every claim checked out
+    #[serde(default)]
+    pub flagged: Vec<FlaggedClaim>,
+}
+
+/// Prompt asking for the synthesis claims the transcript doesn't support
+pub fn fact_check_prompt(result: &DebateResult) -> String {
+    let synthesis = result
+        .synthesis
+        .as_ref()
+        .map(|synthesis| serde_json::to_string_pretty(synthesis).unwrap_or_default())
+        .unwrap_or_default();
+    format!(
+        r#"[FACT CHECK REQUEST]
+You are checking a debate synthesis against the debate it summarizes.
+
+TOPIC: {}
+
+DEBATE TRANSCRIPT:
+{}
+SYNTHESIS:
+{}
+
+Check every claim in the synthesis (summary, consensus points,
+disagreements, insights, and recommendation) against the transcript. Flag
+only claims the transcript does not support: statements nobody made,
+consensus that was not reached, disagreements that did not happen, or
+positions attributed to the wrong participant. For each, quote the
+transcript passages you checked it against, copied exactly.
+
+Respond with JSON in this exact format (an empty list if every claim holds):
+{{
+  "flagged": [
+    {{
+      "claim": "...",
+      "reason": "...",
+      "passages": ["exact quote from the transcript", "..."]
+    }}
+  ]
+}}
+[/FACT CHECK REQUEST]"#,
+        result.topic,
+        synthesis_transcript(&result.rounds, 1),
+        synthesis
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFactCheck {
+    #[serde(default)]
+    flagged: Vec<FlaggedClaim>,
+}
+
+/// `text` with runs of whitespace collapsed, for matching quotes
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parse the flagged claims in a response, keeping only passages quoted
+/// from `transcript`
+pub fn parse_fact_check(
+    response: &str,
+    transcript: &str,
+    checker: &str,
+) -> anyhow::Result<FactCheck> {
+    let json = extract_json_object(response)?;
+    let raw: RawFactCheck = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("Invalid fact check JSON: {}", e))?;
+    let transcript = normalize(transcript);
+    let mut flagged = raw.flagged;
+    flagged.retain(|claim| !claim.claim.trim().is_empty());
+    for claim in &mut flagged {
+        let quoted = claim.passages.len();
+        claim.passages = claim
+            .passages
+            .iter()
+            .map(|passage| normalize(passage.trim_matches('"')))
+            .filter(|passage| !passage.is_empty() && transcript.contains(passage.as_str()))
+            .collect();
+        if claim.passages.len() < quoted {
+            eprintln!(
+                "Warning: dropped {} quote(s) not found in the transcript for claim \"{}\"",
+                quoted - claim.passages.len(),
+                claim.claim
+            );
+        }
+    }
+    Ok(FactCheck {
+        checker: checker.to_string(),
+        flagged,
+    })
+}
+
+impl DebateOrchestrator {
+    /// Ask `cli` to flag the claims in a debate's synthesis that its
+    /// transcript doesn't support
+    pub async fn fact_check(
+        &self,
+        result: &DebateResult,
+        cli: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+    ) -> anyhow::Result<FactCheck> {
+        let invoker = self.invokers().get(cli).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Fact check CLI '{}' not found. Use claude, codex, gemini, or an installed plugin.",
+                cli
+            )
+        })?;
+        if !invoker.is_available().await {
+            anyhow::bail!("Fact check CLI '{}' is not available in PATH.", cli);
+        }
+
+        eprintln!("Fact-checking the synthesis with {}...", cli);
+        let response = invoker
+            .invoke(&fact_check_prompt(result), timeout, access_mode, None)
+            .await?;
+        parse_fact_check(&response, &synthesis_transcript(&result.rounds, 1), cli)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fact_check_drops_invented_quotes() {
+        let transcript =
+            "ROUND 1:\nclaude (CTO):\nWe should   migrate\nthe billing service first.\n\n";
+        let response = r#"```json
+{"flagged": [
+  {"claim": "Everyone agreed to migrate billing", "reason": "Only the CTO proposed it",
+   "passages": ["We should migrate the billing service first.", "Billing is fine as is"]},
+  {"claim": " ", "reason": "empty"}
+]}
+```"#;
+        let check = parse_fact_check(response, transcript, "codex").unwrap();
+        assert_eq!(check.checker, "codex");
+        assert_eq!(check.flagged.len(), 1);
+        assert_eq!(
+            check.flagged[0].passages,
+            vec!["We should migrate the billing service first."]
+        );
+
+        let clean = parse_fact_check(r#"{"flagged": []}"#, transcript, "codex").unwrap();
+        assert!(clean.flagged.is_empty());
+        assert!(parse_fact_check("looks fine", transcript, "codex").is_err());
+    }
+}
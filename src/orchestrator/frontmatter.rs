@@ -0,0 +1,161 @@
+//! Topic frontmatter - Structured requirements ahead of a debate topic
+//!
+//! A topic (argument or piped stdin) may open with YAML frontmatter:
+//!
+//! ```text
+//! ---
+//! constraints: [Keep the Postgres schema, No downtime]
+//! success_criteria: [p99 under 200ms]
+//! non_goals: [Multi-region]
+//! ---
+//! How should we shard the orders table?
+//! ```
+//!
+//! The frontmatter is parsed and validated, removed from the topic text,
+//! and given to every participant as a `[TOPIC BRIEF]` block ahead of the
+//! topic. It is kept in the transcript as `frontmatter`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Requirements a topic's frontmatter sets out
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TopicFrontmatter {
+    /// Limits any answer has to respect
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<String>,
+    /// How to tell a good answer
+    #[serde(
+        default,
+        alias = "success-criteria",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub success_criteria: Vec<String>,
+    /// What is deliberately out of scope
+    #[serde(default, alias = "non-goals", skip_serializing_if = "Vec::is_empty")]
+    pub non_goals: Vec<String>,
+}
+
+impl TopicFrontmatter {
+    /// Each field with its heading, in prompt order
+    fn sections(&self) -> [(&'static str, &[String]); 3] {
+        [
+            ("Constraints", &self.constraints),
+            ("Success criteria", &self.success_criteria),
+            ("Non-goals", &self.non_goals),
+        ]
+    }
+
+    /// Reject frontmatter with nothing in it or with blank entries
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.sections().iter().all(|(_, items)| items.is_empty()) {
+            anyhow::bail!(
+                "Topic frontmatter is empty. Set constraints, success_criteria, or non_goals"
+            );
+        }
+        for (heading, items) in self.sections() {
+            if items.iter().any(|item| item.trim().is_empty()) {
+                anyhow::bail!("Topic frontmatter has a blank entry in {}", heading);
+            }
+        }
+        Ok(())
+    }
+
+    /// The frontmatter as a block for participant prompts
+    pub fn prompt_block(&self) -> String {
+        let mut block = String::from("[TOPIC BRIEF]\n");
+        for (heading, items) in self.sections() {
+            if items.is_empty() {
+                continue;
+            }
+            block.push_str(&format!("{}:\n", heading));
+            for item in items {
+                block.push_str(&format!("- {}\n", item.trim()));
+            }
+        }
+        block.push_str("Keep your answer within this brief.\n");
+        block.push_str("[/TOPIC BRIEF]");
+        block
+    }
+
+    /// The frontmatter as markdown, one bulleted list per field
+    pub fn markdown(&self) -> String {
+        let mut out = String::new();
+        for (heading, items) in self.sections() {
+            if items.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("**{}:**\n\n", heading));
+            for item in items {
+                out.push_str(&format!("- {}\n", item.trim()));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Split leading `---` frontmatter off `text`. Text without frontmatter is
+/// returned unchanged; frontmatter that is unterminated, isn't valid YAML,
+/// or has unknown or blank fields is an error.
+pub fn split_frontmatter(text: &str) -> anyhow::Result<(Option<TopicFrontmatter>, String)> {
+    let trimmed = text.trim_start();
+    let Some(rest) = trimmed
+        .strip_prefix("---\n")
+        .or_else(|| trimmed.strip_prefix("---\r\n"))
+    else {
+        return Ok((None, text.to_string()));
+    };
+
+    let mut yaml_len = None;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            yaml_len = Some(offset);
+            offset += line.len();
+            break;
+        }
+        offset += line.len();
+    }
+    let yaml_len = yaml_len.ok_or_else(|| {
+        anyhow::anyhow!("Topic frontmatter starting with '---' has no closing '---' line")
+    })?;
+
+    let frontmatter: TopicFrontmatter = serde_norway::from_str(&rest[..yaml_len])
+        .map_err(|e| anyhow::anyhow!("Invalid topic frontmatter: {}", e))?;
+    frontmatter.validate()?;
+    Ok((Some(frontmatter), rest[offset..].trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_frontmatter() {
+        let (frontmatter, topic) = split_frontmatter(
+            "---\nconstraints:\n  - No downtime\nnon-goals: [Multi-region]\n---\n\nShard orders?\n",
+        )
+        .unwrap();
+        let frontmatter = frontmatter.unwrap();
+        assert_eq!(frontmatter.constraints, vec!["No downtime"]);
+        assert_eq!(frontmatter.non_goals, vec!["Multi-region"]);
+        assert_eq!(topic, "Shard orders?");
+        assert_eq!(
+            frontmatter.prompt_block(),
+            "[TOPIC BRIEF]\nConstraints:\n- No downtime\nNon-goals:\n- Multi-region\n\
+             Keep your answer within this brief.\n\
+             [/TOPIC BRIEF]"
+        );
+
+        let (none, topic) = split_frontmatter("Tabs or spaces?").unwrap();
+        assert!(none.is_none());
+        assert_eq!(topic, "Tabs or spaces?");
+
+        assert!(split_frontmatter("---\nconstraints: [a]\nShard?").is_err());
+        assert!(split_frontmatter("---\ndeadline: Friday\n---\nShard?").is_err());
+        assert!(split_frontmatter("---\nconstraints: ['']\n---\nShard?").is_err());
+        assert!(split_frontmatter("---\n---\nShard?").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Participant health - Bench participants that keep failing
+//!
+//! A participant that fails (or is cancelled at a round deadline) several
+//! rounds in a row is benched for the rounds that follow, so its repeated
+//! timeouts don't dominate the debate's runtime. Benched participants can
+//! be re-probed with a tiny prompt before the final round and rejoin if
+//! they answer.
+
+use super::debate::{DebateOrchestrator, Participant};
+use crate::invokers::AccessMode;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Longest a re-probe may take, in seconds
+const PROBE_TIMEOUT: u64 = 30;
+
+/// A participant benched during a debate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct Benching {
+    /// Participant label (display name, suffixed with #N for duplicates)
+    pub participant: String,
+    /// First round the participant sat out
+    pub from_round: usize,
+    /// Consecutive failures that led to the benching
+    pub failures: usize,
+    /// Round the participant rejoined after a successful re-probe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejoined: Option<usize>,
+}
+
+/// Consecutive failures and benchings for a debate's participants
+pub struct ParticipantHealth {
+    /// Failures in a row before benching (`None` never benches)
+    bench_after: Option<usize>,
+    failures: Vec<usize>,
+    /// Index into `benchings` for each participant currently benched
+    benched: Vec<Option<usize>>,
+    pub benchings: Vec<Benching>,
+}
+
+impl ParticipantHealth {
+    pub fn new(count: usize, bench_after: Option<usize>) -> Self {
+        Self {
+            bench_after,
+            failures: vec![0; count],
+            benched: vec![None; count],
+            benchings: Vec::new(),
+        }
+    }
+
+    pub fn is_benched(&self, i: usize) -> bool {
+        self.benched[i].is_some()
+    }
+
+    /// Participants currently benched
+    pub fn benched(&self) -> Vec<usize> {
+        (0..self.benched.len())
+            .filter(|i| self.is_benched(*i))
+            .collect()
+    }
+
+    /// Record how a round went for the participants that took part, and
+    /// bench those that reached the failure limit. `names` labels each
+    /// participant for the transcript.
+    pub fn end_round(&mut self, round: usize, responded: &[bool], names: &[String]) {
+        for (i, responded) in responded.iter().enumerate() {
+            if self.is_benched(i) {
+                continue;
+            }
+            if *responded {
+                self.failures[i] = 0;
+                continue;
+            }
+            self.failures[i] += 1;
+            if self
+                .bench_after
+                .is_some_and(|limit| self.failures[i] >= limit)
+            {
+                eprintln!(
+                    "Benching {} after {} consecutive failure(s)",
+                    names[i], self.failures[i]
+                );
+                self.benched[i] = Some(self.benchings.len());
+                self.benchings.push(Benching {
+                    participant: names[i].clone(),
+                    from_round: round + 1,
+                    failures: self.failures[i],
+                    rejoined: None,
+                });
+            }
+        }
+    }
+
+    /// Bring a benched participant back from `round` on
+    pub fn rejoin(&mut self, i: usize, round: usize) {
+        if let Some(benching) = self.benched[i].take() {
+            self.benchings[benching].rejoined = Some(round);
+            self.failures[i] = 0;
+        }
+    }
+}
+
+impl DebateOrchestrator {
+    /// Ask a participant for a one-word reply to check it has recovered
+    pub async fn probe_participant(
+        &self,
+        participant: &Participant,
+        timeout: u64,
+        access_mode: AccessMode,
+    ) -> bool {
+        let Some(invoker) = self.invokers().get(&participant.cli) else {
+            return false;
+        };
+        if !invoker.is_available().await {
+            return false;
+        }
+        invoker
+            .invoke(
+                "Reply with the single word OK.",
+                timeout.min(PROBE_TIMEOUT),
+                access_mode,
+                participant.model.as_deref(),
+            )
+            .await
+            .is_ok_and(|reply| !reply.trim().is_empty())
+    }
+
+    /// Re-probe every benched participant, in parallel, and let those that
+    /// answer rejoin from `round`
+    pub(crate) async fn reprobe_benched(
+        &self,
+        health: &mut ParticipantHealth,
+        participants: &[Participant],
+        names: &[String],
+        round: usize,
+        timeout: u64,
+        access_mode: AccessMode,
+    ) {
+        let benched = health.benched();
+        if benched.is_empty() {
+            return;
+        }
+        let probes = benched
+            .iter()
+            .map(|i| self.probe_participant(&participants[*i], timeout, access_mode));
+        let results = futures::future::join_all(probes).await;
+        for (i, ok) in benched.into_iter().zip(results) {
+            if ok {
+                eprintln!(
+                    "{} answered a re-probe; rejoining for round {}",
+                    names[i], round
+                );
+                health.rejoin(i, round);
+            } else {
+                eprintln!("{} failed a re-probe; staying benched", names[i]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benches_after_consecutive_failures() {
+        let names = vec!["claude".to_string(), "codex".to_string()];
+        let mut health = ParticipantHealth::new(2, Some(2));
+
+        health.end_round(1, &[true, false], &names);
+        health.end_round(2, &[false, true], &names);
+        assert!(health.benched().is_empty(), "failures must be consecutive");
+
+        health.end_round(3, &[false, false], &names);
+        assert_eq!(health.benched(), vec![0]);
+        assert_eq!(health.benchings[0].from_round, 4);
+        assert_eq!(health.benchings[0].failures, 2);
+
+        health.rejoin(0, 5);
+        assert!(!health.is_benched(0));
+        assert_eq!(health.benchings[0].rejoined, Some(5));
+
+        let mut never = ParticipantHealth::new(1, None);
+        for round in 1..=5 {
+            never.end_round(round, &[false], &names);
+        }
+        assert!(never.benchings.is_empty());
+    }
+}
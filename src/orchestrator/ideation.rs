@@ -1,6 +1,7 @@
 //! Ideation orchestrator - Generates divergent idea trees from a seed
 
-use crate::invokers::{get_invoker, AccessMode};
+use crate::invokers::{AccessMode, InvokerRegistry};
+use crate::utils::json::extract_json_object;
 use serde::{Deserialize, Serialize};
 use tokio::task;
 
@@ -234,26 +235,10 @@ Return ONLY valid JSON:
     )
 }
 
-/// Extract a JSON object from LLM response text
-pub fn extract_json_object(text: &str) -> anyhow::Result<String> {
-    // Find the first '{' and last '}' to extract the JSON object
-    if let Some(start) = text.find('{') {
-        if let Some(end) = text.rfind('}') {
-            if start < end {
-                return Ok(text[start..=end].to_string());
-            }
-        }
-    }
-
-    Err(anyhow::anyhow!(
-        "Could not find valid JSON object in response. Expected format: {{...}}"
-    ))
-}
-
 /// Parse raw ideas from an LLM response into IdeaNodes with proper IDs
 fn parse_ideas_response(response: &str, id_prefix: &str) -> anyhow::Result<Vec<IdeaNode>> {
     let json_str = extract_json_object(response)?;
-    let raw: RawIdeationResponse = serde_json::from_str(&json_str).map_err(|e| {
+    let raw: RawIdeationResponse = serde_json::from_str(json_str).map_err(|e| {
         anyhow::anyhow!(
             "Failed to parse ideation response. Error: {}\nJSON: {}",
             e,
@@ -299,20 +284,21 @@ fn parse_ideas_response(response: &str, id_prefix: &str) -> anyhow::Result<Vec<I
 
 /// Generate Level 1 ideas from a seed
 pub async fn generate_l1(
+    invokers: &InvokerRegistry,
     seed: &str,
     sigma: f32,
     cli: &str,
     timeout: u64,
     access_mode: AccessMode,
 ) -> anyhow::Result<Vec<IdeaNode>> {
-    let invoker = get_invoker(cli).ok_or_else(|| {
+    let invoker = invokers.get(cli).ok_or_else(|| {
         anyhow::anyhow!(
             "CLI '{}' not found. Use claude, codex, gemini, or an installed plugin.",
             cli
         )
     })?;
 
-    if !invoker.is_available() {
+    if !invoker.is_available().await {
         return Err(anyhow::anyhow!("CLI '{}' is not available in PATH.", cli));
     }
 
@@ -349,6 +335,7 @@ fn find_node_mut<'a>(ideas: &'a mut [IdeaNode], id: &str) -> Option<&'a mut Idea
 
 /// Expand all leaf nodes one level deeper (in parallel)
 pub async fn expand_leaves(
+    invokers: &InvokerRegistry,
     ideas: &mut [IdeaNode],
     sigma: f32,
     cli: &str,
@@ -373,9 +360,11 @@ pub async fn expand_leaves(
         let id_prefix = id.clone();
         let siblings = sibling_summaries.clone();
         let cli_name = cli.to_string();
+        let invokers = invokers.clone();
 
         let task_handle = task::spawn(async move {
-            let invoker = get_invoker(&cli_name)
+            let invoker = invokers
+                .get(&cli_name)
                 .ok_or_else(|| anyhow::anyhow!("CLI '{}' not found.", cli_name))?;
 
             let prompt = build_l2_prompt(&parent_desc, sigma, &siblings);
@@ -410,6 +399,7 @@ pub async fn expand_leaves(
 
 /// Generate Level 2 ideas for selected L1 nodes (in parallel)
 pub async fn generate_l2(
+    invokers: &InvokerRegistry,
     l1_ideas: &mut [IdeaNode],
     selected_indices: &[usize],
     sigma: f32,
@@ -433,9 +423,11 @@ pub async fn generate_l2(
         let id_prefix = idea.id.clone();
         let siblings = sibling_summaries.clone();
         let cli_name = cli.to_string();
+        let invokers = invokers.clone();
 
         let task_handle = task::spawn(async move {
-            let invoker = get_invoker(&cli_name)
+            let invoker = invokers
+                .get(&cli_name)
                 .ok_or_else(|| anyhow::anyhow!("CLI '{}' not found.", cli_name))?;
 
             let prompt = build_l2_prompt(&parent_desc, sigma, &siblings);
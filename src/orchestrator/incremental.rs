@@ -0,0 +1,260 @@
+//! Incremental synthesis - A running synthesis updated after every round
+//!
+//! With `--incremental-synthesis`, each round's responses go to the
+//! synthesizer as soon as the round closes, together with the synthesis so
+//! far, instead of the whole transcript once the debate is over. Updates
+//! run while the next round does, the evolving summary is printed after
+//! each one, and the call made after the debate only covers what the last
+//! update didn't. A failed update keeps the previous draft, and its rounds
+//! go into the next update.
+
+use super::citations::CITATION_FORMAT;
+use super::debate::{
+    position_changes_section, synthesis_transcript, DebateOrchestrator, RoundResponse, Synthesis,
+    SYNTHESIS_FORMAT,
+};
+use super::stance::PositionChange;
+use crate::invokers::AccessMode;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// The synthesis so far, and the rounds it doesn't cover yet
+#[derive(Debug, Clone, Default)]
+pub struct SynthesisDraft {
+    /// Latest synthesis (None until the first update succeeds)
+    pub synthesis: Option<Synthesis>,
+    /// Number of rounds the synthesis covers
+    pub rounds: usize,
+    /// Rounds received since
+    pub pending: Vec<Vec<RoundResponse>>,
+}
+
+/// Where and how the synthesizer runs
+#[derive(Debug, Clone)]
+pub struct SynthesizerOptions {
+    pub cli: String,
+    pub timeout: u64,
+    pub access_mode: AccessMode,
+}
+
+impl SynthesisDraft {
+    /// Prompt asking the synthesizer to fold the pending rounds (and any
+    /// position changes) into the draft
+    pub fn update_prompt(&self, topic: &str, position_changes: &[PositionChange]) -> String {
+        let new_material = format!(
+            "{}{}",
+            synthesis_transcript(&self.pending, self.rounds + 1),
+            position_changes_section(position_changes)
+        );
+        let previous = self
+            .synthesis
+            .as_ref()
+            .and_then(|synthesis| serde_json::to_string_pretty(synthesis).ok());
+        match previous {
+            Some(previous) => format!(
+                r#"[SYNTHESIS UPDATE]
+You are keeping a running synthesis of a multi-participant debate up to date.
+
+TOPIC: {}
+
+SYNTHESIS OF ROUNDS 1-{}:
+{}
+
+NEW IN THE DEBATE:
+{}
+
+Update the synthesis so it covers the whole debate so far. Keep points that
+still hold, revise or drop points the new material changes, and add new ones.
+
+{}
+
+{}
+[/SYNTHESIS UPDATE]"#,
+                topic, self.rounds, previous, new_material, SYNTHESIS_FORMAT, CITATION_FORMAT
+            ),
+            None => format!(
+                r#"[SYNTHESIS REQUEST]
+You are synthesizing a multi-participant debate that is still in progress.
+
+TOPIC: {}
+
+DEBATE TRANSCRIPT:
+{}
+
+{}
+
+{}
+[/SYNTHESIS REQUEST]"#,
+                topic, new_material, SYNTHESIS_FORMAT, CITATION_FORMAT
+            ),
+        }
+    }
+}
+
+impl DebateOrchestrator {
+    /// Fold the draft's pending rounds and `position_changes` into its
+    /// synthesis. On failure the draft is left as it was.
+    pub async fn update_synthesis(
+        &self,
+        topic: &str,
+        draft: &mut SynthesisDraft,
+        position_changes: &[PositionChange],
+        synthesizer: &SynthesizerOptions,
+    ) -> anyhow::Result<()> {
+        let prompt = draft.update_prompt(topic, position_changes);
+        let response = self
+            .synthesizer(&synthesizer.cli)
+            .await?
+            .invoke(&prompt, synthesizer.timeout, synthesizer.access_mode, None)
+            .await?;
+        draft.synthesis = Some(Self::parse_synthesis_response(&response)?);
+        draft.rounds += draft.pending.len();
+        draft.pending.clear();
+        Ok(())
+    }
+
+    /// Update a draft synthesis with every round received from `rounds`,
+    /// printing the summary after each update, until the sender side
+    /// closes. Rounds that close during an update go into the next one.
+    pub async fn synthesize_incrementally(
+        self,
+        topic: String,
+        mut rounds: UnboundedReceiver<Vec<RoundResponse>>,
+        synthesizer: SynthesizerOptions,
+    ) -> SynthesisDraft {
+        let mut draft = SynthesisDraft::default();
+        while let Some(round) = rounds.recv().await {
+            draft.pending.push(round);
+            while let Ok(round) = rounds.try_recv() {
+                draft.pending.push(round);
+            }
+            match self
+                .update_synthesis(&topic, &mut draft, &[], &synthesizer)
+                .await
+            {
+                Ok(()) => {
+                    if let Some(synthesis) = &draft.synthesis {
+                        println!(
+                            "Synthesis after round {}: {}",
+                            draft.rounds, synthesis.summary
+                        );
+                    }
+                }
+                Err(e) => eprintln!(
+                    "Warning: synthesis update failed; trying again with the next round: {:#}",
+                    e
+                ),
+            }
+        }
+        draft
+    }
+
+    /// The final synthesis from a draft, folding in whatever the draft
+    /// doesn't cover yet
+    pub async fn finish_synthesis(
+        &self,
+        topic: &str,
+        mut draft: SynthesisDraft,
+        position_changes: Vec<PositionChange>,
+        synthesizer: &SynthesizerOptions,
+    ) -> anyhow::Result<Synthesis> {
+        if draft.synthesis.is_none() || !draft.pending.is_empty() || !position_changes.is_empty() {
+            eprintln!("Finishing synthesis with {}...", synthesizer.cli);
+            self.update_synthesis(topic, &mut draft, &position_changes, synthesizer)
+                .await?;
+        }
+        let mut synthesis = draft
+            .synthesis
+            .ok_or_else(|| anyhow::anyhow!("No synthesis was generated"))?;
+        synthesis.position_changes = position_changes;
+        Ok(synthesis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invokers::{Invoker, InvokerRegistry};
+    use std::sync::{Arc, Mutex};
+
+    /// Summarizes by counting the rounds it is shown, recording prompts
+    struct Counter {
+        prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Invoker for Counter {
+        async fn invoke(
+            &self,
+            prompt: &str,
+            _timeout: u64,
+            _access_mode: AccessMode,
+            _model: Option<&str>,
+        ) -> anyhow::Result<String> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            Ok(format!(
+                r#"{{"summary": "saw {} new round(s)"}}"#,
+                prompt.matches("\nROUND ").count()
+            ))
+        }
+
+        fn name(&self) -> &str {
+            "claude"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn round(text: &str) -> Vec<RoundResponse> {
+        vec![RoundResponse {
+            cli: "codex".to_string(),
+            persona: None,
+            response: text.to_string(),
+            log: None,
+        }]
+    }
+
+    #[tokio::test]
+    async fn test_synthesis_is_updated_round_by_round() {
+        let counter = Arc::new(Counter {
+            prompts: Mutex::new(Vec::new()),
+        });
+        let orchestrator = DebateOrchestrator::new(InvokerRegistry::new().with(counter.clone()));
+        let synthesizer = SynthesizerOptions {
+            cli: "claude".to_string(),
+            timeout: 5,
+            access_mode: AccessMode::ReadOnly,
+        };
+
+        let (sink, rounds) = tokio::sync::mpsc::unbounded_channel();
+        sink.send(round("Use tabs")).unwrap();
+        sink.send(round("Use spaces")).unwrap();
+        drop(sink);
+        let draft = orchestrator
+            .clone()
+            .synthesize_incrementally("Tabs".to_string(), rounds, synthesizer.clone())
+            .await;
+        // Both rounds were waiting, so they went into one update
+        assert_eq!(draft.rounds, 2);
+        assert_eq!(
+            draft.synthesis.as_ref().unwrap().summary,
+            "saw 2 new round(s)"
+        );
+
+        let mut draft = draft;
+        draft.pending.push(round("Spaces, then"));
+        let synthesis = orchestrator
+            .finish_synthesis("Tabs", draft, Vec::new(), &synthesizer)
+            .await
+            .unwrap();
+        assert_eq!(synthesis.summary, "saw 1 new round(s)");
+
+        let prompts = counter.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].contains("SYNTHESIS OF ROUNDS 1-2:"));
+        assert!(prompts[1].contains("saw 2 new round(s)"));
+        assert!(prompts[1].contains("ROUND 3:\ncodex:\nSpaces, then"));
+        assert!(!prompts[1].contains("Use tabs"));
+    }
+}
@@ -0,0 +1,110 @@
+//! Judge plugins - Local evaluators that score candidate answers
+//!
+//! Anywhere gptengage asks a judge to compare answers (`invoke --best-of`),
+//! the judge can be a plugin with `kind = "judge"` instead of an LLM CLI.
+//! The plugin reads one JSON request on stdin:
+//!
+//! ```json
+//! {"request": "...", "candidates": ["...", "..."], "prompt": "..."}
+//! ```
+//!
+//! where `prompt` is the judge prompt an LLM CLI would have been sent, and
+//! prints one score per candidate, higher being better:
+//!
+//! ```json
+//! {"scores": [7.5, 9.0], "justification": "..."}
+//! ```
+//!
+//! The highest-scoring candidate wins; ties go to the earlier one.
+
+use super::best_of::{build_judge_prompt, Judgement};
+use crate::invokers::plugin::PluginInvoker;
+use crate::invokers::{AccessMode, Invoker};
+use crate::plugins::PluginConfig;
+use crate::utils::json::extract_json_object;
+use serde::{Deserialize, Serialize};
+
+/// What a judge plugin receives on stdin
+#[derive(Debug, Serialize)]
+pub struct JudgeRequest<'a> {
+    pub request: &'a str,
+    pub candidates: &'a [String],
+    /// The judge prompt an LLM CLI would be sent
+    pub prompt: String,
+}
+
+/// What a judge plugin prints
+#[derive(Debug, Deserialize)]
+struct JudgeScores {
+    scores: Vec<f64>,
+    #[serde(default)]
+    justification: String,
+}
+
+/// Parse a judge plugin's scores for `count` candidates
+pub fn parse_scores(response: &str, count: usize) -> anyhow::Result<Judgement> {
+    let json = extract_json_object(response)?;
+    let raw: JudgeScores = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("Failed to parse judge scores: {}", e))?;
+    if raw.scores.len() != count {
+        anyhow::bail!(
+            "Judge returned {} score(s) for {} candidates",
+            raw.scores.len(),
+            count
+        );
+    }
+    if raw.scores.iter().any(|score| !score.is_finite()) {
+        anyhow::bail!("Judge scores must be finite numbers");
+    }
+    let mut winner = 0;
+    for (i, score) in raw.scores.iter().enumerate() {
+        if *score > raw.scores[winner] {
+            winner = i;
+        }
+    }
+    Ok(Judgement {
+        winner,
+        justification: raw.justification.trim().to_string(),
+        scores: raw.scores,
+    })
+}
+
+/// Have the judge plugin `config` score the candidates for `request`
+pub async fn judge_with_plugin(
+    config: &PluginConfig,
+    request: &str,
+    candidates: &[String],
+    timeout: u64,
+) -> anyhow::Result<Judgement> {
+    let input = serde_json::to_string(&JudgeRequest {
+        request,
+        candidates,
+        prompt: build_judge_prompt(request, candidates),
+    })?;
+    let response = PluginInvoker::new(config.clone())
+        .with_allowlist()
+        .invoke(&input, timeout, AccessMode::ReadOnly, None)
+        .await?;
+    parse_scores(&response, candidates.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scores() {
+        let judgement = parse_scores(
+            r#"{"scores": [3, 9.5, 9.5], "justification": " Fewest bugs "}"#,
+            3,
+        )
+        .unwrap();
+        assert_eq!(judgement.winner, 1);
+        assert_eq!(judgement.scores, vec![3.0, 9.5, 9.5]);
+        assert_eq!(judgement.justification, "Fewest bugs");
+
+        assert!(parse_scores(r#"{"scores": [1, 2]}"#, 3).is_err());
+        assert!(parse_scores(r#"{"winner": 1}"#, 1).is_err());
+        assert!(parse_scores("ok", 1).is_err());
+    }
+}
@@ -1,6 +1,23 @@
 //! Debate orchestrator - Coordinates multi-AI debates
 
+pub mod actions;
+pub mod best_of;
+pub mod citations;
+pub mod continuation;
 pub mod debate;
+pub mod fact_check;
+pub mod frontmatter;
+pub mod health;
 pub mod ideation;
+pub mod incremental;
+pub mod judge;
+pub mod narration;
+pub mod observers;
+pub mod overflow;
+pub mod postprocess;
+pub mod response_format;
+pub mod risks;
+pub mod round_robin;
+pub mod stance;
 
 pub use debate::*;
@@ -0,0 +1,170 @@
+//! Narration - How much a debate reports while it runs
+//!
+//! Round banners are always shown. With `-vv`, each participant's answer
+//! (or failure) is reported with how long it took; with `-vvv`, so are
+//! the size of each prompt as sent and what each output filter did. These
+//! details are `Narration` events, reported on stderr by
+//! `DebateOrchestrator::narrate` when its verbosity is high enough.
+
+use super::debate::DebateOrchestrator;
+use std::fmt;
+use std::time::Duration;
+
+/// How much a debate narrates, from `-v` flags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Round banners only (the default, and `-v`)
+    #[default]
+    Rounds,
+    /// Plus each participant's response time (`-vv`)
+    Timings,
+    /// Plus prompt sizes and output filter actions (`-vvv`)
+    Prompts,
+}
+
+impl Verbosity {
+    /// The level for a count of `-v` flags
+    pub fn from_count(count: u8) -> Self {
+        match count {
+            0 | 1 => Self::Rounds,
+            2 => Self::Timings,
+            _ => Self::Prompts,
+        }
+    }
+}
+
+/// A detail of a running debate
+#[derive(Debug, Clone, PartialEq)]
+pub enum Narration {
+    /// A participant answered or failed after `elapsed`
+    Timing {
+        participant: String,
+        elapsed: Duration,
+        answered: bool,
+    },
+    /// A prompt is about to be sent to a participant
+    Prompt {
+        participant: String,
+        chars: usize,
+        tokens: usize,
+    },
+    /// An output filter ran over a participant's response
+    Filter {
+        participant: String,
+        filter: String,
+        /// Characters before and after the filter
+        before: usize,
+        after: usize,
+    },
+}
+
+impl Narration {
+    /// The verbosity that shows this event
+    pub fn verbosity(&self) -> Verbosity {
+        match self {
+            Narration::Timing { .. } => Verbosity::Timings,
+            Narration::Prompt { .. } | Narration::Filter { .. } => Verbosity::Prompts,
+        }
+    }
+}
+
+impl fmt::Display for Narration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Narration::Timing {
+                participant,
+                elapsed,
+                answered: true,
+            } => write!(
+                f,
+                "{} answered in {:.1}s",
+                participant,
+                elapsed.as_secs_f64()
+            ),
+            Narration::Timing {
+                participant,
+                elapsed,
+                answered: false,
+            } => write!(
+                f,
+                "{} failed after {:.1}s",
+                participant,
+                elapsed.as_secs_f64()
+            ),
+            Narration::Prompt {
+                participant,
+                chars,
+                tokens,
+            } => write!(
+                f,
+                "Sending {} a {}-character prompt (~{} tokens)",
+                participant, chars, tokens
+            ),
+            Narration::Filter {
+                participant,
+                filter,
+                before,
+                after,
+            } if before == after => write!(
+                f,
+                "Filter '{}' left {}'s response unchanged",
+                filter, participant
+            ),
+            Narration::Filter {
+                participant,
+                filter,
+                before,
+                after,
+            } => write!(
+                f,
+                "Filter '{}' cut {}'s response from {} to {} characters",
+                filter, participant, before, after
+            ),
+        }
+    }
+}
+
+impl DebateOrchestrator {
+    /// Whether events at `level` are reported
+    pub fn narrates(&self, level: Verbosity) -> bool {
+        self.verbosity() >= level
+    }
+
+    /// Report `event` on stderr if the verbosity allows
+    pub fn narrate(&self, event: Narration) {
+        if self.narrates(event.verbosity()) {
+            eprintln!("  {}", event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_levels() {
+        assert_eq!(Verbosity::from_count(0), Verbosity::Rounds);
+        assert_eq!(Verbosity::from_count(2), Verbosity::Timings);
+        assert_eq!(Verbosity::from_count(5), Verbosity::Prompts);
+
+        let timing = Narration::Timing {
+            participant: "claude (CTO)".to_string(),
+            elapsed: Duration::from_millis(12_340),
+            answered: true,
+        };
+        assert_eq!(timing.verbosity(), Verbosity::Timings);
+        assert_eq!(timing.to_string(), "claude (CTO) answered in 12.3s");
+        let filter = Narration::Filter {
+            participant: "codex".to_string(),
+            filter: "max-lines:40".to_string(),
+            before: 5210,
+            after: 1030,
+        };
+        assert_eq!(filter.verbosity(), Verbosity::Prompts);
+        assert_eq!(
+            filter.to_string(),
+            "Filter 'max-lines:40' cut codex's response from 5210 to 1030 characters"
+        );
+    }
+}
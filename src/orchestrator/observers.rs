@@ -0,0 +1,128 @@
+//! Debate observers - Participants who watch instead of speaking
+//!
+//! With `--observer <cli:persona>`, an observer (a "red team observer", a
+//! "skeptical CFO") sits out every round and, once the debate is over,
+//! reads the whole transcript and writes an independent critique of it:
+//! blind spots, weak arguments, and what the participants agreed on too
+//! easily. Observers don't see the synthesis or each other's critiques.
+//! Critiques are kept in the transcript as their own section.
+
+use super::debate::{synthesis_transcript, DebateOrchestrator, DebateResult, Participant};
+use crate::invokers::AccessMode;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+/// An observer's critique of the whole debate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct Critique {
+    /// The observer, as `cli (persona)`
+    pub observer: String,
+    pub critique: String,
+}
+
+/// Prompt asking an observer to critique a finished debate
+pub fn observer_prompt(result: &DebateResult) -> String {
+    format!(
+        r#"[OBSERVER REQUEST]
+You watched the debate below without taking part. Write an independent
+critique of it as a whole, from your own perspective:
+- Arguments that were weak, unsupported, or went unchallenged
+- Risks, options, and stakeholders nobody raised
+- Points the participants agreed on too easily
+- What you would tell the decision maker before acting on this debate
+
+Be specific and refer to participants by name. Don't summarize the debate.
+
+TOPIC: {}
+
+DEBATE TRANSCRIPT:
+{}[/OBSERVER REQUEST]"#,
+        result.topic,
+        synthesis_transcript(&result.rounds, 1)
+    )
+}
+
+impl DebateOrchestrator {
+    /// Have each observer critique the finished debate, in parallel.
+    /// Observers that fail are reported and left out.
+    pub async fn observe(
+        &self,
+        result: &DebateResult,
+        observers: &[Participant],
+        timeout: u64,
+        access_mode: AccessMode,
+    ) -> Vec<Critique> {
+        let prompt = observer_prompt(result);
+        let mut tasks = JoinSet::new();
+        for (i, observer) in observers.iter().enumerate() {
+            eprintln!(
+                "Asking observer {} for a critique...",
+                observer.display_name()
+            );
+            let prompt = observer.build_prompt_with_persona(&prompt);
+            let (observer, orchestrator) = (observer.clone(), self.clone());
+            tasks.spawn(async move {
+                let name = observer.display_name();
+                let response = orchestrator
+                    .invoke_participant(observer, prompt, timeout, access_mode)
+                    .await;
+                (i, name, response)
+            });
+        }
+
+        let mut critiques = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((i, observer, Some(response))) = joined {
+                critiques.push((
+                    i,
+                    Critique {
+                        observer,
+                        critique: response.response.trim().to_string(),
+                    },
+                ));
+            }
+        }
+        // Critiques stay in the order observers were given
+        critiques.sort_by_key(|(i, _)| *i);
+        critiques
+            .into_iter()
+            .map(|(_, critique)| critique)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::RoundResponse;
+
+    #[test]
+    fn test_observer_prompt() {
+        let result = DebateResult {
+            schema_version: 2,
+            gptengage_version: None,
+            topic: "Adopt Rust?".to_string(),
+            rounds: vec![vec![RoundResponse {
+                cli: "codex".to_string(),
+                persona: Some("CTO".to_string()),
+                response: "Yes, for the parser.".to_string(),
+                log: None,
+            }]],
+            synthesis: None,
+            stances: vec![],
+            benched: vec![],
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
+        };
+        let observer = Participant::new("claude".to_string(), Some("Red Team".to_string()));
+        let prompt = observer.build_prompt_with_persona(&observer_prompt(&result));
+        assert!(prompt.starts_with("[ROLE CONTEXT]"));
+        assert!(prompt.contains("TOPIC: Adopt Rust?"));
+        assert!(prompt.contains("codex (CTO):\nYes, for the parser."));
+    }
+}
@@ -0,0 +1,228 @@
+//! Context overflow - Fitting each participant's prompt to its model
+//!
+//! A participant whose model has a small context window (declared in the
+//! `context_windows` config key or a plugin's `[invoke] context_window`)
+//! gets its own, smaller view of the debate, so nobody else is cut down to
+//! the smallest window. A prompt may use three quarters of the window,
+//! leaving the rest for the reply. Prompts over that first lose their
+//! oldest rounds of history, then have the responses left in them
+//! shortened evenly until they fit.
+
+use super::debate::{Participant, RoundResponse};
+use crate::config::ConfigManager;
+use crate::plugins::PluginManager;
+use crate::utils::tokens::{counter_for, TokenCounter};
+use std::borrow::Cow;
+
+/// Marks a response cut short to fit a context window
+const TRIMMED: &str = "\n[... trimmed to fit context window]";
+
+/// How much of the debate a participant's prompt shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct View {
+    /// Previous rounds of responses to include
+    pub history_rounds: usize,
+    /// Characters kept of each response shown (None for all)
+    pub response_chars: Option<usize>,
+}
+
+impl View {
+    /// A view with nothing trimmed
+    pub fn full(history_rounds: usize) -> Self {
+        Self {
+            history_rounds,
+            response_chars: None,
+        }
+    }
+
+    /// `text` as this view shows a response
+    pub fn shorten<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.response_chars {
+            Some(chars) if text.chars().count() > chars => {
+                let kept: String = text.chars().take(chars).collect();
+                Cow::Owned(format!("{}{}", kept.trim_end(), TRIMMED))
+            }
+            _ => Cow::Borrowed(text),
+        }
+    }
+
+    /// `rounds` with every response shortened for this view
+    pub fn shorten_rounds(&self, rounds: &[Vec<RoundResponse>]) -> Vec<Vec<RoundResponse>> {
+        rounds
+            .iter()
+            .map(|round| {
+                round
+                    .iter()
+                    .map(|response| RoundResponse {
+                        response: self.shorten(&response.response).into_owned(),
+                        ..response.clone()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// What this view trims, compared with showing `history_rounds` rounds
+    fn describe(&self, history_rounds: usize) -> String {
+        let mut trims = Vec::new();
+        if self.history_rounds < history_rounds {
+            trims.push(format!(
+                "kept {} of {} rounds of history",
+                self.history_rounds, history_rounds
+            ));
+        }
+        if let Some(chars) = self.response_chars {
+            trims.push(format!("responses shortened to {} characters", chars));
+        }
+        trims.join(", ")
+    }
+}
+
+/// The largest view whose prompt, as built by `build`, is at most `budget`
+/// tokens. Returns the prompt and what was trimmed, if anything. When even
+/// the smallest view is over budget it is returned anyway.
+pub fn fit_view(
+    build: impl Fn(View) -> String,
+    history_rounds: usize,
+    budget: usize,
+    counter: &dyn TokenCounter,
+) -> (String, Option<String>) {
+    let fits = |prompt: &str| counter.count(prompt) <= budget;
+    let full = build(View::full(history_rounds));
+    if fits(&full) {
+        return (full, None);
+    }
+
+    // Oldest rounds go first, keeping at least the last one
+    let min_rounds = history_rounds.min(1);
+    for rounds in (min_rounds..history_rounds).rev() {
+        let view = View::full(rounds);
+        let prompt = build(view);
+        if fits(&prompt) {
+            return (prompt, Some(view.describe(history_rounds)));
+        }
+    }
+
+    // Then the responses left are shortened evenly
+    let shortened = |chars| View {
+        history_rounds: min_rounds,
+        response_chars: Some(chars),
+    };
+    let smallest = build(shortened(0));
+    if !fits(&smallest) {
+        let note = format!(
+            "{}; still over the window",
+            shortened(0).describe(history_rounds)
+        );
+        return (smallest, Some(note));
+    }
+    let (mut low, mut high) = (0, full.chars().count());
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if fits(&build(shortened(mid))) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    (
+        build(shortened(low)),
+        Some(shortened(low).describe(history_rounds)),
+    )
+}
+
+/// A participant's context window and the tokenizer to measure it with
+pub struct ContextWindow {
+    tokens: usize,
+    counter: Box<dyn TokenCounter>,
+}
+
+impl ContextWindow {
+    /// The declared context window of `participant`'s model, if any: its
+    /// `cli:model` or `cli` entry in `context_windows`, else its plugin's
+    /// in `plugins`
+    pub fn of(participant: &Participant, plugins: Option<&PluginManager>) -> Option<Self> {
+        let (cli, model) = (participant.cli.as_str(), participant.model.as_deref());
+        let configured = ConfigManager::shared().ok().and_then(|resolved| {
+            let windows = &resolved.config.context_windows;
+            model
+                .and_then(|model| windows.get(&format!("{}:{}", cli, model)))
+                .or_else(|| windows.get(cli))
+                .copied()
+        });
+        let declared = || plugins?.get_plugin(cli)?.invoke.context_window;
+        let tokens = configured.or_else(declared)?;
+        Some(Self {
+            tokens,
+            counter: counter_for(cli, model),
+        })
+    }
+
+    /// Tokens a prompt may use, leaving a quarter of the window for the reply
+    pub fn prompt_budget(&self) -> usize {
+        self.tokens - self.tokens / 4
+    }
+
+    /// The prompt for the largest view that fits, noting on stderr what
+    /// was trimmed for `name`
+    pub fn fit(&self, name: &str, history_rounds: usize, build: impl Fn(View) -> String) -> String {
+        let (prompt, note) = fit_view(
+            build,
+            history_rounds,
+            self.prompt_budget(),
+            self.counter.as_ref(),
+        );
+        if let Some(note) = note {
+            eprintln!(
+                "Fitting {} into its {}-token context window: {}",
+                name, self.tokens, note
+            );
+        }
+        prompt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::DebateOrchestrator;
+    use crate::utils::tokens::ApproxCounter;
+
+    fn round(text: &str) -> Vec<RoundResponse> {
+        vec![RoundResponse {
+            cli: "claude".to_string(),
+            persona: None,
+            response: text.to_string(),
+            log: None,
+        }]
+    }
+
+    #[test]
+    fn test_fit_view() {
+        let previous = vec![round(&"old ".repeat(50)), round(&"new ".repeat(50))];
+        let build = |view: View| {
+            DebateOrchestrator::round_context(
+                "T",
+                3,
+                &view.shorten_rounds(&previous),
+                view.history_rounds,
+            )
+        };
+        let counter = ApproxCounter;
+
+        let (prompt, note) = fit_view(build, 2, 1000, &counter);
+        assert!(prompt.contains("old") && note.is_none());
+
+        let (prompt, note) = fit_view(build, 2, 100, &counter);
+        assert!(!prompt.contains("old") && !prompt.contains(TRIMMED));
+        assert_eq!(note.unwrap(), "kept 1 of 2 rounds of history");
+
+        let (prompt, note) = fit_view(build, 2, 60, &counter);
+        assert!(counter.count(&prompt) <= 60);
+        assert!(prompt.contains("new") && prompt.contains(TRIMMED));
+        assert!(note.unwrap().contains("responses shortened to"));
+
+        let (_, note) = fit_view(build, 2, 1, &counter);
+        assert!(note.unwrap().ends_with("still over the window"));
+    }
+}
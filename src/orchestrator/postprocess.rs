@@ -0,0 +1,269 @@
+//! Response postprocessing - Per-participant output filters
+//!
+//! Templates and agent definitions can give a participant a list of
+//! `output_filters`, applied in order to each of its responses before the
+//! response is recorded and shown to the other participants in later
+//! rounds. A filter that can't apply (no JSON in the response, say) is
+//! skipped with a warning rather than dropping the response.
+
+use crate::utils::json::find_json;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Longest first paragraph treated as preamble
+const MAX_PREAMBLE_CHARS: usize = 300;
+
+/// Openers of filler paragraphs ("Sure! Here's my analysis:")
+const PREAMBLE_OPENERS: &[&str] = &[
+    "sure",
+    "certainly",
+    "of course",
+    "absolutely",
+    "great question",
+    "good question",
+    "okay",
+    "ok,",
+    "here is",
+    "here's",
+    "here are",
+    "below is",
+    "as requested",
+    "happy to",
+    "i'd be happy",
+    "i'll ",
+    "let me ",
+];
+
+/// One filter, written as a string in templates and agent files:
+/// `strip-preamble`, `json`, `drop-lines:<regex>`, `max-lines:<n>`, or
+/// `max-chars:<n>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ResponseFilter {
+    /// Drop a leading filler paragraph ("Sure, here is my take:")
+    StripPreamble,
+    /// Keep only the first JSON object or array, pretty-printed
+    Json,
+    /// Drop lines matching a regex
+    DropLines(String),
+    /// Keep the first N lines
+    MaxLines(usize),
+    /// Keep the first N characters
+    MaxChars(usize),
+}
+
+impl ResponseFilter {
+    /// Parse a filter spec
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let spec = spec.trim();
+        let (name, arg) = match spec.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg)),
+            None => (spec, None),
+        };
+        let limit = |arg: Option<&str>| -> anyhow::Result<usize> {
+            arg.and_then(|n| n.trim().parse().ok())
+                .filter(|&n| n > 0)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("'{}' needs a positive number, e.g. {}:40", spec, name)
+                })
+        };
+        match (name, arg) {
+            ("strip-preamble", None) => Ok(Self::StripPreamble),
+            ("json", None) => Ok(Self::Json),
+            ("drop-lines", Some(pattern)) => {
+                Regex::new(pattern).map_err(|e| {
+                    anyhow::anyhow!("Invalid regex in output filter '{}': {}", spec, e)
+                })?;
+                Ok(Self::DropLines(pattern.to_string()))
+            }
+            ("max-lines", _) => Ok(Self::MaxLines(limit(arg)?)),
+            ("max-chars", _) => Ok(Self::MaxChars(limit(arg)?)),
+            _ => anyhow::bail!(
+                "Unknown output filter '{}'. Use strip-preamble, json, \
+                 drop-lines:<regex>, max-lines:<n>, or max-chars:<n>",
+                spec
+            ),
+        }
+    }
+
+    /// Apply the filter, failing when it doesn't fit the response
+    pub fn apply(&self, response: &str) -> anyhow::Result<String> {
+        match self {
+            Self::StripPreamble => Ok(strip_preamble(response)),
+            Self::Json => extract_json(response),
+            Self::DropLines(pattern) => {
+                let regex = Regex::new(pattern)?;
+                Ok(response
+                    .lines()
+                    .filter(|line| !regex.is_match(line))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+            Self::MaxLines(max) => {
+                let lines: Vec<&str> = response.lines().collect();
+                if lines.len() <= *max {
+                    return Ok(response.to_string());
+                }
+                Ok(format!(
+                    "{}\n[... {} more lines omitted]",
+                    lines[..*max].join("\n"),
+                    lines.len() - max
+                ))
+            }
+            Self::MaxChars(max) => {
+                let total = response.chars().count();
+                if total <= *max {
+                    return Ok(response.to_string());
+                }
+                let kept: String = response.chars().take(*max).collect();
+                Ok(format!(
+                    "{}\n[... {} more characters omitted]",
+                    kept.trim_end(),
+                    total - max
+                ))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for ResponseFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(spec: String) -> anyhow::Result<Self> {
+        Self::parse(&spec)
+    }
+}
+
+impl From<ResponseFilter> for String {
+    fn from(filter: ResponseFilter) -> Self {
+        match filter {
+            ResponseFilter::StripPreamble => "strip-preamble".to_string(),
+            ResponseFilter::Json => "json".to_string(),
+            ResponseFilter::DropLines(pattern) => format!("drop-lines:{}", pattern),
+            ResponseFilter::MaxLines(n) => format!("max-lines:{}", n),
+            ResponseFilter::MaxChars(n) => format!("max-chars:{}", n),
+        }
+    }
+}
+
+/// Drop the first paragraph when it is filler before the actual answer
+fn strip_preamble(response: &str) -> String {
+    let text = response.trim_start();
+    let Some((first, rest)) = text.split_once("\n\n") else {
+        return response.to_string();
+    };
+    let first = first.trim();
+    let lower = first.to_lowercase();
+    let filler = PREAMBLE_OPENERS
+        .iter()
+        .any(|opener| lower.starts_with(opener))
+        || (!first.contains('\n') && first.ends_with(':'));
+    if filler && first.chars().count() <= MAX_PREAMBLE_CHARS && !rest.trim().is_empty() {
+        rest.trim_start_matches('\n').to_string()
+    } else {
+        response.to_string()
+    }
+}
+
+/// The first JSON object or array in a response (fenced or inline)
+fn extract_json(response: &str) -> anyhow::Result<String> {
+    let json = find_json(response, "object or array", |value| {
+        value.is_object() || value.is_array()
+    })?;
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Apply `filters` in order, skipping (with a warning naming `who`) any
+/// that don't fit the response
+pub fn apply_filters(filters: &[ResponseFilter], response: String, who: &str) -> String {
+    filters
+        .iter()
+        .fold(response, |text, filter| match filter.apply(&text) {
+            Ok(filtered) => filtered,
+            Err(e) => {
+                eprintln!(
+                    "Warning: output filter '{}' skipped for {}: {}",
+                    String::from(filter.clone()),
+                    who,
+                    e
+                );
+                text
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filters() {
+        assert_eq!(
+            ResponseFilter::parse("strip-preamble").unwrap(),
+            ResponseFilter::StripPreamble
+        );
+        assert_eq!(
+            ResponseFilter::parse("max-lines: 40").unwrap(),
+            ResponseFilter::MaxLines(40)
+        );
+        assert_eq!(
+            ResponseFilter::parse("drop-lines:^Note:").unwrap(),
+            ResponseFilter::DropLines("^Note:".to_string())
+        );
+        assert!(ResponseFilter::parse("max-chars:0").is_err());
+        assert!(ResponseFilter::parse("drop-lines:(").is_err());
+        assert!(ResponseFilter::parse("summarize").is_err());
+
+        // Specs round-trip through serde as strings
+        let filters: Vec<ResponseFilter> =
+            serde_json::from_str(r#"["json", "max-chars:500"]"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&filters).unwrap(),
+            r#"["json","max-chars:500"]"#
+        );
+    }
+
+    #[test]
+    fn test_apply_filters() {
+        let response = "Sure! Here's my review of the change.\n\n\
+                        ```json\n[{\"severity\": \"high\", \"file\": \"auth.rs\"}]\n```\n\n\
+                        Let me know if you need more.";
+        assert!(strip_preamble(response).starts_with("```json"));
+        assert_eq!(
+            strip_preamble("The cache is the bottleneck.\n\nDetails follow."),
+            "The cache is the bottleneck.\n\nDetails follow."
+        );
+
+        let filters = [ResponseFilter::StripPreamble, ResponseFilter::Json];
+        assert_eq!(
+            apply_filters(&filters, response.to_string(), "claude"),
+            "[\n  {\n    \"file\": \"auth.rs\",\n    \"severity\": \"high\"\n  }\n]"
+        );
+        // No JSON: the filter is skipped and the response kept
+        assert_eq!(
+            apply_filters(
+                &[ResponseFilter::Json],
+                "No findings.".to_string(),
+                "claude"
+            ),
+            "No findings."
+        );
+
+        let long = "a\nb\nc\nd";
+        assert_eq!(
+            ResponseFilter::MaxLines(2).apply(long).unwrap(),
+            "a\nb\n[... 2 more lines omitted]"
+        );
+        assert_eq!(
+            ResponseFilter::DropLines("^[bc]$".to_string())
+                .apply(long)
+                .unwrap(),
+            "a\nd"
+        );
+        assert_eq!(
+            ResponseFilter::MaxChars(3).apply("héllo").unwrap(),
+            "hél\n[... 2 more characters omitted]"
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Response formats - Checking answers against a requested format
+//!
+//! `--response-format` (or the `response_formats` config key, per command)
+//! names the format every answer must be in: `json` (a single JSON value
+//! and nothing else), `markdown` (Markdown, not HTML and not wrapped in a
+//! code block), or `language:<LANG>` (written in that language). An answer
+//! that doesn't match is sent back once with a corrective prompt; if the
+//! corrected answer still doesn't match, it is kept with a warning.
+//!
+//! Languages are recognized offline: by script for non-Latin languages,
+//! and by common words for Latin-script ones. Code is ignored, and answers
+//! too short to tell pass.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+
+/// Commands `response_formats` can be set for
+pub const COMMANDS: &[&str] = &["invoke", "debate"];
+
+/// Fewest common-word hits before a Latin-script language is judged
+const MIN_WORD_HITS: usize = 5;
+
+/// A language answers can be required in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Common words, for Latin-script languages
+    words: &'static [&'static str],
+}
+
+/// Languages recognized by script or by common words
+const LANGUAGES: &[Language] = &[
+    Language {
+        code: "en",
+        name: "English",
+        words: &[
+            "the", "and", "is", "are", "of", "to", "in", "that", "it", "with", "for", "this", "be",
+            "not", "you", "on", "as",
+        ],
+    },
+    Language {
+        code: "es",
+        name: "Spanish",
+        words: &[
+            "el", "los", "las", "que", "y", "es", "una", "por", "con", "para", "del", "se", "lo",
+            "como", "pero", "más",
+        ],
+    },
+    Language {
+        code: "fr",
+        name: "French",
+        words: &[
+            "le", "les", "des", "et", "est", "une", "pour", "dans", "pas", "du", "avec", "ce",
+            "qui", "sur", "au", "sont",
+        ],
+    },
+    Language {
+        code: "de",
+        name: "German",
+        words: &[
+            "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu", "mit", "den", "von",
+            "sich", "auf", "für", "auch",
+        ],
+    },
+    Language {
+        code: "pt",
+        name: "Portuguese",
+        words: &[
+            "os", "que", "e", "é", "um", "uma", "para", "com", "não", "em", "do", "da", "mais",
+            "como", "mas", "são",
+        ],
+    },
+    Language {
+        code: "it",
+        name: "Italian",
+        words: &[
+            "il", "di", "che", "è", "un", "una", "per", "con", "non", "della", "sono", "gli",
+            "anche", "come", "ma", "nel",
+        ],
+    },
+    Language {
+        code: "nl",
+        name: "Dutch",
+        words: &[
+            "het", "een", "en", "van", "dat", "niet", "op", "te", "met", "voor", "zijn", "ook",
+            "maar", "wordt", "naar", "bij",
+        ],
+    },
+    Language {
+        code: "ru",
+        name: "Russian",
+        words: &[],
+    },
+    Language {
+        code: "el",
+        name: "Greek",
+        words: &[],
+    },
+    Language {
+        code: "ar",
+        name: "Arabic",
+        words: &[],
+    },
+    Language {
+        code: "he",
+        name: "Hebrew",
+        words: &[],
+    },
+    Language {
+        code: "hi",
+        name: "Hindi",
+        words: &[],
+    },
+    Language {
+        code: "th",
+        name: "Thai",
+        words: &[],
+    },
+    Language {
+        code: "zh",
+        name: "Chinese",
+        words: &[],
+    },
+    Language {
+        code: "ja",
+        name: "Japanese",
+        words: &[],
+    },
+    Language {
+        code: "ko",
+        name: "Korean",
+        words: &[],
+    },
+];
+
+/// Language code of a non-Latin script letter
+fn script_language(c: char) -> Option<&'static str> {
+    Some(match c as u32 {
+        0x0370..=0x03FF => "el",
+        0x0400..=0x04FF => "ru",
+        0x0590..=0x05FF => "he",
+        0x0600..=0x06FF => "ar",
+        0x0900..=0x097F => "hi",
+        0x0E00..=0x0E7F => "th",
+        0x3040..=0x30FF => "ja",
+        0x4E00..=0x9FFF => "zh",
+        0x1100..=0x11FF | 0xAC00..=0xD7AF => "ko",
+        _ => return None,
+    })
+}
+
+impl Language {
+    /// Look a language up by code or English name
+    fn find(name: &str) -> Option<Self> {
+        let name = name.trim();
+        LANGUAGES
+            .iter()
+            .find(|l| l.code.eq_ignore_ascii_case(name) || l.name.eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    /// The language `text` is written in, if it can be told
+    fn detect(text: &str) -> Option<Self> {
+        let text = strip_code(text);
+        let mut latin = 0;
+        let mut scripts: Vec<(&str, usize)> = Vec::new();
+        for c in text.chars().filter(|c| c.is_alphabetic()) {
+            match script_language(c) {
+                Some(code) => match scripts.iter_mut().find(|(found, _)| *found == code) {
+                    Some((_, count)) => *count += 1,
+                    None => scripts.push((code, 1)),
+                },
+                None => latin += 1,
+            }
+        }
+
+        let non_latin: usize = scripts.iter().map(|(_, count)| count).sum();
+        if non_latin > latin {
+            // Japanese mixes kanji with kana; any kana means Japanese
+            let code = if scripts.iter().any(|(code, _)| *code == "ja") {
+                "ja"
+            } else {
+                scripts.iter().max_by_key(|(_, count)| *count)?.0
+            };
+            return Self::find(code);
+        }
+
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let (hits, language) = LANGUAGES
+            .iter()
+            .filter(|l| !l.words.is_empty())
+            .map(|l| {
+                let hits = words.iter().filter(|w| l.words.contains(&w.as_str()));
+                (hits.count(), l)
+            })
+            .max_by_key(|(hits, _)| *hits)?;
+        (hits >= MIN_WORD_HITS).then_some(*language)
+    }
+}
+
+/// `text` without fenced code blocks and inline code
+fn strip_code(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            let prose: String = line.split('`').step_by(2).collect::<Vec<_>>().join(" ");
+            out.push_str(&prose);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// HTML tags that mark an answer as HTML rather than Markdown
+const HTML_TAGS: &[&str] = &[
+    "<html", "<body", "<div", "<p>", "<br", "<table", "<ul>", "<ol>", "<li>", "<h1", "<h2", "<h3",
+    "<span", "<strong>", "<em>",
+];
+
+/// A format every answer must be in
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFormat {
+    /// A single JSON value and nothing else
+    Json,
+    /// Markdown, not HTML and not wrapped in a code block
+    Markdown,
+    /// Written in a language
+    Language(Language),
+}
+
+impl ResponseFormat {
+    /// Parse `json`, `markdown`, or `language:<LANG>`
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let spec = spec.trim();
+        match spec.split_once(':') {
+            None if spec.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            None if spec.eq_ignore_ascii_case("markdown") => Ok(Self::Markdown),
+            Some((kind, name)) if kind.trim().eq_ignore_ascii_case("language") => {
+                Language::find(name).map(Self::Language).ok_or_else(|| {
+                    let known: Vec<_> = LANGUAGES.iter().map(|l| l.code).collect();
+                    anyhow::anyhow!(
+                        "Unknown language '{}'. Use one of: {}",
+                        name.trim(),
+                        known.join(", ")
+                    )
+                })
+            }
+            _ => anyhow::bail!(
+                "Unknown response format '{}'. Use json, markdown, or language:<LANG> (e.g. language:fr)",
+                spec
+            ),
+        }
+    }
+
+    /// The format to enforce for `command`: `flag` if given, else its
+    /// entry in the `response_formats` config
+    pub fn for_command(
+        flag: Option<Self>,
+        configured: &BTreeMap<String, String>,
+        command: &str,
+    ) -> anyhow::Result<Option<Self>> {
+        if flag.is_some() {
+            return Ok(flag);
+        }
+        configured
+            .get(command)
+            .map(|spec| Self::parse(spec))
+            .transpose()
+    }
+
+    /// What the answer has to be, as said in a prompt
+    fn requirement(&self) -> String {
+        match self {
+            Self::Json => "as JSON only: a single JSON value, with no other text and no code fence"
+                .to_string(),
+            Self::Markdown => {
+                "as Markdown only: no HTML, and not wrapped in a code block".to_string()
+            }
+            Self::Language(language) => format!("entirely in {}", language.name),
+        }
+    }
+
+    /// What's wrong with `response`, if it isn't in this format
+    pub fn check(&self, response: &str) -> Result<(), String> {
+        let trimmed = response.trim();
+        match self {
+            Self::Json => serde_json::from_str::<serde_json::Value>(trimmed)
+                .map(|_| ())
+                .map_err(|e| format!("is not JSON only ({})", e)),
+            Self::Markdown => {
+                let lower = trimmed.to_lowercase();
+                if let Some(tag) = HTML_TAGS.iter().find(|tag| lower.contains(*tag)) {
+                    return Err(format!("contains HTML ({}>)", tag.trim_end_matches('>')));
+                }
+                let lines: Vec<&str> = trimmed.lines().collect();
+                let fenced = lines.len() > 1
+                    && lines[0].starts_with("```")
+                    && lines[lines.len() - 1].trim() == "```"
+                    && !lines[1..lines.len() - 1]
+                        .iter()
+                        .any(|line| line.trim_start().starts_with("```"));
+                if fenced {
+                    return Err("is wrapped in a code block".to_string());
+                }
+                Ok(())
+            }
+            Self::Language(language) => match Language::detect(trimmed) {
+                Some(found) if found != *language => {
+                    Err(format!("is in {}, not {}", found.name, language.name))
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// `prompt` again, asking for `response` to be redone in this format
+    fn corrective_prompt(&self, prompt: &str, response: &str, problem: &str) -> String {
+        format!(
+            "{}\n\n[FORMAT CORRECTION]\nYour previous answer {}. Answer the request above again, {}.\n\nPrevious answer:\n{}\n[/FORMAT CORRECTION]",
+            prompt,
+            problem,
+            self.requirement(),
+            response.trim()
+        )
+    }
+}
+
+/// Check `response` to `prompt` against `format`, and if it doesn't match,
+/// ask once more through `retry` with a corrective prompt. `who` names the
+/// answering CLI in warnings. If the retry fails, the first answer is kept.
+pub async fn enforce<F, Fut>(
+    format: &ResponseFormat,
+    prompt: &str,
+    response: String,
+    who: &str,
+    retry: F,
+) -> String
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = anyhow::Result<String>>,
+{
+    let Err(problem) = format.check(&response) else {
+        return response;
+    };
+    eprintln!("{}'s answer {}; asking again", who, problem);
+    match retry(format.corrective_prompt(prompt, &response, &problem)).await {
+        Ok(corrected) => {
+            if let Err(problem) = format.check(&corrected) {
+                eprintln!("Warning: {}'s corrected answer still {}", who, problem);
+            }
+            corrected
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: {} could not correct its answer ({}); keeping it as is",
+                who, e
+            );
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_enforce() {
+        let json = ResponseFormat::parse("json").unwrap();
+        assert!(json.check(" {\"ok\": true}\n").is_ok());
+        assert!(json.check("```json\n{}\n```").is_err());
+
+        let markdown = ResponseFormat::parse("markdown").unwrap();
+        assert!(markdown
+            .check("# Plan\n\n- Ship it\n\n```sh\nmake\n```")
+            .is_ok());
+        assert!(markdown.check("<p>Ship it</p>").is_err());
+        assert!(markdown.check("```markdown\n# Plan\n```").is_err());
+
+        let french = ResponseFormat::parse("language:French").unwrap();
+        assert_eq!(french, ResponseFormat::parse("language:fr").unwrap());
+        assert!(french
+            .check("Nous pensons que le schéma est stable et que les tests sont prêts pour la mise en production.")
+            .is_ok());
+        assert_eq!(
+            french.check(
+                "We think that the schema is stable and the tests are ready for this release."
+            ),
+            Err("is in English, not French".to_string())
+        );
+        let japanese = ResponseFormat::parse("language:ja").unwrap();
+        assert!(japanese.check("スキーマは安定しています。").is_ok());
+        assert!(french.check("Ship `it`").is_ok());
+        assert!(ResponseFormat::parse("language:klingon").is_err());
+        assert!(ResponseFormat::parse("yaml").is_err());
+
+        let corrected = enforce(
+            &json,
+            "Rate it",
+            "Sure: {}".to_string(),
+            "claude",
+            |prompt| {
+                assert!(prompt.starts_with("Rate it\n\n[FORMAT CORRECTION]"));
+                assert!(prompt.contains("Previous answer:\nSure: {}"));
+                async { Ok("{\"score\": 3}".to_string()) }
+            },
+        )
+        .await;
+        assert_eq!(corrected, "{\"score\": 3}");
+        let kept = enforce(&json, "Rate it", "[1]".to_string(), "claude", |_| async {
+            anyhow::bail!("not asked")
+        })
+        .await;
+        assert_eq!(kept, "[1]");
+    }
+}
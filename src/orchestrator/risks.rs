@@ -0,0 +1,252 @@
+//! Risk register - Structured risks extracted from a debate
+//!
+//! With `--risk-register`, one more CLI call reads the finished debate
+//! and lists the risks participants raised or implied, each with a
+//! likelihood, impact, mitigation, and suggested owner. Risks are kept in
+//! the transcript, rendered as a table in markdown output, and written as
+//! CSV with `--output csv` for spreadsheets and trackers.
+
+use super::debate::{synthesis_transcript, DebateOrchestrator, DebateResult};
+use crate::invokers::AccessMode;
+use crate::utils::json::extract_json_array;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Column headings, shared by the markdown table and CSV
+const COLUMNS: [&str; 5] = ["Risk", "Likelihood", "Impact", "Mitigation", "Owner"];
+
+/// One entry of a risk register
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct Risk {
+    pub description: String,
+    /// low, medium, or high
+    pub likelihood: String,
+    /// low, medium, or high
+    pub impact: String,
+    #[serde(default)]
+    pub mitigation: String,
+    /// Suggested owner, usually a participant's persona
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+impl Risk {
+    /// Likelihood times impact, 1 (low/low) to 9 (high/high); 0 when
+    /// either level is unrecognized
+    pub fn score(&self) -> u8 {
+        level_score(&self.likelihood) * level_score(&self.impact)
+    }
+}
+
+/// 1 for low, 2 for medium, 3 for high, 0 otherwise
+pub(crate) fn level_score(level: &str) -> u8 {
+    match level {
+        "low" => 1,
+        "medium" => 2,
+        "high" => 3,
+        _ => 0,
+    }
+}
+
+/// "Medium", "med", " HIGH " -> "medium", "medium", "high"
+pub(crate) fn normalize_level(level: &str) -> String {
+    let level = level.trim().to_lowercase();
+    match level.as_str() {
+        "med" | "moderate" => "medium".to_string(),
+        "very high" | "critical" => "high".to_string(),
+        "very low" | "negligible" => "low".to_string(),
+        _ => level,
+    }
+}
+
+/// Prompt asking for the risks raised in a debate
+pub fn risk_prompt(result: &DebateResult) -> String {
+    let outcome = match &result.synthesis {
+        Some(synthesis) => format!(
+            "\nSYNTHESIS:\n{}\n",
+            serde_json::to_string_pretty(synthesis).unwrap_or_default()
+        ),
+        None => String::new(),
+    };
+    format!(
+        r#"[RISK REGISTER REQUEST]
+You are writing a risk register for the decision discussed in a debate.
+
+TOPIC: {}
+
+DEBATE TRANSCRIPT:
+{}{}
+List the risks participants raised or that follow from the options they
+discussed. For each risk give its likelihood and impact (low, medium, or
+high), a concrete mitigation, and a suggested owner (a role, preferably one
+of the participants' personas). Order them from most to least severe.
+
+Respond with a JSON array in this exact format:
+[
+  {{
+    "description": "...",
+    "likelihood": "low" | "medium" | "high",
+    "impact": "low" | "medium" | "high",
+    "mitigation": "...",
+    "owner": "..." or null
+  }}
+]
+[/RISK REGISTER REQUEST]"#,
+        result.topic,
+        synthesis_transcript(&result.rounds, 1),
+        outcome
+    )
+}
+
+/// Parse the risks in a response, most severe first
+pub fn parse_risks(response: &str) -> anyhow::Result<Vec<Risk>> {
+    let json = extract_json_array(response)?;
+    let mut risks: Vec<Risk> = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("Invalid risk register JSON: {}", e))?;
+    for risk in &mut risks {
+        risk.likelihood = normalize_level(&risk.likelihood);
+        risk.impact = normalize_level(&risk.impact);
+        risk.owner = risk
+            .owner
+            .take()
+            .map(|owner| owner.trim().to_string())
+            .filter(|owner| !owner.is_empty());
+    }
+    risks.retain(|risk| !risk.description.trim().is_empty());
+    // Stable, so equally severe risks keep the synthesizer's order
+    risks.sort_by_key(|risk| std::cmp::Reverse(risk.score()));
+    Ok(risks)
+}
+
+impl DebateOrchestrator {
+    /// Ask `risk_cli` for a debate's risk register
+    pub async fn extract_risks(
+        &self,
+        result: &DebateResult,
+        risk_cli: &str,
+        timeout: u64,
+        access_mode: AccessMode,
+    ) -> anyhow::Result<Vec<Risk>> {
+        let invoker = self.invokers().get(risk_cli).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Risk CLI '{}' not found. Use claude, codex, gemini, or an installed plugin.",
+                risk_cli
+            )
+        })?;
+        if !invoker.is_available().await {
+            anyhow::bail!("Risk CLI '{}' is not available in PATH.", risk_cli);
+        }
+
+        eprintln!("Extracting risks with {}...", risk_cli);
+        let response = invoker
+            .invoke(&risk_prompt(result), timeout, access_mode, None)
+            .await?;
+        parse_risks(&response)
+    }
+}
+
+/// `text` made safe for a markdown table cell
+pub(crate) fn table_cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// The risks as a markdown table
+pub fn markdown_table(risks: &[Risk]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "| # | {} |", COLUMNS.join(" | "));
+    let _ = writeln!(out, "|---|{}", "---|".repeat(COLUMNS.len()));
+    for (i, risk) in risks.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} |",
+            i + 1,
+            table_cell(&risk.description),
+            risk.likelihood,
+            risk.impact,
+            table_cell(&risk.mitigation),
+            table_cell(risk.owner.as_deref().unwrap_or("")),
+        );
+    }
+    out
+}
+
+/// `field` quoted for CSV when it needs to be
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The risks as CSV, with a header row
+pub fn to_csv(risks: &[Risk]) -> String {
+    let mut out = COLUMNS.join(",");
+    out.push('\n');
+    for risk in risks {
+        let fields = [
+            risk.description.as_str(),
+            &risk.likelihood,
+            &risk.impact,
+            &risk.mitigation,
+            risk.owner.as_deref().unwrap_or(""),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_risks_normalizes_and_sorts() {
+        let response = r#"Here is the register:
+```json
+[
+  {"description": "Team lacks Go experience", "likelihood": "Medium", "impact": "low",
+   "mitigation": "Pair with the platform team", "owner": " "},
+  {"description": "Migration stalls halfway", "likelihood": "HIGH", "impact": "high",
+   "mitigation": "Migrate one service, then review", "owner": "CTO"},
+  {"description": "", "likelihood": "low", "impact": "low"}
+]
+```"#;
+        let risks = parse_risks(response).unwrap();
+        assert_eq!(risks.len(), 2);
+        assert_eq!(risks[0].description, "Migration stalls halfway");
+        assert_eq!(risks[0].score(), 9);
+        assert_eq!(risks[1].likelihood, "medium");
+        assert_eq!(risks[1].owner, None);
+        assert!(parse_risks("No risks worth noting.").is_err());
+    }
+
+    #[test]
+    fn test_render_risks() {
+        let risks = vec![Risk {
+            description: "Vendor lock-in | pricing".to_string(),
+            likelihood: "medium".to_string(),
+            impact: "high".to_string(),
+            mitigation: "Keep an exit plan, \"just in case\"".to_string(),
+            owner: Some("CTO".to_string()),
+        }];
+        let table = markdown_table(&risks);
+        assert!(
+            table.starts_with("| # | Risk | Likelihood | Impact | Mitigation | Owner |\n|---|---|")
+        );
+        assert!(table.contains("| 1 | Vendor lock-in \\| pricing | medium | high |"));
+
+        let csv = to_csv(&risks);
+        assert_eq!(
+            csv,
+            "Risk,Likelihood,Impact,Mitigation,Owner\n\
+             Vendor lock-in | pricing,medium,high,\"Keep an exit plan, \"\"just in case\"\"\",CTO\n"
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! Round-robin pairing - Scale debates to many participants
+//!
+//! Instead of every participant reading every other response each round,
+//! participants are paired into parallel 1v1 exchanges with rotating
+//! partners. Everyone also gets a short digest of the previous round's
+//! other exchanges, so prompts stay small as the participant count grows.
+
+use super::debate::{
+    benched_note, join_round, report_cancelled, DebateOrchestrator, DebateResult, Participant,
+    PromptContext, RoundOptions, RoundResponse, DEBATE_RESULT_SCHEMA_VERSION,
+};
+use super::health::ParticipantHealth;
+use super::overflow::ContextWindow;
+use super::stance::participant_labels;
+use crate::invokers::AccessMode;
+use crate::utils::table::truncate_to_width;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
+
+/// Maximum display width of each response excerpt in a digest
+const DIGEST_WIDTH: usize = 300;
+
+/// Pairings for a round (0-based) using the circle method.
+///
+/// Each entry is `(first, second)` where `first` speaks first and `second`
+/// replies to it; `second` is `None` for the participant sitting out when
+/// the count is odd. Over `count - 1` rounds (or `count` when odd) every
+/// participant meets every other participant exactly once.
+pub fn round_robin_pairs(count: usize, round: usize) -> Vec<(usize, Option<usize>)> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let slots = count + count % 2;
+    let rotating = slots - 1;
+
+    let mut order = vec![0];
+    order.extend((0..rotating).map(|i| 1 + (i + rotating - round % rotating) % rotating));
+
+    (0..slots / 2)
+        .filter_map(|i| {
+            let (mut a, mut b) = (order[i], order[slots - 1 - i]);
+            // Alternate who speaks first so nobody always opens
+            if round % 2 == 1 {
+                std::mem::swap(&mut a, &mut b);
+            }
+            match (a < count, b < count) {
+                (true, true) => Some((a, Some(b))),
+                (true, false) => Some((a, None)),
+                (false, true) => Some((b, None)),
+                (false, false) => None,
+            }
+        })
+        .collect()
+}
+
+/// Stable names for participants, suffixing repeats with `#2`, `#3`, ...
+pub fn participant_names(participants: &[Participant]) -> Vec<String> {
+    let mut names = Vec::with_capacity(participants.len());
+    for (i, participant) in participants.iter().enumerate() {
+        let name = participant.display_name();
+        let seen = participants[..i]
+            .iter()
+            .filter(|p| p.display_name() == name)
+            .count();
+        if seen == 0 {
+            names.push(name);
+        } else {
+            names.push(format!("{} #{}", name, seen + 1));
+        }
+    }
+    names
+}
+
+/// One line per response with a short single-line excerpt
+pub fn round_digest(responses: &[RoundResponse]) -> String {
+    let mut digest = String::new();
+    for (label, response) in participant_labels(responses).iter().zip(responses) {
+        let excerpt = response
+            .response
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        digest.push_str(&format!(
+            "- {}: {}\n",
+            label,
+            truncate_to_width(&excerpt, DIGEST_WIDTH)
+        ));
+    }
+    digest
+}
+
+/// Digests of the last `history_rounds` rounds, shared with every pair
+pub fn digest_context(previous: &[Vec<RoundResponse>], history_rounds: usize) -> String {
+    let mut context = String::new();
+    let start = previous.len().saturating_sub(history_rounds);
+    for (i, prev_round) in previous.iter().enumerate().skip(start) {
+        if history_rounds == 1 {
+            context.push_str("Digest of previous exchanges:\n");
+        } else {
+            context.push_str(&format!(
+                "Digest of previous exchanges (round {}):\n",
+                i + 1
+            ));
+        }
+        context.push_str(&round_digest(prev_round));
+        context.push('\n');
+    }
+    context
+}
+
+/// Shared prompt for one side of a 1v1 exchange, before persona scaffolding.
+///
+/// `partner` is the other participant's name (`None` when sitting out) and
+/// `partner_response` is set for the participant replying second. `digest`
+/// comes from [`digest_context`].
+pub fn pair_context(
+    topic: &str,
+    round: usize,
+    partner: Option<&str>,
+    partner_response: Option<&str>,
+    digest: &str,
+) -> String {
+    let mut context = match partner {
+        Some(partner) => format!(
+            "Topic: {}\n\nRound {} (1v1 with {})\n\n",
+            topic, round, partner
+        ),
+        None => format!("Topic: {}\n\nRound {}\n\n", topic, round),
+    };
+    context.push_str(digest);
+
+    match (partner, partner_response) {
+        (Some(partner), Some(response)) => {
+            context.push_str(&format!("{}'s response:\n{}\n\n", partner, response));
+            context.push_str(&format!(
+                "Respond to {}'s points, then give your own perspective on this topic.",
+                partner
+            ));
+        }
+        _ => context.push_str("Please provide your perspective on this topic."),
+    }
+    context
+}
+
+impl DebateOrchestrator {
+    /// Run a debate as rotating 1v1 exchanges.
+    ///
+    /// Pairs run in parallel; within a pair the second participant replies
+    /// to the first. Each round's responses are kept in participant order.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn run_round_robin_debate(
+        &self,
+        topic: &str,
+        participants: Vec<Participant>,
+        num_rounds: usize,
+        timeout: u64,
+        access_mode: AccessMode,
+        context: PromptContext,
+        options: RoundOptions,
+    ) -> anyhow::Result<DebateResult> {
+        if participants.len() < 2 {
+            return Err(anyhow::anyhow!(
+                "Round-robin pairing needs at least two participants"
+            ));
+        }
+        self.check_access_modes(&participants, access_mode)?;
+
+        let names = participant_names(&participants);
+        let mut rounds: Vec<Vec<RoundResponse>> = Vec::new();
+        let mut health = ParticipantHealth::new(participants.len(), options.bench_after);
+        let plugins = self.invokers().plugins().ok();
+        let windows: Arc<Vec<Option<ContextWindow>>> = Arc::new(
+            participants
+                .iter()
+                .map(|participant| ContextWindow::of(participant, plugins))
+                .collect(),
+        );
+
+        for round in 1..=num_rounds {
+            if options.reprobe && round == num_rounds {
+                self.reprobe_benched(
+                    &mut health,
+                    &participants,
+                    &names,
+                    round,
+                    timeout,
+                    access_mode,
+                )
+                .await;
+            }
+            // A benched participant's partner answers alone
+            let pairs: Vec<(usize, Option<usize>)> =
+                round_robin_pairs(participants.len(), round - 1)
+                    .into_iter()
+                    .filter_map(|(a, b)| {
+                        let b = b.filter(|b| !health.is_benched(*b));
+                        match (health.is_benched(a), b) {
+                            (false, b) => Some((a, b)),
+                            (true, Some(b)) => Some((b, None)),
+                            (true, None) => None,
+                        }
+                    })
+                    .collect();
+            let pairing: Vec<String> = pairs
+                .iter()
+                .map(|(a, b)| match b {
+                    Some(b) => format!("{} vs {}", names[*a], names[*b]),
+                    None => format!("{} sits out", names[*a]),
+                })
+                .collect();
+            options.progress(format!(
+                "Running round {} of {} ({}){}...",
+                round,
+                num_rounds,
+                pairing.join(", "),
+                benched_note(&health, &names)
+            ));
+
+            let digest = digest_context(&rounds, context.history_rounds);
+            let previous = Arc::new(rounds.clone());
+            let mut tasks = JoinSet::new();
+            let started = Instant::now();
+            for (pair, &(a, b)) in pairs.iter().enumerate() {
+                let first = participants[a].clone();
+                let second = b.map(|b| (b, participants[b].clone()));
+                let first_name = names[a].clone();
+                let partner = b.map(|b| names[b].as_str());
+                // Participants with a small context window get their own view
+                let first_prompt = match &windows[a] {
+                    Some(window) => window.fit(&first_name, context.history_rounds, |view| {
+                        let digest =
+                            digest_context(&view.shorten_rounds(&previous), view.history_rounds);
+                        context.participant_prompt(
+                            &first,
+                            &context.phase_context(
+                                round,
+                                pair_context(topic, round, partner, None, &digest),
+                            ),
+                        )
+                    }),
+                    None => context.participant_prompt(
+                        &first,
+                        &context.phase_context(
+                            round,
+                            pair_context(topic, round, partner, None, &digest),
+                        ),
+                    ),
+                };
+                let second_name = b.map(|b| names[b].clone());
+                let topic = topic.to_string();
+                let digest = digest.clone();
+                let previous = Arc::clone(&previous);
+                let windows = Arc::clone(&windows);
+                let orchestrator = self.clone();
+                let options = options.clone();
+
+                tasks.spawn(async move {
+                    let mut responses = Vec::new();
+                    let opening = orchestrator
+                        .invoke_hedged(
+                            first,
+                            first_prompt,
+                            timeout,
+                            access_mode,
+                            options.hedge_after,
+                        )
+                        .await;
+                    if let Some(ref opening) = opening {
+                        options.emit(round, a, opening, started.elapsed());
+                    }
+
+                    if let Some((b, second)) = second {
+                        let opening_text = opening.as_ref().map(|r| r.response.as_str());
+                        let prompt = match &windows[b] {
+                            Some(window) => window.fit(
+                                second_name.as_deref().unwrap_or_default(),
+                                context.history_rounds,
+                                |view| {
+                                    let digest = digest_context(
+                                        &view.shorten_rounds(&previous),
+                                        view.history_rounds,
+                                    );
+                                    let opening_text = opening_text.map(|text| view.shorten(text));
+                                    let base = context.phase_context(
+                                        round,
+                                        pair_context(
+                                            &topic,
+                                            round,
+                                            Some(&first_name),
+                                            opening_text.as_deref(),
+                                            &digest,
+                                        ),
+                                    );
+                                    context.participant_prompt(&second, &base)
+                                },
+                            ),
+                            None => {
+                                let base = context.phase_context(
+                                    round,
+                                    pair_context(
+                                        &topic,
+                                        round,
+                                        Some(&first_name),
+                                        opening_text,
+                                        &digest,
+                                    ),
+                                );
+                                context.participant_prompt(&second, &base)
+                            }
+                        };
+                        if let Some(reply) = orchestrator
+                            .invoke_hedged(
+                                second,
+                                prompt,
+                                timeout,
+                                access_mode,
+                                options.hedge_after,
+                            )
+                            .await
+                        {
+                            options.emit(round, b, &reply, started.elapsed());
+                            responses.push((b, reply));
+                        }
+                    }
+                    if let Some(opening) = opening {
+                        responses.push((a, opening));
+                    }
+                    (pair, responses)
+                });
+            }
+
+            // An exchange still running at the deadline is cancelled whole
+            let (finished, cancelled) = join_round(tasks, pairs.len(), options.round_timeout).await;
+            let cancelled: Vec<usize> = cancelled
+                .into_iter()
+                .flat_map(|pair| {
+                    let (a, b) = pairs[pair];
+                    std::iter::once(a).chain(b)
+                })
+                .collect();
+            report_cancelled(round, &participants, &cancelled, options.round_timeout);
+            let mut indexed: Vec<(usize, RoundResponse)> = finished
+                .into_iter()
+                .flat_map(|(_, responses)| responses)
+                .collect();
+            indexed.sort_by_key(|(i, _)| *i);
+            let mut responded = vec![false; participants.len()];
+            for (i, _) in &indexed {
+                responded[*i] = true;
+            }
+            health.end_round(round, &responded, &names);
+            let round_responses: Vec<RoundResponse> = indexed.into_iter().map(|(_, r)| r).collect();
+
+            if round_responses.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "No participants were able to respond in round {}. Please ensure their CLIs are installed and available.",
+                    round
+                ));
+            }
+
+            self.round_closed(&round_responses);
+            rounds.push(round_responses);
+        }
+
+        Ok(DebateResult {
+            schema_version: DEBATE_RESULT_SCHEMA_VERSION,
+            gptengage_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            topic: topic.to_string(),
+            rounds,
+            synthesis: None,
+            stances: Vec::new(),
+            benched: health.benchings,
+            workspace_changes: None,
+            risks: vec![],
+            action_items: vec![],
+            critiques: vec![],
+            fact_check: None,
+            frontmatter: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_round_robin_pairs_meet_everyone_once() {
+        for count in [2, 4, 5, 6] {
+            let rounds = if count % 2 == 0 { count - 1 } else { count };
+            let mut met = HashSet::new();
+            for round in 0..rounds {
+                let pairs = round_robin_pairs(count, round);
+                let mut seen = HashSet::new();
+                for (a, b) in pairs {
+                    assert!(seen.insert(a));
+                    if let Some(b) = b {
+                        assert!(seen.insert(b));
+                        assert!(met.insert((a.min(b), a.max(b))), "pair met twice");
+                    }
+                }
+                assert_eq!(seen.len(), count, "everyone appears each round");
+            }
+            assert_eq!(met.len(), count * (count - 1) / 2);
+        }
+    }
+
+    #[test]
+    fn test_pair_context_includes_partner_and_digest() {
+        let previous = vec![vec![RoundResponse {
+            cli: "codex".to_string(),
+            persona: None,
+            response: "Use   a queue.\nIt decouples writers.".to_string(),
+            log: None,
+        }]];
+
+        let digest = digest_context(&previous, 1);
+        let reply = pair_context("T", 2, Some("claude (CTO)"), Some("Ship it."), &digest);
+        assert!(reply.contains("Round 2 (1v1 with claude (CTO))"));
+        assert!(reply.contains(
+            "Digest of previous exchanges:\n- codex: Use a queue. It decouples writers.\n"
+        ));
+        assert!(reply.contains("claude (CTO)'s response:\nShip it."));
+
+        let bye = pair_context("T", 2, None, None, &digest_context(&previous, 0));
+        assert!(!bye.contains("Digest"));
+        assert!(bye.ends_with("Please provide your perspective on this topic."));
+    }
+}
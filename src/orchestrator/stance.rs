@@ -0,0 +1,278 @@
+//! Stance tracking - Classify each participant's position per debate round
+
+use super::debate::{DebateResult, RoundResponse};
+use crate::invokers::{AccessMode, InvokerRegistry};
+use crate::utils::json::extract_json_object;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A participant's classified position in a single round
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct Stance {
+    /// 1-based round number
+    pub round: usize,
+    /// Participant label (display name, suffixed with #N for duplicates)
+    pub participant: String,
+    /// Short position label produced by the classifier
+    pub position: String,
+}
+
+/// A participant changing their position between two rounds
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct PositionChange {
+    pub participant: String,
+    /// Round in which the new position was first taken
+    pub round: usize,
+    pub from: String,
+    pub to: String,
+}
+
+/// Raw classifier response
+#[derive(Debug, Deserialize)]
+struct RawStanceResponse {
+    positions: Vec<RawPosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPosition {
+    participant: String,
+    position: String,
+}
+
+/// Build stable labels for a round's responses.
+///
+/// Multi-instance debates produce identical display names, so repeated
+/// names are suffixed with `#2`, `#3`, ... in order of appearance.
+pub fn participant_labels(responses: &[RoundResponse]) -> Vec<String> {
+    let mut labels = Vec::with_capacity(responses.len());
+    for (i, response) in responses.iter().enumerate() {
+        let name = response.display_name();
+        let seen = responses[..i]
+            .iter()
+            .filter(|r| r.display_name() == name)
+            .count();
+        if seen == 0 {
+            labels.push(name);
+        } else {
+            labels.push(format!("{} #{}", name, seen + 1));
+        }
+    }
+    labels
+}
+
+/// Build the classifier prompt for a single round
+pub fn build_stance_prompt(
+    topic: &str,
+    round: usize,
+    labels: &[String],
+    responses: &[RoundResponse],
+    previous: &[Stance],
+) -> String {
+    let mut transcript = String::new();
+    for (label, response) in labels.iter().zip(responses) {
+        transcript.push_str(&format!("[{}]\n{}\n\n", label, response.response));
+    }
+
+    let mut prior = String::new();
+    if !previous.is_empty() {
+        prior.push_str("PREVIOUS POSITIONS (reuse the same label if a position is unchanged):\n");
+        for stance in previous {
+            prior.push_str(&format!("- {}: {}\n", stance.participant, stance.position));
+        }
+        prior.push('\n');
+    }
+
+    format!(
+        r#"[STANCE CLASSIFICATION]
+Classify the position each participant takes on the topic in round {round}.
+
+TOPIC: {topic}
+
+{prior}RESPONSES:
+{transcript}Use a short label of at most 6 words for each position (e.g. "for", "against",
+"for with caveats", "undecided").
+
+Respond with JSON only, using the participant names exactly as given:
+{{
+  "positions": [
+    {{"participant": "...", "position": "..."}}
+  ]
+}}
+[/STANCE CLASSIFICATION]"#,
+        round = round,
+        topic = topic,
+        prior = prior,
+        transcript = transcript,
+    )
+}
+
+/// Parse the classifier response into stances for the given round
+pub fn parse_stance_response(
+    response: &str,
+    round: usize,
+    labels: &[String],
+) -> anyhow::Result<Vec<Stance>> {
+    let json_str = extract_json_object(response)?;
+    let raw: RawStanceResponse = serde_json::from_str(json_str)
+        .map_err(|e| anyhow::anyhow!("Failed to parse stance response: {}", e))?;
+
+    Ok(raw
+        .positions
+        .into_iter()
+        .filter(|p| labels.contains(&p.participant))
+        .map(|p| Stance {
+            round,
+            participant: p.participant,
+            position: p.position.trim().to_string(),
+        })
+        .collect())
+}
+
+/// Compute position changes from a round-ordered list of stances
+pub fn compute_position_changes(stances: &[Stance]) -> Vec<PositionChange> {
+    let mut changes = Vec::new();
+    let mut last: Vec<(&str, &str)> = Vec::new();
+
+    for stance in stances {
+        match last.iter_mut().find(|(p, _)| *p == stance.participant) {
+            Some((_, position)) => {
+                if !position.eq_ignore_ascii_case(&stance.position) {
+                    changes.push(PositionChange {
+                        participant: stance.participant.clone(),
+                        round: stance.round,
+                        from: position.to_string(),
+                        to: stance.position.clone(),
+                    });
+                    *position = &stance.position;
+                }
+            }
+            None => last.push((&stance.participant, &stance.position)),
+        }
+    }
+
+    changes
+}
+
+/// Classify every participant's position in every round of a debate.
+///
+/// Issues one classifier call per round. Rounds whose classification fails
+/// are skipped with a warning rather than failing the debate.
+pub async fn track_stances(
+    invokers: &InvokerRegistry,
+    result: &DebateResult,
+    classifier_cli: &str,
+    timeout: u64,
+    access_mode: AccessMode,
+) -> anyhow::Result<Vec<Stance>> {
+    let invoker = invokers.get(classifier_cli).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Stance classifier CLI '{}' not found. Use claude, codex, gemini, or an installed plugin.",
+            classifier_cli
+        )
+    })?;
+
+    if !invoker.is_available().await {
+        return Err(anyhow::anyhow!(
+            "Stance classifier CLI '{}' is not available in PATH.",
+            classifier_cli
+        ));
+    }
+
+    let mut stances: Vec<Stance> = Vec::new();
+
+    for (idx, responses) in result.rounds.iter().enumerate() {
+        let round = idx + 1;
+        let labels = participant_labels(responses);
+        let previous: Vec<Stance> = stances
+            .iter()
+            .filter(|s| s.round == round - 1)
+            .cloned()
+            .collect();
+
+        eprintln!("Classifying positions for round {}...", round);
+        let prompt = build_stance_prompt(&result.topic, round, &labels, responses, &previous);
+        match invoker.invoke(&prompt, timeout, access_mode, None).await {
+            Ok(response) => match parse_stance_response(&response, round, &labels) {
+                Ok(round_stances) => stances.extend(round_stances),
+                Err(e) => eprintln!("Warning: stance classification failed: {}", e),
+            },
+            Err(e) => eprintln!("Warning: stance classification failed: {}", e),
+        }
+    }
+
+    Ok(stances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(cli: &str, persona: Option<&str>) -> RoundResponse {
+        RoundResponse {
+            cli: cli.to_string(),
+            persona: persona.map(|p| p.to_string()),
+            response: "text".to_string(),
+            log: None,
+        }
+    }
+
+    fn stance(round: usize, participant: &str, position: &str) -> Stance {
+        Stance {
+            round,
+            participant: participant.to_string(),
+            position: position.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_participant_labels_deduplicates() {
+        let responses = vec![
+            response("claude", None),
+            response("claude", None),
+            response("codex", Some("CTO")),
+        ];
+        let labels = participant_labels(&responses);
+        assert_eq!(labels, vec!["claude", "claude #2", "codex (CTO)"]);
+    }
+
+    #[test]
+    fn test_parse_stance_response_filters_unknown() {
+        let labels = vec!["claude".to_string()];
+        let text = r#"Sure: {"positions": [{"participant": "claude", "position": " for "}, {"participant": "ghost", "position": "against"}]}"#;
+        let stances = parse_stance_response(text, 2, &labels).unwrap();
+        assert_eq!(stances, vec![stance(2, "claude", "for")]);
+    }
+
+    #[test]
+    fn test_compute_position_changes() {
+        let stances = vec![
+            stance(1, "claude", "for"),
+            stance(1, "codex", "against"),
+            stance(2, "claude", "For"),
+            stance(2, "codex", "for with caveats"),
+            stance(3, "codex", "for"),
+        ];
+        let changes = compute_position_changes(&stances);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].participant, "codex");
+        assert_eq!(changes[0].round, 2);
+        assert_eq!(changes[0].from, "against");
+        assert_eq!(changes[1].to, "for");
+    }
+
+    #[test]
+    fn test_build_stance_prompt_includes_previous() {
+        let labels = vec!["claude".to_string()];
+        let responses = vec![response("claude", None)];
+        let prompt = build_stance_prompt(
+            "Tabs vs spaces",
+            2,
+            &labels,
+            &responses,
+            &[stance(1, "claude", "tabs")],
+        );
+        assert!(prompt.contains("PREVIOUS POSITIONS"));
+        assert!(prompt.contains("- claude: tabs"));
+        assert!(prompt.contains("[claude]"));
+    }
+}
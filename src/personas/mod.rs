@@ -0,0 +1,279 @@
+//! Persona library - Reusable persona definitions
+//!
+//! Personas live in `~/.gptengage/personas/<name>.toml` and are referenced
+//! by name with an `@` prefix from templates, debate participants
+//! (`-p "claude:@security-reviewer"`), and `generate-agents` roles, so the
+//! same persona prose isn't duplicated across templates.
+
+use crate::orchestrator::AgentDefinition;
+use crate::utils::watch::{definition_files, namespace_of};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A reusable persona
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Persona {
+    /// Reference name (the file stem), used as `@name`
+    #[serde(skip)]
+    pub name: String,
+    /// Role label shown in transcripts (defaults to the reference name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// How this persona should approach a debate
+    pub instructions: String,
+    /// Areas of expertise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expertise: Vec<String>,
+    /// Communication style
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+}
+
+impl Persona {
+    /// Role label shown in transcripts
+    pub fn role(&self) -> &str {
+        self.role.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Build an agent definition for this persona on a given CLI
+    pub fn to_agent_definition(&self, cli: &str, model: Option<String>) -> AgentDefinition {
+        AgentDefinition {
+            cli: cli.to_string(),
+            model,
+            persona: self.role().to_string(),
+            instructions: self.instructions.clone(),
+            expertise: self.expertise.clone(),
+            communication_style: self.style.clone(),
+            output_filters: Vec::new(),
+        }
+    }
+
+    /// Build a persona from an agent definition, named after its role
+    pub fn from_agent_definition(agent: &AgentDefinition) -> Self {
+        Self {
+            name: slugify(&agent.persona),
+            role: Some(agent.persona.clone()),
+            instructions: agent.instructions.clone(),
+            expertise: agent.expertise.clone(),
+            style: agent.communication_style.clone(),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_name(&self.name)?;
+        if self.instructions.trim().len() < 10 {
+            anyhow::bail!("instructions must be at least 10 characters");
+        }
+        Ok(())
+    }
+}
+
+/// Check a persona reference name (letters, digits, '-' and '_')
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid persona name '{}'. Use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Reference name for a role label, e.g. "Principal Architect" ->
+/// "principal-architect"
+pub fn slugify(role: &str) -> String {
+    let mut slug = String::new();
+    for c in role.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Parse a persona TOML document, giving it the reference name `name`
+pub fn parse_persona(content: &str, name: &str) -> Result<Persona> {
+    let mut persona: Persona = toml::from_str(content)?;
+    persona.name = name.to_string();
+    persona.validate()?;
+    Ok(persona)
+}
+
+/// The persona name in an `@name` reference, if `text` is one
+pub fn persona_reference(text: &str) -> Option<&str> {
+    text.trim()
+        .strip_prefix('@')
+        .filter(|name| !name.is_empty())
+}
+
+/// Loads personas from the personas directory
+pub struct PersonaRegistry {
+    personas: HashMap<String, Persona>,
+}
+
+impl PersonaRegistry {
+    /// Load all personas. Invalid files are skipped with a warning.
+    pub fn new() -> Result<Self> {
+        let mut registry = Self {
+            personas: HashMap::new(),
+        };
+        let dir = Self::get_personas_dir()?;
+        if !dir.exists() {
+            return Ok(registry);
+        }
+
+        // Personas in a namespace subdirectory are referenced as `@namespace/name`
+        for path in definition_files(&dir) {
+            match Self::load_persona_file(&path) {
+                Ok(mut persona) => {
+                    if let Some(namespace) = namespace_of(&dir, &path) {
+                        persona.name = format!("{}/{}", namespace, persona.name);
+                    }
+                    registry.personas.insert(persona.name.clone(), persona);
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to load persona {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Get the personas directory path
+    pub fn get_personas_dir() -> Result<PathBuf> {
+        let home = std::env::var("HOME").context("HOME environment variable not set")?;
+        Ok(PathBuf::from(home).join(".gptengage").join("personas"))
+    }
+
+    /// Load a single persona from a TOML file named after it
+    fn load_persona_file(path: &Path) -> Result<Persona> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read persona file: {}", path.display()))?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        parse_persona(&content, &name)
+            .with_context(|| format!("Failed to parse persona file: {}", path.display()))
+    }
+
+    /// Write a persona to the personas directory, returning its path.
+    /// An existing persona with the same name is only replaced with `force`.
+    pub fn save(persona: &Persona, force: bool) -> Result<PathBuf> {
+        persona.validate()?;
+        let dir = Self::get_personas_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.toml", persona.name));
+        if path.exists() && !force {
+            anyhow::bail!(
+                "Persona '@{}' already exists. Use --force to replace it.",
+                persona.name
+            );
+        }
+        std::fs::write(&path, toml::to_string_pretty(persona)?)?;
+        Ok(path)
+    }
+
+    /// Get a persona by name
+    pub fn get(&self, name: &str) -> Option<&Persona> {
+        self.personas.get(name)
+    }
+
+    /// Resolve an `@name` reference, with a helpful error if it is unknown
+    pub fn resolve(&self, name: &str) -> Result<&Persona> {
+        self.get(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Persona '@{}' not found. Add it as {}/{}.toml",
+                name,
+                Self::get_personas_dir()
+                    .map(|d| d.display().to_string())
+                    .unwrap_or_else(|_| "~/.gptengage/personas".to_string()),
+                name
+            )
+        })
+    }
+
+    /// All personas, sorted by name
+    pub fn list(&self) -> Vec<&Persona> {
+        let mut personas: Vec<&Persona> = self.personas.values().collect();
+        personas.sort_by(|a, b| a.name.cmp(&b.name));
+        personas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persona_reference() {
+        assert_eq!(
+            persona_reference("@security-reviewer"),
+            Some("security-reviewer")
+        );
+        assert_eq!(persona_reference(" @sre "), Some("sre"));
+        assert_eq!(persona_reference("CTO"), None);
+        assert_eq!(persona_reference("@"), None);
+    }
+
+    #[test]
+    fn test_load_persona_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("security-reviewer.toml");
+        std::fs::write(
+            &path,
+            r#"
+role = "Security Reviewer"
+instructions = "Look for injection, authz gaps, and secrets handling."
+expertise = ["appsec", "threat modeling"]
+style = "Direct and specific"
+"#,
+        )
+        .unwrap();
+
+        let persona = PersonaRegistry::load_persona_file(&path).unwrap();
+        assert_eq!(persona.name, "security-reviewer");
+        assert_eq!(persona.role(), "Security Reviewer");
+
+        let agent = persona.to_agent_definition("claude", None);
+        assert_eq!(agent.persona, "Security Reviewer");
+        assert_eq!(agent.expertise.len(), 2);
+        assert_eq!(
+            agent.communication_style.as_deref(),
+            Some("Direct and specific")
+        );
+
+        std::fs::write(&path, "instructions = \"short\"").unwrap();
+        assert!(PersonaRegistry::load_persona_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_persona_from_agent_definition_round_trips() {
+        let agent = AgentDefinition {
+            cli: "codex".to_string(),
+            model: None,
+            persona: "Principal Architect".to_string(),
+            instructions: "Weigh long-term maintainability over speed.".to_string(),
+            expertise: vec!["distributed systems".to_string()],
+            communication_style: None,
+            output_filters: vec![],
+        };
+        let persona = Persona::from_agent_definition(&agent);
+        assert_eq!(persona.name, "principal-architect");
+
+        let toml = toml::to_string_pretty(&persona).unwrap();
+        let parsed = parse_persona(&toml, &persona.name).unwrap();
+        assert_eq!(parsed.role(), "Principal Architect");
+        assert_eq!(parsed.instructions, agent.instructions);
+        assert!(parse_persona(&toml, "bad name").is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Context providers - Plugins whose output becomes prompt context
+//!
+//! A plugin with `kind = "context-provider"` is declared like any other
+//! plugin, but instead of answering the prompt it is run once, before the
+//! CLIs are, with the request as its prompt (passed the way `prompt_mode`
+//! says). Whatever it prints (`kubectl describe`, `terraform plan`, a
+//! schema dump) is added to the prompt in a labelled block. Providers run
+//! read-only, and their commands must be allowlisted like invokers'.
+
+use super::{PluginKind, PluginManager};
+use crate::invokers::plugin::PluginInvoker;
+use crate::invokers::{AccessMode, Invoker};
+use crate::utils::stdin::format_context_block;
+
+/// `output` of the provider `name`, labelled for the prompt
+pub fn context_block(name: &str, output: &str) -> String {
+    format_context_block(
+        &format!("CONTEXT FROM {}", name.to_uppercase()),
+        output.trim(),
+    )
+}
+
+/// Run each named provider for `request` and return their labelled
+/// output, in order. A provider that prints nothing is skipped.
+pub async fn gather_context(
+    plugins: &PluginManager,
+    names: &[String],
+    request: &str,
+    timeout: u64,
+) -> anyhow::Result<String> {
+    let mut blocks = Vec::new();
+    for name in names {
+        let config = plugins
+            .get_plugin_of_kind(name, PluginKind::ContextProvider)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Context provider '{}' not found. Install a plugin with kind = \"context-provider\" in ~/.gptengage/plugins/.",
+                    name
+                )
+            })?;
+        eprintln!("Gathering context from {}...", name);
+        let output = PluginInvoker::new(config.clone())
+            .with_allowlist()
+            .invoke(request, timeout, AccessMode::ReadOnly, None)
+            .await
+            .map_err(|e| anyhow::anyhow!("Context provider '{}' failed: {:#}", name, e))?;
+        if output.trim().is_empty() {
+            eprintln!("Warning: context provider '{}' printed nothing", name);
+            continue;
+        }
+        blocks.push(context_block(name, &output));
+    }
+    Ok(blocks.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_block() {
+        assert_eq!(
+            context_block("k8s-state", "\nNAME   READY\nweb    3/3\n\n"),
+            "[CONTEXT FROM K8S-STATE]\nNAME   READY\nweb    3/3\n[/CONTEXT FROM K8S-STATE]"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Plugin availability detection
+//!
+//! A plugin is available when its `check_command` is in PATH and, if
+//! `check_args` are configured, `check_command check_args` exits
+//! successfully within a short timeout (e.g. `ollama list` fails when the
+//! server isn't running). Results are cached for the lifetime of the process.
+
+use super::DetectionConfig;
+use crate::audit::ProcessAudit;
+use crate::config::ConfigManager;
+use crate::invokers::base::command_exists;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Maximum time an availability check may run
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+static CHECK_CACHE: OnceLock<Mutex<HashMap<Vec<String>, bool>>> = OnceLock::new();
+
+/// Check whether a plugin's CLI is available.
+///
+/// The check command is only executed once it is in
+/// `plugins.allowed_commands`; until then only PATH lookup is used.
+pub async fn is_available(detection: &DetectionConfig) -> bool {
+    if !command_exists(&detection.check_command) {
+        return false;
+    }
+    if detection.check_args.is_empty() {
+        return true;
+    }
+
+    let allowed = ConfigManager::shared()
+        .map(|resolved| resolved.config.plugins.is_allowed(&detection.check_command))
+        .unwrap_or(false);
+    if !allowed {
+        return true;
+    }
+
+    let mut key = vec![detection.check_command.clone()];
+    key.extend(detection.check_args.iter().cloned());
+
+    let cache = CHECK_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(&cached) = cache.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return cached;
+    }
+
+    let available = run_check(
+        &detection.check_command,
+        &detection.check_args,
+        CHECK_TIMEOUT,
+    )
+    .await;
+    cache
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key, available);
+    available
+}
+
+/// Run a check command, returning true if it exits successfully in time
+pub async fn run_check(command: &str, args: &[String], timeout: Duration) -> bool {
+    let mut check = Command::new(command);
+    check
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    let audit = ProcessAudit::start(check.as_std());
+    let mut child = match check.spawn() {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) => {
+            audit.finish(&status);
+            status.success()
+        }
+        _ => {
+            let _ = child.kill().await;
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_run_check_exit_status() {
+        assert!(run_check("true", &[], CHECK_TIMEOUT).await);
+        assert!(!run_check("false", &[], CHECK_TIMEOUT).await);
+        assert!(!run_check("gptengage-no-such-command", &[], CHECK_TIMEOUT).await);
+    }
+
+    #[tokio::test]
+    async fn test_run_check_times_out() {
+        let start = Instant::now();
+        assert!(!run_check("sleep", &["5".to_string()], Duration::from_millis(100)).await);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_run_check_leaves_the_runtime_free() {
+        // A slow check must not hold up other tasks on the same thread
+        let ticker = tokio::spawn(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Instant::now()
+        });
+        let start = Instant::now();
+        assert!(!run_check("sleep", &["5".to_string()], Duration::from_millis(500)).await);
+        let ticked = ticker.await.unwrap();
+        assert!(ticked.duration_since(start) < Duration::from_millis(400));
+    }
+}
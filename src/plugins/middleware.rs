@@ -0,0 +1,143 @@
+//! Prompt middleware - Plugins that rewrite prompts before they are sent
+//!
+//! A plugin with `kind = "middleware"` receives the fully assembled prompt
+//! on stdin and prints the prompt to send instead (translated, compressed,
+//! with a policy preamble, ...). The plugins named in `plugins.middleware`
+//! run in that order, each on the previous one's output, before every
+//! `invoke` and every debate turn.
+
+use super::{PluginKind, PluginManager};
+use crate::config::ConfigManager;
+use crate::invokers::plugin::PluginInvoker;
+use crate::invokers::{AccessMode, Invoker};
+
+/// Run `prompt` through the configured middleware chain, looked up in
+/// `plugins`. Without any middleware the prompt is returned unchanged.
+pub async fn apply_middleware(
+    prompt: &str,
+    timeout: u64,
+    plugins: &PluginManager,
+) -> anyhow::Result<String> {
+    let chain = ConfigManager::shared()
+        .map(|resolved| resolved.config.plugins.middleware.clone())
+        .unwrap_or_default();
+    run_chain(prompt, timeout, &chain, |name| {
+        let config = plugins.get_plugin_of_kind(name, PluginKind::Middleware)?;
+        Some(PluginInvoker::new(config.clone()).with_allowlist())
+    })
+    .await
+}
+
+/// Pass `prompt` through the middleware named in `chain`, in order, each
+/// found with `lookup`
+async fn run_chain(
+    prompt: &str,
+    timeout: u64,
+    chain: &[String],
+    lookup: impl Fn(&str) -> Option<PluginInvoker>,
+) -> anyhow::Result<String> {
+    let mut prompt = prompt.to_string();
+    for name in chain {
+        let middleware = lookup(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Middleware '{}' (from plugins.middleware) is not an installed, enabled plugin with kind = \"middleware\"",
+                name
+            )
+        })?;
+        let transformed = middleware
+            .invoke(&prompt, timeout, AccessMode::ReadOnly, None)
+            .await
+            .map_err(|e| anyhow::anyhow!("Middleware '{}' failed: {:#}", name, e))?;
+        if transformed.trim().is_empty() {
+            anyhow::bail!("Middleware '{}' returned an empty prompt", name);
+        }
+        prompt = transformed;
+    }
+    Ok(prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::PluginConfig;
+
+    /// A middleware plugin running `script` through sh, prompt on stdin
+    fn middleware(name: &str, script: &str) -> PluginInvoker {
+        let config: PluginConfig = toml::from_str(&format!(
+            r#"
+[plugin]
+name = "{name}"
+description = "Test middleware"
+command = "{script}"
+shell = true
+kind = "middleware"
+
+[invoke]
+base_args = []
+prompt_mode = "stdin"
+
+[access]
+readonly_args = []
+write_args = []
+
+[detection]
+check_command = "sh"
+check_args = []
+"#
+        ))
+        .unwrap();
+        PluginInvoker::new(config)
+    }
+
+    fn lookup(name: &str) -> Option<PluginInvoker> {
+        match name {
+            "first" => Some(middleware(name, "sed 's/$/ first/'")),
+            "second" => Some(middleware(name, "sed 's/$/ second/'")),
+            "crash" => Some(middleware(name, "cat >/dev/null; exit 3")),
+            "silent" => Some(middleware(name, "cat >/dev/null")),
+            _ => None,
+        }
+    }
+
+    fn chain(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_middleware_runs_in_configured_order() {
+        let out = run_chain("hi", 10, &chain(&["first", "second"]), lookup)
+            .await
+            .unwrap();
+        assert_eq!(out.trim(), "hi first second");
+        let out = run_chain("hi", 10, &chain(&["second", "first"]), lookup)
+            .await
+            .unwrap();
+        assert_eq!(out.trim(), "hi second first");
+        // No middleware leaves the prompt alone
+        assert_eq!(run_chain("hi", 10, &[], lookup).await.unwrap(), "hi");
+    }
+
+    #[tokio::test]
+    async fn test_middleware_failures_stop_the_prompt() {
+        let err = run_chain("hi", 10, &chain(&["first", "crash"]), lookup)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Middleware 'crash' failed"),
+            "{}",
+            err
+        );
+
+        let err = run_chain("hi", 10, &chain(&["silent"]), lookup)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'silent' returned an empty prompt"));
+
+        let err = run_chain("hi", 10, &chain(&["missing"]), lookup)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Middleware 'missing'"));
+    }
+}
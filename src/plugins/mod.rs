@@ -4,23 +4,43 @@
 //! Each plugin defines how to invoke a CLI, including command, arguments, and access modes.
 //!
 //! Plugin files are stored as TOML in `~/.gptengage/plugins/`.
+//!
+//! Besides CLIs to invoke, a plugin can be a context provider: a command
+//! whose output is added to the prompt (see [`context`]), or middleware
+//! that rewrites prompts before they are sent (see [`middleware`]).
+
+pub mod context;
+pub mod detection;
+pub mod middleware;
+pub mod trust;
 
+use crate::config::PluginsConfig;
+use crate::utils::watch::{definition_files, DirFingerprint};
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Plugin configuration loaded from a TOML file
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct PluginConfig {
     pub plugin: PluginMeta,
     pub invoke: InvokeConfig,
     pub access: AccessConfig,
     pub detection: DetectionConfig,
+    /// Interactive mode that keeps the CLI running between prompts,
+    /// used with `--workers`
+    #[serde(default)]
+    pub worker: Option<WorkerConfig>,
+    /// Debate templates shipped with the plugin, registered as
+    /// `<plugin>/<template>` while the plugin is enabled
+    #[serde(default)]
+    pub templates: Vec<crate::templates::DebateTemplate>,
 }
 
 /// Plugin metadata
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct PluginMeta {
     /// Unique plugin name (used as CLI identifier)
     pub name: String,
@@ -28,23 +48,72 @@ pub struct PluginMeta {
     pub description: String,
     /// Command to execute
     pub command: String,
+    /// Run `command` as a shell command line through `sh -c`, allowing
+    /// pipes and environment expansion. Arguments are passed as `"$@"`.
+    #[serde(default)]
+    pub shell: bool,
+    /// What the plugin is for
+    #[serde(default)]
+    pub kind: PluginKind,
+}
+
+/// What a plugin is for
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginKind {
+    /// A CLI that answers prompts, usable wherever claude, codex, or
+    /// gemini are
+    #[default]
+    Invoker,
+    /// A command whose output, given the request, is added to the prompt
+    /// as context with `--context-provider`
+    ContextProvider,
+    /// A command that reads a prompt on stdin and prints the prompt to
+    /// send instead, chained with `plugins.middleware`
+    Middleware,
+    /// A local evaluator that scores candidate answers, usable as a
+    /// `--judge` (see [`crate::orchestrator::judge`])
+    Judge,
+}
+
+impl std::fmt::Display for PluginKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PluginKind::Invoker => "invoker",
+            PluginKind::ContextProvider => "context-provider",
+            PluginKind::Middleware => "middleware",
+            PluginKind::Judge => "judge",
+        })
+    }
 }
 
 /// Invocation configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct InvokeConfig {
-    /// Base arguments passed to the command
+    /// Base arguments passed to the command. `{model}`, `{cwd}`, and
+    /// `{timeout}` are substituted at invocation time.
     pub base_args: Vec<String>,
     /// How to pass the prompt to the CLI
     pub prompt_mode: PromptMode,
     /// Argument flag for prompt (used with Arg mode)
     pub prompt_arg: Option<String>,
+    /// Arguments placing the prompt wherever `{prompt}` appears (used
+    /// with Template mode). Appended after the base, model, and access
+    /// arguments.
+    #[serde(default)]
+    pub args_template: Option<Vec<String>>,
     /// Argument flag for model selection (e.g., "--model")
     pub model_arg: Option<String>,
+    /// Prompt markup the CLI reads best: brackets, xml, or markdown
+    #[serde(default)]
+    pub dialect: Option<String>,
+    /// Context window of the CLI's models, in tokens
+    #[serde(default)]
+    pub context_window: Option<usize>,
 }
 
 /// How the prompt is passed to the CLI
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PromptMode {
     /// Pass prompt via stdin
@@ -53,10 +122,12 @@ pub enum PromptMode {
     Arg,
     /// Pass prompt as the last positional argument
     ArgLast,
+    /// Pass prompt wherever `{prompt}` appears in args_template
+    Template,
 }
 
 /// Access mode configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct AccessConfig {
     /// Additional arguments for read-only mode
     #[serde(default)]
@@ -66,8 +137,25 @@ pub struct AccessConfig {
     pub write_args: Vec<String>,
 }
 
+/// Keep-alive worker configuration.
+///
+/// The CLI is started once with `args` and kept running. Each prompt is
+/// written to its stdin followed by the `submit` line, and everything it
+/// prints up to the `end_marker` line is the response.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct WorkerConfig {
+    /// Arguments that start the interactive mode, used in place of
+    /// `base_args`. The same placeholders are substituted.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Line written after each prompt to send it
+    pub submit: String,
+    /// Line the CLI prints after each complete response
+    pub end_marker: String,
+}
+
 /// CLI detection configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct DetectionConfig {
     /// Command to check for availability
     pub check_command: String,
@@ -76,26 +164,94 @@ pub struct DetectionConfig {
     pub check_args: Vec<String>,
 }
 
+/// SHA-256 checksum of a plugin file's contents
+pub fn plugin_checksum(content: &str) -> String {
+    crate::audit::sha256_hex(content)
+}
+
+/// Check a plugin file's contents against its pinned checksum.
+///
+/// Files without a pin (copied in by hand) are accepted.
+pub fn verify_checksum(
+    file_name: &str,
+    content: &str,
+    pins: &HashMap<String, String>,
+) -> Result<()> {
+    if let Some(expected) = pins.get(file_name) {
+        let actual = plugin_checksum(content);
+        if !actual.eq_ignore_ascii_case(expected) {
+            anyhow::bail!(
+                "Plugin file changed since installation (checksum mismatch). \
+                 Reinstall it with 'gptengage plugin install' to accept the change."
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The minisign signature kept next to a signed plugin file
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".minisig");
+    PathBuf::from(name)
+}
+
+/// Check a minisign signature of a plugin file's contents against the
+/// trusted keys in `plugins.signing_keys`.
+pub fn verify_signature(content: &str, signature: &str, keys: &[String]) -> Result<()> {
+    let signature = minisign_verify::Signature::decode(signature)
+        .map_err(|e| anyhow::anyhow!("Invalid plugin signature: {}", e))?;
+    if keys.is_empty() {
+        anyhow::bail!(
+            "Plugin is signed, but no keys are configured to check it. \
+             Add the author's public key to plugins.signing_keys."
+        );
+    }
+    for key in keys {
+        let public_key = minisign_verify::PublicKey::from_base64(key.trim())
+            .map_err(|e| anyhow::anyhow!("Invalid key '{}' in plugins.signing_keys: {}", key, e))?;
+        if public_key
+            .verify(content.as_bytes(), &signature, false)
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+    anyhow::bail!("Plugin signature doesn't match any key in plugins.signing_keys")
+}
+
 /// Manages loading and accessing plugins
+#[derive(Clone)]
 pub struct PluginManager {
     plugins_dir: PathBuf,
     plugins: HashMap<String, PluginConfig>,
+    /// File each plugin was loaded from
+    files: HashMap<String, PathBuf>,
+    settings: PluginsConfig,
+    /// Snapshot of the plugins directory at the last load
+    fingerprint: DirFingerprint,
 }
 
 impl PluginManager {
     /// Create a new PluginManager and load plugins from the default directory
     pub fn new() -> Result<Self> {
         let plugins_dir = Self::get_plugins_dir()?;
+        let settings = crate::config::ConfigManager::shared()
+            .map(|resolved| resolved.config.plugins.clone())
+            .unwrap_or_default();
         let mut manager = Self {
             plugins_dir,
             plugins: HashMap::new(),
+            files: HashMap::new(),
+            settings,
+            fingerprint: DirFingerprint::default(),
         };
         manager.load_plugins()?;
         Ok(manager)
     }
 
     /// Get the plugins directory path
-    fn get_plugins_dir() -> Result<PathBuf> {
+    pub fn get_plugins_dir() -> Result<PathBuf> {
         let home = std::env::var("HOME").context("HOME environment variable not set")?;
         Ok(PathBuf::from(home).join(".gptengage").join("plugins"))
     }
@@ -103,27 +259,27 @@ impl PluginManager {
     /// Load all plugins from the plugins directory
     pub fn load_plugins(&mut self) -> Result<()> {
         self.plugins.clear();
+        self.files.clear();
+        self.fingerprint = DirFingerprint::of(&self.plugins_dir);
 
         if !self.plugins_dir.exists() {
             // No plugins directory, nothing to load
             return Ok(());
         }
 
-        let entries =
-            std::fs::read_dir(&self.plugins_dir).context("Failed to read plugins directory")?;
-
-        for entry in entries {
-            let entry = entry.context("Failed to read directory entry")?;
-            let path = entry.path();
-
-            if path.extension().map(|e| e == "toml").unwrap_or(false) {
-                match self.load_plugin_file(&path) {
-                    Ok(config) => {
-                        self.plugins.insert(config.plugin.name.clone(), config);
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to load plugin {}: {}", path.display(), e);
+        // Top-level files come first, so they win over a namespaced plugin
+        // (e.g. one synced with `gptengage sync`) of the same name
+        for path in definition_files(&self.plugins_dir) {
+            match self.load_plugin_file(&path) {
+                Ok(config) => {
+                    if self.plugins.contains_key(&config.plugin.name) {
+                        continue;
                     }
+                    self.files.insert(config.plugin.name.clone(), path.clone());
+                    self.plugins.insert(config.plugin.name.clone(), config);
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to load plugin {}: {:#}", path.display(), e);
                 }
             }
         }
@@ -131,29 +287,119 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Reload plugins if the plugins directory changed since the last load.
+    ///
+    /// Returns true if a reload happened.
+    pub fn reload_if_changed(&mut self) -> Result<bool> {
+        if !self.fingerprint.changed(&self.plugins_dir) {
+            return Ok(false);
+        }
+        self.load_plugins()?;
+        Ok(true)
+    }
+
     /// Load a single plugin from a TOML file
     fn load_plugin_file(&self, path: &PathBuf) -> Result<PluginConfig> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read plugin file: {}", path.display()))?;
 
-        let config: PluginConfig = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse plugin file: {}", path.display()))?;
+        // Pins are keyed by the path relative to the plugins directory
+        let file_name = path
+            .strip_prefix(&self.plugins_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        verify_checksum(&file_name, &content, &self.settings.checksums)?;
+        let signature_file = signature_path(path);
+        if signature_file.exists() {
+            let signature = std::fs::read_to_string(&signature_file).with_context(|| {
+                format!("Failed to read signature: {}", signature_file.display())
+            })?;
+            verify_signature(&content, &signature, &self.settings.signing_keys)?;
+        }
 
-        self.validate_plugin(&config)?;
+        Self::parse_plugin(&content)
+            .with_context(|| format!("Failed to parse plugin file: {}", path.display()))
+    }
 
+    /// Parse and validate plugin TOML
+    pub fn parse_plugin(content: &str) -> Result<PluginConfig> {
+        let config: PluginConfig = toml::from_str(content)?;
+        Self::validate_plugin(&config)?;
         Ok(config)
     }
 
     /// Validate a plugin configuration
-    fn validate_plugin(&self, config: &PluginConfig) -> Result<()> {
-        if config.plugin.name.is_empty() {
+    fn validate_plugin(config: &PluginConfig) -> Result<()> {
+        let name = &config.plugin.name;
+        if name.trim().is_empty() {
             anyhow::bail!("Plugin name cannot be empty");
         }
 
-        if config.plugin.command.is_empty() {
+        // Plugins are named in participant lists ("cli:persona,...") and
+        // namespace their templates ("plugin/template")
+        if name.contains(|c: char| c.is_whitespace() || ":,/@".contains(c)) {
+            anyhow::bail!(
+                "Plugin name '{}' can't contain whitespace or any of ':', ',', '/', '@'",
+                name
+            );
+        }
+
+        if config.plugin.command.trim().is_empty() {
             anyhow::bail!("Plugin command cannot be empty");
         }
 
+        if config.plugin.kind != PluginKind::Invoker && config.worker.is_some() {
+            anyhow::bail!("[worker] is only supported by invoker plugins");
+        }
+
+        // Both read a whole document from stdin
+        if matches!(
+            config.plugin.kind,
+            PluginKind::Middleware | PluginKind::Judge
+        ) && config.invoke.prompt_mode != PromptMode::Stdin
+        {
+            anyhow::bail!(
+                "Plugins of kind {} must use prompt_mode = \"stdin\"",
+                config.plugin.kind
+            );
+        }
+
+        match (config.invoke.prompt_mode, &config.invoke.args_template) {
+            (PromptMode::Template, None) => {
+                anyhow::bail!("prompt_mode = \"template\" needs args_template")
+            }
+            (PromptMode::Template, Some(template))
+                if !template.iter().any(|arg| arg.contains("{prompt}")) =>
+            {
+                anyhow::bail!("args_template must contain {{prompt}}")
+            }
+            (PromptMode::Template, Some(_)) | (_, None) => {}
+            (_, Some(_)) => {
+                anyhow::bail!("args_template is only used with prompt_mode = \"template\"")
+            }
+        }
+
+        if let Some(dialect) = &config.invoke.dialect {
+            crate::invokers::dialect::Dialect::parse(dialect)?;
+        }
+
+        if config.invoke.context_window == Some(0) {
+            anyhow::bail!("context_window must be at least 1");
+        }
+
+        if let Some(worker) = &config.worker {
+            // Prompts contain blank lines, so neither line may be blank
+            for (field, line) in [
+                ("submit", &worker.submit),
+                ("end_marker", &worker.end_marker),
+            ] {
+                if line.trim().is_empty() || line.contains('\n') {
+                    anyhow::bail!("[worker] {} must be a single non-blank line", field);
+                }
+            }
+        }
+
         // Ensure name doesn't conflict with built-in CLIs
         let reserved = ["claude", "codex", "gemini"];
         if reserved.contains(&config.plugin.name.to_lowercase().as_str()) {
@@ -166,19 +412,54 @@ impl PluginManager {
         Ok(())
     }
 
-    /// Get a plugin by name
+    /// Get an enabled plugin by name
     pub fn get_plugin(&self, name: &str) -> Option<&PluginConfig> {
+        if self.settings.is_disabled(name) {
+            return None;
+        }
         self.plugins.get(name)
     }
 
-    /// List all loaded plugins
+    /// Get an enabled plugin of the given kind by name
+    pub fn get_plugin_of_kind(&self, name: &str, kind: PluginKind) -> Option<&PluginConfig> {
+        self.get_plugin(name)
+            .filter(|config| config.plugin.kind == kind)
+    }
+
+    /// List all loaded plugins, including disabled ones
     pub fn list_plugins(&self) -> Vec<&PluginConfig> {
         self.plugins.values().collect()
     }
 
-    /// Check if a plugin exists
+    /// Check if an enabled plugin exists
     pub fn has_plugin(&self, name: &str) -> bool {
-        self.plugins.contains_key(name)
+        self.get_plugin(name).is_some()
+    }
+
+    /// Check if a plugin is loaded but disabled
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.plugins.contains_key(name) && self.settings.is_disabled(name)
+    }
+
+    /// Templates shipped by enabled plugins, keyed by namespaced name
+    pub fn plugin_templates(&self) -> Vec<(String, crate::templates::DebateTemplate)> {
+        self.plugins
+            .values()
+            .filter(|config| !self.settings.is_disabled(&config.plugin.name))
+            .flat_map(|config| {
+                config.templates.iter().map(move |template| {
+                    let name = format!("{}/{}", config.plugin.name, template.name);
+                    let mut template = template.clone();
+                    template.name = name.clone();
+                    (name, template)
+                })
+            })
+            .collect()
+    }
+
+    /// File a plugin was loaded from
+    pub fn plugin_file(&self, name: &str) -> Option<&PathBuf> {
+        self.files.get(name)
     }
 
     /// Validate a plugin file without loading it into the manager
@@ -187,25 +468,15 @@ impl PluginManager {
         let content = std::fs::read_to_string(&path)
             .with_context(|| format!("Failed to read plugin file: {}", path.display()))?;
 
-        let config: PluginConfig = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse plugin file: {}", path.display()))?;
-
-        // Basic validation
-        if config.plugin.name.is_empty() {
-            anyhow::bail!("Plugin name cannot be empty");
-        }
-
-        if config.plugin.command.is_empty() {
-            anyhow::bail!("Plugin command cannot be empty");
-        }
-
-        Ok(config)
+        Self::parse_plugin(&content)
+            .with_context(|| format!("Failed to parse plugin file: {}", path.display()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_plugin_config() {
@@ -235,6 +506,74 @@ check_args = ["--version"]
         assert_eq!(config.access.readonly_args, vec!["--readonly"]);
     }
 
+    #[test]
+    fn test_verify_checksum() {
+        let content = "[plugin]\nname = \"x\"\n";
+        let mut pins = HashMap::new();
+        assert!(verify_checksum("x.toml", content, &pins).is_ok());
+
+        pins.insert("x.toml".to_string(), plugin_checksum(content));
+        assert!(verify_checksum("x.toml", content, &pins).is_ok());
+        assert!(verify_checksum("x.toml", "tampered", &pins).is_err());
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        // Minisign's reference signature of the content "test"
+        let key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3".to_string();
+        let signature = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+        assert!(verify_signature("test", signature, std::slice::from_ref(&key)).is_ok());
+        assert!(verify_signature("tampered", signature, std::slice::from_ref(&key)).is_err());
+        assert!(verify_signature("test", signature, &[]).is_err());
+        assert!(verify_signature("test", "not a signature", &[key]).is_err());
+    }
+
+    #[test]
+    fn test_signature_path() {
+        assert_eq!(
+            signature_path(Path::new("/p/ollama.toml")),
+            PathBuf::from("/p/ollama.toml.minisig")
+        );
+    }
+
+    #[test]
+    fn test_parse_plugin_templates() {
+        let toml_content = r#"
+[plugin]
+name = "ollama"
+description = "Local models"
+command = "ollama"
+
+[invoke]
+base_args = ["run", "llama3"]
+prompt_mode = "arg_last"
+
+[access]
+
+[detection]
+check_command = "ollama"
+
+[[templates]]
+name = "local-review"
+description = "Review with local models"
+default_rounds = 2
+
+[[templates.participants]]
+cli = "ollama"
+persona = "Reviewer"
+instructions = "Review the code for correctness."
+"#;
+
+        let config: PluginConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.templates.len(), 1);
+        assert_eq!(config.templates[0].name, "local-review");
+        assert_eq!(config.templates[0].participants[0].cli, "ollama");
+    }
+
     #[test]
     fn test_parse_arg_last_mode() {
         let toml_content = r#"
@@ -280,4 +619,137 @@ check_command = "cmd"
         assert_eq!(config.invoke.prompt_mode, PromptMode::Arg);
         assert_eq!(config.invoke.prompt_arg, Some("-p".to_string()));
     }
+
+    #[test]
+    fn test_parse_template_mode() {
+        let toml_content = r#"
+[plugin]
+name = "test"
+description = "Test"
+command = "cmd"
+
+[invoke]
+base_args = []
+prompt_mode = "template"
+args_template = ["--chat", "{prompt}", "--title={prompt}"]
+
+[access]
+
+[detection]
+check_command = "cmd"
+"#;
+
+        let config = PluginManager::parse_plugin(toml_content).unwrap();
+        assert_eq!(config.invoke.prompt_mode, PromptMode::Template);
+        assert_eq!(
+            config.invoke.args_template.as_deref(),
+            Some(&["--chat", "{prompt}", "--title={prompt}"].map(String::from)[..])
+        );
+
+        let missing = toml_content.replace("args_template = [\"--chat\", ", "x = [");
+        assert!(PluginManager::parse_plugin(&missing).is_err());
+        let no_placeholder = toml_content.replace("{prompt}", "p");
+        assert!(PluginManager::parse_plugin(&no_placeholder).is_err());
+        let wrong_mode = toml_content.replace("\"template\"", "\"stdin\"");
+        assert!(PluginManager::parse_plugin(&wrong_mode).is_err());
+    }
+
+    /// A minimal plugin manifest, with `name` quoted as TOML
+    fn manifest(name: &str) -> String {
+        format!(
+            "[plugin]\nname = {}\ndescription = \"Test\"\ncommand = \"test-cmd\"\n\
+             [invoke]\nbase_args = []\nprompt_mode = \"stdin\"\n[access]\n[detection]\ncheck_command = \"test-cmd\"\n",
+            toml::Value::String(name.to_string())
+        )
+    }
+
+    #[test]
+    fn test_parse_plugin_rejects_unusable_names() {
+        assert!(PluginManager::parse_plugin(&manifest("my-cli")).is_ok());
+        for (name, error) in [
+            ("  ", "cannot be empty"),
+            ("my cli", "can't contain"),
+            ("a:b", "can't contain"),
+            ("Claude", "conflicts with built-in"),
+        ] {
+            let err = PluginManager::parse_plugin(&manifest(name))
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(error), "{}: {}", name, err);
+        }
+    }
+
+    #[test]
+    fn test_parse_plugin_worker() {
+        let with_worker = |submit: &str| {
+            format!(
+                "{}[worker]\nargs = [\"chat\"]\nsubmit = {}\nend_marker = \"/done\"\n",
+                manifest("repl"),
+                toml::Value::String(submit.to_string())
+            )
+        };
+        let config = PluginManager::parse_plugin(&with_worker("/send")).unwrap();
+        let worker = config.worker.unwrap();
+        assert_eq!(worker.args, vec!["chat"]);
+        assert_eq!(worker.submit, "/send");
+        assert!(PluginManager::parse_plugin(&manifest("repl"))
+            .unwrap()
+            .worker
+            .is_none());
+
+        let err = PluginManager::parse_plugin(&with_worker(" "))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("submit must be"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_plugin_kind() {
+        assert_eq!(
+            PluginManager::parse_plugin(&manifest("cli"))
+                .unwrap()
+                .plugin
+                .kind,
+            PluginKind::Invoker
+        );
+        let provider = manifest("k8s").replace("[invoke]", "kind = \"context-provider\"\n[invoke]");
+        let config = PluginManager::parse_plugin(&provider).unwrap();
+        assert_eq!(config.plugin.kind, PluginKind::ContextProvider);
+
+        let with_worker = format!(
+            "{}[worker]\nsubmit = \"/send\"\nend_marker = \"/done\"\n",
+            provider
+        );
+        let err = PluginManager::parse_plugin(&with_worker)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("only supported by invoker plugins"), "{}", err);
+
+        let middleware = manifest("redact").replace("[invoke]", "kind = \"middleware\"\n[invoke]");
+        let config = PluginManager::parse_plugin(&middleware).unwrap();
+        assert_eq!(config.plugin.kind, PluginKind::Middleware);
+        let err = PluginManager::parse_plugin(
+            &middleware.replace("prompt_mode = \"stdin\"", "prompt_mode = \"arg_last\""),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("must use prompt_mode"), "{}", err);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_plugin_never_panics(content in "\\PC*") {
+            let _ = PluginManager::parse_plugin(&content);
+        }
+
+        #[test]
+        fn prop_valid_plugin_names_are_usable(name in "\\PC{0,12}") {
+            if let Ok(config) = PluginManager::parse_plugin(&manifest(&name)) {
+                // A loaded plugin can always be named as a participant
+                let spec = format!("{}:Reviewer", config.plugin.name);
+                prop_assert_eq!(spec.split([':', ',']).count(), 2);
+                prop_assert!(!config.plugin.name.trim().is_empty());
+            }
+        }
+    }
 }
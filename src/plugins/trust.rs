@@ -0,0 +1,162 @@
+//! Plugin command allowlist
+//!
+//! Plugins may only execute commands listed in `plugins.allowed_commands`
+//! in the config file. The first time a plugin with an unknown command is
+//! used, the user is asked to trust it (or must pass `--trust`), so a
+//! malicious or typo'd plugin file can't silently run arbitrary binaries.
+
+use super::PluginConfig;
+use crate::config::{ConfigManager, PluginsConfig};
+use crate::utils::interaction::{can_ask, confirm};
+use anyhow::Result;
+use std::sync::Mutex;
+
+/// Serializes trust prompts when several participants use the same plugin
+static TRUST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Ensure a plugin's command is allowlisted, asking to trust it on first use
+pub fn ensure_trusted(config: &PluginConfig) -> Result<()> {
+    let _guard = TRUST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    // Not with --yes: trusting a command takes --trust
+    let ask = || confirm_trust(config);
+    trust_command(
+        config,
+        &ConfigManager::shared()?.config.plugins,
+        crate::invokers::policy::policy().trust_plugins,
+        can_ask().then_some(&ask as &dyn Fn() -> Result<bool>),
+        |command| {
+            let mut manager = ConfigManager::new()?;
+            manager.plugins.allowed_commands.push(command.to_string());
+            manager.save()
+        },
+    )
+}
+
+/// Allow `config`'s command if `plugins` already does, `trust_all`
+/// (`--trust`) is set, or `ask` approves it; `record` adds a newly trusted
+/// command to the allowlist. Without `ask`, no one can be asked.
+fn trust_command(
+    config: &PluginConfig,
+    plugins: &PluginsConfig,
+    trust_all: bool,
+    ask: Option<&dyn Fn() -> Result<bool>>,
+    record: impl FnOnce(&str) -> Result<()>,
+) -> Result<()> {
+    let command = &config.plugin.command;
+    if plugins.is_allowed(command) {
+        return Ok(());
+    }
+
+    let trusted = match ask {
+        _ if trust_all => true,
+        Some(ask) => ask()?,
+        None => false,
+    };
+
+    if !trusted {
+        return Err(anyhow::anyhow!(
+            "Plugin '{}' wants to run '{}', which is not in plugins.allowed_commands. \
+             Re-run with --trust to allow it, or add it with: \
+             gptengage config set plugins.allowed_commands <commands>",
+            config.plugin.name,
+            command
+        ));
+    }
+
+    record(command)?;
+    eprintln!(
+        "Trusted '{}' for plugin '{}' (added to plugins.allowed_commands)",
+        command, config.plugin.name
+    );
+    Ok(())
+}
+
+/// Ask the user on the terminal whether to trust a plugin command
+fn confirm_trust(config: &PluginConfig) -> Result<bool> {
+    if config.plugin.shell {
+        eprintln!(
+            "Warning: plugin '{}' runs a shell command line through sh -c. \
+             Only trust it if you have read and understand the whole line.",
+            config.plugin.name
+        );
+    }
+    confirm(
+        &format!(
+            "Plugin '{}' wants to run '{}'. Trust this command?",
+            config.plugin.name, config.plugin.command
+        ),
+        false,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin() -> PluginConfig {
+        toml::from_str(
+            r#"
+[plugin]
+name = "local"
+description = "A local model"
+command = "ollama"
+
+[invoke]
+base_args = ["run"]
+prompt_mode = "stdin"
+
+[access]
+readonly_args = []
+write_args = []
+
+[detection]
+check_command = "ollama"
+check_args = []
+"#,
+        )
+        .unwrap()
+    }
+
+    fn unused(_: &str) -> Result<()> {
+        panic!("nothing should be added to the allowlist")
+    }
+
+    #[test]
+    fn test_untrusted_command_refused_when_no_one_can_be_asked() {
+        let err = trust_command(&plugin(), &PluginsConfig::default(), false, None, unused)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'ollama'"));
+        assert!(err.contains("--trust"));
+    }
+
+    #[test]
+    fn test_allowlisted_command_runs_without_asking() {
+        let plugins = PluginsConfig {
+            allowed_commands: vec!["ollama".to_string()],
+            ..Default::default()
+        };
+        let ask = || -> Result<bool> { panic!("an allowlisted command must not prompt") };
+        assert!(trust_command(&plugin(), &plugins, false, Some(&ask), unused).is_ok());
+    }
+
+    #[test]
+    fn test_approving_adds_command_to_allowlist() {
+        let mut plugins = PluginsConfig::default();
+        let mut added = Vec::new();
+        let ask = || Ok(true);
+        let record = |command: &str| {
+            added.push(command.to_string());
+            Ok(())
+        };
+        assert!(trust_command(&plugin(), &plugins, false, Some(&ask), record).is_ok());
+        plugins.allowed_commands = added;
+        assert!(plugins.is_allowed("ollama"));
+
+        // Declining adds nothing
+        let ask = || Ok(false);
+        let fresh = PluginsConfig::default();
+        assert!(trust_command(&plugin(), &fresh, false, Some(&ask), unused).is_err());
+    }
+}
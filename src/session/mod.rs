@@ -1,9 +1,15 @@
 //! Session management - Store and manage conversation history
 
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod store;
+pub mod trash;
+
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use store::{open_store, MemoryStore, SessionStore};
+use trash::{Trash, TrashedSession};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
@@ -13,6 +19,21 @@ pub struct Session {
     pub created_at: DateTime<Utc>,
     pub last_interaction: DateTime<Utc>,
     pub turns: Vec<Turn>,
+    /// The CLI's own conversation continued by this session, when it uses
+    /// native continuation instead of re-sending history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native: Option<NativeConversation>,
+}
+
+/// A conversation kept by the CLI itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NativeConversation {
+    /// CLI that owns the conversation
+    pub cli: String,
+    /// ID the CLI resumes it by
+    pub id: String,
+    /// Number of session turns the conversation has seen
+    pub turns: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,22 +41,60 @@ pub struct Turn {
     pub role: String, // "user" or "assistant"
     pub content: String,
     pub timestamp: DateTime<Utc>,
+    /// Raw output log of the invocation behind an assistant turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+    /// Files the CLI changed while producing an assistant turn (with --write)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changes: Option<crate::utils::workspace::WorkspaceChanges>,
 }
 
 #[derive(Debug)]
 pub struct SessionManager {
-    sessions_dir: PathBuf,
+    store: Box<dyn SessionStore>,
+    /// Where deleted sessions go; without one they are deleted outright
+    trash: Option<Trash>,
+    /// `history.privacy = "hash-only"`: sessions would keep prompts and
+    /// responses as written, so saving them is refused
+    hash_only: bool,
 }
 
 impl SessionManager {
+    /// Use the storage backend selected by the `session_store` config key
     pub fn new() -> Result<Self> {
-        let config_dir = crate::config::ConfigManager::get_config_dir()?;
-        let sessions_dir = config_dir.join("sessions");
+        // A broken config must not quietly send sessions to another store
+        let resolved = crate::config::ConfigManager::shared()?;
+        let config = &resolved.config;
+        Ok(Self::with_store(open_store(&config.session_store)?)
+            .with_trash(Trash::open(config.trash_retention_days)?)
+            .with_hash_only(config.history.is_hash_only()))
+    }
 
-        // Create sessions directory if it doesn't exist
-        std::fs::create_dir_all(&sessions_dir)?;
+    /// Use a specific storage backend
+    pub fn with_store(store: Box<dyn SessionStore>) -> Self {
+        SessionManager {
+            store,
+            trash: None,
+            hash_only: false,
+        }
+    }
 
-        Ok(SessionManager { sessions_dir })
+    /// Refuse to save sessions, as `history.privacy = "hash-only"` requires
+    pub fn with_hash_only(mut self, hash_only: bool) -> Self {
+        self.hash_only = hash_only;
+        self
+    }
+
+    /// Move deleted sessions to `trash`. A trash keeping sessions for 0
+    /// days is no trash at all.
+    pub fn with_trash(mut self, trash: Trash) -> Self {
+        self.trash = Some(trash).filter(|trash| !trash.is_disabled());
+        self
+    }
+
+    /// Keep sessions in memory only
+    pub fn in_memory() -> Self {
+        Self::with_store(Box::new(MemoryStore::default()))
     }
 
     /// Validate session name to prevent directory traversal
@@ -70,73 +129,111 @@ impl SessionManager {
             created_at: now,
             last_interaction: now,
             turns: Vec::new(),
+            native: None,
         })
     }
 
-    /// Load a session from disk
+    /// Load a session
     pub async fn load_session(&self, name: &str) -> Result<Session> {
         Self::validate_name(name)?;
-        let path = self.sessions_dir.join(format!("{}.json", name));
-        if !path.exists() {
-            return Err(anyhow::anyhow!("Session '{}' not found", name));
-        }
-        let content = tokio::fs::read_to_string(&path).await?;
-        let session = serde_json::from_str(&content)?;
-        Ok(session)
+        self.store
+            .load(name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session '{}' not found", name))
     }
 
-    /// Save a session to disk
+    /// Save a session
     pub async fn save_session(&self, session: &Session) -> Result<()> {
         Self::validate_name(&session.name)?;
-        let path = self.sessions_dir.join(format!("{}.json", session.name));
-        let content = serde_json::to_string_pretty(&session)?;
-        tokio::fs::write(&path, content).await?;
-        Ok(())
+        if self.hash_only {
+            anyhow::bail!(
+                "Sessions store prompts and responses as written, which \
+                 history.privacy = \"hash-only\" rules out. Run without --session, \
+                 or set history.privacy to full"
+            );
+        }
+        self.store.save(session).await
     }
 
     /// List all sessions
     pub async fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
-        let mut entries = tokio::fs::read_dir(&self.sessions_dir).await?;
-        let mut summaries = Vec::new();
-
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            if path.extension().is_some_and(|ext| ext == "json") {
-                if let Ok(content) = tokio::fs::read_to_string(&path).await {
-                    if let Ok(session) = serde_json::from_str::<Session>(&content) {
-                        summaries.push(SessionSummary {
-                            name: session.name,
-                            cli: session.cli,
-                            topic: session.topic,
-                            last_interaction: session.last_interaction,
-                        });
-                    }
-                }
-            }
-        }
+        let mut summaries: Vec<SessionSummary> = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .map(|session| SessionSummary {
+                name: session.name,
+                cli: session.cli,
+                topic: session.topic,
+                last_interaction: session.last_interaction,
+            })
+            .collect();
 
         // Sort by last interaction (most recent first)
-        summaries.sort_by(|a, b| b.last_interaction.cmp(&a.last_interaction));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.last_interaction));
         Ok(summaries)
     }
 
-    /// Delete a session
+    /// Load every session with its turns, most recently used first
+    pub async fn all_sessions(&self) -> Result<Vec<Session>> {
+        let mut sessions = self.store.list().await?;
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.last_interaction));
+        Ok(sessions)
+    }
+
+    /// Delete a session, moving it to the trash if there is one
     pub async fn delete_session(&self, name: &str) -> Result<()> {
         Self::validate_name(name)?;
-        let path = self.sessions_dir.join(format!("{}.json", name));
-        if !path.exists() {
+        if let Some(trash) = &self.trash {
+            trash.put(&self.load_session(name).await?)?;
+        }
+        if !self.store.delete(name).await? {
             return Err(anyhow::anyhow!("Session '{}' not found", name));
         }
-        tokio::fs::remove_file(&path).await?;
         Ok(())
     }
 
+    /// Whether deleted sessions go to the trash
+    pub fn has_trash(&self) -> bool {
+        self.trash.is_some()
+    }
+
+    /// Deleted sessions still in the trash, oldest first
+    pub fn trashed_sessions(&self) -> Result<Vec<TrashedSession>> {
+        match &self.trash {
+            Some(trash) => trash.list(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Restore the most recently deleted session named `name`
+    pub async fn undelete_session(&self, name: &str) -> Result<Session> {
+        Self::validate_name(name)?;
+        let trash = self.trash.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Deleted sessions aren't kept (trash_retention_days is 0)")
+        })?;
+        if self.store.load(name).await?.is_some() {
+            anyhow::bail!(
+                "A session named '{}' already exists. Delete or rename it first",
+                name
+            );
+        }
+        let session = trash
+            .take(name)?
+            .ok_or_else(|| anyhow::anyhow!("No deleted session named '{}' in the trash", name))?;
+        self.store.save(&session).await?;
+        Ok(session)
+    }
+
     /// Add a turn to a session
     pub fn add_turn(&self, session: &mut Session, role: String, content: String) {
         let turn = Turn {
             role,
             content,
             timestamp: Utc::now(),
+            log: None,
+            changes: None,
         };
         session.turns.push(turn);
         session.last_interaction = Utc::now();
@@ -144,29 +241,59 @@ impl SessionManager {
 
     /// Build prompt with session history injected
     pub fn build_prompt_with_history(&self, session: &Session, current_prompt: &str) -> String {
-        if session.turns.is_empty() {
-            return current_prompt.to_string();
-        }
+        self.build_prompt_with_recent_history(session, current_prompt, None)
+    }
 
-        let mut prompt = String::new();
-        prompt.push_str("[CONVERSATION HISTORY]\n");
+    /// Build prompt with only the last `last` turns of history injected
+    /// (all turns when None, none when Some(0))
+    pub fn build_prompt_with_recent_history(
+        &self,
+        session: &Session,
+        current_prompt: &str,
+        last: Option<usize>,
+    ) -> String {
+        prompt_with_turns(recent_turns(session, last), current_prompt)
+    }
+}
 
-        for turn in &session.turns {
-            let role = if turn.role == "user" {
-                "User"
-            } else {
-                "Assistant"
-            };
-            prompt.push_str(&format!("{}: {}\n\n", role, turn.content));
-        }
+/// Prompt with the given turns injected as conversation history
+pub fn prompt_with_turns(turns: &[Turn], current_prompt: &str) -> String {
+    if turns.is_empty() {
+        return current_prompt.to_string();
+    }
 
-        prompt.push_str("[/CONVERSATION HISTORY]\n\n");
-        prompt.push_str("[CURRENT REQUEST]\n");
-        prompt.push_str(current_prompt);
-        prompt.push_str("\n[/CURRENT REQUEST]");
+    let mut prompt = String::new();
+    prompt.push_str("[CONVERSATION HISTORY]\n");
 
-        prompt
+    for turn in turns {
+        let role = if turn.role == "user" {
+            "User"
+        } else {
+            "Assistant"
+        };
+        prompt.push_str(&format!("{}: {}\n\n", role, turn.content));
     }
+
+    prompt.push_str("[/CONVERSATION HISTORY]\n\n");
+    prompt.push_str("[CURRENT REQUEST]\n");
+    prompt.push_str(current_prompt);
+    prompt.push_str("\n[/CURRENT REQUEST]");
+
+    prompt
+}
+
+/// Turns missing from the session's native conversation with `cli` (added
+/// with another CLI or without native continuation), or None when the
+/// session has no native conversation with that CLI
+pub fn unseen_turns<'a>(session: &'a Session, cli: &str) -> Option<&'a [Turn]> {
+    let native = session.native.as_ref().filter(|n| n.cli == cli)?;
+    Some(&session.turns[native.turns.min(session.turns.len())..])
+}
+
+/// The last `last` turns of a session (all turns when None)
+pub fn recent_turns(session: &Session, last: Option<usize>) -> &[Turn] {
+    let keep = last.unwrap_or(session.turns.len()).min(session.turns.len());
+    &session.turns[session.turns.len() - keep..]
 }
 
 #[derive(Debug, Clone)]
@@ -215,7 +342,7 @@ mod tests {
 
     #[test]
     fn test_create_session() {
-        let manager = SessionManager::new().unwrap();
+        let manager = SessionManager::in_memory();
         let session = manager
             .create_session(
                 "test-session".to_string(),
@@ -232,7 +359,7 @@ mod tests {
 
     #[test]
     fn test_add_turn() {
-        let manager = SessionManager::new().unwrap();
+        let manager = SessionManager::in_memory();
         let mut session = manager
             .create_session(
                 "test-session".to_string(),
@@ -258,7 +385,7 @@ mod tests {
 
     #[test]
     fn test_build_prompt_with_empty_history() {
-        let manager = SessionManager::new().unwrap();
+        let manager = SessionManager::in_memory();
         let session = manager
             .create_session(
                 "test-session".to_string(),
@@ -273,7 +400,7 @@ mod tests {
 
     #[test]
     fn test_build_prompt_with_history() {
-        let manager = SessionManager::new().unwrap();
+        let manager = SessionManager::in_memory();
         let mut session = manager
             .create_session(
                 "test-session".to_string(),
@@ -316,7 +443,7 @@ mod tests {
 
     #[test]
     fn test_prompt_injection_multiple_turns() {
-        let manager = SessionManager::new().unwrap();
+        let manager = SessionManager::in_memory();
         let mut session = manager
             .create_session(
                 "test".to_string(),
@@ -355,6 +482,69 @@ mod tests {
         assert!(prompt.contains("New question"));
     }
 
+    #[test]
+    fn test_build_prompt_with_recent_history() {
+        let manager = SessionManager::in_memory();
+        let mut session = manager
+            .create_session(
+                "test".to_string(),
+                "claude".to_string(),
+                "Topic".to_string(),
+            )
+            .unwrap();
+        for i in 0..3 {
+            manager.add_turn(&mut session, "user".to_string(), format!("Question {}", i));
+            manager.add_turn(
+                &mut session,
+                "assistant".to_string(),
+                format!("Answer {}", i),
+            );
+        }
+
+        let prompt = manager.build_prompt_with_recent_history(&session, "Next", Some(2));
+        assert!(!prompt.contains("Answer 1"));
+        assert!(prompt.contains("User: Question 2"));
+        assert!(prompt.contains("Assistant: Answer 2"));
+
+        let prompt = manager.build_prompt_with_recent_history(&session, "Next", Some(0));
+        assert_eq!(prompt, "Next");
+
+        assert_eq!(recent_turns(&session, Some(100)).len(), 6);
+        assert_eq!(recent_turns(&session, None).len(), 6);
+
+        assert!(unseen_turns(&session, "claude").is_none());
+        session.native = Some(NativeConversation {
+            cli: "claude".to_string(),
+            id: "abc".to_string(),
+            turns: 4,
+        });
+        let unseen = unseen_turns(&session, "claude").unwrap();
+        assert_eq!(unseen.len(), 2);
+        assert_eq!(unseen[0].content, "Question 2");
+        assert!(unseen_turns(&session, "codex").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_save_load_and_list() {
+        let manager = SessionManager::in_memory();
+        let mut session = manager
+            .create_session("a".to_string(), "claude".to_string(), "A".to_string())
+            .unwrap();
+        manager.add_turn(&mut session, "user".to_string(), "Hi".to_string());
+        manager.save_session(&session).await.unwrap();
+
+        assert_eq!(manager.load_session("a").await.unwrap().turns.len(), 1);
+        assert_eq!(manager.list_sessions().await.unwrap()[0].name, "a");
+        assert!(manager.load_session("../a").await.is_err());
+        manager.delete_session("a").await.unwrap();
+        assert!(manager.delete_session("a").await.is_err());
+
+        let manager = SessionManager::in_memory().with_hash_only(true);
+        let err = manager.save_session(&session).await.unwrap_err();
+        assert!(err.to_string().contains("hash-only"), "{}", err);
+        assert!(manager.list_sessions().await.unwrap().is_empty());
+    }
+
     #[test]
     fn test_session_serialization() {
         let now = Utc::now();
@@ -369,13 +559,22 @@ mod tests {
                     role: "user".to_string(),
                     content: "Hello".to_string(),
                     timestamp: now,
+                    log: None,
+                    changes: None,
                 },
                 Turn {
                     role: "assistant".to_string(),
                     content: "Hi".to_string(),
                     timestamp: now,
+                    log: None,
+                    changes: None,
                 },
             ],
+            native: Some(NativeConversation {
+                cli: "claude".to_string(),
+                id: "3f1c-77aa".to_string(),
+                turns: 2,
+            }),
         };
 
         // Serialize
@@ -388,5 +587,15 @@ mod tests {
         assert_eq!(deserialized.cli, session.cli);
         assert_eq!(deserialized.topic, session.topic);
         assert_eq!(deserialized.turns.len(), 2);
+        assert_eq!(deserialized.native, session.native);
+
+        // Sessions saved before native continuation existed still load
+        let legacy = json.replace(
+            r#","native":{"cli":"claude","id":"3f1c-77aa","turns":2}"#,
+            "",
+        );
+        assert!(!legacy.contains("native"));
+        let legacy: Session = serde_json::from_str(&legacy).unwrap();
+        assert_eq!(legacy.native, None);
     }
 }
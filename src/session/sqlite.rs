@@ -0,0 +1,144 @@
+//! SQLite session storage (`session_store = "sqlite"`)
+//!
+//! Each session is one row holding its JSON document, so the schema does
+//! not change when session fields are added.
+
+use super::store::SessionStore;
+use super::Session;
+use anyhow::Result;
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Sessions in a single SQLite database file
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Open (creating if needed) the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// A private in-memory database
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                name TEXT PRIMARY KEY,
+                data TEXT NOT NULL
+            )",
+        )?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Run `query` against the connection on the blocking thread pool, so
+    /// a slow disk never stalls the async runtime
+    async fn with_conn<T, F>(&self, query: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || query(&conn.lock().unwrap_or_else(|e| e.into_inner())))
+            .await?
+    }
+}
+
+#[async_trait]
+impl SessionStore for SqliteStore {
+    async fn load(&self, name: &str) -> Result<Option<Session>> {
+        let name = name.to_string();
+        let data: Option<String> = self
+            .with_conn(move |conn| {
+                Ok(conn
+                    .query_row(
+                        "SELECT data FROM sessions WHERE name = ?1",
+                        params![name],
+                        |row| row.get(0),
+                    )
+                    .optional()?)
+            })
+            .await?;
+        match data {
+            Some(data) => Ok(Some(serde_json::from_str(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn save(&self, session: &Session) -> Result<()> {
+        let name = session.name.clone();
+        let data = serde_json::to_string(session)?;
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO sessions (name, data) VALUES (?1, ?2)
+                 ON CONFLICT(name) DO UPDATE SET data = excluded.data",
+                params![name, data],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn list(&self) -> Result<Vec<Session>> {
+        let rows = self
+            .with_conn(|conn| {
+                let mut statement = conn.prepare("SELECT data FROM sessions")?;
+                let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+                Ok(rows.filter_map(|data| data.ok()).collect::<Vec<_>>())
+            })
+            .await?;
+        Ok(rows
+            .iter()
+            .filter_map(|data| serde_json::from_str(data).ok())
+            .collect())
+    }
+
+    async fn delete(&self, name: &str) -> Result<bool> {
+        let name = name.to_string();
+        let deleted = self
+            .with_conn(move |conn| {
+                Ok(conn.execute("DELETE FROM sessions WHERE name = ?1", params![name])?)
+            })
+            .await?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_sqlite_store_round_trip() {
+        let store = SqliteStore::in_memory().unwrap();
+        let now = Utc::now();
+        let mut session = Session {
+            name: "review".to_string(),
+            cli: "claude".to_string(),
+            topic: "Topic".to_string(),
+            created_at: now,
+            last_interaction: now,
+            turns: Vec::new(),
+            native: None,
+        };
+        store.save(&session).await.unwrap();
+        session.topic = "Renamed".to_string();
+        store.save(&session).await.unwrap();
+
+        let loaded = store.load("review").await.unwrap().unwrap();
+        assert_eq!(loaded.topic, "Renamed");
+        assert_eq!(store.list().await.unwrap().len(), 1);
+        assert!(store.delete("review").await.unwrap());
+        assert!(store.load("review").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,186 @@
+//! Session storage backends
+//!
+//! `SessionManager` reads and writes sessions through a [`SessionStore`]:
+//! JSON files in `~/.gptengage/sessions/` by default, a SQLite database
+//! when built with the `sqlite` feature, or memory (for tests and embedding).
+
+use super::Session;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Where sessions are persisted
+#[async_trait]
+pub trait SessionStore: Send + Sync + std::fmt::Debug {
+    /// Load a session, or None if it doesn't exist
+    async fn load(&self, name: &str) -> Result<Option<Session>>;
+
+    /// Create or replace a session
+    async fn save(&self, session: &Session) -> Result<()>;
+
+    /// All readable sessions, in no particular order
+    async fn list(&self) -> Result<Vec<Session>>;
+
+    /// Delete a session, returning whether it existed
+    async fn delete(&self, name: &str) -> Result<bool>;
+}
+
+/// Storage backend names accepted by the `session_store` config key
+pub const STORE_KINDS: &[&str] = &["file", "sqlite"];
+
+/// Open the backend named by the `session_store` config key
+pub fn open_store(kind: &str) -> Result<Box<dyn SessionStore>> {
+    let config_dir = crate::config::ConfigManager::get_config_dir()?;
+    match kind {
+        "file" => Ok(Box::new(FileStore::open(config_dir.join("sessions"))?)),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => Ok(Box::new(super::sqlite::SqliteStore::open(
+            config_dir.join("sessions.db"),
+        )?)),
+        #[cfg(not(feature = "sqlite"))]
+        "sqlite" => Err(anyhow::anyhow!(
+            "session_store = \"sqlite\" needs a build with the `sqlite` feature \
+             (cargo install gptengage --features sqlite)"
+        )),
+        other => Err(anyhow::anyhow!(
+            "Unknown session_store '{}'. Use one of: {}",
+            other,
+            STORE_KINDS.join(", ")
+        )),
+    }
+}
+
+/// One JSON file per session
+#[derive(Debug)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    /// Use `dir`, creating it if needed
+    pub fn open(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+#[async_trait]
+impl SessionStore for FileStore {
+    async fn load(&self, name: &str) -> Result<Option<Session>> {
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = tokio::fs::read_to_string(&path).await?;
+        let session = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse session file: {}", path.display()))?;
+        Ok(Some(session))
+    }
+
+    async fn save(&self, session: &Session) -> Result<()> {
+        let content = serde_json::to_string_pretty(session)?;
+        tokio::fs::write(self.path(&session.name), content).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<Session>> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        let mut sessions = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Ok(content) = tokio::fs::read_to_string(&path).await {
+                    if let Ok(session) = serde_json::from_str::<Session>(&content) {
+                        sessions.push(session);
+                    }
+                }
+            }
+        }
+        Ok(sessions)
+    }
+
+    async fn delete(&self, name: &str) -> Result<bool> {
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(false);
+        }
+        tokio::fs::remove_file(&path).await?;
+        Ok(true)
+    }
+}
+
+/// Sessions kept in memory for the life of the store
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl MemoryStore {
+    fn sessions(&self) -> std::sync::MutexGuard<'_, HashMap<String, Session>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemoryStore {
+    async fn load(&self, name: &str) -> Result<Option<Session>> {
+        Ok(self.sessions().get(name).cloned())
+    }
+
+    async fn save(&self, session: &Session) -> Result<()> {
+        self.sessions()
+            .insert(session.name.clone(), session.clone());
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<Session>> {
+        Ok(self.sessions().values().cloned().collect())
+    }
+
+    async fn delete(&self, name: &str) -> Result<bool> {
+        Ok(self.sessions().remove(name).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn session(name: &str) -> Session {
+        let now = Utc::now();
+        Session {
+            name: name.to_string(),
+            cli: "claude".to_string(),
+            topic: "Topic".to_string(),
+            created_at: now,
+            last_interaction: now,
+            turns: Vec::new(),
+            native: None,
+        }
+    }
+
+    async fn exercise(store: &dyn SessionStore) {
+        assert!(store.load("a").await.unwrap().is_none());
+        store.save(&session("a")).await.unwrap();
+        store.save(&session("b")).await.unwrap();
+        assert_eq!(store.load("a").await.unwrap().unwrap().name, "a");
+        assert_eq!(store.list().await.unwrap().len(), 2);
+        assert!(store.delete("a").await.unwrap());
+        assert!(!store.delete("a").await.unwrap());
+        assert_eq!(store.list().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_and_memory_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        exercise(&FileStore::open(dir.path().join("sessions")).unwrap()).await;
+        exercise(&MemoryStore::default()).await;
+    }
+}
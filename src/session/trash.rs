@@ -0,0 +1,176 @@
+//! Session trash - Deleted sessions kept for a while
+//!
+//! Deleting a session moves it to `~/.gptengage/trash/sessions/` as a JSON
+//! file, whichever store it came from, so `session undelete <name>` can
+//! bring it back. Entries older than `trash_retention_days` are removed
+//! whenever the trash is used; a retention of 0 deletes sessions outright.
+
+use super::Session;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Timestamp prefix of trash file names, sortable and parseable
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3f";
+
+/// A deleted session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedSession {
+    pub deleted_at: DateTime<Utc>,
+    pub session: Session,
+}
+
+/// Directory of deleted sessions
+#[derive(Debug, Clone)]
+pub struct Trash {
+    dir: PathBuf,
+    retention: Duration,
+}
+
+impl Trash {
+    /// The trash in the config directory, keeping sessions for
+    /// `retention_days`
+    pub fn open(retention_days: u64) -> Result<Self> {
+        let config_dir = crate::config::ConfigManager::get_config_dir()?;
+        Ok(Self::at(
+            config_dir.join("trash").join("sessions"),
+            retention_days,
+        ))
+    }
+
+    /// Use a trash at a specific directory
+    pub fn at(dir: PathBuf, retention_days: u64) -> Self {
+        Self {
+            dir,
+            retention: Duration::days(retention_days.min(i32::MAX as u64) as i64),
+        }
+    }
+
+    /// Whether sessions are kept at all
+    pub fn is_disabled(&self) -> bool {
+        self.retention.is_zero()
+    }
+
+    /// When the entry at `path` was deleted, from its file name
+    fn deleted_at(path: &std::path::Path) -> Option<DateTime<Utc>> {
+        let name = path.file_name()?.to_str()?;
+        let (stamp, _) = name.split_once('-')?;
+        NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT)
+            .ok()
+            .map(|t| t.and_utc())
+    }
+
+    /// Trash files, oldest first. Expired ones are removed.
+    fn entries(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let cutoff = Utc::now() - self.retention;
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            match Self::deleted_at(&path) {
+                Some(deleted_at) if deleted_at < cutoff => {
+                    if let Err(e) = std::fs::remove_file(&path) {
+                        eprintln!(
+                            "Warning: could not remove expired {}: {}",
+                            path.display(),
+                            e
+                        );
+                    }
+                }
+                Some(_) => entries.push(path),
+                None => {}
+            }
+        }
+        entries.sort();
+        Ok(entries)
+    }
+
+    /// Move `session` into the trash
+    pub fn put(&self, session: &Session) -> Result<()> {
+        self.entries()?;
+        std::fs::create_dir_all(&self.dir)?;
+        let deleted_at = Utc::now();
+        let path = self.dir.join(format!(
+            "{}-{}.json",
+            deleted_at.format(STAMP_FORMAT),
+            session.name
+        ));
+        let entry = TrashedSession {
+            deleted_at,
+            session: session.clone(),
+        };
+        std::fs::write(&path, serde_json::to_string_pretty(&entry)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Deleted sessions still in the trash, oldest first
+    pub fn list(&self) -> Result<Vec<TrashedSession>> {
+        Ok(self
+            .entries()?
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect())
+    }
+
+    /// Take the most recently deleted session named `name` out of the
+    /// trash, or None if there is none
+    pub fn take(&self, name: &str) -> Result<Option<Session>> {
+        for path in self.entries()?.iter().rev() {
+            let content = std::fs::read_to_string(path)?;
+            let Ok(entry) = serde_json::from_str::<TrashedSession>(&content) else {
+                continue;
+            };
+            if entry.session.name == name {
+                std::fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                return Ok(Some(entry.session));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(name: &str, topic: &str) -> Session {
+        Session {
+            name: name.to_string(),
+            cli: "claude".to_string(),
+            topic: topic.to_string(),
+            created_at: Utc::now(),
+            last_interaction: Utc::now(),
+            turns: vec![],
+            native: None,
+        }
+    }
+
+    #[test]
+    fn test_put_take_and_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = Trash::at(dir.path().to_path_buf(), 30);
+        trash.put(&session("auth", "first")).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        trash.put(&session("auth", "second")).unwrap();
+        trash.put(&session("db", "schema")).unwrap();
+        assert_eq!(trash.list().unwrap().len(), 3);
+
+        assert_eq!(trash.take("auth").unwrap().unwrap().topic, "second");
+        assert_eq!(trash.take("auth").unwrap().unwrap().topic, "first");
+        assert!(trash.take("auth").unwrap().is_none());
+
+        // An entry from 31 days ago is past retention
+        let old = Utc::now() - Duration::days(31);
+        let stale = dir
+            .path()
+            .join(format!("{}-old.json", old.format(STAMP_FORMAT)));
+        std::fs::write(&stale, "{}").unwrap();
+        assert_eq!(trash.list().unwrap().len(), 1);
+        assert!(!stale.exists());
+    }
+}
@@ -41,6 +41,7 @@ fn code_review_template() -> DebateTemplate {
                     "input validation".to_string(),
                     "cryptography".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -52,6 +53,7 @@ fn code_review_template() -> DebateTemplate {
                     "memory management".to_string(),
                     "profiling".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -63,6 +65,7 @@ fn code_review_template() -> DebateTemplate {
                     "documentation".to_string(),
                     "design patterns".to_string(),
                 ],
+                output_filters: vec![],
             },
         ],
         context: Some(TemplateContext {
@@ -88,6 +91,7 @@ fn architecture_decision_template() -> DebateTemplate {
                     "distributed systems".to_string(),
                     "microservices".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -99,6 +103,7 @@ fn architecture_decision_template() -> DebateTemplate {
                     "developer experience".to_string(),
                     "code organization".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -110,6 +115,7 @@ fn architecture_decision_template() -> DebateTemplate {
                     "infrastructure".to_string(),
                     "incident response".to_string(),
                 ],
+                output_filters: vec![],
             },
         ],
         context: None,
@@ -132,6 +138,7 @@ fn security_audit_template() -> DebateTemplate {
                     "security strategy".to_string(),
                     "incident response".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -143,6 +150,7 @@ fn security_audit_template() -> DebateTemplate {
                     "secure coding".to_string(),
                     "threat modeling".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -154,6 +162,7 @@ fn security_audit_template() -> DebateTemplate {
                     "audit requirements".to_string(),
                     "data protection".to_string(),
                 ],
+                output_filters: vec![],
             },
         ],
         context: Some(TemplateContext {
@@ -179,6 +188,7 @@ fn api_design_template() -> DebateTemplate {
                     "database design".to_string(),
                     "caching".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -190,6 +200,7 @@ fn api_design_template() -> DebateTemplate {
                     "error handling".to_string(),
                     "user experience".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -201,6 +212,7 @@ fn api_design_template() -> DebateTemplate {
                     "documentation".to_string(),
                     "developer experience".to_string(),
                 ],
+                output_filters: vec![],
             },
         ],
         context: Some(TemplateContext {
@@ -226,6 +238,7 @@ fn incident_postmortem_template() -> DebateTemplate {
                     "SLOs".to_string(),
                     "runbooks".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -237,6 +250,7 @@ fn incident_postmortem_template() -> DebateTemplate {
                     "testing".to_string(),
                     "code review".to_string(),
                 ],
+                output_filters: vec![],
             },
             TemplateParticipant {
                 cli: "claude".to_string(),
@@ -248,6 +262,7 @@ fn incident_postmortem_template() -> DebateTemplate {
                     "stakeholder communication".to_string(),
                     "roadmap".to_string(),
                 ],
+                output_filters: vec![],
             },
         ],
         context: Some(TemplateContext {
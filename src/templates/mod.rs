@@ -5,7 +5,12 @@
 
 mod builtin;
 
+use crate::orchestrator::postprocess::ResponseFilter;
+use crate::personas::{persona_reference, PersonaRegistry};
+use crate::plugins::PluginManager;
+use crate::utils::watch::{definition_files, namespace_of, DirFingerprint};
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -13,7 +18,7 @@ use std::path::PathBuf;
 pub use builtin::get_builtin_templates;
 
 /// A debate template with pre-configured participants and settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DebateTemplate {
     /// Unique template name
     pub name: String,
@@ -29,21 +34,28 @@ pub struct DebateTemplate {
 }
 
 /// A participant defined in a template
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TemplateParticipant {
     /// CLI to use (claude, codex, gemini, or plugin name)
     pub cli: String,
-    /// Persona name
+    /// Persona name, or `@name` to use a persona from the persona library
     pub persona: String,
-    /// Instructions for this participant
+    /// Instructions for this participant (added to the library persona's
+    /// instructions when `persona` is an `@name` reference)
+    #[serde(default)]
     pub instructions: String,
     /// Areas of expertise
     #[serde(default)]
     pub expertise: Vec<String>,
+    /// Filters applied to this participant's responses before other
+    /// participants see them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(with = "Vec<String>")]
+    pub output_filters: Vec<ResponseFilter>,
 }
 
 /// Context configuration for a template
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TemplateContext {
     /// Text to prepend to the topic
     pub prefix: Option<String>,
@@ -51,6 +63,27 @@ pub struct TemplateContext {
     pub suffix: Option<String>,
 }
 
+/// A template file (`~/.gptengage/templates/*.toml`)
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TemplateFile {
+    pub template: TemplateHeader,
+    #[serde(default)]
+    pub participants: Vec<TemplateParticipant>,
+    #[serde(default)]
+    pub context: Option<TemplateContext>,
+}
+
+/// The `[template]` table of a template file
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TemplateHeader {
+    /// Unique template name
+    pub name: String,
+    /// Human-readable description
+    pub description: String,
+    /// Default number of rounds
+    pub default_rounds: usize,
+}
+
 /// Summary information about a template
 #[derive(Debug, Clone)]
 pub struct TemplateSummary {
@@ -59,6 +92,8 @@ pub struct TemplateSummary {
     pub participant_count: usize,
     pub default_rounds: usize,
     pub is_builtin: bool,
+    /// Plugin that ships the template, if any
+    pub plugin: Option<String>,
 }
 
 /// Manages loading and accessing templates
@@ -66,11 +101,16 @@ pub struct TemplateManager {
     builtin_templates: HashMap<String, DebateTemplate>,
     user_templates: HashMap<String, DebateTemplate>,
     user_templates_dir: PathBuf,
+    /// Templates shipped by plugins, keyed by `<plugin>/<template>`
+    plugin_templates: HashMap<String, DebateTemplate>,
+    /// Snapshot of the templates directory at the last load
+    fingerprint: DirFingerprint,
 }
 
 impl TemplateManager {
-    /// Create a new TemplateManager with built-in templates and user templates
-    pub fn new() -> Result<Self> {
+    /// Create a new TemplateManager with built-in templates, user templates,
+    /// and the templates shipped by `plugins`
+    pub fn new(plugins: Option<&PluginManager>) -> Result<Self> {
         let builtin_templates = get_builtin_templates();
         let user_templates_dir = Self::get_templates_dir()?;
 
@@ -78,10 +118,13 @@ impl TemplateManager {
             builtin_templates,
             user_templates: HashMap::new(),
             user_templates_dir,
+            plugin_templates: HashMap::new(),
+            fingerprint: DirFingerprint::default(),
         };
 
         // Load user templates (non-fatal if directory doesn't exist)
         let _ = manager.load_user_templates();
+        manager.load_plugin_templates(plugins);
 
         Ok(manager)
     }
@@ -95,26 +138,28 @@ impl TemplateManager {
     /// Load user templates from the templates directory
     pub fn load_user_templates(&mut self) -> Result<()> {
         self.user_templates.clear();
+        self.fingerprint = DirFingerprint::of(&self.user_templates_dir);
 
         if !self.user_templates_dir.exists() {
             return Ok(());
         }
 
-        let entries = std::fs::read_dir(&self.user_templates_dir)
-            .context("Failed to read templates directory")?;
-
-        for entry in entries {
-            let entry = entry.context("Failed to read directory entry")?;
-            let path = entry.path();
-
-            if path.extension().map(|e| e == "toml").unwrap_or(false) {
-                match self.load_template_file(&path) {
-                    Ok(template) => {
-                        self.user_templates.insert(template.name.clone(), template);
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to load template {}: {}", path.display(), e);
+        // Templates in a namespace subdirectory (e.g. synced with `gptengage
+        // sync`) are named `<namespace>/<template>`
+        for path in definition_files(&self.user_templates_dir) {
+            match self.load_template_file(&path) {
+                Ok(mut template) => {
+                    if let Some(namespace) = namespace_of(&self.user_templates_dir, &path) {
+                        template.name = format!("{}/{}", namespace, template.name);
                     }
+                    self.user_templates.insert(template.name.clone(), template);
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Failed to load template {}: {:#}",
+                        path.display(),
+                        e
+                    );
                 }
             }
         }
@@ -122,62 +167,97 @@ impl TemplateManager {
         Ok(())
     }
 
+    /// Register templates shipped by enabled plugins
+    pub fn load_plugin_templates(&mut self, plugins: Option<&PluginManager>) {
+        self.plugin_templates.clear();
+        let Some(plugin_manager) = plugins else {
+            return;
+        };
+
+        for (name, template) in plugin_manager.plugin_templates() {
+            match Self::validate_template(&template) {
+                Ok(()) => {
+                    self.plugin_templates.insert(name, template);
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to load plugin template {}: {}", name, e);
+                }
+            }
+        }
+    }
+
+    /// Reload user templates if their directory changed since the last
+    /// load, so long-lived processes pick up edits. Plugin templates are
+    /// reloaded with `load_plugin_templates` when the plugins are.
+    ///
+    /// Returns true if a reload happened.
+    pub fn reload_if_changed(&mut self) -> Result<bool> {
+        if !self.fingerprint.changed(&self.user_templates_dir) {
+            return Ok(false);
+        }
+        self.load_user_templates()?;
+        Ok(true)
+    }
+
     /// Load a single template from a TOML file
     fn load_template_file(&self, path: &PathBuf) -> Result<DebateTemplate> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read template file: {}", path.display()))?;
 
-        // Parse the TOML wrapper
-        #[derive(Deserialize)]
-        struct TemplateWrapper {
-            template: TemplateInner,
-            #[serde(default)]
-            participants: Vec<TemplateParticipant>,
-            #[serde(default)]
-            context: Option<TemplateContext>,
-        }
-
-        #[derive(Deserialize)]
-        struct TemplateInner {
-            name: String,
-            description: String,
-            default_rounds: usize,
-        }
+        Self::parse_template(&content)
+            .with_context(|| format!("Failed to parse template file: {}", path.display()))
+    }
 
-        let wrapper: TemplateWrapper = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse template file: {}", path.display()))?;
+    /// Parse and validate template TOML
+    pub fn parse_template(content: &str) -> Result<DebateTemplate> {
+        let file: TemplateFile = toml::from_str(content)?;
 
         let template = DebateTemplate {
-            name: wrapper.template.name,
-            description: wrapper.template.description,
-            default_rounds: wrapper.template.default_rounds,
-            participants: wrapper.participants,
-            context: wrapper.context,
+            name: file.template.name,
+            description: file.template.description,
+            default_rounds: file.template.default_rounds,
+            participants: file.participants,
+            context: file.context,
         };
 
-        self.validate_template(&template)?;
+        Self::validate_template(&template)?;
 
         Ok(template)
     }
 
     /// Validate a template
-    fn validate_template(&self, template: &DebateTemplate) -> Result<()> {
-        if template.name.is_empty() {
+    fn validate_template(template: &DebateTemplate) -> Result<()> {
+        if template.name.trim().is_empty() {
             anyhow::bail!("Template name cannot be empty");
         }
 
+        if template.default_rounds == 0 {
+            anyhow::bail!("Template default_rounds must be at least 1");
+        }
+
         if template.participants.is_empty() {
             anyhow::bail!("Template must have at least one participant");
         }
 
         for (i, p) in template.participants.iter().enumerate() {
-            if p.cli.is_empty() {
+            if p.cli.trim().is_empty() {
                 anyhow::bail!("Participant {} has empty CLI", i + 1);
             }
-            if p.persona.is_empty() {
+            if p.cli
+                .contains(|c: char| c.is_whitespace() || c == ':' || c == ',')
+            {
+                anyhow::bail!(
+                    "Participant {} CLI '{}' can't contain whitespace, ':' or ','",
+                    i + 1,
+                    p.cli
+                );
+            }
+            if p.persona.trim().is_empty() {
                 anyhow::bail!("Participant {} has empty persona", i + 1);
             }
-            if p.instructions.len() < 10 {
+            // Library personas bring their own instructions
+            if persona_reference(&p.persona).is_none() && p.instructions.trim().chars().count() < 10
+            {
                 anyhow::bail!(
                     "Participant {} instructions must be at least 10 characters",
                     i + 1
@@ -188,11 +268,13 @@ impl TemplateManager {
         Ok(())
     }
 
-    /// Get a template by name (checks user templates first, then built-in)
+    /// Get a template by name (checks user templates first, then built-in,
+    /// then plugin-provided)
     pub fn get_template(&self, name: &str) -> Option<&DebateTemplate> {
         self.user_templates
             .get(name)
             .or_else(|| self.builtin_templates.get(name))
+            .or_else(|| self.plugin_templates.get(name))
     }
 
     /// List all available templates
@@ -207,6 +289,7 @@ impl TemplateManager {
                 participant_count: template.participants.len(),
                 default_rounds: template.default_rounds,
                 is_builtin: true,
+                plugin: None,
             });
         }
 
@@ -220,6 +303,22 @@ impl TemplateManager {
                 participant_count: template.participants.len(),
                 default_rounds: template.default_rounds,
                 is_builtin: false,
+                plugin: None,
+            });
+        }
+
+        // Add plugin templates (namespaced, so they never override)
+        for (name, template) in &self.plugin_templates {
+            if summaries.iter().any(|s| &s.name == name) {
+                continue;
+            }
+            summaries.push(TemplateSummary {
+                name: name.clone(),
+                description: template.description.clone(),
+                participant_count: template.participants.len(),
+                default_rounds: template.default_rounds,
+                is_builtin: false,
+                plugin: name.split('/').next().map(|p| p.to_string()),
             });
         }
 
@@ -230,22 +329,62 @@ impl TemplateManager {
     }
 }
 
+impl TemplateParticipant {
+    /// Convert to an orchestrator participant. `registry` resolves an
+    /// `@name` persona and is required for one.
+    pub fn to_participant(
+        &self,
+        registry: Option<&PersonaRegistry>,
+    ) -> Result<crate::orchestrator::Participant> {
+        let Some(name) = persona_reference(&self.persona) else {
+            let agent_def = crate::orchestrator::AgentDefinition {
+                cli: self.cli.clone(),
+                model: None,
+                persona: self.persona.clone(),
+                instructions: self.instructions.clone(),
+                expertise: self.expertise.clone(),
+                communication_style: None,
+                output_filters: self.output_filters.clone(),
+            };
+            return Ok(agent_def.to_participant());
+        };
+
+        let registry = registry
+            .ok_or_else(|| anyhow::anyhow!("Persona '@{}' needs the persona library", name))?;
+        let mut agent_def = registry.resolve(name)?.to_agent_definition(&self.cli, None);
+        if !self.instructions.trim().is_empty() {
+            agent_def.instructions = format!("{}\n{}", agent_def.instructions, self.instructions);
+        }
+        for area in &self.expertise {
+            if !agent_def.expertise.contains(area) {
+                agent_def.expertise.push(area.clone());
+            }
+        }
+        agent_def.output_filters = self.output_filters.clone();
+        Ok(agent_def.to_participant())
+    }
+}
+
 impl DebateTemplate {
-    /// Convert template participants to orchestrator participants
-    pub fn to_participants(&self) -> Vec<crate::orchestrator::Participant> {
+    /// Whether any participant uses a persona from the persona library
+    pub fn uses_persona_library(&self) -> bool {
         self.participants
             .iter()
-            .map(|p| {
-                let agent_def = crate::orchestrator::AgentDefinition {
-                    cli: p.cli.clone(),
-                    model: None,
-                    persona: p.persona.clone(),
-                    instructions: p.instructions.clone(),
-                    expertise: p.expertise.clone(),
-                    communication_style: None,
-                };
-                agent_def.to_participant()
-            })
+            .any(|p| persona_reference(&p.persona).is_some())
+    }
+
+    /// Convert template participants to orchestrator participants,
+    /// resolving `@name` personas from the persona library
+    pub fn to_participants(&self) -> Result<Vec<crate::orchestrator::Participant>> {
+        let registry = if self.uses_persona_library() {
+            Some(PersonaRegistry::new()?)
+        } else {
+            None
+        };
+
+        self.participants
+            .iter()
+            .map(|p| p.to_participant(registry.as_ref()))
             .collect()
     }
 
@@ -276,17 +415,18 @@ impl DebateTemplate {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_template_manager_has_builtin_templates() {
-        let manager = TemplateManager::new().unwrap();
+        let manager = TemplateManager::new(None).unwrap();
         let templates = manager.list_templates();
         assert!(!templates.is_empty());
     }
 
     #[test]
     fn test_get_builtin_template() {
-        let manager = TemplateManager::new().unwrap();
+        let manager = TemplateManager::new(None).unwrap();
         let template = manager.get_template("code-review");
         assert!(template.is_some());
     }
@@ -309,4 +449,82 @@ mod tests {
         assert!(result.contains("TOPIC"));
         assert!(result.contains("SUFFIX."));
     }
+
+    #[test]
+    fn test_participant_output_filters() {
+        let participant: TemplateParticipant = toml::from_str(
+            r#"
+            cli = "claude"
+            persona = "Security Reviewer"
+            instructions = "Report findings as a JSON array."
+            output_filters = ["strip-preamble", "json"]
+            "#,
+        )
+        .unwrap();
+        let agent = participant.to_participant(None).unwrap().agent_definition;
+        assert_eq!(
+            agent.unwrap().output_filters,
+            vec![ResponseFilter::StripPreamble, ResponseFilter::Json]
+        );
+
+        let invalid = toml::from_str::<TemplateParticipant>(
+            "cli = \"claude\"\npersona = \"CTO\"\noutput_filters = [\"summarize\"]",
+        );
+        assert!(invalid
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown output filter 'summarize'"));
+    }
+
+    #[test]
+    fn test_parse_template_rejects_misconfigured_participants() {
+        let template = |rounds: usize, cli: &str, instructions: &str| {
+            format!(
+                "[template]\nname = \"review\"\ndescription = \"Review\"\ndefault_rounds = {}\n\
+                 [[participants]]\ncli = \"{}\"\npersona = \"Reviewer\"\ninstructions = \"{}\"\n",
+                rounds, cli, instructions
+            )
+        };
+        let parsed =
+            TemplateManager::parse_template(&template(2, "claude", "Check the error paths."))
+                .unwrap();
+        assert_eq!(parsed.participants[0].cli, "claude");
+
+        for (content, error) in [
+            (
+                template(0, "claude", "Check the error paths."),
+                "default_rounds",
+            ),
+            (
+                template(2, "claude code", "Check the error paths."),
+                "can't contain",
+            ),
+            (
+                template(2, "claude", "          "),
+                "at least 10 characters",
+            ),
+            ("[template]\nname = \"x\"".to_string(), "missing field"),
+        ] {
+            let err = format!(
+                "{:#}",
+                TemplateManager::parse_template(&content).unwrap_err()
+            );
+            assert!(err.contains(error), "{}", err);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_template_never_panics(content in "\\PC*") {
+            let _ = TemplateManager::parse_template(&content);
+        }
+
+        #[test]
+        fn prop_truncated_template_never_panics(cut in 0usize..200) {
+            let content = "[template]\nname = \"review\"\ndescription = \"Review\"\n\
+                           default_rounds = 2\n[[participants]]\ncli = \"claude\"\n\
+                           persona = \"Reviewer\"\ninstructions = \"Check the error paths.\"\n";
+            let _ = TemplateManager::parse_template(&content[..cut.min(content.len())]);
+        }
+    }
 }
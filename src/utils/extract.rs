@@ -0,0 +1,101 @@
+//! Answer extraction for closed questions
+//!
+//! Reduces a free-form response to a short canonical answer, so scripts
+//! can branch on `$(gptengage invoke ... --extract "yes|no")`.
+
+use regex::Regex;
+
+/// How to reduce a response to an answer
+#[derive(Debug, Clone)]
+pub enum Extractor {
+    /// One of a fixed set of answers, e.g. "yes|no"
+    Choices(Vec<String>),
+    /// The first capture group (or whole match) of a regex
+    Pattern(Regex),
+}
+
+impl Extractor {
+    /// Parse a `--extract` choice list separated by '|'
+    pub fn choices(spec: &str) -> anyhow::Result<Self> {
+        let choices: Vec<String> = spec
+            .split('|')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        if choices.is_empty() {
+            anyhow::bail!("--extract needs at least one choice, e.g. \"yes|no\"");
+        }
+        Ok(Extractor::Choices(choices))
+    }
+
+    /// Compile an `--extract-regex` pattern
+    pub fn pattern(pattern: &str) -> anyhow::Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| anyhow::anyhow!("Invalid --extract-regex '{}': {}", pattern, e))?;
+        Ok(Extractor::Pattern(regex))
+    }
+
+    /// The canonical answer in `response`, if any.
+    ///
+    /// For choices, the earliest whole-word, case-insensitive mention wins
+    /// and is returned as spelled in the choice list.
+    pub fn extract(&self, response: &str) -> Option<String> {
+        match self {
+            Extractor::Choices(choices) => choices
+                .iter()
+                .filter_map(|choice| {
+                    let word = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(choice))).ok()?;
+                    word.find(response).map(|m| (m.start(), choice))
+                })
+                .min_by_key(|(start, _)| *start)
+                .map(|(_, choice)| choice.clone()),
+            Extractor::Pattern(regex) => regex.captures(response).map(|caps| {
+                caps.get(1)
+                    .or_else(|| caps.get(0))
+                    .map(|m| m.as_str().trim().to_string())
+                    .unwrap_or_default()
+            }),
+        }
+    }
+
+    /// Human-readable description for error messages
+    pub fn describe(&self) -> String {
+        match self {
+            Extractor::Choices(choices) => format!("one of {}", choices.join("|")),
+            Extractor::Pattern(regex) => format!("/{}/", regex.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_choices() {
+        let extractor = Extractor::choices("Yes|No").unwrap();
+        assert_eq!(
+            extractor.extract("**NO.** Although yes, it could work..."),
+            Some("No".to_string())
+        );
+        assert_eq!(extractor.extract("yes"), Some("Yes".to_string()));
+        assert_eq!(extractor.extract("Nothing to say"), None);
+        assert!(Extractor::choices(" | ").is_err());
+    }
+
+    #[test]
+    fn test_extract_regex() {
+        let extractor = Extractor::pattern(r"(?i)score:\s*(\d+)").unwrap();
+        assert_eq!(
+            extractor.extract("Overall SCORE: 7 out of 10"),
+            Some("7".to_string())
+        );
+        let whole = Extractor::pattern(r"v\d+\.\d+").unwrap();
+        assert_eq!(
+            whole.extract("Upgrade to v2.4 now"),
+            Some("v2.4".to_string())
+        );
+        assert_eq!(whole.extract("none"), None);
+        assert!(Extractor::pattern("(").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Interaction - Confirmations and questions for the user
+//!
+//! Every prompt gptengage shows goes through here, so it can be scripted
+//! without getting stuck waiting for input. With `--yes`, confirmations are
+//! answered yes without asking. With `--non-interactive`, in CI (a `CI`
+//! environment variable), or when stdin isn't a terminal, nothing is asked:
+//! confirmations take the safe answer and questions their default, and the
+//! caller says which flag to pass instead.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::OnceLock;
+
+/// How prompts are answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interaction {
+    /// Ask on the terminal
+    Ask,
+    /// Answer every confirmation yes (`--yes`)
+    AssumeYes,
+    /// Never ask; take the safe answer
+    NonInteractive,
+}
+
+static INTERACTION: OnceLock<Interaction> = OnceLock::new();
+
+/// Whether the `CI` environment variable marks a CI run
+fn in_ci() -> bool {
+    std::env::var("CI").is_ok_and(|ci| !matches!(ci.trim(), "" | "0" | "false"))
+}
+
+impl Interaction {
+    /// The mode for the `--yes` and `--non-interactive` flags
+    pub fn from_flags(yes: bool, non_interactive: bool) -> Self {
+        if yes {
+            Self::AssumeYes
+        } else if non_interactive || in_ci() || !std::io::stdin().is_terminal() {
+            Self::NonInteractive
+        } else {
+            Self::Ask
+        }
+    }
+}
+
+/// Install the process-wide mode. Only the first call takes effect.
+pub fn set_interaction(mode: Interaction) {
+    let _ = INTERACTION.set(mode);
+}
+
+/// The active mode (from the environment alone if none was installed)
+pub fn interaction() -> Interaction {
+    *INTERACTION.get_or_init(|| Interaction::from_flags(false, false))
+}
+
+/// Whether the user can be asked at all
+pub fn can_ask() -> bool {
+    interaction() == Interaction::Ask
+}
+
+/// Read one line from the terminal after showing `prompt` on stderr
+fn read_answer(prompt: &str) -> anyhow::Result<String> {
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// The answer to a yes/no question: `default` when blank
+fn parse_yes_no(answer: &str, default: bool) -> bool {
+    match answer.to_lowercase().as_str() {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => default,
+    }
+}
+
+/// Ask a yes/no question. `--yes` answers yes; when nothing can be asked,
+/// the answer is `default`.
+pub fn confirm(question: &str, default: bool) -> anyhow::Result<bool> {
+    match interaction() {
+        Interaction::AssumeYes => {
+            eprintln!("{} yes (--yes)", question);
+            Ok(true)
+        }
+        Interaction::NonInteractive => Ok(default),
+        Interaction::Ask => {
+            let hint = if default { "[Y/n]" } else { "[y/N]" };
+            let answer = read_answer(&format!("{} {}: ", question, hint))?;
+            Ok(parse_yes_no(&answer, default))
+        }
+    }
+}
+
+/// Ask a question with a free-form answer, or `None` when nothing can be
+/// asked (including with `--yes`, which only answers confirmations)
+pub fn ask(question: &str) -> anyhow::Result<Option<String>> {
+    if !can_ask() {
+        return Ok(None);
+    }
+    read_answer(&format!("{} ", question)).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yes_no() {
+        assert!(parse_yes_no("Y", false));
+        assert!(parse_yes_no("yes", false));
+        assert!(!parse_yes_no("n", true));
+        assert!(parse_yes_no("", true));
+        assert!(!parse_yes_no("", false));
+        assert!(!parse_yes_no("maybe", false));
+        assert_eq!(Interaction::from_flags(true, true), Interaction::AssumeYes);
+        assert_eq!(
+            Interaction::from_flags(false, true),
+            Interaction::NonInteractive
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! JSON extraction - Structured answers embedded in free-form responses
+//!
+//! CLIs asked for JSON often wrap it in prose or a markdown fence, and
+//! sometimes quote an example before the real answer. Each `{` or `[` in
+//! the response is tried as the start of a JSON value, and the first one
+//! that parses to a value the caller accepts wins, so braces in the
+//! surrounding text neither truncate nor swallow the answer.
+
+use serde_json::Value;
+
+/// The first JSON value in `text` that `accept` takes, as written in
+/// the text. The error names the parse failure of the first candidate
+/// when nothing was accepted.
+pub fn find_json<'a>(
+    text: &'a str,
+    what: &str,
+    accept: impl Fn(&Value) -> bool,
+) -> anyhow::Result<&'a str> {
+    let mut first_error = None;
+    for (start, _) in text.match_indices(['{', '[']) {
+        let rest = &text[start..];
+        let mut values = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+        match values.next() {
+            Some(Ok(value)) if accept(&value) => return Ok(&rest[..values.byte_offset()]),
+            Some(Err(e)) if first_error.is_none() => first_error = Some(e),
+            _ => {}
+        }
+    }
+    match first_error {
+        Some(e) => anyhow::bail!(
+            "Could not find a valid JSON {} in the response: {}",
+            what,
+            e
+        ),
+        None => anyhow::bail!("Could not find a JSON {} in the response", what),
+    }
+}
+
+/// The first JSON object in `text`
+pub fn extract_json_object(text: &str) -> anyhow::Result<&str> {
+    find_json(text, "object", Value::is_object)
+}
+
+/// The first JSON array of objects in `text`
+pub fn extract_json_array(text: &str) -> anyhow::Result<&str> {
+    find_json(text, "array", |value| {
+        value
+            .as_array()
+            .is_some_and(|items| items.iter().all(Value::is_object))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_skips_braces_in_surrounding_text() {
+        let text = "Format: {like this}. Example [1].\n```json\n{\"a\": [{\"b\": 1}]}\n``` {done}";
+        assert_eq!(extract_json_object(text).unwrap(), "{\"a\": [{\"b\": 1}]}");
+        assert_eq!(
+            extract_json_array("See [1] and [2]: [{\"cli\": \"claude\"}] ok").unwrap(),
+            "[{\"cli\": \"claude\"}]"
+        );
+        let err = extract_json_object("{\"a\": 1,}").unwrap_err().to_string();
+        assert!(err.contains("valid JSON object"), "{}", err);
+        assert!(extract_json_object("no json here").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_never_panics(text in "\\PC*") {
+            let _ = extract_json_object(&text);
+            let _ = extract_json_array(&text);
+        }
+
+        #[test]
+        fn prop_finds_object_in_prose(
+            prefix in "[^{\\[]*",
+            suffix in "\\PC*",
+            key in "[a-z]{1,8}",
+            value in "\\PC{0,20}",
+        ) {
+            let object = serde_json::json!({ key.clone(): value.clone() }).to_string();
+            let text = format!("{}{}{}", prefix, object, suffix);
+            let found: Value = serde_json::from_str(extract_json_object(&text).unwrap()).unwrap();
+            prop_assert_eq!(&found[&key], &Value::String(value));
+        }
+    }
+}
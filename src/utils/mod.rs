@@ -1,11 +1,24 @@
 //! Utility functions
 
 pub mod colors;
+pub mod extract;
 pub mod formatter;
+pub mod interaction;
+pub mod json;
 pub mod output_filter;
 pub mod pager;
+pub mod picker;
+pub mod prompt_preview;
+pub mod run_snapshot;
+pub mod sanitize;
+pub mod sidecar;
 pub mod stdin;
+pub mod table;
+pub mod time;
+pub mod tokens;
 pub mod tree_renderer;
+pub mod watch;
+pub mod workspace;
 
 pub use formatter::*;
 pub use output_filter::*;
@@ -9,10 +9,10 @@ use crossterm::{
 use std::io::{self, Write};
 
 /// RAII guard that restores terminal state on drop (handles panics/early returns)
-struct RawModeGuard;
+pub(crate) struct RawModeGuard;
 
 impl RawModeGuard {
-    fn enter() -> io::Result<Self> {
+    pub(crate) fn enter() -> io::Result<Self> {
         terminal::enable_raw_mode()?;
         Ok(Self)
     }
@@ -0,0 +1,275 @@
+//! Interactive fuzzy selector using crossterm
+//!
+//! The selector is drawn on stderr so that `$(gptengage session pick)`
+//! captures only the chosen value on stdout.
+
+use super::pager::RawModeGuard;
+use super::table::{display_width, pad_to_width, terminal_width, truncate_to_width};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute,
+    terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io::{self, IsTerminal, Write};
+
+/// A selectable entry
+#[derive(Debug, Clone)]
+pub struct PickItem {
+    /// Primary text, matched against the query
+    pub label: String,
+    /// Secondary text shown after the label, also matched
+    pub detail: String,
+}
+
+/// Score how well `query` fuzzy-matches `candidate` (higher is better).
+///
+/// Every query character must appear in order (case-insensitive).
+/// Consecutive matches and matches at word starts score higher; gaps
+/// between matches cost a little.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut qi = 0;
+    let mut last_match: Option<usize> = None;
+
+    for (i, c) in chars.iter().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(query[qi].to_lowercase()) {
+            continue;
+        }
+
+        score += 1;
+        let word_start = i == 0 || !chars[i - 1].is_alphanumeric();
+        if word_start {
+            score += 8;
+        }
+        match last_match {
+            Some(prev) if prev + 1 == i => score += 5,
+            Some(prev) => score -= (i - prev - 1).min(5) as i64,
+            None => score -= i.min(10) as i64,
+        }
+        last_match = Some(i);
+        qi += 1;
+    }
+
+    (qi == query.len()).then_some(score)
+}
+
+/// Indices of `items` matching `query`, best match first
+pub fn filter_items(query: &str, items: &[PickItem]) -> Vec<usize> {
+    let mut matches: Vec<(usize, i64)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            // Prefer label matches over matches that spill into the detail
+            let label = fuzzy_score(query, &item.label).map(|s| s + 100);
+            let full = fuzzy_score(query, &format!("{} {}", item.label, item.detail));
+            label.or(full).map(|s| (i, s))
+        })
+        .collect();
+    // Stable sort keeps the original order among equal scores
+    matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    matches.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Let the user pick one of `items` interactively.
+///
+/// Returns the index of the chosen item, or None if the user cancelled
+/// with Esc or Ctrl-C. Type to filter, ↑/↓ (or Ctrl-P/Ctrl-N) to move,
+/// Enter to select.
+pub fn pick(prompt: &str, items: &[PickItem]) -> anyhow::Result<Option<usize>> {
+    if !io::stderr().is_terminal() || !crate::utils::interaction::can_ask() {
+        anyhow::bail!(
+            "Interactive selection requires a terminal (and not --yes, --non-interactive, or CI)"
+        );
+    }
+
+    let mut stderr = io::stderr();
+    execute!(stderr, EnterAlternateScreen)?;
+    let guard = RawModeGuard::enter()?;
+    execute!(stderr, cursor::Hide)?;
+
+    let result = run_picker(&mut stderr, prompt, items);
+
+    execute!(stderr, cursor::Show, LeaveAlternateScreen)?;
+    drop(guard);
+    result
+}
+
+fn run_picker(
+    stderr: &mut io::Stderr,
+    prompt: &str,
+    items: &[PickItem],
+) -> anyhow::Result<Option<usize>> {
+    let mut query = String::new();
+    let mut matches = filter_items(&query, items);
+    let mut selected = 0;
+
+    loop {
+        let rows = match terminal::size()? {
+            (_, 0) => 24,
+            (_, rows) => rows as usize,
+        };
+        draw(
+            stderr,
+            prompt,
+            &query,
+            items,
+            &matches,
+            selected,
+            terminal_width(),
+            rows,
+        )?;
+
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+
+        match code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if ctrl => return Ok(None),
+            KeyCode::Enter => return Ok(matches.get(selected).copied()),
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => selected = selected.saturating_sub(1),
+            KeyCode::Down => selected = (selected + 1).min(matches.len().saturating_sub(1)),
+            KeyCode::Char('n') if ctrl => {
+                selected = (selected + 1).min(matches.len().saturating_sub(1))
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                matches = filter_items(&query, items);
+                selected = 0;
+            }
+            KeyCode::Char('u') if ctrl => {
+                query.clear();
+                matches = filter_items(&query, items);
+                selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                query.push(c);
+                matches = filter_items(&query, items);
+                selected = 0;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draw the query line, match count, and the visible window of matches
+#[allow(clippy::too_many_arguments)]
+fn draw(
+    stderr: &mut io::Stderr,
+    prompt: &str,
+    query: &str,
+    items: &[PickItem],
+    matches: &[usize],
+    selected: usize,
+    cols: usize,
+    rows: usize,
+) -> io::Result<()> {
+    execute!(
+        stderr,
+        cursor::MoveTo(0, 0),
+        terminal::Clear(ClearType::All)
+    )?;
+
+    let line = format!("{} > {}", prompt, query);
+    write!(stderr, "{}\r\n", truncate_to_width(&line, cols))?;
+    let status = format!(
+        "  {}/{}  (↑↓ move, Enter select, Esc cancel)",
+        matches.len(),
+        items.len()
+    );
+    write!(
+        stderr,
+        "\x1b[2m{}\x1b[0m\r\n",
+        truncate_to_width(&status, cols)
+    )?;
+
+    // Keep the selection in view
+    let visible = rows.saturating_sub(2).max(1);
+    let offset = selected.saturating_sub(visible - 1);
+    let label_width = items
+        .iter()
+        .map(|item| display_width(&item.label))
+        .max()
+        .unwrap_or(0)
+        .min(cols / 2);
+
+    for (pos, &idx) in matches.iter().enumerate().skip(offset).take(visible) {
+        let item = &items[idx];
+        let marker = if pos == selected { "▶ " } else { "  " };
+        let text = format!(
+            "{}{}  {}",
+            marker,
+            pad_to_width(&item.label, label_width),
+            item.detail
+        );
+        let text = truncate_to_width(&text, cols);
+        if pos == selected {
+            write!(stderr, "\x1b[7m{}\x1b[0m\r\n", text)?;
+        } else {
+            write!(stderr, "{}\r\n", text)?;
+        }
+    }
+
+    stderr.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(label: &str, detail: &str) -> PickItem {
+        PickItem {
+            label: label.to_string(),
+            detail: detail.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score_subsequence() {
+        assert!(fuzzy_score("arv", "auth-review").is_some());
+        assert!(fuzzy_score("AR", "auth-review").is_some());
+        assert!(fuzzy_score("xyz", "auth-review").is_none());
+        assert!(fuzzy_score("ra", "ar").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts_and_runs() {
+        let prefix = fuzzy_score("rev", "review-notes").unwrap();
+        let inner = fuzzy_score("rev", "prevent-bugs").unwrap();
+        let scattered = fuzzy_score("rev", "refactor-everything").unwrap();
+        assert!(prefix > inner);
+        assert!(inner > scattered);
+    }
+
+    #[test]
+    fn test_filter_items_ranks_label_matches_first() {
+        let items = vec![
+            item("perf", "claude · review of the cache"),
+            item("auth-review", "codex · JWT handling"),
+            item("docs", "gemini · README"),
+        ];
+        assert_eq!(filter_items("review", &items), vec![1, 0]);
+        assert_eq!(filter_items("", &items), vec![0, 1, 2]);
+        assert_eq!(filter_items("gemini", &items), vec![2]);
+    }
+}
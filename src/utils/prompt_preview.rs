@@ -0,0 +1,174 @@
+//! Assembled prompt previews for `--show-prompt`
+//!
+//! A preview lists the size of each component that went into a prompt
+//! (session history, context files, piped stdin, persona scaffolding, ...)
+//! followed by the exact text sent to the CLI.
+
+use super::tokens::{ApproxCounter, TokenCounter};
+use std::fmt::Write;
+
+/// Whether to preview prompts before invoking
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShowPrompt {
+    /// Invoke without a preview
+    #[default]
+    Off,
+    /// Print the preview to stderr, then invoke
+    Before,
+    /// Print the preview to stdout instead of invoking
+    Only,
+}
+
+impl ShowPrompt {
+    pub fn from_flags(show_prompt: bool, show_prompt_only: bool) -> Self {
+        if show_prompt_only {
+            ShowPrompt::Only
+        } else if show_prompt {
+            ShowPrompt::Before
+        } else {
+            ShowPrompt::Off
+        }
+    }
+
+    /// Print a rendered preview where this mode sends it
+    pub fn emit(self, preview: &str) {
+        match self {
+            ShowPrompt::Off => {}
+            ShowPrompt::Before => eprint!("{}", preview),
+            ShowPrompt::Only => print!("{}", preview),
+        }
+    }
+}
+
+/// Size breakdown of one assembled prompt
+#[derive(Debug, Clone)]
+pub struct PromptPreview {
+    title: String,
+    prompt: String,
+    parts: Vec<(String, usize)>,
+    /// Tokens in the whole prompt
+    tokens: usize,
+    /// Tokenizer that counted them, and whether the count is exact
+    tokenizer: (&'static str, bool),
+}
+
+impl PromptPreview {
+    pub fn new(title: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            prompt: prompt.into(),
+            parts: Vec::new(),
+            tokens: 0,
+            tokenizer: ("", false),
+        }
+        .counted_with(&ApproxCounter)
+    }
+
+    /// Count the prompt's tokens with `counter`, usually the one for the
+    /// CLI and model it goes to. Components get their share by size.
+    pub fn counted_with(mut self, counter: &dyn TokenCounter) -> Self {
+        self.tokens = counter.count(&self.prompt);
+        self.tokenizer = (counter.name(), counter.is_exact());
+        self
+    }
+
+    /// Record a component of the prompt by its length in bytes.
+    /// Empty components are skipped.
+    pub fn part(mut self, label: impl Into<String>, len: usize) -> Self {
+        if len > 0 {
+            self.parts.push((label.into(), len));
+        }
+        self
+    }
+
+    /// Components with any unaccounted text reported as framing
+    pub fn components(&self) -> Vec<(String, usize)> {
+        let mut parts = self.parts.clone();
+        let accounted: usize = parts.iter().map(|(_, len)| len).sum();
+        let framing = self.prompt.len().saturating_sub(accounted);
+        if framing > 0 {
+            parts.push(("framing and separators".to_string(), framing));
+        }
+        parts
+    }
+
+    /// Render the breakdown followed by the full prompt text
+    pub fn render(&self) -> anyhow::Result<String> {
+        let mut out = String::new();
+        let components = self.components();
+        let width = components
+            .iter()
+            .map(|(label, _)| label.len())
+            .max()
+            .unwrap_or(0)
+            .max("total".len());
+
+        writeln!(out, "=== Prompt: {} ===", self.title)?;
+        for (label, len) in &components {
+            // A component's share of the tokens is only ever approximate
+            let tokens = (self.tokens * len).div_ceil(self.prompt.len().max(1));
+            writeln!(
+                out,
+                "  {:<width$}  {:>8} bytes  ~{} tokens",
+                label,
+                len,
+                tokens,
+                width = width
+            )?;
+        }
+        let (tokenizer, exact) = self.tokenizer;
+        writeln!(
+            out,
+            "  {:<width$}  {:>8} bytes  {}{} tokens ({})",
+            "total",
+            self.prompt.len(),
+            if exact { "" } else { "~" },
+            self.tokens,
+            tokenizer,
+            width = width
+        )?;
+        writeln!(out, "--- prompt ---")?;
+        writeln!(out, "{}", self.prompt)?;
+        writeln!(out, "--- end prompt ---")?;
+        writeln!(out)?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components_report_framing() {
+        let prompt = "[CTX]\nhello\n[/CTX]\n\nquestion";
+        let preview = PromptPreview::new("claude", prompt)
+            .part("context", "hello".len())
+            .part("request", "question".len())
+            .part("empty", 0);
+        let components = preview.components();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[2].0, "framing and separators");
+        let total: usize = components.iter().map(|(_, len)| len).sum();
+        assert_eq!(total, prompt.len());
+    }
+
+    #[test]
+    fn test_render_includes_prompt_and_sizes() {
+        let rendered = PromptPreview::new("codex", "abcdefgh")
+            .part("request", 8)
+            .render()
+            .unwrap();
+        assert!(rendered.starts_with("=== Prompt: codex ==="));
+        assert!(rendered.contains("request"));
+        assert!(rendered.contains("~2 tokens (estimate)"));
+        assert!(rendered.contains("--- prompt ---\nabcdefgh\n--- end prompt ---"));
+    }
+
+    #[test]
+    fn test_show_prompt_from_flags() {
+        assert_eq!(ShowPrompt::from_flags(false, false), ShowPrompt::Off);
+        assert_eq!(ShowPrompt::from_flags(true, false), ShowPrompt::Before);
+        assert_eq!(ShowPrompt::from_flags(true, true), ShowPrompt::Only);
+    }
+}
@@ -0,0 +1,573 @@
+//! Run snapshots - Roll back what a `--write` run changed
+//!
+//! With `--snapshot`, the working directory is saved before each
+//! write-mode run and `gptengage rollback <id>` puts it back. Inside a git
+//! repository the snapshot is a tree written from a scratch index (tracked
+//! and untracked files, minus ignored ones) and kept by a
+//! `refs/gptengage/snapshots/<id>` ref, so the repository's index, stash,
+//! and branches are left alone. Elsewhere the files are copied into
+//! ~/.gptengage/snapshots/<id>/, skipping version control and build
+//! directories. Only the newest snapshots are kept.
+
+use super::stdin::STDIN_FILE_PREFIX;
+use super::workspace::{WorkspaceChanges, WorkspaceSnapshot};
+use crate::audit::ProcessAudit;
+use crate::config::ConfigManager;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Snapshots kept; older ones are deleted when a new one is taken
+const MAX_SNAPSHOTS: usize = 20;
+
+/// Where a snapshot's files are kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Storage {
+    /// A tree object in the repository at the root
+    Git { tree: String },
+    /// A copy in the snapshot's directory
+    Copy,
+}
+
+/// The working directory as it was before a write-mode run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// Directory the run wrote to
+    pub root: PathBuf,
+    /// What the run was ("debate: ...", "invoke claude", ...)
+    pub label: String,
+    #[serde(flatten)]
+    pub storage: Storage,
+    /// Files the run created, recorded when it finished. A rollback
+    /// removes only these, never files created by hand afterwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_created: Option<Vec<String>>,
+}
+
+/// Directory holding snapshot metadata and copies
+pub fn snapshots_dir() -> Result<PathBuf> {
+    Ok(ConfigManager::get_config_dir()?.join("snapshots"))
+}
+
+/// Run git in `root`, optionally against a scratch index and with input,
+/// returning stdout
+fn git(root: &Path, index: Option<&Path>, args: &[&str], input: Option<&[u8]>) -> Result<String> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(index) = index {
+        command.env("GIT_INDEX_FILE", index);
+    }
+    let audit = ProcessAudit::start(&command);
+    let mut child = command.spawn().context("Failed to run git")?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether `root` is inside a git working tree
+pub fn in_git_worktree(root: &Path) -> bool {
+    git(root, None, &["rev-parse", "--is-inside-work-tree"], None)
+        .is_ok_and(|out| out.trim() == "true")
+}
+
+/// Pathspec leaving out piped input saved by `--stdin-as file`
+fn stdin_file_exclude() -> String {
+    format!(":(exclude,glob)**/{}*", STDIN_FILE_PREFIX)
+}
+
+fn snapshot_ref(id: &str) -> String {
+    format!("refs/gptengage/snapshots/{}", id)
+}
+
+/// A scratch index file, removed when dropped
+struct ScratchIndex(PathBuf);
+
+impl Drop for ScratchIndex {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Copy `from` to `to`, creating parent directories
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(from, to)
+        .with_context(|| format!("Failed to copy {} to {}", from.display(), to.display()))?;
+    Ok(())
+}
+
+/// Remove a file the run created, then any directories it leaves empty
+fn remove_created(root: &Path, path: &str) -> Result<()> {
+    let file = root.join(path);
+    std::fs::remove_file(&file).with_context(|| format!("Failed to remove {}", file.display()))?;
+    let mut dir = file.parent();
+    while let Some(parent) = dir.filter(|d| *d != root) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
+
+/// Split NUL-separated git output
+fn nul_separated(output: &str) -> impl Iterator<Item = &str> {
+    output.split('\0').filter(|s| !s.is_empty())
+}
+
+impl RunSnapshot {
+    /// Snapshot `root`, storing metadata (and any copy) under `dir`
+    pub fn take(root: &Path, dir: &Path, label: &str) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let created_at = Utc::now();
+        let id = format!(
+            "{}-{:04x}",
+            created_at.format("%Y%m%d-%H%M%S"),
+            (std::process::id() ^ created_at.timestamp_subsec_nanos()) & 0xffff
+        );
+        let storage = if in_git_worktree(root) {
+            let index = ScratchIndex(dir.join(format!("{}.index", id)));
+            git(
+                root,
+                Some(&index.0),
+                &["add", "-A", "--", ".", &stdin_file_exclude()],
+                None,
+            )?;
+            let tree = git(root, Some(&index.0), &["write-tree"], None)?
+                .trim()
+                .to_string();
+            git(root, None, &["update-ref", &snapshot_ref(&id), &tree], None)?;
+            Storage::Git { tree }
+        } else {
+            let files = WorkspaceSnapshot::capture(root)?;
+            let copy = dir.join(&id);
+            for path in files.paths() {
+                let source = root.join(path);
+                // Running from $HOME would otherwise copy earlier snapshots
+                if source.starts_with(dir) {
+                    continue;
+                }
+                copy_file(&source, &copy.join(path))?;
+            }
+            Storage::Copy
+        };
+        let snapshot = Self {
+            id,
+            created_at,
+            root: root.to_path_buf(),
+            label: label.to_string(),
+            storage,
+            run_created: None,
+        };
+        snapshot.save(dir)?;
+        Ok(snapshot)
+    }
+
+    /// Write the snapshot's metadata
+    fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::write(
+            dir.join(format!("{}.json", self.id)),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Record the files the run created once it has finished
+    pub fn record_created(&mut self, dir: &Path, created: &[String]) -> Result<()> {
+        self.run_created = Some(created.to_vec());
+        self.save(dir)
+    }
+
+    /// Load a snapshot by id
+    pub fn load(dir: &Path, id: &str) -> Result<Self> {
+        let path = dir.join(format!("{}.json", id));
+        if id.contains(['/', '\\']) || !path.exists() {
+            anyhow::bail!(
+                "No snapshot '{}'. Run 'gptengage rollback --list' to see snapshots.",
+                id
+            );
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid snapshot {}", id))
+    }
+
+    /// All snapshots, newest first
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Ok(Vec::new());
+        };
+        let mut snapshots: Vec<Self> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let content = std::fs::read_to_string(entry.path()).ok()?;
+                serde_json::from_str(&content).ok()
+            })
+            .collect();
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        Ok(snapshots)
+    }
+
+    /// Delete the snapshot's ref or copy, and its metadata
+    pub fn delete(&self, dir: &Path) -> Result<()> {
+        match &self.storage {
+            Storage::Git { .. } => {
+                // The repository may be gone; the metadata still goes
+                let _ = git(
+                    &self.root,
+                    None,
+                    &["update-ref", "-d", &snapshot_ref(&self.id)],
+                    None,
+                );
+            }
+            Storage::Copy => {
+                let copy = dir.join(&self.id);
+                if copy.exists() {
+                    std::fs::remove_dir_all(&copy)?;
+                }
+            }
+        }
+        std::fs::remove_file(dir.join(format!("{}.json", self.id)))?;
+        Ok(())
+    }
+
+    /// Changes made since the snapshot: what a rollback undoes
+    pub fn changes(&self, dir: &Path) -> Result<WorkspaceChanges> {
+        if !self.root.is_dir() {
+            anyhow::bail!("{} no longer exists", self.root.display());
+        }
+        match &self.storage {
+            Storage::Git { tree } => {
+                let index = ScratchIndex(dir.join(format!("{}.index", self.id)));
+                git(&self.root, Some(&index.0), &["read-tree", tree], None)?;
+                // Record stat data so unchanged files aren't reported; exits
+                // non-zero when files differ, which is expected
+                let _ = git(
+                    &self.root,
+                    Some(&index.0),
+                    &["update-index", "-q", "--refresh"],
+                    None,
+                );
+                let mut changes = WorkspaceChanges::default();
+                let diff = git(
+                    &self.root,
+                    Some(&index.0),
+                    &["diff-files", "-z", "--relative", "--name-status"],
+                    None,
+                )?;
+                let mut fields = nul_separated(&diff);
+                while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+                    match status {
+                        "D" => changes.deleted.push(path.to_string()),
+                        _ => changes.modified.push(path.to_string()),
+                    }
+                }
+                let others = git(
+                    &self.root,
+                    Some(&index.0),
+                    &[
+                        "ls-files",
+                        "-z",
+                        "--others",
+                        "--exclude-standard",
+                        "--",
+                        ".",
+                        &stdin_file_exclude(),
+                    ],
+                    None,
+                )?;
+                changes.created = nul_separated(&others).map(String::from).collect();
+                for paths in [
+                    &mut changes.created,
+                    &mut changes.modified,
+                    &mut changes.deleted,
+                ] {
+                    paths.sort();
+                }
+                Ok(changes)
+            }
+            Storage::Copy => {
+                let before = WorkspaceSnapshot::capture(&dir.join(&self.id))?;
+                let now = WorkspaceSnapshot::capture(&self.root)?;
+                let mut changes = before.diff(&now);
+                let dir_in_root = dir.strip_prefix(&self.root).ok();
+                if let Some(inside) = dir_in_root {
+                    let inside = inside.to_string_lossy().replace('\\', "/");
+                    changes.created.retain(|path| !path.starts_with(&inside));
+                }
+                Ok(changes)
+            }
+        }
+    }
+
+    /// Changes made since the snapshot as a patch for `git apply`, with
+    /// paths relative to the root. Only git snapshots can make one.
+    pub fn patch(&self, dir: &Path) -> Result<String> {
+        let Storage::Git { tree } = &self.storage else {
+            anyhow::bail!(
+                "Snapshot {} is a copy; patches need a git repository",
+                self.id
+            );
+        };
+        let index = ScratchIndex(dir.join(format!("{}.index", self.id)));
+        git(&self.root, Some(&index.0), &["read-tree", tree], None)?;
+        git(
+            &self.root,
+            Some(&index.0),
+            &["add", "-A", "--", ".", &stdin_file_exclude()],
+            None,
+        )?;
+        git(
+            &self.root,
+            Some(&index.0),
+            &[
+                "diff",
+                "--cached",
+                "--binary",
+                "--no-color",
+                "--no-ext-diff",
+                "--relative",
+                tree,
+            ],
+            None,
+        )
+    }
+
+    /// What a rollback would undo: the changes since the snapshot, but of
+    /// the files created since, only those the run itself created
+    pub fn plan(&self, dir: &Path) -> Result<WorkspaceChanges> {
+        let mut changes = self.changes(dir)?;
+        let run_created = self.run_created.as_deref().unwrap_or_default();
+        changes.created.retain(|path| run_created.contains(path));
+        Ok(changes)
+    }
+
+    /// Put the working directory back as it was, returning what was undone
+    pub fn restore(&self, dir: &Path) -> Result<WorkspaceChanges> {
+        let changes = self.plan(dir)?;
+        let restored: Vec<&String> = changes.modified.iter().chain(&changes.deleted).collect();
+        match &self.storage {
+            Storage::Git { tree } => {
+                if !restored.is_empty() {
+                    let index = ScratchIndex(dir.join(format!("{}.index", self.id)));
+                    git(&self.root, Some(&index.0), &["read-tree", tree], None)?;
+                    let mut paths = Vec::new();
+                    for path in &restored {
+                        paths.extend_from_slice(path.as_bytes());
+                        paths.push(0);
+                    }
+                    git(
+                        &self.root,
+                        Some(&index.0),
+                        &["checkout-index", "-f", "-z", "--stdin"],
+                        Some(&paths),
+                    )?;
+                }
+            }
+            Storage::Copy => {
+                let copy = dir.join(&self.id);
+                for path in &restored {
+                    copy_file(&copy.join(path), &self.root.join(path))?;
+                }
+            }
+        }
+        for path in &changes.created {
+            remove_created(&self.root, path)?;
+        }
+        Ok(changes)
+    }
+}
+
+/// Whether `--snapshot` was given
+static SNAPSHOT_RUNS: OnceLock<bool> = OnceLock::new();
+
+/// Install the `--snapshot` flag (once, at startup)
+pub fn set_snapshot_runs(enabled: bool) {
+    let _ = SNAPSHOT_RUNS.set(enabled);
+}
+
+/// Take a snapshot of the current directory before a write-mode run when
+/// `--snapshot` was given. Failing to snapshot fails the run: the user
+/// asked for a way back.
+pub fn snapshot_run(label: &str) -> Result<Option<RunSnapshot>> {
+    if !SNAPSHOT_RUNS.get().copied().unwrap_or(false) {
+        return Ok(None);
+    }
+    let dir = snapshots_dir()?;
+    let snapshot = RunSnapshot::take(&std::env::current_dir()?, &dir, label)
+        .context("Failed to snapshot the workspace (--snapshot)")?;
+    eprintln!(
+        "Snapshot {} taken; undo this run with: gptengage rollback {}",
+        snapshot.id, snapshot.id
+    );
+    for old in RunSnapshot::list(&dir)?.iter().skip(MAX_SNAPSHOTS) {
+        if let Err(e) = old.delete(&dir) {
+            eprintln!("Warning: could not delete snapshot {}: {}", old.id, e);
+        }
+    }
+    Ok(Some(snapshot))
+}
+
+/// Record what a snapshotted run created, so `rollback` removes only
+/// those files. Failing to record only warns.
+pub fn record_run(run: Option<RunSnapshot>, changes: Option<&WorkspaceChanges>) {
+    let (Some(mut run), Some(changes)) = (run, changes) else {
+        return;
+    };
+    let recorded = snapshots_dir().and_then(|dir| run.record_created(&dir, &changes.created));
+    if let Err(e) = recorded {
+        eprintln!(
+            "Warning: could not record the files this run created; \
+             rolling back snapshot {} won't remove them: {}",
+            run.id, e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Make changes a write-mode run might, roll them back, and check the
+    /// tree is as it was
+    fn check_round_trip(root: &Path, dir: &Path) -> Storage {
+        write(root, "src/lib.rs", "fn a() {}");
+        write(root, "notes.txt", "keep me");
+        let snapshot = RunSnapshot::take(root, dir, "test run").unwrap();
+
+        write(root, "src/lib.rs", "fn b() {}");
+        std::fs::remove_file(root.join("notes.txt")).unwrap();
+        write(root, "src/gen/new.rs", "new");
+        let mut snapshot = snapshot;
+        snapshot
+            .record_created(dir, &["src/gen/new.rs".to_string()])
+            .unwrap();
+        // Created by hand after the run
+        write(root, "mine.txt", "by hand");
+
+        let loaded = RunSnapshot::load(dir, &snapshot.id).unwrap();
+        assert_eq!(
+            loaded.plan(dir).unwrap(),
+            WorkspaceChanges {
+                created: vec!["src/gen/new.rs".to_string()],
+                modified: vec!["src/lib.rs".to_string()],
+                deleted: vec!["notes.txt".to_string()],
+            }
+        );
+        loaded.restore(dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("mine.txt")).unwrap(),
+            "by hand"
+        );
+        std::fs::remove_file(root.join("mine.txt")).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "fn a() {}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("notes.txt")).unwrap(),
+            "keep me"
+        );
+        assert!(!root.join("src/gen").exists());
+        assert!(loaded.changes(dir).unwrap().is_empty());
+
+        assert_eq!(RunSnapshot::list(dir).unwrap().len(), 1);
+        loaded.delete(dir).unwrap();
+        assert!(RunSnapshot::list(dir).unwrap().is_empty());
+        assert!(RunSnapshot::load(dir, &snapshot.id).is_err());
+        loaded.storage
+    }
+
+    #[test]
+    fn test_copy_snapshot_round_trip() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let storage = check_round_trip(root.path(), dir.path());
+        assert_eq!(storage, Storage::Copy);
+    }
+
+    #[test]
+    fn test_git_snapshot_round_trip() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        if git(root.path(), None, &["init", "-q"], None).is_err() {
+            return; // git not installed
+        }
+        write(root.path(), ".gitignore", "build/\n");
+        write(root.path(), "build/out.bin", "ignored");
+        let stdin_file = format!("{}1.txt", STDIN_FILE_PREFIX);
+        write(root.path(), &stdin_file, "piped");
+        let storage = check_round_trip(root.path(), dir.path());
+        assert!(matches!(storage, Storage::Git { .. }));
+        // Ignored files and saved piped input are neither snapshotted nor
+        // removed
+        assert!(root.path().join("build/out.bin").exists());
+        assert!(root.path().join(&stdin_file).exists());
+        // The repository's own index is untouched
+        assert!(!root.path().join(".git/index").exists());
+    }
+
+    #[test]
+    fn test_git_snapshot_patch_applies() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        if git(root.path(), None, &["init", "-q"], None).is_err() {
+            return; // git not installed
+        }
+        write(root.path(), "src/lib.rs", "fn a() {}\n");
+        let mut snapshot = RunSnapshot::take(root.path(), dir.path(), "test run").unwrap();
+        write(root.path(), "src/lib.rs", "fn b() {}\n");
+        write(root.path(), "src/new.rs", "new\n");
+        snapshot
+            .record_created(dir.path(), &["src/new.rs".to_string()])
+            .unwrap();
+
+        let patch = snapshot.patch(dir.path()).unwrap();
+        assert!(patch.contains("-fn a() {}\n+fn b() {}"));
+        assert!(patch.contains("+++ b/src/new.rs"));
+
+        snapshot.restore(dir.path()).unwrap();
+        git(root.path(), None, &["apply"], Some(patch.as_bytes())).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.path().join("src/lib.rs")).unwrap(),
+            "fn b() {}\n"
+        );
+        assert!(root.path().join("src/new.rs").exists());
+        snapshot.delete(dir.path()).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Terminal sanitization - Plain text from output meant for a terminal
+//!
+//! Some CLIs draw colors, progress spinners, and status lines even when
+//! their output is piped. A terminal hides this by overwriting; stored as
+//! text it becomes escape garbage that later prompts (synthesis, session
+//! history) would feed straight back to a model. `sanitize` keeps what a
+//! terminal would finally have shown.
+
+/// Longest line treated as a spinner status line ("⠋ Thinking...")
+const MAX_STATUS_CHARS: usize = 80;
+
+/// Remove ANSI escape sequences: CSI (colors, cursor movement, erasing),
+/// OSC (window titles, hyperlinks), and two-character escapes
+pub fn strip_escapes(text: &str) -> String {
+    if !text.contains('\x1b') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC (e.g. window title): up to BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Charset selection (ESC ( B) names one more character
+            Some('(') | Some(')') => {
+                chars.next();
+            }
+            // Two-character sequences (ESC 7, ESC =, ...)
+            Some(_) | None => {}
+        }
+    }
+    out
+}
+
+/// Spinner frames: braille dots and quarter circles
+fn is_spinner_glyph(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2800}'..='\u{28FF}' | '◐' | '◓' | '◑' | '◒' | '◴' | '◷' | '◶' | '◵'
+    )
+}
+
+/// A line left behind by a spinner: only spinner frames, or a frame
+/// followed by a short status ending in an ellipsis
+fn is_spinner_line(line: &str) -> bool {
+    let line = line.trim();
+    let Some(first) = line.chars().next() else {
+        return false;
+    };
+    if !is_spinner_glyph(first) {
+        return false;
+    }
+    line.chars()
+        .all(|c| is_spinner_glyph(c) || c.is_whitespace())
+        || (line.chars().count() <= MAX_STATUS_CHARS
+            && (line.ends_with("...") || line.ends_with('…')))
+}
+
+/// What a terminal would show for one line: each carriage return starts the
+/// line over, backspaces erase, and other control characters are invisible
+fn render_line(line: &str) -> String {
+    // A trailing \r (or \r\r) rewrites nothing, so the last text drawn stays
+    let drawn = line
+        .rsplit('\r')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default();
+    let mut out = String::with_capacity(drawn.len());
+    for c in drawn.chars() {
+        match c {
+            '\x08' => {
+                out.pop();
+            }
+            '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Plain text from terminal output: escape sequences removed, overwritten
+/// text resolved, and spinner lines dropped. Line endings are kept.
+pub fn sanitize(text: &str) -> String {
+    let text = strip_escapes(text);
+    if !text
+        .chars()
+        .any(|c| (c.is_control() && c != '\n' && c != '\t') || is_spinner_glyph(c))
+    {
+        return text;
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let (body, newline) = match line.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (line, ""),
+        };
+        let rendered = render_line(body);
+        if is_spinner_line(&rendered) {
+            continue;
+        }
+        out.push_str(&rendered);
+        out.push_str(newline);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_escapes() {
+        assert_eq!(
+            strip_escapes("\x1b[1;32mOK\x1b[0m done\x1b[2K\x1b[1G"),
+            "OK done"
+        );
+        assert_eq!(
+            strip_escapes("\x1b]0;title\x07text\x1b]8;;http://x\x1b\\link"),
+            "textlink"
+        );
+        assert_eq!(strip_escapes("\x1b(Bplain\x1b7 ünïcode"), "plain ünïcode");
+    }
+
+    #[test]
+    fn test_sanitize_resolves_overwrites_and_spinners() {
+        let output = "⠋ Thinking...\r⠙ Thinking...\r\x1b[2K\rAnswer: 42\n\
+                      ⠹⠸\n\
+                      Progress 10%\rProgress 100%\n\
+                      typo\x08\x08po\x07\n\
+                      - keep markdown\n---\n";
+        assert_eq!(
+            sanitize(output),
+            "Answer: 42\nProgress 100%\ntypo\n- keep markdown\n---\n"
+        );
+        assert_eq!(sanitize("⠋ Loading…\nDone"), "Done");
+        // Braille in prose is left alone
+        assert_eq!(
+            sanitize("The letter ⠁ is a in braille.\n"),
+            "The letter ⠁ is a in braille.\n"
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Sidecar - Watching a debate's participants in tmux panes
+//!
+//! With `--sidecar`, a debate run inside tmux opens a pane per participant
+//! and one for the synthesis. Each pane follows a file the debate appends
+//! to as responses arrive, so parallel responses can be watched side by
+//! side without a full TUI. iTerm2 shows the panes natively when tmux runs
+//! with `tmux -CC`. The panes stay open for reading after the debate.
+
+use crate::audit::ProcessAudit;
+use crate::orchestrator::Synthesis;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Title of the synthesis pane
+const SYNTHESIS: &str = "synthesis";
+
+/// Files behind a debate's sidecar panes
+#[derive(Debug)]
+pub struct Sidecar {
+    /// One per participant, in participant order
+    participants: Vec<PathBuf>,
+    synthesis: PathBuf,
+}
+
+/// Run `tmux` with `args`, returning its trimmed stdout
+fn tmux(args: &[&str]) -> anyhow::Result<String> {
+    let mut command = Command::new("tmux");
+    command.args(args);
+    let audit = ProcessAudit::start(&command);
+    let output = command
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run tmux: {}", e))?;
+    audit.finish(&output.status);
+    if !output.status.success() {
+        anyhow::bail!(
+            "tmux {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `path` quoted for the shell command a pane runs
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', r"'\''"))
+}
+
+/// Append `text` to `path`. The panes are a convenience, so a failed
+/// write is only reported.
+fn append(path: &Path, text: &str) {
+    let written = std::fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(text.as_bytes()));
+    if let Err(e) = written {
+        eprintln!(
+            "Warning: could not update sidecar pane {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+impl Sidecar {
+    /// Open a pane for each of `names` and one for the synthesis, next to
+    /// the current tmux pane
+    pub fn open(names: &[String]) -> anyhow::Result<Self> {
+        if std::env::var_os("TMUX").is_none() {
+            anyhow::bail!(
+                "--sidecar opens tmux panes, so run the debate inside tmux (in iTerm2, `tmux -CC` shows them as native panes)"
+            );
+        }
+        // A fresh, private directory: responses can hold anything the
+        // participants saw. The panes keep reading it after we exit.
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("gptengage-sidecar-");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            builder.permissions(std::fs::Permissions::from_mode(0o700));
+        }
+        let dir = builder
+            .tempdir()
+            .map_err(|e| anyhow::anyhow!("Failed to create the sidecar directory: {}", e))?
+            .keep();
+
+        let titles = names.iter().map(String::as_str).chain([SYNTHESIS]);
+        let mut files = Vec::new();
+        for (i, title) in titles.enumerate() {
+            let path = dir.join(format!("{:02}.txt", i + 1));
+            let waiting = if title == SYNTHESIS {
+                "Waiting for the debate to finish..."
+            } else {
+                "Waiting for round 1..."
+            };
+            std::fs::write(&path, format!("== {} ==\n\n{}\n", title, waiting))
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+
+            let follow = format!("tail -n +1 -f {}", shell_quote(&path));
+            let pane = tmux(&["split-window", "-d", "-P", "-F", "#{pane_id}", &follow])?;
+            tmux(&["select-pane", "-t", &pane, "-T", title])?;
+            // Retile after every split so there's room for the next one
+            tmux(&["select-layout", "tiled"])?;
+            files.push(path);
+        }
+        let synthesis = files.pop().unwrap_or_default();
+        Ok(Self {
+            participants: files,
+            synthesis,
+        })
+    }
+
+    /// Add a participant's response to its pane
+    pub fn response(&self, participant: usize, round: usize, response: &str) {
+        if let Some(path) = self.participants.get(participant) {
+            append(
+                path,
+                &format!("\n--- Round {} ---\n{}\n", round, response.trim_end()),
+            );
+        }
+    }
+
+    /// Show the synthesis in its pane
+    pub fn synthesis(&self, synthesis: &Synthesis) {
+        let mut text = format!("\nSummary:\n  {}\n", synthesis.summary);
+        for (heading, points) in [
+            ("Consensus", &synthesis.consensus_points),
+            ("Disagreements", &synthesis.disagreement_points),
+            ("Key Insights", &synthesis.key_insights),
+        ] {
+            if !points.is_empty() {
+                text.push_str(&format!("\n{}:\n", heading));
+                for point in points {
+                    text.push_str(&format!("  • {}\n", point));
+                }
+            }
+        }
+        if let Some(recommendation) = &synthesis.recommendation {
+            text.push_str(&format!("\nRecommendation:\n  {}\n", recommendation));
+        }
+        append(&self.synthesis, &text);
+    }
+
+    /// Mark every pane as done
+    pub fn finish(&self) {
+        for path in self.participants.iter().chain([&self.synthesis]) {
+            append(path, "\n[debate finished]\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(
+            shell_quote(Path::new("/tmp/it's here/01.txt")),
+            r"'/tmp/it'\''s here/01.txt'"
+        );
+    }
+}
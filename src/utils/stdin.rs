@@ -1,6 +1,14 @@
 //! Stdin handling utilities for Unix-style piping
 
 use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+
+/// Name prefix of files written by `--stdin-as file`. Workspace change
+/// tracking and snapshots skip them.
+pub const STDIN_FILE_PREFIX: &str = ".gptengage-stdin-";
+
+/// Largest piped input `--stdin-as file` saves
+pub const MAX_STDIN_FILE_BYTES: usize = 20 * 1024 * 1024;
 
 /// Read from stdin if input is piped (not an interactive terminal).
 ///
@@ -26,6 +34,8 @@ pub fn read_stdin_if_piped() -> Option<String> {
         return None;
     }
 
+    // Piped command output may carry colors and progress redraws
+    let buffer = super::sanitize::sanitize(&buffer.replace("\r\n", "\n"));
     let trimmed = buffer.trim();
     if trimmed.is_empty() {
         None
@@ -39,7 +49,68 @@ pub fn read_stdin_if_piped() -> Option<String> {
 /// Wraps the content in `[PIPED CONTEXT]` markers to clearly delineate
 /// piped input from the main topic or prompt.
 pub fn format_piped_context(content: &str) -> String {
-    format!("[PIPED CONTEXT]\n{}\n[/PIPED CONTEXT]", content)
+    format_context_block("PIPED CONTEXT", content)
+}
+
+/// Wrap content in `[LABEL]` ... `[/LABEL]` markers
+pub fn format_context_block(label: &str, content: &str) -> String {
+    format!("[{}]\n{}\n[/{}]", label, content, label)
+}
+
+/// Piped input saved to a file for the CLIs to read instead of inline
+/// text, removed when dropped.
+///
+/// The file goes in the current directory: CLIs restricted to the
+/// workspace (gemini's `--include-directories .`) can't read temp dirs.
+pub struct StdinFile {
+    path: PathBuf,
+    /// Path as given to the CLIs, relative to the working directory
+    name: String,
+    bytes: usize,
+    lines: usize,
+}
+
+impl StdinFile {
+    /// Save `content` in `dir`
+    pub fn create_in(dir: &Path, content: &str) -> anyhow::Result<Self> {
+        if content.len() > MAX_STDIN_FILE_BYTES {
+            anyhow::bail!(
+                "Piped input is {:.1} MB; --stdin-as file accepts up to {} MB",
+                content.len() as f64 / (1024.0 * 1024.0),
+                MAX_STDIN_FILE_BYTES / (1024 * 1024)
+            );
+        }
+        let file_name = format!("{}{}.txt", STDIN_FILE_PREFIX, std::process::id());
+        let path = dir.join(&file_name);
+        std::fs::write(&path, content)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+        Ok(Self {
+            path,
+            name: format!("./{}", file_name),
+            bytes: content.len(),
+            lines: content.lines().count(),
+        })
+    }
+
+    /// Save `content` in the current directory
+    pub fn create(content: &str) -> anyhow::Result<Self> {
+        Self::create_in(&std::env::current_dir()?, content)
+    }
+
+    /// Prompt block pointing the CLIs at the file
+    pub fn reference(&self) -> String {
+        format_piped_context(&format!(
+            "The piped input ({} bytes, {} lines) is saved in the file {}. \
+             Read it from there; it is not repeated here.",
+            self.bytes, self.lines, self.name
+        ))
+    }
+}
+
+impl Drop for StdinFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
 }
 
 #[cfg(test)]
@@ -54,4 +125,26 @@ mod tests {
         assert!(formatted.contains("some code here"));
         assert!(formatted.contains("[/PIPED CONTEXT]"));
     }
+
+    #[test]
+    fn test_stdin_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StdinFile::create_in(dir.path(), "line one\nline two\n").unwrap();
+        let path = dir
+            .path()
+            .join(format!("{}{}.txt", STDIN_FILE_PREFIX, std::process::id()));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "line one\nline two\n"
+        );
+        let reference = file.reference();
+        assert!(reference.starts_with("[PIPED CONTEXT]"));
+        assert!(reference.contains("(18 bytes, 2 lines)"));
+        assert!(reference.contains(&format!("./{}", STDIN_FILE_PREFIX)));
+        drop(file);
+        assert!(!path.exists());
+
+        let huge = "x".repeat(MAX_STDIN_FILE_BYTES + 1);
+        assert!(StdinFile::create_in(dir.path(), &huge).is_err());
+    }
 }
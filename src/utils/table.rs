@@ -0,0 +1,218 @@
+//! Width-aware tables and boxes for terminal output
+//!
+//! Widths are measured in terminal columns (not bytes or chars), so wide
+//! CJK characters and emoji line up and long values wrap or truncate
+//! instead of breaking the box borders.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Fallback when the terminal size can't be determined
+pub const DEFAULT_TERM_WIDTH: usize = 80;
+
+/// Current terminal width in columns
+pub fn terminal_width() -> usize {
+    crossterm::terminal::size()
+        .map(|(w, _)| w as usize)
+        .ok()
+        .filter(|w| *w > 0)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(DEFAULT_TERM_WIDTH)
+}
+
+/// Replace tabs and control characters so widths are predictable
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '\t' => vec![' '; 4],
+            c if c.is_control() => vec![],
+            c => vec![c],
+        })
+        .collect()
+}
+
+/// Width of a string in terminal columns
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(sanitize(text).as_str())
+}
+
+/// Truncate to at most `width` columns, ending with "…" when cut
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    let text = sanitize(text);
+    if UnicodeWidthStr::width(text.as_str()) <= width {
+        return text;
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width - 1 {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+/// Pad with spaces to exactly `width` columns (truncating if longer)
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let mut out = truncate_to_width(text, width);
+    let used = UnicodeWidthStr::width(out.as_str());
+    out.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+    out
+}
+
+/// Word-wrap a single line to `width` columns, hard-breaking words that
+/// don't fit on a line of their own
+pub fn wrap_to_width(text: &str, width: usize) -> Vec<String> {
+    let text = sanitize(text);
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+
+    for word in text.split_whitespace() {
+        let word_width = UnicodeWidthStr::width(word);
+        let needed = if current.is_empty() {
+            word_width
+        } else {
+            current_width + 1 + word_width
+        };
+
+        if needed <= width {
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+            current_width = needed;
+            continue;
+        }
+
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+
+        // Hard-break words wider than a full line
+        for c in word.chars() {
+            let w = c.width().unwrap_or(0);
+            if current_width + w > width && !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            current.push(c);
+            current_width += w;
+        }
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Render a boxed table that fits in `max_width` columns.
+///
+/// Columns listed in `shrinkable` (in order) give up width, down to
+/// `min_width` columns each, when the table is too wide; their values are
+/// truncated with "…".
+pub fn render_table(
+    headers: &[&str],
+    rows: &[Vec<String>],
+    max_width: usize,
+    shrinkable: &[usize],
+    min_width: usize,
+) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| display_width(h)).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate().take(widths.len()) {
+            widths[i] = widths[i].max(display_width(cell));
+        }
+    }
+
+    // Borders and padding: "│ " + " │ " between columns + " │"
+    let overhead = 3 * widths.len() + 1;
+    let mut total = widths.iter().sum::<usize>() + overhead;
+    for &col in shrinkable {
+        if total <= max_width {
+            break;
+        }
+        let floor = min_width.min(widths[col]);
+        let cut = (total - max_width).min(widths[col] - floor);
+        widths[col] -= cut;
+        total -= cut;
+    }
+
+    let border = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{}{}{}\n", left, segments.join(mid), right)
+    };
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, w)| pad_to_width(cells.get(i).map(String::as_str).unwrap_or(""), *w))
+            .collect();
+        format!("│ {} │\n", padded.join(" │ "))
+    };
+
+    let mut out = border("┌", "┬", "┐");
+    let header_cells: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    out.push_str(&line(&header_cells));
+    out.push_str(&border("├", "┼", "┤"));
+    for row in rows {
+        out.push_str(&line(row));
+    }
+    out.push_str(&border("└", "┴", "┘"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width_counts_columns() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("日本"), 4);
+        assert_eq!(display_width("a\tb"), 6);
+    }
+
+    #[test]
+    fn test_truncate_and_pad() {
+        assert_eq!(truncate_to_width("hello world", 6), "hello…");
+        assert_eq!(truncate_to_width("日本語", 5), "日本…");
+        assert_eq!(pad_to_width("日本", 6), "日本  ");
+        assert_eq!(display_width(&pad_to_width("日本語テキスト", 5)), 5);
+    }
+
+    #[test]
+    fn test_wrap_to_width() {
+        assert_eq!(
+            wrap_to_width("hello world foo", 11),
+            vec!["hello world", "foo"]
+        );
+        assert_eq!(wrap_to_width("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert_eq!(wrap_to_width("日本語です", 4), vec!["日本", "語で", "す"]);
+        assert_eq!(wrap_to_width("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_render_table_fits_width() {
+        let rows = vec![vec![
+            "a-very-long-session-name".to_string(),
+            "claude".to_string(),
+            "A topic that is much too long to fit in a narrow terminal".to_string(),
+        ]];
+        let table = render_table(&["Session", "CLI", "Topic"], &rows, 40, &[2, 0], 8);
+        for line in table.lines() {
+            assert_eq!(display_width(line), 40, "line: {}", line);
+        }
+        assert!(table.contains("…"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Time display shared by session lists, status, transcripts, and the audit log
+//!
+//! The display is configured once per process from the `time_format`
+//! config key and the `--absolute-times` flag.
+
+use chrono::{DateTime, Local, Utc};
+use std::sync::OnceLock;
+
+/// How timestamps are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// "5m ago" in lists; UTC timestamps where an exact time is shown
+    Relative,
+    /// Absolute timestamps in UTC
+    Utc,
+    /// Absolute timestamps in the local time zone
+    Local,
+    /// A strftime pattern (e.g. "%d/%m/%Y %H:%M"), in local time
+    Custom(String),
+}
+
+impl TimeFormat {
+    /// Parse a `time_format` config value
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "relative" => Ok(TimeFormat::Relative),
+            "utc" => Ok(TimeFormat::Utc),
+            "local" => Ok(TimeFormat::Local),
+            pattern if pattern.contains('%') => {
+                // Reject patterns chrono can't format instead of panicking later
+                use std::fmt::Write;
+                let mut probe = String::new();
+                write!(probe, "{}", Utc::now().format(pattern))
+                    .map_err(|_| anyhow::anyhow!("Invalid time_format pattern '{}'", pattern))?;
+                Ok(TimeFormat::Custom(pattern.to_string()))
+            }
+            other => Err(anyhow::anyhow!(
+                "Invalid time_format '{}'. Expected relative, utc, local, or a strftime pattern",
+                other
+            )),
+        }
+    }
+}
+
+/// Process-wide time display settings
+#[derive(Debug, Clone)]
+pub struct TimeDisplay {
+    pub format: TimeFormat,
+    /// Show absolute timestamps even where relative times are the default
+    pub absolute: bool,
+}
+
+impl Default for TimeDisplay {
+    fn default() -> Self {
+        Self {
+            format: TimeFormat::Relative,
+            absolute: false,
+        }
+    }
+}
+
+impl TimeDisplay {
+    /// Time for lists and summaries ("5m ago" unless absolute times apply)
+    pub fn when(&self, time: DateTime<Utc>) -> String {
+        if self.format == TimeFormat::Relative && !self.absolute {
+            format_time_ago(time, Utc::now())
+        } else {
+            self.timestamp(time)
+        }
+    }
+
+    /// Exact timestamp for transcripts and details
+    pub fn timestamp(&self, time: DateTime<Utc>) -> String {
+        match &self.format {
+            TimeFormat::Relative | TimeFormat::Utc => {
+                time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+            }
+            TimeFormat::Local => time
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S %:z")
+                .to_string(),
+            TimeFormat::Custom(pattern) => time.with_timezone(&Local).format(pattern).to_string(),
+        }
+    }
+}
+
+/// Compact relative time, e.g. "42s ago", "5m ago", "3h ago", "2d ago"
+pub fn format_time_ago(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let duration = now.signed_duration_since(time);
+
+    if duration.num_seconds() < 0 {
+        "just now".to_string()
+    } else if duration.num_seconds() < 60 {
+        format!("{}s ago", duration.num_seconds())
+    } else if duration.num_minutes() < 60 {
+        format!("{}m ago", duration.num_minutes())
+    } else if duration.num_hours() < 24 {
+        format!("{}h ago", duration.num_hours())
+    } else {
+        format!("{}d ago", duration.num_days())
+    }
+}
+
+/// Duration as "1h 5m", "3m 12s", or "8s"
+pub fn format_duration(ms: u64) -> String {
+    let seconds = ms / 1000;
+    match seconds {
+        s if s >= 3600 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s if s >= 60 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// Start of a period given as an age ("30m", "24h", "7d", "2w") or a
+/// date ("2026-01-05", midnight UTC)
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> anyhow::Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid period '{}'. Use an age like 30m, 24h, 7d, 2w, or a date like 2026-01-05",
+            value
+        )
+    };
+    let (split, _) = value.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let age = match unit {
+        "m" => chrono::Duration::minutes(amount),
+        "h" => chrono::Duration::hours(amount),
+        "d" => chrono::Duration::days(amount),
+        "w" => chrono::Duration::weeks(amount),
+        _ => return Err(invalid()),
+    };
+    Ok(now - age)
+}
+
+static TIME_DISPLAY: OnceLock<TimeDisplay> = OnceLock::new();
+
+/// Install the process-wide time display. Only the first call takes effect.
+pub fn set_time_display(display: TimeDisplay) {
+    let _ = TIME_DISPLAY.set(display);
+}
+
+/// Get the active time display (defaults if none was installed)
+pub fn time_display() -> &'static TimeDisplay {
+    TIME_DISPLAY.get_or_init(TimeDisplay::default)
+}
+
+/// Format a time for lists using the process-wide display
+pub fn format_when(time: DateTime<Utc>) -> String {
+    time_display().when(time)
+}
+
+/// Format an exact timestamp using the process-wide display
+pub fn format_timestamp(time: DateTime<Utc>) -> String {
+    time_display().timestamp(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(8_500), "8s");
+        assert_eq!(format_duration(192_000), "3m 12s");
+        assert_eq!(format_duration(3_900_000), "1h 5m");
+    }
+
+    #[test]
+    fn test_format_time_ago() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+        assert_eq!(format_time_ago(now - Duration::seconds(5), now), "5s ago");
+        assert_eq!(format_time_ago(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(format_time_ago(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(format_time_ago(now - Duration::days(2), now), "2d ago");
+        assert_eq!(format_time_ago(now + Duration::minutes(1), now), "just now");
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+        assert_eq!(parse_since("7d", now).unwrap(), now - Duration::days(7));
+        assert_eq!(parse_since("24h", now).unwrap(), now - Duration::hours(24));
+        assert_eq!(parse_since("2w", now).unwrap(), now - Duration::weeks(2));
+        assert_eq!(
+            parse_since("2026-01-05", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap()
+        );
+        assert!(parse_since("7", now).is_err());
+        assert!(parse_since("d", now).is_err());
+        assert!(parse_since("", now).is_err());
+    }
+
+    #[test]
+    fn test_parse_time_format() {
+        assert_eq!(TimeFormat::parse("utc").unwrap(), TimeFormat::Utc);
+        assert_eq!(
+            TimeFormat::parse("%d/%m/%Y").unwrap(),
+            TimeFormat::Custom("%d/%m/%Y".to_string())
+        );
+        assert!(TimeFormat::parse("iso").is_err());
+        assert!(TimeFormat::parse("%Q").is_err());
+    }
+
+    #[test]
+    fn test_absolute_display() {
+        let time = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+        let display = TimeDisplay {
+            format: TimeFormat::Relative,
+            absolute: true,
+        };
+        assert_eq!(display.when(time), "2026-01-10 12:00:00 UTC");
+
+        let display = TimeDisplay {
+            format: TimeFormat::Utc,
+            absolute: false,
+        };
+        assert_eq!(display.when(time), "2026-01-10 12:00:00 UTC");
+        assert!(TimeDisplay::default().when(time).ends_with("ago"));
+    }
+}
@@ -0,0 +1,154 @@
+//! Token counting - How many tokens a prompt costs a given model
+//!
+//! Counts go through a [`TokenCounter`] picked for the CLI and model.
+//! Builds with the `tiktoken` feature count OpenAI models (codex, `gpt-*`,
+//! `o*`) exactly with their BPE tokenizer and estimate other families with
+//! `cl100k_base`, whose vocabulary is close to theirs. Without it, every
+//! family gets a character-class estimate.
+
+/// Counts the tokens in a text for one model family
+pub trait TokenCounter: Send + Sync {
+    /// Tokens in `text`
+    fn count(&self, text: &str) -> usize;
+
+    /// Tokenizer name shown with counts
+    fn name(&self) -> &'static str;
+
+    /// Whether counts are exact for the model, rather than estimates
+    fn is_exact(&self) -> bool;
+}
+
+/// Estimate from character classes: runs of ASCII letters and digits at
+/// about four characters a token, every other non-space character
+/// (punctuation, CJK, emoji) about a token each
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproxCounter;
+
+impl TokenCounter for ApproxCounter {
+    fn count(&self, text: &str) -> usize {
+        let mut tokens = 0;
+        let mut run: usize = 0;
+        for c in text.chars() {
+            if c.is_ascii_alphanumeric() {
+                run += 1;
+                continue;
+            }
+            tokens += run.div_ceil(4);
+            run = 0;
+            if !c.is_whitespace() {
+                tokens += 1;
+            }
+        }
+        tokens + run.div_ceil(4)
+    }
+
+    fn name(&self) -> &'static str {
+        "estimate"
+    }
+
+    fn is_exact(&self) -> bool {
+        false
+    }
+}
+
+/// A tiktoken BPE tokenizer
+#[cfg(feature = "tiktoken")]
+pub struct BpeCounter {
+    bpe: &'static tiktoken_rs::CoreBPE,
+    name: &'static str,
+    exact: bool,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TokenCounter for BpeCounter {
+    fn count(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_exact(&self) -> bool {
+        self.exact
+    }
+}
+
+/// Whether `cli` with `model` is an OpenAI model
+#[cfg_attr(not(feature = "tiktoken"), allow(dead_code))]
+fn is_openai(cli: &str, model: Option<&str>) -> bool {
+    match model {
+        Some(model) => {
+            let model = model.to_lowercase();
+            model.starts_with("gpt-")
+                || model.starts_with("chatgpt-")
+                || (model.starts_with('o') && model[1..].starts_with(|c: char| c.is_ascii_digit()))
+        }
+        None => cli == "codex",
+    }
+}
+
+/// The counter for a CLI and the model it is asked for
+#[cfg(feature = "tiktoken")]
+pub fn counter_for(cli: &str, model: Option<&str>) -> Box<dyn TokenCounter> {
+    use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+
+    if is_openai(cli, model) {
+        // Older models still use cl100k_base
+        let tokenizer = model.and_then(get_tokenizer);
+        if tokenizer == Some(Tokenizer::Cl100kBase) {
+            return Box::new(BpeCounter {
+                bpe: tiktoken_rs::cl100k_base_singleton(),
+                name: "cl100k_base",
+                exact: true,
+            });
+        }
+        return Box::new(BpeCounter {
+            bpe: tiktoken_rs::o200k_base_singleton(),
+            name: "o200k_base",
+            exact: true,
+        });
+    }
+    Box::new(BpeCounter {
+        bpe: tiktoken_rs::cl100k_base_singleton(),
+        name: "cl100k_base",
+        exact: false,
+    })
+}
+
+/// The counter for a CLI and the model it is asked for
+#[cfg(not(feature = "tiktoken"))]
+pub fn counter_for(_cli: &str, _model: Option<&str>) -> Box<dyn TokenCounter> {
+    Box::new(ApproxCounter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approx_counter() {
+        let counter = ApproxCounter;
+        assert_eq!(counter.count(""), 0);
+        assert_eq!(counter.count("hello world"), 4);
+        assert_eq!(counter.count("fn main() {}"), 6);
+        assert_eq!(counter.count("日本語"), 3);
+        assert!(!counter.is_exact());
+
+        assert!(is_openai("codex", None));
+        assert!(is_openai("claude", Some("gpt-4o")));
+        assert!(is_openai("plugin", Some("o3-mini")));
+        assert!(!is_openai("gemini", Some("gemini-2.5-pro")));
+        assert!(!is_openai("ollama", Some("orca")));
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn test_bpe_counter() {
+        let counter = counter_for("codex", None);
+        assert_eq!(counter.name(), "o200k_base");
+        assert!(counter.is_exact());
+        assert_eq!(counter.count("hello world"), 2);
+        assert!(!counter_for("claude", None).is_exact());
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::orchestrator::ideation::{IdeaNode, IdeationResult};
 use crate::utils::colors::*;
+use crate::utils::table::display_width;
 use crossterm::style::Attribute;
 use std::io::Write;
 
@@ -206,7 +207,7 @@ impl TreeRenderer {
     ) -> std::io::Result<()> {
         // Pad prefix to align with text start (after "X  " in header)
         let text_indent = format!("{}     ", prefix);
-        let available_width = self.term_width.saturating_sub(text_indent.len());
+        let available_width = self.term_width.saturating_sub(display_width(&text_indent));
 
         if available_width < 10 {
             // Terminal too narrow for wrapping, just output as-is
@@ -326,7 +327,7 @@ fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
         if current_line.is_empty() {
             // First word on the line — always add it even if it exceeds width
             current_line.push_str(word);
-        } else if current_line.len() + 1 + word.len() <= max_width {
+        } else if display_width(&current_line) + 1 + display_width(word) <= max_width {
             current_line.push(' ');
             current_line.push_str(word);
         } else {
@@ -0,0 +1,109 @@
+//! Directory change detection for reloading definitions
+//!
+//! Long-lived processes take a fingerprint of a definitions directory
+//! after loading it and compare it later to decide whether to reload.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// `.toml` files in a definitions directory and its namespace
+/// subdirectories (`<dir>/<namespace>/*.toml`), top-level files first
+pub fn definition_files(dir: &Path) -> Vec<PathBuf> {
+    let mut top = Vec::new();
+    let mut nested = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return top;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Ok(inner) = std::fs::read_dir(&path) {
+                nested.extend(inner.flatten().map(|e| e.path()).filter(|p| is_toml(p)));
+            }
+        } else if is_toml(&path) {
+            top.push(path);
+        }
+    }
+    top.sort();
+    nested.sort();
+    top.extend(nested);
+    top
+}
+
+/// Namespace of a file found by [`definition_files`], if it is nested
+pub fn namespace_of(dir: &Path, path: &Path) -> Option<String> {
+    let parent = path.parent()?;
+    if parent == dir {
+        return None;
+    }
+    parent
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "toml")
+}
+
+/// Snapshot of the `.toml` files in a directory and its namespace
+/// subdirectories (path, mtime, size)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirFingerprint(Vec<(PathBuf, Option<SystemTime>, u64)>);
+
+impl DirFingerprint {
+    /// Fingerprint a directory; a missing directory yields an empty snapshot
+    pub fn of(dir: &Path) -> Self {
+        let mut files = Vec::new();
+        for path in definition_files(dir) {
+            if let Ok(meta) = std::fs::metadata(&path) {
+                files.push((path, meta.modified().ok(), meta.len()));
+            }
+        }
+        files.sort();
+        Self(files)
+    }
+
+    /// Whether the directory differs from this snapshot
+    pub fn changed(&self, dir: &Path) -> bool {
+        *self != Self::of(dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let before = DirFingerprint::of(dir.path());
+        assert!(!before.changed(dir.path()));
+
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+        assert!(!before.changed(dir.path()));
+
+        std::fs::write(dir.path().join("a.toml"), "x = 1").unwrap();
+        assert!(before.changed(dir.path()));
+
+        let after = DirFingerprint::of(dir.path());
+        std::fs::write(dir.path().join("a.toml"), "x = 12").unwrap();
+        assert!(after.changed(dir.path()));
+    }
+
+    #[test]
+    fn test_definition_files_include_namespaces() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("acme")).unwrap();
+        std::fs::write(dir.path().join("acme/b.toml"), "").unwrap();
+        std::fs::write(dir.path().join("z.toml"), "").unwrap();
+        std::fs::write(dir.path().join("acme/notes.md"), "").unwrap();
+
+        let files = definition_files(dir.path());
+        assert_eq!(
+            files,
+            vec![dir.path().join("z.toml"), dir.path().join("acme/b.toml")]
+        );
+        assert_eq!(namespace_of(dir.path(), &files[0]), None);
+        assert_eq!(namespace_of(dir.path(), &files[1]).as_deref(), Some("acme"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Workspace changes - What `--write` runs actually changed
+//!
+//! Before a write-mode run the working directory is snapshotted (size,
+//! modification time, and content hash of every file), and afterwards the
+//! snapshot is compared with the tree to list the files the CLIs created,
+//! modified, or deleted. Version control and build directories are skipped.
+
+use super::stdin::STDIN_FILE_PREFIX;
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Directories never snapshotted
+const SKIP_DIRS: &[&str] = &[".git", ".hg", ".svn", "target", "node_modules"];
+
+/// Files larger than this are compared by size and modification time only
+const MAX_HASH_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Snapshots stop (and no report is made) past this many files
+const MAX_FILES: usize = 50_000;
+
+#[derive(Debug, Clone, PartialEq)]
+struct FileState {
+    size: u64,
+    modified: Option<SystemTime>,
+    hash: Option<[u8; 32]>,
+}
+
+/// State of every file under a directory at one point in time
+#[derive(Debug, Clone)]
+pub struct WorkspaceSnapshot {
+    root: PathBuf,
+    /// Keyed by path relative to the root, with `/` separators
+    files: BTreeMap<String, FileState>,
+}
+
+/// Files created, modified, and deleted between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceChanges {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub created: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modified: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deleted: Vec<String>,
+}
+
+fn hash_file(path: &Path) -> Option<[u8; 32]> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hasher.finalize().into())
+}
+
+impl WorkspaceSnapshot {
+    /// Snapshot every file under `root`
+    pub fn capture(root: &Path) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        let mut pending = vec![root.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let entries = std::fs::read_dir(&dir)
+                .with_context(|| format!("Failed to read {}", dir.display()))?;
+            for entry in entries.flatten() {
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let path = entry.path();
+                if file_type.is_dir() {
+                    let name = entry.file_name();
+                    if !SKIP_DIRS.iter().any(|skip| name == *skip) {
+                        pending.push(path);
+                    }
+                    continue;
+                }
+                // Piped input saved by `--stdin-as file` comes and goes with the run
+                if !file_type.is_file()
+                    || entry
+                        .file_name()
+                        .to_string_lossy()
+                        .starts_with(STDIN_FILE_PREFIX)
+                {
+                    continue;
+                }
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if files.len() >= MAX_FILES {
+                    anyhow::bail!(
+                        "{} has more than {} files; not tracking changes",
+                        root.display(),
+                        MAX_FILES
+                    );
+                }
+                let relative = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let hash = if metadata.len() <= MAX_HASH_BYTES {
+                    hash_file(&path)
+                } else {
+                    None
+                };
+                files.insert(
+                    relative,
+                    FileState {
+                        size: metadata.len(),
+                        modified: metadata.modified().ok(),
+                        hash,
+                    },
+                );
+            }
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            files,
+        })
+    }
+
+    /// Snapshot the current directory
+    pub fn capture_current_dir() -> Result<Self> {
+        Self::capture(&std::env::current_dir()?)
+    }
+
+    /// Changes from `self` to `after`. Files are modified when their
+    /// content hash differs, or, for files too large to hash, their size
+    /// or modification time.
+    pub fn diff(&self, after: &WorkspaceSnapshot) -> WorkspaceChanges {
+        let mut changes = WorkspaceChanges::default();
+        for (path, state) in &after.files {
+            match self.files.get(path) {
+                None => changes.created.push(path.clone()),
+                Some(before) => {
+                    let changed = match (before.hash, state.hash) {
+                        (Some(a), Some(b)) => a != b,
+                        _ => before != state,
+                    };
+                    if changed {
+                        changes.modified.push(path.clone());
+                    }
+                }
+            }
+        }
+        changes.deleted = self
+            .files
+            .keys()
+            .filter(|path| !after.files.contains_key(*path))
+            .cloned()
+            .collect();
+        changes
+    }
+
+    /// Paths of the snapshotted files, relative to the root
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Changes made since this snapshot was taken
+    pub fn changes(&self) -> Result<WorkspaceChanges> {
+        Ok(self.diff(&Self::capture(&self.root)?))
+    }
+}
+
+impl WorkspaceChanges {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+
+    /// One line per changed file, marked `A` (created), `M`, or `D`
+    pub fn render(&self) -> Result<String> {
+        let mut out = String::new();
+        if self.is_empty() {
+            writeln!(out, "No files were changed.")?;
+            return Ok(out);
+        }
+        for (mark, paths) in [
+            ("A", &self.created),
+            ("M", &self.modified),
+            ("D", &self.deleted),
+        ] {
+            for path in paths {
+                writeln!(out, "  {} {}", mark, path)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Start tracking workspace changes for a write-mode run. Failing to
+/// snapshot only warns, and the run goes ahead untracked.
+pub fn track_changes() -> Option<WorkspaceSnapshot> {
+    match WorkspaceSnapshot::capture_current_dir() {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            eprintln!("Warning: not tracking workspace changes: {}", e);
+            None
+        }
+    }
+}
+
+/// Changes since `snapshot`, warning instead of failing
+pub fn tracked_changes(snapshot: Option<&WorkspaceSnapshot>) -> Option<WorkspaceChanges> {
+    match snapshot?.changes() {
+        Ok(changes) => Some(changes),
+        Err(e) => {
+            eprintln!("Warning: could not compare workspace snapshots: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("README.md"), "readme").unwrap();
+        std::fs::write(root.join("old.txt"), "old").unwrap();
+        std::fs::write(root.join("same.txt"), "same").unwrap();
+        let before = WorkspaceSnapshot::capture(root).unwrap();
+
+        std::fs::write(root.join("src/lib.rs"), "fn b() {}").unwrap();
+        std::fs::write(root.join("src/new.rs"), "new").unwrap();
+        std::fs::remove_file(root.join("old.txt")).unwrap();
+        // Rewritten with the same content: not a change
+        std::fs::write(root.join("same.txt"), "same").unwrap();
+        std::fs::write(root.join(".git/index"), "ignored").unwrap();
+
+        let changes = before.changes().unwrap();
+        assert_eq!(
+            changes,
+            WorkspaceChanges {
+                created: vec!["src/new.rs".to_string()],
+                modified: vec!["src/lib.rs".to_string()],
+                deleted: vec!["old.txt".to_string()],
+            }
+        );
+        assert_eq!(
+            changes.render().unwrap(),
+            "  A src/new.rs\n  M src/lib.rs\n  D old.txt\n"
+        );
+        assert!(before.diff(&before).is_empty());
+    }
+}
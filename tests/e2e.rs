@@ -0,0 +1,276 @@
+//! End-to-end tests - The gptengage binary driving fake CLIs
+
+mod support;
+
+use support::{Fake, Harness};
+
+#[test]
+fn test_invoke_prints_response() {
+    let harness = Harness::new();
+    harness.install("claude", Fake::Reply("Use a B-tree.".to_string()));
+
+    let run = harness.run(&["invoke", "claude", "Which index?"]);
+    run.assert_success();
+    assert!(run.stdout().contains("Use a B-tree."));
+    assert_eq!(harness.prompts("claude"), vec!["Which index?"]);
+}
+
+#[test]
+fn test_invoke_reports_failure_and_timeout() {
+    let harness = Harness::new();
+    harness.install("codex", Fake::Fail("codex boom".to_string(), 3));
+    harness.install("gemini", Fake::Hang);
+
+    let run = harness.run(&["invoke", "codex", "hi"]);
+    run.assert_failure();
+    assert!(run.stderr().contains("codex boom"), "{}", run.stderr());
+
+    let started = std::time::Instant::now();
+    let run = harness.run(&["invoke", "gemini", "hi", "--timeout", "1"]);
+    run.assert_failure();
+    assert!(run.stderr().contains("timed out"), "{}", run.stderr());
+    assert!(started.elapsed() < std::time::Duration::from_secs(15));
+}
+
+#[test]
+fn test_timeout_and_rounds_default_to_config() {
+    let harness = Harness::new();
+    harness.install("claude", Fake::Echo);
+    harness.install("gemini", Fake::Hang);
+    std::fs::create_dir_all(harness.gptengage_home()).unwrap();
+    std::fs::write(
+        harness.gptengage_home().join("config.json"),
+        r#"{"default_timeout": 1, "default_debate_rounds": 1}"#,
+    )
+    .unwrap();
+
+    // Without --timeout, default_timeout applies
+    let started = std::time::Instant::now();
+    let run = harness.run(&["invoke", "gemini", "hi"]);
+    run.assert_failure();
+    assert!(
+        run.stderr().contains("timed out after 1 seconds"),
+        "{}",
+        run.stderr()
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(15));
+
+    // The flag wins over the config
+    let run = harness.run(&["invoke", "gemini", "hi", "--timeout", "2"]);
+    run.assert_failure();
+    assert!(
+        run.stderr().contains("timed out after 2 seconds"),
+        "{}",
+        run.stderr()
+    );
+
+    // Likewise default_debate_rounds without --rounds
+    let debate = ["debate", "Tabs or spaces", "--participants", "claude"];
+    let run = harness.run(&[&debate[..], &["--output", "json"]].concat());
+    run.assert_success();
+    assert_eq!(run.json()["rounds"].as_array().unwrap().len(), 1);
+    let run = harness.run(&[&debate[..], &["--output", "json", "--rounds", "2"]].concat());
+    run.assert_success();
+    assert_eq!(run.json()["rounds"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_debate_skips_missing_and_failing_participants() {
+    let harness = Harness::new();
+    harness.install("claude", Fake::Echo);
+    harness.install("gemini", Fake::Fail("quota exceeded".to_string(), 1));
+    // codex is not installed
+
+    let run = harness.run(&[
+        "debate",
+        "Tabs or spaces",
+        "--rounds",
+        "2",
+        "--timeout",
+        "5",
+        "--output",
+        "json",
+    ]);
+    run.assert_success();
+    let result = run.json();
+    let rounds = result["rounds"].as_array().unwrap();
+    assert_eq!(rounds.len(), 2);
+    for round in rounds {
+        let clis: Vec<&str> = round
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|response| response["cli"].as_str().unwrap())
+            .collect();
+        assert_eq!(clis, vec!["claude"]);
+    }
+    assert!(run.stderr().contains("codex is not available, skipping"));
+    assert!(run.stderr().contains("quota exceeded"), "{}", run.stderr());
+
+    // The second round shows claude its own first-round response
+    let prompts = harness.prompts("claude");
+    assert_eq!(prompts.len(), 2);
+    assert!(prompts[0].contains("Tabs or spaces"));
+    assert!(prompts[1].contains("claude heard:"));
+}
+
+#[test]
+fn test_debate_parses_synthesis() {
+    let harness = Harness::with_default_clis();
+    harness.install(
+        "codex",
+        Fake::Reply(
+            "Here is the synthesis:\n```json\n{\"summary\": \"Spaces win\", \
+             \"consensus_points\": [\"Be consistent\"], \"recommendation\": \"Use spaces\"}\n```"
+                .to_string(),
+        ),
+    );
+
+    let run = harness.run(&[
+        "debate",
+        "Tabs or spaces",
+        "--participants",
+        "claude,gemini",
+        "--rounds",
+        "1",
+        "--synthesize",
+        "--synthesizer",
+        "codex",
+        "--output",
+        "json",
+    ]);
+    run.assert_success();
+    let result = run.json();
+    assert_eq!(result["rounds"][0].as_array().unwrap().len(), 2);
+    assert_eq!(result["synthesis"]["summary"], "Spaces win");
+    assert_eq!(result["synthesis"]["consensus_points"][0], "Be consistent");
+    assert_eq!(result["synthesis"]["recommendation"], "Use spaces");
+    // The synthesizer saw both participants' responses
+    let prompt = &harness.prompts("codex")[0];
+    assert!(prompt.contains("claude heard:") && prompt.contains("gemini heard:"));
+}
+
+#[test]
+fn test_session_history_is_replayed() {
+    let harness = Harness::new();
+    harness.install("claude", Fake::Echo);
+
+    harness
+        .run(&["invoke", "claude", "Plan the migration", "--session", "db"])
+        .assert_success();
+    harness
+        .run(&["invoke", "claude", "What next?", "--session", "db"])
+        .assert_success();
+
+    let prompts = harness.prompts("claude");
+    assert_eq!(prompts.len(), 2);
+    assert!(prompts[1].contains("Plan the migration"));
+    assert!(prompts[1].contains("What next?"));
+    assert!(harness.gptengage_home().join("sessions").exists());
+
+    let run = harness.run(&["session", "list"]);
+    run.assert_success();
+    assert!(run.stdout().contains("db"));
+
+    harness.run(&["session", "end", "db"]).assert_success();
+    let run = harness.run(&["session", "show", "db"]);
+    run.assert_failure();
+}
+
+#[test]
+fn test_plugin_invocation() {
+    let harness = Harness::new();
+    let command = harness.install("parrot", Fake::Echo);
+    harness.install_plugin(
+        "parrot",
+        &format!(
+            "[plugin]\n\
+             name = \"parrot\"\n\
+             description = \"Repeats the prompt\"\n\
+             command = \"{}\"\n\
+             [invoke]\n\
+             base_args = []\n\
+             prompt_mode = \"arg_last\"\n\
+             [access]\n\
+             [detection]\n\
+             check_command = \"{}\"\n",
+            command.display(),
+            command.display()
+        ),
+    );
+
+    // Untrusted plugin commands are refused in non-interactive runs
+    let run = harness.run(&["invoke", "parrot", "Polly"]);
+    run.assert_failure();
+    assert!(harness.prompts("parrot").is_empty());
+
+    let run = harness.run(&["--trust", "invoke", "parrot", "Polly"]);
+    run.assert_success();
+    assert!(
+        run.stdout().contains("parrot heard: Polly"),
+        "{}",
+        run.stdout()
+    );
+    let config = std::fs::read_to_string(harness.gptengage_home().join("config.json")).unwrap();
+    assert!(config.contains(&command.display().to_string()));
+
+    // Once trusted, it runs without --trust
+    harness.run(&["invoke", "parrot", "Again"]).assert_success();
+}
+
+#[test]
+fn test_plugin_disable_enable_and_remove() {
+    let harness = Harness::new();
+    let command = harness.install("parrot", Fake::Echo);
+    harness.install_plugin(
+        "parrot",
+        &format!(
+            "[plugin]\n\
+             name = \"parrot\"\n\
+             description = \"Repeats the prompt\"\n\
+             command = \"{}\"\n\
+             [invoke]\n\
+             base_args = []\n\
+             prompt_mode = \"arg_last\"\n\
+             [access]\n\
+             [detection]\n\
+             check_command = \"{}\"\n",
+            command.display(),
+            command.display()
+        ),
+    );
+    let plugins = harness.gptengage_home().join("plugins");
+
+    harness
+        .run(&["plugin", "disable", "parrot"])
+        .assert_success();
+    let run = harness.run(&["plugin", "list"]);
+    run.assert_success();
+    assert!(run
+        .stdout()
+        .contains("parrot (Repeats the prompt) [disabled]"));
+
+    harness
+        .run(&["plugin", "enable", "parrot"])
+        .assert_success();
+    let run = harness.run(&["plugin", "list"]);
+    assert!(run.stdout().contains("parrot (Repeats the prompt)"));
+    assert!(!run.stdout().contains("[disabled]"));
+
+    harness
+        .run(&["plugin", "remove", "parrot"])
+        .assert_success();
+    assert!(!plugins.join("parrot.toml").exists());
+    let run = harness.run(&["plugin", "list"]);
+    assert!(run.stdout().contains("No plugins installed."));
+
+    // A plugin that no longer loads can still be removed by name
+    harness.install_plugin("broken", "[plugin\nname = ");
+    harness
+        .run(&["plugin", "remove", "broken"])
+        .assert_success();
+    assert!(!plugins.join("broken.toml").exists());
+    harness
+        .run(&["plugin", "remove", "broken"])
+        .assert_failure();
+}
@@ -0,0 +1,201 @@
+//! Test support - Fake CLIs and a throwaway home for end-to-end tests
+//!
+//! `Harness` installs shell scripts standing in for `claude`, `codex`, and
+//! `gemini` (or any plugin command) into a temporary directory placed first
+//! on PATH, and points HOME at another temporary directory, so
+//! `~/.gptengage` starts empty and the user's real CLIs, config, and
+//! sessions are never touched. Every fake records the prompt it was given.
+
+#![allow(dead_code)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use tempfile::TempDir;
+
+/// How a fake CLI responds
+#[derive(Debug, Clone)]
+pub enum Fake {
+    /// Print `<name> heard: <last line of the prompt>`
+    Echo,
+    /// Print this text
+    Reply(String),
+    /// Print this to stderr and exit with this code
+    Fail(String, i32),
+    /// Sleep past any reasonable timeout
+    Hang,
+}
+
+impl Fake {
+    fn body(&self, name: &str) -> String {
+        match self {
+            Fake::Echo => format!(
+                "printf '{} heard: %s\\n' \"$(printf '%s\\n' \"$prompt\" | grep -v '^$' | tail -n 1)\"",
+                name
+            ),
+            Fake::Reply(text) => format!("cat <<'REPLY'\n{}\nREPLY", text),
+            Fake::Fail(stderr, code) => format!("echo '{}' >&2\nexit {}", stderr, code),
+            Fake::Hang => "sleep 30".to_string(),
+        }
+    }
+}
+
+/// A temporary home and PATH to run the gptengage binary in
+pub struct Harness {
+    root: TempDir,
+}
+
+impl Harness {
+    /// An empty home with no CLIs installed
+    pub fn new() -> Self {
+        let root = tempfile::tempdir().unwrap();
+        for dir in ["home", "bin", "work", "prompts"] {
+            std::fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        Self { root }
+    }
+
+    /// A home with `claude`, `codex`, and `gemini` all echoing
+    pub fn with_default_clis() -> Self {
+        let harness = Self::new();
+        for name in ["claude", "codex", "gemini"] {
+            harness.install(name, Fake::Echo);
+        }
+        harness
+    }
+
+    /// The temporary HOME
+    pub fn home(&self) -> PathBuf {
+        self.root.path().join("home")
+    }
+
+    /// `~/.gptengage` inside the temporary HOME
+    pub fn gptengage_home(&self) -> PathBuf {
+        self.home().join(".gptengage")
+    }
+
+    /// The working directory commands run in
+    pub fn work_dir(&self) -> PathBuf {
+        self.root.path().join("work")
+    }
+
+    /// Install (or replace) a fake CLI on PATH, returning its path.
+    /// The prompt is taken from stdin, or from the last argument when
+    /// stdin is empty, as plugins in `arg_last` mode pass it.
+    pub fn install(&self, name: &str, fake: Fake) -> PathBuf {
+        let path = self.root.path().join("bin").join(name);
+        let prompts = self.prompts_dir(name);
+        std::fs::create_dir_all(&prompts).unwrap();
+        let script = format!(
+            "#!/bin/sh\n\
+             prompt=$(cat)\n\
+             if [ -z \"$prompt\" ]; then for arg in \"$@\"; do prompt=\"$arg\"; done; fi\n\
+             n=$(ls '{dir}' | wc -l)\n\
+             printf '%s' \"$prompt\" > '{dir}/'$n\n\
+             {body}\n",
+            dir = prompts.display(),
+            body = fake.body(name)
+        );
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    /// Install a plugin manifest under `~/.gptengage/plugins`
+    pub fn install_plugin(&self, name: &str, manifest: &str) {
+        let dir = self.gptengage_home().join("plugins");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("{}.toml", name)), manifest).unwrap();
+    }
+
+    fn prompts_dir(&self, name: &str) -> PathBuf {
+        self.root.path().join("prompts").join(name)
+    }
+
+    /// Prompts a fake CLI was given, oldest first
+    pub fn prompts(&self, name: &str) -> Vec<String> {
+        let dir = self.prompts_dir(name);
+        let mut calls: Vec<(usize, String)> = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .flatten()
+                .filter_map(|entry| {
+                    let n = entry.file_name().to_string_lossy().parse().ok()?;
+                    Some((n, std::fs::read_to_string(entry.path()).ok()?))
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        calls.sort();
+        calls.into_iter().map(|(_, prompt)| prompt).collect()
+    }
+
+    /// The gptengage binary with a clean environment: the temporary HOME,
+    /// the fake CLIs ahead of the system directories on PATH, and no stdin
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_gptengage"));
+        let path = format!("{}:/usr/bin:/bin", self.root.path().join("bin").display());
+        command
+            .env_clear()
+            .env("HOME", self.home())
+            .env("PATH", path)
+            .current_dir(self.work_dir())
+            .stdin(Stdio::null());
+        command
+    }
+
+    /// Run gptengage with `args`
+    pub fn run(&self, args: &[&str]) -> Run {
+        Run(self.command().args(args).output().unwrap())
+    }
+}
+
+/// Output of one gptengage run
+pub struct Run(pub Output);
+
+impl Run {
+    pub fn stdout(&self) -> String {
+        String::from_utf8_lossy(&self.0.stdout).into_owned()
+    }
+
+    pub fn stderr(&self) -> String {
+        String::from_utf8_lossy(&self.0.stderr).into_owned()
+    }
+
+    pub fn success(&self) -> bool {
+        self.0.status.success()
+    }
+
+    /// Assert the run succeeded, showing its output if not
+    pub fn assert_success(&self) -> &Self {
+        assert!(
+            self.success(),
+            "gptengage failed\nstdout:\n{}\nstderr:\n{}",
+            self.stdout(),
+            self.stderr()
+        );
+        self
+    }
+
+    /// Assert the run failed, showing its output if not
+    pub fn assert_failure(&self) -> &Self {
+        assert!(
+            !self.success(),
+            "gptengage unexpectedly succeeded\nstdout:\n{}",
+            self.stdout()
+        );
+        self
+    }
+
+    /// The JSON document on stdout. Progress lines printed before it
+    /// (the debate banner, say) are skipped.
+    pub fn json(&self) -> serde_json::Value {
+        let stdout = self.stdout();
+        let start = stdout
+            .lines()
+            .position(|line| line.starts_with('{') || line.starts_with('['))
+            .unwrap_or_else(|| panic!("no JSON on stdout:\n{}", stdout));
+        let document = stdout.lines().skip(start).collect::<Vec<_>>().join("\n");
+        serde_json::from_str(&document)
+            .unwrap_or_else(|e| panic!("stdout is not JSON ({}):\n{}", e, stdout))
+    }
+}